@@ -0,0 +1,230 @@
+//! Flash chip database mapping JEDEC identifiers to image header flash configurations.
+//!
+//! Most serial NOR flash chips on BL808 boards share commands and differ in how the quad
+//! enable bit is set, so built-in configurations derive from two families: quad enable in
+//! bit 1 of status register 2, and quad enable in bit 6 of status register 1.
+
+/// Length of flash configuration in image header.
+pub const FLASH_CONFIG_LENGTH: usize = 84;
+
+/// Flash configuration of chips with quad enable bit 1 in status register 2.
+///
+/// This is the default configuration of Bouffalo image tools, with Winbond W25Q series
+/// commands and timings, and manufacturer identifier left as `0xff`.
+const QE_SR2_BIT1: [u8; FLASH_CONFIG_LENGTH] = [
+    0x11, 0x00, 0x01, 0x01, 0x66, 0x99, 0xff, 0x03, 0x9f, 0x00, 0xb7, 0xe9, 0x04, 0xff, 0x00, 0x01,
+    0xc7, 0x20, 0x52, 0xd8, 0x06, 0x02, 0x32, 0x00, 0x0b, 0x01, 0x0b, 0x01, 0x3b, 0x01, 0xbb, 0x00,
+    0x6b, 0x01, 0xeb, 0x02, 0xeb, 0x02, 0x02, 0x50, 0x00, 0x01, 0x00, 0x01, 0x01, 0x00, 0x02, 0x01,
+    0x02, 0x01, 0xab, 0x01, 0x05, 0x35, 0x00, 0x00, 0x01, 0x01, 0x00, 0x00, 0x38, 0xff, 0xff, 0xf0,
+    0x77, 0x03, 0x02, 0x40, 0x77, 0x03, 0x02, 0xf0, 0x2c, 0x01, 0xb0, 0x04, 0xb0, 0x04, 0x32, 0x00,
+    0xe8, 0x80, 0x14, 0x00,
+];
+
+/// Flash configuration of chips with quad enable bit 6 in status register 1, e.g. Macronix and ISSI.
+const QE_SR1_BIT6: [u8; FLASH_CONFIG_LENGTH] = with_quad_enable(QE_SR2_BIT1, 0, 6, 1);
+
+/// Offset of manufacturer identifier in flash configuration.
+const MID_OFFSET: usize = 0x0d;
+/// Offset of register length written when setting write enable bit.
+const WR_ENABLE_WRITE_REG_LEN_OFFSET: usize = 0x2e;
+/// Offsets of quad enable register index, bit, write and read register lengths.
+const QE_INDEX_OFFSET: usize = 0x29;
+const QE_BIT_OFFSET: usize = 0x2c;
+const QE_WRITE_REG_LEN_OFFSET: usize = 0x30;
+const QE_READ_REG_LEN_OFFSET: usize = 0x31;
+
+/// Set quad enable bit in status register `index`, writing `write_len` status register bytes at once.
+const fn with_quad_enable(
+    mut cfg: [u8; FLASH_CONFIG_LENGTH],
+    index: u8,
+    bit: u8,
+    write_len: u8,
+) -> [u8; FLASH_CONFIG_LENGTH] {
+    cfg[WR_ENABLE_WRITE_REG_LEN_OFFSET] = write_len;
+    cfg[QE_INDEX_OFFSET] = index;
+    cfg[QE_BIT_OFFSET] = bit;
+    cfg[QE_WRITE_REG_LEN_OFFSET] = write_len;
+    cfg[QE_READ_REG_LEN_OFFSET] = 1;
+    cfg
+}
+
+/// Set manufacturer identifier of flash configuration `cfg`.
+const fn with_mid(mut cfg: [u8; FLASH_CONFIG_LENGTH], mid: u8) -> [u8; FLASH_CONFIG_LENGTH] {
+    cfg[MID_OFFSET] = mid;
+    cfg
+}
+
+const WINBOND: [u8; FLASH_CONFIG_LENGTH] = with_mid(QE_SR2_BIT1, 0xef);
+const GIGADEVICE: [u8; FLASH_CONFIG_LENGTH] = with_mid(QE_SR2_BIT1, 0xc8);
+const XMC: [u8; FLASH_CONFIG_LENGTH] = with_mid(QE_SR2_BIT1, 0x20);
+const PUYA: [u8; FLASH_CONFIG_LENGTH] = with_mid(QE_SR2_BIT1, 0x85);
+const ZBIT: [u8; FLASH_CONFIG_LENGTH] = with_mid(QE_SR2_BIT1, 0x5e);
+const BOYA: [u8; FLASH_CONFIG_LENGTH] = with_mid(QE_SR2_BIT1, 0x68);
+const MACRONIX: [u8; FLASH_CONFIG_LENGTH] = with_mid(QE_SR1_BIT6, 0xc2);
+const ISSI: [u8; FLASH_CONFIG_LENGTH] = with_mid(QE_SR1_BIT6, 0x9d);
+
+/// Flash chip known to the database.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FlashChip {
+    /// JEDEC manufacturer identifier.
+    pub manufacturer_id: u8,
+    /// JEDEC memory type.
+    pub device_type: u8,
+    /// JEDEC capacity, size being `1 << capacity` bytes.
+    pub capacity: u8,
+    /// Chip series name.
+    pub name: &'static str,
+    /// Flash configuration for image header.
+    pub config: &'static [u8],
+}
+
+impl FlashChip {
+    /// JEDEC identifier as read by In-System Programming.
+    #[inline]
+    pub const fn jedec_id(&self) -> u32 {
+        (self.manufacturer_id as u32) << 16 | (self.device_type as u32) << 8 | self.capacity as u32
+    }
+}
+
+const fn chip(
+    jedec_id: u32,
+    name: &'static str,
+    config: &'static [u8; FLASH_CONFIG_LENGTH],
+) -> FlashChip {
+    FlashChip {
+        manufacturer_id: (jedec_id >> 16) as u8,
+        device_type: (jedec_id >> 8) as u8,
+        capacity: jedec_id as u8,
+        name,
+        config,
+    }
+}
+
+/// Built-in flash chips.
+pub static FLASH_CHIPS: [FlashChip; 15] = [
+    chip(0xef4016, "Winbond W25Q32", &WINBOND),
+    chip(0xef4017, "Winbond W25Q64", &WINBOND),
+    chip(0xef4018, "Winbond W25Q128", &WINBOND),
+    chip(0xc84016, "GigaDevice GD25Q32", &GIGADEVICE),
+    chip(0xc84017, "GigaDevice GD25Q64", &GIGADEVICE),
+    chip(0xc84018, "GigaDevice GD25Q128", &GIGADEVICE),
+    chip(0x204017, "XMC XM25QH64C", &XMC),
+    chip(0x204018, "XMC XM25QH128C", &XMC),
+    chip(0x856016, "Puya P25Q32H", &PUYA),
+    chip(0x5e4017, "Zbit ZB25VQ64", &ZBIT),
+    chip(0x684017, "Boya BY25Q64", &BOYA),
+    chip(0xc22016, "Macronix MX25L32", &MACRONIX),
+    chip(0xc22017, "Macronix MX25L64", &MACRONIX),
+    chip(0xc22018, "Macronix MX25L128", &MACRONIX),
+    chip(0x9d6017, "ISSI IS25LP064", &ISSI),
+];
+
+/// Source of flash chips beyond the built-in ones, e.g. for a custom board.
+pub trait FlashLookup {
+    /// Find flash chip by JEDEC manufacturer identifier, memory type and capacity.
+    fn lookup(&self, manufacturer_id: u8, device_type: u8, capacity: u8) -> Option<FlashChip>;
+}
+
+/// Flash chip database with optional caller supplied chips.
+#[derive(Clone, Copy, Default)]
+pub struct FlashDatabase<'a> {
+    extension: Option<&'a dyn FlashLookup>,
+}
+
+impl<'a> FlashDatabase<'a> {
+    /// Create a database of built-in chips.
+    #[inline]
+    pub const fn new() -> Self {
+        Self { extension: None }
+    }
+
+    /// Create a database looking up `extension` before built-in chips.
+    #[inline]
+    pub const fn with_extension(extension: &'a dyn FlashLookup) -> Self {
+        Self {
+            extension: Some(extension),
+        }
+    }
+
+    /// Find flash chip by JEDEC identifier `jedec_id`.
+    pub fn get(&self, jedec_id: u32) -> Option<FlashChip> {
+        let [_, manufacturer_id, device_type, capacity] = jedec_id.to_be_bytes();
+        self.extension
+            .and_then(|e| e.lookup(manufacturer_id, device_type, capacity))
+            .or_else(|| {
+                FLASH_CHIPS
+                    .iter()
+                    .find(|c| c.jedec_id() == jedec_id)
+                    .copied()
+            })
+    }
+}
+
+/// Get flash configuration of built-in chip with JEDEC identifier `jedec_id`.
+pub fn get_flash_config_for_flash_id(jedec_id: u32) -> Option<&'static [u8]> {
+    FlashDatabase::new().get(jedec_id).map(|c| c.config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        get_flash_config_for_flash_id, FlashChip, FlashDatabase, FlashLookup, FLASH_CHIPS,
+        FLASH_CONFIG_LENGTH,
+    };
+    use crate::header::HalFlashConfig;
+
+    fn field(config: &[u8], name: &str) -> u32 {
+        let mut buf = [0u8; 92];
+        buf[4..4 + FLASH_CONFIG_LENGTH].copy_from_slice(config);
+        HalFlashConfig::from_bytes(&buf)
+            .fields()
+            .find(|&(n, _)| n == name)
+            .unwrap()
+            .1
+    }
+
+    #[test]
+    fn flash_database_builtin() {
+        let config = get_flash_config_for_flash_id(0xc84017).unwrap();
+        assert_eq!(field(config, "mid"), 0xc8);
+        assert_eq!(field(config, "qe_index"), 1);
+        assert_eq!(field(config, "qe_bit"), 1);
+        let config = get_flash_config_for_flash_id(0xc22018).unwrap();
+        assert_eq!(field(config, "mid"), 0xc2);
+        assert_eq!(field(config, "qe_index"), 0);
+        assert_eq!(field(config, "qe_bit"), 6);
+        assert_eq!(field(config, "qe_write_reg_len"), 1);
+        assert_eq!(field(config, "wr_enable_write_reg_len"), 1);
+        assert_eq!(get_flash_config_for_flash_id(0x123456), None);
+        for chip in &FLASH_CHIPS {
+            assert_eq!(chip.config.len(), FLASH_CONFIG_LENGTH);
+            assert_eq!(field(chip.config, "mid"), chip.manufacturer_id as u32);
+        }
+    }
+
+    struct Board;
+
+    static BOARD_CONFIG: [u8; FLASH_CONFIG_LENGTH] = [0xa5; FLASH_CONFIG_LENGTH];
+
+    impl FlashLookup for Board {
+        fn lookup(&self, manufacturer_id: u8, device_type: u8, capacity: u8) -> Option<FlashChip> {
+            ((manufacturer_id, device_type, capacity) == (0x0b, 0x40, 0x17)).then_some(FlashChip {
+                manufacturer_id,
+                device_type,
+                capacity,
+                name: "Board flash",
+                config: &BOARD_CONFIG,
+            })
+        }
+    }
+
+    #[test]
+    fn flash_database_extension() {
+        let db = FlashDatabase::with_extension(&Board);
+        let chip = db.get(0x0b4017).unwrap();
+        assert_eq!(chip.name, "Board flash");
+        assert_eq!(chip.jedec_id(), 0x0b4017);
+        assert_eq!(db.get(0xef4018).unwrap().name, "Winbond W25Q128");
+        assert_eq!(FlashDatabase::new().get(0x0b4017), None);
+    }
+}
@@ -0,0 +1,508 @@
+//! Decode BL808 ROM image header.
+
+use crate::{Error, Result, CLOCK_MAGIC, FLASH_MAGIC, HEAD_LENGTH, HEAD_MAGIC};
+use sha2::{Digest, Sha256};
+
+/// Flash configuration fields with name, offset and byte length.
+pub const FLASH_CONFIG_FIELDS: [(&str, usize, usize); 72] = [
+    ("io_mode", 0x00, 1),
+    ("c_read_support", 0x01, 1),
+    ("clk_delay", 0x02, 1),
+    ("clk_invert", 0x03, 1),
+    ("reset_en_cmd", 0x04, 1),
+    ("reset_cmd", 0x05, 1),
+    ("reset_cread_cmd", 0x06, 1),
+    ("reset_cread_cmd_size", 0x07, 1),
+    ("jedec_id_cmd", 0x08, 1),
+    ("jedec_id_cmd_dmy_clk", 0x09, 1),
+    ("enter_32_bits_addr_cmd", 0x0a, 1),
+    ("exit_32_bits_addr_cmd", 0x0b, 1),
+    ("sector_size", 0x0c, 1),
+    ("mid", 0x0d, 1),
+    ("page_size", 0x0e, 2),
+    ("chip_erase_cmd", 0x10, 1),
+    ("sector_erase_cmd", 0x11, 1),
+    ("blk32_erase_cmd", 0x12, 1),
+    ("blk64_erase_cmd", 0x13, 1),
+    ("write_enable_cmd", 0x14, 1),
+    ("page_program_cmd", 0x15, 1),
+    ("qpage_program_cmd", 0x16, 1),
+    ("qpp_addr_mode", 0x17, 1),
+    ("fast_read_cmd", 0x18, 1),
+    ("fr_dmy_clk", 0x19, 1),
+    ("qpi_fast_read_cmd", 0x1a, 1),
+    ("qpi_fr_dmy_clk", 0x1b, 1),
+    ("fast_read_do_cmd", 0x1c, 1),
+    ("fr_do_dmy_clk", 0x1d, 1),
+    ("fast_read_dio_cmd", 0x1e, 1),
+    ("fr_dio_dmy_clk", 0x1f, 1),
+    ("fast_read_qo_cmd", 0x20, 1),
+    ("fr_qo_dmy_clk", 0x21, 1),
+    ("fast_read_qio_cmd", 0x22, 1),
+    ("fr_qio_dmy_clk", 0x23, 1),
+    ("qpi_fast_read_qio_cmd", 0x24, 1),
+    ("qpi_fr_qio_dmy_clk", 0x25, 1),
+    ("qpi_page_program_cmd", 0x26, 1),
+    ("writev_reg_enable_cmd", 0x27, 1),
+    ("wr_enable_index", 0x28, 1),
+    ("qe_index", 0x29, 1),
+    ("busy_index", 0x2a, 1),
+    ("wr_enable_bit", 0x2b, 1),
+    ("qe_bit", 0x2c, 1),
+    ("busy_bit", 0x2d, 1),
+    ("wr_enable_write_reg_len", 0x2e, 1),
+    ("wr_enable_read_reg_len", 0x2f, 1),
+    ("qe_write_reg_len", 0x30, 1),
+    ("qe_read_reg_len", 0x31, 1),
+    ("release_power_down", 0x32, 1),
+    ("busy_read_reg_len", 0x33, 1),
+    ("read_reg_cmd", 0x34, 4),
+    ("write_reg_cmd", 0x38, 4),
+    ("enter_qpi", 0x3c, 1),
+    ("exit_qpi", 0x3d, 1),
+    ("c_read_mode", 0x3e, 1),
+    ("cr_exit", 0x3f, 1),
+    ("burst_wrap_cmd", 0x40, 1),
+    ("burst_wrap_cmd_dmy_clk", 0x41, 1),
+    ("burst_wrap_data_mode", 0x42, 1),
+    ("burst_wrap_data", 0x43, 1),
+    ("de_burst_wrap_cmd", 0x44, 1),
+    ("de_burst_wrap_cmd_dmy_clk", 0x45, 1),
+    ("de_burst_wrap_data_mode", 0x46, 1),
+    ("de_burst_wrap_data", 0x47, 1),
+    ("time_e_sector", 0x48, 2),
+    ("time_e_32k", 0x4a, 2),
+    ("time_e_64k", 0x4c, 2),
+    ("time_page_pgm", 0x4e, 2),
+    ("time_ce", 0x50, 2),
+    ("pd_delay", 0x52, 1),
+    ("qe_data", 0x53, 1),
+];
+
+/// Clock configuration fields with name and offset; each field is one byte.
+pub const CLOCK_CONFIG_FIELDS: [(&str, usize); 20] = [
+    ("xtal_type", 0x00),
+    ("mcu_clk", 0x01),
+    ("mcu_clk_div", 0x02),
+    ("mcu_bclk_div", 0x03),
+    ("mcu_pbclk_div", 0x04),
+    ("lp_div", 0x05),
+    ("dsp_clk", 0x06),
+    ("dsp_clk_div", 0x07),
+    ("dsp_bclk_div", 0x08),
+    ("dsp_pbclk", 0x09),
+    ("dsp_pbclk_div", 0x0a),
+    ("emi_clk", 0x0b),
+    ("emi_clk_div", 0x0c),
+    ("flash_clk_type", 0x0d),
+    ("flash_clk_div", 0x0e),
+    ("wifipll_pu", 0x0f),
+    ("aupll_pu", 0x10),
+    ("cpupll_pu", 0x11),
+    ("mipipll_pu", 0x12),
+    ("uhspll_pu", 0x13),
+];
+
+#[inline]
+fn u32_at(buf: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(buf[offset..offset + 4].try_into().unwrap())
+}
+
+#[inline]
+fn crc32(buf: &[u8]) -> u32 {
+    crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC).checksum(buf)
+}
+
+/// Flash configuration in ROM header.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HalFlashConfig {
+    /// Magic number, should be `FCFG`.
+    pub magic: u32,
+    /// Raw flash configuration bytes.
+    pub cfg: [u8; 84],
+    /// Checksum of configuration bytes.
+    pub crc32: u32,
+}
+
+impl HalFlashConfig {
+    /// Decode flash configuration from 92 bytes.
+    #[inline]
+    pub fn from_bytes(buf: &[u8; 92]) -> Self {
+        Self {
+            magic: u32_at(buf, 0x00),
+            cfg: buf[0x04..0x58].try_into().unwrap(),
+            crc32: u32_at(buf, 0x58),
+        }
+    }
+    /// Check if stored checksum matches configuration bytes.
+    #[inline]
+    pub fn is_crc_valid(&self) -> bool {
+        crc32(&self.cfg) == self.crc32
+    }
+    /// Iterate over field names and values in little endian.
+    #[inline]
+    pub fn fields(&self) -> impl Iterator<Item = (&'static str, u32)> + '_ {
+        FLASH_CONFIG_FIELDS
+            .iter()
+            .map(|&(name, offset, len)| match len {
+                1 => (name, self.cfg[offset] as u32),
+                2 => (
+                    name,
+                    u16::from_le_bytes([self.cfg[offset], self.cfg[offset + 1]]) as u32,
+                ),
+                _ => (name, u32_at(&self.cfg, offset)),
+            })
+    }
+}
+
+/// Clock configuration in ROM header.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HalPllConfig {
+    /// Magic number, should be `PCFG`.
+    pub magic: u32,
+    /// Raw clock configuration bytes.
+    pub cfg: [u8; 20],
+    /// Checksum of configuration bytes.
+    pub crc32: u32,
+}
+
+impl HalPllConfig {
+    /// Decode clock configuration from 28 bytes.
+    #[inline]
+    pub fn from_bytes(buf: &[u8; 28]) -> Self {
+        Self {
+            magic: u32_at(buf, 0x00),
+            cfg: buf[0x04..0x18].try_into().unwrap(),
+            crc32: u32_at(buf, 0x18),
+        }
+    }
+    /// Check if stored checksum matches configuration bytes.
+    #[inline]
+    pub fn is_crc_valid(&self) -> bool {
+        crc32(&self.cfg) == self.crc32
+    }
+    /// Iterate over field names and values.
+    #[inline]
+    pub fn fields(&self) -> impl Iterator<Item = (&'static str, u8)> + '_ {
+        CLOCK_CONFIG_FIELDS
+            .iter()
+            .map(|&(name, offset)| (name, self.cfg[offset]))
+    }
+}
+
+/// Basic configuration flags.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BasicConfigFlags(u32);
+
+impl BasicConfigFlags {
+    /// Decode flags from raw value.
+    #[inline]
+    pub const fn from_u32(val: u32) -> Self {
+        Self(val)
+    }
+    #[inline]
+    const fn bits(self, shift: u32, len: u32) -> u32 {
+        (self.0 >> shift) & ((1 << len) - 1)
+    }
+    /// Signature type.
+    #[inline]
+    pub const fn sign(self) -> u32 {
+        self.bits(0, 2)
+    }
+    /// Encryption type.
+    #[inline]
+    pub const fn encrypt_type(self) -> u32 {
+        self.bits(2, 2)
+    }
+    /// Encryption key slot.
+    #[inline]
+    pub const fn key_slot(self) -> u32 {
+        self.bits(4, 2)
+    }
+    /// AES XTS mode.
+    #[inline]
+    pub const fn xts_mode(self) -> bool {
+        self.bits(6, 1) != 0
+    }
+    /// AES region lock.
+    #[inline]
+    pub const fn aes_region_lock(self) -> bool {
+        self.bits(7, 1) != 0
+    }
+    /// Image has no segment information.
+    #[inline]
+    pub const fn no_segment(self) -> bool {
+        self.bits(8, 1) != 0
+    }
+    /// Secondary bootloader enable.
+    #[inline]
+    pub const fn boot2_enable(self) -> bool {
+        self.bits(9, 1) != 0
+    }
+    /// Secondary bootloader rollback.
+    #[inline]
+    pub const fn boot2_rollback(self) -> bool {
+        self.bits(10, 1) != 0
+    }
+    /// Master processor core identifier.
+    #[inline]
+    pub const fn cpu_master_id(self) -> u32 {
+        self.bits(11, 4)
+    }
+    /// Image is not loaded by boot ROM.
+    #[inline]
+    pub const fn notload_in_bootrom(self) -> bool {
+        self.bits(15, 1) != 0
+    }
+    /// Ignore header checksum.
+    #[inline]
+    pub const fn crc_ignore(self) -> bool {
+        self.bits(16, 1) != 0
+    }
+    /// Ignore image hash.
+    #[inline]
+    pub const fn hash_ignore(self) -> bool {
+        self.bits(17, 1) != 0
+    }
+    /// Power on multimedia subsystem.
+    #[inline]
+    pub const fn power_on_mm(self) -> bool {
+        self.bits(18, 1) != 0
+    }
+    /// Embedded memory selection.
+    #[inline]
+    pub const fn em_sel(self) -> u32 {
+        self.bits(19, 3)
+    }
+    /// Command splitter enable.
+    #[inline]
+    pub const fn cmds_en(self) -> bool {
+        self.bits(22, 1) != 0
+    }
+    /// Command wrap mode.
+    #[inline]
+    pub const fn cmds_wrap_mode(self) -> u32 {
+        self.bits(23, 2)
+    }
+    /// Command wrap length.
+    #[inline]
+    pub const fn cmds_wrap_len(self) -> u32 {
+        self.bits(25, 4)
+    }
+    /// Invalidate instruction cache.
+    #[inline]
+    pub const fn icache_invalid(self) -> bool {
+        self.bits(29, 1) != 0
+    }
+    /// Invalidate data cache.
+    #[inline]
+    pub const fn dcache_invalid(self) -> bool {
+        self.bits(30, 1) != 0
+    }
+    /// FPGA halt release function.
+    #[inline]
+    pub const fn fpga_halt_release(self) -> bool {
+        self.bits(31, 1) != 0
+    }
+}
+
+/// Basic image configuration.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HalBasicConfig {
+    /// Configuration flags.
+    pub flag: BasicConfigFlags,
+    /// Image body offset in flash.
+    pub group_image_offset: u32,
+    /// AES region length.
+    pub aes_region_len: u32,
+    /// Image length or segment count.
+    pub img_len_cnt: u32,
+    /// SHA-256 hash of image body.
+    pub hash: [u8; 32],
+}
+
+impl HalBasicConfig {
+    /// Decode basic configuration from 48 bytes.
+    #[inline]
+    pub fn from_bytes(buf: &[u8; 48]) -> Self {
+        Self {
+            flag: BasicConfigFlags::from_u32(u32_at(buf, 0x00)),
+            group_image_offset: u32_at(buf, 0x04),
+            aes_region_len: u32_at(buf, 0x08),
+            img_len_cnt: u32_at(buf, 0x0c),
+            hash: buf[0x10..0x30].try_into().unwrap(),
+        }
+    }
+}
+
+/// Processor core configuration.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HalCpuCfg {
+    /// Configure this core.
+    pub config_enable: bool,
+    /// Halt this core.
+    pub halt_cpu: bool,
+    /// Cache setting.
+    pub cache_flags: u8,
+    /// Cache range high.
+    pub cache_range_h: u32,
+    /// Cache range low.
+    pub cache_range_l: u32,
+    /// Image address on flash.
+    pub image_address_offset: u32,
+    /// Entry point of this core.
+    pub boot_entry: u32,
+    /// Initial stack pointer value.
+    pub msp_val: u32,
+}
+
+impl HalCpuCfg {
+    /// Decode processor core configuration from 24 bytes.
+    #[inline]
+    pub fn from_bytes(buf: &[u8; 24]) -> Self {
+        Self {
+            config_enable: buf[0] != 0,
+            halt_cpu: buf[1] != 0,
+            cache_flags: buf[2],
+            cache_range_h: u32_at(buf, 0x04),
+            cache_range_l: u32_at(buf, 0x08),
+            image_address_offset: u32_at(buf, 0x0c),
+            boot_entry: u32_at(buf, 0x10),
+            msp_val: u32_at(buf, 0x14),
+        }
+    }
+}
+
+/// Program or ROM code patch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HalPatchCfg {
+    /// Address to patch.
+    pub addr: u32,
+    /// Value to write.
+    pub value: u32,
+}
+
+/// Full ROM image header.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HalBootheader {
+    /// Magic number, should be `BFNP`.
+    pub magic: u32,
+    /// Header revision.
+    pub revision: u32,
+    /// Flash configuration.
+    pub flash_cfg: HalFlashConfig,
+    /// Clock configuration.
+    pub clk_cfg: HalPllConfig,
+    /// Basic image configuration.
+    pub basic_cfg: HalBasicConfig,
+    /// Configurations of three processor cores.
+    pub cpu_cfg: [HalCpuCfg; 3],
+    /// Address of partition table 0.
+    pub boot2_pt_table_0: u32,
+    /// Address of partition table 1.
+    pub boot2_pt_table_1: u32,
+    /// Address of flash configuration table list.
+    pub flash_cfg_table_addr: u32,
+    /// Length of flash configuration table list.
+    pub flash_cfg_table_len: u32,
+    /// Patches when reading flash.
+    pub patch_on_read: [HalPatchCfg; 4],
+    /// Patches when jumping to image.
+    pub patch_on_jump: [HalPatchCfg; 4],
+    /// Checksum of header.
+    pub crc32: u32,
+    calculated_crc32: u32,
+}
+
+impl HalBootheader {
+    /// Decode ROM image header from beginning of `buf`.
+    pub fn from_bytes(buf: &[u8]) -> Result<Self> {
+        if (buf.len() as u64) < HEAD_LENGTH {
+            return Err(Error::HeadLength {
+                wrong_length: buf.len() as u64,
+            });
+        }
+        let magic = u32::from_be_bytes(buf[0x00..0x04].try_into().unwrap());
+        if magic != HEAD_MAGIC {
+            return Err(Error::MagicNumber { wrong_magic: magic });
+        }
+        let flash_cfg = HalFlashConfig::from_bytes(buf[0x08..0x64].try_into().unwrap());
+        if flash_cfg.magic.swap_bytes() != FLASH_MAGIC {
+            return Err(Error::FlashConfigMagic {
+                wrong_magic: flash_cfg.magic.swap_bytes(),
+            });
+        }
+        let clk_cfg = HalPllConfig::from_bytes(buf[0x64..0x80].try_into().unwrap());
+        if clk_cfg.magic.swap_bytes() != CLOCK_MAGIC {
+            return Err(Error::ClockConfigMagic {
+                wrong_magic: clk_cfg.magic.swap_bytes(),
+            });
+        }
+        let cpu_cfg = |i: usize| {
+            let offset = 0xb0 + i * 24;
+            HalCpuCfg::from_bytes(buf[offset..offset + 24].try_into().unwrap())
+        };
+        let patch = |offset: usize| {
+            core::array::from_fn(|i| HalPatchCfg {
+                addr: u32_at(buf, offset + i * 8),
+                value: u32_at(buf, offset + i * 8 + 4),
+            })
+        };
+        Ok(Self {
+            magic,
+            revision: u32_at(buf, 0x04),
+            flash_cfg,
+            clk_cfg,
+            basic_cfg: HalBasicConfig::from_bytes(buf[0x80..0xb0].try_into().unwrap()),
+            cpu_cfg: [cpu_cfg(0), cpu_cfg(1), cpu_cfg(2)],
+            boot2_pt_table_0: u32_at(buf, 0xf8),
+            boot2_pt_table_1: u32_at(buf, 0xfc),
+            flash_cfg_table_addr: u32_at(buf, 0x100),
+            flash_cfg_table_len: u32_at(buf, 0x104),
+            patch_on_read: patch(0x108),
+            patch_on_jump: patch(0x128),
+            crc32: u32_at(buf, 0x15c),
+            calculated_crc32: crc32(&buf[..0x15c]),
+        })
+    }
+    /// Check if stored header checksum matches header content.
+    #[inline]
+    pub fn is_crc_valid(&self) -> bool {
+        self.calculated_crc32 == self.crc32
+    }
+    /// Calculate SHA-256 hash of image body in `image`.
+    ///
+    /// Returns `None` if image body exceeds `image`.
+    #[inline]
+    pub fn calculate_hash(&self, image: &[u8]) -> Option<[u8; 32]> {
+        let start = self.basic_cfg.group_image_offset as usize;
+        let end = start.checked_add(self.basic_cfg.img_len_cnt as usize)?;
+        let body = image.get(start..end)?;
+        Some(Sha256::digest(body).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BasicConfigFlags;
+
+    #[test]
+    fn struct_basic_config_flags_functions() {
+        let flags = BasicConfigFlags::from_u32(0x654c0100);
+        assert_eq!(flags.sign(), 0);
+        assert_eq!(flags.encrypt_type(), 0);
+        assert_eq!(flags.key_slot(), 0);
+        assert!(flags.no_segment());
+        assert!(!flags.boot2_enable());
+        assert_eq!(flags.cpu_master_id(), 0);
+        assert!(!flags.crc_ignore());
+        assert!(flags.power_on_mm());
+        assert_eq!(flags.em_sel(), 1);
+        assert!(flags.cmds_en());
+        assert_eq!(flags.cmds_wrap_mode(), 2);
+        assert_eq!(flags.cmds_wrap_len(), 2);
+        assert!(flags.icache_invalid());
+        assert!(flags.dcache_invalid());
+        assert!(!flags.fpga_halt_release());
+    }
+}
@@ -0,0 +1,394 @@
+//! Bouffalo ROM In-System Programming protocol.
+//!
+//! Boot ROM in download mode accepts commands over UART. Each command is framed as
+//! command byte, checksum byte, little endian `u16` data length and data; the checksum is
+//! the wrapping sum of length and data bytes. Boot ROM answers `OK`, optionally followed by
+//! length and data, or `FL` and a little endian `u16` error code.
+//!
+//! [`UartIsp`] works over any `Read + Write` port, so it can drive a serial port opened by
+//! the caller, a native USB [`UsbPort`], or a mock in tests.
+
+use std::io::{self, Read, Write};
+use std::time::Duration;
+
+mod usb;
+
+pub use usb::UsbPort;
+
+/// Errors on In-System Programming.
+#[derive(thiserror::Error, Debug)]
+pub enum IspError {
+    #[error("I/O error")]
+    Io(#[from] io::Error),
+    #[error("No response on handshake")]
+    Handshake,
+    #[error("Command failed with error code 0x{code:04x}")]
+    Command { code: u16 },
+    #[error("Unexpected response {response:02x?}")]
+    Response { response: [u8; 2] },
+    #[error("Flash range of 0x{len:x} bytes from 0x{start:x} exceeds address space")]
+    FlashRange { start: u32, len: u32 },
+}
+
+/// Bouffalo ROM In-System Programming commands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum IspCommand {
+    GetBootInfo = 0x10,
+    Reset = 0x21,
+    FlashErase = 0x30,
+    FlashWrite = 0x31,
+    FlashRead = 0x32,
+    FlashReadJedecId = 0x36,
+    FlashWriteCheck = 0x3a,
+    FlashSetParameter = 0x3b,
+}
+
+/// Chip information from boot ROM.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BootInfo {
+    /// Boot ROM version.
+    pub bootrom_version: u32,
+    /// One-time programmable information, including chip identifier and flash settings.
+    pub otp_info: Vec<u8>,
+}
+
+/// Maximum data length of one flash write command.
+///
+/// The boot ROM acknowledges each command only after it's committed to flash,
+/// thus writes cannot be pipelined; larger chunks reduce round trips instead.
+pub const WRITE_CHUNK_LENGTH: usize = 4096;
+
+/// Maximum data length of one flash read command.
+pub const READ_CHUNK_LENGTH: usize = 4096;
+
+/// Client of the Bouffalo ROM In-System Programming protocol over serial port or USB.
+pub struct UartIsp<P> {
+    port: P,
+}
+
+impl<P: Read + Write> UartIsp<P> {
+    /// Create an In-System Programming client over serial port.
+    pub fn new(port: P) -> Self {
+        Self { port }
+    }
+
+    /// Release the serial port.
+    pub fn free(self) -> P {
+        self.port
+    }
+
+    /// Synchronize baudrate with boot ROM.
+    ///
+    /// Also sends the magic string for running bootloaders to reset into download mode.
+    pub fn handshake(&mut self, baudrate: u32) -> Result<(), IspError> {
+        self.port.write_all(b"BOUFFALOLAB5555RESET\0\0")?;
+        std::thread::sleep(Duration::from_millis(100));
+        // send 0x55 continuously for 5 milliseconds
+        let sync = vec![0x55u8; (baudrate / 10 / 200) as usize];
+        for _ in 0..3 {
+            self.port.write_all(&sync)?;
+            self.port.flush()?;
+            std::thread::sleep(Duration::from_millis(20));
+            let mut response = [0u8; 2];
+            if self.port.read_exact(&mut response).is_ok() && &response == b"OK" {
+                return Ok(());
+            }
+        }
+        Err(IspError::Handshake)
+    }
+
+    /// Send command, returning response data if any.
+    pub fn send_command(
+        &mut self,
+        command: IspCommand,
+        data: &[u8],
+        has_response_data: bool,
+    ) -> Result<Vec<u8>, IspError> {
+        self.port.write_all(&encode_command(command, data))?;
+        self.port.flush()?;
+        let mut response = [0u8; 2];
+        self.port.read_exact(&mut response)?;
+        match &response {
+            b"OK" => {}
+            b"FL" => {
+                let mut code = [0u8; 2];
+                self.port.read_exact(&mut code)?;
+                return Err(IspError::Command {
+                    code: u16::from_le_bytes(code),
+                });
+            }
+            _ => return Err(IspError::Response { response }),
+        }
+        if !has_response_data {
+            return Ok(Vec::new());
+        }
+        let mut len = [0u8; 2];
+        self.port.read_exact(&mut len)?;
+        let mut buf = vec![0u8; u16::from_le_bytes(len) as usize];
+        self.port.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// Read boot ROM version and one-time programmable information.
+    pub fn get_boot_info(&mut self) -> Result<BootInfo, IspError> {
+        let data = self.send_command(IspCommand::GetBootInfo, &[], true)?;
+        if data.len() < 4 {
+            return Err(IspError::Io(io::ErrorKind::UnexpectedEof.into()));
+        }
+        Ok(BootInfo {
+            bootrom_version: u32::from_le_bytes(data[..4].try_into().unwrap()),
+            otp_info: data[4..].to_vec(),
+        })
+    }
+
+    /// Select the pad group flash is connected to.
+    ///
+    /// Needed before flash commands on boards whose flash pins differ from the setting
+    /// in efuse. Clock, I/O mode and clock delay settings are left at boot ROM defaults.
+    pub fn set_flash_pin(&mut self, flash_pin: u8) -> Result<(), IspError> {
+        self.send_command(IspCommand::FlashSetParameter, &[flash_pin, 0, 0, 0], false)?;
+        Ok(())
+    }
+
+    /// Read JEDEC manufacturer and device identifier of flash.
+    pub fn read_flash_id(&mut self) -> Result<u32, IspError> {
+        let data = self.send_command(IspCommand::FlashReadJedecId, &[], true)?;
+        if data.len() < 3 {
+            return Err(IspError::Io(io::ErrorKind::UnexpectedEof.into()));
+        }
+        Ok(u32::from(data[0]) << 16 | u32::from(data[1]) << 8 | u32::from(data[2]))
+    }
+
+    /// Erase flash sectors covering `len` bytes from `start`.
+    ///
+    /// Nothing is erased if `len` is zero.
+    pub fn erase_flash(&mut self, start: u32, len: u32) -> Result<(), IspError> {
+        if len == 0 {
+            return Ok(());
+        }
+        let end = start
+            .checked_add(len - 1)
+            .ok_or(IspError::FlashRange { start, len })?;
+        let mut data = Vec::with_capacity(8);
+        data.extend_from_slice(&start.to_le_bytes());
+        data.extend_from_slice(&end.to_le_bytes());
+        self.send_command(IspCommand::FlashErase, &data, false)?;
+        Ok(())
+    }
+
+    /// Write `image` to flash from `start`.
+    ///
+    /// `progress` is called with the length of each chunk written.
+    pub fn write_flash(
+        &mut self,
+        start: u32,
+        image: &[u8],
+        mut progress: impl FnMut(usize),
+    ) -> Result<(), IspError> {
+        for (i, chunk) in image.chunks(WRITE_CHUNK_LENGTH).enumerate() {
+            let address = start + (i * WRITE_CHUNK_LENGTH) as u32;
+            let mut data = Vec::with_capacity(4 + chunk.len());
+            data.extend_from_slice(&address.to_le_bytes());
+            data.extend_from_slice(chunk);
+            self.send_command(IspCommand::FlashWrite, &data, false)?;
+            progress(chunk.len());
+        }
+        Ok(())
+    }
+
+    /// Read `len` bytes of flash from `start`.
+    ///
+    /// `progress` is called with the length of each chunk read.
+    pub fn read_flash(
+        &mut self,
+        start: u32,
+        len: usize,
+        mut progress: impl FnMut(usize),
+    ) -> Result<Vec<u8>, IspError> {
+        let mut buf = Vec::with_capacity(len);
+        while buf.len() < len {
+            let address = start + buf.len() as u32;
+            let chunk_len = (len - buf.len()).min(READ_CHUNK_LENGTH);
+            let mut data = Vec::with_capacity(8);
+            data.extend_from_slice(&address.to_le_bytes());
+            data.extend_from_slice(&(chunk_len as u32).to_le_bytes());
+            let chunk = self.send_command(IspCommand::FlashRead, &data, true)?;
+            if chunk.len() != chunk_len {
+                return Err(IspError::Io(io::ErrorKind::UnexpectedEof.into()));
+            }
+            buf.extend_from_slice(&chunk);
+            progress(chunk_len);
+        }
+        Ok(buf)
+    }
+
+    /// Ask boot ROM to check previous flash writes.
+    pub fn write_check(&mut self) -> Result<(), IspError> {
+        self.send_command(IspCommand::FlashWriteCheck, &[], false)?;
+        Ok(())
+    }
+
+    /// Reset the chip, e.g. to boot newly written firmware.
+    ///
+    /// Boot ROM resets right away without answering, so no response is read.
+    pub fn reset(&mut self) -> Result<(), IspError> {
+        self.port
+            .write_all(&encode_command(IspCommand::Reset, &[]))?;
+        self.port.flush()?;
+        Ok(())
+    }
+}
+
+/// Encode command with checksum and length header.
+pub fn encode_command(command: IspCommand, data: &[u8]) -> Vec<u8> {
+    let len = (data.len() as u16).to_le_bytes();
+    let checksum = len
+        .iter()
+        .chain(data)
+        .fold(0u8, |sum, &byte| sum.wrapping_add(byte));
+    let mut buf = Vec::with_capacity(4 + data.len());
+    buf.push(command as u8);
+    buf.push(checksum);
+    buf.extend_from_slice(&len);
+    buf.extend_from_slice(data);
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_command, IspCommand, IspError, UartIsp};
+    use std::io::{self, Cursor, Read, Write};
+
+    /// Serial port mock replaying prepared responses and recording written bytes.
+    struct MockPort {
+        response: Cursor<Vec<u8>>,
+        written: Vec<u8>,
+    }
+
+    impl Read for MockPort {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.response.read(buf)
+        }
+    }
+
+    impl Write for MockPort {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.written.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn mock_isp(response: &[u8]) -> UartIsp<MockPort> {
+        UartIsp::new(MockPort {
+            response: Cursor::new(response.to_vec()),
+            written: Vec::new(),
+        })
+    }
+
+    #[test]
+    fn encode_command_checksum() {
+        let buf = encode_command(IspCommand::FlashErase, &[0x00, 0x10, 0x00, 0x00]);
+        assert_eq!(buf, [0x30, 0x14, 0x04, 0x00, 0x00, 0x10, 0x00, 0x00]);
+        let buf = encode_command(IspCommand::GetBootInfo, &[]);
+        assert_eq!(buf, [0x10, 0x00, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn send_command_responses() {
+        let mut isp = mock_isp(b"OK\x03\x00\xef\x40\x18");
+        assert_eq!(isp.read_flash_id().unwrap(), 0xef4018);
+        assert_eq!(isp.port.written, [0x36, 0x00, 0x00, 0x00]);
+
+        let mut isp = mock_isp(b"FL\x03\x00");
+        match isp.write_check() {
+            Err(IspError::Command { code }) => assert_eq!(code, 0x0003),
+            _ => panic!("this test case should raise Command error"),
+        }
+
+        let mut isp = mock_isp(b"XX");
+        match isp.write_check() {
+            Err(IspError::Response { response }) => assert_eq!(&response, b"XX"),
+            _ => panic!("this test case should raise Response error"),
+        }
+    }
+
+    #[test]
+    fn set_flash_pin_and_reset() {
+        let mut isp = mock_isp(b"OK");
+        isp.set_flash_pin(0x04).unwrap();
+        isp.reset().unwrap();
+        assert_eq!(
+            isp.free().written,
+            [0x3b, 0x08, 0x04, 0x00, 0x04, 0x00, 0x00, 0x00, 0x21, 0x00, 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn erase_flash_range() {
+        let mut isp = mock_isp(b"OK");
+        isp.erase_flash(0x1000, 0x2000).unwrap();
+        assert_eq!(
+            isp.port.written,
+            [0x30, 0x46, 0x08, 0x00, 0x00, 0x10, 0x00, 0x00, 0xff, 0x2f, 0x00, 0x00]
+        );
+
+        let mut isp = mock_isp(b"");
+        isp.erase_flash(0x1000, 0).unwrap();
+        assert!(isp.port.written.is_empty());
+        match isp.erase_flash(0xffff_f000, 0x2000) {
+            Err(IspError::FlashRange { start, len }) => {
+                assert_eq!((start, len), (0xffff_f000, 0x2000))
+            }
+            _ => panic!("this test case should raise FlashRange error"),
+        }
+        assert!(isp.port.written.is_empty());
+    }
+
+    #[test]
+    fn write_flash_chunks() {
+        let mut isp = mock_isp(b"OKOK");
+        let image = vec![0xa5u8; 4096 + 16];
+        let mut done = 0;
+        isp.write_flash(0x1000, &image, |len| done += len).unwrap();
+        assert_eq!(done, image.len());
+        let written = &isp.port.written;
+        assert_eq!(written.len(), (4 + 4 + 4096) + (4 + 4 + 16));
+        assert_eq!(
+            &written[..8],
+            [0x31, 0x24, 0x04, 0x10, 0x00, 0x10, 0x00, 0x00]
+        );
+        assert_eq!(
+            &written[4104..4112],
+            [0x31, 0x84, 0x14, 0x00, 0x00, 0x20, 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn read_flash_chunks() {
+        let mut response = b"OK\x00\x10".to_vec();
+        response.extend_from_slice(&[0x5a; 4096]);
+        response.extend_from_slice(b"OK\x10\x00");
+        response.extend_from_slice(&[0xa5; 16]);
+        let mut isp = mock_isp(&response);
+        let mut done = 0;
+        let data = isp
+            .read_flash(0x2000, 4096 + 16, |len| done += len)
+            .unwrap();
+        assert_eq!(done, data.len());
+        assert_eq!(&data[4095..4097], [0x5a, 0xa5]);
+        assert_eq!(
+            isp.port.written,
+            [
+                0x32, 0x38, 0x08, 0x00, 0x00, 0x20, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x32, 0x48,
+                0x08, 0x00, 0x00, 0x30, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00
+            ]
+        );
+
+        let mut isp = mock_isp(b"OK\x02\x00\x5a\x5a");
+        assert!(isp.read_flash(0x0, 4, |_| {}).is_err());
+    }
+}
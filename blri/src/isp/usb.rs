@@ -0,0 +1,151 @@
+use rusb::{DeviceHandle, Direction, GlobalContext, TransferType};
+use std::io::{self, Read, Write};
+use std::time::Duration;
+
+/// Native USB port of boot ROM, transferring In-System Programming frames over bulk endpoints.
+///
+/// Boot ROM in USB download mode enumerates as `ffff:ffff` by default.
+///
+/// Implements `Read + Write`, so it drives [`UartIsp`](super::UartIsp) the same way a
+/// serial port does. There is no baudrate to synchronize over USB; skip
+/// [`handshake`](super::UartIsp::handshake) and send commands right away.
+pub struct UsbPort {
+    handle: DeviceHandle<GlobalContext>,
+    interface: u8,
+    endpoint_in: u8,
+    endpoint_out: u8,
+    timeout: Duration,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl UsbPort {
+    /// Open the first device with `vendor_id` and `product_id` and claim its bulk interface.
+    pub fn open(vendor_id: u16, product_id: u16, timeout: Duration) -> io::Result<Self> {
+        let handle = rusb::open_device_with_vid_pid(vendor_id, product_id).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no USB device {vendor_id:04x}:{product_id:04x}"),
+            )
+        })?;
+        let config = handle
+            .device()
+            .active_config_descriptor()
+            .map_err(usb_error)?;
+        let (interface, endpoint_in, endpoint_out) = config
+            .interfaces()
+            .flat_map(|interface| interface.descriptors())
+            .find_map(|descriptor| {
+                let endpoints = descriptor.endpoint_descriptors().map(|endpoint| {
+                    (
+                        endpoint.address(),
+                        endpoint.direction(),
+                        endpoint.transfer_type(),
+                    )
+                });
+                bulk_endpoints(endpoints).map(|(i, o)| (descriptor.interface_number(), i, o))
+            })
+            .ok_or_else(|| {
+                io::Error::new(io::ErrorKind::NotFound, "no bulk endpoints on USB device")
+            })?;
+        // Not supported on every platform; claiming fails later if a kernel driver holds it.
+        let _ = handle.set_auto_detach_kernel_driver(true);
+        handle.claim_interface(interface).map_err(usb_error)?;
+        Ok(UsbPort {
+            handle,
+            interface,
+            endpoint_in,
+            endpoint_out,
+            timeout,
+            buf: Vec::new(),
+            pos: 0,
+        })
+    }
+}
+
+impl Drop for UsbPort {
+    fn drop(&mut self) {
+        let _ = self.handle.release_interface(self.interface);
+    }
+}
+
+impl Read for UsbPort {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // A bulk transfer must read the whole packet, so keep what the caller didn't take.
+        if self.pos == self.buf.len() {
+            self.buf.resize(MAX_PACKET_LENGTH, 0);
+            let len = self
+                .handle
+                .read_bulk(self.endpoint_in, &mut self.buf, self.timeout)
+                .map_err(usb_error)?;
+            self.buf.truncate(len);
+            self.pos = 0;
+        }
+        let len = buf.len().min(self.buf.len() - self.pos);
+        buf[..len].copy_from_slice(&self.buf[self.pos..self.pos + len]);
+        self.pos += len;
+        Ok(len)
+    }
+}
+
+impl Write for UsbPort {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.handle
+            .write_bulk(self.endpoint_out, buf, self.timeout)
+            .map_err(usb_error)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Receive buffer length, large enough for any response of boot ROM.
+const MAX_PACKET_LENGTH: usize = 4096;
+
+/// Find the first bulk IN and bulk OUT endpoint addresses of an interface.
+fn bulk_endpoints(
+    endpoints: impl IntoIterator<Item = (u8, Direction, TransferType)>,
+) -> Option<(u8, u8)> {
+    let (mut endpoint_in, mut endpoint_out) = (None, None);
+    for (address, direction, transfer_type) in endpoints {
+        match (direction, transfer_type) {
+            (Direction::In, TransferType::Bulk) => {
+                endpoint_in.get_or_insert(address);
+            }
+            (Direction::Out, TransferType::Bulk) => {
+                endpoint_out.get_or_insert(address);
+            }
+            _ => {}
+        }
+    }
+    Some((endpoint_in?, endpoint_out?))
+}
+
+fn usb_error(e: rusb::Error) -> io::Error {
+    let kind = match e {
+        rusb::Error::Timeout => io::ErrorKind::TimedOut,
+        rusb::Error::NoDevice | rusb::Error::NotFound => io::ErrorKind::NotFound,
+        rusb::Error::Access => io::ErrorKind::PermissionDenied,
+        _ => io::ErrorKind::Other,
+    };
+    io::Error::new(kind, e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::bulk_endpoints;
+    use rusb::{Direction, TransferType};
+
+    #[test]
+    fn bulk_endpoints_selection() {
+        let endpoints = [
+            (0x83, Direction::In, TransferType::Interrupt),
+            (0x81, Direction::In, TransferType::Bulk),
+            (0x02, Direction::Out, TransferType::Bulk),
+            (0x82, Direction::In, TransferType::Bulk),
+        ];
+        assert_eq!(bulk_endpoints(endpoints), Some((0x81, 0x02)));
+        let endpoints = [(0x81, Direction::In, TransferType::Bulk)];
+        assert_eq!(bulk_endpoints(endpoints), None);
+    }
+}
@@ -3,6 +3,11 @@ use sha2::{Digest, Sha256};
 use std::fs::File;
 use std::io::{self, Read, Seek, SeekFrom, Write};
 
+pub mod flash_db;
+pub mod header;
+pub mod isp;
+pub mod ota;
+
 const HEAD_LENGTH: u64 = 0x160;
 const HEAD_MAGIC: u32 = 0x42464e50;
 const FLASH_MAGIC: u32 = 0x46434647;
@@ -28,6 +33,14 @@ pub enum Error {
     },
     #[error("Wrong sha256 checksum")]
     Sha256Checksum { wrong_checksum: Vec<u8> },
+    #[error("OTA slot offset 0x{offset:x} is not aligned to flash sector")]
+    OtaSlotAlignment { offset: u32 },
+    #[error(
+        "OTA slot at offset 0x{offset:x} overlaps previous region ending at 0x{previous_end:x}"
+    )]
+    OtaSlotOverlap { previous_end: u64, offset: u32 },
+    #[error("Wrong OTA active slot index {wrong_slot}")]
+    OtaActiveSlot { wrong_slot: u32 },
 }
 
 /// Process operations.
@@ -154,7 +167,7 @@ pub fn check(f: &mut File) -> Result<Operations> {
 pub fn process(f: &mut File, ops: &Operations) -> Result<()> {
     if let Some(hash_to_fill) = &ops.refill_hash {
         f.seek(SeekFrom::Start(0x90))?;
-        f.write(&hash_to_fill[..32])?;
+        f.write_all(&hash_to_fill[..32])?;
     }
     if let Some(header_crc_to_fill) = &ops.refill_header_crc {
         f.seek(SeekFrom::Start(0x15C))?;
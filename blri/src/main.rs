@@ -1,71 +1,343 @@
+use blri::flash_db::FlashDatabase;
+use blri::header::HalBootheader;
+use blri::isp::{IspError, UartIsp, UsbPort};
+use blri::ota::{self, Slot, SECTOR_SIZE};
 use blri::Error;
-use clap::Parser;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use sha2::{Digest, Sha256};
 use std::fs::{self, File};
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
 
 /// Bouffalo ROM image helper
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
-struct Args {
+#[command(args_conflicts_with_subcommands = true, arg_required_else_help = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Commands>,
+    #[command(flatten)]
+    patch: Patch,
+}
+
+#[derive(Subcommand, Debug)]
+enum Commands {
+    /// Flash ROM image to chip over serial In-System Programming
+    Flash(Flash),
+    /// Print decoded ROM image header without modifying the image
+    Inspect(Inspect),
+    /// Check ROM image header without modifying the image, and flash contents if connected to chip;
+    /// exit nonzero if patching is needed or flash differs
+    Verify(Verify),
+    /// Combine two ROM images into a dual-slot over-the-air update image
+    Ota(Ota),
+}
+
+/// Patch ROM image header
+#[derive(Args, Debug)]
+struct Patch {
     /// Input ROM image filename
-    input: String,
+    input: Option<String>,
     /// Write output to <filename>
     #[arg(short, long, value_name = "FILENAME")]
     output: Option<String>,
 }
 
+/// Flash ROM image
+#[derive(Args, Debug)]
+struct Flash {
+    /// ROM image filename
+    image: String,
+    #[command(flatten)]
+    connection: Connection,
+    #[command(flatten)]
+    placement: Placement,
+    /// Read back flash after writing and compare it with image
+    #[arg(long)]
+    verify: bool,
+}
+
+/// Connection to chip in download mode
+#[derive(Args, Debug)]
+struct Connection {
+    /// Connection to chip, over serial port or native USB of boot ROM
+    #[arg(long, value_enum, default_value_t = Transport::Serial)]
+    transport: Transport,
+    /// Serial port connected to chip
+    #[arg(short, long)]
+    port: Option<String>,
+    /// USB vendor and product identifier of chip in download mode, as `<vid>:<pid>` in hexadecimal
+    #[arg(long, value_name = "VID:PID", value_parser = parse_usb_id, default_value = "ffff:ffff")]
+    usb_id: (u16, u16),
+    /// Serial baudrate
+    #[arg(short, long, default_value_t = 2_000_000)]
+    baudrate: u32,
+    /// Enter download mode by driving BOOT over DTR and RESET over RTS
+    #[arg(long)]
+    auto_reset: bool,
+    /// Line level that activates BOOT and RESET on auto reset
+    #[arg(long, value_enum, default_value_t = ResetPolarity::Asserted)]
+    reset_polarity: ResetPolarity,
+}
+
+/// Location of image in flash
+#[derive(Args, Debug)]
+struct Placement {
+    /// Flash offset of image, must be aligned to 4 KiB sectors
+    #[arg(long, value_parser = parse_offset, conflicts_with = "partition")]
+    offset: Option<u32>,
+    /// Partition table file with lines of `<name> <offset> [size]`
+    #[arg(long, value_name = "FILENAME", requires = "partition")]
+    partition_table: Option<String>,
+    /// Use offset of named partition in partition table
+    #[arg(long, value_name = "NAME", requires = "partition_table")]
+    partition: Option<String>,
+}
+
+/// Connection to boot ROM in download mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Transport {
+    /// Serial port, over the chip UART or a USB-serial adapter
+    Serial,
+    /// Native USB peripheral of the chip, on chips whose boot ROM supports it
+    Usb,
+}
+
+/// Parse USB identifier `<vid>:<pid>` of two hexadecimal numbers.
+fn parse_usb_id(s: &str) -> Result<(u16, u16), String> {
+    let parse = |id: &str| {
+        let id = id.strip_prefix("0x").unwrap_or(id);
+        u16::from_str_radix(id, 16).map_err(|e| format!("invalid USB identifier `{s}`: {e}"))
+    };
+    let (vid, pid) = s
+        .split_once(':')
+        .ok_or_else(|| format!("invalid USB identifier `{s}`, expected `<vid>:<pid>`"))?;
+    Ok((parse(vid)?, parse(pid)?))
+}
+
+/// Line level of DTR and RTS that activates BOOT and RESET signals.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum ResetPolarity {
+    /// Asserting a line activates its signal, as with common auto download circuits
+    Asserted,
+    /// Deasserting a line activates its signal, for boards wired without inverters
+    Deasserted,
+}
+
+/// DTR and RTS line states with the time to hold them, in milliseconds.
+///
+/// BOOT is held while RESET is released, so boot ROM samples BOOT and stays in
+/// download mode; BOOT is released afterwards to free the pin for firmware.
+fn auto_reset_sequence(polarity: ResetPolarity) -> [(bool, bool, u64); 3] {
+    let level = |active: bool| active == (polarity == ResetPolarity::Asserted);
+    [
+        (level(true), level(true), 50),
+        (level(true), level(false), 100),
+        (level(false), level(false), 0),
+    ]
+}
+
+/// Reset chip into download mode over DTR and RTS lines.
+fn auto_reset(port: &mut dyn serialport::SerialPort, polarity: ResetPolarity) {
+    for (dtr, rts, hold) in auto_reset_sequence(polarity) {
+        port.write_data_terminal_ready(dtr)
+            .expect("set DTR line of serial port");
+        port.write_request_to_send(rts)
+            .expect("set RTS line of serial port");
+        std::thread::sleep(Duration::from_millis(hold));
+    }
+}
+
+/// Parse a decimal or `0x` prefixed hexadecimal number, naming it `what` in errors.
+fn parse_number(s: &str, what: &str) -> Result<u32, String> {
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => s.parse(),
+    }
+    .map_err(|e| format!("invalid {what} `{s}`: {e}"))
+}
+
+/// Parse a decimal or `0x` prefixed hexadecimal sector aligned offset.
+fn parse_offset(s: &str) -> Result<u32, String> {
+    let offset = parse_number(s, "offset")?;
+    if offset % SECTOR_SIZE != 0 {
+        return Err(format!(
+            "offset 0x{offset:x} is not aligned to {SECTOR_SIZE} bytes flash sector"
+        ));
+    }
+    Ok(offset)
+}
+
+/// Parse a decimal or `0x` prefixed hexadecimal nonzero size in bytes.
+fn parse_size(s: &str) -> Result<u32, String> {
+    match parse_number(s, "size")? {
+        0 => Err(format!("invalid size `{s}`: must not be zero")),
+        size => Ok(size),
+    }
+}
+
+/// Named flash region in partition table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Partition {
+    name: String,
+    offset: u32,
+    size: Option<u32>,
+}
+
+/// Parse partition table, one `<name> <offset> [size]` entry per line.
+///
+/// Empty lines and text after `#` are ignored.
+fn parse_partition_table(text: &str) -> Result<Vec<Partition>, String> {
+    let mut partitions = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default();
+        let mut words = line.split_whitespace();
+        let Some(name) = words.next() else {
+            continue;
+        };
+        let error = |e: String| format!("line {}: {e}", number + 1);
+        let offset = words
+            .next()
+            .ok_or_else(|| error(format!("missing offset of partition `{name}`")))
+            .and_then(|s| parse_offset(s).map_err(error))?;
+        let size = words.next().map(parse_size).transpose().map_err(error)?;
+        if words.next().is_some() {
+            return Err(error("unexpected trailing words".to_string()));
+        }
+        partitions.push(Partition {
+            name: name.to_string(),
+            offset,
+            size,
+        });
+    }
+    Ok(partitions)
+}
+
+/// Resolve flash offset of image from command line arguments.
+fn flash_offset(args: &Placement, image_len: usize) -> Result<u32, String> {
+    let (Some(table), Some(name)) = (&args.partition_table, &args.partition) else {
+        return Ok(args.offset.unwrap_or(0));
+    };
+    let text = fs::read_to_string(table).map_err(|e| format!("read partition table: {e}"))?;
+    let partitions = parse_partition_table(&text)?;
+    let partition = partitions
+        .iter()
+        .find(|p| &p.name == name)
+        .ok_or_else(|| format!("no partition named `{name}` in {table}"))?;
+    if let Some(size) = partition.size {
+        if image_len > size as usize {
+            return Err(format!(
+                "image of {image_len} bytes does not fit in partition `{name}` of {size} bytes"
+            ));
+        }
+    }
+    Ok(partition.offset)
+}
+
+/// Inspect ROM image
+#[derive(Args, Debug)]
+struct Inspect {
+    /// ROM image filename
+    image: String,
+    /// Chip the image is built for
+    #[arg(long, value_enum, default_value_t = Chip::Bl808)]
+    chip: Chip,
+}
+
+/// Chip model a ROM image targets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Chip {
+    Bl808,
+    Bl616,
+    Bl702,
+}
+
+impl Chip {
+    /// Core names in order of CPU configurations in image header, if its layout is decoded.
+    fn cpu_names(self) -> Option<[&'static str; 3]> {
+        match self {
+            Chip::Bl808 => Some(["M0, E907", "D0, C906", "LP, E902"]),
+            // Image headers of these chips differ in layout from that of BL808.
+            Chip::Bl616 | Chip::Bl702 => None,
+        }
+    }
+}
+
+/// Verify ROM image
+#[derive(Args, Debug)]
+struct Verify {
+    /// ROM image filename
+    image: String,
+    /// With `--port` or `--transport usb`, also read back flash and compare it with image
+    #[command(flatten)]
+    connection: Connection,
+    #[command(flatten)]
+    placement: Placement,
+}
+
+/// Build dual-slot update image
+#[derive(Args, Debug)]
+struct Ota {
+    /// ROM image filename of slot A
+    #[arg(long, value_name = "FILENAME")]
+    slot_a: String,
+    /// ROM image filename of slot B
+    #[arg(long, value_name = "FILENAME")]
+    slot_b: String,
+    /// Write combined image to <filename>
+    #[arg(long, value_name = "FILENAME")]
+    out: String,
+    /// Offset of slot A in combined image, after the manifest sector
+    #[arg(long, value_parser = parse_offset, default_value = "0x1000")]
+    slot_a_offset: u32,
+    /// Offset of slot B in combined image; defaults to the first sector after slot A
+    #[arg(long, value_parser = parse_offset)]
+    slot_b_offset: Option<u32>,
+    /// Slot to boot from, `a` or `b`
+    #[arg(long, value_parser = parse_slot, default_value = "a")]
+    active: Slot,
+}
+
+/// Parse slot name `a` or `b`, case insensitive.
+fn parse_slot(s: &str) -> Result<Slot, String> {
+    match s {
+        "a" | "A" => Ok(Slot::A),
+        "b" | "B" => Ok(Slot::B),
+        _ => Err(format!("invalid slot `{s}`, expected `a` or `b`")),
+    }
+}
+
 fn main() {
-    let args = Args::parse();
-    let mut f_in = File::open(&args.input).expect("open input file");
+    let cli = Cli::parse();
+    match cli.command {
+        Some(Commands::Flash(flash)) => flash_image(flash),
+        Some(Commands::Inspect(inspect)) => inspect_image(inspect),
+        Some(Commands::Verify(verify)) => {
+            if !verify_image(verify) {
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Ota(args)) => ota_image(args),
+        None => patch_image(cli.patch),
+    }
+}
+
+fn patch_image(args: Patch) {
+    let input = args.input.expect("input ROM image filename");
+    let mut f_in = File::open(&input).expect("open input file");
 
     let ops = match blri::check(&mut f_in) {
         Ok(ops) => ops,
-        Err(e) => match e {
-            Error::MagicNumber { wrong_magic } => {
-                println!("error: incorrect magic number 0x{wrong_magic:08x}!");
-                return;
-            }
-            Error::HeadLength { wrong_length } => {
-                println!("File is too short to include an image header, it only includes {wrong_length} bytes");
-                return;
-            }
-            Error::FlashConfigMagic { wrong_magic } => {
-                println!("error: incorrect flash config magic 0x{wrong_magic:08x}!");
-                return;
-            }
-            Error::ClockConfigMagic { wrong_magic } => {
-                println!("error: incorrect clock config magic 0x{wrong_magic:08x}!");
-                return;
-            }
-            Error::ImageOffsetOverflow {
-                file_length,
-                wrong_image_offset,
-                wrong_image_length,
-            } => {
-                println!(
-                    "error: file length is only {}, but offset is {} and image length is {}",
-                    file_length, wrong_image_offset, wrong_image_length
-                );
-                return;
-            }
-            Error::Sha256Checksum { wrong_checksum } => {
-                let mut wrong_checksum_hex = String::new();
-                for i in wrong_checksum {
-                    wrong_checksum_hex.push_str(&format!("{:02x}", i));
-                }
-                println!("error: wrong sha256 verification: {}.", wrong_checksum_hex);
-                return;
-            }
-            Error::Io(source) => {
-                println!("error: io error! {:?}", source);
-                return;
-            }
-        },
+        Err(e) => {
+            print_check_error(e);
+            return;
+        }
     };
 
-    let output = args.output.clone().unwrap_or(args.input.clone());
+    let output = args.output.clone().unwrap_or(input.clone());
 
-    if output != args.input {
-        fs::copy(&args.input, &output).expect("copy input to output");
+    if output != input {
+        fs::copy(&input, &output).expect("copy input to output");
     }
 
     // release input file
@@ -75,8 +347,532 @@ fn main() {
     let mut f_out = File::options()
         .write(true)
         .create(true)
+        .truncate(false)
         .open(output)
         .expect("open output file");
 
     blri::process(&mut f_out, &ops).expect("process file");
 }
+
+fn print_check_error(e: Error) {
+    match e {
+        Error::MagicNumber { wrong_magic } => {
+            println!("error: incorrect magic number 0x{wrong_magic:08x}!");
+        }
+        Error::HeadLength { wrong_length } => {
+            println!("File is too short to include an image header, it only includes {wrong_length} bytes");
+        }
+        Error::FlashConfigMagic { wrong_magic } => {
+            println!("error: incorrect flash config magic 0x{wrong_magic:08x}!");
+        }
+        Error::ClockConfigMagic { wrong_magic } => {
+            println!("error: incorrect clock config magic 0x{wrong_magic:08x}!");
+        }
+        Error::ImageOffsetOverflow {
+            file_length,
+            wrong_image_offset,
+            wrong_image_length,
+        } => {
+            println!(
+                "error: file length is only {}, but offset is {} and image length is {}",
+                file_length, wrong_image_offset, wrong_image_length
+            );
+        }
+        Error::Sha256Checksum { wrong_checksum } => {
+            let mut wrong_checksum_hex = String::new();
+            for i in wrong_checksum {
+                wrong_checksum_hex.push_str(&format!("{:02x}", i));
+            }
+            println!("error: wrong sha256 verification: {}.", wrong_checksum_hex);
+        }
+        Error::OtaSlotAlignment { offset } => {
+            println!("error: slot offset 0x{offset:x} is not aligned to {SECTOR_SIZE} bytes flash sector");
+        }
+        Error::OtaSlotOverlap {
+            previous_end,
+            offset,
+        } => {
+            println!("error: slot at offset 0x{offset:x} overlaps previous region ending at 0x{previous_end:x}");
+        }
+        Error::OtaActiveSlot { wrong_slot } => {
+            println!("error: incorrect active slot index {wrong_slot}!");
+        }
+        Error::Io(source) => {
+            println!("error: io error! {:?}", source);
+        }
+    }
+}
+
+/// Check image header read-only, returning whether it's already correct.
+///
+/// If connected to chip, flash contents are read back and compared with image as well.
+fn verify_image(args: Verify) -> bool {
+    let mut f = File::open(&args.image).expect("open image file");
+    let header_valid = match blri::check(&mut f) {
+        Ok(ops) => {
+            let state = |refill: bool| if refill { "INVALID" } else { "valid" };
+            println!("sha256 hash: {}", state(ops.refill_hash.is_some()));
+            println!("header crc: {}", state(ops.refill_header_crc.is_some()));
+            let valid = ops.refill_hash.is_none() && ops.refill_header_crc.is_none();
+            if !valid {
+                println!(
+                    "image header is not patched, run `blri {}` to fix",
+                    args.image
+                );
+            }
+            valid
+        }
+        Err(e) => {
+            print_check_error(e);
+            false
+        }
+    };
+    if args.connection.port.is_none() && args.connection.transport == Transport::Serial {
+        return header_valid;
+    }
+    let image = fs::read(&args.image).expect("read image file");
+    let offset = match flash_offset(&args.placement, image.len()) {
+        Ok(offset) => offset,
+        Err(e) => {
+            println!("error: {e}");
+            return false;
+        }
+    };
+    let Some(mut isp) = connect(&args.connection) else {
+        return false;
+    };
+    let flash_valid = verify_flash(&mut isp, offset, &image);
+    header_valid && flash_valid
+}
+
+fn ota_image(args: Ota) {
+    let mut images = Vec::new();
+    for filename in [&args.slot_a, &args.slot_b] {
+        let mut f = File::open(filename).expect("open slot image file");
+        match blri::check(&mut f) {
+            Ok(ops) if ops.refill_hash.is_some() || ops.refill_header_crc.is_some() => {
+                println!("warning: image header is not patched, run `blri {filename}` first");
+            }
+            Ok(_) => {}
+            Err(e) => print_check_error(e),
+        }
+        images.push(fs::read(filename).expect("read slot image file"));
+    }
+    let slot_a_end = args.slot_a_offset as usize + images[0].len();
+    let slot_b_offset = args
+        .slot_b_offset
+        .unwrap_or(slot_a_end.next_multiple_of(SECTOR_SIZE as usize) as u32);
+    let image = match ota::build(
+        &images[0],
+        args.slot_a_offset,
+        &images[1],
+        slot_b_offset,
+        args.active,
+    ) {
+        Ok(image) => image,
+        Err(e) => {
+            print_check_error(e);
+            return;
+        }
+    };
+    fs::write(&args.out, &image).expect("write combined image");
+    println!(
+        "slot a: 0x{:x} bytes at 0x{:x}",
+        images[0].len(),
+        args.slot_a_offset
+    );
+    println!(
+        "slot b: 0x{:x} bytes at 0x{:x}",
+        images[1].len(),
+        slot_b_offset
+    );
+    println!(
+        "active slot: {:?}, combined image of 0x{:x} bytes",
+        args.active,
+        image.len()
+    );
+}
+
+fn inspect_image(args: Inspect) {
+    let Some(cpu_names) = args.chip.cpu_names() else {
+        println!(
+            "error: image header of {:?} is not supported, only BL808 images can be inspected",
+            args.chip
+        );
+        return;
+    };
+    let image = fs::read(&args.image).expect("read image file");
+    let header = match HalBootheader::from_bytes(&image) {
+        Ok(header) => header,
+        Err(e) => {
+            print_check_error(e);
+            return;
+        }
+    };
+    let valid = |ok: bool| if ok { "valid" } else { "INVALID" };
+
+    println!("magic: 0x{:08x}", header.magic);
+    println!("revision: {}", header.revision);
+
+    println!(
+        "flash config: crc 0x{:08x} ({})",
+        header.flash_cfg.crc32,
+        valid(header.flash_cfg.is_crc_valid())
+    );
+    for (name, value) in header.flash_cfg.fields() {
+        println!("    {name}: 0x{value:x}");
+    }
+
+    println!(
+        "clock config: crc 0x{:08x} ({})",
+        header.clk_cfg.crc32,
+        valid(header.clk_cfg.is_crc_valid())
+    );
+    for (name, value) in header.clk_cfg.fields() {
+        println!("    {name}: {value}");
+    }
+
+    let basic = &header.basic_cfg;
+    let flag = basic.flag;
+    println!("basic config:");
+    println!("    group_image_offset: 0x{:x}", basic.group_image_offset);
+    println!("    aes_region_len: 0x{:x}", basic.aes_region_len);
+    println!("    img_len_cnt: 0x{:x}", basic.img_len_cnt);
+    println!("    flags:");
+    println!("        sign: {}", flag.sign());
+    println!("        encrypt_type: {}", flag.encrypt_type());
+    println!("        key_slot: {}", flag.key_slot());
+    println!("        xts_mode: {}", flag.xts_mode());
+    println!("        aes_region_lock: {}", flag.aes_region_lock());
+    println!("        no_segment: {}", flag.no_segment());
+    println!("        boot2_enable: {}", flag.boot2_enable());
+    println!("        boot2_rollback: {}", flag.boot2_rollback());
+    println!("        cpu_master_id: {}", flag.cpu_master_id());
+    println!("        notload_in_bootrom: {}", flag.notload_in_bootrom());
+    println!("        crc_ignore: {}", flag.crc_ignore());
+    println!("        hash_ignore: {}", flag.hash_ignore());
+    println!("        power_on_mm: {}", flag.power_on_mm());
+    println!("        em_sel: {}", flag.em_sel());
+    println!("        cmds_en: {}", flag.cmds_en());
+    println!("        cmds_wrap_mode: {}", flag.cmds_wrap_mode());
+    println!("        cmds_wrap_len: {}", flag.cmds_wrap_len());
+    println!("        icache_invalid: {}", flag.icache_invalid());
+    println!("        dcache_invalid: {}", flag.dcache_invalid());
+    println!("        fpga_halt_release: {}", flag.fpga_halt_release());
+    let hash_state = match header.calculate_hash(&image) {
+        Some(hash) if hash == basic.hash => "valid",
+        Some(_) => "INVALID",
+        None => "image body out of range",
+    };
+    println!("    hash: {} ({hash_state})", to_hex(&basic.hash));
+
+    for (i, cpu) in header.cpu_cfg.iter().enumerate() {
+        println!("cpu {i} ({}):", cpu_names[i]);
+        println!("    config_enable: {}", cpu.config_enable);
+        println!("    halt_cpu: {}", cpu.halt_cpu);
+        println!("    cache_flags: 0x{:02x}", cpu.cache_flags);
+        println!(
+            "    cache_range: 0x{:08x}..0x{:08x}",
+            cpu.cache_range_l, cpu.cache_range_h
+        );
+        println!(
+            "    image_address_offset: 0x{:08x}",
+            cpu.image_address_offset
+        );
+        println!("    boot_entry: 0x{:08x}", cpu.boot_entry);
+        println!("    msp_val: 0x{:08x}", cpu.msp_val);
+    }
+
+    println!("boot2_pt_table_0: 0x{:08x}", header.boot2_pt_table_0);
+    println!("boot2_pt_table_1: 0x{:08x}", header.boot2_pt_table_1);
+    println!(
+        "flash_cfg_table_addr: 0x{:08x}",
+        header.flash_cfg_table_addr
+    );
+    println!("flash_cfg_table_len: 0x{:x}", header.flash_cfg_table_len);
+    for (name, patches) in [
+        ("patch_on_read", &header.patch_on_read),
+        ("patch_on_jump", &header.patch_on_jump),
+    ] {
+        for (i, patch) in patches.iter().enumerate() {
+            if patch.addr != 0 || patch.value != 0 {
+                println!("{name}[{i}]: 0x{:08x} <- 0x{:08x}", patch.addr, patch.value);
+            }
+        }
+    }
+    println!(
+        "header crc: 0x{:08x} ({})",
+        header.crc32,
+        valid(header.is_crc_valid())
+    );
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn flash_image(args: Flash) {
+    let mut f = File::open(&args.image).expect("open image file");
+    match blri::check(&mut f) {
+        Ok(ops) if ops.refill_hash.is_some() || ops.refill_header_crc.is_some() => {
+            println!(
+                "warning: image header is not patched, run `blri {}` first",
+                args.image
+            );
+        }
+        Ok(_) => {}
+        Err(e) => print_check_error(e),
+    }
+    let image = fs::read(&args.image).expect("read image file");
+    let offset = match flash_offset(&args.placement, image.len()) {
+        Ok(offset) => offset,
+        Err(e) => {
+            println!("error: {e}");
+            return;
+        }
+    };
+    let Some(mut isp) = connect(&args.connection) else {
+        return;
+    };
+    write_image(&mut isp, offset, &image);
+    if args.verify && !verify_flash(&mut isp, offset, &image) {
+        std::process::exit(1);
+    }
+}
+
+/// Serial port or native USB device talking to boot ROM.
+trait Port: io::Read + io::Write {}
+
+impl<T: io::Read + io::Write> Port for T {}
+
+/// Connect to boot ROM in download mode, printing a hint and returning `None` on failure.
+fn connect(args: &Connection) -> Option<UartIsp<Box<dyn Port>>> {
+    match args.transport {
+        Transport::Serial => {
+            let Some(port_name) = args.port.as_deref() else {
+                println!("error: no serial port given, pass it with `--port`");
+                return None;
+            };
+            let mut port = serialport::new(port_name, args.baudrate)
+                .timeout(Duration::from_secs(2))
+                .open()
+                .expect("open serial port");
+            if args.auto_reset {
+                auto_reset(&mut *port, args.reset_polarity);
+            }
+            let mut isp = UartIsp::new(Box::new(port) as Box<dyn Port>);
+            match isp.handshake(args.baudrate) {
+                Ok(()) => Some(isp),
+                Err(IspError::Handshake) => {
+                    println!("no response from chip, please hold BOOT and press RESET to enter download mode, or use `--auto-reset` if the serial adapter drives them");
+                    None
+                }
+                Err(e) => panic!("handshake with chip: {e:?}"),
+            }
+        }
+        Transport::Usb => {
+            let (vid, pid) = args.usb_id;
+            match UsbPort::open(vid, pid, Duration::from_secs(2)) {
+                // no baudrate to synchronize on native USB
+                Ok(port) => Some(UartIsp::new(Box::new(port) as Box<dyn Port>)),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    println!(
+                        "error: {e}, please hold BOOT and press RESET to enter USB download mode"
+                    );
+                    None
+                }
+                Err(e) => panic!("open USB device: {e:?}"),
+            }
+        }
+    }
+}
+
+/// Erase and write image to flash over a connected In-System Programming client.
+fn write_image<P: io::Read + io::Write>(isp: &mut UartIsp<P>, offset: u32, image: &[u8]) {
+    let boot_info = isp.get_boot_info().expect("get boot info");
+    println!("bootrom version: 0x{:08x}", boot_info.bootrom_version);
+    let flash_id = isp.read_flash_id().expect("read flash id");
+    match FlashDatabase::new().get(flash_id) {
+        Some(chip) => println!("flash id: 0x{:06x} ({})", flash_id, chip.name),
+        None => println!(
+            "flash id: 0x{:06x} (unknown chip, check flash config in image header)",
+            flash_id
+        ),
+    }
+
+    println!("erasing 0x{:x} bytes from 0x{:x}", image.len(), offset);
+    isp.erase_flash(offset, image.len() as u32)
+        .expect("erase flash");
+    let mut progress = Progress::new("flashing", "flashed", image.len());
+    isp.write_flash(offset, image, |len| progress.update(len))
+        .expect("write flash");
+    progress.finish();
+    isp.write_check().expect("check flash write");
+}
+
+/// Read back flash from `offset` and compare with `image`, returning whether they match.
+fn verify_flash<P: io::Read + io::Write>(isp: &mut UartIsp<P>, offset: u32, image: &[u8]) -> bool {
+    let mut progress = Progress::new("verifying", "read", image.len());
+    let readback = isp
+        .read_flash(offset, image.len(), |len| progress.update(len))
+        .expect("read flash");
+    progress.finish();
+    match first_mismatch(image, &readback) {
+        None => {
+            println!(
+                "verify: flash matches image, sha256 {}",
+                to_hex(&Sha256::digest(image))
+            );
+            true
+        }
+        Some(i) => {
+            println!(
+                "error: flash differs from image, first mismatch at flash offset 0x{:x}",
+                offset as usize + i
+            );
+            false
+        }
+    }
+}
+
+/// Compare flash `readback` with `image`.
+///
+/// Returns `None` if they match, or offset of the first differing byte otherwise.
+fn first_mismatch(image: &[u8], readback: &[u8]) -> Option<usize> {
+    let common = image.len().min(readback.len());
+    match image.iter().zip(readback).position(|(a, b)| a != b) {
+        Some(i) => Some(i),
+        None if image.len() != readback.len() => Some(common),
+        None => None,
+    }
+}
+
+/// Progress bar with throughput and remaining time estimation.
+struct Progress {
+    action: &'static str,
+    finished: &'static str,
+    total: usize,
+    done: usize,
+    start: Instant,
+}
+
+impl Progress {
+    const BAR_WIDTH: usize = 30;
+
+    /// Start progress of `total` bytes, labeled with `action` and `finished` verbs.
+    fn new(action: &'static str, finished: &'static str, total: usize) -> Self {
+        Self {
+            action,
+            finished,
+            total,
+            done: 0,
+            start: Instant::now(),
+        }
+    }
+
+    /// Add `len` finished bytes and redraw progress bar.
+    fn update(&mut self, len: usize) {
+        self.done = (self.done + len).min(self.total);
+        let elapsed = self.start.elapsed().as_secs_f64();
+        let rate = self.done as f64 / elapsed.max(f64::EPSILON);
+        let eta = (self.total - self.done) as f64 / rate.max(f64::EPSILON);
+        let filled = Self::BAR_WIDTH * self.done / self.total.max(1);
+        print!(
+            "\r{}: [{}{}] {:3}% {:.1} KB/s ETA {:.0}s ",
+            self.action,
+            "#".repeat(filled),
+            " ".repeat(Self::BAR_WIDTH - filled),
+            100 * self.done / self.total.max(1),
+            rate / 1024.0,
+            eta,
+        );
+        io::stdout().flush().ok();
+    }
+
+    /// Finish progress and print effective rate.
+    fn finish(&self) {
+        let elapsed = self.start.elapsed().as_secs_f64();
+        println!(
+            "\n{} {} bytes in {:.2}s ({:.1} KB/s)",
+            self.finished,
+            self.done,
+            elapsed,
+            self.done as f64 / elapsed.max(f64::EPSILON) / 1024.0
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        auto_reset_sequence, first_mismatch, parse_offset, parse_partition_table, parse_size,
+        parse_usb_id, Partition, ResetPolarity,
+    };
+
+    #[test]
+    fn auto_reset_sequence_polarity() {
+        assert_eq!(
+            auto_reset_sequence(ResetPolarity::Asserted),
+            [(true, true, 50), (true, false, 100), (false, false, 0)]
+        );
+        assert_eq!(
+            auto_reset_sequence(ResetPolarity::Deasserted),
+            [(false, false, 50), (false, true, 100), (true, true, 0)]
+        );
+    }
+
+    #[test]
+    fn parse_usb_id_hex() {
+        assert_eq!(parse_usb_id("ffff:ffff"), Ok((0xffff, 0xffff)));
+        assert_eq!(parse_usb_id("0x349b:0x6160"), Ok((0x349b, 0x6160)));
+        assert!(parse_usb_id("ffff").is_err());
+        assert!(parse_usb_id("ffff:10000").is_err());
+    }
+
+    #[test]
+    fn parse_offset_alignment() {
+        assert_eq!(parse_offset("0x10000"), Ok(0x10000));
+        assert_eq!(parse_offset("8192"), Ok(0x2000));
+        assert!(parse_offset("0x10010").is_err());
+        assert!(parse_offset("boot").is_err());
+    }
+
+    #[test]
+    fn parse_size_nonzero() {
+        assert_eq!(parse_size("0x1234"), Ok(0x1234));
+        assert_eq!(parse_size("100"), Ok(100));
+        assert!(parse_size("0").is_err());
+        assert!(parse_size("fw").is_err());
+    }
+
+    #[test]
+    fn parse_partition_table_entries() {
+        let text = "# name offset size\nboot2 0x0 0x10000\n\nfw 0x10000 # application\n";
+        let partitions = parse_partition_table(text).unwrap();
+        assert_eq!(
+            partitions,
+            [
+                Partition {
+                    name: "boot2".to_string(),
+                    offset: 0x0,
+                    size: Some(0x10000),
+                },
+                Partition {
+                    name: "fw".to_string(),
+                    offset: 0x10000,
+                    size: None,
+                },
+            ]
+        );
+        assert!(parse_partition_table("fw\n").is_err());
+        assert!(parse_partition_table("fw 0x1001\n").is_err());
+    }
+
+    #[test]
+    fn first_mismatch_offset() {
+        let image = [0x11u8, 0x22, 0x33, 0x44];
+        assert_eq!(first_mismatch(&image, &image), None);
+        assert_eq!(first_mismatch(&image, &[0x11, 0x22, 0xff, 0x44]), Some(2));
+        assert_eq!(first_mismatch(&image, &[0x11, 0x22]), Some(2));
+    }
+}
@@ -0,0 +1,263 @@
+//! Dual-slot (A/B) over-the-air update image.
+//!
+//! A combined image starts with one flash sector of slot manifest, followed by both
+//! firmware images at their configured offsets. Gaps are filled with `0xff` as erased
+//! flash, so the combined image can be written to flash as a whole.
+//!
+//! # Manifest format
+//!
+//! All fields are little endian `u32`.
+//!
+//! | Offset | Field                                        |
+//! |--------|----------------------------------------------|
+//! | 0x00   | magic, ASCII `BLOT` (`0x544f4c42`)           |
+//! | 0x04   | manifest version, currently 1                |
+//! | 0x08   | active slot, 0 for slot A and 1 for slot B   |
+//! | 0x0c   | reserved, 0                                  |
+//! | 0x10   | slot A offset from start of combined image   |
+//! | 0x14   | slot A length in bytes                       |
+//! | 0x18   | slot A CRC32 (ISO-HDLC) of image bytes       |
+//! | 0x1c   | reserved, 0                                  |
+//! | 0x20   | slot B offset, length, CRC32 and reserved    |
+//! | 0x30   | CRC32 of manifest bytes 0x00 to 0x30         |
+//!
+//! A bootloader should boot the active slot if its CRC32 matches, and fall back to the
+//! other slot otherwise. An updater writes the new firmware into the inactive slot, then
+//! rewrites the manifest with updated slot entry and active slot index.
+
+use crate::{Error, Result};
+
+/// Magic number of slot manifest, ASCII `BLOT`.
+pub const MANIFEST_MAGIC: u32 = 0x544f4c42;
+/// Current slot manifest version.
+pub const MANIFEST_VERSION: u32 = 1;
+/// Length of slot manifest including its checksum.
+pub const MANIFEST_LENGTH: usize = 0x34;
+/// Flash sector size; erase and write offsets, manifest and slots are aligned to it.
+pub const SECTOR_SIZE: u32 = 4096;
+
+/// Firmware slot of dual-slot image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Slot {
+    /// First firmware slot.
+    A,
+    /// Second firmware slot.
+    B,
+}
+
+/// Location and checksum of a firmware slot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SlotEntry {
+    /// Offset from start of combined image.
+    pub offset: u32,
+    /// Image length in bytes.
+    pub length: u32,
+    /// CRC32 of image bytes.
+    pub crc32: u32,
+}
+
+/// Slot manifest at beginning of combined image.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Manifest {
+    /// Manifest version.
+    pub version: u32,
+    /// Slot to boot from.
+    pub active: Slot,
+    /// Slot A and slot B entries.
+    pub slots: [SlotEntry; 2],
+    /// Checksum of manifest.
+    pub crc32: u32,
+    calculated_crc32: u32,
+}
+
+impl Manifest {
+    /// Decode slot manifest from beginning of `buf`.
+    pub fn from_bytes(buf: &[u8]) -> Result<Self> {
+        if buf.len() < MANIFEST_LENGTH {
+            return Err(Error::HeadLength {
+                wrong_length: buf.len() as u64,
+            });
+        }
+        let magic = u32_at(buf, 0x00);
+        if magic != MANIFEST_MAGIC {
+            return Err(Error::MagicNumber { wrong_magic: magic });
+        }
+        let active = match u32_at(buf, 0x08) {
+            0 => Slot::A,
+            1 => Slot::B,
+            wrong_slot => return Err(Error::OtaActiveSlot { wrong_slot }),
+        };
+        let slot = |offset: usize| SlotEntry {
+            offset: u32_at(buf, offset),
+            length: u32_at(buf, offset + 4),
+            crc32: u32_at(buf, offset + 8),
+        };
+        Ok(Self {
+            version: u32_at(buf, 0x04),
+            active,
+            slots: [slot(0x10), slot(0x20)],
+            crc32: u32_at(buf, 0x30),
+            calculated_crc32: crc32(&buf[..0x30]),
+        })
+    }
+    /// Check if stored manifest checksum matches manifest content.
+    #[inline]
+    pub fn is_crc_valid(&self) -> bool {
+        self.calculated_crc32 == self.crc32
+    }
+    /// Get entry of `slot`.
+    #[inline]
+    pub fn slot(&self, slot: Slot) -> &SlotEntry {
+        &self.slots[slot as usize]
+    }
+    /// Check if image bytes of `slot` in combined `image` match its checksum.
+    ///
+    /// Returns `false` if the slot exceeds `image`.
+    pub fn is_slot_valid(&self, image: &[u8], slot: Slot) -> bool {
+        let entry = self.slot(slot);
+        let start = entry.offset as usize;
+        match image.get(start..start + entry.length as usize) {
+            Some(body) => crc32(body) == entry.crc32,
+            None => false,
+        }
+    }
+}
+
+/// Lay out both firmware images and slot manifest into a combined image.
+///
+/// Offsets should be aligned to flash sectors. Slot A must start after the manifest
+/// sector, and slot B after the end of slot A.
+pub fn build(
+    slot_a: &[u8],
+    slot_a_offset: u32,
+    slot_b: &[u8],
+    slot_b_offset: u32,
+    active: Slot,
+) -> Result<Vec<u8>> {
+    for offset in [slot_a_offset, slot_b_offset] {
+        if offset % SECTOR_SIZE != 0 {
+            return Err(Error::OtaSlotAlignment { offset });
+        }
+    }
+    if slot_a_offset < SECTOR_SIZE {
+        return Err(Error::OtaSlotOverlap {
+            previous_end: SECTOR_SIZE as u64,
+            offset: slot_a_offset,
+        });
+    }
+    let slot_a_end = slot_a_offset as u64 + slot_a.len() as u64;
+    if (slot_b_offset as u64) < slot_a_end {
+        return Err(Error::OtaSlotOverlap {
+            previous_end: slot_a_end,
+            offset: slot_b_offset,
+        });
+    }
+    let slots = [
+        SlotEntry {
+            offset: slot_a_offset,
+            length: slot_a.len() as u32,
+            crc32: crc32(slot_a),
+        },
+        SlotEntry {
+            offset: slot_b_offset,
+            length: slot_b.len() as u32,
+            crc32: crc32(slot_b),
+        },
+    ];
+    let mut image = vec![0xff; slot_b_offset as usize + slot_b.len()];
+    image[slot_a_offset as usize..][..slot_a.len()].copy_from_slice(slot_a);
+    image[slot_b_offset as usize..][..slot_b.len()].copy_from_slice(slot_b);
+    image[..MANIFEST_LENGTH].copy_from_slice(&encode_manifest(active, &slots));
+    Ok(image)
+}
+
+/// Encode slot manifest with its checksum.
+fn encode_manifest(active: Slot, slots: &[SlotEntry; 2]) -> [u8; MANIFEST_LENGTH] {
+    let mut buf = [0u8; MANIFEST_LENGTH];
+    let mut put = |offset: usize, value: u32| {
+        buf[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    };
+    put(0x00, MANIFEST_MAGIC);
+    put(0x04, MANIFEST_VERSION);
+    put(0x08, active as u32);
+    for (i, slot) in slots.iter().enumerate() {
+        put(0x10 + i * 0x10, slot.offset);
+        put(0x14 + i * 0x10, slot.length);
+        put(0x18 + i * 0x10, slot.crc32);
+    }
+    let crc = crc32(&buf[..0x30]);
+    buf[0x30..].copy_from_slice(&crc.to_le_bytes());
+    buf
+}
+
+#[inline]
+fn u32_at(buf: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(buf[offset..offset + 4].try_into().unwrap())
+}
+
+#[inline]
+fn crc32(buf: &[u8]) -> u32 {
+    crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC).checksum(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build, Manifest, Slot, MANIFEST_MAGIC};
+    use crate::Error;
+
+    #[test]
+    fn build_and_decode_manifest() {
+        let a = vec![0x11u8; 100];
+        let b = vec![0x22u8; 50];
+        let image = build(&a, 0x1000, &b, 0x2000, Slot::B).unwrap();
+        assert_eq!(image.len(), 0x2000 + 50);
+        assert_eq!(&image[..4], b"BLOT");
+        assert_eq!(image[0x1000 + 100], 0xff);
+        let manifest = Manifest::from_bytes(&image).unwrap();
+        assert!(manifest.is_crc_valid());
+        assert_eq!(manifest.version, 1);
+        assert_eq!(manifest.active, Slot::B);
+        assert_eq!(manifest.slot(Slot::A).offset, 0x1000);
+        assert_eq!(manifest.slot(Slot::A).length, 100);
+        assert_eq!(manifest.slot(Slot::B).offset, 0x2000);
+        assert!(manifest.is_slot_valid(&image, Slot::A));
+        assert!(manifest.is_slot_valid(&image, Slot::B));
+
+        let mut broken = image.clone();
+        broken[0x2000] ^= 0xff;
+        assert!(!manifest.is_slot_valid(&broken, Slot::B));
+        broken[0x08] = 0;
+        assert!(!Manifest::from_bytes(&broken).unwrap().is_crc_valid());
+    }
+
+    #[test]
+    fn build_errors() {
+        let a = vec![0u8; 0x1001];
+        assert!(matches!(
+            build(&a, 0x1000, &[], 0x2000, Slot::A),
+            Err(Error::OtaSlotOverlap {
+                previous_end: 0x2001,
+                offset: 0x2000
+            })
+        ));
+        assert!(matches!(
+            build(&[], 0x0, &[], 0x2000, Slot::A),
+            Err(Error::OtaSlotOverlap { offset: 0, .. })
+        ));
+        assert!(matches!(
+            build(&[], 0x1000, &[], 0x2010, Slot::A),
+            Err(Error::OtaSlotAlignment { offset: 0x2010 })
+        ));
+        assert!(matches!(
+            Manifest::from_bytes(&[0u8; 0x34]),
+            Err(Error::MagicNumber { wrong_magic: 0 })
+        ));
+        let mut buf = [0u8; 0x34];
+        buf[..4].copy_from_slice(&MANIFEST_MAGIC.to_le_bytes());
+        buf[0x08] = 2;
+        assert!(matches!(
+            Manifest::from_bytes(&buf),
+            Err(Error::OtaActiveSlot { wrong_slot: 2 })
+        ));
+    }
+}
@@ -103,3 +103,36 @@ fn error_sha256_checksum() {
         panic!("this test case should raise Sha256Sum error")
     }
 }
+
+#[test]
+fn decode_header() {
+    let header = blri::header::HalBootheader::from_bytes(CORRECT_IMAGE).expect("decode header");
+    assert!(header.is_crc_valid());
+    assert!(header.flash_cfg.is_crc_valid());
+    assert!(header.clk_cfg.is_crc_valid());
+    assert_eq!(
+        header.calculate_hash(CORRECT_IMAGE),
+        Some(header.basic_cfg.hash)
+    );
+    let res = blri::header::HalBootheader::from_bytes(&CORRECT_IMAGE[..0x123]);
+    assert!(matches!(
+        res,
+        Err(Error::HeadLength {
+            wrong_length: 0x123
+        })
+    ));
+}
+
+#[test]
+fn verify_correct_and_unpatched() {
+    let mut f = tempfile::tempfile().expect("create tempfile for test");
+    f.write_all(CORRECT_IMAGE).expect("prepare correct image");
+    let ops = blri::check(&mut f).expect("check correct image");
+    assert!(ops.refill_hash.is_none());
+    assert!(ops.refill_header_crc.is_none());
+    f.seek(SeekFrom::Start(0x15C)).expect("seek to header crc");
+    f.write_all(&[0; 4]).expect("prepare wrong header crc");
+    let ops = blri::check(&mut f).expect("check unpatched image");
+    assert!(ops.refill_hash.is_none());
+    assert!(ops.refill_header_crc.is_some());
+}
@@ -1,28 +1,901 @@
 //! System-on-Chip clock configuration.
 
+use crate::glb;
+use crate::hbn::{self, F32kSource, RootClockSource2};
+use crate::timer::{self, ClockSource, Mode};
+use core::ops::Deref;
 use embedded_time::rate::Hertz;
 
 /// Clock settings for current chip.
 #[derive(Debug, Clone)]
 pub struct Clocks {
-    // todo: clock setting fields
+    /// Crystal oscillator clock frequency.
     pub xtal: Hertz,
+    /// Processor clock frequency.
+    pub cpu: Hertz,
+    /// Processor bus clock frequency.
+    pub bus: Hertz,
+    /// Universal Asynchronous Receiver/Transmitter 0 to 2 clock frequency.
+    pub uart: Hertz,
+    /// Serial Peripheral Interface clock frequency.
+    pub spi: Hertz,
+    /// Inter-Integrated Circuit clock frequency.
+    pub i2c: Hertz,
 }
 
+/// Frequency of 160-MHz multiplexer PLL output.
+const MUX_PLL_160M_HZ: u32 = 160_000_000;
+/// Frequency of 240-MHz multiplexer PLL output.
+const MUX_PLL_240M_HZ: u32 = 240_000_000;
+/// Frequency of 320-MHz multiplexer PLL output.
+const MUX_PLL_320M_HZ: u32 = 320_000_000;
+/// Frequency of internal 32-MHz RC oscillator.
+const RC32M_HZ: u32 = 32_000_000;
+/// BL702 phase-locked loop outputs selectable as root clock.
+const BL702_PLL_HZ: [u32; 3] = [57_600_000, 96_000_000, 144_000_000];
+/// BL702 phase-locked loop output selectable as uart clock.
+const BL702_UART_PLL_HZ: u32 = 96_000_000;
+
 impl Clocks {
+    /// Read clock settings of BL808 M0 core or BL616 from `glb` and `hbn` registers.
+    ///
+    /// Processor clock follows root clock selection, phase-locked loop output and
+    /// processor divider; bus and peripheral clocks are derived from it. `cpu_pll` is the
+    /// processor phase-locked loop on BL808, or `None` on BL616. Returns
+    /// `ClockError::UnknownCpuSource` if processor runs on a phase-locked loop not read here.
+    #[inline]
+    pub fn from_registers<HBN: Deref<Target = hbn::RegisterBlock>>(
+        glb: &glb::v2::RegisterBlock,
+        hbn: &HBN,
+        cpu_pll: Option<&glb::pll::RegisterBlock>,
+        xtal: Hertz,
+    ) -> Result<Clocks, ClockError> {
+        let global = hbn.global.read();
+        let config = glb.mcu_clock_config.read();
+        let root = match global.root_clock_2() {
+            RootClockSource2::Xclk => xclk_frequency(global.root_clock_1(), xtal.0),
+            RootClockSource2::Pllsel => match (config.pll_select(), cpu_pll) {
+                (glb::v2::McuPllSource::CpuPll, Some(pll)) => pll_frequency(pll, xtal.0)?,
+                (glb::v2::McuPllSource::MuxPll240M, _) => MUX_PLL_240M_HZ,
+                (glb::v2::McuPllSource::MuxPll320M, _) => MUX_PLL_320M_HZ,
+                _ => return Err(ClockError::UnknownCpuSource),
+            },
+        };
+        let cpu = root / (config.cpu_divide() as u32 + 1);
+        Ok(Clocks::from_cpu(glb, hbn, xtal, Hertz(cpu)))
+    }
+    /// Read clock settings of BL808 D0 core from `mm_glb`, `glb` and `hbn` registers.
+    ///
+    /// Processor clock follows D0 root clock selection and divider in `mm_glb`; bus and
+    /// peripheral clocks follow M0 clock tree as in [`Clocks::from_registers`].
+    #[inline]
+    pub fn from_registers_d0<HBN: Deref<Target = hbn::RegisterBlock>>(
+        glb: &glb::v2::RegisterBlock,
+        mm_glb: &glb::mm::RegisterBlock,
+        hbn: &HBN,
+        cpu_pll: &glb::pll::RegisterBlock,
+        xtal: Hertz,
+    ) -> Result<Clocks, ClockError> {
+        let config = mm_glb.cpu_config_0.read();
+        let root = match config.cpu_root_clock_source() {
+            glb::mm::CpuRootClockSource::Xclk => {
+                xclk_frequency(hbn.global.read().root_clock_1(), xtal.0)
+            }
+            glb::mm::CpuRootClockSource::Pll => match config.cpu_clock_source() {
+                glb::mm::CpuClockSource::MuxPll240M => MUX_PLL_240M_HZ,
+                glb::mm::CpuClockSource::MuxPll320M => MUX_PLL_320M_HZ,
+                glb::mm::CpuClockSource::CpuPll400M => pll_frequency(cpu_pll, xtal.0)?,
+            },
+        };
+        let cpu = root / (mm_glb.cpu_config_1.read().cpu_clock_divide() as u32 + 1);
+        let bus = Self::from_registers(glb, hbn, Some(cpu_pll), xtal)?;
+        Ok(Clocks {
+            cpu: Hertz(cpu),
+            ..bus
+        })
+    }
+    /// Read clock settings of BL702 from `glb` version 1 and `hbn` registers.
+    ///
+    /// Returns `ClockError::UnknownCpuSource` if root clock selects a reserved
+    /// phase-locked loop output.
+    #[inline]
+    pub fn from_registers_v1<HBN: Deref<Target = hbn::RegisterBlock>>(
+        glb: &glb::v1::RegisterBlock,
+        hbn: &HBN,
+        xtal: Hertz,
+    ) -> Result<Clocks, ClockError> {
+        let config = glb.clock_config_0.read();
+        let root = match config.root_clock_2() {
+            RootClockSource2::Xclk => xclk_frequency(config.root_clock_1(), xtal.0),
+            RootClockSource2::Pllsel => *BL702_PLL_HZ
+                .get(config.pll_select() as usize)
+                .ok_or(ClockError::UnknownCpuSource)?,
+        };
+        let cpu = root / (config.cpu_divide() as u32 + 1);
+        let bus = cpu / (config.bus_divide() as u32 + 1);
+        let uart_source = if hbn.global.read().is_uart_clock_pll_96m() {
+            BL702_UART_PLL_HZ
+        } else {
+            cpu
+        };
+        let dividers = glb.clock_config_3.read();
+        Ok(Clocks {
+            xtal,
+            cpu: Hertz(cpu),
+            bus: Hertz(bus),
+            uart: Hertz(uart_source / (glb.clock_config_2.read().uart_divide() as u32 + 1)),
+            spi: Hertz(bus / (dividers.spi_divide() as u32 + 1)),
+            i2c: Hertz(bus / (dividers.i2c_divide() as u32 + 1)),
+        })
+    }
+    /// Read peripheral clock settings from `glb` and `hbn` registers for processor
+    /// frequency `cpu`; bus clock is derived from it by the bus divider.
+    #[inline]
+    fn from_cpu<HBN: Deref<Target = hbn::RegisterBlock>>(
+        glb: &glb::v2::RegisterBlock,
+        hbn: &HBN,
+        xtal: Hertz,
+        cpu: Hertz,
+    ) -> Clocks {
+        let uart = glb.uart_config.read();
+        let spi = glb.spi_config.read();
+        let i2c = glb.i2c_config.read();
+        derive_clocks(
+            xtal.0,
+            cpu.0,
+            glb.mcu_clock_config.read().bus_divide(),
+            (hbn.global.read().uart_clock_source(), uart.clock_divide()),
+            (spi.clock_source(), spi.clock_divide()),
+            (i2c.clock_source(), i2c.clock_divide()),
+        )
+    }
     /// Crystal oscillator clock frequency.
     #[inline]
     pub const fn xclk(&self) -> Hertz {
         self.xtal
     }
+    /// Processor clock frequency.
+    #[inline]
+    pub const fn cpu_clk(&self) -> Hertz {
+        self.cpu
+    }
+    /// Processor bus clock frequency.
+    #[inline]
+    pub const fn bus_clk(&self) -> Hertz {
+        self.bus
+    }
+    /// Universal Asynchronous Receiver/Transmitter 0 to 2 clock frequency.
+    #[inline]
+    pub const fn uart_clk(&self) -> Hertz {
+        self.uart
+    }
+    /// Serial Peripheral Interface clock frequency.
+    #[inline]
+    pub const fn spi_clk(&self) -> Hertz {
+        self.spi
+    }
+    /// Inter-Integrated Circuit clock frequency.
+    #[inline]
+    pub const fn i2c_clk(&self) -> Hertz {
+        self.i2c
+    }
     /// Universal Asynchronous Receiver/Transmitter clock frequency.
     #[inline]
     pub const fn uart_clock<const I: usize>(&self) -> Option<Hertz> {
-        // todo: calculate from Clocks structure fields
         match I {
-            0..=2 => Some(Hertz(80_000_000)),
-            3..=4 => Some(Hertz(160_000_000)),
+            0..=2 => Some(self.uart),
+            3..=4 => Some(Hertz(MUX_PLL_160M_HZ)),
             _ => unreachable!(),
         }
     }
 }
+
+/// Compute clock settings from processor frequency, bus divider, and peripheral
+/// clock sources with their dividers.
+#[inline]
+const fn derive_clocks(
+    xtal: u32,
+    cpu: u32,
+    bus_divide: u8,
+    uart: (hbn::UartClockSource, u8),
+    spi: (glb::v2::SpiClockSource, u8),
+    i2c: (glb::v2::I2cClockSource, u8),
+) -> Clocks {
+    let bus = cpu / (bus_divide as u32 + 1);
+    let uart_source = match uart.0 {
+        hbn::UartClockSource::McuBclk => bus,
+        hbn::UartClockSource::MuxPll160M => MUX_PLL_160M_HZ,
+        hbn::UartClockSource::Xclk => xtal,
+    };
+    let spi_source = match spi.0 {
+        glb::v2::SpiClockSource::MuxPll160M => MUX_PLL_160M_HZ,
+        glb::v2::SpiClockSource::Xclk => xtal,
+    };
+    let i2c_source = match i2c.0 {
+        glb::v2::I2cClockSource::Bclk => bus,
+        glb::v2::I2cClockSource::Xclk => xtal,
+    };
+    Clocks {
+        xtal: Hertz(xtal),
+        cpu: Hertz(cpu),
+        bus: Hertz(bus),
+        uart: Hertz(uart_source / (uart.1 as u32 + 1)),
+        spi: Hertz(spi_source / (spi.1 as u32 + 1)),
+        i2c: Hertz(i2c_source / (i2c.1 as u32 + 1)),
+    }
+}
+
+/// Root clock frequency when crystal oscillator or RC oscillator is selected.
+#[inline]
+const fn xclk_frequency(source: hbn::RootClockSource1, xtal: u32) -> u32 {
+    match source {
+        hbn::RootClockSource1::RC32M => RC32M_HZ,
+        hbn::RootClockSource1::Xtal => xtal,
+    }
+}
+
+/// Read current output frequency of phase-locked loop `pll` from crystal oscillator `xtal`.
+#[inline]
+fn pll_frequency(pll: &glb::pll::RegisterBlock, xtal: u32) -> Result<u32, ClockError> {
+    let divide = pll.divide.read();
+    pll_output(
+        xtal,
+        divide.ref_divide(),
+        pll.feedback.read().ratio(),
+        divide.post_divide(),
+    )
+    .ok_or(ClockError::UnknownCpuSource)
+}
+
+/// Phase-locked loop output frequency from reference clock `xtal` and divider settings.
+///
+/// Returns `None` if a divider is zero, e.g. on an unconfigured phase-locked loop.
+#[inline]
+const fn pll_output(xtal: u32, ref_divide: u8, feedback: u32, post_divide: u8) -> Option<u32> {
+    if ref_divide == 0 || post_divide == 0 {
+        return None;
+    }
+    let out = xtal as u64 * feedback as u64 / ((ref_divide as u64 * post_divide as u64) << 11);
+    if out > u32::MAX as u64 {
+        return None;
+    }
+    Some(out as u32)
+}
+
+/// Measure actual frequency of a timer clock source.
+///
+/// This function uses both counters of `timer`: counter 0 runs on `source`, while
+/// counter 1 runs on crystal oscillator as reference. Both counters start in the same
+/// register write; once the reference counter passes 10 milliseconds of crystal cycles,
+/// the measured frequency is `target_count * xtal / reference_count`.
+///
+/// Any previous settings on both counters are lost. Phase-locked loop outputs are not
+/// selectable by timer directly; use `ClockSource::Bclk` to verify the bus clock derived
+/// from them, or `ClockSource::Gpio` with a clock output pad.
+#[inline]
+pub fn measure_clock<TIMER: Deref<Target = timer::RegisterBlock>>(
+    timer: &TIMER,
+    source: ClockSource,
+    clocks: &Clocks,
+) -> Hertz {
+    let gate = clocks.xtal.0 / 100;
+    unsafe {
+        timer.counter_enable.modify(|v| v.disable(0).disable(1));
+        timer.timer_config.modify(|v| {
+            v.set_clock_source(0, source)
+                .set_clock_source(1, ClockSource::Xtal)
+        });
+        timer
+            .clock_divide
+            .modify(|v| v.set_divide(0, 0).set_divide(1, 0));
+        timer
+            .counter_mode
+            .modify(|v| v.set_mode(0, Mode::FreeRun).set_mode(1, Mode::FreeRun));
+        timer.counter_enable.modify(|v| v.enable(0).enable(1));
+    }
+    let (target, reference) = loop {
+        let reference = timer.counter_value[1].read();
+        let target = timer.counter_value[0].read();
+        if reference >= gate {
+            break (target, reference);
+        }
+    };
+    unsafe {
+        timer.counter_enable.modify(|v| v.disable(0).disable(1));
+    }
+    Hertz((target as u64 * clocks.xtal.0 as u64 / reference as u64) as u32)
+}
+
+/// Errors on clock source startup or clock settings readout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClockError {
+    /// Main crystal oscillator did not report ready in time, e.g. it is missing or broken.
+    XtalNotReady,
+    /// Processor runs on a clock source whose frequency can't be read from registers,
+    /// e.g. audio phase-locked loop or an unconfigured phase-locked loop.
+    UnknownCpuSource,
+}
+
+/// Wait for main crystal oscillator to report stable oscillation.
+///
+/// Polls crystal ready status up to `timeout` times. On failure the chip may be running
+/// from its internal 32 MHz RC oscillator, so every frequency in [`Clocks`] could be wrong;
+/// firmware should stop, or switch to a configuration meant for RC oscillator on purpose.
+#[inline]
+pub fn wait_xtal_ready<HBN: Deref<Target = hbn::RegisterBlock>>(
+    hbn: &HBN,
+    timeout: usize,
+) -> Result<(), ClockError> {
+    if (0..timeout).any(|_| hbn.xtal_status.read().is_xtal_ready()) {
+        Ok(())
+    } else {
+        Err(ClockError::XtalNotReady)
+    }
+}
+
+/// Errors on processor frequency change.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CpuFreqError {
+    /// Frequency is not one of the steps listed by [`cpu_freq_steps`].
+    InvalidStep {
+        /// Requested frequency.
+        requested: Hertz,
+    },
+    /// Bus clock divider update was not acknowledged by hardware.
+    BusDivideTimeout,
+}
+
+/// Highest processor bus clock frequency.
+const BUS_CLOCK_MAX_HZ: u32 = 80_000_000;
+/// Number of status reads to wait for bus clock divider update.
+const BUS_DIVIDE_TIMEOUT: usize = 1024;
+
+/// List valid processor frequency steps, from fastest to slowest.
+///
+/// Steps are the processor root clock divided by integers from 1 to 256, keeping only
+/// exact frequencies. Root clock is the one set up at boot, so no step exceeds the boot
+/// processor frequency at divider 1.
+#[inline]
+pub fn cpu_freq_steps(
+    glb: &glb::v2::RegisterBlock,
+    clocks: &Clocks,
+) -> impl Iterator<Item = Hertz> {
+    let root = cpu_root(glb, clocks);
+    (1..=256u32)
+        .filter(move |div| root.is_multiple_of(*div))
+        .map(move |div| Hertz(root / div))
+}
+
+/// Change processor frequency at runtime, e.g. for power or thermal management.
+///
+/// The processor root clock is switched to crystal oscillator while dividers are
+/// reprogrammed, and switched back afterwards. Bus clock runs at the highest frequency
+/// up to 80 MHz that divides from new processor frequency, thus it follows processor
+/// clock at low frequency steps. Flash controller clock is derived separately and
+/// frequencies never exceed the boot processor frequency, so flash read timing is left
+/// unchanged.
+///
+/// Returns updated clock settings. Drivers holding rates derived from bus clock, e.g.
+/// UART 0 to 2 baudrate, I2C and SPI clock dividers, are not notified: they should be
+/// released and created again with the returned `Clocks`, or keep their own settings
+/// if `bus` of returned `Clocks` is unchanged.
+pub fn set_cpu_freq<HBN>(
+    glb: &glb::v2::RegisterBlock,
+    hbn: &HBN,
+    clocks: &Clocks,
+    freq: Hertz,
+) -> Result<Clocks, CpuFreqError>
+where
+    HBN: Deref<Target = hbn::RegisterBlock>,
+{
+    let root = cpu_root(glb, clocks);
+    let (cpu_divide, bus_divide) = cpu_dividers(root, freq.0, BUS_CLOCK_MAX_HZ)
+        .ok_or(CpuFreqError::InvalidStep { requested: freq })?;
+    let global = hbn.global.read();
+    hbn.global
+        .write(global.set_root_clock_2(RootClockSource2::Xclk));
+    let done = write_dividers(glb, cpu_divide, bus_divide);
+    hbn.global.write(global);
+    if !done {
+        return Err(CpuFreqError::BusDivideTimeout);
+    }
+    Ok(Clocks::from_cpu(glb, hbn, clocks.xtal, freq))
+}
+
+/// Processor root clock source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CpuClockSource {
+    /// Crystal oscillator, divided down to requested frequency.
+    Xtal,
+    /// Processor phase-locked loop, reprogrammed for requested frequency.
+    CpuPll,
+}
+
+/// Errors on processor clock configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CpuClockError {
+    /// Frequency can't be generated exactly from the requested source.
+    Unreachable {
+        /// Requested frequency.
+        requested: Hertz,
+    },
+    /// Phase-locked loop did not lock in time; processor stays on crystal oscillator.
+    PllNotLocked,
+    /// Bus clock divider update was not acknowledged by hardware.
+    BusDivideTimeout,
+}
+
+/// Lowest phase-detector frequency, i.e. reference clock after reference divider.
+const PLL_PFD_MIN_HZ: u32 = 8_000_000;
+/// Highest phase-detector frequency.
+const PLL_PFD_MAX_HZ: u32 = 40_000_000;
+/// Lowest voltage controlled oscillator frequency.
+const PLL_VCO_MIN_HZ: u32 = 800_000_000;
+/// Highest voltage controlled oscillator frequency.
+const PLL_VCO_MAX_HZ: u32 = 1_600_000_000;
+/// Largest phase-locked loop reference divide factor.
+const PLL_REF_DIVIDE_MAX: u32 = 15;
+/// Largest phase-locked loop output divide factor; only even factors are used.
+const PLL_POST_DIVIDE_MAX: u32 = 62;
+/// Largest phase-locked loop feedback ratio, with 11 fractional bits.
+const PLL_FEEDBACK_MAX: u64 = 0x7ffff;
+/// Number of status reads to wait for phase-locked loop to lock.
+const PLL_LOCK_TIMEOUT: usize = 100_000;
+
+/// Phase-locked loop divider settings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct PllDividers {
+    ref_divide: u8,
+    feedback: u32,
+    post_divide: u8,
+}
+
+/// Set processor clock to `freq` from `source`, and update `clocks` accordingly.
+///
+/// For `CpuClockSource::CpuPll`, the phase-locked loop `pll` is reprogrammed to the
+/// lowest output that reaches `freq` with processor divider, and the bus divider keeps
+/// bus clock at or below 80 MHz. To avoid glitches, the processor runs from crystal
+/// oscillator while dividers and the phase-locked loop change, and is only switched to
+/// the phase-locked loop once it reports lock. If it doesn't lock in time, the processor
+/// is left on crystal oscillator, `clocks` describes that, and `PllNotLocked` is returned.
+///
+/// The processor phase-locked loop may be shared with other cores, e.g. D0 core of
+/// BL808 on its 400 MHz setting; they follow the new frequency. Drivers holding rates
+/// derived from bus clock should be created again with updated `clocks`, as with
+/// [`set_cpu_freq`].
+pub fn set_cpu_clock<HBN>(
+    glb: &glb::v2::RegisterBlock,
+    hbn: &HBN,
+    pll: &glb::pll::RegisterBlock,
+    freq: Hertz,
+    source: CpuClockSource,
+    clocks: &mut Clocks,
+) -> Result<(), CpuClockError>
+where
+    HBN: Deref<Target = hbn::RegisterBlock>,
+{
+    let xtal = clocks.xtal.0;
+    let unreachable = CpuClockError::Unreachable { requested: freq };
+    let (dividers, cpu_divide) = match source {
+        CpuClockSource::Xtal => (
+            None,
+            cpu_dividers(xtal, freq.0, u32::MAX).ok_or(unreachable)?.0,
+        ),
+        CpuClockSource::CpuPll => {
+            let (dividers, cpu_divide) = cpu_pll_settings(xtal, freq.0).ok_or(unreachable)?;
+            (Some(dividers), cpu_divide)
+        }
+    };
+    let bus_divide = freq.0.div_ceil(BUS_CLOCK_MAX_HZ);
+    if bus_divide > 256 {
+        return Err(unreachable);
+    }
+    let global = hbn.global.read();
+    hbn.global
+        .write(global.set_root_clock_2(RootClockSource2::Xclk));
+    if !write_dividers(glb, cpu_divide, (bus_divide - 1) as u8) {
+        *clocks = Clocks::from_cpu(glb, hbn, clocks.xtal, Hertz(xtal / (cpu_divide as u32 + 1)));
+        return Err(CpuClockError::BusDivideTimeout);
+    }
+    if let Some(dividers) = dividers {
+        if !write_pll(pll, dividers) {
+            *clocks =
+                Clocks::from_cpu(glb, hbn, clocks.xtal, Hertz(xtal / (cpu_divide as u32 + 1)));
+            return Err(CpuClockError::PllNotLocked);
+        }
+        unsafe {
+            glb.mcu_clock_config
+                .modify(|v| v.set_pll_select(glb::v2::McuPllSource::CpuPll))
+        };
+        hbn.global
+            .write(global.set_root_clock_2(RootClockSource2::Pllsel));
+    }
+    *clocks = Clocks::from_cpu(glb, hbn, clocks.xtal, freq);
+    Ok(())
+}
+
+/// Write processor and bus divide factors, and wait for bus clock to follow.
+///
+/// Returns `false` if bus divider update was not acknowledged in time.
+#[inline]
+fn write_dividers(glb: &glb::v2::RegisterBlock, cpu_divide: u8, bus_divide: u8) -> bool {
+    unsafe {
+        glb.mcu_clock_config
+            .modify(|v| v.set_cpu_divide(cpu_divide).set_bus_divide(bus_divide));
+        glb.bus_clock_gate.write(1);
+        glb.bus_clock_gate.write(0);
+    }
+    (0..BUS_DIVIDE_TIMEOUT).any(|_| glb.mcu_clock_status.read().is_bus_divide_done())
+}
+
+/// Program phase-locked loop with `dividers` and wait for lock.
+///
+/// Returns `false` if the phase-locked loop did not lock in time.
+#[inline]
+fn write_pll(pll: &glb::pll::RegisterBlock, dividers: PllDividers) -> bool {
+    unsafe {
+        pll.power.modify(|v| v.assert_reset().power_down());
+        pll.divide.modify(|v| {
+            v.set_ref_divide(dividers.ref_divide)
+                .set_post_divide(dividers.post_divide)
+        });
+        pll.feedback.modify(|v| v.set_ratio(dividers.feedback));
+        pll.power.modify(|v| v.power_up());
+        pll.power.modify(|v| v.deassert_reset());
+    }
+    (0..PLL_LOCK_TIMEOUT).any(|_| pll.status.read().is_locked())
+}
+
+/// Phase-locked loop dividers and processor divide factor for `cpu` frequency from
+/// crystal oscillator `xtal`.
+///
+/// The lowest processor divide factor is preferred, so the phase-locked loop runs
+/// as slow as possible.
+#[inline]
+const fn cpu_pll_settings(xtal: u32, cpu: u32) -> Option<(PllDividers, u8)> {
+    let mut cpu_divide = 1;
+    while cpu_divide <= 256 {
+        if let Some(out) = cpu.checked_mul(cpu_divide) {
+            if let Some(dividers) = pll_dividers(xtal, out) {
+                return Some((dividers, (cpu_divide - 1) as u8));
+            }
+        }
+        cpu_divide += 1;
+    }
+    None
+}
+
+/// Phase-locked loop dividers generating exactly `out` from reference clock `xtal`.
+///
+/// Reference dividers are tried from the smallest, and output dividers from the
+/// smallest even factor keeping the oscillator in its operating range.
+#[inline]
+const fn pll_dividers(xtal: u32, out: u32) -> Option<PllDividers> {
+    if out == 0 {
+        return None;
+    }
+    let mut ref_divide = 1;
+    while ref_divide <= PLL_REF_DIVIDE_MAX {
+        let pfd = xtal / ref_divide;
+        if xtal.is_multiple_of(ref_divide) && pfd >= PLL_PFD_MIN_HZ && pfd <= PLL_PFD_MAX_HZ {
+            let mut post_divide = 2;
+            while post_divide <= PLL_POST_DIVIDE_MAX {
+                let vco = out as u64 * post_divide as u64;
+                if vco > PLL_VCO_MAX_HZ as u64 {
+                    break;
+                }
+                if vco >= PLL_VCO_MIN_HZ as u64 && (vco << 11).is_multiple_of(pfd as u64) {
+                    let feedback = (vco << 11) / pfd as u64;
+                    if feedback <= PLL_FEEDBACK_MAX {
+                        return Some(PllDividers {
+                            ref_divide: ref_divide as u8,
+                            feedback: feedback as u32,
+                            post_divide: post_divide as u8,
+                        });
+                    }
+                }
+                post_divide += 2;
+            }
+        }
+        ref_divide += 1;
+    }
+    None
+}
+
+/// Processor root clock frequency before processor divider.
+#[inline]
+fn cpu_root(glb: &glb::v2::RegisterBlock, clocks: &Clocks) -> u32 {
+    clocks.cpu.0 * (glb.mcu_clock_config.read().cpu_divide() as u32 + 1)
+}
+
+/// Processor and bus divide factors for `cpu` frequency from `root`, keeping bus clock
+/// at or below `bus_max`.
+#[inline]
+const fn cpu_dividers(root: u32, cpu: u32, bus_max: u32) -> Option<(u8, u8)> {
+    if cpu == 0 || !root.is_multiple_of(cpu) || root / cpu > 256 {
+        return None;
+    }
+    let bus_divide = cpu.div_ceil(bus_max);
+    if bus_divide > 256 {
+        return None;
+    }
+    Some(((root / cpu - 1) as u8, (bus_divide - 1) as u8))
+}
+
+/// Low frequency clock source for Hibernation and Real-Time Clock.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LfClock {
+    /// External 32.768 kHz crystal oscillator.
+    Xtal32k,
+    /// Internal 32 kHz RC oscillator, calibrated against crystal oscillator.
+    Rc32k,
+}
+
+/// Errors on low frequency clock selection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LfClockError {
+    /// External crystal did not start or runs off frequency; internal RC oscillator is selected.
+    XtalNotStarted {
+        /// Last measured frequency of external crystal oscillator.
+        measured: Hertz,
+    },
+    /// Internal RC oscillator did not report ready after power up.
+    RcNotReady,
+}
+
+/// Nominal frequency of low frequency clock.
+const LF_CLOCK_HZ: u32 = 32_768;
+/// Allowed deviation of a running external crystal, in parts per thousand.
+const XTAL32K_TOLERANCE: u32 = 10;
+/// Number of 10 millisecond measurements to wait for external crystal to start.
+const XTAL32K_STARTUP_ROUNDS: u32 = 100;
+/// Number of status reads to wait for internal RC oscillator to be ready.
+const RC32K_READY_TIMEOUT: usize = 1 << 20;
+
+/// Select low frequency clock source for Hibernation and Real-Time Clock.
+///
+/// Both options are verified with [`measure_clock`] on `timer`, thus any previous settings
+/// on both of its counters are lost. Returns measured frequency of the new active source.
+///
+/// For `LfClock::Xtal32k`, the crystal is powered up and measured for up to one second
+/// until it runs within 1% of 32.768 kHz. If it doesn't start in time, the internal RC
+/// oscillator is selected back and `LfClockError::XtalNotStarted` is returned.
+///
+/// For `LfClock::Rc32k`, the oscillator frequency code is trimmed by bisection against
+/// the main crystal oscillator, which takes about 13 measurements of 10 ms each; the
+/// external 32 kHz crystal is then powered down. Remaining error is typically below 1%,
+/// which should be taken into account for RTC timekeeping; use the external crystal when
+/// better accuracy is required. If the oscillator doesn't report ready after power up,
+/// `LfClockError::RcNotReady` is returned and clock selection is left unchanged.
+pub fn set_lf_clock<HBN, TIMER>(
+    hbn: &HBN,
+    timer: &TIMER,
+    clock: LfClock,
+    clocks: &Clocks,
+) -> Result<Hertz, LfClockError>
+where
+    HBN: Deref<Target = hbn::RegisterBlock>,
+    TIMER: Deref<Target = timer::RegisterBlock>,
+{
+    match clock {
+        LfClock::Xtal32k => {
+            unsafe { hbn.xtal32k.modify(|v| v.power_up()) };
+            hbn.global
+                .write(hbn.global.read().set_f32k_source(F32kSource::Xtal32K));
+            let mut measured = Hertz(0);
+            for _ in 0..XTAL32K_STARTUP_ROUNDS {
+                measured = measure_clock(timer, ClockSource::F32k, clocks);
+                if is_within_tolerance(measured.0, LF_CLOCK_HZ, XTAL32K_TOLERANCE) {
+                    return Ok(measured);
+                }
+            }
+            hbn.global
+                .write(hbn.global.read().set_f32k_source(F32kSource::RC32K));
+            unsafe { hbn.xtal32k.modify(|v| v.power_down()) };
+            Err(LfClockError::XtalNotStarted { measured })
+        }
+        LfClock::Rc32k => {
+            unsafe { hbn.rc32k.modify(|v| v.power_up()) };
+            if !(0..RC32K_READY_TIMEOUT).any(|_| hbn.rc32k.read().is_ready()) {
+                return Err(LfClockError::RcNotReady);
+            }
+            hbn.global
+                .write(hbn.global.read().set_f32k_source(F32kSource::RC32K));
+            let mut measure = |code: u16| {
+                unsafe {
+                    hbn.rc32k
+                        .modify(|v| v.set_external_code(code).enable_external_code())
+                };
+                measure_clock(timer, ClockSource::F32k, clocks).0
+            };
+            let code = trim_rc32k(&mut measure);
+            let frequency = measure(code);
+            unsafe { hbn.xtal32k.modify(|v| v.power_down()) };
+            Ok(Hertz(frequency))
+        }
+    }
+}
+
+/// Get active low frequency clock source.
+///
+/// Returns `None` if neither crystal nor RC oscillator is selected.
+#[inline]
+pub fn lf_clock<HBN: Deref<Target = hbn::RegisterBlock>>(hbn: &HBN) -> Option<LfClock> {
+    match hbn.global.read().f32k_source() {
+        F32kSource::Xtal32K => Some(LfClock::Xtal32k),
+        F32kSource::RC32K => Some(LfClock::Rc32k),
+        F32kSource::Dig32K => None,
+    }
+}
+
+/// Search for 10-bit RC oscillator code with output closest to 32.768 kHz.
+///
+/// Frequency is assumed to change monotonically with code, in either direction.
+fn trim_rc32k(measure: &mut impl FnMut(u16) -> u32) -> u16 {
+    let (mut low, mut high) = (0u16, 0x3ff);
+    let rising = measure(high) > measure(low);
+    let mut best = (low, u32::MAX);
+    while low <= high {
+        let code = low + (high - low) / 2;
+        let frequency = measure(code);
+        let error = frequency.abs_diff(LF_CLOCK_HZ);
+        if error < best.1 {
+            best = (code, error);
+        }
+        if (frequency < LF_CLOCK_HZ) == rising {
+            low = code + 1;
+        } else if code == 0 {
+            break;
+        } else {
+            high = code - 1;
+        }
+    }
+    best.0
+}
+
+/// Check if `measured` deviates from `nominal` within `permille` parts per thousand.
+#[inline]
+const fn is_within_tolerance(measured: u32, nominal: u32, permille: u32) -> bool {
+    measured.abs_diff(nominal) as u64 * 1000 <= nominal as u64 * permille as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        cpu_dividers, cpu_pll_settings, derive_clocks, is_within_tolerance, pll_dividers,
+        pll_output, trim_rc32k, PllDividers, LF_CLOCK_HZ,
+    };
+    use crate::glb::v2::{I2cClockSource, SpiClockSource};
+    use crate::hbn::UartClockSource;
+
+    #[test]
+    fn function_derive_clocks() {
+        let clocks = derive_clocks(
+            40_000_000,
+            320_000_000,
+            3,
+            (UartClockSource::McuBclk, 0),
+            (SpiClockSource::MuxPll160M, 3),
+            (I2cClockSource::Bclk, 1),
+        );
+        assert_eq!(clocks.cpu_clk().0, 320_000_000);
+        assert_eq!(clocks.bus_clk().0, 80_000_000);
+        assert_eq!(clocks.uart_clk().0, 80_000_000);
+        assert_eq!(clocks.uart_clock::<1>().unwrap().0, 80_000_000);
+        assert_eq!(clocks.uart_clock::<3>().unwrap().0, 160_000_000);
+        assert_eq!(clocks.spi_clk().0, 40_000_000);
+        assert_eq!(clocks.i2c_clk().0, 40_000_000);
+        let clocks = derive_clocks(
+            40_000_000,
+            160_000_000,
+            1,
+            (UartClockSource::MuxPll160M, 1),
+            (SpiClockSource::Xclk, 0),
+            (I2cClockSource::Xclk, 3),
+        );
+        assert_eq!(clocks.bus_clk().0, 80_000_000);
+        assert_eq!(clocks.uart_clk().0, 80_000_000);
+        assert_eq!(clocks.spi_clk().0, 40_000_000);
+        assert_eq!(clocks.i2c_clk().0, 10_000_000);
+        assert_eq!(clocks.xclk().0, 40_000_000);
+    }
+
+    #[test]
+    fn function_cpu_dividers() {
+        assert_eq!(
+            cpu_dividers(320_000_000, 320_000_000, 80_000_000),
+            Some((0, 3))
+        );
+        assert_eq!(
+            cpu_dividers(320_000_000, 160_000_000, 80_000_000),
+            Some((1, 1))
+        );
+        assert_eq!(
+            cpu_dividers(320_000_000, 64_000_000, 80_000_000),
+            Some((4, 0))
+        );
+        assert_eq!(cpu_dividers(320_000_000, 100_000_000, 80_000_000), None);
+        assert_eq!(cpu_dividers(320_000_000, 1_000_000, 80_000_000), None);
+        assert_eq!(cpu_dividers(320_000_000, 0, 80_000_000), None);
+    }
+
+    #[test]
+    fn function_pll_dividers() {
+        let pll = |ref_divide, feedback, post_divide| {
+            Some(PllDividers {
+                ref_divide,
+                feedback,
+                post_divide,
+            })
+        };
+        // 40 MHz crystal: 800 MHz oscillator divided by 2.
+        assert_eq!(pll_dividers(40_000_000, 400_000_000), pll(1, 40960, 2));
+        assert_eq!(pll_dividers(40_000_000, 480_000_000), pll(1, 49152, 2));
+        assert_eq!(pll_dividers(40_000_000, 100_000_000), pll(1, 40960, 8));
+        // 24 MHz crystal: 960 MHz oscillator divided by 2.
+        assert_eq!(pll_dividers(24_000_000, 480_000_000), pll(1, 81920, 2));
+        // Oscillator can't run fast enough, or output is too slow for largest divider.
+        assert_eq!(pll_dividers(40_000_000, 1_000_000_000), None);
+        assert_eq!(pll_dividers(40_000_000, 10_000_000), None);
+        assert_eq!(pll_dividers(40_000_000, 0), None);
+    }
+
+    #[test]
+    fn function_pll_output() {
+        assert_eq!(pll_output(40_000_000, 1, 40960, 2), Some(400_000_000));
+        assert_eq!(pll_output(40_000_000, 1, 40960, 8), Some(100_000_000));
+        assert_eq!(pll_output(24_000_000, 1, 81920, 2), Some(480_000_000));
+        let dividers = pll_dividers(40_000_000, 480_000_000).unwrap();
+        assert_eq!(
+            pll_output(
+                40_000_000,
+                dividers.ref_divide,
+                dividers.feedback,
+                dividers.post_divide
+            ),
+            Some(480_000_000)
+        );
+        // Unconfigured phase-locked loop.
+        assert_eq!(pll_output(40_000_000, 0, 40960, 2), None);
+        assert_eq!(pll_output(40_000_000, 1, 40960, 0), None);
+    }
+
+    #[test]
+    fn function_cpu_pll_settings() {
+        assert_eq!(
+            cpu_pll_settings(40_000_000, 400_000_000),
+            Some((pll_dividers(40_000_000, 400_000_000).unwrap(), 0))
+        );
+        // Slow processor clock comes from processor divider.
+        assert_eq!(
+            cpu_pll_settings(40_000_000, 10_000_000),
+            Some((pll_dividers(40_000_000, 20_000_000).unwrap(), 1))
+        );
+        assert_eq!(cpu_pll_settings(40_000_000, 1_000_000_000), None);
+        assert_eq!(cpu_pll_settings(40_000_000, 0), None);
+    }
+
+    #[test]
+    fn function_is_within_tolerance() {
+        assert!(is_within_tolerance(32_768, 32_768, 10));
+        assert!(is_within_tolerance(33_095, 32_768, 10));
+        assert!(!is_within_tolerance(33_100, 32_768, 10));
+        assert!(is_within_tolerance(32_441, 32_768, 10));
+        assert!(!is_within_tolerance(0, 32_768, 10));
+    }
+
+    #[test]
+    fn function_trim_rc32k() {
+        // Rising oscillator: 20 kHz at code 0, 30 Hz per step.
+        let mut rising = |code: u16| 20_000 + code as u32 * 30;
+        let code = trim_rc32k(&mut rising);
+        assert!(rising(code).abs_diff(LF_CLOCK_HZ) <= 15);
+        // Falling oscillator: 45 kHz at code 0, 25 Hz per step.
+        let mut falling = |code: u16| 45_000 - code as u32 * 25;
+        let code = trim_rc32k(&mut falling);
+        assert!(falling(code).abs_diff(LF_CLOCK_HZ) <= 13);
+        // Out of range oscillator is trimmed to the closest end.
+        let mut slow = |code: u16| 10_000 + code as u32;
+        assert_eq!(trim_rc32k(&mut slow), 0x3ff);
+    }
+}
@@ -1,17 +1,29 @@
 //! Display bus interface.
 
-use volatile_register::RW;
+use core::ops::Deref;
+use embedded_hal::spi::Mode;
+use volatile_register::{RO, RW, WO};
+
+mod display;
+pub use display::*;
 
 /// Display bus interface registers.
 #[repr(C)]
 pub struct RegisterBlock {
     /// Function configuration register.
     pub config: RW<Config>,
-    _reserved: [u8; 0x7b],
+    _reserved0: [u8; 0x4],
+    /// Bus busy state indicator.
+    pub bus_busy: RO<BusBusy>,
+    /// Number of pixels in one pixel transaction.
+    pub pixel_count: RW<PixelCount>,
+    _reserved1: [u8; 0x70],
     /// First-in first-out queue configuration 0.
     pub fifo_config_0: RW<FifoConfig0>,
     /// First-in first-out queue configuration 1.
     pub fifo_config_1: RW<FifoConfig1>,
+    /// First-in first-out queue write data register.
+    pub fifo_write: WO<u32>,
 }
 
 /// Function configuration register.
@@ -277,6 +289,41 @@ impl Config {
     }
 }
 
+/// Bus busy state indicator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct BusBusy(u32);
+
+impl BusBusy {
+    const BUS_BUSY: u32 = 1 << 0;
+
+    /// Check if bus is busy.
+    #[inline]
+    pub const fn is_bus_busy(self) -> bool {
+        self.0 & Self::BUS_BUSY != 0
+    }
+}
+
+/// Number of pixels in one pixel transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[repr(transparent)]
+pub struct PixelCount(u32);
+
+impl PixelCount {
+    const PIXEL_COUNT: u32 = 0xff_ffff;
+
+    /// Set number of pixels.
+    #[inline]
+    pub const fn set_pixel_count(self, val: u32) -> Self {
+        Self((self.0 & !Self::PIXEL_COUNT) | (val & Self::PIXEL_COUNT))
+    }
+    /// Get number of pixels.
+    #[inline]
+    pub const fn pixel_count(self) -> u32 {
+        self.0 & Self::PIXEL_COUNT
+    }
+}
+
 /// First-in first-out queue configuration 0.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
@@ -346,15 +393,176 @@ impl FifoConfig1 {
     }
 }
 
+/// Maximum number of pixels in one pixel transaction.
+pub const PIXEL_COUNT_MAX: u32 = 0xff_ffff;
+
+/// Managed Display Bus Interface peripheral in type C 4-wire mode.
+///
+/// Type C 4-wire mode is the SPI-like serial interface with a separate data/command signal,
+/// used by most small TFT panels.
+pub struct Dbi<DBI, PADS> {
+    dbi: DBI,
+    pads: PADS,
+}
+
+impl<DBI: Deref<Target = RegisterBlock>, PADS> Dbi<DBI, PADS> {
+    /// Create a new Display Bus Interface instance with clock polarity and phase of `mode`.
+    #[inline]
+    pub fn new(dbi: DBI, pads: PADS, mode: Mode) -> Self {
+        let config = Config(0)
+            .set_type_c()
+            .set_type_c_4_wire_mode()
+            .set_scl_polarity(mode.polarity == embedded_hal::spi::Polarity::IdleHigh)
+            .set_scl_phase(mode.phase == embedded_hal::spi::Phase::CaptureOnSecondTransition)
+            .disable_master();
+        unsafe {
+            dbi.config.write(config);
+            dbi.fifo_config_0
+                .modify(|v| v.disable_dma_transmit().clear_transmit_fifo());
+        }
+        Dbi { dbi, pads }
+    }
+
+    /// Release the DBI instance and return its peripheral and pads.
+    #[inline]
+    pub fn free(self) -> (DBI, PADS) {
+        (self.dbi, self.pads)
+    }
+
+    /// Send `command` followed by parameter bytes in `data`.
+    ///
+    /// Parameters are sent in frames of up to four bytes. Chip select stays asserted
+    /// between frames, thus panels see one command with all of its parameters.
+    pub fn write_command(&mut self, command: u8, data: &[u8]) {
+        let base = self
+            .dbi
+            .config
+            .read()
+            .set_command(command)
+            .set_data_normal()
+            .set_data_write()
+            .disable_dummy_cycle();
+        if data.is_empty() {
+            self.run(base.enable_command().disable_data());
+            return;
+        }
+        let frames = data.chunks(4);
+        let last = frames.len() - 1;
+        for (index, chunk) in frames.enumerate() {
+            let mut config = base
+                .enable_data()
+                .set_data_byte_count(chunk.len() as u8 - 1);
+            config = if index == 0 {
+                config.enable_command()
+            } else {
+                config.disable_command()
+            };
+            config = if index == last {
+                config.disable_continuous_transfer()
+            } else {
+                config.enable_continuous_transfer()
+            };
+            let mut word = [0u8; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            unsafe {
+                self.dbi.fifo_config_0.modify(|v| v.clear_transmit_fifo());
+                self.dbi.fifo_write.write(u32::from_le_bytes(word));
+            }
+            self.run(config);
+        }
+    }
+
+    /// Start a pixel transaction of `command` with `count` pixels fed by DMA.
+    ///
+    /// Pixels are written into [`RegisterBlock::fifo_write`] by a DMA channel with DBI
+    /// transmit request line; call [`Dbi::finish_pixels`] after the channel completes.
+    ///
+    /// # Panics
+    ///
+    /// If `count` is zero or exceeds [`PIXEL_COUNT_MAX`].
+    #[inline]
+    pub fn start_pixels(&mut self, command: u8, count: u32) {
+        assert!(
+            (1..=PIXEL_COUNT_MAX).contains(&count),
+            "pixel count should be in 1..=PIXEL_COUNT_MAX"
+        );
+        let config = self
+            .dbi
+            .config
+            .read()
+            .set_command(command)
+            .enable_command()
+            .enable_data()
+            .set_data_write()
+            .set_data_pixel()
+            .disable_continuous_transfer()
+            .disable_dummy_cycle();
+        unsafe {
+            self.dbi
+                .pixel_count
+                .write(PixelCount::default().set_pixel_count(count));
+            self.dbi
+                .fifo_config_0
+                .modify(|v| v.clear_transmit_fifo().enable_dma_transmit());
+            self.dbi.config.write(config.enable_master());
+        }
+    }
+
+    /// Wait until pixel transaction completes on bus and stop it.
+    #[inline]
+    pub fn finish_pixels(&mut self) {
+        self.wait_idle();
+        unsafe {
+            self.dbi.config.modify(|v| v.disable_master());
+            self.dbi.fifo_config_0.modify(|v| v.disable_dma_transmit());
+        }
+    }
+
+    /// Address of transmit FIFO, as destination of DMA transfers.
+    #[inline]
+    pub fn fifo_address(&self) -> u32 {
+        &self.dbi.fifo_write as *const _ as usize as u32
+    }
+
+    #[inline]
+    fn run(&mut self, config: Config) {
+        unsafe { self.dbi.config.write(config.enable_master()) };
+        self.wait_idle();
+        unsafe { self.dbi.config.write(config.disable_master()) };
+    }
+
+    #[inline]
+    fn wait_idle(&self) {
+        while self.dbi.fifo_config_1.read().transmit_available_bytes() != 8
+            || self.dbi.bus_busy.read().is_bus_busy()
+        {
+            core::hint::spin_loop();
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::RegisterBlock;
+    use super::{PixelCount, RegisterBlock};
     use memoffset::offset_of;
 
     #[test]
     fn struct_register_block_offset() {
         assert_eq!(offset_of!(RegisterBlock, config), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, bus_busy), 0x08);
+        assert_eq!(offset_of!(RegisterBlock, pixel_count), 0x0c);
         assert_eq!(offset_of!(RegisterBlock, fifo_config_0), 0x80);
         assert_eq!(offset_of!(RegisterBlock, fifo_config_1), 0x84);
+        assert_eq!(offset_of!(RegisterBlock, fifo_write), 0x88);
+    }
+
+    #[test]
+    fn struct_pixel_count_functions() {
+        let val = PixelCount(0x0).set_pixel_count(0xff_ffff);
+        assert_eq!(val.0, 0x00ff_ffff);
+        assert_eq!(val.pixel_count(), 0xff_ffff);
+        let val = PixelCount(0xff00_0000).set_pixel_count(0x12_3456);
+        assert_eq!(val.0, 0xff12_3456);
+        assert_eq!(val.pixel_count(), 0x12_3456);
     }
 }
@@ -0,0 +1,328 @@
+use super::{Dbi, RegisterBlock};
+use crate::dma::{
+    self, DmaChannel, DmaChannelConfig, DmaMode, LliItem, LliTransfer2d, Periph4Dma01,
+    TransferError, TransferWidth,
+};
+use core::convert::Infallible;
+use core::ops::Deref;
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{Dimensions, OriginDimensions, Point, Size},
+    pixelcolor::{IntoStorage, Rgb565},
+    primitives::Rectangle,
+    Pixel,
+};
+
+/// Column address set command of MIPI display command set.
+const COLUMN_ADDRESS_SET: u8 = 0x2a;
+/// Page (row) address set command of MIPI display command set.
+const PAGE_ADDRESS_SET: u8 = 0x2b;
+/// Memory write command of MIPI display command set.
+const MEMORY_WRITE: u8 = 0x2c;
+
+/// Display flush error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisplayError {
+    /// Dirty region cannot be described by the linked list item pool.
+    Transfer(TransferError),
+    /// DMA channel stopped with a bus error.
+    Dma,
+}
+
+/// RGB565 framebuffer display on Display Bus Interface, implementing `embedded-graphics` target.
+///
+/// Drawing operations only update the framebuffer in RAM or PSRAM and record the bounding
+/// box of changed pixels. [`DbiDisplay::flush`] sets the panel address window to this
+/// region with MIPI DCS commands, then streams its rows to the panel over DMA.
+///
+/// Pixels are stored in wire byte order, i.e. most significant byte first.
+pub struct DbiDisplay<'a, DBI, PADS, DMA> {
+    dbi: Dbi<DBI, PADS>,
+    channel: DmaChannel<DMA>,
+    framebuffer: &'a mut [u16],
+    pool: &'a mut [LliItem],
+    size: Size,
+    dirty: Option<DirtyArea>,
+}
+
+impl<'a, DBI, PADS, DMA> DbiDisplay<'a, DBI, PADS, DMA>
+where
+    DBI: Deref<Target = RegisterBlock>,
+    DMA: Deref<Target = dma::RegisterBlock>,
+{
+    /// Creates a display of `size` pixels from DBI instance, DMA channel and framebuffer.
+    ///
+    /// Linked list items in `pool` describe the flushed region, one item per row at most
+    /// when the region is narrower than the panel; a pool as long as panel height always
+    /// suffices. Panel initialization commands should be sent by [`DbiDisplay::dbi_mut`]
+    /// before drawing.
+    ///
+    /// # Panics
+    ///
+    /// If `framebuffer` is shorter than `size.width * size.height` pixels.
+    #[inline]
+    pub fn new(
+        dbi: Dbi<DBI, PADS>,
+        mut channel: DmaChannel<DMA>,
+        framebuffer: &'a mut [u16],
+        pool: &'a mut [LliItem],
+        size: Size,
+    ) -> Self {
+        assert!(
+            framebuffer.len() >= (size.width * size.height) as usize,
+            "framebuffer should hold at least width * height pixels"
+        );
+        channel
+            .configure(DmaChannelConfig {
+                direction: DmaMode::Mem2Periph,
+                dst_req: Some(Periph4Dma01::DbiTx),
+                dst_addr_inc: false,
+                src_transfer_width: TransferWidth::HalfWord,
+                dst_transfer_width: TransferWidth::HalfWord,
+                ..Default::default()
+            })
+            .expect("display channel configuration is valid");
+        Self {
+            dbi,
+            channel,
+            framebuffer,
+            pool,
+            size,
+            dirty: None,
+        }
+    }
+
+    /// Release the display and return its DBI instance and DMA channel.
+    #[inline]
+    pub fn free(self) -> (Dbi<DBI, PADS>, DmaChannel<DMA>) {
+        (self.dbi, self.channel)
+    }
+
+    /// Get mutable reference to the DBI instance, e.g. to send panel specific commands.
+    #[inline]
+    pub fn dbi_mut(&mut self) -> &mut Dbi<DBI, PADS> {
+        &mut self.dbi
+    }
+
+    /// Mark the whole screen to be sent on next flush.
+    #[inline]
+    pub fn invalidate(&mut self) {
+        self.mark(DirtyArea::full(self.size));
+    }
+
+    /// Send region changed since last flush to the panel and wait until it completes.
+    pub fn flush(&mut self) -> Result<(), DisplayError> {
+        let Some(area) = self.dirty else {
+            return Ok(());
+        };
+        let width = self.size.width;
+        let transfer = LliTransfer2d {
+            src_addr: self.framebuffer.as_ptr() as usize as u32 + (area.y0 * width + area.x0) * 2,
+            dst_addr: self.dbi.fifo_address(),
+            width: area.width() * 2,
+            height: area.height(),
+            src_stride: width * 2,
+            dst_stride: 0,
+        };
+        self.channel
+            .lli_reload_2d(self.pool, &transfer)
+            .map_err(DisplayError::Transfer)?;
+        self.dbi
+            .write_command(COLUMN_ADDRESS_SET, &address_window(area.x0, area.x1));
+        self.dbi
+            .write_command(PAGE_ADDRESS_SET, &address_window(area.y0, area.y1));
+        self.dbi
+            .start_pixels(MEMORY_WRITE, area.width() * area.height());
+        self.channel.start();
+        while self.channel.is_busy() && !self.channel.has_error() {
+            core::hint::spin_loop();
+        }
+        if self.channel.has_error() {
+            self.channel.stop();
+            self.dbi.finish_pixels();
+            return Err(DisplayError::Dma);
+        }
+        self.dbi.finish_pixels();
+        self.dirty = None;
+        Ok(())
+    }
+
+    #[inline]
+    fn mark(&mut self, area: DirtyArea) {
+        self.dirty = Some(match self.dirty {
+            Some(dirty) => dirty.union(area),
+            None => area,
+        });
+    }
+}
+
+impl<DBI, PADS, DMA> OriginDimensions for DbiDisplay<'_, DBI, PADS, DMA> {
+    #[inline]
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl<DBI, PADS, DMA> DrawTarget for DbiDisplay<'_, DBI, PADS, DMA>
+where
+    DBI: Deref<Target = RegisterBlock>,
+    DMA: Deref<Target = dma::RegisterBlock>,
+{
+    type Color = Rgb565;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let (width, height) = (self.size.width, self.size.height);
+        for Pixel(Point { x, y }, color) in pixels {
+            if x < 0 || y < 0 || x as u32 >= width || y as u32 >= height {
+                continue;
+            }
+            let (x, y) = (x as u32, y as u32);
+            self.framebuffer[(y * width + x) as usize] = color.into_storage().to_be();
+            self.mark(DirtyArea {
+                x0: x,
+                y0: y,
+                x1: x,
+                y1: y,
+            });
+        }
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&self.bounding_box());
+        let Some(bottom_right) = area.bottom_right() else {
+            return Ok(());
+        };
+        let area = DirtyArea {
+            x0: area.top_left.x as u32,
+            y0: area.top_left.y as u32,
+            x1: bottom_right.x as u32,
+            y1: bottom_right.y as u32,
+        };
+        let width = self.size.width;
+        let raw = color.into_storage().to_be();
+        for y in area.y0..=area.y1 {
+            let start = (y * width + area.x0) as usize;
+            self.framebuffer[start..start + area.width() as usize].fill(raw);
+        }
+        self.mark(area);
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        let len = (self.size.width * self.size.height) as usize;
+        self.framebuffer[..len].fill(color.into_storage().to_be());
+        self.invalidate();
+        Ok(())
+    }
+}
+
+/// Inclusive pixel rectangle changed since last flush.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct DirtyArea {
+    x0: u32,
+    y0: u32,
+    x1: u32,
+    y1: u32,
+}
+
+impl DirtyArea {
+    #[inline]
+    const fn full(size: Size) -> Self {
+        Self {
+            x0: 0,
+            y0: 0,
+            x1: size.width - 1,
+            y1: size.height - 1,
+        }
+    }
+    #[inline]
+    const fn union(self, other: Self) -> Self {
+        Self {
+            x0: min(self.x0, other.x0),
+            y0: min(self.y0, other.y0),
+            x1: max(self.x1, other.x1),
+            y1: max(self.y1, other.y1),
+        }
+    }
+    #[inline]
+    const fn width(self) -> u32 {
+        self.x1 - self.x0 + 1
+    }
+    #[inline]
+    const fn height(self) -> u32 {
+        self.y1 - self.y0 + 1
+    }
+}
+
+#[inline]
+const fn min(a: u32, b: u32) -> u32 {
+    if a < b {
+        a
+    } else {
+        b
+    }
+}
+
+#[inline]
+const fn max(a: u32, b: u32) -> u32 {
+    if a > b {
+        a
+    } else {
+        b
+    }
+}
+
+/// Parameters of address set command from `start` to `end` inclusive, big endian.
+#[inline]
+const fn address_window(start: u32, end: u32) -> [u8; 4] {
+    let (start, end) = ((start as u16).to_be_bytes(), (end as u16).to_be_bytes());
+    [start[0], start[1], end[0], end[1]]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{address_window, DirtyArea};
+    use embedded_graphics_core::geometry::Size;
+
+    #[test]
+    fn struct_dirty_area_functions() {
+        let a = DirtyArea {
+            x0: 10,
+            y0: 20,
+            x1: 10,
+            y1: 20,
+        };
+        assert_eq!((a.width(), a.height()), (1, 1));
+        let b = DirtyArea {
+            x0: 5,
+            y0: 30,
+            x1: 7,
+            y1: 40,
+        };
+        let c = a.union(b);
+        assert_eq!(
+            c,
+            DirtyArea {
+                x0: 5,
+                y0: 20,
+                x1: 10,
+                y1: 40
+            }
+        );
+        assert_eq!((c.width(), c.height()), (6, 21));
+        let full = DirtyArea::full(Size::new(320, 240));
+        assert_eq!((full.width(), full.height()), (320, 240));
+        assert_eq!(full.union(a), full);
+    }
+
+    #[test]
+    fn function_address_window() {
+        assert_eq!(address_window(0, 319), [0x00, 0x00, 0x01, 0x3f]);
+        assert_eq!(address_window(0x12, 0x34), [0x00, 0x12, 0x00, 0x34]);
+    }
+}
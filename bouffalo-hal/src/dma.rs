@@ -1,5 +1,11 @@
 //! Direct Memory Access peripheral.
 
+use core::{
+    future::poll_fn,
+    ops::Deref,
+    sync::atomic::{AtomicU8, AtomicUsize, Ordering},
+    task::Poll,
+};
 use volatile_register::{RO, RW, WO};
 
 /// Direct Memory Access peripheral registers.
@@ -39,14 +45,35 @@ pub struct InterruptRegisters {
     pub raw_transfer_complete: RO<u8>,
     _reserved5: [u8; 3],
     /// Error interrupt state before masking.
-    pub raw_error: WO<u8>,
+    pub raw_error: RO<u8>,
     _reserved6: [u8; 3],
 }
 
 /// Peripheral configuration register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
 pub struct GlobalConfig(u32);
 
+impl GlobalConfig {
+    const DMA_ENABLE: u32 = 1 << 0;
+
+    /// Enable Direct Memory Access peripheral.
+    #[inline]
+    pub const fn enable_dma(self) -> Self {
+        Self(self.0 | Self::DMA_ENABLE)
+    }
+    /// Disable Direct Memory Access peripheral.
+    #[inline]
+    pub const fn disable_dma(self) -> Self {
+        Self(self.0 & !Self::DMA_ENABLE)
+    }
+    /// Check if Direct Memory Access peripheral is enabled.
+    #[inline]
+    pub const fn is_dma_enabled(self) -> bool {
+        self.0 & Self::DMA_ENABLE != 0
+    }
+}
+
 /// Channel register block.
 #[repr(C)]
 pub struct ChannelRegisters {
@@ -64,6 +91,7 @@ pub struct ChannelRegisters {
 }
 
 /// Linked list item descriptor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 #[repr(C)]
 pub struct LliItem {
     /// Source address.
@@ -77,16 +105,1194 @@ pub struct LliItem {
 }
 
 /// Control register in linked list item.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[repr(transparent)]
 pub struct LliControl(u32);
 
-/// Channel configuration register.
+impl LliControl {
+    const TRANSFER_SIZE: u32 = 0xfff;
+    const SOURCE_BURST_SIZE: u32 = 0x3 << 12;
+    const DESTINATION_BURST_SIZE: u32 = 0x3 << 15;
+    const DESTINATION_INCREMENT: u32 = 1 << 27;
+    const SOURCE_TRANSFER_WIDTH: u32 = 0x3 << 18;
+    const DESTINATION_TRANSFER_WIDTH: u32 = 0x3 << 21;
+    const SOURCE_INCREMENT: u32 = 1 << 26;
+    const COMPLETE_INTERRUPT: u32 = 1 << 31;
+
+    /// Set transfer size in units of source transfer width.
+    #[inline]
+    pub const fn set_transfer_size(self, val: u16) -> Self {
+        Self((self.0 & !Self::TRANSFER_SIZE) | (val as u32 & Self::TRANSFER_SIZE))
+    }
+    /// Get transfer size in units of source transfer width.
+    #[inline]
+    pub const fn transfer_size(self) -> u16 {
+        (self.0 & Self::TRANSFER_SIZE) as u16
+    }
+    /// Set source burst size.
+    #[inline]
+    pub const fn set_source_burst_size(self, val: BurstSize) -> Self {
+        Self((self.0 & !Self::SOURCE_BURST_SIZE) | ((val as u32) << 12))
+    }
+    /// Get source burst size.
+    #[inline]
+    pub const fn source_burst_size(self) -> BurstSize {
+        BurstSize::from_bits((self.0 & Self::SOURCE_BURST_SIZE) >> 12)
+    }
+    /// Set destination burst size.
+    #[inline]
+    pub const fn set_destination_burst_size(self, val: BurstSize) -> Self {
+        Self((self.0 & !Self::DESTINATION_BURST_SIZE) | ((val as u32) << 15))
+    }
+    /// Get destination burst size.
+    #[inline]
+    pub const fn destination_burst_size(self) -> BurstSize {
+        BurstSize::from_bits((self.0 & Self::DESTINATION_BURST_SIZE) >> 15)
+    }
+    /// Set source transfer width.
+    #[inline]
+    pub const fn set_source_transfer_width(self, val: TransferWidth) -> Self {
+        Self((self.0 & !Self::SOURCE_TRANSFER_WIDTH) | ((val as u32) << 18))
+    }
+    /// Get source transfer width.
+    #[inline]
+    pub const fn source_transfer_width(self) -> TransferWidth {
+        TransferWidth::from_bits((self.0 & Self::SOURCE_TRANSFER_WIDTH) >> 18)
+    }
+    /// Set destination transfer width.
+    #[inline]
+    pub const fn set_destination_transfer_width(self, val: TransferWidth) -> Self {
+        Self((self.0 & !Self::DESTINATION_TRANSFER_WIDTH) | ((val as u32) << 21))
+    }
+    /// Get destination transfer width.
+    #[inline]
+    pub const fn destination_transfer_width(self) -> TransferWidth {
+        TransferWidth::from_bits((self.0 & Self::DESTINATION_TRANSFER_WIDTH) >> 21)
+    }
+    /// Enable source address increment.
+    #[inline]
+    pub const fn enable_source_increment(self) -> Self {
+        Self(self.0 | Self::SOURCE_INCREMENT)
+    }
+    /// Disable source address increment.
+    #[inline]
+    pub const fn disable_source_increment(self) -> Self {
+        Self(self.0 & !Self::SOURCE_INCREMENT)
+    }
+    /// Check if source address increment is enabled.
+    #[inline]
+    pub const fn is_source_increment_enabled(self) -> bool {
+        self.0 & Self::SOURCE_INCREMENT != 0
+    }
+    /// Enable destination address increment.
+    #[inline]
+    pub const fn enable_destination_increment(self) -> Self {
+        Self(self.0 | Self::DESTINATION_INCREMENT)
+    }
+    /// Disable destination address increment.
+    #[inline]
+    pub const fn disable_destination_increment(self) -> Self {
+        Self(self.0 & !Self::DESTINATION_INCREMENT)
+    }
+    /// Check if destination address increment is enabled.
+    #[inline]
+    pub const fn is_destination_increment_enabled(self) -> bool {
+        self.0 & Self::DESTINATION_INCREMENT != 0
+    }
+    /// Enable transfer complete interrupt after this item.
+    #[inline]
+    pub const fn enable_complete_interrupt(self) -> Self {
+        Self(self.0 | Self::COMPLETE_INTERRUPT)
+    }
+    /// Disable transfer complete interrupt after this item.
+    #[inline]
+    pub const fn disable_complete_interrupt(self) -> Self {
+        Self(self.0 & !Self::COMPLETE_INTERRUPT)
+    }
+    /// Check if transfer complete interrupt after this item is enabled.
+    #[inline]
+    pub const fn is_complete_interrupt_enabled(self) -> bool {
+        self.0 & Self::COMPLETE_INTERRUPT != 0
+    }
+}
+
+/// Number of data beats in one burst.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum BurstSize {
+    /// Single beat.
+    Incr1 = 0,
+    /// Four beats.
+    Incr4 = 1,
+    /// Eight beats.
+    Incr8 = 2,
+    /// Sixteen beats.
+    Incr16 = 3,
+}
+
+impl BurstSize {
+    #[inline]
+    const fn from_bits(val: u32) -> Self {
+        match val {
+            0 => BurstSize::Incr1,
+            1 => BurstSize::Incr4,
+            2 => BurstSize::Incr8,
+            3 => BurstSize::Incr16,
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Width of each data beat.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum TransferWidth {
+    /// 8 bits.
+    Byte = 0,
+    /// 16 bits.
+    HalfWord = 1,
+    /// 32 bits.
+    Word = 2,
+    /// 64 bits.
+    DoubleWord = 3,
+}
+
+impl TransferWidth {
+    #[inline]
+    const fn from_bits(val: u32) -> Self {
+        match val {
+            0 => TransferWidth::Byte,
+            1 => TransferWidth::HalfWord,
+            2 => TransferWidth::Word,
+            3 => TransferWidth::DoubleWord,
+            _ => unreachable!(),
+        }
+    }
+    /// Number of bytes in one data beat.
+    #[inline]
+    pub const fn bytes(self) -> u32 {
+        1 << self as u32
+    }
+}
+
+/// Channel configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[repr(transparent)]
 pub struct ChannelConfig(u32);
 
+impl ChannelConfig {
+    const CHANNEL_ENABLE: u32 = 1 << 0;
+    const SOURCE_PERIPHERAL: u32 = 0x1f << 1;
+    const DESTINATION_PERIPHERAL: u32 = 0x1f << 6;
+    const FLOW_CONTROL: u32 = 0x7 << 11;
+    const ERROR_INTERRUPT: u32 = 1 << 14;
+    const COMPLETE_INTERRUPT: u32 = 1 << 15;
+    const ACTIVE: u32 = 1 << 17;
+    const HALT: u32 = 1 << 18;
+
+    /// Enable channel.
+    #[inline]
+    pub const fn enable_channel(self) -> Self {
+        Self(self.0 | Self::CHANNEL_ENABLE)
+    }
+    /// Disable channel.
+    #[inline]
+    pub const fn disable_channel(self) -> Self {
+        Self(self.0 & !Self::CHANNEL_ENABLE)
+    }
+    /// Check if channel is enabled.
+    #[inline]
+    pub const fn is_channel_enabled(self) -> bool {
+        self.0 & Self::CHANNEL_ENABLE != 0
+    }
+    /// Set source peripheral request line.
+    #[inline]
+    pub const fn set_source_peripheral(self, val: u8) -> Self {
+        Self((self.0 & !Self::SOURCE_PERIPHERAL) | (((val as u32) << 1) & Self::SOURCE_PERIPHERAL))
+    }
+    /// Get source peripheral request line.
+    #[inline]
+    pub const fn source_peripheral(self) -> u8 {
+        ((self.0 & Self::SOURCE_PERIPHERAL) >> 1) as u8
+    }
+    /// Set destination peripheral request line.
+    #[inline]
+    pub const fn set_destination_peripheral(self, val: u8) -> Self {
+        Self(
+            (self.0 & !Self::DESTINATION_PERIPHERAL)
+                | (((val as u32) << 6) & Self::DESTINATION_PERIPHERAL),
+        )
+    }
+    /// Get destination peripheral request line.
+    #[inline]
+    pub const fn destination_peripheral(self) -> u8 {
+        ((self.0 & Self::DESTINATION_PERIPHERAL) >> 6) as u8
+    }
+    /// Set transfer direction and flow control.
+    #[inline]
+    pub const fn set_flow_control(self, val: DmaMode) -> Self {
+        Self((self.0 & !Self::FLOW_CONTROL) | ((val as u32) << 11))
+    }
+    /// Get transfer direction and flow control.
+    #[inline]
+    pub const fn flow_control(self) -> DmaMode {
+        DmaMode::from_bits((self.0 & Self::FLOW_CONTROL) >> 11)
+    }
+    /// Enable error interrupt.
+    #[inline]
+    pub const fn enable_error_interrupt(self) -> Self {
+        Self(self.0 | Self::ERROR_INTERRUPT)
+    }
+    /// Disable error interrupt.
+    #[inline]
+    pub const fn disable_error_interrupt(self) -> Self {
+        Self(self.0 & !Self::ERROR_INTERRUPT)
+    }
+    /// Check if error interrupt is enabled.
+    #[inline]
+    pub const fn is_error_interrupt_enabled(self) -> bool {
+        self.0 & Self::ERROR_INTERRUPT != 0
+    }
+    /// Enable transfer complete interrupt.
+    #[inline]
+    pub const fn enable_complete_interrupt(self) -> Self {
+        Self(self.0 | Self::COMPLETE_INTERRUPT)
+    }
+    /// Disable transfer complete interrupt.
+    #[inline]
+    pub const fn disable_complete_interrupt(self) -> Self {
+        Self(self.0 & !Self::COMPLETE_INTERRUPT)
+    }
+    /// Check if transfer complete interrupt is enabled.
+    #[inline]
+    pub const fn is_complete_interrupt_enabled(self) -> bool {
+        self.0 & Self::COMPLETE_INTERRUPT != 0
+    }
+    /// Check if there is data in channel FIFO.
+    #[inline]
+    pub const fn is_active(self) -> bool {
+        self.0 & Self::ACTIVE != 0
+    }
+    /// Ignore further source requests and let channel drain its FIFO.
+    #[inline]
+    pub const fn enable_halt(self) -> Self {
+        Self(self.0 | Self::HALT)
+    }
+    /// Accept source requests.
+    #[inline]
+    pub const fn disable_halt(self) -> Self {
+        Self(self.0 & !Self::HALT)
+    }
+    /// Check if channel ignores source requests.
+    #[inline]
+    pub const fn is_halt_enabled(self) -> bool {
+        self.0 & Self::HALT != 0
+    }
+}
+
+/// Transfer direction and flow control.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum DmaMode {
+    /// Memory to memory.
+    Mem2Mem = 0,
+    /// Memory to peripheral.
+    Mem2Periph = 1,
+    /// Peripheral to memory.
+    Periph2Mem = 2,
+    /// Peripheral to peripheral.
+    ///
+    /// Channel reads after a request on the source line and writes after a request on
+    /// the destination line, so the slower side paces the transfer. Both request lines
+    /// must belong to the controller of this channel, e.g. [`Periph4Dma01::GpAdc`] to
+    /// [`Periph4Dma01::GpDac`], or [`Periph4Dma01::Uart0Rx`] to [`Periph4Dma01::Uart1Tx`];
+    /// any receive line of that controller may feed any transmit line. Use single beat
+    /// bursts unless both peripherals request at the same burst size.
+    Periph2Periph = 3,
+}
+
+impl DmaMode {
+    #[inline]
+    const fn from_bits(val: u32) -> Self {
+        match val {
+            0 => DmaMode::Mem2Mem,
+            1 => DmaMode::Mem2Periph,
+            2 => DmaMode::Periph2Mem,
+            3 => DmaMode::Periph2Periph,
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Peripheral request line of DMA0 and DMA1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Periph4Dma01 {
+    /// UART0 receive.
+    Uart0Rx = 0,
+    /// UART0 transmit.
+    Uart0Tx = 1,
+    /// UART1 receive.
+    Uart1Rx = 2,
+    /// UART1 transmit.
+    Uart1Tx = 3,
+    /// UART2 receive.
+    Uart2Rx = 4,
+    /// UART2 transmit.
+    Uart2Tx = 5,
+    /// I2C0 receive.
+    I2c0Rx = 6,
+    /// I2C0 transmit.
+    I2c0Tx = 7,
+    /// Infrared remote transmit.
+    IrTx = 8,
+    /// GPIO transmit.
+    GpioTx = 9,
+    /// SPI0 receive.
+    Spi0Rx = 10,
+    /// SPI0 transmit.
+    Spi0Tx = 11,
+    /// Audio receive.
+    AudioRx = 12,
+    /// Audio transmit.
+    AudioTx = 13,
+    /// I2C1 receive.
+    I2c1Rx = 14,
+    /// I2C1 transmit.
+    I2c1Tx = 15,
+    /// I2S receive.
+    I2sRx = 16,
+    /// I2S transmit.
+    I2sTx = 17,
+    /// PDM receive.
+    PdmRx = 18,
+    /// Display bus interface transmit.
+    DbiTx = 20,
+    /// General purpose ADC.
+    GpAdc = 22,
+    /// General purpose DAC.
+    GpDac = 23,
+}
+
+/// Maximum transfer size of one linked list item, in units of source transfer width.
+pub const LLI_TRANSFER_SIZE_MAX: u32 = 4095;
+
+/// Channel configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DmaChannelConfig {
+    /// Transfer direction and flow control.
+    pub direction: DmaMode,
+    /// Source peripheral request line, used when transferring from peripheral.
+    pub src_req: Option<Periph4Dma01>,
+    /// Destination peripheral request line, used when transferring to peripheral.
+    pub dst_req: Option<Periph4Dma01>,
+    /// Increment source address after each beat.
+    pub src_addr_inc: bool,
+    /// Increment destination address after each beat.
+    pub dst_addr_inc: bool,
+    /// Source burst size.
+    pub src_burst_size: BurstSize,
+    /// Destination burst size.
+    pub dst_burst_size: BurstSize,
+    /// Source transfer width.
+    pub src_transfer_width: TransferWidth,
+    /// Destination transfer width.
+    pub dst_transfer_width: TransferWidth,
+}
+
+impl Default for DmaChannelConfig {
+    /// Channel configuration defaults to byte-wide memory to memory copy with single beat bursts.
+    #[inline]
+    fn default() -> Self {
+        DmaChannelConfig {
+            direction: DmaMode::Mem2Mem,
+            src_req: None,
+            dst_req: None,
+            src_addr_inc: true,
+            dst_addr_inc: true,
+            src_burst_size: BurstSize::Incr1,
+            dst_burst_size: BurstSize::Incr1,
+            src_transfer_width: TransferWidth::Byte,
+            dst_transfer_width: TransferWidth::Byte,
+        }
+    }
+}
+
+impl DmaChannelConfig {
+    /// Check that settings are consistent with each other and with transfer direction.
+    ///
+    /// The peripheral side of a transfer needs a request line and a fixed 32-bit or narrower
+    /// access to the peripheral FIFO register, while a memory side takes no request line.
+    /// Address alignment to transfer width is checked when building linked lists, see
+    /// [`TransferError::Unaligned`].
+    #[inline]
+    pub const fn validate(&self) -> Result<(), DmaConfigError> {
+        let (src_periph, dst_periph) = match self.direction {
+            DmaMode::Mem2Mem => (false, false),
+            DmaMode::Mem2Periph => (false, true),
+            DmaMode::Periph2Mem => (true, false),
+            DmaMode::Periph2Periph => (true, true),
+        };
+        if src_periph && self.src_req.is_none() {
+            return Err(DmaConfigError::MissingSourceRequest);
+        }
+        if dst_periph && self.dst_req.is_none() {
+            return Err(DmaConfigError::MissingDestinationRequest);
+        }
+        if (!src_periph && self.src_req.is_some()) || (!dst_periph && self.dst_req.is_some()) {
+            return Err(DmaConfigError::UnexpectedRequest);
+        }
+        if (src_periph && self.src_addr_inc) || (dst_periph && self.dst_addr_inc) {
+            return Err(DmaConfigError::PeripheralAddressIncrement);
+        }
+        if (src_periph && matches!(self.src_transfer_width, TransferWidth::DoubleWord))
+            || (dst_periph && matches!(self.dst_transfer_width, TransferWidth::DoubleWord))
+        {
+            return Err(DmaConfigError::PeripheralTransferWidth);
+        }
+        Ok(())
+    }
+}
+
+/// Channel configuration error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DmaConfigError {
+    /// Transfer from peripheral has no source request line.
+    MissingSourceRequest,
+    /// Transfer to peripheral has no destination request line.
+    MissingDestinationRequest,
+    /// Request line is set on a memory side of the transfer.
+    UnexpectedRequest,
+    /// Address of peripheral side increments, moving off its FIFO register.
+    PeripheralAddressIncrement,
+    /// Peripheral side uses 64-bit transfer width, wider than peripheral FIFO registers.
+    PeripheralTransferWidth,
+}
+
+/// Contiguous transfer of `nbytes` bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LliTransfer {
+    /// Source address.
+    pub src_addr: u32,
+    /// Destination address.
+    pub dst_addr: u32,
+    /// Number of bytes to transfer.
+    pub nbytes: u32,
+}
+
+/// Two-dimensional transfer of `height` rows, each `width` bytes long.
+///
+/// Row `n` starts at `src_addr + n * src_stride` and is written to `dst_addr + n * dst_stride`,
+/// which copies a rectangle out of or into a larger framebuffer. Stride of a side without
+/// address increment is ignored, e.g. when writing rows into a peripheral FIFO.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LliTransfer2d {
+    /// Source address of the first row.
+    pub src_addr: u32,
+    /// Destination address of the first row.
+    pub dst_addr: u32,
+    /// Number of bytes in each row.
+    pub width: u32,
+    /// Number of rows.
+    pub height: u32,
+    /// Distance in bytes between starts of two source rows.
+    pub src_stride: u32,
+    /// Distance in bytes between starts of two destination rows.
+    pub dst_stride: u32,
+}
+
+/// Linked list transfer error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransferError {
+    /// Transfer has no data.
+    ZeroLength,
+    /// Address, length or stride is not a multiple of transfer width.
+    Unaligned,
+    /// Row stride is smaller than row width.
+    StrideTooSmall,
+    /// Linked list item pool is too small for this transfer.
+    PoolTooSmall,
+}
+
+/// Managed Direct Memory Access peripheral.
+///
+/// Channels are either split into fixed fields with [`Dma::split`], or handed out on
+/// request with [`Dma::acquire`] so that drivers don't collide on a hardcoded channel.
+pub struct Dma<DMA> {
+    dma: DMA,
+    used: AtomicU8,
+}
+
+impl<DMA: Deref<Target = RegisterBlock>> Dma<DMA> {
+    /// Create and enable Direct Memory Access peripheral.
+    #[inline]
+    pub fn new(dma: DMA) -> Self {
+        unsafe { dma.global_config.modify(|v| v.enable_dma()) };
+        Dma {
+            dma,
+            used: AtomicU8::new(0),
+        }
+    }
+    /// Create and enable DMA peripheral which only hands out channels in `channels` mask.
+    ///
+    /// Use `0x0f` for DMA1 on BL808, which only implements channels 0 to 3, or leave out
+    /// channels reserved for other harts.
+    #[inline]
+    pub fn with_channels(dma: DMA, channels: u8) -> Self {
+        let ans = Self::new(dma);
+        ans.used.store(!channels, Ordering::Relaxed);
+        ans
+    }
+    /// Acquire a free channel, or `None` if all channels are in use.
+    ///
+    /// Channels are handed out lowest number first. Return a channel with
+    /// [`Dma::release`] after use so other drivers may acquire it.
+    #[inline]
+    pub fn acquire(&self) -> Option<DmaChannel<DMA>> {
+        let mut id = 0;
+        self.used
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |used| {
+                id = first_free(used)?;
+                Some(used | (1 << id))
+            })
+            .ok()?;
+        Some(DmaChannel {
+            dma: unsafe { core::ptr::read(&self.dma as *const _) },
+            id,
+            control: LliControl::default(),
+            circular: None,
+        })
+    }
+    /// Stop a channel acquired from this peripheral and return it to the pool.
+    ///
+    /// # Panics
+    ///
+    /// If the channel belongs to another DMA peripheral.
+    #[inline]
+    pub fn release(&self, mut channel: DmaChannel<DMA>) {
+        assert!(
+            core::ptr::eq(channel.register_block(), &*self.dma),
+            "DMA channel should be released to the peripheral it was acquired from"
+        );
+        if channel.is_busy() {
+            channel.stop();
+        }
+        self.used.fetch_and(!(1 << channel.id), Ordering::AcqRel);
+        core::mem::forget(channel);
+    }
+    /// Split peripheral into its channels.
+    ///
+    /// DMA1 on BL808 only implements channels 0 to 3. Should not be mixed with
+    /// [`Dma::acquire`], as split channels are not tracked by channel pool.
+    #[inline]
+    pub fn split(self) -> EightChannels<DMA> {
+        let channel = |id| DmaChannel {
+            dma: unsafe { core::ptr::read(&self.dma as *const _) },
+            id,
+            control: LliControl::default(),
+            circular: None,
+        };
+        let channels = EightChannels {
+            ch0: channel(0),
+            ch1: channel(1),
+            ch2: channel(2),
+            ch3: channel(3),
+            ch4: channel(4),
+            ch5: channel(5),
+            ch6: channel(6),
+            ch7: channel(7),
+        };
+        core::mem::forget(self);
+        channels
+    }
+    /// Disable peripheral and release it.
+    ///
+    /// All acquired channels should be released before.
+    #[inline]
+    pub fn free(self) -> DMA {
+        unsafe { self.dma.global_config.modify(|v| v.disable_dma()) };
+        self.dma
+    }
+}
+
+/// Lowest channel number not set in `used` mask.
+#[inline]
+const fn first_free(used: u8) -> Option<usize> {
+    match (!used).trailing_zeros() {
+        8 => None,
+        id => Some(id as usize),
+    }
+}
+
+/// Channels of a Direct Memory Access peripheral.
+pub struct EightChannels<DMA> {
+    /// Channel 0.
+    pub ch0: DmaChannel<DMA>,
+    /// Channel 1.
+    pub ch1: DmaChannel<DMA>,
+    /// Channel 2.
+    pub ch2: DmaChannel<DMA>,
+    /// Channel 3.
+    pub ch3: DmaChannel<DMA>,
+    /// Channel 4.
+    pub ch4: DmaChannel<DMA>,
+    /// Channel 5.
+    pub ch5: DmaChannel<DMA>,
+    /// Channel 6.
+    pub ch6: DmaChannel<DMA>,
+    /// Channel 7.
+    pub ch7: DmaChannel<DMA>,
+}
+
+/// Direct Memory Access channel.
+pub struct DmaChannel<DMA> {
+    dma: DMA,
+    id: usize,
+    control: LliControl,
+    circular: Option<CircularLayout>,
+}
+
+/// Point reached by a circular transfer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CircularEvent {
+    /// First half of buffer is filled or drained, and channel works on the second half.
+    Half,
+    /// Second half of buffer is filled or drained, and channel restarts from the first half.
+    Complete,
+}
+
+/// Memory side buffer of a circular transfer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct CircularLayout {
+    /// Buffer is on destination side, otherwise on source side.
+    destination: bool,
+    /// Start address of buffer.
+    start: u32,
+    /// Length of each half in bytes.
+    half: u32,
+}
+
+impl<DMA: Deref<Target = RegisterBlock>> DmaChannel<DMA> {
+    /// Channel number in its peripheral.
+    #[inline]
+    pub fn id(&self) -> usize {
+        self.id
+    }
+    /// Configure transfer direction, request lines, widths and bursts of this channel.
+    ///
+    /// Channel is stopped before applying new configuration. Returns an error without
+    /// touching the channel if `config` is inconsistent, see [`DmaChannelConfig::validate`].
+    #[inline]
+    pub fn configure(&mut self, config: DmaChannelConfig) -> Result<(), DmaConfigError> {
+        config.validate()?;
+        let mut control = LliControl::default()
+            .set_source_burst_size(config.src_burst_size)
+            .set_destination_burst_size(config.dst_burst_size)
+            .set_source_transfer_width(config.src_transfer_width)
+            .set_destination_transfer_width(config.dst_transfer_width);
+        if config.src_addr_inc {
+            control = control.enable_source_increment();
+        }
+        if config.dst_addr_inc {
+            control = control.enable_destination_increment();
+        }
+        self.control = control;
+        let channel_config = ChannelConfig::default()
+            .set_flow_control(config.direction)
+            .set_source_peripheral(config.src_req.map_or(0, |v| v as u8))
+            .set_destination_peripheral(config.dst_req.map_or(0, |v| v as u8))
+            .enable_error_interrupt()
+            .enable_complete_interrupt();
+        let channel = &self.dma.channels[self.id];
+        unsafe {
+            channel.config.modify(|v| v.disable_channel());
+            channel.config.write(channel_config);
+        }
+        Ok(())
+    }
+    /// Build linked list in `pool` for contiguous `transfers` and load its head into channel.
+    ///
+    /// Transfers longer than one item can hold are split into several items. Returns number
+    /// of items used. Items in `pool` must stay in place until the transfer completes.
+    #[inline]
+    pub fn lli_reload(
+        &mut self,
+        pool: &mut [LliItem],
+        transfers: &[LliTransfer],
+    ) -> Result<usize, TransferError> {
+        let mut used = 0;
+        for transfer in transfers {
+            push_block(pool, &mut used, self.control, *transfer)?;
+        }
+        self.circular = None;
+        self.load(pool, used)
+    }
+    /// Build linked list in `pool` for a two-dimensional transfer and load its head into channel.
+    ///
+    /// Each row takes at least one item, while rows without stride gap on both sides are
+    /// merged into one contiguous block. Returns number of items used. Items in `pool` must
+    /// stay in place until the transfer completes.
+    #[inline]
+    pub fn lli_reload_2d(
+        &mut self,
+        pool: &mut [LliItem],
+        transfer: &LliTransfer2d,
+    ) -> Result<usize, TransferError> {
+        let mut used = 0;
+        push_2d(pool, &mut used, self.control, transfer)?;
+        self.circular = None;
+        self.load(pool, used)
+    }
+    /// Build a circular linked list in `pool` for `transfers` and load its head into channel.
+    ///
+    /// Each transfer raises transfer complete interrupt at its end, and the last transfer
+    /// links back to the first, so the channel keeps running until its list is cut or it
+    /// is stopped. Returns number of items used.
+    #[inline]
+    pub fn lli_reload_ring(
+        &mut self,
+        pool: &mut [LliItem],
+        transfers: &[LliTransfer],
+    ) -> Result<usize, TransferError> {
+        let mut used = 0;
+        push_ring(pool, &mut used, self.control, transfers)?;
+        self.circular = None;
+        self.load(pool, used)
+    }
+    /// Build a circular double buffer transfer in `pool` and load its head into channel.
+    ///
+    /// The buffer on incrementing side of `transfer`, usually memory, is split into two
+    /// halves which the channel fills or drains in turn without stopping. Transfer complete
+    /// interrupt is raised at the end of each half; call
+    /// [`on_circular_interrupt`](Self::on_circular_interrupt) in the DMA interrupt handler
+    /// to process one half while the channel works on the other. `transfer.nbytes` should
+    /// cover both halves. Returns number of items used.
+    #[inline]
+    pub fn lli_reload_circular(
+        &mut self,
+        pool: &mut [LliItem],
+        transfer: LliTransfer,
+    ) -> Result<usize, TransferError> {
+        let (halves, layout) = circular_halves(self.control, transfer)?;
+        let mut used = 0;
+        push_ring(pool, &mut used, self.control, &halves)?;
+        self.circular = Some(layout);
+        self.load(pool, used)
+    }
+    /// Handle transfer complete interrupt of a circular transfer.
+    ///
+    /// Calls `on_half` when the first half of buffer is done, or `on_complete` when the
+    /// second half is done, and clears the interrupt. Does nothing if this channel has no
+    /// pending complete interrupt or isn't loaded by
+    /// [`lli_reload_circular`](Self::lli_reload_circular). The half is told from the
+    /// address channel is working on, so the handler should run within one half period.
+    #[inline]
+    pub fn on_circular_interrupt(&mut self, on_half: impl FnOnce(), on_complete: impl FnOnce()) {
+        let Some(layout) = self.circular else {
+            return;
+        };
+        if self.dma.interrupts.transfer_complete_state.read() & (1 << self.id) == 0 {
+            return;
+        }
+        unsafe {
+            self.dma
+                .interrupts
+                .transfer_complete_clear
+                .write(1 << self.id)
+        };
+        let channel = &self.dma.channels[self.id];
+        let address = if layout.destination {
+            channel.destination_address.read()
+        } else {
+            channel.source_address.read()
+        };
+        match circular_event(layout, address) {
+            CircularEvent::Half => on_half(),
+            CircularEvent::Complete => on_complete(),
+        }
+    }
+    /// Start transfer from loaded linked list.
+    #[inline]
+    pub fn start(&mut self) {
+        unsafe {
+            self.dma
+                .interrupts
+                .transfer_complete_clear
+                .write(1 << self.id);
+            self.dma.interrupts.error_clear.write(1 << self.id);
+            self.dma.channels[self.id]
+                .config
+                .modify(|v| v.disable_halt().enable_error_interrupt().enable_channel());
+        }
+    }
+    /// Stop transfer after draining data already in channel FIFO.
+    #[inline]
+    pub fn stop(&mut self) {
+        let channel = &self.dma.channels[self.id];
+        unsafe { channel.config.modify(|v| v.enable_halt()) };
+        while channel.config.read().is_active() {
+            core::hint::spin_loop();
+        }
+        unsafe {
+            channel
+                .config
+                .modify(|v| v.disable_channel().disable_halt())
+        };
+    }
+    /// Check if channel is still transferring.
+    #[inline]
+    pub fn is_busy(&self) -> bool {
+        self.dma.enabled_channels.read() & (1 << self.id) != 0
+    }
+    /// Check if channel stopped with a bus error.
+    #[inline]
+    pub fn has_error(&self) -> bool {
+        self.dma.interrupts.raw_error.read() & (1 << self.id) != 0
+    }
+
+    /// Wait until transfer of this channel completes or stops with a bus error.
+    ///
+    /// `state` should be dedicated to this channel, with its
+    /// [`on_interrupt`](DmaChannelState::on_interrupt) called in the DMA interrupt handler.
+    /// Check [`has_error`](Self::has_error) afterwards to tell both outcomes apart. A
+    /// circular transfer never completes on its own; stop it instead.
+    #[inline]
+    pub async fn wait_complete(&mut self, state: &'static DmaChannelState) {
+        state
+            .ref_to_dma
+            .store(&*self.dma as *const _ as usize, Ordering::Release);
+        state.channel.store(self.id, Ordering::Release);
+        poll_fn(|cx| {
+            state.complete.register(cx.waker());
+            if self.is_busy() {
+                Poll::Pending
+            } else {
+                Poll::Ready(())
+            }
+        })
+        .await
+    }
+    /// Block until transfer of this channel completes or stops with a bus error.
+    ///
+    /// The core sleeps with `wfi` between checks, so DMA interrupt should be enabled in the
+    /// interrupt controller; otherwise this busy waits. Check [`has_error`](Self::has_error)
+    /// afterwards to tell both outcomes apart.
+    #[inline]
+    pub fn wait_complete_blocking(&self) {
+        while self.is_busy() {
+            wait_for_interrupt();
+        }
+    }
+    /// Register block of the DMA peripheral this channel belongs to.
+    #[inline]
+    pub(crate) fn register_block(&self) -> &RegisterBlock {
+        &self.dma
+    }
+
+    #[inline]
+    fn load(&mut self, pool: &[LliItem], used: usize) -> Result<usize, TransferError> {
+        let head = pool[..used].first().ok_or(TransferError::ZeroLength)?;
+        let channel = &self.dma.channels[self.id];
+        unsafe {
+            channel.source_address.write(head.source_address);
+            channel.destination_address.write(head.destination_address);
+            channel.linked_list_item.write(head.linked_list_item);
+            channel.control.write(head.control);
+        }
+        Ok(used)
+    }
+}
+
+/// Waker as the state for one async/await Direct Memory Access channel.
+///
+/// Use one state per channel, and call [`DmaChannelState::on_interrupt`] from the
+/// interrupt handler of that channel, or from the shared handler of its peripheral.
+///
+/// # Examples
+///
+/// ```ignore
+/// static DMA2_CH0_STATE: DmaChannelState = DmaChannelState::new();
+///
+/// #[interrupt]
+/// fn dma2_int0() {
+///     DMA2_CH0_STATE.on_interrupt();
+/// }
+///
+/// channel.start();
+/// channel.wait_complete(&DMA2_CH0_STATE).await;
+/// ```
+#[derive(Debug)]
+pub struct DmaChannelState {
+    complete: atomic_waker::AtomicWaker,
+    ref_to_dma: AtomicUsize,
+    channel: AtomicUsize,
+}
+
+impl DmaChannelState {
+    /// Creates the waker for a DMA channel.
+    #[inline]
+    pub const fn new() -> DmaChannelState {
+        DmaChannelState {
+            complete: atomic_waker::AtomicWaker::new(),
+            ref_to_dma: AtomicUsize::new(0),
+            channel: AtomicUsize::new(0),
+        }
+    }
+    /// Use this waker to handle DMA interrupt.
+    ///
+    /// Clears transfer complete interrupt of the channel, and masks its error interrupt
+    /// so that the error state stays readable by `has_error` until the channel restarts.
+    /// Does nothing if this channel raised no interrupt.
+    #[inline]
+    pub fn on_interrupt(&self) {
+        let dma = self.ref_to_dma.load(Ordering::Acquire) as *const RegisterBlock;
+        if dma.is_null() {
+            return;
+        }
+        let dma = unsafe { &*dma };
+        let id = self.channel.load(Ordering::Acquire);
+        let mut wake = false;
+        if dma.interrupts.transfer_complete_state.read() & (1 << id) != 0 {
+            unsafe { dma.interrupts.transfer_complete_clear.write(1 << id) };
+            wake = true;
+        }
+        if dma.interrupts.error_state.read() & (1 << id) != 0 {
+            unsafe {
+                dma.channels[id]
+                    .config
+                    .modify(|v| v.disable_error_interrupt())
+            };
+            wake = true;
+        }
+        if wake {
+            self.complete.wake();
+        }
+    }
+}
+
+impl Default for DmaChannelState {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Sleep until an interrupt is pending.
+#[inline]
+fn wait_for_interrupt() {
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+    unsafe {
+        core::arch::asm!("wfi")
+    };
+    #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+    core::hint::spin_loop();
+}
+
+/// Append items for a two-dimensional transfer at `pool[*used..]`.
+fn push_2d(
+    pool: &mut [LliItem],
+    used: &mut usize,
+    control: LliControl,
+    transfer: &LliTransfer2d,
+) -> Result<(), TransferError> {
+    let src_inc = control.is_source_increment_enabled();
+    let dst_inc = control.is_destination_increment_enabled();
+    if transfer.width == 0 || transfer.height == 0 {
+        return Err(TransferError::ZeroLength);
+    }
+    if (src_inc && transfer.src_stride < transfer.width)
+        || (dst_inc && transfer.dst_stride < transfer.width)
+    {
+        return Err(TransferError::StrideTooSmall);
+    }
+    let src_align = control.source_transfer_width().bytes();
+    let dst_align = control.destination_transfer_width().bytes();
+    if (src_inc && !transfer.src_stride.is_multiple_of(src_align))
+        || (dst_inc && !transfer.dst_stride.is_multiple_of(dst_align))
+    {
+        return Err(TransferError::Unaligned);
+    }
+    let contiguous = (!src_inc || transfer.src_stride == transfer.width)
+        && (!dst_inc || transfer.dst_stride == transfer.width);
+    if contiguous {
+        let block = LliTransfer {
+            src_addr: transfer.src_addr,
+            dst_addr: transfer.dst_addr,
+            nbytes: transfer.width * transfer.height,
+        };
+        return push_block(pool, used, control, block);
+    }
+    for row in 0..transfer.height {
+        let block = LliTransfer {
+            src_addr: transfer.src_addr
+                + if src_inc {
+                    row * transfer.src_stride
+                } else {
+                    0
+                },
+            dst_addr: transfer.dst_addr
+                + if dst_inc {
+                    row * transfer.dst_stride
+                } else {
+                    0
+                },
+            nbytes: transfer.width,
+        };
+        push_block(pool, used, control, block)?;
+    }
+    Ok(())
+}
+
+/// Split `transfer` into two halves on its memory side for a circular transfer.
+fn circular_halves(
+    control: LliControl,
+    transfer: LliTransfer,
+) -> Result<([LliTransfer; 2], CircularLayout), TransferError> {
+    let src_inc = control.is_source_increment_enabled();
+    let dst_inc = control.is_destination_increment_enabled();
+    let width = control
+        .source_transfer_width()
+        .bytes()
+        .max(control.destination_transfer_width().bytes());
+    if transfer.nbytes == 0 {
+        return Err(TransferError::ZeroLength);
+    }
+    if !transfer.nbytes.is_multiple_of(2 * width) {
+        return Err(TransferError::Unaligned);
+    }
+    let half = transfer.nbytes / 2;
+    let second = LliTransfer {
+        src_addr: transfer.src_addr + if src_inc { half } else { 0 },
+        dst_addr: transfer.dst_addr + if dst_inc { half } else { 0 },
+        nbytes: half,
+    };
+    let first = LliTransfer {
+        nbytes: half,
+        ..transfer
+    };
+    let destination = dst_inc || !src_inc;
+    let layout = CircularLayout {
+        destination,
+        start: if destination {
+            transfer.dst_addr
+        } else {
+            transfer.src_addr
+        },
+        half,
+    };
+    Ok(([first, second], layout))
+}
+
+/// Tell which half of a circular transfer is done from the address channel is working on.
+#[inline]
+const fn circular_event(layout: CircularLayout, address: u32) -> CircularEvent {
+    let offset = address.wrapping_sub(layout.start);
+    if offset >= layout.half && offset < 2 * layout.half {
+        CircularEvent::Half
+    } else {
+        CircularEvent::Complete
+    }
+}
+
+/// Append items for `transfers` as a ring, each raising complete interrupt at its end.
+fn push_ring(
+    pool: &mut [LliItem],
+    used: &mut usize,
+    control: LliControl,
+    transfers: &[LliTransfer],
+) -> Result<(), TransferError> {
+    let head = *used;
+    for transfer in transfers {
+        let start = *used;
+        push_block(pool, used, control, *transfer)?;
+        // Linking cleared interrupt on end of previous transfer.
+        if let Some(prev) = start.checked_sub(1).filter(|&prev| prev >= head) {
+            pool[prev].control = pool[prev].control.enable_complete_interrupt();
+        }
+    }
+    if *used == head {
+        return Err(TransferError::ZeroLength);
+    }
+    pool[*used - 1].linked_list_item = &pool[head] as *const LliItem as usize as u32;
+    Ok(())
+}
+
+/// Append items for contiguous `transfer` at `pool[*used..]` and link them after previous items.
+///
+/// Only the last item appended raises transfer complete interrupt.
+fn push_block(
+    pool: &mut [LliItem],
+    used: &mut usize,
+    control: LliControl,
+    transfer: LliTransfer,
+) -> Result<(), TransferError> {
+    let src_width = control.source_transfer_width().bytes();
+    let dst_width = control.destination_transfer_width().bytes();
+    if transfer.nbytes == 0 {
+        return Err(TransferError::ZeroLength);
+    }
+    if !transfer.src_addr.is_multiple_of(src_width)
+        || !transfer.dst_addr.is_multiple_of(dst_width)
+        || !transfer.nbytes.is_multiple_of(src_width.max(dst_width))
+    {
+        return Err(TransferError::Unaligned);
+    }
+    let chunk_max = max_chunk_bytes(src_width, dst_width);
+    let mut offset = 0;
+    while offset < transfer.nbytes {
+        let nbytes = (transfer.nbytes - offset).min(chunk_max);
+        let item = pool.get_mut(*used).ok_or(TransferError::PoolTooSmall)?;
+        *item = LliItem {
+            source_address: transfer.src_addr
+                + if control.is_source_increment_enabled() {
+                    offset
+                } else {
+                    0
+                },
+            destination_address: transfer.dst_addr
+                + if control.is_destination_increment_enabled() {
+                    offset
+                } else {
+                    0
+                },
+            linked_list_item: 0,
+            control: control
+                .set_transfer_size((nbytes / src_width) as u16)
+                .enable_complete_interrupt(),
+        };
+        if let Some(prev) = used.checked_sub(1) {
+            let address = &pool[*used] as *const LliItem as usize as u32;
+            let prev = &mut pool[prev];
+            prev.linked_list_item = address;
+            prev.control = prev.control.disable_complete_interrupt();
+        }
+        *used += 1;
+        offset += nbytes;
+    }
+    Ok(())
+}
+
+/// Largest number of bytes in one item which is a multiple of both transfer widths.
+#[inline]
+const fn max_chunk_bytes(src_width: u32, dst_width: u32) -> u32 {
+    let bytes = LLI_TRANSFER_SIZE_MAX * src_width;
+    let align = if src_width > dst_width {
+        src_width
+    } else {
+        dst_width
+    };
+    bytes - bytes % align
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{ChannelRegisters, InterruptRegisters, RegisterBlock};
+    use super::{
+        circular_event, circular_halves, first_free, max_chunk_bytes, push_2d, push_block,
+        push_ring, BurstSize, ChannelConfig, ChannelRegisters, CircularEvent, CircularLayout,
+        DmaChannelConfig, DmaConfigError, DmaMode, GlobalConfig, InterruptRegisters, LliControl,
+        LliItem, LliTransfer, LliTransfer2d, Periph4Dma01, RegisterBlock, TransferError,
+        TransferWidth,
+    };
     use memoffset::offset_of;
 
     #[test]
@@ -118,4 +1324,441 @@ mod tests {
         assert_eq!(offset_of!(ChannelRegisters, config), 0x10);
         assert_eq!(core::mem::size_of::<ChannelRegisters>(), 0x100);
     }
+
+    #[test]
+    fn struct_global_config_functions() {
+        let val = GlobalConfig(0x0).enable_dma();
+        assert_eq!(val.0, 0x1);
+        assert!(val.is_dma_enabled());
+        let val = val.disable_dma();
+        assert_eq!(val.0, 0x0);
+        assert!(!val.is_dma_enabled());
+    }
+
+    #[test]
+    fn struct_lli_control_functions() {
+        let val = LliControl(0x0).set_transfer_size(0xfff);
+        assert_eq!(val.0, 0x0000_0fff);
+        assert_eq!(val.transfer_size(), 0xfff);
+        let val = LliControl(0x0).set_source_burst_size(BurstSize::Incr16);
+        assert_eq!(val.0, 0x0000_3000);
+        assert_eq!(val.source_burst_size(), BurstSize::Incr16);
+        let val = LliControl(0x0).set_destination_burst_size(BurstSize::Incr8);
+        assert_eq!(val.0, 0x0001_0000);
+        assert_eq!(val.destination_burst_size(), BurstSize::Incr8);
+        let val = LliControl(0x0).set_source_transfer_width(TransferWidth::Word);
+        assert_eq!(val.0, 0x0008_0000);
+        assert_eq!(val.source_transfer_width(), TransferWidth::Word);
+        let val = LliControl(0x0).set_destination_transfer_width(TransferWidth::DoubleWord);
+        assert_eq!(val.0, 0x0060_0000);
+        assert_eq!(val.destination_transfer_width(), TransferWidth::DoubleWord);
+        let val = LliControl(0x0).enable_source_increment();
+        assert_eq!(val.0, 0x0400_0000);
+        assert!(val.is_source_increment_enabled());
+        assert!(!val.disable_source_increment().is_source_increment_enabled());
+        let val = LliControl(0x0).enable_destination_increment();
+        assert_eq!(val.0, 0x0800_0000);
+        assert!(val.is_destination_increment_enabled());
+        assert!(!val
+            .disable_destination_increment()
+            .is_destination_increment_enabled());
+        let val = LliControl(0x0).enable_complete_interrupt();
+        assert_eq!(val.0, 0x8000_0000);
+        assert!(val.is_complete_interrupt_enabled());
+        assert!(!val
+            .disable_complete_interrupt()
+            .is_complete_interrupt_enabled());
+    }
+
+    #[test]
+    fn struct_channel_config_functions() {
+        let val = ChannelConfig(0x0).enable_channel();
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_channel_enabled());
+        assert!(!val.disable_channel().is_channel_enabled());
+        let val = ChannelConfig(0x0).set_source_peripheral(0x1f);
+        assert_eq!(val.0, 0x0000_003e);
+        assert_eq!(val.source_peripheral(), 0x1f);
+        let val = ChannelConfig(0x0).set_destination_peripheral(0x1f);
+        assert_eq!(val.0, 0x0000_07c0);
+        assert_eq!(val.destination_peripheral(), 0x1f);
+        let val = ChannelConfig(0x0).set_flow_control(DmaMode::Periph2Mem);
+        assert_eq!(val.0, 0x0000_1000);
+        assert_eq!(val.flow_control(), DmaMode::Periph2Mem);
+        let val = ChannelConfig(0x0).set_flow_control(DmaMode::Periph2Periph);
+        assert_eq!(val.0, 0x0000_1800);
+        assert_eq!(val.flow_control(), DmaMode::Periph2Periph);
+        let val = ChannelConfig(0x0).enable_error_interrupt();
+        assert_eq!(val.0, 0x0000_4000);
+        assert!(val.is_error_interrupt_enabled());
+        assert!(!val.disable_error_interrupt().is_error_interrupt_enabled());
+        let val = ChannelConfig(0x0).enable_complete_interrupt();
+        assert_eq!(val.0, 0x0000_8000);
+        assert!(val.is_complete_interrupt_enabled());
+        assert!(!val
+            .disable_complete_interrupt()
+            .is_complete_interrupt_enabled());
+        assert!(ChannelConfig(0x0002_0000).is_active());
+        let val = ChannelConfig(0x0).enable_halt();
+        assert_eq!(val.0, 0x0004_0000);
+        assert!(val.is_halt_enabled());
+        assert!(!val.disable_halt().is_halt_enabled());
+    }
+
+    #[test]
+    fn function_max_chunk_bytes() {
+        assert_eq!(max_chunk_bytes(1, 1), 4095);
+        assert_eq!(max_chunk_bytes(1, 4), 4092);
+        assert_eq!(max_chunk_bytes(4, 1), 16380);
+        assert_eq!(max_chunk_bytes(2, 8), 8184);
+    }
+
+    fn memory_control(width: TransferWidth) -> LliControl {
+        LliControl::default()
+            .set_source_transfer_width(width)
+            .set_destination_transfer_width(width)
+            .enable_source_increment()
+            .enable_destination_increment()
+    }
+
+    #[test]
+    fn function_push_block() {
+        let control = memory_control(TransferWidth::Word);
+        let mut pool = [LliItem::default(); 4];
+        let mut used = 0;
+        let transfer = LliTransfer {
+            src_addr: 0x1000,
+            dst_addr: 0x8000_0000,
+            nbytes: 20000,
+        };
+        assert_eq!(push_block(&mut pool, &mut used, control, transfer), Ok(()));
+        assert_eq!(used, 2);
+        assert_eq!(pool[0].source_address, 0x1000);
+        assert_eq!(pool[0].control.transfer_size(), 4095);
+        assert!(!pool[0].control.is_complete_interrupt_enabled());
+        assert_eq!(
+            pool[0].linked_list_item,
+            &pool[1] as *const _ as usize as u32
+        );
+        assert_eq!(pool[1].source_address, 0x1000 + 16380);
+        assert_eq!(pool[1].destination_address, 0x8000_0000 + 16380);
+        assert_eq!(pool[1].control.transfer_size(), (20000 - 16380) / 4);
+        assert!(pool[1].control.is_complete_interrupt_enabled());
+        assert_eq!(pool[1].linked_list_item, 0);
+
+        let fifo = control.disable_destination_increment();
+        let transfer = LliTransfer {
+            src_addr: 0x1000,
+            dst_addr: 0x2000_a288,
+            nbytes: 16384,
+        };
+        assert_eq!(push_block(&mut pool, &mut used, fifo, transfer), Ok(()));
+        assert_eq!(used, 4);
+        assert_eq!(pool[3].destination_address, 0x2000_a288);
+        assert_eq!(
+            pool[1].linked_list_item,
+            &pool[2] as *const _ as usize as u32
+        );
+        assert!(!pool[1].control.is_complete_interrupt_enabled());
+        assert_eq!(
+            push_block(&mut pool, &mut used, control, transfer),
+            Err(TransferError::PoolTooSmall)
+        );
+        let mut used = 0;
+        let unaligned = LliTransfer {
+            src_addr: 0x1002,
+            dst_addr: 0x2000,
+            nbytes: 4,
+        };
+        assert_eq!(
+            push_block(&mut pool, &mut used, control, unaligned),
+            Err(TransferError::Unaligned)
+        );
+        let empty = LliTransfer {
+            src_addr: 0x1000,
+            dst_addr: 0x2000,
+            nbytes: 0,
+        };
+        assert_eq!(
+            push_block(&mut pool, &mut used, control, empty),
+            Err(TransferError::ZeroLength)
+        );
+    }
+
+    #[test]
+    fn function_push_2d() {
+        let control = memory_control(TransferWidth::HalfWord);
+        let mut pool = [LliItem::default(); 8];
+        let mut used = 0;
+        // 16x4 pixel RGB565 window at (8, 2) of a 320 pixel wide framebuffer.
+        let window = LliTransfer2d {
+            src_addr: 0x1000 + 2 * 640 + 8 * 2,
+            dst_addr: 0x9000,
+            width: 32,
+            height: 4,
+            src_stride: 640,
+            dst_stride: 32,
+        };
+        assert_eq!(push_2d(&mut pool, &mut used, control, &window), Ok(()));
+        assert_eq!(used, 4);
+        for (row, item) in pool[..4].iter().enumerate() {
+            assert_eq!(item.source_address, window.src_addr + row as u32 * 640);
+            assert_eq!(item.destination_address, 0x9000 + row as u32 * 32);
+            assert_eq!(item.control.transfer_size(), 16);
+        }
+        assert!(pool[3].control.is_complete_interrupt_enabled());
+
+        let mut used = 0;
+        let full_rows = LliTransfer2d {
+            src_stride: 32,
+            ..window
+        };
+        assert_eq!(push_2d(&mut pool, &mut used, control, &full_rows), Ok(()));
+        assert_eq!(used, 1);
+        assert_eq!(pool[0].control.transfer_size(), 64);
+
+        let mut used = 0;
+        let to_fifo = LliTransfer2d {
+            dst_stride: 0,
+            ..window
+        };
+        let fifo = control.disable_destination_increment();
+        assert_eq!(push_2d(&mut pool, &mut used, fifo, &to_fifo), Ok(()));
+        assert_eq!(used, 4);
+        assert_eq!(pool[3].destination_address, 0x9000);
+
+        let mut used = 0;
+        let narrow = LliTransfer2d {
+            src_stride: 16,
+            ..window
+        };
+        assert_eq!(
+            push_2d(&mut pool, &mut used, control, &narrow),
+            Err(TransferError::StrideTooSmall)
+        );
+        let odd = LliTransfer2d {
+            src_stride: 641,
+            ..window
+        };
+        assert_eq!(
+            push_2d(&mut pool, &mut used, control, &odd),
+            Err(TransferError::Unaligned)
+        );
+        let empty = LliTransfer2d {
+            height: 0,
+            ..window
+        };
+        assert_eq!(
+            push_2d(&mut pool, &mut used, control, &empty),
+            Err(TransferError::ZeroLength)
+        );
+    }
+
+    #[test]
+    fn function_push_ring() {
+        let control = LliControl::default().enable_source_increment();
+        let mut pool = [LliItem::default(); 4];
+        let mut used = 0;
+        let transfers = [
+            LliTransfer {
+                src_addr: 0x1000,
+                dst_addr: 0x2000,
+                nbytes: 4095 + 5,
+            },
+            LliTransfer {
+                src_addr: 0x4000,
+                dst_addr: 0x2000,
+                nbytes: 10,
+            },
+        ];
+        push_ring(&mut pool, &mut used, control, &transfers).unwrap();
+        assert_eq!(used, 3);
+        let address = |i: usize| &pool[i] as *const LliItem as usize as u32;
+        assert_eq!(pool[0].linked_list_item, address(1));
+        assert!(!pool[0].control.is_complete_interrupt_enabled());
+        assert_eq!(pool[1].linked_list_item, address(2));
+        assert!(pool[1].control.is_complete_interrupt_enabled());
+        assert_eq!(pool[2].source_address, 0x4000);
+        assert_eq!(pool[2].linked_list_item, address(0));
+        assert!(pool[2].control.is_complete_interrupt_enabled());
+
+        let mut used = 0;
+        assert_eq!(
+            push_ring(&mut pool, &mut used, control, &[]),
+            Err(TransferError::ZeroLength)
+        );
+    }
+
+    #[test]
+    fn function_circular_halves() {
+        let control = LliControl::default()
+            .enable_destination_increment()
+            .set_source_transfer_width(TransferWidth::HalfWord)
+            .set_destination_transfer_width(TransferWidth::HalfWord);
+        let transfer = LliTransfer {
+            src_addr: 0x2000_a000,
+            dst_addr: 0x6200_0000,
+            nbytes: 1024,
+        };
+        let (halves, layout) = circular_halves(control, transfer).unwrap();
+        assert_eq!(
+            halves,
+            [
+                LliTransfer {
+                    src_addr: 0x2000_a000,
+                    dst_addr: 0x6200_0000,
+                    nbytes: 512,
+                },
+                LliTransfer {
+                    src_addr: 0x2000_a000,
+                    dst_addr: 0x6200_0200,
+                    nbytes: 512,
+                },
+            ]
+        );
+        assert_eq!(
+            layout,
+            CircularLayout {
+                destination: true,
+                start: 0x6200_0000,
+                half: 512,
+            }
+        );
+
+        let control = LliControl::default().enable_source_increment();
+        let (halves, layout) = circular_halves(control, transfer).unwrap();
+        assert_eq!(halves[1].src_addr, 0x2000_a200);
+        assert_eq!(halves[1].dst_addr, 0x6200_0000);
+        assert!(!layout.destination);
+
+        let transfer = LliTransfer {
+            nbytes: 6,
+            ..transfer
+        };
+        let control = control.set_source_transfer_width(TransferWidth::Word);
+        assert_eq!(
+            circular_halves(control, transfer),
+            Err(TransferError::Unaligned)
+        );
+        let transfer = LliTransfer {
+            nbytes: 0,
+            ..transfer
+        };
+        assert_eq!(
+            circular_halves(control, transfer),
+            Err(TransferError::ZeroLength)
+        );
+    }
+
+    #[test]
+    fn function_circular_event() {
+        let layout = CircularLayout {
+            destination: true,
+            start: 0x6200_0000,
+            half: 512,
+        };
+        assert_eq!(circular_event(layout, 0x6200_0000), CircularEvent::Complete);
+        assert_eq!(circular_event(layout, 0x6200_01fe), CircularEvent::Complete);
+        assert_eq!(circular_event(layout, 0x6200_0200), CircularEvent::Half);
+        assert_eq!(circular_event(layout, 0x6200_03fe), CircularEvent::Half);
+        assert_eq!(circular_event(layout, 0x6200_0400), CircularEvent::Complete);
+    }
+
+    #[test]
+    fn function_first_free() {
+        assert_eq!(first_free(0x00), Some(0));
+        assert_eq!(first_free(0x01), Some(1));
+        assert_eq!(first_free(0x7f), Some(7));
+        assert_eq!(first_free(0xfe), Some(0));
+        assert_eq!(first_free(0xff), None);
+        // DMA1 on BL808 with channels 4 to 7 marked as used.
+        assert_eq!(first_free(!0x0f | 0x07), Some(3));
+        assert_eq!(first_free(!0x0f | 0x0f), None);
+    }
+
+    #[test]
+    fn struct_dma_channel_config_validate() {
+        assert_eq!(DmaChannelConfig::default().validate(), Ok(()));
+        let tx = DmaChannelConfig {
+            direction: DmaMode::Mem2Periph,
+            dst_req: Some(Periph4Dma01::Uart0Tx),
+            dst_addr_inc: false,
+            ..Default::default()
+        };
+        assert_eq!(tx.validate(), Ok(()));
+        let rx = DmaChannelConfig {
+            direction: DmaMode::Periph2Mem,
+            src_req: Some(Periph4Dma01::Uart0Rx),
+            src_addr_inc: false,
+            src_transfer_width: TransferWidth::Word,
+            dst_transfer_width: TransferWidth::DoubleWord,
+            ..Default::default()
+        };
+        assert_eq!(rx.validate(), Ok(()));
+        let pipe = DmaChannelConfig {
+            direction: DmaMode::Periph2Periph,
+            src_req: Some(Periph4Dma01::GpAdc),
+            dst_req: Some(Periph4Dma01::GpDac),
+            src_addr_inc: false,
+            dst_addr_inc: false,
+            ..Default::default()
+        };
+        assert_eq!(pipe.validate(), Ok(()));
+        let config = DmaChannelConfig {
+            dst_req: None,
+            ..pipe
+        };
+        assert_eq!(
+            config.validate(),
+            Err(DmaConfigError::MissingDestinationRequest)
+        );
+        let config = DmaChannelConfig {
+            src_addr_inc: true,
+            ..pipe
+        };
+        assert_eq!(
+            config.validate(),
+            Err(DmaConfigError::PeripheralAddressIncrement)
+        );
+
+        let config = DmaChannelConfig {
+            dst_req: None,
+            ..tx
+        };
+        assert_eq!(
+            config.validate(),
+            Err(DmaConfigError::MissingDestinationRequest)
+        );
+        let config = DmaChannelConfig {
+            src_req: None,
+            ..rx
+        };
+        assert_eq!(config.validate(), Err(DmaConfigError::MissingSourceRequest));
+        let config = DmaChannelConfig {
+            src_req: Some(Periph4Dma01::Uart0Rx),
+            ..tx
+        };
+        assert_eq!(config.validate(), Err(DmaConfigError::UnexpectedRequest));
+        let config = DmaChannelConfig {
+            dst_req: Some(Periph4Dma01::Spi0Tx),
+            ..Default::default()
+        };
+        assert_eq!(config.validate(), Err(DmaConfigError::UnexpectedRequest));
+        let config = DmaChannelConfig {
+            dst_addr_inc: true,
+            ..tx
+        };
+        assert_eq!(
+            config.validate(),
+            Err(DmaConfigError::PeripheralAddressIncrement)
+        );
+        let config = DmaChannelConfig {
+            src_transfer_width: TransferWidth::DoubleWord,
+            ..rx
+        };
+        assert_eq!(
+            config.validate(),
+            Err(DmaConfigError::PeripheralTransferWidth)
+        );
+    }
 }
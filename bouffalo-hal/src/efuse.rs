@@ -0,0 +1,111 @@
+//! Electronic fuse data, read through shadow registers.
+//!
+//! Efuse contents are loaded into shadow registers at power on, so reads here never access
+//! the efuse macro and never need programming voltage. This module only reads factory
+//! programmed identification and security configuration; key slots are not exposed.
+use core::ops::Deref;
+use volatile_register::RO;
+
+/// Efuse data shadow registers.
+#[repr(C)]
+pub struct RegisterBlock {
+    /// Security and feature configuration.
+    pub config: RO<EfuseConfig>,
+    _reserved0: [u8; 0x10],
+    /// Factory programmed MAC address, lower and upper word.
+    pub mac_address: [RO<u32>; 2],
+}
+
+/// Efuse security and feature configuration word.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct EfuseConfig(u32);
+
+impl EfuseConfig {
+    const SECURE_BOOT: u32 = 0x3 << 4;
+
+    /// Check if boot ROM verifies signature of boot images.
+    #[inline]
+    pub const fn is_secure_boot_enabled(self) -> bool {
+        self.0 & Self::SECURE_BOOT != 0
+    }
+}
+
+/// Managed efuse data reader.
+pub struct Efuse<EF> {
+    efuse: EF,
+}
+
+impl<EF: Deref<Target = RegisterBlock>> Efuse<EF> {
+    /// Create an efuse reader.
+    #[inline]
+    pub const fn new(efuse: EF) -> Self {
+        Self { efuse }
+    }
+    /// Read factory programmed 48-bit MAC address.
+    ///
+    /// Bytes are in transmission order, the same order as in Bouffalo SDK.
+    #[inline]
+    pub fn read_mac_address(&mut self) -> [u8; 6] {
+        let low = self.efuse.mac_address[0].read();
+        let high = self.efuse.mac_address[1].read();
+        mac_address(low, high)
+    }
+    /// Read 64-bit chip identifier.
+    ///
+    /// BL808 has no separate chip identifier field; as in `bflb_efuse_get_chipid` of
+    /// Bouffalo SDK, it is the factory programmed MAC address followed by two zero bytes.
+    #[inline]
+    pub fn read_chip_id(&mut self) -> [u8; 8] {
+        let mac = self.read_mac_address();
+        [mac[0], mac[1], mac[2], mac[3], mac[4], mac[5], 0, 0]
+    }
+    /// Check if boot ROM verifies signature of boot images.
+    #[inline]
+    pub fn is_secure_boot_enabled(&self) -> bool {
+        self.efuse.config.read().is_secure_boot_enabled()
+    }
+    /// Release the efuse peripheral.
+    #[inline]
+    pub fn free(self) -> EF {
+        self.efuse
+    }
+}
+
+/// MAC address from lower and upper efuse words.
+///
+/// Upper 16 bits of the upper word hold zero bit count of the address, and are ignored.
+#[inline]
+const fn mac_address(low: u32, high: u32) -> [u8; 6] {
+    let low = low.to_le_bytes();
+    let high = high.to_le_bytes();
+    [low[0], low[1], low[2], low[3], high[0], high[1]]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mac_address, EfuseConfig, RegisterBlock};
+    use memoffset::offset_of;
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, config), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, mac_address), 0x14);
+    }
+
+    #[test]
+    fn struct_efuse_config_functions() {
+        assert!(!EfuseConfig(0x0).is_secure_boot_enabled());
+        assert!(EfuseConfig(0x10).is_secure_boot_enabled());
+        assert!(EfuseConfig(0x20).is_secure_boot_enabled());
+        assert!(!EfuseConfig(0xffff_ffcf).is_secure_boot_enabled());
+    }
+
+    #[test]
+    fn function_mac_address() {
+        assert_eq!(
+            mac_address(0x5634_1200, 0x0017_bc9a),
+            [0x00, 0x12, 0x34, 0x56, 0x9a, 0xbc]
+        );
+    }
+}
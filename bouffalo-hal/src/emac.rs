@@ -1,6 +1,9 @@
 //! Ethernet Media Access Control peripheral.
 use volatile_register::{RO, RW};
 
+mod ring;
+pub use ring::*;
+
 /// Ethernet Media Access Control peripheral registers.
 #[repr(C)]
 pub struct RegisterBlock {
@@ -42,6 +45,20 @@ pub struct RegisterBlock {
     pub hash: [RW<Hash>; 2],
     /// Transmit control.
     pub transmit_control: RW<TransmitControl>,
+    _reserved2: [u8; 0x3ac],
+    /// Buffer descriptors, transmit descriptors first and receive descriptors after.
+    ///
+    /// The number of transmit descriptors is set in `transmit_buffer`.
+    pub buffer_descriptor: [BufferDescriptor; 128],
+}
+
+/// Buffer descriptor in peripheral memory.
+#[repr(C)]
+pub struct BufferDescriptor {
+    /// Frame length and descriptor state.
+    pub control: RW<DescriptorControl>,
+    /// Address of frame buffer.
+    pub address: RW<u32>,
 }
 
 /// EMAC mode configuration register.
@@ -49,16 +66,170 @@ pub struct RegisterBlock {
 #[repr(transparent)]
 pub struct Mode(u32);
 
+impl Mode {
+    const RECEIVE: u32 = 1 << 0;
+    const TRANSMIT: u32 = 1 << 1;
+    const PROMISCUOUS: u32 = 1 << 5;
+    const FULL_DUPLEX: u32 = 1 << 10;
+    const CRC: u32 = 1 << 13;
+    const PAD: u32 = 1 << 15;
+
+    /// Enable frame receive.
+    #[inline]
+    pub const fn enable_receive(self) -> Self {
+        Self(self.0 | Self::RECEIVE)
+    }
+    /// Disable frame receive.
+    #[inline]
+    pub const fn disable_receive(self) -> Self {
+        Self(self.0 & !Self::RECEIVE)
+    }
+    /// Check if frame receive is enabled.
+    #[inline]
+    pub const fn is_receive_enabled(self) -> bool {
+        self.0 & Self::RECEIVE != 0
+    }
+    /// Enable frame transmit.
+    #[inline]
+    pub const fn enable_transmit(self) -> Self {
+        Self(self.0 | Self::TRANSMIT)
+    }
+    /// Disable frame transmit.
+    #[inline]
+    pub const fn disable_transmit(self) -> Self {
+        Self(self.0 & !Self::TRANSMIT)
+    }
+    /// Check if frame transmit is enabled.
+    #[inline]
+    pub const fn is_transmit_enabled(self) -> bool {
+        self.0 & Self::TRANSMIT != 0
+    }
+    /// Enable promiscuous mode, receiving frames of all destination addresses.
+    #[inline]
+    pub const fn enable_promiscuous(self) -> Self {
+        Self(self.0 | Self::PROMISCUOUS)
+    }
+    /// Disable promiscuous mode.
+    #[inline]
+    pub const fn disable_promiscuous(self) -> Self {
+        Self(self.0 & !Self::PROMISCUOUS)
+    }
+    /// Check if promiscuous mode is enabled.
+    #[inline]
+    pub const fn is_promiscuous_enabled(self) -> bool {
+        self.0 & Self::PROMISCUOUS != 0
+    }
+    /// Enable full duplex.
+    #[inline]
+    pub const fn enable_full_duplex(self) -> Self {
+        Self(self.0 | Self::FULL_DUPLEX)
+    }
+    /// Disable full duplex.
+    #[inline]
+    pub const fn disable_full_duplex(self) -> Self {
+        Self(self.0 & !Self::FULL_DUPLEX)
+    }
+    /// Check if full duplex is enabled.
+    #[inline]
+    pub const fn is_full_duplex_enabled(self) -> bool {
+        self.0 & Self::FULL_DUPLEX != 0
+    }
+    /// Enable appending CRC to transmitted frames.
+    #[inline]
+    pub const fn enable_crc(self) -> Self {
+        Self(self.0 | Self::CRC)
+    }
+    /// Disable appending CRC to transmitted frames.
+    #[inline]
+    pub const fn disable_crc(self) -> Self {
+        Self(self.0 & !Self::CRC)
+    }
+    /// Check if appending CRC is enabled.
+    #[inline]
+    pub const fn is_crc_enabled(self) -> bool {
+        self.0 & Self::CRC != 0
+    }
+    /// Enable padding short transmitted frames to minimum length.
+    #[inline]
+    pub const fn enable_pad(self) -> Self {
+        Self(self.0 | Self::PAD)
+    }
+    /// Disable padding short transmitted frames.
+    #[inline]
+    pub const fn disable_pad(self) -> Self {
+        Self(self.0 & !Self::PAD)
+    }
+    /// Check if padding short frames is enabled.
+    #[inline]
+    pub const fn is_pad_enabled(self) -> bool {
+        self.0 & Self::PAD != 0
+    }
+}
+
 /// EMAC transmit control register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 #[repr(transparent)]
 pub struct InterruptSource(u32);
 
+impl InterruptSource {
+    /// Check if interrupt flag is set.
+    #[inline]
+    pub const fn has_interrupt(self, val: Interrupt) -> bool {
+        self.0 & (1 << (val as u32)) != 0
+    }
+    /// Clear interrupt flag.
+    ///
+    /// Flags are cleared by writing one, so write the result of this function on
+    /// a default value to leave other flags unchanged.
+    #[inline]
+    pub const fn clear_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 | (1 << (val as u32)))
+    }
+}
+
 /// EMAC interrupt mask register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 #[repr(transparent)]
 pub struct InterruptMask(u32);
 
+impl InterruptMask {
+    /// Enable interrupt.
+    #[inline]
+    pub const fn enable_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 | (1 << (val as u32)))
+    }
+    /// Disable interrupt.
+    #[inline]
+    pub const fn disable_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 & !(1 << (val as u32)))
+    }
+    /// Check if interrupt is enabled.
+    #[inline]
+    pub const fn is_interrupt_enabled(self, val: Interrupt) -> bool {
+        self.0 & (1 << (val as u32)) != 0
+    }
+}
+
+/// Interrupt event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Interrupt {
+    /// A frame has been transmitted from a descriptor with interrupt bit set.
+    TransmitBuffer = 0,
+    /// Transmit error.
+    TransmitError = 1,
+    /// A frame has been received into a descriptor with interrupt bit set.
+    ReceiveBuffer = 2,
+    /// Receive error.
+    ReceiveError = 3,
+    /// A frame was dropped because no empty receive descriptor was available.
+    Busy = 4,
+    /// Control frame transmitted.
+    TransmitControl = 5,
+    /// Control frame received.
+    ReceiveControl = 6,
+}
+
 /// EMAC inter packet gap (backed gap) register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 #[repr(transparent)]
@@ -69,6 +240,32 @@ pub struct BackedGap(u32);
 #[repr(transparent)]
 pub struct FrameLength(u32);
 
+impl FrameLength {
+    const MAX: u32 = 0xffff;
+    const MIN: u32 = 0xffff << 16;
+
+    /// Set maximum frame length in bytes.
+    #[inline]
+    pub const fn set_max(self, val: u16) -> Self {
+        Self((self.0 & !Self::MAX) | val as u32)
+    }
+    /// Get maximum frame length in bytes.
+    #[inline]
+    pub const fn max(self) -> u16 {
+        (self.0 & Self::MAX) as u16
+    }
+    /// Set minimum frame length in bytes.
+    #[inline]
+    pub const fn set_min(self, val: u16) -> Self {
+        Self((self.0 & !Self::MIN) | ((val as u32) << 16))
+    }
+    /// Get minimum frame length in bytes.
+    #[inline]
+    pub const fn min(self) -> u16 {
+        ((self.0 & Self::MIN) >> 16) as u16
+    }
+}
+
 /// EMAC collision register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 #[repr(transparent)]
@@ -79,6 +276,21 @@ pub struct Collision(u32);
 #[repr(transparent)]
 pub struct TransmitBuffer(u32);
 
+impl TransmitBuffer {
+    const COUNT: u32 = 0xff;
+
+    /// Set number of transmit buffer descriptors, at most 128.
+    #[inline]
+    pub const fn set_count(self, val: u8) -> Self {
+        Self((self.0 & !Self::COUNT) | val as u32)
+    }
+    /// Get number of transmit buffer descriptors.
+    #[inline]
+    pub const fn count(self) -> u8 {
+        (self.0 & Self::COUNT) as u8
+    }
+}
+
 /// MII clock divider and premable register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 #[repr(transparent)]
@@ -114,6 +326,19 @@ pub struct MiiState(u32);
 #[repr(transparent)]
 pub struct MacAddress(u32);
 
+impl MacAddress {
+    /// Registers holding Media Access Control address `val`, in register order.
+    ///
+    /// The first register holds the last four bytes, and the second one the first two.
+    #[inline]
+    pub const fn from_bytes(val: [u8; 6]) -> [Self; 2] {
+        [
+            Self(u32::from_be_bytes([val[2], val[3], val[4], val[5]])),
+            Self(u32::from_be_bytes([0, 0, val[0], val[1]])),
+        ]
+    }
+}
+
 /// hash register (64-bit to double 32-bit).
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 #[repr(transparent)]
@@ -123,9 +348,109 @@ pub struct Hash(u32);
 #[repr(transparent)]
 pub struct TransmitControl(u32);
 
+/// Buffer descriptor control word.
+///
+/// Transmit and receive descriptors share frame length, ownership, interrupt and wrap
+/// bits; the remaining bits report per-direction status.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct DescriptorControl(u32);
+
+impl DescriptorControl {
+    const LENGTH: u32 = 0xffff << 16;
+    const HARDWARE: u32 = 1 << 15;
+    const INTERRUPT: u32 = 1 << 14;
+    const WRAP: u32 = 1 << 13;
+    const TRANSMIT_PAD: u32 = 1 << 12;
+    const TRANSMIT_CRC: u32 = 1 << 11;
+    const TRANSMIT_ERRORS: u32 = (1 << 8) | (1 << 3) | (1 << 2) | (1 << 0);
+    const RECEIVE_ERRORS: u32 = 0x7f;
+
+    /// Set frame length in bytes.
+    #[inline]
+    pub const fn set_length(self, val: u16) -> Self {
+        Self((self.0 & !Self::LENGTH) | ((val as u32) << 16))
+    }
+    /// Get frame length in bytes.
+    #[inline]
+    pub const fn length(self) -> u16 {
+        ((self.0 & Self::LENGTH) >> 16) as u16
+    }
+    /// Hand descriptor over to the peripheral.
+    ///
+    /// Means 'ready to transmit' on transmit descriptors, and 'empty' on receive ones.
+    #[inline]
+    pub const fn give_to_hardware(self) -> Self {
+        Self(self.0 | Self::HARDWARE)
+    }
+    /// Check if descriptor is owned by the peripheral.
+    ///
+    /// The peripheral hands the descriptor back once its frame is transmitted or received.
+    #[inline]
+    pub const fn is_owned_by_hardware(self) -> bool {
+        self.0 & Self::HARDWARE != 0
+    }
+    /// Enable interrupt on completion of this descriptor.
+    #[inline]
+    pub const fn enable_interrupt(self) -> Self {
+        Self(self.0 | Self::INTERRUPT)
+    }
+    /// Disable interrupt on completion of this descriptor.
+    #[inline]
+    pub const fn disable_interrupt(self) -> Self {
+        Self(self.0 & !Self::INTERRUPT)
+    }
+    /// Check if interrupt on completion is enabled.
+    #[inline]
+    pub const fn is_interrupt_enabled(self) -> bool {
+        self.0 & Self::INTERRUPT != 0
+    }
+    /// Mark this descriptor as the last one of its ring.
+    #[inline]
+    pub const fn set_wrap(self) -> Self {
+        Self(self.0 | Self::WRAP)
+    }
+    /// Check if this descriptor is the last one of its ring.
+    #[inline]
+    pub const fn is_wrap(self) -> bool {
+        self.0 & Self::WRAP != 0
+    }
+    /// Enable padding short frame of a transmit descriptor to minimum length.
+    #[inline]
+    pub const fn enable_transmit_pad(self) -> Self {
+        Self(self.0 | Self::TRANSMIT_PAD)
+    }
+    /// Check if padding short frame of a transmit descriptor is enabled.
+    #[inline]
+    pub const fn is_transmit_pad_enabled(self) -> bool {
+        self.0 & Self::TRANSMIT_PAD != 0
+    }
+    /// Enable appending CRC to frame of a transmit descriptor.
+    #[inline]
+    pub const fn enable_transmit_crc(self) -> Self {
+        Self(self.0 | Self::TRANSMIT_CRC)
+    }
+    /// Check if appending CRC to frame of a transmit descriptor is enabled.
+    #[inline]
+    pub const fn is_transmit_crc_enabled(self) -> bool {
+        self.0 & Self::TRANSMIT_CRC != 0
+    }
+    /// Check if a transmit descriptor reports underrun, retry limit, late collision or carrier loss.
+    #[inline]
+    pub const fn has_transmit_error(self) -> bool {
+        self.0 & Self::TRANSMIT_ERRORS != 0
+    }
+    /// Check if a receive descriptor reports overrun, invalid symbol, dribble nibble,
+    /// frame too long or too short, CRC error or late collision.
+    #[inline]
+    pub const fn has_receive_error(self) -> bool {
+        self.0 & Self::RECEIVE_ERRORS != 0
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::RegisterBlock;
+    use super::{DescriptorControl, MacAddress, Mode, RegisterBlock};
     use memoffset::offset_of;
 
     #[test]
@@ -146,5 +471,61 @@ mod tests {
         assert_eq!(offset_of!(RegisterBlock, mac_address), 0x40);
         assert_eq!(offset_of!(RegisterBlock, hash), 0x48);
         assert_eq!(offset_of!(RegisterBlock, transmit_control), 0x50);
+        assert_eq!(offset_of!(RegisterBlock, buffer_descriptor), 0x400);
+        assert_eq!(core::mem::size_of::<RegisterBlock>(), 0x800);
+    }
+
+    #[test]
+    fn struct_mode_functions() {
+        let val = Mode(0x0000a000)
+            .enable_receive()
+            .enable_transmit()
+            .enable_full_duplex();
+        assert_eq!(val.0, 0x0000a403);
+        assert!(val.is_receive_enabled());
+        assert!(val.is_crc_enabled());
+        assert!(val.is_pad_enabled());
+        assert!(!val.is_promiscuous_enabled());
+        let val = val.disable_receive().disable_crc().enable_promiscuous();
+        assert_eq!(val.0, 0x00008422);
+        assert!(!val.is_receive_enabled());
+        assert!(val.is_transmit_enabled());
+        assert!(val.is_promiscuous_enabled());
+    }
+
+    #[test]
+    fn struct_descriptor_control_functions() {
+        let val = DescriptorControl(0)
+            .set_length(1514)
+            .give_to_hardware()
+            .enable_interrupt()
+            .set_wrap();
+        assert_eq!(val.0, 0x05eae000);
+        assert_eq!(val.length(), 1514);
+        assert!(val.is_owned_by_hardware());
+        assert!(val.is_interrupt_enabled());
+        assert!(val.is_wrap());
+        assert!(!val.has_receive_error());
+        assert!(!val.has_transmit_error());
+
+        let val = DescriptorControl(0x00404002);
+        assert_eq!(val.length(), 64);
+        assert!(!val.is_owned_by_hardware());
+        assert!(val.has_receive_error());
+        assert!(!val.has_transmit_error());
+        let val = DescriptorControl(0)
+            .enable_transmit_pad()
+            .enable_transmit_crc();
+        assert_eq!(val.0, 0x00001800);
+        assert!(val.is_transmit_pad_enabled());
+        assert!(val.is_transmit_crc_enabled());
+        assert!(DescriptorControl(0x00000100).has_transmit_error());
+    }
+
+    #[test]
+    fn struct_mac_address_functions() {
+        let val = MacAddress::from_bytes([0x18, 0xb9, 0x05, 0x12, 0x34, 0x56]);
+        assert_eq!(val[0].0, 0x05123456);
+        assert_eq!(val[1].0, 0x000018b9);
     }
 }
@@ -0,0 +1,379 @@
+use super::{DescriptorControl, Interrupt, InterruptSource, MacAddress, RegisterBlock};
+use core::{
+    ops::Deref,
+    sync::atomic::{fence, Ordering},
+};
+
+/// Size of each frame buffer in bytes.
+///
+/// Fits a maximum sized Ethernet frame with VLAN tag and frame check sequence.
+pub const FRAME_BUFFER_SIZE: usize = 1536;
+
+/// Minimum frame length in bytes including frame check sequence.
+const MIN_FRAME_LENGTH: u16 = 64;
+/// Length of frame check sequence counted in received frame length.
+const CRC_LENGTH: usize = 4;
+
+/// Frame buffer, word aligned for the peripheral to access.
+#[derive(Clone, Copy)]
+#[repr(C, align(4))]
+struct FrameBuffer([u8; FRAME_BUFFER_SIZE]);
+
+/// Receive ring of `N` frame buffers.
+///
+/// Buffer descriptors live in peripheral memory; the ring owns the frame buffers they
+/// point to, so received frames are handed out in place without copying. Place the ring
+/// in a `static` in memory the peripheral accesses coherently with the core, as drivers
+/// here do not maintain data cache.
+pub struct EmacRxRing<const N: usize> {
+    buffers: [FrameBuffer; N],
+    next: usize,
+}
+
+impl<const N: usize> EmacRxRing<N> {
+    /// Create an empty receive ring.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            buffers: [FrameBuffer([0; FRAME_BUFFER_SIZE]); N],
+            next: 0,
+        }
+    }
+}
+
+impl<const N: usize> Default for EmacRxRing<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Transmit ring of `N` frame buffers.
+///
+/// Frames are built in place in ring buffers and transmitted from there. The same
+/// placement requirements as [`EmacRxRing`] apply.
+pub struct EmacTxRing<const N: usize> {
+    buffers: [FrameBuffer; N],
+    next: usize,
+}
+
+impl<const N: usize> EmacTxRing<N> {
+    /// Create an empty transmit ring.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            buffers: [FrameBuffer([0; FRAME_BUFFER_SIZE]); N],
+            next: 0,
+        }
+    }
+}
+
+impl<const N: usize> Default for EmacTxRing<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Ethernet Media Access Control driver transferring frames through descriptor rings.
+///
+/// The peripheral has 128 buffer descriptors in its own memory; `TX` of them are used
+/// for transmit and the following `RX` ones for receive. Tokens returned by
+/// [`receive`](Self::receive) and [`transmit`](Self::transmit) mirror those of `smoltcp`
+/// `phy::Device`, so a network stack adapter only needs to forward to them.
+pub struct Emac<EMAC, const RX: usize, const TX: usize> {
+    emac: EMAC,
+    rx: &'static mut EmacRxRing<RX>,
+    tx: &'static mut EmacTxRing<TX>,
+}
+
+impl<EMAC: Deref<Target = RegisterBlock>, const RX: usize, const TX: usize> Emac<EMAC, RX, TX> {
+    /// Set up descriptor rings and start receiving and transmitting frames.
+    ///
+    /// Frames are padded to minimum length and have frame check sequence appended on
+    /// transmit. Duplex mode stays unchanged; set it with [`set_full_duplex`](Self::set_full_duplex)
+    /// after the physical layer transceiver has negotiated link.
+    ///
+    /// # Panics
+    ///
+    /// If either ring is empty, or both rings need more than 128 descriptors.
+    #[inline]
+    pub fn init_dma(
+        emac: EMAC,
+        rx_ring: &'static mut EmacRxRing<RX>,
+        tx_ring: &'static mut EmacTxRing<TX>,
+        mac_address: [u8; 6],
+    ) -> Self {
+        assert!(RX > 0 && TX > 0 && RX + TX <= 128);
+        unsafe {
+            emac.mode.modify(|v| v.disable_receive().disable_transmit());
+            emac.transmit_buffer.modify(|v| v.set_count(TX as u8));
+            emac.frame_length.modify(|v| {
+                v.set_min(MIN_FRAME_LENGTH)
+                    .set_max(FRAME_BUFFER_SIZE as u16)
+            });
+            let [address_0, address_1] = MacAddress::from_bytes(mac_address);
+            emac.mac_address[0].write(address_0);
+            emac.mac_address[1].write(address_1);
+        }
+        rx_ring.next = 0;
+        tx_ring.next = 0;
+        let ans = Emac {
+            emac,
+            rx: rx_ring,
+            tx: tx_ring,
+        };
+        for i in 0..TX {
+            let descriptor = &ans.emac.buffer_descriptor[i];
+            let address = ans.tx.buffers[i].0.as_ptr() as usize as u32;
+            unsafe {
+                descriptor.address.write(address);
+                descriptor
+                    .control
+                    .write(ring_control(DescriptorControl::default(), i, TX));
+            }
+        }
+        for i in 0..RX {
+            let descriptor = &ans.emac.buffer_descriptor[TX + i];
+            let address = ans.rx.buffers[i].0.as_ptr() as usize as u32;
+            unsafe { descriptor.address.write(address) };
+            ans.rx_give_to_hardware(i);
+        }
+        unsafe {
+            ans.emac.interrupt_source.write(
+                [
+                    Interrupt::TransmitBuffer,
+                    Interrupt::TransmitError,
+                    Interrupt::ReceiveBuffer,
+                    Interrupt::ReceiveError,
+                    Interrupt::Busy,
+                    Interrupt::TransmitControl,
+                    Interrupt::ReceiveControl,
+                ]
+                .into_iter()
+                .fold(InterruptSource::default(), |v, i| v.clear_interrupt(i)),
+            );
+            ans.emac.mode.modify(|v| {
+                v.enable_crc()
+                    .enable_pad()
+                    .enable_receive()
+                    .enable_transmit()
+            });
+        }
+        ans
+    }
+    /// Get a received frame together with a transmit buffer for the reply.
+    ///
+    /// Frames received with errors are dropped. Returns `None` if no frame is pending,
+    /// or if no transmit buffer is free.
+    #[inline]
+    pub fn receive(&mut self) -> Option<(RxToken<'_, RX>, TxToken<'_, TX>)> {
+        loop {
+            let control = self.emac.buffer_descriptor[TX + self.rx.next]
+                .control
+                .read();
+            if control.is_owned_by_hardware() {
+                return None;
+            }
+            if !control.has_receive_error() {
+                break;
+            }
+            let index = self.rx.next;
+            self.rx_give_to_hardware(index);
+            self.rx.next = (index + 1) % RX;
+        }
+        fence(Ordering::Acquire);
+        let transmit_free = !self.emac.buffer_descriptor[self.tx.next]
+            .control
+            .read()
+            .is_owned_by_hardware();
+        if !transmit_free {
+            return None;
+        }
+        Some((
+            RxToken {
+                emac: &self.emac,
+                ring: self.rx,
+                first_descriptor: TX,
+            },
+            TxToken {
+                emac: &self.emac,
+                ring: self.tx,
+            },
+        ))
+    }
+    /// Get a free transmit buffer, or `None` if all are waiting to be transmitted.
+    #[inline]
+    pub fn transmit(&mut self) -> Option<TxToken<'_, TX>> {
+        if self.emac.buffer_descriptor[self.tx.next]
+            .control
+            .read()
+            .is_owned_by_hardware()
+        {
+            return None;
+        }
+        Some(TxToken {
+            emac: &self.emac,
+            ring: self.tx,
+        })
+    }
+    /// Set full or half duplex mode, as negotiated by the physical layer transceiver.
+    #[inline]
+    pub fn set_full_duplex(&mut self, full_duplex: bool) {
+        unsafe {
+            self.emac.mode.modify(|v| match full_duplex {
+                true => v.enable_full_duplex(),
+                false => v.disable_full_duplex(),
+            })
+        };
+    }
+    /// Enable interrupt event.
+    #[inline]
+    pub fn enable_interrupt(&mut self, val: Interrupt) {
+        unsafe { self.emac.interrupt_mask.modify(|v| v.enable_interrupt(val)) };
+    }
+    /// Disable interrupt event.
+    #[inline]
+    pub fn disable_interrupt(&mut self, val: Interrupt) {
+        unsafe {
+            self.emac
+                .interrupt_mask
+                .modify(|v| v.disable_interrupt(val))
+        };
+    }
+    /// Check if interrupt flag is set.
+    #[inline]
+    pub fn has_interrupt(&self, val: Interrupt) -> bool {
+        self.emac.interrupt_source.read().has_interrupt(val)
+    }
+    /// Clear interrupt flag.
+    #[inline]
+    pub fn clear_interrupt(&mut self, val: Interrupt) {
+        unsafe {
+            self.emac
+                .interrupt_source
+                .write(InterruptSource::default().clear_interrupt(val))
+        };
+    }
+    /// Stop receiving and transmitting, and release the peripheral and rings.
+    #[inline]
+    pub fn free(
+        self,
+    ) -> (
+        EMAC,
+        &'static mut EmacRxRing<RX>,
+        &'static mut EmacTxRing<TX>,
+    ) {
+        unsafe {
+            self.emac
+                .mode
+                .modify(|v| v.disable_receive().disable_transmit())
+        };
+        (self.emac, self.rx, self.tx)
+    }
+
+    /// Return receive descriptor `index` to the peripheral as empty.
+    #[inline]
+    fn rx_give_to_hardware(&self, index: usize) {
+        let control = DescriptorControl::default().enable_interrupt();
+        fence(Ordering::Release);
+        unsafe {
+            self.emac.buffer_descriptor[TX + index]
+                .control
+                .write(ring_control(control, index, RX))
+        };
+    }
+}
+
+/// Token granting access to a received frame.
+///
+/// Dropping the token without consuming it leaves the frame pending, to be returned
+/// again on next [`Emac::receive`].
+pub struct RxToken<'a, const N: usize> {
+    emac: &'a RegisterBlock,
+    ring: &'a mut EmacRxRing<N>,
+    first_descriptor: usize,
+}
+
+impl<'a, const N: usize> RxToken<'a, N> {
+    /// Process the received frame in place, then return its buffer to the peripheral.
+    ///
+    /// The frame excludes the frame check sequence.
+    #[inline]
+    pub fn consume<R, F: FnOnce(&mut [u8]) -> R>(self, f: F) -> R {
+        let index = self.ring.next;
+        let descriptor = &self.emac.buffer_descriptor[self.first_descriptor + index];
+        let length = (descriptor.control.read().length() as usize)
+            .saturating_sub(CRC_LENGTH)
+            .min(FRAME_BUFFER_SIZE);
+        let ans = f(&mut self.ring.buffers[index].0[..length]);
+        let control = DescriptorControl::default().enable_interrupt();
+        fence(Ordering::Release);
+        unsafe { descriptor.control.write(ring_control(control, index, N)) };
+        self.ring.next = (index + 1) % N;
+        ans
+    }
+}
+
+/// Token granting a transmit buffer.
+///
+/// Dropping the token without consuming it transmits nothing.
+pub struct TxToken<'a, const N: usize> {
+    emac: &'a RegisterBlock,
+    ring: &'a mut EmacTxRing<N>,
+}
+
+impl<'a, const N: usize> TxToken<'a, N> {
+    /// Build a frame of `len` bytes in place, then hand it to the peripheral for transmit.
+    ///
+    /// The frame excludes the frame check sequence, which the peripheral appends.
+    ///
+    /// # Panics
+    ///
+    /// If `len` exceeds [`FRAME_BUFFER_SIZE`].
+    #[inline]
+    pub fn consume<R, F: FnOnce(&mut [u8]) -> R>(self, len: usize, f: F) -> R {
+        assert!(len <= FRAME_BUFFER_SIZE);
+        let index = self.ring.next;
+        let ans = f(&mut self.ring.buffers[index].0[..len]);
+        let control = DescriptorControl::default()
+            .set_length(len as u16)
+            .enable_transmit_pad()
+            .enable_transmit_crc()
+            .enable_interrupt()
+            .give_to_hardware();
+        fence(Ordering::Release);
+        unsafe {
+            self.emac.buffer_descriptor[index]
+                .control
+                .write(ring_control(control, index, N))
+        };
+        self.ring.next = (index + 1) % N;
+        ans
+    }
+}
+
+/// Set wrap bit on `control` if descriptor `index` is the last of a ring of `len`.
+#[inline]
+const fn ring_control(control: DescriptorControl, index: usize, len: usize) -> DescriptorControl {
+    if index + 1 == len {
+        control.set_wrap()
+    } else {
+        control
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ring_control, DescriptorControl};
+
+    #[test]
+    fn function_ring_control() {
+        let control = DescriptorControl::default().enable_interrupt();
+        assert!(!ring_control(control, 0, 4).is_wrap());
+        assert!(!ring_control(control, 2, 4).is_wrap());
+        assert!(ring_control(control, 3, 4).is_wrap());
+        assert!(ring_control(control, 0, 1).is_wrap());
+        assert!(ring_control(control, 3, 4).is_interrupt_enabled());
+    }
+}
@@ -1,6 +1,7 @@
 //! Global configuration peripheral.
 
 pub mod mm;
+pub mod pll;
 pub mod v1;
 pub mod v2;
 
@@ -0,0 +1,172 @@
+//! Phase-locked loop configuration registers.
+//!
+//! Output frequency of a phase-locked loop is:
+//!
+//! ```text
+//! f_out = f_ref / ref_divide * (feedback / 2^11) / post_divide
+//! ```
+//!
+//! where `f_ref` is the crystal oscillator frequency, and `feedback` is the sigma-delta
+//! modulator input with 11 fractional bits. The voltage controlled oscillator runs at
+//! `f_out * post_divide`, which must stay within its operating range.
+
+use volatile_register::{RO, RW};
+
+/// Phase-locked loop registers.
+#[repr(C)]
+pub struct RegisterBlock {
+    /// Power and reset control.
+    pub power: RW<Power>,
+    /// Reference and post dividers.
+    pub divide: RW<Divide>,
+    _reserved0: [u8; 0x8],
+    /// Lock detector status.
+    pub status: RO<Status>,
+    _reserved1: [u8; 0x4],
+    /// Sigma-delta modulator input, i.e. feedback divide ratio.
+    pub feedback: RW<Feedback>,
+}
+
+/// Power and reset control register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct Power(u32);
+
+impl Power {
+    const RESET_RELEASE: u32 = 0xf;
+    const POWER_UP: u32 = 0xff << 4;
+
+    /// Power up all stages of the phase-locked loop.
+    #[inline]
+    pub const fn power_up(self) -> Self {
+        Self(self.0 | Self::POWER_UP)
+    }
+    /// Power down all stages of the phase-locked loop.
+    #[inline]
+    pub const fn power_down(self) -> Self {
+        Self(self.0 & !Self::POWER_UP)
+    }
+    /// Check if all stages of the phase-locked loop are powered up.
+    #[inline]
+    pub const fn is_powered_up(self) -> bool {
+        self.0 & Self::POWER_UP == Self::POWER_UP
+    }
+    /// Hold dividers and sigma-delta modulator in reset.
+    #[inline]
+    pub const fn assert_reset(self) -> Self {
+        Self(self.0 & !Self::RESET_RELEASE)
+    }
+    /// Release dividers and sigma-delta modulator from reset.
+    #[inline]
+    pub const fn deassert_reset(self) -> Self {
+        Self(self.0 | Self::RESET_RELEASE)
+    }
+    /// Check if dividers or sigma-delta modulator are held in reset.
+    #[inline]
+    pub const fn is_reset_asserted(self) -> bool {
+        self.0 & Self::RESET_RELEASE != Self::RESET_RELEASE
+    }
+}
+
+/// Reference and post divider register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct Divide(u32);
+
+impl Divide {
+    const POST_DIVIDE: u32 = 0x7f;
+    const REF_DIVIDE: u32 = 0xf << 8;
+
+    /// Set output divide factor.
+    #[inline]
+    pub const fn set_post_divide(self, val: u8) -> Self {
+        Self((self.0 & !Self::POST_DIVIDE) | (val as u32 & Self::POST_DIVIDE))
+    }
+    /// Get output divide factor.
+    #[inline]
+    pub const fn post_divide(self) -> u8 {
+        (self.0 & Self::POST_DIVIDE) as u8
+    }
+    /// Set reference clock divide factor.
+    #[inline]
+    pub const fn set_ref_divide(self, val: u8) -> Self {
+        Self((self.0 & !Self::REF_DIVIDE) | ((val as u32) << 8) & Self::REF_DIVIDE)
+    }
+    /// Get reference clock divide factor.
+    #[inline]
+    pub const fn ref_divide(self) -> u8 {
+        ((self.0 & Self::REF_DIVIDE) >> 8) as u8
+    }
+}
+
+/// Lock detector status register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct Status(u32);
+
+impl Status {
+    const LOCKED: u32 = 1 << 0;
+
+    /// Check if the phase-locked loop output is locked to reference clock.
+    #[inline]
+    pub const fn is_locked(self) -> bool {
+        self.0 & Self::LOCKED != 0
+    }
+}
+
+/// Sigma-delta modulator input register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct Feedback(u32);
+
+impl Feedback {
+    const FEEDBACK: u32 = 0x7ffff;
+
+    /// Set feedback divide ratio, with 11 fractional bits.
+    #[inline]
+    pub const fn set_ratio(self, val: u32) -> Self {
+        Self((self.0 & !Self::FEEDBACK) | (val & Self::FEEDBACK))
+    }
+    /// Get feedback divide ratio, with 11 fractional bits.
+    #[inline]
+    pub const fn ratio(self) -> u32 {
+        self.0 & Self::FEEDBACK
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Divide, Feedback, Power, RegisterBlock, Status};
+    use memoffset::offset_of;
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, power), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, divide), 0x04);
+        assert_eq!(offset_of!(RegisterBlock, status), 0x10);
+        assert_eq!(offset_of!(RegisterBlock, feedback), 0x18);
+    }
+
+    #[test]
+    fn struct_pll_functions() {
+        let val = Power(0).power_up().deassert_reset();
+        assert_eq!(val.0, 0x0fff);
+        assert!(val.is_powered_up());
+        assert!(!val.is_reset_asserted());
+        let val = val.assert_reset();
+        assert!(val.is_reset_asserted());
+        assert!(!val.power_down().is_powered_up());
+
+        let val = Divide(0).set_post_divide(2).set_ref_divide(1);
+        assert_eq!(val.0, 0x0102);
+        assert_eq!(val.post_divide(), 2);
+        assert_eq!(val.ref_divide(), 1);
+
+        assert!(Status(1).is_locked());
+        assert!(!Status(0).is_locked());
+
+        let val = Feedback(0).set_ratio(40960);
+        assert_eq!(val.ratio(), 40960);
+        assert_eq!(Feedback(0).set_ratio(u32::MAX).ratio(), 0x7ffff);
+    }
+}
@@ -1,35 +1,129 @@
 //! Global configurations on BL602 and BL702 series.
 use super::{Drive, Pull};
+use crate::hbn::{RootClockSource1, RootClockSource2};
 use volatile_register::{RO, RW, WO};
 
 /// Global configuration registers.
 #[repr(C)]
 pub struct RegisterBlock {
-    _reserved0: [u8; 0x100],
+    /// Root clock selection, processor and bus clock dividers.
+    pub clock_config_0: RW<ClockConfig0>,
+    _reserved0: [u8; 0x4],
+    /// Universal Asynchronous Receiver/Transmitter clock divider.
+    pub clock_config_2: RW<ClockConfig2>,
+    /// Serial Peripheral Interface and Inter-Integrated Circuit clock dividers.
+    pub clock_config_3: RW<ClockConfig3>,
+    _reserved1: [u8; 0xf0],
     /// Generic Purpose Input/Output configuration register.
     pub gpio_config: [RW<GpioConfig>; 16],
-    _reserved1: [u8; 0x40],
+    _reserved2: [u8; 0x40],
     /// Read value from Generic Purpose Input/Output pads.
     pub gpio_input_value: RO<u32>,
-    _reserved2: [u8; 0x4],
+    _reserved3: [u8; 0x4],
     /// Write value to Generic Purpose Input/Output pads.
     pub gpio_output_value: RW<u32>,
-    _reserved3: [u8; 0x4],
+    _reserved4: [u8; 0x4],
     /// Enable output function of Generic Purpose Input/Output pads.
     pub gpio_output_enable: RW<u32>,
     /// Interrupt mask of Generic Purpose Input/Output pads.
     pub gpio_interrupt_mask: RW<u32>,
-    _reserved4: [u8; 0x10],
+    _reserved5: [u8; 0x10],
     /// Interrupt state of Generic Purpose Input/Output pads.
     pub gpio_interrupt_state: RO<u32>,
-    _reserved5: [u8; 0x4],
+    _reserved6: [u8; 0x4],
     /// Clear interrupt state of Generic Purpose Input/Output pads.
     pub gpio_interrupt_clear: WO<u32>,
-    _reserved6: [u8; 0xc],
+    _reserved7: [u8; 0xc],
     /// Generic Purpose Input/Output interrupt mode register.
     pub gpio_interrupt_mode: [RW<GpioInterruptMode>; 16],
 }
 
+/// Root clock selection, processor and bus clock divider register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct ClockConfig0(u32);
+
+impl ClockConfig0 {
+    const PLL_SELECT: u32 = 0x3 << 4;
+    const ROOT_CLOCK_1: u32 = 0x1 << 6;
+    const ROOT_CLOCK_2: u32 = 0x1 << 7;
+    const CPU_DIVIDE: u32 = 0xff << 8;
+    const BUS_DIVIDE: u32 = 0xff << 16;
+
+    /// Get phase-locked loop output used as root clock.
+    ///
+    /// Meaning of the value depends on chip; on BL702 it is 57.6, 96 and 144 MHz for
+    /// 0 to 2 respectively.
+    #[inline]
+    pub const fn pll_select(self) -> u8 {
+        ((self.0 & Self::PLL_SELECT) >> 4) as u8
+    }
+    /// Get root clock source 1, mirrored from Hibernation registers.
+    #[inline]
+    pub const fn root_clock_1(self) -> RootClockSource1 {
+        if self.0 & Self::ROOT_CLOCK_1 != 0 {
+            RootClockSource1::Xtal
+        } else {
+            RootClockSource1::RC32M
+        }
+    }
+    /// Get root clock source 2, mirrored from Hibernation registers.
+    #[inline]
+    pub const fn root_clock_2(self) -> RootClockSource2 {
+        if self.0 & Self::ROOT_CLOCK_2 != 0 {
+            RootClockSource2::Pllsel
+        } else {
+            RootClockSource2::Xclk
+        }
+    }
+    /// Get processor clock divide factor.
+    #[inline]
+    pub const fn cpu_divide(self) -> u8 {
+        ((self.0 & Self::CPU_DIVIDE) >> 8) as u8
+    }
+    /// Get bus clock divide factor.
+    #[inline]
+    pub const fn bus_divide(self) -> u8 {
+        ((self.0 & Self::BUS_DIVIDE) >> 16) as u8
+    }
+}
+
+/// Universal Asynchronous Receiver/Transmitter clock divider register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct ClockConfig2(u32);
+
+impl ClockConfig2 {
+    const UART_DIVIDE: u32 = 0x7;
+
+    /// Get Universal Asynchronous Receiver/Transmitter clock divide factor.
+    #[inline]
+    pub const fn uart_divide(self) -> u8 {
+        (self.0 & Self::UART_DIVIDE) as u8
+    }
+}
+
+/// Serial Peripheral Interface and Inter-Integrated Circuit clock divider register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct ClockConfig3(u32);
+
+impl ClockConfig3 {
+    const SPI_DIVIDE: u32 = 0x1f;
+    const I2C_DIVIDE: u32 = 0xff << 16;
+
+    /// Get Serial Peripheral Interface clock divide factor.
+    #[inline]
+    pub const fn spi_divide(self) -> u8 {
+        (self.0 & Self::SPI_DIVIDE) as u8
+    }
+    /// Get Inter-Integrated Circuit clock divide factor.
+    #[inline]
+    pub const fn i2c_divide(self) -> u8 {
+        ((self.0 & Self::I2C_DIVIDE) >> 16) as u8
+    }
+}
+
 /// Generic Purpose Input/Output Configuration register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 #[repr(transparent)]
@@ -211,3 +305,37 @@ pub enum InterruptMode {
     AsyncLowLevel = 6,
     AsyncHighLevel = 7,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ClockConfig0, ClockConfig2, ClockConfig3, RegisterBlock};
+    use crate::hbn::{RootClockSource1, RootClockSource2};
+    use memoffset::offset_of;
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, clock_config_0), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, clock_config_2), 0x08);
+        assert_eq!(offset_of!(RegisterBlock, clock_config_3), 0x0c);
+        assert_eq!(offset_of!(RegisterBlock, gpio_config), 0x100);
+        assert_eq!(offset_of!(RegisterBlock, gpio_interrupt_mode), 0x1c0);
+    }
+
+    #[test]
+    fn struct_clock_config_functions() {
+        let val = ClockConfig0(0x0001_01e0);
+        assert_eq!(val.pll_select(), 2);
+        assert_eq!(val.root_clock_1(), RootClockSource1::Xtal);
+        assert_eq!(val.root_clock_2(), RootClockSource2::Pllsel);
+        assert_eq!(val.cpu_divide(), 1);
+        assert_eq!(val.bus_divide(), 1);
+        let val = ClockConfig0(0x0000_0000);
+        assert_eq!(val.root_clock_1(), RootClockSource1::RC32M);
+        assert_eq!(val.root_clock_2(), RootClockSource2::Xclk);
+
+        assert_eq!(ClockConfig2(0x13).uart_divide(), 3);
+        let val = ClockConfig3(0x0103_0107);
+        assert_eq!(val.spi_divide(), 7);
+        assert_eq!(val.i2c_divide(), 3);
+    }
+}
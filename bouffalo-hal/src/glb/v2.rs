@@ -5,45 +5,62 @@ use volatile_register::{RO, RW, WO};
 /// Global configuration registers.
 #[repr(C)]
 pub struct RegisterBlock {
-    _reserved0: [u8; 0x150],
+    _reserved0: [u8; 0x90],
+    /// Processor and bus clock divider configuration.
+    pub mcu_clock_config: RW<McuClockConfig>,
+    /// Bus clock divider update handshake.
+    pub mcu_clock_status: RW<McuClockStatus>,
+    _reserved1: [u8; 0xb8],
     /// Universal Asynchronous Receiver/Transmitter clock and mode configurations.
     pub uart_config: RW<UartConfig>,
     /// Universal Asynchronous Receiver/Transmitter signal multiplexer.
     pub uart_mux_group: [RW<UartMuxGroup>; 2],
-    _reserved1: [u8; 0x24],
+    _reserved2: [u8; 0x24],
     /// Inter-Integrated Circuit configuration register.
     pub i2c_config: RW<I2cConfig>,
-    _reserved2: [u8; 0x2c],
+    _reserved3: [u8; 0x2c],
     /// Serial Peripheral Interface configuration register.
     pub spi_config: RW<SpiConfig>,
-    _reserved3: [u8; 0x1c],
+    _reserved4: [u8; 0x1c],
     /// Pulse Width Modulation configuration register.
     pub pwm_config: RW<PwmConfig>,
-    _reserved4: [u8; 0x25c],
+    _reserved5: [u8; 0x25c],
     /// SDH configuration register.
     pub sdh_config: RW<SdhConfig>,
-    _reserved5: [u8; 0xdd],
+    _reserved6: [u8; 0xdd],
     pub param_config: RW<ParamConfig>,
-    _reserved6: [u8; 0x70],
-    // TODO: clock_config_0, clock_config_2, clock_config_3 registers
+    _reserved7: [u8; 0x2c],
+    /// Peripheral software reset registers.
+    pub peripheral_reset: [RW<PeripheralReset>; 2],
+    /// Chip and processor software reset register.
+    pub software_reset: RW<SoftwareReset>,
+    _reserved8: [u8; 0x34],
+    // TODO: clock_config_0, clock_config_3 register fields
+    /// Clock generation configuration 0.
+    pub clock_config_0: RW<u32>,
     /// Clock generation configuration 1.
     pub clock_config_1: RW<ClockConfig1>,
-    _reserved7: [u8; 0x148],
+    /// Clock generation configuration 2.
+    pub clock_config_2: RW<ClockConfig2>,
+    _reserved9: [u8; 0x144],
     /// LDO12UHS config.
     pub ldo12uhs_config: RW<Ldo12uhsConfig>,
-    _reserved8: [u8; 0x1f0],
+    _reserved10: [u8; 0x1f0],
     /// Generic Purpose Input/Output config.
     pub gpio_config: [RW<GpioConfig>; 46],
-    _reserved9: [u8; 0x148],
+    _reserved11: [u8; 0x148],
     /// Read value from Generic Purpose Input/Output pads.
     pub gpio_input: [RO<u32>; 2],
-    _reserved10: [u8; 0x18],
+    _reserved12: [u8; 0x18],
     /// Write value to Generic Purpose Input/Output pads.
     pub gpio_output: [RW<u32>; 2],
     /// Set pin output value to high.
     pub gpio_set: [WO<u32>; 2],
     /// Clear pin output value to low.
     pub gpio_clear: [WO<u32>; 2],
+    _reserved13: [u8; 0x500],
+    /// Bus clock gate; pulsed to apply new bus clock divider.
+    pub bus_clock_gate: RW<u32>,
 }
 
 /// Universal Asynchronous Receiver/Transmitter clock and mode configuration.
@@ -342,7 +359,9 @@ pub struct ParamConfig(u32);
 
 impl ParamConfig {
     const SPI_0_MASTER_MODE: u32 = 0x1 << 12;
+    const SPI_0_SWAP: u32 = 0x1 << 13;
     const SPI_1_MASTER_MODE: u32 = 0x1 << 27;
+    const SPI_1_SWAP: u32 = 0x1 << 28;
 
     /// Set mode for Serial Peripheral Interface.
     #[inline]
@@ -381,6 +400,33 @@ impl ParamConfig {
             _ => unreachable!(),
         }
     }
+    /// Swap MOSI and MISO signals of Serial Peripheral Interface.
+    #[inline]
+    pub const fn enable_spi_swap<const I: usize>(self) -> Self {
+        match I {
+            0 => Self(self.0 | Self::SPI_0_SWAP),
+            1 => Self(self.0 | Self::SPI_1_SWAP),
+            _ => unreachable!(),
+        }
+    }
+    /// Restore MOSI and MISO signals of Serial Peripheral Interface.
+    #[inline]
+    pub const fn disable_spi_swap<const I: usize>(self) -> Self {
+        match I {
+            0 => Self(self.0 & !Self::SPI_0_SWAP),
+            1 => Self(self.0 & !Self::SPI_1_SWAP),
+            _ => unreachable!(),
+        }
+    }
+    /// Check if MOSI and MISO signals of Serial Peripheral Interface are swapped.
+    #[inline]
+    pub const fn is_spi_swap_enabled<const I: usize>(self) -> bool {
+        match I {
+            0 => self.0 & Self::SPI_0_SWAP != 0,
+            1 => self.0 & Self::SPI_1_SWAP != 0,
+            _ => unreachable!(),
+        }
+    }
 }
 
 /// SDH configuration register.
@@ -451,6 +497,22 @@ impl ClockConfig1 {
     const UART2: u32 = 0x1 << 26;
     const LZ4D: u32 = 0x1 << 29;
 
+    /// Enable clock gate at bit `idx`.
+    #[inline]
+    pub const fn enable_clock(self, idx: u32) -> Self {
+        Self(self.0 | (1 << idx))
+    }
+    /// Disable clock gate at bit `idx`.
+    #[inline]
+    pub const fn disable_clock(self, idx: u32) -> Self {
+        Self(self.0 & !(1 << idx))
+    }
+    /// Check if clock gate at bit `idx` is enabled.
+    #[inline]
+    pub const fn is_clock_enabled(self, idx: u32) -> bool {
+        self.0 & (1 << idx) != 0
+    }
+
     /// Enable clock gate for Universal Asynchronous Receiver/Transmitter peripheral.
     #[inline]
     pub const fn enable_uart<const I: usize>(self) -> Self {
@@ -531,6 +593,29 @@ impl ClockConfig1 {
     }
 }
 
+/// Clock generation configuration register 2.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct ClockConfig2(u32);
+
+impl ClockConfig2 {
+    /// Enable clock gate at bit `idx`.
+    #[inline]
+    pub const fn enable_clock(self, idx: u32) -> Self {
+        Self(self.0 | (1 << idx))
+    }
+    /// Disable clock gate at bit `idx`.
+    #[inline]
+    pub const fn disable_clock(self, idx: u32) -> Self {
+        Self(self.0 & !(1 << idx))
+    }
+    /// Check if clock gate at bit `idx` is enabled.
+    #[inline]
+    pub const fn is_clock_enabled(self, idx: u32) -> bool {
+        self.0 & (1 << idx) != 0
+    }
+}
+
 /// Generic Purpose Input/Output Configuration register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 #[repr(transparent)]
@@ -643,6 +728,11 @@ impl GpioConfig {
     pub const fn clear_interrupt(self) -> Self {
         Self(self.0 | Self::CLEAR_INTERRUPT)
     }
+    /// Release interrupt flag clear of current pin, so that new interrupts are latched again.
+    #[inline]
+    pub const fn release_interrupt_clear(self) -> Self {
+        Self(self.0 & !Self::CLEAR_INTERRUPT)
+    }
     /// Get drive strength of current pin.
     #[inline]
     pub const fn drive(self) -> Drive {
@@ -842,19 +932,369 @@ impl Ldo12uhsConfig {
     }
 }
 
+/// Chip and processor software reset register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct SoftwareReset(u32);
+
+impl SoftwareReset {
+    const POWER_ON_RESET: u32 = 0x1;
+    const CPU_RESET: u32 = 0x1 << 1;
+    const SYSTEM_RESET: u32 = 0x1 << 2;
+
+    /// Request power-on reset of digital domain.
+    #[inline]
+    pub const fn enable_power_on_reset(self) -> Self {
+        Self(self.0 | Self::POWER_ON_RESET)
+    }
+    /// Clear power-on reset request.
+    #[inline]
+    pub const fn disable_power_on_reset(self) -> Self {
+        Self(self.0 & !Self::POWER_ON_RESET)
+    }
+    /// Check if power-on reset is requested.
+    #[inline]
+    pub const fn is_power_on_reset_enabled(self) -> bool {
+        self.0 & Self::POWER_ON_RESET != 0
+    }
+    /// Request processor core reset.
+    #[inline]
+    pub const fn enable_cpu_reset(self) -> Self {
+        Self(self.0 | Self::CPU_RESET)
+    }
+    /// Clear processor core reset request.
+    #[inline]
+    pub const fn disable_cpu_reset(self) -> Self {
+        Self(self.0 & !Self::CPU_RESET)
+    }
+    /// Check if processor core reset is requested.
+    #[inline]
+    pub const fn is_cpu_reset_enabled(self) -> bool {
+        self.0 & Self::CPU_RESET != 0
+    }
+    /// Request system reset.
+    #[inline]
+    pub const fn enable_system_reset(self) -> Self {
+        Self(self.0 | Self::SYSTEM_RESET)
+    }
+    /// Clear system reset request.
+    #[inline]
+    pub const fn disable_system_reset(self) -> Self {
+        Self(self.0 & !Self::SYSTEM_RESET)
+    }
+    /// Check if system reset is requested.
+    #[inline]
+    pub const fn is_system_reset_enabled(self) -> bool {
+        self.0 & Self::SYSTEM_RESET != 0
+    }
+}
+
+/// Peripheral software reset register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct PeripheralReset(u32);
+
+impl PeripheralReset {
+    /// Hold peripheral at bit `idx` in reset.
+    #[inline]
+    pub const fn assert_reset(self, idx: u32) -> Self {
+        Self(self.0 | (1 << idx))
+    }
+    /// Release peripheral at bit `idx` from reset.
+    #[inline]
+    pub const fn deassert_reset(self, idx: u32) -> Self {
+        Self(self.0 & !(1 << idx))
+    }
+    /// Check if peripheral at bit `idx` is held in reset.
+    #[inline]
+    pub const fn is_reset_asserted(self, idx: u32) -> bool {
+        self.0 & (1 << idx) != 0
+    }
+}
+
+/// Peripheral with software reset and clock gate in global configuration.
+///
+/// Value is the register index times 32 plus the bit index. Multimedia domain
+/// peripherals on BL808, e.g. SPI 1, are reset and gated by multimedia global
+/// configuration instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Peripheral {
+    /// Ethernet Media Access Control.
+    Emac = 19,
+    /// Universal Serial Bus controller.
+    Usb = 21,
+    /// Secure Digital Host controller.
+    Sdh = 22,
+    /// Pseudo Static Random Access Memory controller.
+    Psram = 24,
+    /// General Purpose ADC and DAC.
+    Gpip = 34,
+    /// Security engine.
+    SecEng = 36,
+    /// Direct Memory Access controller 0.
+    Dma = 44,
+    /// Universal Asynchronous Receiver/Transmitter 0.
+    Uart0 = 48,
+    /// Universal Asynchronous Receiver/Transmitter 1.
+    Uart1 = 49,
+    /// Serial Peripheral Interface 0.
+    Spi = 50,
+    /// Inter-Integrated Circuit bus 0.
+    I2c0 = 51,
+    /// Pulse Width Modulation.
+    Pwm = 52,
+    /// Timer and watchdog.
+    Timer = 53,
+    /// Infrared remote.
+    IrRemote = 54,
+    /// Checksum engine.
+    Checksum = 55,
+    /// Inter-Integrated Circuit bus 1.
+    I2c1 = 57,
+    /// Universal Asynchronous Receiver/Transmitter 2.
+    Uart2 = 58,
+    /// Inter-IC Sound.
+    I2s = 59,
+    /// LZ4 decompressor.
+    Lz4d = 61,
+}
+
+/// Phase-locked loop output for processor root clock.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum McuPllSource {
+    /// Processor PLL, 400 MHz by default.
+    CpuPll = 0,
+    /// Audio PLL.
+    AudioPll = 1,
+    /// 240-MHz multiplexer PLL.
+    MuxPll240M = 2,
+    /// 320-MHz multiplexer PLL.
+    MuxPll320M = 3,
+}
+
+/// Processor and bus clock divider configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct McuClockConfig(u32);
+
+impl McuClockConfig {
+    const PLL_SELECT: u32 = 0x3 << 6;
+    const CPU_DIVIDE: u32 = 0xff << 8;
+    const BUS_DIVIDE: u32 = 0xff << 16;
+
+    /// Set phase-locked loop output used as processor root clock.
+    #[inline]
+    pub const fn set_pll_select(self, val: McuPllSource) -> Self {
+        Self((self.0 & !Self::PLL_SELECT) | ((val as u32) << 6))
+    }
+    /// Get phase-locked loop output used as processor root clock.
+    #[inline]
+    pub const fn pll_select(self) -> McuPllSource {
+        match (self.0 & Self::PLL_SELECT) >> 6 {
+            0 => McuPllSource::CpuPll,
+            1 => McuPllSource::AudioPll,
+            2 => McuPllSource::MuxPll240M,
+            3 => McuPllSource::MuxPll320M,
+            _ => unreachable!(),
+        }
+    }
+    /// Set processor clock divide factor, dividing root clock by `val + 1`.
+    #[inline]
+    pub const fn set_cpu_divide(self, val: u8) -> Self {
+        Self((self.0 & !Self::CPU_DIVIDE) | ((val as u32) << 8))
+    }
+    /// Get processor clock divide factor.
+    #[inline]
+    pub const fn cpu_divide(self) -> u8 {
+        ((self.0 & Self::CPU_DIVIDE) >> 8) as u8
+    }
+    /// Set bus clock divide factor, dividing processor clock by `val + 1`.
+    #[inline]
+    pub const fn set_bus_divide(self, val: u8) -> Self {
+        Self((self.0 & !Self::BUS_DIVIDE) | ((val as u32) << 16))
+    }
+    /// Get bus clock divide factor.
+    #[inline]
+    pub const fn bus_divide(self) -> u8 {
+        ((self.0 & Self::BUS_DIVIDE) >> 16) as u8
+    }
+}
+
+/// Bus clock divider update handshake register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct McuClockStatus(u32);
+
+impl McuClockStatus {
+    const BUS_DIVIDE_DONE: u32 = 1 << 1;
+
+    /// Check if new bus clock divider has taken effect.
+    #[inline]
+    pub const fn is_bus_divide_done(self) -> bool {
+        self.0 & Self::BUS_DIVIDE_DONE != 0
+    }
+}
+
+/// Number of register reads a peripheral is held in reset.
+const PERIPHERAL_RESET_CYCLES: usize = 32;
+
+/// Reset a single peripheral by software, e.g. to recover from a stuck state machine.
+///
+/// The reset bit is asserted, held for a few bus cycles and released, so the
+/// peripheral returns to its register reset values. Its driver should be configured
+/// again afterwards; pad and clock settings in global configuration are kept.
+#[inline]
+pub fn reset_peripheral(glb: &RegisterBlock, peripheral: Peripheral) {
+    let (reg, idx) = (peripheral as usize / 32, peripheral as u32 % 32);
+    let reg = &glb.peripheral_reset[reg];
+    unsafe { reg.modify(|v| v.deassert_reset(idx).assert_reset(idx)) };
+    // Reading back makes sure the write has reached the peripheral bus.
+    for _ in 0..PERIPHERAL_RESET_CYCLES {
+        core::hint::black_box(reg.read());
+    }
+    unsafe { reg.modify(|v| v.deassert_reset(idx)) };
+}
+
+/// Enable clock of a single peripheral.
+///
+/// Clock gates mirror software reset bits: register 0 peripherals are gated by
+/// `clock_config_2`, and register 1 peripherals by `clock_config_1`.
+#[inline]
+pub fn enable_peripheral_clock(glb: &RegisterBlock, peripheral: Peripheral) {
+    let idx = peripheral as u32 % 32;
+    unsafe {
+        match peripheral as usize / 32 {
+            0 => glb.clock_config_2.modify(|v| v.enable_clock(idx)),
+            _ => glb.clock_config_1.modify(|v| v.enable_clock(idx)),
+        }
+    }
+}
+
+/// Disable clock of a single peripheral to save power.
+///
+/// Registers of a gated peripheral can't be accessed; enable its clock again before
+/// creating its driver.
+#[inline]
+pub fn disable_peripheral_clock(glb: &RegisterBlock, peripheral: Peripheral) {
+    let idx = peripheral as u32 % 32;
+    unsafe {
+        match peripheral as usize / 32 {
+            0 => glb.clock_config_2.modify(|v| v.disable_clock(idx)),
+            _ => glb.clock_config_1.modify(|v| v.disable_clock(idx)),
+        }
+    }
+}
+
+/// Check if clock of a single peripheral is enabled.
+#[inline]
+pub fn is_peripheral_clock_enabled(glb: &RegisterBlock, peripheral: Peripheral) -> bool {
+    let idx = peripheral as u32 % 32;
+    match peripheral as usize / 32 {
+        0 => glb.clock_config_2.read().is_clock_enabled(idx),
+        _ => glb.clock_config_1.read().is_clock_enabled(idx),
+    }
+}
+
+/// Peripheral clock enabled while this guard is alive.
+///
+/// With `clock-gate-on-drop` feature, dropping the guard gates the clock again, so
+/// a peripheral no longer in use stops drawing power; without it, the clock is left
+/// enabled and the guard only records that the peripheral is in use.
+pub struct ClockGuard<'a> {
+    glb: &'a RegisterBlock,
+    peripheral: Peripheral,
+}
+
+impl<'a> ClockGuard<'a> {
+    /// Enable clock of `peripheral` until the returned guard is dropped.
+    #[inline]
+    pub fn new(glb: &'a RegisterBlock, peripheral: Peripheral) -> Self {
+        enable_peripheral_clock(glb, peripheral);
+        ClockGuard { glb, peripheral }
+    }
+    /// Get peripheral whose clock is held by this guard.
+    #[inline]
+    pub const fn peripheral(&self) -> Peripheral {
+        self.peripheral
+    }
+    /// Check if clock of the peripheral is still enabled.
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        is_peripheral_clock_enabled(self.glb, self.peripheral)
+    }
+}
+
+#[cfg(feature = "clock-gate-on-drop")]
+impl Drop for ClockGuard<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        disable_peripheral_clock(self.glb, self.peripheral);
+    }
+}
+
+/// Scope of software reset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ResetScope {
+    /// Reset processor cores only.
+    ///
+    /// Peripherals keep their configuration, including PSRAM controller; PSRAM content
+    /// is retained, and firmware restarts from boot ROM with peripherals left configured.
+    Cpu,
+    /// Reset processor cores and peripherals under global configuration.
+    ///
+    /// Always-on and hibernate domains are kept. PSRAM controller is reset, thus PSRAM
+    /// should be initialized again and its content should be considered lost.
+    System,
+    /// Reset whole digital domain as on power on, except always-on domain.
+    ///
+    /// PSRAM content is lost; this is the closest to a power cycle.
+    PowerOn,
+}
+
+/// Reset the chip or its processor cores by software.
+///
+/// Uses `SWRST_CFG2` register of global configuration. Reset takes effect immediately;
+/// pending output, e.g. in UART transmit FIFO, should be flushed before calling.
+#[inline]
+pub fn software_reset(glb: &RegisterBlock, scope: ResetScope) -> ! {
+    unsafe {
+        glb.software_reset.modify(|v| {
+            v.disable_cpu_reset()
+                .disable_system_reset()
+                .disable_power_on_reset()
+        });
+        glb.software_reset.modify(|v| match scope {
+            ResetScope::Cpu => v.enable_cpu_reset(),
+            ResetScope::System => v.enable_system_reset(),
+            ResetScope::PowerOn => v
+                .enable_cpu_reset()
+                .enable_system_reset()
+                .enable_power_on_reset(),
+        });
+    }
+    loop {
+        core::hint::spin_loop();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::glb::v2::SpiClockSource;
 
     use super::{
-        Drive, Function, GpioConfig, I2cClockSource, I2cConfig, InterruptMode, Mode, Pull,
-        PwmConfig, PwmSignal0, PwmSignal1, RegisterBlock, SdhConfig, SpiConfig, UartConfig,
-        UartMuxGroup, UartSignal,
+        ClockConfig1, ClockConfig2, Drive, Function, GpioConfig, I2cClockSource, I2cConfig,
+        InterruptMode, McuClockConfig, McuClockStatus, McuPllSource, Mode, ParamConfig, Peripheral,
+        PeripheralReset, Pull, PwmConfig, PwmSignal0, PwmSignal1, RegisterBlock, SdhConfig,
+        SoftwareReset, SpiConfig, UartConfig, UartMuxGroup, UartSignal,
     };
     use memoffset::offset_of;
 
     #[test]
     fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, mcu_clock_config), 0x090);
+        assert_eq!(offset_of!(RegisterBlock, mcu_clock_status), 0x094);
         assert_eq!(offset_of!(RegisterBlock, uart_config), 0x150);
         assert_eq!(offset_of!(RegisterBlock, uart_mux_group), 0x154);
         assert_eq!(offset_of!(RegisterBlock, i2c_config), 0x180);
@@ -862,13 +1302,18 @@ mod tests {
         assert_eq!(offset_of!(RegisterBlock, pwm_config), 0x1d0);
         assert_eq!(offset_of!(RegisterBlock, sdh_config), 0x430);
         assert_eq!(offset_of!(RegisterBlock, param_config), 0x510);
+        assert_eq!(offset_of!(RegisterBlock, peripheral_reset), 0x540);
+        assert_eq!(offset_of!(RegisterBlock, software_reset), 0x548);
+        assert_eq!(offset_of!(RegisterBlock, clock_config_0), 0x580);
         assert_eq!(offset_of!(RegisterBlock, clock_config_1), 0x584);
+        assert_eq!(offset_of!(RegisterBlock, clock_config_2), 0x588);
         assert_eq!(offset_of!(RegisterBlock, ldo12uhs_config), 0x6d0);
         assert_eq!(offset_of!(RegisterBlock, gpio_config), 0x8c4);
         assert_eq!(offset_of!(RegisterBlock, gpio_input), 0xac4);
         assert_eq!(offset_of!(RegisterBlock, gpio_output), 0xae4);
         assert_eq!(offset_of!(RegisterBlock, gpio_set), 0xaec);
         assert_eq!(offset_of!(RegisterBlock, gpio_clear), 0xaf4);
+        assert_eq!(offset_of!(RegisterBlock, bus_clock_gate), 0xffc);
     }
 
     #[test]
@@ -916,6 +1361,10 @@ mod tests {
         assert_eq!(GpioConfig(0x0).clear(), GpioConfig(0x04000000));
 
         assert_eq!(GpioConfig(0x0).clear_interrupt(), GpioConfig(0x00100000));
+        assert_eq!(
+            GpioConfig(0x00100000).release_interrupt_clear(),
+            GpioConfig(0x0)
+        );
 
         let mut val = GpioConfig(0x0);
         val = val.set_drive(Drive::Drive0);
@@ -1122,6 +1571,94 @@ mod tests {
         assert_eq!(config.signal_1(), PwmSignal1::BrushlessDcMotor);
     }
 
+    #[test]
+    fn struct_param_config_functions() {
+        let mut val = ParamConfig(0x0);
+
+        val = val.enable_spi_swap::<0>();
+        assert_eq!(val.0, 0x0000_2000);
+        assert!(val.is_spi_swap_enabled::<0>());
+        assert!(!val.is_spi_swap_enabled::<1>());
+        val = val.enable_spi_swap::<1>();
+        assert_eq!(val.0, 0x1000_2000);
+        val = val.disable_spi_swap::<0>();
+        assert_eq!(val.0, 0x1000_0000);
+        val = val.disable_spi_swap::<1>();
+        assert_eq!(val.0, 0x0000_0000);
+    }
+
+    #[test]
+    fn struct_peripheral_reset_functions() {
+        let mut val = PeripheralReset(0x0);
+        val = val.assert_reset(16);
+        assert!(val.is_reset_asserted(16));
+        assert!(!val.is_reset_asserted(17));
+        assert_eq!(val.0, 0x0001_0000);
+        val = val.assert_reset(19);
+        assert_eq!(val.0, 0x0009_0000);
+        val = val.deassert_reset(16);
+        assert!(!val.is_reset_asserted(16));
+        assert_eq!(val.0, 0x0008_0000);
+    }
+
+    #[test]
+    fn struct_software_reset_functions() {
+        let mut val = SoftwareReset(0x0);
+        val = val.enable_power_on_reset();
+        assert!(val.is_power_on_reset_enabled());
+        assert_eq!(val.0, 0x0000_0001);
+        val = val.enable_cpu_reset();
+        assert!(val.is_cpu_reset_enabled());
+        assert_eq!(val.0, 0x0000_0003);
+        val = val.enable_system_reset();
+        assert!(val.is_system_reset_enabled());
+        assert_eq!(val.0, 0x0000_0007);
+        val = val.disable_power_on_reset().disable_cpu_reset();
+        assert!(!val.is_power_on_reset_enabled());
+        assert!(!val.is_cpu_reset_enabled());
+        assert_eq!(val.0, 0x0000_0004);
+        val = val.disable_system_reset();
+        assert_eq!(val.0, 0x0000_0000);
+    }
+
+    #[test]
+    fn struct_mcu_clock_config_functions() {
+        let mut val = McuClockConfig(0x0);
+        val = val.set_cpu_divide(0x3);
+        assert_eq!(val.cpu_divide(), 0x3);
+        assert_eq!(val.0, 0x0000_0300);
+        val = val.set_bus_divide(0xff);
+        assert_eq!(val.bus_divide(), 0xff);
+        assert_eq!(val.0, 0x00ff_0300);
+        val = val.set_cpu_divide(0).set_bus_divide(0);
+        assert_eq!(val.0, 0x0000_0000);
+        val = val.set_pll_select(McuPllSource::MuxPll320M);
+        assert_eq!(val.pll_select(), McuPllSource::MuxPll320M);
+        assert_eq!(val.0, 0x0000_00c0);
+        val = val.set_pll_select(McuPllSource::CpuPll);
+        assert_eq!(val.pll_select(), McuPllSource::CpuPll);
+        assert_eq!(val.0, 0x0000_0000);
+
+        assert!(McuClockStatus(0x2).is_bus_divide_done());
+        assert!(!McuClockStatus(0x1).is_bus_divide_done());
+    }
+
+    #[test]
+    fn struct_clock_config_functions() {
+        let val = ClockConfig1(0).enable_clock(Peripheral::Uart0 as u32 % 32);
+        assert_eq!(val, ClockConfig1(0).enable_uart::<0>());
+        assert!(val.is_clock_enabled(16));
+        let val = val.enable_clock(Peripheral::Lz4d as u32 % 32);
+        assert!(val.is_lz4d_enabled());
+        assert_eq!(val.0, 0x2001_0000);
+        assert_eq!(val.disable_clock(16).disable_clock(29).0, 0);
+
+        let val = ClockConfig2(0).enable_clock(Peripheral::Emac as u32);
+        assert_eq!(val.0, 0x0008_0000);
+        assert!(val.is_clock_enabled(19));
+        assert!(!val.disable_clock(19).is_clock_enabled(19));
+    }
+
     #[test]
     fn struct_sdh_config_functions() {
         let mut val = SdhConfig(0x0);
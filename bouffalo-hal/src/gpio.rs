@@ -87,7 +87,8 @@
 //! # fn main() {
 //! # let glb: &bouffalo_hal::glb::RegisterBlock = unsafe { &*core::ptr::null() };
 //! # let p: Peripherals = Peripherals { gpio: Pads::__pads_from_glb(glb), glb: (), uart0: UART0 };
-//! # let clocks = Clocks { xtal: Hertz(40_000_000) };
+//! # let clocks = Clocks { xtal: Hertz(40_000_000), cpu: Hertz(320_000_000), bus: Hertz(80_000_000),
+//! #     uart: Hertz(80_000_000), spi: Hertz(160_000_000), i2c: Hertz(80_000_000) };
 //! // Prepare UART transmit and receive pads by converting io14 and io15 into
 //! // UART signal alternate mode.
 //! # #[cfg(feature = "glb-v2")]
@@ -117,6 +118,7 @@
 //! ```
 
 mod alternate;
+mod asynch;
 mod convert;
 mod disabled;
 mod gpio_group;
@@ -125,11 +127,16 @@ mod output;
 mod pad_dummy;
 mod pad_v1;
 mod pad_v2;
+mod parallel;
 mod typestate;
+mod wakeup;
 
+pub use asynch::{GpioState, InterruptPin};
 pub use convert::{IntoPad, IntoPadv2};
 pub use gpio_group::Pads;
+pub use parallel::{InputPins, OutputPins, ParallelPort, PortPins};
 pub use typestate::*;
+pub use wakeup::WakeupSource;
 pub use {alternate::Alternate, disabled::Disabled, input::Input, output::Output};
 pub use {pad_v1::Padv1, pad_v2::Padv2};
 
@@ -142,3 +149,55 @@ cfg_if::cfg_if! {
         pub(crate) use pad_dummy::PadDummy as Inner;
     }
 }
+
+/// Configure multiple GPIO pads from a table of pad conversions.
+///
+/// Each entry names a pad of given `Pads` structure, the `into_` conversion to apply
+/// and, optionally, internal pull and drive strength settings. Every entry expands to a
+/// `let` binding of the strongly typed pad, so peripherals still check pad modes at
+/// compile time, and a pad listed twice fails to compile as a moved value.
+///
+/// `pull` is available on alternate function pads, as pull of GPIO input and output pads
+/// is part of their type; `drive` is available on alternate function and output pads.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use bouffalo_hal::{configure_pins, glb::{Drive, Pull}, gpio::{Pads, IntoPad, IntoPadv2}};
+/// # pub struct Peripherals { gpio: Pads<'static> }
+/// # fn main() {
+/// # let glb: &bouffalo_hal::glb::RegisterBlock = unsafe { &*(0x20000000 as *const _) };
+/// # let p: Peripherals = Peripherals { gpio: Pads::__pads_from_glb(glb) };
+/// # #[cfg(feature = "glb-v2")]
+/// configure_pins!(p.gpio, {
+///     let sdh_clk = io0.into_sdh() with { drive: Drive::Drive2 };
+///     let sdh_cmd = io1.into_sdh();
+///     let sdh_d0 = io2.into_sdh() with { pull: Pull::Up, drive: Drive::Drive1 };
+///     let mut led = io8.into_floating_output();
+/// });
+/// # }
+/// ```
+#[macro_export]
+macro_rules! configure_pins {
+    ($pads:expr, {
+        $(
+            let $binding:pat_param = $pad:ident . $convert:ident $(::<$($generic:tt),+>)? ()
+            $(with { $($setting:ident : $value:expr),+ $(,)? })?;
+        )+
+    }) => {
+        $(
+            let $binding = {
+                #[allow(unused_mut)]
+                let mut pad = $pads.$pad.$convert$(::<$($generic),+>)?();
+                $($($crate::configure_pins!(@setting pad, $setting, $value);)+)?
+                pad
+            };
+        )+
+    };
+    (@setting $pad:ident, pull, $value:expr) => {
+        $pad.set_pull($value)
+    };
+    (@setting $pad:ident, drive, $value:expr) => {
+        $pad.set_drive($value)
+    };
+}
@@ -6,12 +6,28 @@ use super::{
 };
 #[cfg(any(doc, feature = "glb-v2"))]
 use super::{convert::IntoPadv2, typestate};
+#[cfg(any(doc, feature = "glb-v2"))]
+use crate::glb::{Drive, Pull};
 
 /// GPIO pad with alternate mode.
 pub struct Alternate<'a, const N: usize, M> {
     inner: super::Inner<'a, N, M>,
 }
 
+#[cfg(any(doc, feature = "glb-v2"))]
+impl<'a, const N: usize, M> Alternate<'a, N, M> {
+    /// Set internal pull of this pad.
+    #[inline]
+    pub fn set_pull(&mut self, val: Pull) {
+        self.inner.set_alternate_pull(val)
+    }
+    /// Set drive strength of this pad.
+    #[inline]
+    pub fn set_drive(&mut self, val: Drive) {
+        self.inner.set_alternate_drive(val)
+    }
+}
+
 impl<'a, const N: usize, M> IntoPad<'a, N> for Alternate<'a, N, M> {
     #[inline]
     fn into_pull_up_output(self) -> Output<'a, N, PullUp> {
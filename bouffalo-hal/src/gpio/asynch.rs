@@ -0,0 +1,199 @@
+use super::input::Input;
+use core::{
+    convert::Infallible,
+    future::poll_fn,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::Poll,
+};
+use embedded_hal::digital::{ErrorType, InputPin};
+
+/// Number of pads with an interrupt waker.
+#[cfg(feature = "glb-v1")]
+const PAD_COUNT: usize = 32;
+#[cfg(feature = "glb-v2")]
+const PAD_COUNT: usize = 46;
+#[cfg(not(any(feature = "glb-v1", feature = "glb-v2")))]
+const PAD_COUNT: usize = 0;
+
+/// Pad condition to wait for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Trigger {
+    High,
+    Low,
+    RisingEdge,
+    FallingEdge,
+    AnyEdge,
+}
+
+/// GPIO input pad waiting for level or edge interrupts in async/await context.
+///
+/// Pads of one chip share a GPIO interrupt line; the interrupt handler calling
+/// [`GpioState::on_interrupt`] wakes every pad whose condition was met, so simultaneous
+/// edges on different pads are all delivered. A wait completes only once the interrupt
+/// flag of its own pad is set; wakes for other reasons poll again and keep waiting.
+///
+/// Edges are detected without debouncing. For mechanical switches, enable the schmitt
+/// trigger before conversion and check the level again after a delay once woken.
+///
+/// # Examples
+///
+/// ```ignore
+/// static GPIO_STATE: GpioState = GpioState::new();
+///
+/// #[interrupt]
+/// fn gpio() {
+///     GPIO_STATE.on_interrupt();
+/// }
+///
+/// let mut button = p.gpio.io22.into_pull_up_input().into_interrupt_pin(&GPIO_STATE);
+/// button.wait_for_falling_edge().await.ok();
+/// ```
+pub struct InterruptPin<'a, const N: usize, M> {
+    pin: Input<'a, N, M>,
+    state: &'static GpioState,
+}
+
+impl<'a, const N: usize, M> Input<'a, N, M> {
+    /// Convert this pad into an interrupt pad using the waker registry `state`.
+    ///
+    /// Interrupt of the pad is masked until a wait starts.
+    #[inline]
+    pub fn into_interrupt_pin(mut self, state: &'static GpioState) -> InterruptPin<'a, N, M> {
+        critical_section::with(|_| self.mask_interrupt());
+        state.ref_to_glb.store(
+            self.register_block() as *const _ as usize,
+            Ordering::Release,
+        );
+        InterruptPin { pin: self, state }
+    }
+}
+
+impl<'a, const N: usize, M> InterruptPin<'a, N, M> {
+    /// Wait until the pad is high; completes at once if it already is.
+    #[inline]
+    pub async fn wait_for_high(&mut self) -> Result<(), Infallible> {
+        self.wait_for(Trigger::High).await
+    }
+    /// Wait until the pad is low; completes at once if it already is.
+    #[inline]
+    pub async fn wait_for_low(&mut self) -> Result<(), Infallible> {
+        self.wait_for(Trigger::Low).await
+    }
+    /// Wait for a transition from low to high.
+    #[inline]
+    pub async fn wait_for_rising_edge(&mut self) -> Result<(), Infallible> {
+        self.wait_for(Trigger::RisingEdge).await
+    }
+    /// Wait for a transition from high to low.
+    #[inline]
+    pub async fn wait_for_falling_edge(&mut self) -> Result<(), Infallible> {
+        self.wait_for(Trigger::FallingEdge).await
+    }
+    /// Wait for any transition.
+    ///
+    /// BL602 and BL702 have no both edges interrupt mode; there the wait is for the edge
+    /// leaving the level sampled when it starts.
+    #[inline]
+    pub async fn wait_for_any_edge(&mut self) -> Result<(), Infallible> {
+        self.wait_for(Trigger::AnyEdge).await
+    }
+    /// Release the interrupt pad and return the input pad, with interrupt masked.
+    #[inline]
+    pub fn free(mut self) -> Input<'a, N, M> {
+        critical_section::with(|_| self.pin.mask_interrupt());
+        self.pin
+    }
+
+    #[inline]
+    async fn wait_for(&mut self, trigger: Trigger) -> Result<(), Infallible> {
+        self.pin.listen(trigger);
+        poll_fn(|cx| {
+            // Register before checking, so an interrupt in between still wakes us.
+            self.state.wakers[N].register(cx.waker());
+            if self.pin.has_interrupt() {
+                // Interrupt handler has masked the pad already.
+                self.pin.clear_interrupt();
+                Poll::Ready(Ok(()))
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+
+impl<'a, const N: usize, M> ErrorType for InterruptPin<'a, N, M> {
+    type Error = Infallible;
+}
+
+impl<'a, const N: usize, M> InputPin for InterruptPin<'a, N, M> {
+    #[inline]
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        self.pin.is_high()
+    }
+    #[inline]
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        self.pin.is_low()
+    }
+}
+
+/// Set of per-pad wakers as the state for GPIO interrupts.
+#[derive(Debug)]
+pub struct GpioState {
+    wakers: [atomic_waker::AtomicWaker; PAD_COUNT],
+    ref_to_glb: AtomicUsize,
+}
+
+impl GpioState {
+    /// Creates the set of wakers for all GPIO pads.
+    #[inline]
+    pub const fn new() -> GpioState {
+        GpioState {
+            wakers: [const { atomic_waker::AtomicWaker::new() }; PAD_COUNT],
+            ref_to_glb: AtomicUsize::new(0),
+        }
+    }
+    /// Use this waker set to handle GPIO interrupt.
+    ///
+    /// Masks every pad with a pending interrupt and wakes its waiting task; the task
+    /// clears the flag. Masking here keeps a level interrupt from firing again before
+    /// the task runs.
+    #[inline]
+    pub fn on_interrupt(&self) {
+        let glb = self.ref_to_glb.load(Ordering::Acquire) as *const crate::glb::RegisterBlock;
+        if glb.is_null() {
+            return;
+        }
+        let glb = unsafe { &*glb };
+        #[cfg(feature = "glb-v1")]
+        {
+            let mask = glb.gpio_interrupt_mask.read();
+            let pending = glb.gpio_interrupt_state.read() & !mask;
+            if pending != 0 {
+                unsafe { glb.gpio_interrupt_mask.write(mask | pending) };
+            }
+            for (n, waker) in self.wakers.iter().enumerate() {
+                if pending & (1 << n) != 0 {
+                    waker.wake();
+                }
+            }
+        }
+        #[cfg(feature = "glb-v2")]
+        for (config, waker) in glb.gpio_config.iter().zip(&self.wakers) {
+            let val = config.read();
+            if val.has_interrupt() && !val.is_interrupt_masked() {
+                unsafe { config.write(val.mask_interrupt()) };
+                waker.wake();
+            }
+        }
+        #[cfg(not(any(feature = "glb-v1", feature = "glb-v2")))]
+        let _ = glb;
+    }
+}
+
+impl Default for GpioState {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,6 +1,7 @@
 #[cfg(any(doc, feature = "glb-v2"))]
 use super::{alternate::Alternate, convert::IntoPadv2};
 use super::{
+    asynch::Trigger,
     convert::IntoPad,
     output::Output,
     typestate::{self, Floating, PullDown, PullUp},
@@ -43,6 +44,21 @@ impl<'a, const N: usize, M> Input<'a, N, M> {
     pub fn unmask_interrupt(&mut self) {
         self.inner.unmask_interrupt();
     }
+    /// Read input levels of the whole bank this pad belongs to.
+    #[inline]
+    pub(crate) fn read_bank(&self) -> u32 {
+        self.inner.read_bank()
+    }
+    /// Unmask interrupt on `trigger` with its flag cleared.
+    #[inline]
+    pub(crate) fn listen(&mut self, trigger: Trigger) {
+        self.inner.listen(trigger)
+    }
+    /// Global configuration registers this pad belongs to.
+    #[inline]
+    pub(crate) fn register_block(&self) -> &'a crate::glb::RegisterBlock {
+        self.inner.register_block()
+    }
 }
 
 impl<'a, const N: usize, M> IntoPad<'a, N> for Input<'a, N, M> {
@@ -6,9 +6,15 @@ use super::{
     typestate::{self, Floating, PullDown, PullUp},
 };
 use crate::glb::Drive;
-use embedded_hal::digital::{ErrorType, OutputPin};
+use embedded_hal::digital::{ErrorType, OutputPin, StatefulOutputPin};
 
 /// GPIO pad in output mode.
+///
+/// Output level changes through `set_high`, `set_low` and `toggle` are atomic with respect
+/// to other pads: they only affect this pad even if code in interrupt context operates
+/// pads on the same bank at the same time. On BL808 and BL616 dedicated set and clear
+/// registers are used; on BL602 and BL702 the output register is modified inside a
+/// critical section, which requires a `critical-section` implementation to be linked.
 pub struct Output<'a, const N: usize, M> {
     inner: super::Inner<'a, N, typestate::Output<M>>,
 }
@@ -24,6 +30,11 @@ impl<'a, const N: usize, M> Output<'a, N, M> {
     pub fn set_drive(&mut self, val: Drive) {
         self.inner.set_drive(val)
     }
+    /// Write `value` into output bits under `mask` of the bank this pad belongs to.
+    #[inline]
+    pub(crate) fn write_bank(&mut self, mask: u32, value: u32) {
+        self.inner.write_bank(mask, value)
+    }
 }
 
 impl<'a, const N: usize, M> IntoPad<'a, N> for Output<'a, N, M> {
@@ -116,6 +127,21 @@ impl<'a, const N: usize, M> OutputPin for Output<'a, N, M> {
     }
 }
 
+impl<'a, const N: usize, M> StatefulOutputPin for Output<'a, N, M> {
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        self.inner.is_set_high()
+    }
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        self.inner.is_set_low()
+    }
+    #[inline]
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        self.inner.toggle()
+    }
+}
+
 // This part of implementation using `embedded_hal_027` is designed for backward compatibility of
 // ecosystem crates, as some of them depends on embedded-hal v0.2.7 traits.
 // We encourage ecosystem developers to use embedded-hal v1.0.0 traits; after that, this part of code
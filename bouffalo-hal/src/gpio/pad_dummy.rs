@@ -2,7 +2,7 @@
 use super::typestate::{Floating, Input, Output, PullDown, PullUp};
 use crate::glb::Drive;
 use core::marker::PhantomData;
-use embedded_hal::digital::{ErrorType, InputPin, OutputPin};
+use embedded_hal::digital::{ErrorType, InputPin, OutputPin, StatefulOutputPin};
 
 pub struct PadDummy<'a, const N: usize, M> {
     _unused: PhantomData<(&'a (), M)>,
@@ -103,6 +103,42 @@ impl<'a, const N: usize, M> OutputPin for PadDummy<'a, N, Output<M>> {
     }
 }
 
+impl<'a, const N: usize, M> StatefulOutputPin for PadDummy<'a, N, Output<M>> {
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        unimplemented!()
+    }
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        unimplemented!()
+    }
+}
+
+impl<'a, const N: usize, M> PadDummy<'a, N, Input<M>> {
+    #[inline]
+    pub(crate) fn read_bank(&self) -> u32 {
+        unimplemented!()
+    }
+}
+
+impl<'a, const N: usize, M> PadDummy<'a, N, Input<M>> {
+    #[inline]
+    pub(crate) fn listen(&mut self, _: super::asynch::Trigger) {
+        unimplemented!()
+    }
+    #[inline]
+    pub(crate) fn register_block(&self) -> &'a crate::glb::RegisterBlock {
+        unimplemented!()
+    }
+}
+
+impl<'a, const N: usize, M> PadDummy<'a, N, Output<M>> {
+    #[inline]
+    pub(crate) fn write_bank(&mut self, _: u32, _: u32) {
+        unimplemented!()
+    }
+}
+
 // Macro internal functions, do not use.
 impl<'a, const N: usize> PadDummy<'a, N, super::typestate::Disabled> {
     #[doc(hidden)]
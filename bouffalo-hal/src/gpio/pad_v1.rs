@@ -1,7 +1,7 @@
 use super::typestate::{Floating, Input, Output, PullDown, PullUp};
 use crate::glb::{v1, Drive, Pull};
 use core::marker::PhantomData;
-use embedded_hal::digital::{ErrorType, InputPin, OutputPin};
+use embedded_hal::digital::{ErrorType, InputPin, OutputPin, StatefulOutputPin};
 
 /// Raw GPIO pad of BL602 and BL702.
 pub struct Padv1<'a, const N: usize, M> {
@@ -27,7 +27,11 @@ impl<'a, const N: usize, M> Padv1<'a, N, Input<M>> {
     /// Clear interrupt flag.
     #[inline]
     pub fn clear_interrupt(&mut self) {
-        unsafe { self.base.gpio_interrupt_clear.write(1 << N) };
+        // Flag is held cleared while the clear bit is set, release it afterwards.
+        unsafe {
+            self.base.gpio_interrupt_clear.write(1 << N);
+            self.base.gpio_interrupt_clear.write(0);
+        }
     }
     /// Check if interrupt flag is set.
     #[inline]
@@ -198,21 +202,118 @@ impl<'a, const N: usize, M> InputPin for Padv1<'a, N, Input<M>> {
     }
 }
 
+impl<'a, const N: usize, M> Padv1<'a, N, Output<M>> {
+    // There are no set or clear registers on BL602 and BL702; output value register is
+    // modified with machine interrupts disabled so that pins on the same bank don't race.
+    #[inline]
+    fn modify_output(&mut self, f: impl FnOnce(u32) -> u32) {
+        interrupt_free(|| {
+            let val = self.base.gpio_output_value.read();
+            unsafe { self.base.gpio_output_value.write(f(val)) };
+        })
+    }
+}
+
+/// Run `f` with machine interrupts of the current core disabled.
+///
+/// `mstatus.MIE` is cleared before `f` and restored to its previous value afterwards.
+/// Unlike `critical_section::with`, this doesn't need an implementation linked in.
+#[inline]
+fn interrupt_free<T>(f: impl FnOnce() -> T) -> T {
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+    let mstatus: usize = {
+        let mstatus;
+        unsafe { core::arch::asm!("csrrci {}, mstatus, 8", out(reg) mstatus) };
+        mstatus
+    };
+    let ans = f();
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+    if mstatus & 0x8 != 0 {
+        unsafe { core::arch::asm!("csrsi mstatus, 8") };
+    }
+    ans
+}
+
 impl<'a, const N: usize, M> OutputPin for Padv1<'a, N, Output<M>> {
     #[inline]
     fn set_low(&mut self) -> Result<(), Self::Error> {
-        let val = self.base.gpio_output_value.read();
-        unsafe { self.base.gpio_output_value.write(val & !(1 << N)) };
+        self.modify_output(|val| val & !(1 << N));
         Ok(())
     }
     #[inline]
     fn set_high(&mut self) -> Result<(), Self::Error> {
-        let val = self.base.gpio_output_value.read();
-        unsafe { self.base.gpio_output_value.write(val | (1 << N)) };
+        self.modify_output(|val| val | (1 << N));
+        Ok(())
+    }
+}
+
+impl<'a, const N: usize, M> StatefulOutputPin for Padv1<'a, N, Output<M>> {
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.base.gpio_output_value.read() & (1 << N) != 0)
+    }
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.base.gpio_output_value.read() & (1 << N) == 0)
+    }
+    #[inline]
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        self.modify_output(|val| val ^ (1 << N));
         Ok(())
     }
 }
 
+#[cfg(feature = "glb-v1")]
+impl<'a, const N: usize, M> Padv1<'a, N, Input<M>> {
+    /// Read input levels of the whole bank this pad belongs to.
+    #[inline]
+    pub(crate) fn read_bank(&self) -> u32 {
+        self.base.gpio_input_value.read()
+    }
+}
+
+#[cfg(feature = "glb-v1")]
+impl<'a, const N: usize, M> Padv1<'a, N, Input<M>> {
+    /// Unmask interrupt of this pad on `trigger` with its flag cleared.
+    ///
+    /// There's no both edges mode, so any edge waits for the edge leaving current level.
+    #[inline]
+    pub(crate) fn listen(&mut self, trigger: super::asynch::Trigger) {
+        use super::asynch::Trigger;
+        let mode = match trigger {
+            Trigger::High => v1::InterruptMode::SyncHighLevel,
+            Trigger::Low => v1::InterruptMode::SyncLowLevel,
+            Trigger::RisingEdge => v1::InterruptMode::SyncRisingEdge,
+            Trigger::FallingEdge => v1::InterruptMode::SyncFallingEdge,
+            Trigger::AnyEdge if self.base.gpio_input_value.read() & (1 << N) != 0 => {
+                v1::InterruptMode::SyncFallingEdge
+            }
+            Trigger::AnyEdge => v1::InterruptMode::SyncRisingEdge,
+        };
+        // Mask and mode registers are shared with other pads and the interrupt handler.
+        critical_section::with(|_| {
+            self.mask_interrupt();
+            self.set_interrupt_mode(mode);
+            self.clear_interrupt();
+            self.unmask_interrupt();
+        })
+    }
+    /// Global configuration registers this pad belongs to.
+    #[inline]
+    pub(crate) fn register_block(&self) -> &'a v1::RegisterBlock {
+        self.base
+    }
+}
+
+#[cfg(feature = "glb-v1")]
+impl<'a, const N: usize, M> Padv1<'a, N, Output<M>> {
+    /// Write `value` into output bits under `mask` of the bank this pad belongs to.
+    #[inline]
+    pub(crate) fn write_bank(&mut self, mask: u32, value: u32) {
+        self.modify_output(|val| (val & !mask) | (value & mask));
+    }
+}
+
 // Macro internal functions, do not use.
 impl<'a, const N: usize> Padv1<'a, N, super::typestate::Disabled> {
     #[doc(hidden)]
@@ -7,7 +7,7 @@ use super::{
 };
 use crate::glb::{v2, Drive, Pull};
 use core::marker::PhantomData;
-use embedded_hal::digital::{ErrorType, InputPin, OutputPin};
+use embedded_hal::digital::{ErrorType, InputPin, OutputPin, StatefulOutputPin};
 
 /// Raw GPIO pad of BL808 and BL616.
 pub struct Padv2<'a, const N: usize, M> {
@@ -31,8 +31,12 @@ impl<'a, const N: usize, M> Padv2<'a, N, Input<M>> {
     /// Clear interrupt flag.
     #[inline]
     pub fn clear_interrupt(&mut self) {
-        let config = self.base.gpio_config[N].read().clear_interrupt();
-        unsafe { self.base.gpio_config[N].write(config) };
+        // Flag is held cleared while the clear bit is set, release it afterwards.
+        let config = self.base.gpio_config[N].read();
+        unsafe {
+            self.base.gpio_config[N].write(config.clear_interrupt());
+            self.base.gpio_config[N].write(config.release_interrupt_clear());
+        }
     }
     /// Check if interrupt flag is set.
     #[inline]
@@ -350,6 +354,22 @@ impl<'a, const N: usize, M> Padv2<'a, N, M> {
     }
 }
 
+#[cfg(feature = "glb-v2")]
+impl<'a, const N: usize, M> Padv2<'a, N, M> {
+    /// Set internal pull of this pin, keeping its function.
+    #[inline]
+    pub(crate) fn set_alternate_pull(&mut self, val: Pull) {
+        let config = self.base.gpio_config[N].read().set_pull(val);
+        unsafe { self.base.gpio_config[N].write(config) };
+    }
+    /// Set drive strength of this pin, keeping its function.
+    #[inline]
+    pub(crate) fn set_alternate_drive(&mut self, val: Drive) {
+        let config = self.base.gpio_config[N].read().set_drive(val);
+        unsafe { self.base.gpio_config[N].write(config) };
+    }
+}
+
 impl<'a, const N: usize, M> ErrorType for Padv2<'a, N, Input<M>> {
     type Error = core::convert::Infallible;
 }
@@ -382,6 +402,79 @@ impl<'a, const N: usize, M> OutputPin for Padv2<'a, N, Output<M>> {
     }
 }
 
+impl<'a, const N: usize, M> StatefulOutputPin for Padv2<'a, N, Output<M>> {
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.base.gpio_output[N >> 5].read() & (1 << (N & 0x1F)) != 0)
+    }
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.base.gpio_output[N >> 5].read() & (1 << (N & 0x1F)) == 0)
+    }
+    #[inline]
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        // Only this pad changes its own output bit, so writing set or clear register
+        // from its current state won't affect other pads on the same bank.
+        if self.is_set_high()? {
+            self.set_low()
+        } else {
+            self.set_high()
+        }
+    }
+}
+
+#[cfg(feature = "glb-v2")]
+impl<'a, const N: usize, M> Padv2<'a, N, Input<M>> {
+    /// Read input levels of the whole bank this pad belongs to.
+    #[inline]
+    pub(crate) fn read_bank(&self) -> u32 {
+        self.base.gpio_input[N >> 5].read()
+    }
+}
+
+#[cfg(feature = "glb-v2")]
+impl<'a, const N: usize, M> Padv2<'a, N, Input<M>> {
+    /// Unmask interrupt of this pad on `trigger` with its flag cleared.
+    #[inline]
+    pub(crate) fn listen(&mut self, trigger: super::asynch::Trigger) {
+        use super::asynch::Trigger;
+        let mode = match trigger {
+            Trigger::High => v2::InterruptMode::SyncHighLevel,
+            Trigger::Low => v2::InterruptMode::SyncLowLevel,
+            Trigger::RisingEdge => v2::InterruptMode::SyncRisingEdge,
+            Trigger::FallingEdge => v2::InterruptMode::SyncFallingEdge,
+            Trigger::AnyEdge => v2::InterruptMode::SyncBothEdges,
+        };
+        // Pad configuration is also modified by the interrupt handler.
+        critical_section::with(|_| {
+            self.mask_interrupt();
+            self.set_interrupt_mode(mode);
+            self.clear_interrupt();
+            self.unmask_interrupt();
+        })
+    }
+    /// Global configuration registers this pad belongs to.
+    #[inline]
+    pub(crate) fn register_block(&self) -> &'a v2::RegisterBlock {
+        self.base
+    }
+}
+
+#[cfg(feature = "glb-v2")]
+impl<'a, const N: usize, M> Padv2<'a, N, Output<M>> {
+    /// Write `value` into output bits under `mask` of the bank this pad belongs to.
+    ///
+    /// All bits change in a single register write; the output register is modified inside
+    /// a critical section so that pads on the same bank don't race.
+    #[inline]
+    pub(crate) fn write_bank(&mut self, mask: u32, value: u32) {
+        critical_section::with(|_| {
+            let val = self.base.gpio_output[N >> 5].read();
+            unsafe { self.base.gpio_output[N >> 5].write((val & !mask) | (value & mask)) };
+        })
+    }
+}
+
 // Macro internal functions, do not use.
 impl<'a, const N: usize> Padv2<'a, N, super::typestate::Disabled> {
     #[doc(hidden)]
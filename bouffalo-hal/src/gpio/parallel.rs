@@ -0,0 +1,187 @@
+use super::{input::Input, output::Output};
+
+/// Consecutive GPIO pads grouped into one parallel port of `N` bits.
+///
+/// Pads are given as a tuple, least significant bit first, and must be numbered
+/// consecutively within one 32-pad bank; otherwise the port fails to compile. Bank mask
+/// and bit offset are computed once at compile time, so reading or writing the port
+/// accesses the bank data register only once.
+///
+/// A port of output pads changes every bit in a single write to the output register,
+/// modified inside a critical section so that other pads on the same bank don't race.
+/// Other processor cores must not drive pads of the same bank at the same time.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use bouffalo_hal::gpio::{Pads, IntoPad, ParallelPort};
+/// # pub struct Peripherals { gpio: Pads<'static> }
+/// # fn main() {
+/// # let glb: &bouffalo_hal::glb::RegisterBlock = unsafe { &*(0x20000000 as *const _) };
+/// # let p: Peripherals = Peripherals { gpio: Pads::__pads_from_glb(glb) };
+/// let mut data = ParallelPort::new((
+///     p.gpio.io0.into_floating_output(),
+///     p.gpio.io1.into_floating_output(),
+///     p.gpio.io2.into_floating_output(),
+///     p.gpio.io3.into_floating_output(),
+///     p.gpio.io4.into_floating_output(),
+///     p.gpio.io5.into_floating_output(),
+///     p.gpio.io6.into_floating_output(),
+///     p.gpio.io7.into_floating_output(),
+/// ));
+/// data.write(0xA5);
+/// # }
+/// ```
+pub struct ParallelPort<PINS, const N: usize> {
+    pins: PINS,
+}
+
+/// Tuple of GPIO pads that can form a parallel port.
+pub trait PortPins<const N: usize> {
+    /// Pad numbers, least significant bit first.
+    const NUMBERS: [usize; N];
+}
+
+/// Tuple of GPIO input pads that can form a parallel port.
+pub trait InputPins<const N: usize>: PortPins<N> {
+    /// Read input levels of the whole bank these pads belong to.
+    fn read_bank(&self) -> u32;
+}
+
+/// Tuple of GPIO output pads that can form a parallel port.
+pub trait OutputPins<const N: usize>: PortPins<N> {
+    /// Write `value` into output bits under `mask` of the bank these pads belong to.
+    fn write_bank(&mut self, mask: u32, value: u32);
+}
+
+impl<PINS: PortPins<N>, const N: usize> ParallelPort<PINS, N> {
+    /// Bit offset of the first pad in its bank.
+    const SHIFT: u32 = port_shift(&PINS::NUMBERS);
+    /// Bits of all pads in the bank.
+    const MASK: u32 = port_mask(N, Self::SHIFT);
+
+    /// Create a parallel port from consecutive pads, least significant bit first.
+    #[inline]
+    pub fn new(pins: PINS) -> Self {
+        // Evaluate pad checks at compile time.
+        let _ = Self::MASK;
+        Self { pins }
+    }
+    /// Release the pads.
+    #[inline]
+    pub fn free(self) -> PINS {
+        self.pins
+    }
+}
+
+impl<PINS: OutputPins<N>, const N: usize> ParallelPort<PINS, N> {
+    /// Write lower `N` bits of `value` to the port; higher bits are ignored.
+    #[inline]
+    pub fn write(&mut self, value: u32) {
+        self.pins.write_bank(Self::MASK, value << Self::SHIFT);
+    }
+}
+
+impl<PINS: InputPins<N>, const N: usize> ParallelPort<PINS, N> {
+    /// Read input levels of the port as lower `N` bits of the result.
+    #[inline]
+    pub fn read(&self) -> u32 {
+        (self.pins.read_bank() & Self::MASK) >> Self::SHIFT
+    }
+}
+
+/// Bit offset in bank of consecutively numbered pads, panics if pads are not consecutive.
+const fn port_shift(numbers: &[usize]) -> u32 {
+    assert!(!numbers.is_empty(), "parallel port needs at least one pad");
+    let first = numbers[0];
+    let mut i = 1;
+    while i < numbers.len() {
+        assert!(
+            numbers[i] == first + i,
+            "parallel port pads must be numbered consecutively"
+        );
+        i += 1;
+    }
+    assert!(
+        first >> 5 == (first + numbers.len() - 1) >> 5,
+        "parallel port pads must be on the same bank"
+    );
+    (first & 0x1F) as u32
+}
+
+/// Bank mask of `width` bits from `shift`.
+const fn port_mask(width: usize, shift: u32) -> u32 {
+    if width == 32 {
+        u32::MAX
+    } else {
+        ((1 << width) - 1) << shift
+    }
+}
+
+macro_rules! impl_port_pins {
+    ($n: expr; $($number: ident, $mode: ident),+) => {
+        impl<'a, $(const $number: usize, $mode),+> PortPins<$n> for ($(Input<'a, $number, $mode>,)+) {
+            const NUMBERS: [usize; $n] = [$($number),+];
+        }
+        impl<'a, $(const $number: usize, $mode),+> InputPins<$n> for ($(Input<'a, $number, $mode>,)+) {
+            #[inline]
+            fn read_bank(&self) -> u32 {
+                self.0.read_bank()
+            }
+        }
+        impl<'a, $(const $number: usize, $mode),+> PortPins<$n> for ($(Output<'a, $number, $mode>,)+) {
+            const NUMBERS: [usize; $n] = [$($number),+];
+        }
+        impl<'a, $(const $number: usize, $mode),+> OutputPins<$n> for ($(Output<'a, $number, $mode>,)+) {
+            #[inline]
+            fn write_bank(&mut self, mask: u32, value: u32) {
+                self.0.write_bank(mask, value)
+            }
+        }
+    };
+}
+
+impl_port_pins!(2; N0, M0, N1, M1);
+impl_port_pins!(3; N0, M0, N1, M1, N2, M2);
+impl_port_pins!(4; N0, M0, N1, M1, N2, M2, N3, M3);
+impl_port_pins!(5; N0, M0, N1, M1, N2, M2, N3, M3, N4, M4);
+impl_port_pins!(6; N0, M0, N1, M1, N2, M2, N3, M3, N4, M4, N5, M5);
+impl_port_pins!(7; N0, M0, N1, M1, N2, M2, N3, M3, N4, M4, N5, M5, N6, M6);
+impl_port_pins!(8; N0, M0, N1, M1, N2, M2, N3, M3, N4, M4, N5, M5, N6, M6, N7, M7);
+impl_port_pins!(
+    16; N0, M0, N1, M1, N2, M2, N3, M3, N4, M4, N5, M5, N6, M6, N7, M7,
+    N8, M8, N9, M9, N10, M10, N11, M11, N12, M12, N13, M13, N14, M14, N15, M15
+);
+
+#[cfg(test)]
+mod tests {
+    use super::{port_mask, port_shift};
+
+    #[test]
+    fn function_port_shift_mask() {
+        let shift = port_shift(&[0, 1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(shift, 0);
+        assert_eq!(port_mask(8, shift), 0x0000_00FF);
+        let shift = port_shift(&[36, 37, 38, 39]);
+        assert_eq!(shift, 4);
+        assert_eq!(port_mask(4, shift), 0x0000_00F0);
+        let shift = port_shift(&[
+            16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31,
+        ]);
+        assert_eq!(shift, 16);
+        assert_eq!(port_mask(16, shift), 0xFFFF_0000);
+        assert_eq!(port_mask(32, 0), u32::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "consecutively")]
+    fn function_port_shift_gap() {
+        port_shift(&[8, 9, 11]);
+    }
+
+    #[test]
+    #[should_panic(expected = "same bank")]
+    fn function_port_shift_bank() {
+        port_shift(&[30, 31, 32, 33]);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::glb::v2;
+use crate::glb::{v2, Pull};
 use core::marker::PhantomData;
 
 /// Input mode (type state).
@@ -23,6 +23,24 @@ pub struct PullUp;
 /// Floating (type state).
 pub struct Floating;
 
+/// Pull direction of an input or output mode (type state).
+pub trait PullMode {
+    /// Pull direction constant for pad configuration registers.
+    const PULL: Pull;
+}
+
+impl PullMode for PullDown {
+    const PULL: Pull = Pull::Down;
+}
+
+impl PullMode for PullUp {
+    const PULL: Pull = Pull::Up;
+}
+
+impl PullMode for Floating {
+    const PULL: Pull = Pull::None;
+}
+
 /// UART alternate (type state).
 pub struct Uart;
 
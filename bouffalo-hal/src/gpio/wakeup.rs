@@ -0,0 +1,58 @@
+use super::{input::Input, typestate::PullMode};
+use crate::hbn::{self, WakeupTrigger, AON_PAD_BASE, AON_PAD_COUNT};
+use core::ops::Deref;
+
+/// GPIO pad registered as hibernation wakeup source.
+///
+/// Only always-on pads, GPIO9 to GPIO13, can wake the chip from hibernation; using
+/// other pads fails to compile. During sleep the pad is held as input by hibernate
+/// control, with the same pull direction as the input mode `M`.
+pub struct WakeupSource<'a, const N: usize, M> {
+    pin: Input<'a, N, M>,
+}
+
+impl<'a, const N: usize, M> WakeupSource<'a, N, M> {
+    const AON_INDEX: usize = match N {
+        n if n >= AON_PAD_BASE && n < AON_PAD_BASE + AON_PAD_COUNT => n - AON_PAD_BASE,
+        _ => panic!("only always-on pads can wake the chip from hibernation"),
+    };
+}
+
+impl<'a, const N: usize, M: PullMode> Input<'a, N, M> {
+    /// Register this pad as hibernation wakeup source on `trigger` condition.
+    ///
+    /// Trigger condition is shared by all always-on pads; registering another pad
+    /// replaces it. After waking up, [`hbn::wakeup_pin`] reports the triggering pad.
+    #[inline]
+    pub fn into_wakeup_source<HBN: Deref<Target = hbn::RegisterBlock>>(
+        self,
+        trigger: WakeupTrigger,
+        hbn: &HBN,
+    ) -> WakeupSource<'a, N, M> {
+        let idx = WakeupSource::<N, M>::AON_INDEX;
+        unsafe {
+            hbn.pad_control_1
+                .modify(|v| v.disable_output(idx).set_pull(idx, M::PULL));
+            hbn.pad_control_0
+                .modify(|v| v.enable_input(idx).enable_aon_control(idx));
+            hbn.interrupt_clear
+                .write(hbn::InterruptClear::default().clear_pin_wakeup(idx));
+            hbn.interrupt_mode
+                .modify(|v| v.set_pin_wakeup_trigger(trigger).enable_pin_wakeup(idx));
+        }
+        WakeupSource { pin: self }
+    }
+}
+
+impl<'a, const N: usize, M> WakeupSource<'a, N, M> {
+    /// Unregister the wakeup source and return the input pad.
+    #[inline]
+    pub fn free<HBN: Deref<Target = hbn::RegisterBlock>>(self, hbn: &HBN) -> Input<'a, N, M> {
+        let idx = Self::AON_INDEX;
+        unsafe {
+            hbn.interrupt_mode.modify(|v| v.disable_pin_wakeup(idx));
+            hbn.pad_control_0.modify(|v| v.disable_aon_control(idx));
+        }
+        self.pin
+    }
+}
@@ -2,6 +2,13 @@
 
 use core::ops::Deref;
 
+pub mod asynch;
+mod sysmon;
+mod tsen;
+
+pub use sysmon::*;
+pub use tsen::*;
+
 use volatile_register::RW;
 
 /// Generic DAC, ADC and ACOMP interface control peripheral registers.
@@ -42,10 +49,166 @@ pub struct RegisterBlock {
 #[repr(transparent)]
 pub struct GpadcConfig(u32);
 
+impl GpadcConfig {
+    const FIFO_CLEAR: u32 = 1 << 1;
+    const FIFO_NOT_EMPTY: u32 = 1 << 2;
+    const FIFO_FULL: u32 = 1 << 3;
+    const READY: u32 = 1 << 4;
+    const FIFO_OVERRUN: u32 = 1 << 5;
+    const READY_CLEAR: u32 = 1 << 8;
+    const FIFO_OVERRUN_CLEAR: u32 = 1 << 9;
+    const READY_MASK: u32 = 1 << 12;
+    const FIFO_OVERRUN_MASK: u32 = 1 << 13;
+    const FIFO_DATA_COUNT: u32 = 0x3f << 16;
+    const FIFO_THRESHOLD: u32 = 0x3 << 22;
+
+    /// Clear result first-in first-out queue.
+    #[inline]
+    pub const fn clear_fifo(self) -> Self {
+        Self(self.0 | Self::FIFO_CLEAR)
+    }
+    /// Check if result first-in first-out queue is not empty.
+    #[inline]
+    pub const fn is_fifo_not_empty(self) -> bool {
+        self.0 & Self::FIFO_NOT_EMPTY != 0
+    }
+    /// Get number of results in first-in first-out queue.
+    #[inline]
+    pub const fn fifo_data_count(self) -> u8 {
+        ((self.0 & Self::FIFO_DATA_COUNT) >> 16) as u8
+    }
+    /// Check if result first-in first-out queue is full.
+    #[inline]
+    pub const fn is_fifo_full(self) -> bool {
+        self.0 & Self::FIFO_FULL != 0
+    }
+    /// Check if results in queue have reached the threshold.
+    #[inline]
+    pub const fn is_ready(self) -> bool {
+        self.0 & Self::READY != 0
+    }
+    /// Check if results were lost because the queue was full.
+    #[inline]
+    pub const fn is_fifo_overrun(self) -> bool {
+        self.0 & Self::FIFO_OVERRUN != 0
+    }
+    /// Clear ready flag.
+    #[inline]
+    pub const fn clear_ready(self) -> Self {
+        Self(self.0 | Self::READY_CLEAR)
+    }
+    /// Clear queue overrun flag.
+    #[inline]
+    pub const fn clear_fifo_overrun(self) -> Self {
+        Self(self.0 | Self::FIFO_OVERRUN_CLEAR)
+    }
+    /// Release flag clear bits, which should be written after flags are cleared.
+    #[inline]
+    pub const fn release_clear(self) -> Self {
+        Self(self.0 & !(Self::READY_CLEAR | Self::FIFO_OVERRUN_CLEAR))
+    }
+    /// Mask ready interrupt.
+    #[inline]
+    pub const fn mask_ready_interrupt(self) -> Self {
+        Self(self.0 | Self::READY_MASK)
+    }
+    /// Unmask ready interrupt.
+    #[inline]
+    pub const fn unmask_ready_interrupt(self) -> Self {
+        Self(self.0 & !Self::READY_MASK)
+    }
+    /// Check if ready interrupt is masked.
+    #[inline]
+    pub const fn is_ready_interrupt_masked(self) -> bool {
+        self.0 & Self::READY_MASK != 0
+    }
+    /// Mask queue overrun interrupt.
+    #[inline]
+    pub const fn mask_fifo_overrun_interrupt(self) -> Self {
+        Self(self.0 | Self::FIFO_OVERRUN_MASK)
+    }
+    /// Unmask queue overrun interrupt.
+    #[inline]
+    pub const fn unmask_fifo_overrun_interrupt(self) -> Self {
+        Self(self.0 & !Self::FIFO_OVERRUN_MASK)
+    }
+    /// Check if queue overrun interrupt is masked.
+    #[inline]
+    pub const fn is_fifo_overrun_interrupt_masked(self) -> bool {
+        self.0 & Self::FIFO_OVERRUN_MASK != 0
+    }
+    /// Set number of results in queue to raise ready flag.
+    #[inline]
+    pub const fn set_fifo_threshold(self, val: FifoThreshold) -> Self {
+        Self((self.0 & !Self::FIFO_THRESHOLD) | ((val as u32) << 22))
+    }
+    /// Get number of results in queue to raise ready flag.
+    #[inline]
+    pub const fn fifo_threshold(self) -> FifoThreshold {
+        match (self.0 & Self::FIFO_THRESHOLD) >> 22 {
+            0 => FifoThreshold::One,
+            1 => FifoThreshold::Four,
+            2 => FifoThreshold::Eight,
+            3 => FifoThreshold::Sixteen,
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Number of results in queue to raise ready flag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum FifoThreshold {
+    /// One result.
+    One = 0,
+    /// Four results.
+    Four = 1,
+    /// Eight results.
+    Eight = 2,
+    /// Sixteen results.
+    Sixteen = 3,
+}
+
+impl FifoThreshold {
+    /// Number of results this threshold represents.
+    #[inline]
+    pub const fn count(self) -> u8 {
+        match self {
+            FifoThreshold::One => 1,
+            FifoThreshold::Four => 4,
+            FifoThreshold::Eight => 8,
+            FifoThreshold::Sixteen => 16,
+        }
+    }
+}
+
+/// Conversion result read from first-in first-out queue.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct GpadcDmaRdata(u32);
 
+impl GpadcDmaRdata {
+    const DATA: u32 = 0xffff;
+    const NEGATIVE_CHANNEL: u32 = 0x1f << 16;
+    const POSITIVE_CHANNEL: u32 = 0x1f << 21;
+
+    /// Get conversion data, left aligned to 16 bits.
+    #[inline]
+    pub const fn data(self) -> u16 {
+        (self.0 & Self::DATA) as u16
+    }
+    /// Get negative input channel of this result.
+    #[inline]
+    pub const fn negative_channel(self) -> AdcChannel {
+        AdcChannel::from_bits(((self.0 & Self::NEGATIVE_CHANNEL) >> 16) as u8)
+    }
+    /// Get positive input channel of this result.
+    #[inline]
+    pub const fn positive_channel(self) -> AdcChannel {
+        AdcChannel::from_bits(((self.0 & Self::POSITIVE_CHANNEL) >> 21) as u8)
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct GpadcPirTrain(u32);
@@ -56,7 +219,11 @@ pub struct GpadcCommand(u32);
 
 impl GpadcCommand {
     const GLOBAL_ENABLE: u32 = 1 << 0;
+    const CONVERSION_START: u32 = 1 << 1;
     const SOFTWARE_RESET: u32 = 1 << 2;
+    const NEGATIVE_CHANNEL: u32 = 0x1f << 3;
+    const POSITIVE_CHANNEL: u32 = 0x1f << 8;
+    const NEGATIVE_GND: u32 = 1 << 13;
 
     /// Enable the Analog-to-Digital Converter.
     #[inline]
@@ -88,32 +255,533 @@ impl GpadcCommand {
     pub const fn is_software_reset_enabled(self) -> bool {
         self.0 & Self::SOFTWARE_RESET != 0
     }
+    /// Start analog-to-digital conversion.
+    #[inline]
+    pub const fn start_conversion(self) -> Self {
+        Self(self.0 | Self::CONVERSION_START)
+    }
+    /// Stop analog-to-digital conversion.
+    #[inline]
+    pub const fn stop_conversion(self) -> Self {
+        Self(self.0 & !Self::CONVERSION_START)
+    }
+    /// Check if analog-to-digital conversion is started.
+    #[inline]
+    pub const fn is_conversion_started(self) -> bool {
+        self.0 & Self::CONVERSION_START != 0
+    }
+    /// Set negative input channel.
+    #[inline]
+    pub const fn set_negative_channel(self, val: AdcChannel) -> Self {
+        Self((self.0 & !Self::NEGATIVE_CHANNEL) | ((val as u32) << 3))
+    }
+    /// Get negative input channel.
+    #[inline]
+    pub const fn negative_channel(self) -> AdcChannel {
+        AdcChannel::from_bits(((self.0 & Self::NEGATIVE_CHANNEL) >> 3) as u8)
+    }
+    /// Set positive input channel.
+    #[inline]
+    pub const fn set_positive_channel(self, val: AdcChannel) -> Self {
+        Self((self.0 & !Self::POSITIVE_CHANNEL) | ((val as u32) << 8))
+    }
+    /// Get positive input channel.
+    #[inline]
+    pub const fn positive_channel(self) -> AdcChannel {
+        AdcChannel::from_bits(((self.0 & Self::POSITIVE_CHANNEL) >> 8) as u8)
+    }
+    /// Connect negative input to ground.
+    #[inline]
+    pub const fn enable_negative_gnd(self) -> Self {
+        Self(self.0 | Self::NEGATIVE_GND)
+    }
+    /// Disconnect negative input from ground.
+    #[inline]
+    pub const fn disable_negative_gnd(self) -> Self {
+        Self(self.0 & !Self::NEGATIVE_GND)
+    }
+    /// Check if negative input is connected to ground.
+    #[inline]
+    pub const fn is_negative_gnd_enabled(self) -> bool {
+        self.0 & Self::NEGATIVE_GND != 0
+    }
+}
+
+/// Analog-to-Digital Converter input channel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum AdcChannel {
+    /// External channel 0.
+    Channel0 = 0,
+    /// External channel 1.
+    Channel1 = 1,
+    /// External channel 2.
+    Channel2 = 2,
+    /// External channel 3.
+    Channel3 = 3,
+    /// External channel 4.
+    Channel4 = 4,
+    /// External channel 5.
+    Channel5 = 5,
+    /// External channel 6.
+    Channel6 = 6,
+    /// External channel 7.
+    Channel7 = 7,
+    /// External channel 8.
+    Channel8 = 8,
+    /// External channel 9.
+    Channel9 = 9,
+    /// External channel 10.
+    Channel10 = 10,
+    /// External channel 11.
+    Channel11 = 11,
+    /// Digital-to-Analog Converter output A.
+    DacA = 12,
+    /// Digital-to-Analog Converter output B.
+    DacB = 13,
+    /// Temperature sensor positive node.
+    TsenP = 14,
+    /// Temperature sensor negative node.
+    TsenN = 15,
+    /// Internal voltage reference.
+    Vref = 16,
+    /// DC test output.
+    DcTest = 17,
+    /// Half of battery voltage.
+    VbatHalf = 18,
+    /// Internal sensor 3.
+    Sensor3 = 19,
+    /// Internal sensor 2.
+    Sensor2 = 20,
+    /// Internal sensor 1.
+    Sensor1 = 21,
+    /// Internal sensor 0.
+    Sensor0 = 22,
+    /// Analog ground.
+    Gnd = 23,
+}
+
+impl AdcChannel {
+    #[inline]
+    const fn from_bits(val: u8) -> Self {
+        match val {
+            0 => AdcChannel::Channel0,
+            1 => AdcChannel::Channel1,
+            2 => AdcChannel::Channel2,
+            3 => AdcChannel::Channel3,
+            4 => AdcChannel::Channel4,
+            5 => AdcChannel::Channel5,
+            6 => AdcChannel::Channel6,
+            7 => AdcChannel::Channel7,
+            8 => AdcChannel::Channel8,
+            9 => AdcChannel::Channel9,
+            10 => AdcChannel::Channel10,
+            11 => AdcChannel::Channel11,
+            12 => AdcChannel::DacA,
+            13 => AdcChannel::DacB,
+            14 => AdcChannel::TsenP,
+            15 => AdcChannel::TsenN,
+            16 => AdcChannel::Vref,
+            17 => AdcChannel::DcTest,
+            18 => AdcChannel::VbatHalf,
+            19 => AdcChannel::Sensor3,
+            20 => AdcChannel::Sensor2,
+            21 => AdcChannel::Sensor1,
+            22 => AdcChannel::Sensor0,
+            23 => AdcChannel::Gnd,
+            _ => unreachable!(),
+        }
+    }
+    /// Check if this channel is connected to an external pad.
+    #[inline]
+    pub const fn is_external(self) -> bool {
+        (self as u8) <= 11
+    }
 }
 
+/// Generic Analog-to-Digital Converter configuration register 1.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct GpadcConfig1(u32);
 
+impl GpadcConfig1 {
+    const CONTINUOUS_CONVERSION: u32 = 1 << 1;
+    const RESOLUTION: u32 = 0x7 << 2;
+    const SCAN_LENGTH: u32 = 0xf << 21;
+    const SCAN_ENABLE: u32 = 1 << 25;
+
+    /// Enable continuous conversion.
+    #[inline]
+    pub const fn enable_continuous_conversion(self) -> Self {
+        Self(self.0 | Self::CONTINUOUS_CONVERSION)
+    }
+    /// Disable continuous conversion.
+    #[inline]
+    pub const fn disable_continuous_conversion(self) -> Self {
+        Self(self.0 & !Self::CONTINUOUS_CONVERSION)
+    }
+    /// Check if continuous conversion is enabled.
+    #[inline]
+    pub const fn is_continuous_conversion_enabled(self) -> bool {
+        self.0 & Self::CONTINUOUS_CONVERSION != 0
+    }
+    /// Set hardware oversampling ratio.
+    #[inline]
+    pub const fn set_oversample_ratio(self, val: OversampleRatio) -> Self {
+        Self((self.0 & !Self::RESOLUTION) | ((val as u32) << 2))
+    }
+    /// Get hardware oversampling ratio.
+    #[inline]
+    pub const fn oversample_ratio(self) -> OversampleRatio {
+        match (self.0 & Self::RESOLUTION) >> 2 {
+            0 => OversampleRatio::X1,
+            1 => OversampleRatio::X16,
+            2 => OversampleRatio::X64,
+            3 => OversampleRatio::X128,
+            4 => OversampleRatio::X256,
+            _ => unreachable!(),
+        }
+    }
+    /// Set number of channels in scan list, from 1 to 12.
+    #[inline]
+    pub const fn set_scan_length(self, val: u8) -> Self {
+        Self((self.0 & !Self::SCAN_LENGTH) | (((val as u32 - 1) << 21) & Self::SCAN_LENGTH))
+    }
+    /// Get number of channels in scan list.
+    #[inline]
+    pub const fn scan_length(self) -> u8 {
+        ((self.0 & Self::SCAN_LENGTH) >> 21) as u8 + 1
+    }
+    /// Enable scanning channels in scan list.
+    #[inline]
+    pub const fn enable_scan(self) -> Self {
+        Self(self.0 | Self::SCAN_ENABLE)
+    }
+    /// Disable scanning channels in scan list.
+    #[inline]
+    pub const fn disable_scan(self) -> Self {
+        Self(self.0 & !Self::SCAN_ENABLE)
+    }
+    /// Check if scanning channels in scan list is enabled.
+    #[inline]
+    pub const fn is_scan_enabled(self) -> bool {
+        self.0 & Self::SCAN_ENABLE != 0
+    }
+}
+
+/// Generic Analog-to-Digital Converter configuration register 2.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct GpadcConfig2(u32);
 
+impl GpadcConfig2 {
+    const DIFFERENTIAL_MODE: u32 = 1 << 2;
+    const VREF_SELECT: u32 = 1 << 3;
+    const VBAT_ENABLE: u32 = 1 << 4;
+    const TSEN_EXTERNAL: u32 = 1 << 5;
+    const TSEN_ENABLE: u32 = 1 << 6;
+    const PGA_ENABLE: u32 = 1 << 13;
+    const PGA2_GAIN: u32 = 0x7 << 22;
+    const PGA1_GAIN: u32 = 0x7 << 25;
+    const TSEN_VBE_LOW: u32 = 1 << 31;
+
+    /// Enable differential input mode.
+    #[inline]
+    pub const fn enable_differential_mode(self) -> Self {
+        Self(self.0 | Self::DIFFERENTIAL_MODE)
+    }
+    /// Disable differential input mode.
+    #[inline]
+    pub const fn disable_differential_mode(self) -> Self {
+        Self(self.0 & !Self::DIFFERENTIAL_MODE)
+    }
+    /// Check if differential input mode is enabled.
+    #[inline]
+    pub const fn is_differential_mode_enabled(self) -> bool {
+        self.0 & Self::DIFFERENTIAL_MODE != 0
+    }
+    /// Set voltage reference.
+    #[inline]
+    pub const fn set_voltage_reference(self, val: VoltageReference) -> Self {
+        match val {
+            VoltageReference::Internal3V2 => Self(self.0 & !Self::VREF_SELECT),
+            VoltageReference::Internal2V => Self(self.0 | Self::VREF_SELECT),
+        }
+    }
+    /// Get voltage reference.
+    #[inline]
+    pub const fn voltage_reference(self) -> VoltageReference {
+        match self.0 & Self::VREF_SELECT {
+            0 => VoltageReference::Internal3V2,
+            _ => VoltageReference::Internal2V,
+        }
+    }
+    /// Enable battery voltage divider for `VbatHalf` channel.
+    #[inline]
+    pub const fn enable_vbat(self) -> Self {
+        Self(self.0 | Self::VBAT_ENABLE)
+    }
+    /// Disable battery voltage divider.
+    #[inline]
+    pub const fn disable_vbat(self) -> Self {
+        Self(self.0 & !Self::VBAT_ENABLE)
+    }
+    /// Check if battery voltage divider is enabled.
+    #[inline]
+    pub const fn is_vbat_enabled(self) -> bool {
+        self.0 & Self::VBAT_ENABLE != 0
+    }
+    /// Enable temperature sensor.
+    #[inline]
+    pub const fn enable_tsen(self) -> Self {
+        Self(self.0 | Self::TSEN_ENABLE)
+    }
+    /// Disable temperature sensor.
+    #[inline]
+    pub const fn disable_tsen(self) -> Self {
+        Self(self.0 & !Self::TSEN_ENABLE)
+    }
+    /// Check if temperature sensor is enabled.
+    #[inline]
+    pub const fn is_tsen_enabled(self) -> bool {
+        self.0 & Self::TSEN_ENABLE != 0
+    }
+    /// Select external diode instead of internal diode as temperature sensor.
+    #[inline]
+    pub const fn enable_tsen_external(self) -> Self {
+        Self(self.0 | Self::TSEN_EXTERNAL)
+    }
+    /// Select internal diode as temperature sensor.
+    #[inline]
+    pub const fn disable_tsen_external(self) -> Self {
+        Self(self.0 & !Self::TSEN_EXTERNAL)
+    }
+    /// Check if external diode is selected as temperature sensor.
+    #[inline]
+    pub const fn is_tsen_external_enabled(self) -> bool {
+        self.0 & Self::TSEN_EXTERNAL != 0
+    }
+    /// Bias temperature sensor diode with low current.
+    #[inline]
+    pub const fn enable_tsen_vbe_low(self) -> Self {
+        Self(self.0 | Self::TSEN_VBE_LOW)
+    }
+    /// Bias temperature sensor diode with high current.
+    #[inline]
+    pub const fn disable_tsen_vbe_low(self) -> Self {
+        Self(self.0 & !Self::TSEN_VBE_LOW)
+    }
+    /// Check if temperature sensor diode is biased with low current.
+    #[inline]
+    pub const fn is_tsen_vbe_low_enabled(self) -> bool {
+        self.0 & Self::TSEN_VBE_LOW != 0
+    }
+    /// Enable programmable gain amplifier.
+    #[inline]
+    pub const fn enable_pga(self) -> Self {
+        Self(self.0 | Self::PGA_ENABLE)
+    }
+    /// Disable programmable gain amplifier.
+    #[inline]
+    pub const fn disable_pga(self) -> Self {
+        Self(self.0 & !Self::PGA_ENABLE)
+    }
+    /// Check if programmable gain amplifier is enabled.
+    #[inline]
+    pub const fn is_pga_enabled(self) -> bool {
+        self.0 & Self::PGA_ENABLE != 0
+    }
+    /// Set gain of the second amplifier stage, in power of 2.
+    #[inline]
+    pub const fn set_pga2_gain(self, val: u8) -> Self {
+        Self((self.0 & !Self::PGA2_GAIN) | (((val as u32) << 22) & Self::PGA2_GAIN))
+    }
+    /// Get gain of the second amplifier stage, in power of 2.
+    #[inline]
+    pub const fn pga2_gain(self) -> u8 {
+        ((self.0 & Self::PGA2_GAIN) >> 22) as u8
+    }
+    /// Set gain of the first amplifier stage, in power of 2.
+    #[inline]
+    pub const fn set_pga1_gain(self, val: u8) -> Self {
+        Self((self.0 & !Self::PGA1_GAIN) | (((val as u32) << 25) & Self::PGA1_GAIN))
+    }
+    /// Get gain of the first amplifier stage, in power of 2.
+    #[inline]
+    pub const fn pga1_gain(self) -> u8 {
+        ((self.0 & Self::PGA1_GAIN) >> 25) as u8
+    }
+    /// Set overall programmable amplifier gain.
+    ///
+    /// Gains above 8x are split into both amplifier stages.
+    #[inline]
+    pub const fn set_pga_gain(self, val: PgaGain) -> Self {
+        let (pga1, pga2) = match val {
+            PgaGain::X1 => (0, 0),
+            PgaGain::X2 => (1, 0),
+            PgaGain::X4 => (2, 0),
+            PgaGain::X8 => (3, 0),
+            PgaGain::X16 => (3, 1),
+            PgaGain::X32 => (3, 2),
+        };
+        self.set_pga1_gain(pga1).set_pga2_gain(pga2)
+    }
+}
+
+/// Analog-to-Digital Converter voltage reference.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VoltageReference {
+    /// Internal 3.2 V reference.
+    Internal3V2,
+    /// Internal 2.0 V reference.
+    Internal2V,
+}
+
+/// Programmable gain amplifier setting.
+///
+/// In differential mode, the full-scale input range is `±Vref / gain`.
+/// With the internal 3.2 V reference, this is:
+///
+/// | Gain | Differential input range |
+/// |:-----|:-------------------------|
+/// | 1x   | ±3.2 V                   |
+/// | 2x   | ±1.6 V                   |
+/// | 4x   | ±800 mV                  |
+/// | 8x   | ±400 mV                  |
+/// | 16x  | ±200 mV                  |
+/// | 32x  | ±100 mV                  |
+///
+/// With the internal 2.0 V reference, divide the range by 1.6 accordingly.
+/// Absolute voltage on every input pad must still remain within supply rails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PgaGain {
+    /// Gain of 1x, amplifier bypassed.
+    X1,
+    /// Gain of 2x.
+    X2,
+    /// Gain of 4x.
+    X4,
+    /// Gain of 8x.
+    X8,
+    /// Gain of 16x.
+    X16,
+    /// Gain of 32x.
+    X32,
+}
+
+/// Hardware oversampling ratio.
+///
+/// The converter accumulates this many raw samples and right-shifts the sum internally,
+/// returning one filtered result per conversion. Oversampling trades sample rate for
+/// effective resolution:
+///
+/// | Ratio | Effective resolution | Sample rate   |
+/// |:------|:---------------------|:--------------|
+/// | 1x    | 12 bits              | 2 MS/s        |
+/// | 16x   | 14 bits              | 125 kS/s      |
+/// | 64x   | 14 bits              | 31.25 kS/s    |
+/// | 128x  | 16 bits              | 15.625 kS/s   |
+/// | 256x  | 16 bits              | 7.8125 kS/s   |
+///
+/// Results stay left aligned to 16 bits, so extra resolution appears in lower bits
+/// which are zero without oversampling. Each channel of a scan sweep is oversampled
+/// separately, thus a sweep takes `ratio` times longer. Parts without oversampling can
+/// still average results of 1x conversions in software.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum OversampleRatio {
+    /// No oversampling, 12-bit results.
+    X1 = 0,
+    /// 16 samples, 14-bit results.
+    X16 = 1,
+    /// 64 samples, 14-bit results.
+    X64 = 2,
+    /// 128 samples, 16-bit results.
+    X128 = 3,
+    /// 256 samples, 16-bit results.
+    X256 = 4,
+}
+
+/// Positive channels of scan list slots 0 to 5.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct AdcConverationSequence1(u32);
 
+impl AdcConverationSequence1 {
+    const CHANNEL: u32 = 0x1f;
+
+    /// Set channel of scan list slot `idx` in this register, from 0 to 5.
+    #[inline]
+    pub const fn set_channel(self, idx: usize, val: AdcChannel) -> Self {
+        Self((self.0 & !(Self::CHANNEL << (idx * 5))) | ((val as u32) << (idx * 5)))
+    }
+    /// Get channel of scan list slot `idx` in this register.
+    #[inline]
+    pub const fn channel(self, idx: usize) -> AdcChannel {
+        AdcChannel::from_bits(((self.0 >> (idx * 5)) & Self::CHANNEL) as u8)
+    }
+}
+
+/// Positive channels of scan list slots 6 to 11.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct AdcConverationSequence2(u32);
 
+impl AdcConverationSequence2 {
+    const CHANNEL: u32 = 0x1f;
+
+    /// Set channel of scan list slot `idx` in this register, from 0 to 5.
+    #[inline]
+    pub const fn set_channel(self, idx: usize, val: AdcChannel) -> Self {
+        Self((self.0 & !(Self::CHANNEL << (idx * 5))) | ((val as u32) << (idx * 5)))
+    }
+    /// Get channel of scan list slot `idx` in this register.
+    #[inline]
+    pub const fn channel(self, idx: usize) -> AdcChannel {
+        AdcChannel::from_bits(((self.0 >> (idx * 5)) & Self::CHANNEL) as u8)
+    }
+}
+
+/// Negative channels of scan list slots 0 to 5.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct AdcConverationSequence3(u32);
 
+impl AdcConverationSequence3 {
+    const CHANNEL: u32 = 0x1f;
+
+    /// Set channel of scan list slot `idx` in this register, from 0 to 5.
+    #[inline]
+    pub const fn set_channel(self, idx: usize, val: AdcChannel) -> Self {
+        Self((self.0 & !(Self::CHANNEL << (idx * 5))) | ((val as u32) << (idx * 5)))
+    }
+    /// Get channel of scan list slot `idx` in this register.
+    #[inline]
+    pub const fn channel(self, idx: usize) -> AdcChannel {
+        AdcChannel::from_bits(((self.0 >> (idx * 5)) & Self::CHANNEL) as u8)
+    }
+}
+
+/// Negative channels of scan list slots 6 to 11.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct AdcConverationSequence4(u32);
 
+impl AdcConverationSequence4 {
+    const CHANNEL: u32 = 0x1f;
+
+    /// Set channel of scan list slot `idx` in this register, from 0 to 5.
+    #[inline]
+    pub const fn set_channel(self, idx: usize, val: AdcChannel) -> Self {
+        Self((self.0 & !(Self::CHANNEL << (idx * 5))) | ((val as u32) << (idx * 5)))
+    }
+    /// Get channel of scan list slot `idx` in this register.
+    #[inline]
+    pub const fn channel(self, idx: usize) -> AdcChannel {
+        AdcChannel::from_bits(((self.0 >> (idx * 5)) & Self::CHANNEL) as u8)
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct GpadcStatus(u32);
@@ -166,21 +834,332 @@ pub struct GpdacBctrl(u32);
 #[repr(transparent)]
 pub struct GpdacData(u32);
 
+/// Analog-to-Digital Converter configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AdcConfig {
+    /// Single-ended or differential input.
+    pub input_mode: InputMode,
+    /// Programmable gain amplifier setting, applied in differential mode only.
+    pub gain: PgaGain,
+    /// Voltage reference.
+    pub voltage_reference: VoltageReference,
+    /// Hardware oversampling ratio.
+    pub oversample: OversampleRatio,
+}
+
+impl AdcConfig {
+    /// Set input mode.
+    #[inline]
+    pub const fn set_input_mode(self, input_mode: InputMode) -> Self {
+        Self { input_mode, ..self }
+    }
+    /// Set programmable gain amplifier setting.
+    #[inline]
+    pub const fn set_gain(self, gain: PgaGain) -> Self {
+        Self { gain, ..self }
+    }
+    /// Set voltage reference.
+    #[inline]
+    pub const fn set_voltage_reference(self, voltage_reference: VoltageReference) -> Self {
+        Self {
+            voltage_reference,
+            ..self
+        }
+    }
+    /// Set hardware oversampling ratio.
+    #[inline]
+    pub const fn set_oversample(self, oversample: OversampleRatio) -> Self {
+        Self { oversample, ..self }
+    }
+}
+
+impl Default for AdcConfig {
+    /// ADC configuration defaults to single-ended input with 3.2 V reference, amplifier bypassed
+    /// and no oversampling.
+    #[inline]
+    fn default() -> Self {
+        AdcConfig {
+            input_mode: InputMode::SingleEnded,
+            gain: PgaGain::X1,
+            voltage_reference: VoltageReference::Internal3V2,
+            oversample: OversampleRatio::X1,
+        }
+    }
+}
+
+/// Analog input mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InputMode {
+    /// Positive channel measured against analog ground.
+    SingleEnded,
+    /// Positive channel measured against negative channel.
+    Differential,
+}
+
+/// Errors on ADC configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigError {
+    /// Positive and negative inputs select the same channel.
+    SameChannel,
+    /// Channel cannot be used as differential input.
+    IllegalDifferentialPair,
+    /// Negative input is not ground in single-ended mode.
+    IllegalSingleEndedNegative,
+    /// Ground cannot be selected as positive input.
+    IllegalPositiveChannel,
+    /// Scan list is empty or longer than 12 channels.
+    IllegalScanLength,
+}
+
+/// Errors on ADC conversion.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// Results were lost because the queue was full.
+    FifoOverrun,
+    /// Channels cannot be selected under current input mode.
+    Config(ConfigError),
+}
+
+impl From<ConfigError> for Error {
+    #[inline]
+    fn from(value: ConfigError) -> Self {
+        Error::Config(value)
+    }
+}
+
+/// Check if positive and negative channels form a legal pair under given input mode.
+///
+/// In differential mode, both channels should be distinct external channels,
+/// or the temperature sensor `TsenP` and `TsenN` nodes as a pair.
+/// In single-ended mode, the negative channel should be `Gnd`.
+#[inline]
+pub const fn check_channel_pair(
+    mode: InputMode,
+    positive: AdcChannel,
+    negative: AdcChannel,
+) -> Result<(), ConfigError> {
+    if matches!(positive, AdcChannel::Gnd) {
+        return Err(ConfigError::IllegalPositiveChannel);
+    }
+    match mode {
+        InputMode::SingleEnded => match negative {
+            AdcChannel::Gnd => Ok(()),
+            _ => Err(ConfigError::IllegalSingleEndedNegative),
+        },
+        InputMode::Differential => {
+            if positive as u8 == negative as u8 {
+                return Err(ConfigError::SameChannel);
+            }
+            match (positive, negative) {
+                (AdcChannel::TsenP, AdcChannel::TsenN) => Ok(()),
+                (p, n) if p.is_external() && n.is_external() => Ok(()),
+                _ => Err(ConfigError::IllegalDifferentialPair),
+            }
+        }
+    }
+}
+
+/// Maximum number of channels in one scan sweep.
+pub const SCAN_LENGTH_MAX: usize = 12;
+
+/// Conversion result tagged with its source channels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AdcResult {
+    /// Positive input channel.
+    pub positive: AdcChannel,
+    /// Negative input channel.
+    pub negative: AdcChannel,
+    /// Conversion value, left aligned to 16 bits.
+    pub value: u16,
+}
+
+/// Managed Analog-to-Digital Converter peripheral.
+///
+/// Conversions are started by software. Selecting a hardware trigger on the converter
+/// side is not supported yet; PWM only has its trigger event selected by
+/// [`GroupConfig::set_adc_trigger_source`](crate::pwm::GroupConfig::set_adc_trigger_source).
 pub struct Adc<ADC> {
     adc: ADC,
+    input_mode: InputMode,
+    fifo_threshold: FifoThreshold,
 }
 
 impl<ADC: Deref<Target = RegisterBlock>> Adc<ADC> {
+    /// Create a new ADC instance with configuration.
     #[inline]
-    pub fn new(adc: ADC) -> Self {
+    pub fn new(adc: ADC, config: AdcConfig) -> Self {
         unsafe {
             adc.gpadc_command.modify(|v| v.enable_global());
             adc.gpadc_command.modify(|v| v.enable_software_reset());
             adc.gpadc_command.modify(|v| v.disable_software_reset());
+            adc.gpadc_config_2.modify(|v| {
+                let v = v.set_voltage_reference(config.voltage_reference);
+                match config.input_mode {
+                    InputMode::SingleEnded => v
+                        .disable_differential_mode()
+                        .disable_pga()
+                        .set_pga_gain(PgaGain::X1),
+                    InputMode::Differential => match config.gain {
+                        PgaGain::X1 => v
+                            .enable_differential_mode()
+                            .disable_pga()
+                            .set_pga_gain(PgaGain::X1),
+                        gain => v.enable_differential_mode().enable_pga().set_pga_gain(gain),
+                    },
+                }
+            });
+            adc.gpadc_config_1
+                .modify(|v| v.set_oversample_ratio(config.oversample));
+        }
+        Self {
+            adc,
+            input_mode: config.input_mode,
+            fifo_threshold: FifoThreshold::One,
         }
-        Self { adc }
     }
 
+    /// Set number of results in queue to raise ready flag and interrupt.
+    ///
+    /// Async conversions wake once per `threshold` results instead of once per result.
+    #[inline]
+    pub fn set_adc_fifo_threshold(&mut self, threshold: FifoThreshold) {
+        unsafe {
+            self.adc
+                .gpadc_config
+                .modify(|v| v.set_fifo_threshold(threshold))
+        };
+        self.fifo_threshold = threshold;
+    }
+
+    /// Select positive and negative input channels.
+    ///
+    /// Returns an error if the two channels do not form a legal pair under current input mode;
+    /// channel selection is left unchanged in this case.
+    #[inline]
+    pub fn select_channels(
+        &mut self,
+        positive: AdcChannel,
+        negative: AdcChannel,
+    ) -> Result<(), ConfigError> {
+        check_channel_pair(self.input_mode, positive, negative)?;
+        unsafe {
+            self.adc.gpadc_command.modify(|v| {
+                let v = v
+                    .set_positive_channel(positive)
+                    .set_negative_channel(negative);
+                match self.input_mode {
+                    InputMode::SingleEnded => v.enable_negative_gnd(),
+                    InputMode::Differential => v.disable_negative_gnd(),
+                }
+            });
+        }
+        Ok(())
+    }
+
+    /// Convert each channel pair in `channels` once in a single scan sweep.
+    ///
+    /// Each entry is a positive and negative channel pair; in single-ended mode the negative
+    /// channel should be `Gnd`. Results are returned in the order of `channels`. Each result
+    /// read from hardware queue is matched against the first pending entry with the same channel
+    /// pair, thus duplicate entries are filled in order of conversion.
+    pub fn adc_scan(
+        &mut self,
+        channels: &[(AdcChannel, AdcChannel)],
+    ) -> Result<heapless::Vec<AdcResult, SCAN_LENGTH_MAX>, ConfigError> {
+        if channels.is_empty() || channels.len() > SCAN_LENGTH_MAX {
+            return Err(ConfigError::IllegalScanLength);
+        }
+        for &(positive, negative) in channels {
+            check_channel_pair(self.input_mode, positive, negative)?;
+        }
+        let (mut pos1, mut pos2) = (AdcConverationSequence1(0), AdcConverationSequence2(0));
+        let (mut neg1, mut neg2) = (AdcConverationSequence3(0), AdcConverationSequence4(0));
+        for (i, &(positive, negative)) in channels.iter().enumerate() {
+            if i < 6 {
+                pos1 = pos1.set_channel(i, positive);
+                neg1 = neg1.set_channel(i, negative);
+            } else {
+                pos2 = pos2.set_channel(i - 6, positive);
+                neg2 = neg2.set_channel(i - 6, negative);
+            }
+        }
+        unsafe {
+            self.adc.adc_converation_sequence_1.write(pos1);
+            self.adc.adc_converation_sequence_2.write(pos2);
+            self.adc.adc_converation_sequence_3.write(neg1);
+            self.adc.adc_converation_sequence_4.write(neg2);
+            self.adc.gpadc_config_1.modify(|v| {
+                v.disable_continuous_conversion()
+                    .set_scan_length(channels.len() as u8)
+                    .enable_scan()
+            });
+            self.adc.gpadc_command.modify(|v| match self.input_mode {
+                InputMode::SingleEnded => v.enable_negative_gnd(),
+                InputMode::Differential => v.disable_negative_gnd(),
+            });
+            self.adc.gpadc_config.modify(|v| v.clear_fifo());
+            self.adc.gpadc_command.modify(|v| v.stop_conversion());
+            self.adc.gpadc_command.modify(|v| v.start_conversion());
+        }
+
+        let mut slots: [Option<AdcResult>; SCAN_LENGTH_MAX] = [None; SCAN_LENGTH_MAX];
+        for _ in 0..channels.len() {
+            while self.adc.gpadc_config.read().fifo_data_count() == 0 {
+                core::hint::spin_loop();
+            }
+            let word = self.adc.gpadc_dma_rdata.read();
+            let result = AdcResult {
+                positive: word.positive_channel(),
+                negative: word.negative_channel(),
+                value: word.data(),
+            };
+            let slot = channels
+                .iter()
+                .zip(slots.iter_mut())
+                .find(|((p, n), slot)| {
+                    slot.is_none() && *p == result.positive && *n == result.negative
+                })
+                .map(|(_, slot)| slot);
+            if let Some(slot) = slot {
+                *slot = Some(result);
+            }
+        }
+
+        unsafe {
+            self.adc.gpadc_command.modify(|v| v.stop_conversion());
+            self.adc.gpadc_config_1.modify(|v| v.disable_scan());
+        }
+        Ok(slots
+            .iter()
+            .zip(channels)
+            .map(|(slot, &(positive, negative))| {
+                slot.unwrap_or(AdcResult {
+                    positive,
+                    negative,
+                    value: 0,
+                })
+            })
+            .collect())
+    }
+
+    /// Convert selected channels once, returning the result left aligned to 16 bits.
+    #[inline]
+    fn convert_once(&mut self) -> u16 {
+        let adc = &self.adc;
+        unsafe {
+            adc.gpadc_config.modify(|v| v.clear_fifo());
+            adc.gpadc_command.modify(|v| v.stop_conversion());
+            adc.gpadc_command.modify(|v| v.start_conversion());
+        }
+        while adc.gpadc_config.read().fifo_data_count() == 0 {
+            core::hint::spin_loop();
+        }
+        let value = adc.gpadc_dma_rdata.read().data();
+        unsafe { adc.gpadc_command.modify(|v| v.stop_conversion()) };
+        value
+    }
+
+    /// Release the ADC instance.
     #[inline]
     pub fn free(self) -> ADC {
         unsafe {
@@ -192,7 +1171,11 @@ impl<ADC: Deref<Target = RegisterBlock>> Adc<ADC> {
 
 #[cfg(test)]
 mod tests {
-    use super::RegisterBlock;
+    use super::{
+        check_channel_pair, AdcChannel, AdcConverationSequence1, AdcConverationSequence4,
+        ConfigError, FifoThreshold, GpadcCommand, GpadcConfig, GpadcConfig1, GpadcConfig2,
+        GpadcDmaRdata, InputMode, OversampleRatio, PgaGain, RegisterBlock, VoltageReference,
+    };
     use memoffset::offset_of;
 
     #[test]
@@ -221,4 +1204,233 @@ mod tests {
         assert_eq!(offset_of!(RegisterBlock, gpadc_raw_result), 0x934);
         assert_eq!(offset_of!(RegisterBlock, gpadc_define), 0x938);
     }
+
+    #[test]
+    fn struct_gpadc_command_functions() {
+        let mut val = GpadcCommand(0x0);
+
+        val = val.start_conversion();
+        assert_eq!(val.0, 0x0000_0002);
+        assert!(val.is_conversion_started());
+        val = val.stop_conversion();
+        assert_eq!(val.0, 0x0000_0000);
+        assert!(!val.is_conversion_started());
+
+        val = val.set_negative_channel(AdcChannel::Gnd);
+        assert_eq!(val.0, 0x0000_00b8);
+        assert_eq!(val.negative_channel(), AdcChannel::Gnd);
+        val = val.set_negative_channel(AdcChannel::Channel0);
+        assert_eq!(val.0, 0x0000_0000);
+
+        val = val.set_positive_channel(AdcChannel::Channel11);
+        assert_eq!(val.0, 0x0000_0b00);
+        assert_eq!(val.positive_channel(), AdcChannel::Channel11);
+        val = val.set_positive_channel(AdcChannel::Channel0);
+
+        val = val.enable_negative_gnd();
+        assert_eq!(val.0, 0x0000_2000);
+        assert!(val.is_negative_gnd_enabled());
+        val = val.disable_negative_gnd();
+        assert_eq!(val.0, 0x0000_0000);
+        assert!(!val.is_negative_gnd_enabled());
+    }
+
+    #[test]
+    fn struct_gpadc_config_2_functions() {
+        let mut val = GpadcConfig2(0x0);
+
+        val = val.enable_differential_mode();
+        assert_eq!(val.0, 0x0000_0004);
+        assert!(val.is_differential_mode_enabled());
+        val = val.disable_differential_mode();
+        assert_eq!(val.0, 0x0000_0000);
+        assert!(!val.is_differential_mode_enabled());
+
+        val = val.set_voltage_reference(VoltageReference::Internal2V);
+        assert_eq!(val.0, 0x0000_0008);
+        assert_eq!(val.voltage_reference(), VoltageReference::Internal2V);
+        val = val.set_voltage_reference(VoltageReference::Internal3V2);
+        assert_eq!(val.0, 0x0000_0000);
+        assert_eq!(val.voltage_reference(), VoltageReference::Internal3V2);
+
+        val = val.enable_pga();
+        assert_eq!(val.0, 0x0000_2000);
+        assert!(val.is_pga_enabled());
+        val = val.disable_pga();
+        assert_eq!(val.0, 0x0000_0000);
+        assert!(!val.is_pga_enabled());
+
+        val = val.set_pga2_gain(0x7);
+        assert_eq!(val.0, 0x01c0_0000);
+        assert_eq!(val.pga2_gain(), 0x7);
+        val = val.set_pga2_gain(0x0);
+        val = val.set_pga1_gain(0x7);
+        assert_eq!(val.0, 0x0e00_0000);
+        assert_eq!(val.pga1_gain(), 0x7);
+        val = val.set_pga1_gain(0x0);
+
+        val = val.set_pga_gain(PgaGain::X8);
+        assert_eq!(val.0, 0x0600_0000);
+        val = val.set_pga_gain(PgaGain::X32);
+        assert_eq!(val.0, 0x0680_0000);
+        val = val.set_pga_gain(PgaGain::X1);
+        assert_eq!(val.0, 0x0000_0000);
+
+        val = val.enable_vbat();
+        assert_eq!(val.0, 0x0000_0010);
+        assert!(val.is_vbat_enabled());
+        val = val.disable_vbat();
+        assert_eq!(val.0, 0x0000_0000);
+        assert!(!val.is_vbat_enabled());
+
+        val = val.enable_tsen();
+        assert_eq!(val.0, 0x0000_0040);
+        assert!(val.is_tsen_enabled());
+        val = val.disable_tsen();
+        assert_eq!(val.0, 0x0000_0000);
+        assert!(!val.is_tsen_enabled());
+
+        val = val.enable_tsen_external();
+        assert_eq!(val.0, 0x0000_0020);
+        assert!(val.is_tsen_external_enabled());
+        val = val.disable_tsen_external();
+        assert_eq!(val.0, 0x0000_0000);
+        assert!(!val.is_tsen_external_enabled());
+
+        val = val.enable_tsen_vbe_low();
+        assert_eq!(val.0, 0x8000_0000);
+        assert!(val.is_tsen_vbe_low_enabled());
+        val = val.disable_tsen_vbe_low();
+        assert_eq!(val.0, 0x0000_0000);
+        assert!(!val.is_tsen_vbe_low_enabled());
+    }
+
+    #[test]
+    fn function_check_channel_pair() {
+        use AdcChannel::*;
+        use InputMode::*;
+        assert_eq!(check_channel_pair(SingleEnded, Channel3, Gnd), Ok(()));
+        assert_eq!(check_channel_pair(SingleEnded, VbatHalf, Gnd), Ok(()));
+        assert_eq!(
+            check_channel_pair(SingleEnded, Channel3, Channel4),
+            Err(ConfigError::IllegalSingleEndedNegative)
+        );
+        assert_eq!(
+            check_channel_pair(SingleEnded, Gnd, Gnd),
+            Err(ConfigError::IllegalPositiveChannel)
+        );
+        assert_eq!(check_channel_pair(Differential, Channel3, Channel4), Ok(()));
+        assert_eq!(check_channel_pair(Differential, TsenP, TsenN), Ok(()));
+        assert_eq!(
+            check_channel_pair(Differential, Channel3, Channel3),
+            Err(ConfigError::SameChannel)
+        );
+        assert_eq!(
+            check_channel_pair(Differential, Channel3, Gnd),
+            Err(ConfigError::IllegalDifferentialPair)
+        );
+        assert_eq!(
+            check_channel_pair(Differential, VbatHalf, Channel0),
+            Err(ConfigError::IllegalDifferentialPair)
+        );
+        assert_eq!(
+            check_channel_pair(Differential, TsenN, TsenP),
+            Err(ConfigError::IllegalDifferentialPair)
+        );
+    }
+
+    #[test]
+    fn struct_gpadc_config_functions_fifo() {
+        let val = GpadcConfig(0x0).clear_fifo();
+        assert_eq!(val.0, 0x0000_0002);
+        let val = GpadcConfig(0x000c_0004);
+        assert!(val.is_fifo_not_empty());
+        assert_eq!(val.fifo_data_count(), 12);
+        let val = GpadcConfig(0x0000_0038);
+        assert!(val.is_fifo_full());
+        assert!(val.is_ready());
+        assert!(val.is_fifo_overrun());
+    }
+
+    #[test]
+    fn struct_gpadc_config_functions_interrupt() {
+        let val = GpadcConfig(0x0).clear_ready().clear_fifo_overrun();
+        assert_eq!(val.0, 0x0000_0300);
+        assert_eq!(val.release_clear().0, 0x0000_0000);
+        let val = GpadcConfig(0x0).mask_ready_interrupt();
+        assert_eq!(val.0, 0x0000_1000);
+        assert!(val.is_ready_interrupt_masked());
+        assert!(!val.unmask_ready_interrupt().is_ready_interrupt_masked());
+        let val = GpadcConfig(0x0).mask_fifo_overrun_interrupt();
+        assert_eq!(val.0, 0x0000_2000);
+        assert!(val.is_fifo_overrun_interrupt_masked());
+        assert!(!val
+            .unmask_fifo_overrun_interrupt()
+            .is_fifo_overrun_interrupt_masked());
+        let val = GpadcConfig(0x0).set_fifo_threshold(FifoThreshold::Sixteen);
+        assert_eq!(val.0, 0x00c0_0000);
+        assert_eq!(val.fifo_threshold(), FifoThreshold::Sixteen);
+        assert_eq!(val.fifo_threshold().count(), 16);
+        let val = GpadcConfig(0x0).set_fifo_threshold(FifoThreshold::Four);
+        assert_eq!(val.0, 0x0040_0000);
+        assert_eq!(val.fifo_threshold(), FifoThreshold::Four);
+    }
+
+    #[test]
+    fn struct_gpadc_dma_rdata_functions() {
+        let val = GpadcDmaRdata(0x00b7_1230);
+        assert_eq!(val.positive_channel(), AdcChannel::Channel5);
+        assert_eq!(val.negative_channel(), AdcChannel::Gnd);
+        assert_eq!(val.data(), 0x1230);
+    }
+
+    #[test]
+    fn struct_gpadc_config_1_functions() {
+        let mut val = GpadcConfig1(0x0);
+
+        val = val.enable_continuous_conversion();
+        assert_eq!(val.0, 0x0000_0002);
+        assert!(val.is_continuous_conversion_enabled());
+        val = val.disable_continuous_conversion();
+        assert_eq!(val.0, 0x0000_0000);
+
+        val = val.set_oversample_ratio(OversampleRatio::X256);
+        assert_eq!(val.0, 0x0000_0010);
+        assert_eq!(val.oversample_ratio(), OversampleRatio::X256);
+        val = val.set_oversample_ratio(OversampleRatio::X16);
+        assert_eq!(val.0, 0x0000_0004);
+        assert_eq!(val.oversample_ratio(), OversampleRatio::X16);
+        val = val.set_oversample_ratio(OversampleRatio::X1);
+        assert_eq!(val.0, 0x0000_0000);
+
+        val = val.set_scan_length(12);
+        assert_eq!(val.0, 0x0160_0000);
+        assert_eq!(val.scan_length(), 12);
+        val = val.set_scan_length(1);
+        assert_eq!(val.0, 0x0000_0000);
+        assert_eq!(val.scan_length(), 1);
+
+        val = val.enable_scan();
+        assert_eq!(val.0, 0x0200_0000);
+        assert!(val.is_scan_enabled());
+        val = val.disable_scan();
+        assert_eq!(val.0, 0x0000_0000);
+        assert!(!val.is_scan_enabled());
+    }
+
+    #[test]
+    fn struct_adc_converation_sequence_functions() {
+        let mut val = AdcConverationSequence1(0x0);
+        val = val.set_channel(0, AdcChannel::Channel3);
+        val = val.set_channel(5, AdcChannel::Channel11);
+        assert_eq!(val.0, 0x1600_0003);
+        assert_eq!(val.channel(0), AdcChannel::Channel3);
+        assert_eq!(val.channel(5), AdcChannel::Channel11);
+        val = val.set_channel(5, AdcChannel::Channel0);
+        assert_eq!(val.0, 0x0000_0003);
+
+        let val = AdcConverationSequence4(0x0).set_channel(1, AdcChannel::Gnd);
+        assert_eq!(val.0, 0x0000_02e0);
+        assert_eq!(val.channel(1), AdcChannel::Gnd);
+    }
 }
@@ -0,0 +1,249 @@
+//! Interrupt driven async/await Analog-to-Digital Converter.
+
+use super::{Adc, AdcChannel, AdcResult, Error, FifoThreshold, RegisterBlock};
+use core::{
+    future::Future,
+    ops::Deref,
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::{Context, Poll},
+};
+
+/// Waker registry as the state for an async/await ADC peripheral.
+#[derive(Debug)]
+pub struct AdcState {
+    ready: atomic_waker::AtomicWaker,
+    ref_to_adc: AtomicUsize,
+}
+
+impl AdcState {
+    /// Creates the waker registry for an ADC peripheral.
+    #[inline]
+    pub const fn new() -> AdcState {
+        AdcState {
+            ready: atomic_waker::AtomicWaker::new(),
+            ref_to_adc: AtomicUsize::new(0),
+        }
+    }
+    /// Use this waker registry to handle interrupt.
+    ///
+    /// Ready and overrun interrupts are masked until the waiting task polls again, as the
+    /// ready flag stays raised as long as the queue holds at least threshold results.
+    #[inline]
+    pub fn on_interrupt(&self) {
+        let adc = unsafe { &*(self.ref_to_adc.load(Ordering::Acquire) as *const RegisterBlock) };
+        let config = adc.gpadc_config.read();
+        if config.is_ready() || config.is_fifo_overrun() {
+            unsafe {
+                adc.gpadc_config.modify(|v| {
+                    v.mask_ready_interrupt()
+                        .mask_fifo_overrun_interrupt()
+                        .clear_ready()
+                });
+                adc.gpadc_config.modify(|v| v.release_clear());
+            }
+            self.ready.wake();
+        }
+    }
+}
+
+impl Default for AdcState {
+    /// Creates an empty waker registry.
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Managed async/await Analog-to-Digital Converter peripheral.
+pub struct AsyncAdc<ADC> {
+    adc: Adc<ADC>,
+    state: &'static AdcState,
+}
+
+impl<ADC: Deref<Target = RegisterBlock>> AsyncAdc<ADC> {
+    /// Creates the async/await ADC from configured ADC instance and a waker registry.
+    #[inline]
+    pub fn new(adc: Adc<ADC>, state: &'static AdcState) -> Self {
+        unsafe {
+            adc.adc.gpadc_config.modify(|v| {
+                v.mask_ready_interrupt()
+                    .mask_fifo_overrun_interrupt()
+                    .clear_ready()
+                    .clear_fifo_overrun()
+            });
+            adc.adc.gpadc_config.modify(|v| v.release_clear());
+        }
+        state
+            .ref_to_adc
+            .store(&*adc.adc as *const _ as usize, Ordering::Release);
+        AsyncAdc { adc, state }
+    }
+
+    /// Release async/await ADC and return the ADC instance.
+    #[inline]
+    pub fn free(self) -> Adc<ADC> {
+        self.adc
+    }
+
+    /// Get mutable reference to the ADC instance, e.g. to select channels or set queue threshold.
+    #[inline]
+    pub fn adc_mut(&mut self) -> &mut Adc<ADC> {
+        &mut self.adc
+    }
+
+    /// Convert `channel` against ground once and return the result.
+    ///
+    /// Selects `channel` as positive input in single-ended mode, starts a single conversion
+    /// and waits for its interrupt, leaving `channel` selected afterwards. Returns
+    /// `Error::Config` if the converter is configured in differential mode.
+    pub async fn read_once(&mut self, channel: AdcChannel) -> Result<AdcResult, Error> {
+        self.adc.select_channels(channel, AdcChannel::Gnd)?;
+        let adc = &*self.adc.adc;
+        unsafe {
+            adc.gpadc_config_1
+                .modify(|v| v.disable_scan().disable_continuous_conversion());
+            // Ready flag should rise on the only result regardless of configured threshold.
+            adc.gpadc_config.modify(|v| {
+                v.set_fifo_threshold(FifoThreshold::One)
+                    .clear_fifo()
+                    .clear_ready()
+                    .clear_fifo_overrun()
+            });
+            adc.gpadc_config.modify(|v| v.release_clear());
+            adc.gpadc_command.modify(|v| v.stop_conversion());
+            adc.gpadc_command.modify(|v| v.start_conversion());
+        }
+        let ans = WaitForReady {
+            adc,
+            threshold: 1,
+            registry: &self.state.ready,
+        }
+        .await
+        .map(|()| {
+            let word = adc.gpadc_dma_rdata.read();
+            AdcResult {
+                positive: word.positive_channel(),
+                negative: word.negative_channel(),
+                value: word.data(),
+            }
+        });
+        unsafe {
+            adc.gpadc_command.modify(|v| v.stop_conversion());
+            adc.gpadc_config.modify(|v| {
+                v.mask_ready_interrupt()
+                    .mask_fifo_overrun_interrupt()
+                    .set_fifo_threshold(self.adc.fifo_threshold)
+                    .clear_fifo()
+                    .clear_fifo_overrun()
+            });
+            adc.gpadc_config.modify(|v| v.release_clear());
+        }
+        ans
+    }
+
+    /// Continuously convert selected channels until `buf` is filled.
+    ///
+    /// The task is woken each time the queue reaches the threshold set by
+    /// [`Adc::set_adc_fifo_threshold`], e.g. a 32-result capture with threshold of sixteen
+    /// wakes twice instead of 32 times. Results converted after `buf` is filled are dropped.
+    ///
+    /// Returns `Error::FifoOverrun` if results were lost because the task was not polled
+    /// in time to read the queue.
+    pub async fn convert(&mut self, buf: &mut [AdcResult]) -> Result<(), Error> {
+        if buf.is_empty() {
+            return Ok(());
+        }
+        let adc = &*self.adc.adc;
+        unsafe {
+            adc.gpadc_config_1
+                .modify(|v| v.disable_scan().enable_continuous_conversion());
+            adc.gpadc_config
+                .modify(|v| v.clear_fifo().clear_ready().clear_fifo_overrun());
+            adc.gpadc_config.modify(|v| v.release_clear());
+            adc.gpadc_command.modify(|v| v.stop_conversion());
+            adc.gpadc_command.modify(|v| v.start_conversion());
+        }
+        let threshold = self.adc.fifo_threshold.count();
+        let mut filled = 0;
+        let ans = loop {
+            if let Err(e) = (WaitForReady {
+                adc,
+                threshold,
+                registry: &self.state.ready,
+            })
+            .await
+            {
+                break Err(e);
+            }
+            let count = adc.gpadc_config.read().fifo_data_count() as usize;
+            for slot in buf[filled..].iter_mut().take(count) {
+                let word = adc.gpadc_dma_rdata.read();
+                *slot = AdcResult {
+                    positive: word.positive_channel(),
+                    negative: word.negative_channel(),
+                    value: word.data(),
+                };
+                filled += 1;
+            }
+            if filled == buf.len() {
+                break Ok(());
+            }
+        };
+        unsafe {
+            adc.gpadc_command.modify(|v| v.stop_conversion());
+            adc.gpadc_config_1
+                .modify(|v| v.disable_continuous_conversion());
+            adc.gpadc_config.modify(|v| {
+                v.mask_ready_interrupt()
+                    .mask_fifo_overrun_interrupt()
+                    .clear_fifo()
+                    .clear_fifo_overrun()
+            });
+            adc.gpadc_config.modify(|v| v.release_clear());
+        }
+        ans
+    }
+}
+
+struct WaitForReady<'r> {
+    adc: &'r RegisterBlock,
+    threshold: u8,
+    registry: &'r atomic_waker::AtomicWaker,
+}
+
+impl WaitForReady<'_> {
+    #[inline]
+    fn check(&self) -> Option<Result<(), Error>> {
+        let config = self.adc.gpadc_config.read();
+        if config.is_fifo_overrun() {
+            Some(Err(Error::FifoOverrun))
+        } else if config.fifo_data_count() >= self.threshold {
+            Some(Ok(()))
+        } else {
+            None
+        }
+    }
+}
+
+impl Future for WaitForReady<'_> {
+    type Output = Result<(), Error>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Some(ans) = self.check() {
+            return Poll::Ready(ans);
+        }
+        self.registry.register(cx.waker());
+        unsafe {
+            self.adc
+                .gpadc_config
+                .modify(|v| v.unmask_ready_interrupt().unmask_fifo_overrun_interrupt())
+        };
+        // Results may have arrived before the interrupt was unmasked.
+        match self.check() {
+            Some(ans) => Poll::Ready(ans),
+            None => Poll::Pending,
+        }
+    }
+}
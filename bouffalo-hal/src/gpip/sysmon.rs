@@ -0,0 +1,99 @@
+use super::{AdcChannel, RegisterBlock, TemperatureMonitor};
+use core::ops::Deref;
+
+/// Converter reference voltage used by system monitor, in millivolts.
+const VREF_MILLIVOLTS: u32 = 3200;
+
+/// Chip temperature and supply voltage in engineering units.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SysMonReading {
+    /// Die temperature in degrees Celsius.
+    pub temp_c: f32,
+    /// Supply voltage in millivolts.
+    pub vdd_mv: u32,
+}
+
+/// System monitor reporting chip temperature and supply voltage.
+///
+/// Temperature comes from the calibrated internal sensor, see [`TemperatureMonitor`];
+/// supply voltage is measured on the `VbatHalf` channel through the internal divider.
+/// Both share the general purpose converter, which is switched between the two inputs
+/// on every reading, thus the converter should not be used elsewhere at the same time.
+pub struct SysMon<ADC> {
+    temperature: TemperatureMonitor<ADC>,
+}
+
+impl<ADC: Deref<Target = RegisterBlock>> SysMon<ADC> {
+    /// Create a system monitor over the converter.
+    ///
+    /// `tsen_offset` is the temperature sensor trim value in efuse, or
+    /// [`DEFAULT_TSEN_OFFSET`](super::DEFAULT_TSEN_OFFSET) on parts without a valid trim.
+    #[inline]
+    pub fn new(adc: ADC, tsen_offset: u16) -> Self {
+        let temperature = TemperatureMonitor::new(adc, tsen_offset);
+        unsafe {
+            temperature
+                .adc
+                .adc
+                .gpadc_config_2
+                .modify(|v| v.enable_vbat())
+        };
+        Self { temperature }
+    }
+    /// Read chip temperature and supply voltage.
+    #[inline]
+    pub fn read(&mut self) -> SysMonReading {
+        SysMonReading {
+            temp_c: self.temperature.read_celsius(),
+            vdd_mv: self.read_vdd_mv(),
+        }
+    }
+    /// Read supply voltage in millivolts.
+    #[inline]
+    pub fn read_vdd_mv(&mut self) -> u32 {
+        let adc = &mut self.temperature.adc;
+        adc.select_channels(AdcChannel::VbatHalf, AdcChannel::Gnd)
+            .expect("battery voltage channel pair is legal in single-ended mode");
+        let value = adc.convert_once();
+        adc.select_channels(AdcChannel::TsenP, AdcChannel::Gnd)
+            .expect("temperature sensor channel pair is legal in single-ended mode");
+        vbat_half_millivolts(value)
+    }
+    /// Get temperature monitor, e.g. to set over-temperature threshold.
+    #[inline]
+    pub fn temperature(&mut self) -> &mut TemperatureMonitor<ADC> {
+        &mut self.temperature
+    }
+    /// Disable sensors and release the converter.
+    #[inline]
+    pub fn free(self) -> ADC {
+        unsafe {
+            self.temperature
+                .adc
+                .adc
+                .gpadc_config_2
+                .modify(|v| v.disable_vbat())
+        };
+        self.temperature.free()
+    }
+}
+
+/// Convert a `VbatHalf` result left aligned to 16 bits into supply millivolts.
+#[inline]
+const fn vbat_half_millivolts(value: u16) -> u32 {
+    // Divider halves the supply voltage before conversion.
+    (value as u32 * VREF_MILLIVOLTS * 2 + (1 << 15)) >> 16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::vbat_half_millivolts;
+
+    #[test]
+    fn function_vbat_half_millivolts() {
+        assert_eq!(vbat_half_millivolts(0), 0);
+        assert_eq!(vbat_half_millivolts(0x8000), 3200);
+        assert_eq!(vbat_half_millivolts(0x8400), 3300);
+        assert_eq!(vbat_half_millivolts(0xffff), 6400);
+    }
+}
@@ -0,0 +1,136 @@
+use super::{
+    Adc, AdcChannel, AdcConfig, InputMode, OversampleRatio, RegisterBlock, VoltageReference,
+};
+use core::ops::Deref;
+
+/// Temperature sensor offset used when efuse holds no valid trim value.
+pub const DEFAULT_TSEN_OFFSET: u16 = 2042;
+
+/// Result counts per degree Celsius of diode voltage difference, on 16-bit results.
+const COUNTS_PER_CELSIUS: f32 = 7.753;
+
+/// Calibrated internal temperature sensor for continuous monitoring.
+///
+/// Each reading converts the internal diode voltage under high and low bias currents;
+/// their difference is proportional to absolute temperature, and the per-chip offset
+/// from efuse trim cancels process variation. Converter runs with 256x oversampling,
+/// thus one reading takes two conversions of about 130 microseconds each.
+///
+/// Converter has no hardware comparator on temperature. For thermal throttling, call
+/// [`read_celsius`](Self::read_celsius) periodically, e.g. from a timer interrupt;
+/// each reading is checked against the threshold set by
+/// [`set_threshold`](Self::set_threshold), latching the over-temperature flag.
+pub struct TemperatureMonitor<ADC> {
+    pub(super) adc: Adc<ADC>,
+    offset: u16,
+    threshold: Option<f32>,
+    over_temperature: bool,
+}
+
+impl<ADC: Deref<Target = RegisterBlock>> TemperatureMonitor<ADC> {
+    /// Create a temperature monitor over the converter.
+    ///
+    /// `tsen_offset` is the temperature sensor trim value in efuse, or
+    /// [`DEFAULT_TSEN_OFFSET`] on parts without a valid trim.
+    #[inline]
+    pub fn new(adc: ADC, tsen_offset: u16) -> Self {
+        let config = AdcConfig::default()
+            .set_input_mode(InputMode::SingleEnded)
+            .set_voltage_reference(VoltageReference::Internal3V2)
+            .set_oversample(OversampleRatio::X256);
+        let mut adc = Adc::new(adc, config);
+        adc.select_channels(AdcChannel::TsenP, AdcChannel::Gnd)
+            .expect("temperature sensor channel pair is legal in single-ended mode");
+        unsafe {
+            adc.adc
+                .gpadc_config_1
+                .modify(|v| v.disable_continuous_conversion().disable_scan());
+            adc.adc
+                .gpadc_config_2
+                .modify(|v| v.disable_tsen_external().enable_tsen());
+        }
+        Self {
+            adc,
+            offset: tsen_offset,
+            threshold: None,
+            over_temperature: false,
+        }
+    }
+    /// Read calibrated chip temperature in degrees Celsius.
+    #[inline]
+    pub fn read_celsius(&mut self) -> f32 {
+        let (high, low) = self.read_raw();
+        let celsius = tsen_celsius(high, low, self.offset);
+        if self.threshold.is_some_and(|threshold| celsius >= threshold) {
+            self.over_temperature = true;
+        }
+        celsius
+    }
+    /// Convert diode voltage under high and low bias currents, in 16-bit results.
+    #[inline]
+    pub fn read_raw(&mut self) -> (u16, u16) {
+        unsafe {
+            self.adc
+                .adc
+                .gpadc_config_2
+                .modify(|v| v.disable_tsen_vbe_low())
+        };
+        let high = self.adc.convert_once();
+        unsafe {
+            self.adc
+                .adc
+                .gpadc_config_2
+                .modify(|v| v.enable_tsen_vbe_low())
+        };
+        let low = self.adc.convert_once();
+        (high, low)
+    }
+    /// Set over-temperature threshold in degrees Celsius, or `None` to disable it.
+    #[inline]
+    pub fn set_threshold(&mut self, threshold: Option<f32>) {
+        self.threshold = threshold;
+    }
+    /// Check if any reading has reached the over-temperature threshold.
+    #[inline]
+    pub fn is_over_temperature(&self) -> bool {
+        self.over_temperature
+    }
+    /// Clear the over-temperature flag.
+    #[inline]
+    pub fn clear_over_temperature(&mut self) {
+        self.over_temperature = false;
+    }
+    /// Disable temperature sensor and release the converter.
+    #[inline]
+    pub fn free(self) -> ADC {
+        unsafe {
+            self.adc
+                .adc
+                .gpadc_config_2
+                .modify(|v| v.disable_tsen().disable_tsen_vbe_low())
+        };
+        self.adc.free()
+    }
+}
+
+/// Compute temperature from diode results under high and low bias currents.
+#[inline]
+fn tsen_celsius(high: u16, low: u16, offset: u16) -> f32 {
+    let difference = high.abs_diff(low);
+    (difference as f32 - offset as f32) / COUNTS_PER_CELSIUS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{tsen_celsius, DEFAULT_TSEN_OFFSET};
+
+    #[test]
+    fn function_tsen_celsius() {
+        assert_eq!(tsen_celsius(2042, 0, DEFAULT_TSEN_OFFSET), 0.0);
+        assert_eq!(tsen_celsius(0, 2042, DEFAULT_TSEN_OFFSET), 0.0);
+        let celsius = tsen_celsius(40_000 + 2_042 + 194, 40_000, DEFAULT_TSEN_OFFSET);
+        assert!((celsius - 25.02).abs() < 0.01);
+        let celsius = tsen_celsius(40_000 + 2_042 - 78, 40_000, DEFAULT_TSEN_OFFSET);
+        assert!((celsius + 10.06).abs() < 0.01);
+    }
+}
@@ -1,5 +1,9 @@
 //! Hibernation (deep-sleep) control peripheral.
+use crate::glb::Pull;
 use core::cell::UnsafeCell;
+use core::ops::Deref;
+use core::sync::atomic::{compiler_fence, Ordering};
+use embedded_time::rate::Hertz;
 
 use volatile_register::{RO, RW, WO};
 
@@ -8,21 +12,21 @@ use volatile_register::{RO, RW, WO};
 pub struct RegisterBlock {
     /// todo: fill in all registers
     /// Miscellaneous control register
-    pub control: RW<u32>,
+    pub control: RW<Control>,
     /// Low bits of hibernate time
     pub time_lo: RW<u32>,
     /// High bits of hibernate time
     pub time_hi: RW<u32>,
     /// Low bits of Real-Time Clock time
     pub rtc_time_lo: RO<u32>,
-    /// High bits of Real-Time Clock time
-    pub rtc_time_hi: RO<u32>,
+    /// High bits of Real-Time Clock time, and time latch control
+    pub rtc_time_hi: RW<u32>,
     /// Hibernate interrupt contol
-    pub interrupt_mode: RW<u32>,
+    pub interrupt_mode: RW<InterruptMode>,
     /// Hibernate interrupt state
-    pub interrupt_state: RO<u32>,
+    pub interrupt_state: RO<InterruptState>,
     /// Clear hibernate interrupt
-    pub interrupt_clear: WO<u32>,
+    pub interrupt_clear: WO<InterruptClear>,
     /// Passive infrared sensor configuration
     pub pir_config: RW<u32>,
     /// Passive infrared sensor voltage threshold
@@ -30,24 +34,114 @@ pub struct RegisterBlock {
     /// Passive infrared sensor time interval
     pub pir_interval: RW<u32>,
     /// Brown-out reset function configuration
-    pub bor_config: RW<u32>,
+    pub bor_config: RW<BorControl>,
     /// Global hibernate configuration
     pub global: GLOBAL,
     /// Static Random-Access Memory hibernate control
     pub sram: RW<u32>,
     /// Always-on pad control register 0
-    pub pad_control_0: RW<u32>,
+    pub pad_control_0: RW<AonPadControl0>,
     /// Always-on pad control register 1
-    pub pad_control_1: RW<u32>,
-    _reserved0: [u8; 448],
+    pub pad_control_1: RW<AonPadControl1>,
+    _reserved0: [u8; 0xc0],
+    /// Always-on scratch registers retained across hibernation.
+    pub scratch: [RW<u32>; SCRATCH_COUNT],
+    _reserved1: [u8; 0xf0],
     /// 32-kHz internal RC oscillator control
-    pub rc32k: RW<u32>,
+    pub rc32k: RW<Rc32kControl>,
     /// External crystal oscillator control
-    pub xtal32k: RW<u32>,
+    pub xtal32k: RW<Xtal32kControl>,
     /// Real-Time Clock control and reset register 0
     pub rtc_control_0: RW<u32>,
     /// Real-Time Clock control and reset register 1
     pub rtc_control_1: RW<u32>,
+    _reserved2: [u8; 0x674],
+    /// Always-on temperature sensor reference and crystal oscillator status
+    pub xtal_status: RO<XtalStatus>,
+}
+
+/// Hibernate miscellaneous control register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct Control(u32);
+
+impl Control {
+    const RTC_ENABLE: u32 = 1 << 0;
+    const RTC_COMPARE: u32 = 1 << 1;
+    const LDO18_IO: u32 = 1 << 5;
+    const HIBERNATE: u32 = 1 << 7;
+    const POWER_DOWN_CORE: u32 = 1 << 9;
+
+    /// Enable Real-Time Clock counter.
+    #[inline]
+    pub const fn enable_rtc(self) -> Self {
+        Self(self.0 | Self::RTC_ENABLE)
+    }
+    /// Disable Real-Time Clock counter.
+    #[inline]
+    pub const fn disable_rtc(self) -> Self {
+        Self(self.0 & !Self::RTC_ENABLE)
+    }
+    /// Check if Real-Time Clock counter is enabled.
+    #[inline]
+    pub const fn is_rtc_enabled(self) -> bool {
+        self.0 & Self::RTC_ENABLE != 0
+    }
+    /// Enable wakeup when Real-Time Clock reaches hibernate time.
+    #[inline]
+    pub const fn enable_rtc_compare(self) -> Self {
+        Self(self.0 | Self::RTC_COMPARE)
+    }
+    /// Disable wakeup on Real-Time Clock compare.
+    #[inline]
+    pub const fn disable_rtc_compare(self) -> Self {
+        Self(self.0 & !Self::RTC_COMPARE)
+    }
+    /// Check if wakeup on Real-Time Clock compare is enabled.
+    #[inline]
+    pub const fn is_rtc_compare_enabled(self) -> bool {
+        self.0 & Self::RTC_COMPARE != 0
+    }
+    /// Keep 1.8-V I/O supply powered in hibernation, e.g. for pseudo static RAM.
+    #[inline]
+    pub const fn enable_ldo18_io(self) -> Self {
+        Self(self.0 | Self::LDO18_IO)
+    }
+    /// Power down 1.8-V I/O supply in hibernation.
+    #[inline]
+    pub const fn disable_ldo18_io(self) -> Self {
+        Self(self.0 & !Self::LDO18_IO)
+    }
+    /// Check if 1.8-V I/O supply is kept powered in hibernation.
+    #[inline]
+    pub const fn is_ldo18_io_enabled(self) -> bool {
+        self.0 & Self::LDO18_IO != 0
+    }
+    /// Request entering hibernation.
+    #[inline]
+    pub const fn enter_hibernate(self) -> Self {
+        Self(self.0 | Self::HIBERNATE)
+    }
+    /// Check if hibernation is requested.
+    #[inline]
+    pub const fn is_hibernate_requested(self) -> bool {
+        self.0 & Self::HIBERNATE != 0
+    }
+    /// Power down core domain in hibernation.
+    #[inline]
+    pub const fn power_down_core(self) -> Self {
+        Self(self.0 | Self::POWER_DOWN_CORE)
+    }
+    /// Keep core domain powered in hibernation.
+    #[inline]
+    pub const fn power_up_core(self) -> Self {
+        Self(self.0 & !Self::POWER_DOWN_CORE)
+    }
+    /// Check if core domain is powered down in hibernation.
+    #[inline]
+    pub const fn is_core_powered_down(self) -> bool {
+        self.0 & Self::POWER_DOWN_CORE != 0
+    }
 }
 
 /// Global hibernate configuration register.
@@ -205,6 +299,848 @@ impl Global {
             _ => unreachable!(),
         }
     }
+    /// Check if BL702 uart clock is selected from 96-MHz PLL output.
+    ///
+    /// BL702 only has the lower uart clock select bit; uart runs on processor clock if it's clear.
+    #[inline]
+    pub const fn is_uart_clock_pll_96m(self) -> bool {
+        self.0 & Self::UART_CLOCK_SOURCE_1 != 0
+    }
+}
+
+/// Number of always-on pads that can wake the chip from hibernation.
+pub const AON_PAD_COUNT: usize = 5;
+/// GPIO number of the first always-on pad, i.e. always-on pads are GPIO9 to GPIO13.
+pub const AON_PAD_BASE: usize = 9;
+
+/// Hibernate interrupt control register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct InterruptMode(u32);
+
+impl InterruptMode {
+    const PIN_WAKEUP_TRIGGER: u32 = 0x3;
+    const PIN_WAKEUP_ASYNC: u32 = 1 << 2;
+    const PIN_WAKEUP_MASK: u32 = 0x1f << 4;
+    const BOR: u32 = 1 << 18;
+
+    /// Set trigger condition of always-on pad wakeup, shared by all pads.
+    ///
+    /// Asynchronous detection is selected, as clocks are stopped in hibernation.
+    #[inline]
+    pub const fn set_pin_wakeup_trigger(self, val: WakeupTrigger) -> Self {
+        Self((self.0 & !Self::PIN_WAKEUP_TRIGGER) | Self::PIN_WAKEUP_ASYNC | val as u32)
+    }
+    /// Get trigger condition of always-on pad wakeup.
+    #[inline]
+    pub const fn pin_wakeup_trigger(self) -> WakeupTrigger {
+        match self.0 & Self::PIN_WAKEUP_TRIGGER {
+            0 => WakeupTrigger::FallingEdge,
+            1 => WakeupTrigger::RisingEdge,
+            2 => WakeupTrigger::LowLevel,
+            3 => WakeupTrigger::HighLevel,
+            _ => unreachable!(),
+        }
+    }
+    /// Enable wakeup from always-on pad `idx`.
+    #[inline]
+    pub const fn enable_pin_wakeup(self, idx: usize) -> Self {
+        Self(self.0 & !(1 << (idx + 4)))
+    }
+    /// Disable wakeup from always-on pad `idx`.
+    #[inline]
+    pub const fn disable_pin_wakeup(self, idx: usize) -> Self {
+        Self(self.0 | (1 << (idx + 4)))
+    }
+    /// Check if wakeup from always-on pad `idx` is enabled.
+    #[inline]
+    pub const fn is_pin_wakeup_enabled(self, idx: usize) -> bool {
+        self.0 & Self::PIN_WAKEUP_MASK & (1 << (idx + 4)) == 0
+    }
+    /// Enable brown-out interrupt.
+    #[inline]
+    pub const fn enable_bor(self) -> Self {
+        Self(self.0 | Self::BOR)
+    }
+    /// Disable brown-out interrupt.
+    #[inline]
+    pub const fn disable_bor(self) -> Self {
+        Self(self.0 & !Self::BOR)
+    }
+    /// Check if brown-out interrupt is enabled.
+    #[inline]
+    pub const fn is_bor_enabled(self) -> bool {
+        self.0 & Self::BOR != 0
+    }
+}
+
+/// Always-on pad wakeup trigger condition.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum WakeupTrigger {
+    /// Falling edge.
+    FallingEdge = 0,
+    /// Rising edge.
+    RisingEdge = 1,
+    /// Low level.
+    LowLevel = 2,
+    /// High level.
+    HighLevel = 3,
+}
+
+/// Hibernate interrupt state register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct InterruptState(u32);
+
+impl InterruptState {
+    /// Check if always-on pad `idx` has triggered wakeup.
+    #[inline]
+    pub const fn has_pin_wakeup(self, idx: usize) -> bool {
+        self.0 & (1 << idx) != 0
+    }
+    /// Check if Real-Time Clock compare has triggered wakeup.
+    #[inline]
+    pub const fn has_rtc(self) -> bool {
+        self.0 & (1 << 16) != 0
+    }
+    /// Check if brown-out interrupt is pending.
+    #[inline]
+    pub const fn has_bor(self) -> bool {
+        self.0 & (1 << 18) != 0
+    }
+}
+
+/// Hibernate interrupt clear register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct InterruptClear(u32);
+
+impl InterruptClear {
+    /// Clear wakeup state of always-on pad `idx`.
+    #[inline]
+    pub const fn clear_pin_wakeup(self, idx: usize) -> Self {
+        Self(self.0 | (1 << idx))
+    }
+    /// Clear Real-Time Clock compare wakeup state.
+    #[inline]
+    pub const fn clear_rtc(self) -> Self {
+        Self(self.0 | (1 << 16))
+    }
+    /// Clear brown-out interrupt.
+    #[inline]
+    pub const fn clear_bor(self) -> Self {
+        Self(self.0 | (1 << 18))
+    }
+}
+
+/// Always-on pad control register 0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct AonPadControl0(u32);
+
+impl AonPadControl0 {
+    /// Enable input and schmitt trigger of always-on pad `idx`.
+    #[inline]
+    pub const fn enable_input(self, idx: usize) -> Self {
+        Self(self.0 | (1 << idx))
+    }
+    /// Disable input and schmitt trigger of always-on pad `idx`.
+    #[inline]
+    pub const fn disable_input(self, idx: usize) -> Self {
+        Self(self.0 & !(1 << idx))
+    }
+    /// Check if input of always-on pad `idx` is enabled.
+    #[inline]
+    pub const fn is_input_enabled(self, idx: usize) -> bool {
+        self.0 & (1 << idx) != 0
+    }
+    /// Hand over always-on pad `idx` to hibernate control during sleep.
+    #[inline]
+    pub const fn enable_aon_control(self, idx: usize) -> Self {
+        Self(self.0 | (1 << (idx + 10)))
+    }
+    /// Leave always-on pad `idx` under global peripheral control.
+    #[inline]
+    pub const fn disable_aon_control(self, idx: usize) -> Self {
+        Self(self.0 & !(1 << (idx + 10)))
+    }
+    /// Check if always-on pad `idx` is under hibernate control.
+    #[inline]
+    pub const fn is_aon_control_enabled(self, idx: usize) -> bool {
+        self.0 & (1 << (idx + 10)) != 0
+    }
+}
+
+/// Always-on pad control register 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct AonPadControl1(u32);
+
+impl AonPadControl1 {
+    /// Enable output of always-on pad `idx`.
+    #[inline]
+    pub const fn enable_output(self, idx: usize) -> Self {
+        Self(self.0 | (1 << idx))
+    }
+    /// Disable output of always-on pad `idx`.
+    #[inline]
+    pub const fn disable_output(self, idx: usize) -> Self {
+        Self(self.0 & !(1 << idx))
+    }
+    /// Check if output of always-on pad `idx` is enabled.
+    #[inline]
+    pub const fn is_output_enabled(self, idx: usize) -> bool {
+        self.0 & (1 << idx) != 0
+    }
+    /// Set pull direction of always-on pad `idx`.
+    #[inline]
+    pub const fn set_pull(self, idx: usize, val: Pull) -> Self {
+        let (down, up) = (1 << (idx + 10), 1 << (idx + 20));
+        let val = match val {
+            Pull::None => 0,
+            Pull::Up => up,
+            Pull::Down => down,
+        };
+        Self((self.0 & !(down | up)) | val)
+    }
+    /// Get pull direction of always-on pad `idx`.
+    #[inline]
+    pub const fn pull(self, idx: usize) -> Pull {
+        let (down, up) = (1 << (idx + 10), 1 << (idx + 20));
+        if self.0 & up != 0 {
+            Pull::Up
+        } else if self.0 & down != 0 {
+            Pull::Down
+        } else {
+            Pull::None
+        }
+    }
+}
+
+/// Get GPIO number of the always-on pad that woke the chip from hibernation.
+///
+/// Returns `None` if the last wakeup was not caused by an always-on pad.
+#[inline]
+pub fn wakeup_pin<HBN: Deref<Target = RegisterBlock>>(hbn: &HBN) -> Option<usize> {
+    let state = hbn.interrupt_state.read();
+    (0..AON_PAD_COUNT)
+        .find(|&idx| state.has_pin_wakeup(idx))
+        .map(|idx| idx + AON_PAD_BASE)
+}
+
+/// Clear wakeup state of all always-on pads.
+#[inline]
+pub fn clear_wakeup_pins<HBN: Deref<Target = RegisterBlock>>(hbn: &HBN) {
+    let clear =
+        (0..AON_PAD_COUNT).fold(InterruptClear::default(), |v, idx| v.clear_pin_wakeup(idx));
+    unsafe { hbn.interrupt_clear.write(clear) };
+}
+
+/// Number of always-on scratch registers.
+pub const SCRATCH_COUNT: usize = 4;
+
+/// Read always-on scratch register `index`.
+///
+/// # Panics
+///
+/// If `index` is not less than [`SCRATCH_COUNT`].
+#[inline]
+pub fn read_scratch<HBN: Deref<Target = RegisterBlock>>(hbn: &HBN, index: usize) -> u32 {
+    hbn.scratch[index].read()
+}
+
+/// Write `value` into always-on scratch register `index`.
+///
+/// Scratch registers sit in the always-on domain and keep their values across hibernation
+/// and software reset; they are cleared only when the chip loses power. Boot ROM checks
+/// register 0 for a magic word on wakeup from hibernation and may jump to the address in
+/// register 1, so firmware relying on ROM wakeup should keep these two untouched.
+/// Registers 2 and 3 hold the Real-Time Clock offset once [`Hbn::rtc_write`] is used.
+///
+/// # Panics
+///
+/// If `index` is not less than [`SCRATCH_COUNT`].
+#[inline]
+pub fn write_scratch<HBN: Deref<Target = RegisterBlock>>(hbn: &HBN, index: usize, value: u32) {
+    unsafe { hbn.scratch[index].write(value) };
+}
+
+/// Set of events that wake the chip from hibernation.
+///
+/// Sources combine with `|`, e.g. `WakeupSources::RTC | WakeupSources::pin(9)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct WakeupSources(u32);
+
+impl WakeupSources {
+    const PINS: u32 = 0x1f;
+    const PIN_TRIGGER: u32 = 0x3 << 24;
+
+    /// No wakeup source; only a reset or power cycle ends hibernation.
+    pub const NONE: Self = Self(0);
+    /// Real-Time Clock alarm, set by [`Hbn::rtc_set_alarm`].
+    pub const RTC: Self = Self(1 << 16);
+
+    /// Always-on pad `gpio`, one of GPIO9 to GPIO13, on falling edge unless changed
+    /// by [`with_pin_trigger`](Self::with_pin_trigger).
+    ///
+    /// # Panics
+    ///
+    /// If `gpio` is not an always-on pad.
+    #[inline]
+    pub const fn pin(gpio: usize) -> Self {
+        assert!(
+            gpio >= AON_PAD_BASE && gpio < AON_PAD_BASE + AON_PAD_COUNT,
+            "only always-on pads can wake the chip from hibernation"
+        );
+        Self(1 << (gpio - AON_PAD_BASE))
+    }
+    /// Start bit on receive line of a UART, which must be routed to always-on pad `gpio`.
+    ///
+    /// Clocks are stopped in hibernation, so the received character itself is lost.
+    #[inline]
+    pub const fn uart_rx(gpio: usize) -> Self {
+        Self::pin(gpio).with_pin_trigger(WakeupTrigger::FallingEdge)
+    }
+    /// Set trigger condition shared by all pin sources.
+    #[inline]
+    pub const fn with_pin_trigger(self, val: WakeupTrigger) -> Self {
+        Self((self.0 & !Self::PIN_TRIGGER) | ((val as u32) << 24))
+    }
+    /// Get trigger condition of pin sources.
+    #[inline]
+    pub const fn pin_trigger(self) -> WakeupTrigger {
+        match (self.0 & Self::PIN_TRIGGER) >> 24 {
+            0 => WakeupTrigger::FallingEdge,
+            1 => WakeupTrigger::RisingEdge,
+            2 => WakeupTrigger::LowLevel,
+            _ => WakeupTrigger::HighLevel,
+        }
+    }
+    /// Check if always-on pad `idx` is a wakeup source.
+    #[inline]
+    pub const fn has_pin(self, idx: usize) -> bool {
+        self.0 & Self::PINS & (1 << idx) != 0
+    }
+    /// Check if Real-Time Clock is a wakeup source.
+    #[inline]
+    pub const fn has_rtc(self) -> bool {
+        self.0 & Self::RTC.0 != 0
+    }
+}
+
+impl core::ops::BitOr for WakeupSources {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// Real-Time Clock tick rate, of the 32-kHz crystal or the trimmed RC oscillator.
+pub const RTC_HZ: u32 = 32_768;
+/// First of two scratch registers holding the offset set by [`Hbn::rtc_write`].
+pub const RTC_OFFSET_SCRATCH: usize = 2;
+/// Real-Time Clock counter and alarm width in bits.
+const RTC_BITS: u32 = 40;
+/// Latch counter value into time registers.
+const RTC_LATCH: u32 = 1 << 31;
+
+/// Managed hibernation power mode control.
+///
+/// Hibernation powers down everything but the always-on domain, so no CPU state survives
+/// it: on wakeup the chip boots again from `_start`. Firmware keeps what it needs across
+/// hibernation in scratch registers, see [`write_scratch`], and checks [`wakeup_pin`] and
+/// [`is_rtc_alarm_pending`](Self::is_rtc_alarm_pending) early in boot, before peripherals
+/// are initialized.
+///
+/// The Real-Time Clock counter sits in the always-on domain and keeps counting in
+/// hibernation, so its readings order events across sleep cycles.
+pub struct Hbn<'a> {
+    hbn: &'a RegisterBlock,
+}
+
+impl<'a> Hbn<'a> {
+    /// Create hibernation control over hibernate registers.
+    #[inline]
+    pub fn new<HBN: Deref<Target = RegisterBlock>>(hbn: &'a HBN) -> Self {
+        Hbn { hbn }
+    }
+    /// Clear wakeup state of all pin and Real-Time Clock sources.
+    ///
+    /// Wakeup state is read by [`wakeup_pin`] and
+    /// [`is_rtc_alarm_pending`](Self::is_rtc_alarm_pending) until cleared.
+    #[inline]
+    pub fn clear_wakeup_cause(&self) {
+        let clear = (0..AON_PAD_COUNT)
+            .fold(InterruptClear::default(), |v, idx| v.clear_pin_wakeup(idx))
+            .clear_rtc();
+        unsafe { self.hbn.interrupt_clear.write(clear) };
+    }
+    /// Read Real-Time Clock in [`RTC_HZ`] ticks.
+    ///
+    /// Time is the hardware counter plus the offset set by [`rtc_write`](Self::rtc_write).
+    /// Hardware counter is 40 bits wide, wrapping after about 388 days.
+    #[inline]
+    pub fn rtc_read(&self) -> u64 {
+        self.rtc_counter().wrapping_add(self.rtc_offset())
+    }
+    /// Set Real-Time Clock to `value` ticks.
+    ///
+    /// Hardware counter can only be cleared, so it is restarted from zero and `value` is
+    /// kept as an offset in scratch registers [`RTC_OFFSET_SCRATCH`] and the one after,
+    /// which keep it across hibernation. A pending alarm is cancelled.
+    #[inline]
+    pub fn rtc_write(&mut self, value: u64) {
+        self.rtc_cancel_alarm();
+        unsafe {
+            self.hbn.control.modify(|v| v.disable_rtc());
+            self.hbn.control.modify(|v| v.enable_rtc());
+            self.hbn.scratch[RTC_OFFSET_SCRATCH].write(value as u32);
+            self.hbn.scratch[RTC_OFFSET_SCRATCH + 1].write((value >> 32) as u32);
+        }
+    }
+    /// Raise the hibernate interrupt, or wake from hibernation, when Real-Time Clock reaches `at`.
+    #[inline]
+    pub fn rtc_set_alarm(&self, at: u64) {
+        let compare = rtc_compare_value(at, self.rtc_offset());
+        unsafe {
+            self.hbn.control.modify(|v| v.disable_rtc_compare());
+            self.hbn.time_lo.write(compare as u32);
+            self.hbn.time_hi.write((compare >> 32) as u32);
+            self.hbn
+                .interrupt_clear
+                .write(InterruptClear::default().clear_rtc());
+            self.hbn
+                .control
+                .modify(|v| v.enable_rtc().enable_rtc_compare());
+        }
+    }
+    /// Check if Real-Time Clock alarm has fired.
+    #[inline]
+    pub fn is_rtc_alarm_pending(&self) -> bool {
+        self.hbn.interrupt_state.read().has_rtc()
+    }
+    /// Disable Real-Time Clock alarm and clear its pending state.
+    #[inline]
+    pub fn rtc_cancel_alarm(&self) {
+        unsafe {
+            self.hbn.control.modify(|v| v.disable_rtc_compare());
+            self.hbn
+                .interrupt_clear
+                .write(InterruptClear::default().clear_rtc());
+        }
+    }
+    /// Enter hibernation, to be woken by `wakeup_sources`.
+    ///
+    /// Interrupts are disabled, wakeup sources are configured and pending wakeup state
+    /// is cleared before the entry sequence is issued. Pads registered by
+    /// [`Input::into_wakeup_source`](crate::gpio::Input::into_wakeup_source) stay enabled
+    /// in addition to `wakeup_sources`; their shared trigger condition is only replaced
+    /// if `wakeup_sources` has pin sources. With `retain_psram`, the 1.8-V I/O
+    /// supply stays powered, so pseudo static RAM put into self refresh beforehand keeps
+    /// its contents. This function never returns; the chip boots again on wakeup.
+    pub fn enter_sleep(&self, wakeup_sources: WakeupSources, retain_psram: bool) -> ! {
+        #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+        unsafe {
+            // Clear machine interrupt enable bit in mstatus.
+            core::arch::asm!("csrci mstatus, 8")
+        };
+        let hbn = self.hbn;
+        let pins = (0..AON_PAD_COUNT).filter(|&idx| wakeup_sources.has_pin(idx));
+        unsafe {
+            for idx in pins {
+                hbn.pad_control_0
+                    .modify(|v| v.enable_input(idx).enable_aon_control(idx));
+            }
+            hbn.interrupt_mode.modify(|v| {
+                let v = if (0..AON_PAD_COUNT).any(|idx| wakeup_sources.has_pin(idx)) {
+                    v.set_pin_wakeup_trigger(wakeup_sources.pin_trigger())
+                } else {
+                    v
+                };
+                (0..AON_PAD_COUNT)
+                    .filter(|&idx| wakeup_sources.has_pin(idx))
+                    .fold(v, |v, idx| v.enable_pin_wakeup(idx))
+            });
+            hbn.control.modify(|v| {
+                let v = if wakeup_sources.has_rtc() {
+                    v.enable_rtc().enable_rtc_compare()
+                } else {
+                    v.disable_rtc_compare()
+                };
+                let v = if retain_psram {
+                    v.enable_ldo18_io()
+                } else {
+                    v.disable_ldo18_io()
+                };
+                v.power_down_core()
+            });
+        }
+        self.clear_wakeup_cause();
+        compiler_fence(Ordering::SeqCst);
+        unsafe { hbn.control.modify(|v| v.enter_hibernate()) };
+        loop {
+            #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+            unsafe {
+                core::arch::asm!("wfi")
+            };
+            #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Latch and read the 40-bit hardware counter.
+    fn rtc_counter(&self) -> u64 {
+        let hbn = self.hbn;
+        unsafe {
+            hbn.rtc_time_hi.modify(|v| v | RTC_LATCH);
+            hbn.rtc_time_hi.modify(|v| v & !RTC_LATCH);
+        }
+        let low = hbn.rtc_time_lo.read() as u64;
+        let high = (hbn.rtc_time_hi.read() & 0xff) as u64;
+        (high << 32) | low
+    }
+    #[inline]
+    fn rtc_offset(&self) -> u64 {
+        let low = self.hbn.scratch[RTC_OFFSET_SCRATCH].read() as u64;
+        let high = self.hbn.scratch[RTC_OFFSET_SCRATCH + 1].read() as u64;
+        (high << 32) | low
+    }
+}
+
+/// Hardware compare value for Real-Time Clock alarm at `at` ticks with `offset`.
+#[inline]
+const fn rtc_compare_value(at: u64, offset: u64) -> u64 {
+    at.wrapping_sub(offset) & ((1 << RTC_BITS) - 1)
+}
+
+/// Convert Real-Time Clock `ticks` to microseconds.
+#[inline]
+pub const fn rtc_ticks_to_micros(ticks: u64) -> u64 {
+    ((ticks as u128 * 1_000_000) / RTC_HZ as u128) as u64
+}
+
+/// Real-Time Clock as a monotonic time source, in the manner of `embedded_time::Clock`.
+///
+/// Counter keeps running in hibernation, so instants taken before and after sleep
+/// compare correctly, e.g. to timestamp logged data.
+pub struct RtcClock<'a>(Hbn<'a>);
+
+impl<'a> RtcClock<'a> {
+    /// Tick rate of instants returned by [`now`](Self::now).
+    pub const FREQUENCY: Hertz = Hertz(RTC_HZ);
+
+    /// Create a clock over hibernate registers, starting the counter if stopped.
+    ///
+    /// The counter is not cleared, so time carries on from before hibernation.
+    #[inline]
+    pub fn new<HBN: Deref<Target = RegisterBlock>>(hbn: &'a HBN) -> Self {
+        unsafe { hbn.control.modify(|v| v.enable_rtc()) };
+        RtcClock(Hbn::new(hbn))
+    }
+    /// Get current instant in [`RTC_HZ`] ticks.
+    #[inline]
+    pub fn now(&self) -> u64 {
+        self.0.rtc_read()
+    }
+    /// Get current instant in microseconds.
+    #[inline]
+    pub fn now_micros(&self) -> u64 {
+        rtc_ticks_to_micros(self.now())
+    }
+    /// Release hibernation control.
+    #[inline]
+    pub fn free(self) -> Hbn<'a> {
+        self.0
+    }
+}
+
+/// Brown-out reset function configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct BorControl(u32);
+
+impl BorControl {
+    const RESET: u32 = 1 << 0;
+    const THRESHOLD: u32 = 0x7 << 1;
+    const POWER: u32 = 1 << 4;
+    const VOLTAGE_OK: u32 = 1 << 5;
+
+    /// Reset the chip when supply voltage drops below threshold.
+    #[inline]
+    pub const fn enable_reset(self) -> Self {
+        Self(self.0 | Self::RESET)
+    }
+    /// Only raise interrupt when supply voltage drops below threshold.
+    #[inline]
+    pub const fn disable_reset(self) -> Self {
+        Self(self.0 & !Self::RESET)
+    }
+    /// Check if brown-out resets the chip.
+    #[inline]
+    pub const fn is_reset_enabled(self) -> bool {
+        self.0 & Self::RESET != 0
+    }
+    /// Set brown-out threshold voltage.
+    #[inline]
+    pub const fn set_threshold(self, val: BorThreshold) -> Self {
+        Self((self.0 & !Self::THRESHOLD) | ((val as u32) << 1))
+    }
+    /// Get brown-out threshold voltage.
+    #[inline]
+    pub const fn threshold(self) -> BorThreshold {
+        match (self.0 & Self::THRESHOLD) >> 1 {
+            0 => BorThreshold::V2_05,
+            1 => BorThreshold::V2_10,
+            2 => BorThreshold::V2_15,
+            3 => BorThreshold::V2_20,
+            4 => BorThreshold::V2_25,
+            5 => BorThreshold::V2_30,
+            6 => BorThreshold::V2_35,
+            7 => BorThreshold::V2_40,
+            _ => unreachable!(),
+        }
+    }
+    /// Power up brown-out detector.
+    #[inline]
+    pub const fn power_up(self) -> Self {
+        Self(self.0 | Self::POWER)
+    }
+    /// Power down brown-out detector.
+    #[inline]
+    pub const fn power_down(self) -> Self {
+        Self(self.0 & !Self::POWER)
+    }
+    /// Check if brown-out detector is powered up.
+    #[inline]
+    pub const fn is_powered_up(self) -> bool {
+        self.0 & Self::POWER != 0
+    }
+    /// Check if supply voltage is above threshold.
+    #[inline]
+    pub const fn is_voltage_ok(self) -> bool {
+        self.0 & Self::VOLTAGE_OK != 0
+    }
+}
+
+/// Brown-out detector threshold voltage of the 3.3 V supply.
+///
+/// Levels go from 2.05 V to 2.40 V in 50 mV steps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum BorThreshold {
+    /// 2.05 V.
+    V2_05 = 0,
+    /// 2.10 V.
+    V2_10 = 1,
+    /// 2.15 V.
+    V2_15 = 2,
+    /// 2.20 V.
+    V2_20 = 3,
+    /// 2.25 V.
+    V2_25 = 4,
+    /// 2.30 V.
+    V2_30 = 5,
+    /// 2.35 V.
+    V2_35 = 6,
+    /// 2.40 V.
+    V2_40 = 7,
+}
+
+/// Brown-out detector configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BorConfig {
+    /// Threshold voltage of brown-out.
+    pub threshold: BorThreshold,
+    /// Raise brown-out interrupt when supply drops below threshold.
+    pub interrupt: bool,
+    /// Reset the chip when supply drops below threshold.
+    pub reset: bool,
+}
+
+impl Default for BorConfig {
+    /// Lowest threshold with interrupt only.
+    #[inline]
+    fn default() -> Self {
+        Self {
+            threshold: BorThreshold::V2_05,
+            interrupt: true,
+            reset: false,
+        }
+    }
+}
+
+/// Managed brown-out detector.
+///
+/// With interrupt enabled and reset disabled, firmware gets a chance to save state
+/// while the supply is dipping; call [`Bor::on_interrupt`] in the hibernate interrupt
+/// handler. With reset enabled, the chip is reset without further notice.
+pub struct Bor<'a> {
+    hbn: &'a RegisterBlock,
+}
+
+impl<'a> Bor<'a> {
+    /// Power up and configure brown-out detector.
+    #[inline]
+    pub fn new<HBN: Deref<Target = RegisterBlock>>(hbn: &'a HBN, config: BorConfig) -> Self {
+        let bor = Bor { hbn };
+        bor.set_config(config);
+        bor
+    }
+    /// Change brown-out detector configuration.
+    #[inline]
+    pub fn set_config(&self, config: BorConfig) {
+        unsafe {
+            self.hbn.interrupt_mode.modify(|v| v.disable_bor());
+            self.hbn.bor_config.modify(|v| {
+                let v = v.set_threshold(config.threshold).power_up();
+                if config.reset {
+                    v.enable_reset()
+                } else {
+                    v.disable_reset()
+                }
+            });
+            self.hbn
+                .interrupt_clear
+                .write(InterruptClear::default().clear_bor());
+            if config.interrupt {
+                self.hbn.interrupt_mode.modify(|v| v.enable_bor());
+            }
+        }
+    }
+    /// Check if supply voltage is above threshold.
+    #[inline]
+    pub fn voltage_ok(&self) -> bool {
+        self.hbn.bor_config.read().is_voltage_ok()
+    }
+    /// Handle hibernate interrupt, returning whether a brown-out occurred.
+    ///
+    /// Brown-out interrupt state is cleared. Handler should keep the work after a
+    /// brown-out short, e.g. flush state to flash, as the supply may keep dropping.
+    #[inline]
+    pub fn on_interrupt(&self) -> bool {
+        if !self.hbn.interrupt_state.read().has_bor() {
+            return false;
+        }
+        unsafe {
+            self.hbn
+                .interrupt_clear
+                .write(InterruptClear::default().clear_bor())
+        };
+        true
+    }
+    /// Disable brown-out interrupt and reset, and power down the detector.
+    #[inline]
+    pub fn free(self) {
+        unsafe {
+            self.hbn.interrupt_mode.modify(|v| v.disable_bor());
+            self.hbn
+                .bor_config
+                .modify(|v| v.disable_reset().power_down());
+        }
+    }
+}
+
+/// 32-kHz internal RC oscillator control register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct Rc32kControl(u32);
+
+impl Rc32kControl {
+    const READY: u32 = 1 << 1;
+    const EXTERNAL_CODE_ENABLE: u32 = 1 << 19;
+    const POWER_UP: u32 = 1 << 21;
+    const EXTERNAL_CODE: u32 = 0x3ff << 22;
+
+    /// Check if oscillator output is stable.
+    #[inline]
+    pub const fn is_ready(self) -> bool {
+        self.0 & Self::READY != 0
+    }
+    /// Use frequency code from this register instead of calibration result.
+    #[inline]
+    pub const fn enable_external_code(self) -> Self {
+        Self(self.0 | Self::EXTERNAL_CODE_ENABLE)
+    }
+    /// Use frequency code from calibration result.
+    #[inline]
+    pub const fn disable_external_code(self) -> Self {
+        Self(self.0 & !Self::EXTERNAL_CODE_ENABLE)
+    }
+    /// Check if frequency code from this register is used.
+    #[inline]
+    pub const fn is_external_code_enabled(self) -> bool {
+        self.0 & Self::EXTERNAL_CODE_ENABLE != 0
+    }
+    /// Power up oscillator.
+    #[inline]
+    pub const fn power_up(self) -> Self {
+        Self(self.0 | Self::POWER_UP)
+    }
+    /// Power down oscillator.
+    #[inline]
+    pub const fn power_down(self) -> Self {
+        Self(self.0 & !Self::POWER_UP)
+    }
+    /// Check if oscillator is powered up.
+    #[inline]
+    pub const fn is_powered_up(self) -> bool {
+        self.0 & Self::POWER_UP != 0
+    }
+    /// Set frequency code.
+    #[inline]
+    pub const fn set_external_code(self, val: u16) -> Self {
+        Self((self.0 & !Self::EXTERNAL_CODE) | (((val as u32) << 22) & Self::EXTERNAL_CODE))
+    }
+    /// Get frequency code.
+    #[inline]
+    pub const fn external_code(self) -> u16 {
+        ((self.0 & Self::EXTERNAL_CODE) >> 22) as u16
+    }
+}
+
+/// External 32-kHz crystal oscillator control register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct Xtal32kControl(u32);
+
+impl Xtal32kControl {
+    const BUFFER_POWER_UP: u32 = 1 << 17;
+    const POWER_UP: u32 = 1 << 18;
+
+    /// Power up oscillator and its output buffer.
+    #[inline]
+    pub const fn power_up(self) -> Self {
+        Self(self.0 | Self::POWER_UP | Self::BUFFER_POWER_UP)
+    }
+    /// Power down oscillator and its output buffer.
+    #[inline]
+    pub const fn power_down(self) -> Self {
+        Self(self.0 & !(Self::POWER_UP | Self::BUFFER_POWER_UP))
+    }
+    /// Check if oscillator is powered up.
+    #[inline]
+    pub const fn is_powered_up(self) -> bool {
+        self.0 & Self::POWER_UP != 0
+    }
+}
+
+/// Always-on temperature sensor reference and crystal oscillator status register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct XtalStatus(u32);
+
+impl XtalStatus {
+    const XTAL_READY: u32 = 1 << 28;
+
+    /// Check if main crystal oscillator is running stably.
+    #[inline]
+    pub const fn is_xtal_ready(self) -> bool {
+        self.0 & Self::XTAL_READY != 0
+    }
 }
 
 /// Root clock source 1.
@@ -309,9 +1245,199 @@ pub enum ResetEvent {
 
 #[cfg(test)]
 mod tests {
-    use super::RegisterBlock;
+    use super::{rtc_compare_value, rtc_ticks_to_micros};
+    use super::{
+        AonPadControl0, AonPadControl1, BorControl, BorThreshold, Control, InterruptClear,
+        InterruptMode, InterruptState, Pull, Rc32kControl, RegisterBlock, WakeupSources,
+        WakeupTrigger, Xtal32kControl, XtalStatus,
+    };
     use memoffset::offset_of;
 
+    #[test]
+    fn struct_interrupt_mode_functions() {
+        let val = InterruptMode(0x0).set_pin_wakeup_trigger(WakeupTrigger::HighLevel);
+        assert_eq!(val.0, 0x0000_0007);
+        assert_eq!(val.pin_wakeup_trigger(), WakeupTrigger::HighLevel);
+        let val = val.set_pin_wakeup_trigger(WakeupTrigger::RisingEdge);
+        assert_eq!(val.0, 0x0000_0005);
+        assert_eq!(val.pin_wakeup_trigger(), WakeupTrigger::RisingEdge);
+        let val = InterruptMode(0x1f0).enable_pin_wakeup(2);
+        assert_eq!(val.0, 0x0000_01b0);
+        assert!(val.is_pin_wakeup_enabled(2));
+        assert!(!val.is_pin_wakeup_enabled(3));
+        let val = val.disable_pin_wakeup(2);
+        assert_eq!(val.0, 0x0000_01f0);
+        assert!(!val.is_pin_wakeup_enabled(2));
+    }
+
+    #[test]
+    fn struct_control_functions() {
+        let mut val = Control(0x0);
+
+        val = val.enable_rtc();
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_rtc_enabled());
+        val = val.disable_rtc();
+        assert_eq!(val.0, 0x0000_0000);
+
+        val = val.enable_rtc_compare();
+        assert_eq!(val.0, 0x0000_0002);
+        assert!(val.is_rtc_compare_enabled());
+        val = val.disable_rtc_compare();
+        assert_eq!(val.0, 0x0000_0000);
+
+        val = val.enable_ldo18_io();
+        assert_eq!(val.0, 0x0000_0020);
+        assert!(val.is_ldo18_io_enabled());
+        val = val.disable_ldo18_io();
+        assert_eq!(val.0, 0x0000_0000);
+
+        assert_eq!(val.enter_hibernate().0, 0x0000_0080);
+        assert!(val.enter_hibernate().is_hibernate_requested());
+
+        val = val.power_down_core();
+        assert_eq!(val.0, 0x0000_0200);
+        assert!(val.is_core_powered_down());
+        val = val.power_up_core();
+        assert_eq!(val.0, 0x0000_0000);
+        assert!(!val.is_core_powered_down());
+    }
+
+    #[test]
+    fn struct_wakeup_sources_functions() {
+        let val = WakeupSources::RTC | WakeupSources::pin(9) | WakeupSources::uart_rx(13);
+        assert!(val.has_rtc());
+        assert!(val.has_pin(0));
+        assert!(val.has_pin(4));
+        assert!(!val.has_pin(1));
+        assert_eq!(val.pin_trigger(), WakeupTrigger::FallingEdge);
+        let val = val.with_pin_trigger(WakeupTrigger::HighLevel);
+        assert_eq!(val.pin_trigger(), WakeupTrigger::HighLevel);
+        assert!(val.has_pin(4));
+        assert!(!WakeupSources::NONE.has_rtc());
+        assert!(!WakeupSources::pin(10).has_rtc());
+    }
+
+    #[test]
+    #[should_panic]
+    fn struct_wakeup_sources_non_aon_pin() {
+        let _ = WakeupSources::pin(8);
+    }
+
+    #[test]
+    fn struct_interrupt_state_rtc_functions() {
+        assert!(InterruptState(0x0001_0000).has_rtc());
+        assert!(!InterruptState(0x0000_0004).has_rtc());
+        assert_eq!(InterruptClear::default().clear_rtc().0, 0x0001_0000);
+    }
+
+    #[test]
+    fn function_rtc_conversions() {
+        assert_eq!(rtc_compare_value(100, 0), 100);
+        assert_eq!(rtc_compare_value(1_000_100, 1_000_000), 100);
+        assert_eq!(rtc_compare_value(1 << 40, 0), 0);
+        assert_eq!(rtc_compare_value(5, 10), 0xff_ffff_fffb);
+        assert_eq!(rtc_ticks_to_micros(32_768), 1_000_000);
+        assert_eq!(rtc_ticks_to_micros(1), 30);
+        assert_eq!(rtc_ticks_to_micros(1 << 40), 33_554_432_000_000);
+    }
+
+    #[test]
+    fn struct_interrupt_state_clear_functions() {
+        let val = InterruptState(0x0000_0010);
+        assert!(val.has_pin_wakeup(4));
+        assert!(!val.has_pin_wakeup(0));
+        let val = InterruptClear::default()
+            .clear_pin_wakeup(0)
+            .clear_pin_wakeup(4);
+        assert_eq!(val.0, 0x0000_0011);
+    }
+
+    #[test]
+    fn struct_aon_pad_control_functions() {
+        let val = AonPadControl0(0x0).enable_input(1).enable_aon_control(1);
+        assert_eq!(val.0, 0x0000_0802);
+        assert!(val.is_input_enabled(1));
+        assert!(val.is_aon_control_enabled(1));
+        let val = val.disable_input(1).disable_aon_control(1);
+        assert_eq!(val.0, 0x0);
+        assert!(!val.is_input_enabled(1));
+        assert!(!val.is_aon_control_enabled(1));
+
+        let val = AonPadControl1(0x0).enable_output(3);
+        assert_eq!(val.0, 0x0000_0008);
+        assert!(val.is_output_enabled(3));
+        assert!(!val.disable_output(3).is_output_enabled(3));
+        let val = AonPadControl1(0x0).set_pull(3, Pull::Up);
+        assert_eq!(val.0, 0x0080_0000);
+        assert_eq!(val.pull(3), Pull::Up);
+        let val = val.set_pull(3, Pull::Down);
+        assert_eq!(val.0, 0x0000_2000);
+        assert_eq!(val.pull(3), Pull::Down);
+        let val = val.set_pull(3, Pull::None);
+        assert_eq!(val.0, 0x0);
+        assert_eq!(val.pull(3), Pull::None);
+    }
+
+    #[test]
+    fn struct_bor_control_functions() {
+        let val = BorControl(0x0).enable_reset();
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_reset_enabled());
+        assert!(!val.disable_reset().is_reset_enabled());
+        let val = BorControl(0x0).set_threshold(BorThreshold::V2_40);
+        assert_eq!(val.0, 0x0000_000e);
+        assert_eq!(val.threshold(), BorThreshold::V2_40);
+        let val = val.set_threshold(BorThreshold::V2_15);
+        assert_eq!(val.0, 0x0000_0004);
+        assert_eq!(val.threshold(), BorThreshold::V2_15);
+        let val = BorControl(0x0).power_up();
+        assert_eq!(val.0, 0x0000_0010);
+        assert!(val.is_powered_up());
+        assert!(!val.power_down().is_powered_up());
+        assert!(BorControl(0x0000_0020).is_voltage_ok());
+        assert!(!BorControl(0x0).is_voltage_ok());
+
+        let val = InterruptMode(0x0).enable_bor();
+        assert_eq!(val.0, 0x0004_0000);
+        assert!(val.is_bor_enabled());
+        assert!(!val.disable_bor().is_bor_enabled());
+        assert!(InterruptState(0x0004_0000).has_bor());
+        assert_eq!(InterruptClear::default().clear_bor().0, 0x0004_0000);
+    }
+
+    #[test]
+    fn struct_rc32k_control_functions() {
+        assert!(Rc32kControl(0x0000_0002).is_ready());
+        let val = Rc32kControl(0x0).enable_external_code();
+        assert_eq!(val.0, 0x0008_0000);
+        assert!(val.is_external_code_enabled());
+        assert!(!val.disable_external_code().is_external_code_enabled());
+        let val = Rc32kControl(0x0).power_up();
+        assert_eq!(val.0, 0x0020_0000);
+        assert!(val.is_powered_up());
+        assert!(!val.power_down().is_powered_up());
+        let val = Rc32kControl(0x0).set_external_code(0x3ff);
+        assert_eq!(val.0, 0xffc0_0000);
+        assert_eq!(val.external_code(), 0x3ff);
+    }
+
+    #[test]
+    fn struct_xtal_status_functions() {
+        assert!(XtalStatus(0x1000_0000).is_xtal_ready());
+        assert!(!XtalStatus(0xefff_ffff).is_xtal_ready());
+    }
+
+    #[test]
+    fn struct_xtal32k_control_functions() {
+        let val = Xtal32kControl(0x0).power_up();
+        assert_eq!(val.0, 0x0006_0000);
+        assert!(val.is_powered_up());
+        let val = val.power_down();
+        assert_eq!(val.0, 0x0);
+        assert!(!val.is_powered_up());
+    }
+
     #[test]
     fn struct_register_block_offset() {
         assert_eq!(offset_of!(RegisterBlock, control), 0x00);
@@ -330,9 +1456,11 @@ mod tests {
         assert_eq!(offset_of!(RegisterBlock, sram), 0x34);
         assert_eq!(offset_of!(RegisterBlock, pad_control_0), 0x38);
         assert_eq!(offset_of!(RegisterBlock, pad_control_1), 0x3c);
+        assert_eq!(offset_of!(RegisterBlock, scratch), 0x100);
         assert_eq!(offset_of!(RegisterBlock, rc32k), 0x200);
         assert_eq!(offset_of!(RegisterBlock, xtal32k), 0x204);
         assert_eq!(offset_of!(RegisterBlock, rtc_control_0), 0x208);
         assert_eq!(offset_of!(RegisterBlock, rtc_control_1), 0x20c);
+        assert_eq!(offset_of!(RegisterBlock, xtal_status), 0x884);
     }
 }
@@ -1,4 +1,9 @@
 //! Inter-Integrated Circuit bus.
+//!
+//! The controller only operates as bus master. It has no own-address register and no
+//! address match, start or stop condition events for incoming transfers, so the chip
+//! cannot act as an I2C slave device on hardware; `Config::set_slave_address` sets the
+//! address of the remote device a master transfer is sent to.
 use core::ops::Deref;
 
 use crate::{
@@ -495,9 +500,29 @@ impl FifoConfig1 {
 }
 
 /// Managed Inter-Integrated Circuit peripheral.
+///
+/// Operates in master mode only; see module documentation.
 pub struct I2c<I2C, PADS> {
     i2c: I2C,
     pads: PADS,
+    timeout: u32,
+    stretch_timeout: Option<u32>,
+}
+
+/// Clock divide factor of I2C module clock from crystal oscillator, as set by [`I2c::new`].
+const CLOCK_DIVIDE: u32 = 0xff;
+/// Length of each data phase in module clock cycles minus one, as set by [`I2c::new`].
+const DATA_PHASE: u32 = 0xff;
+
+/// Status polls to wait for one byte, twice its transfer time in crystal clock cycles.
+///
+/// A status register read takes at least one crystal clock cycle, so polls cover
+/// at least twice the byte time regardless of processor frequency.
+#[inline]
+const fn byte_timeout(clock_divide: u32, data_phase: u32) -> u32 {
+    // 8 data bits and acknowledge, each bit in 4 data phases.
+    let byte_cycles = 9 * 4 * (data_phase + 1) * (clock_divide + 1);
+    2 * byte_cycles
 }
 
 impl<I2C: Deref<Target = RegisterBlock>, SCL, SDA> I2c<I2C, (SCL, SDA)> {
@@ -515,7 +540,7 @@ impl<I2C: Deref<Target = RegisterBlock>, SCL, SDA> I2c<I2C, (SCL, SDA)> {
                 config
                     .enable_clock()
                     .set_clock_source(I2cClockSource::Xclk)
-                    .set_clock_divide(0xff)
+                    .set_clock_divide(CLOCK_DIVIDE as u8)
             });
             glb.clock_config_1.modify(|config| config.enable_i2c());
             i2c.period_start.write(
@@ -534,10 +559,10 @@ impl<I2C: Deref<Target = RegisterBlock>, SCL, SDA> I2c<I2C, (SCL, SDA)> {
             );
             i2c.period_data.write(
                 PeriodData(0)
-                    .set_phase(0, 0xff)
-                    .set_phase(1, 0xff)
-                    .set_phase(2, 0xff)
-                    .set_phase(3, 0xff),
+                    .set_phase(0, DATA_PHASE as u8)
+                    .set_phase(1, DATA_PHASE as u8)
+                    .set_phase(2, DATA_PHASE as u8)
+                    .set_phase(3, DATA_PHASE as u8),
             );
             i2c.config.write(
                 Config(0)
@@ -547,7 +572,12 @@ impl<I2C: Deref<Target = RegisterBlock>, SCL, SDA> I2c<I2C, (SCL, SDA)> {
             );
         }
 
-        Self { i2c, pads }
+        Self {
+            i2c,
+            pads,
+            timeout: byte_timeout(CLOCK_DIVIDE, DATA_PHASE),
+            stretch_timeout: None,
+        }
     }
 
     /// Release the I2C instance and return the pads.
@@ -559,6 +589,88 @@ impl<I2C: Deref<Target = RegisterBlock>, SCL, SDA> I2c<I2C, (SCL, SDA)> {
         }
         (self.i2c, self.pads)
     }
+}
+
+/// I2C error.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// SMBus packet error code mismatch.
+    Pec {
+        expected: u8,
+        received: u8,
+    },
+    /// Illegal SMBus block length.
+    BlockLength(u8),
+    /// Slave did not acknowledge address or data byte.
+    Nack,
+    /// Slave did not complete transfer in time, e.g. it is absent or stretches clock for
+    /// too long.
+    Timeout,
+    /// Bus held low by another device.
+    ArbitrationOrStretch,
+    Other,
+}
+
+impl embedded_hal::i2c::Error for Error {
+    #[inline(always)]
+    fn kind(&self) -> embedded_hal::i2c::ErrorKind {
+        use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+        match self {
+            Error::ArbitrationOrStretch => ErrorKind::ArbitrationLoss,
+            // Controller does not tell whether address or data byte was not acknowledged.
+            Error::Nack => ErrorKind::NoAcknowledge(NoAcknowledgeSource::Unknown),
+            Error::Pec { .. } | Error::BlockLength(_) | Error::Timeout | Error::Other => {
+                ErrorKind::Other
+            }
+        }
+    }
+}
+
+impl<I2C: Deref<Target = RegisterBlock>, PADS> embedded_hal::i2c::ErrorType for I2c<I2C, PADS> {
+    type Error = Error;
+}
+
+impl<I2C: Deref<Target = RegisterBlock>, PADS> I2c<I2C, PADS> {
+    /// Set transfer timeout in status register polls for each byte.
+    ///
+    /// A transfer of `n` data bytes fails with [`Error::Timeout`] after `(n + 1) * polls`
+    /// polls without completing, counting the address byte. Default is twice the byte
+    /// time at bus clock set by [`I2c::new`], measured in crystal oscillator cycles;
+    /// lower it for faster detection of an absent slave. Slaves that stretch clock need
+    /// [`I2c::enable_clock_stretching`] instead.
+    #[inline]
+    pub fn set_timeout(&mut self, polls: u32) {
+        self.timeout = polls;
+    }
+
+    /// Allow slaves to stretch clock for up to `polls` extra status register polls per byte.
+    ///
+    /// Controller then waits for the clock line to be released before continuing each
+    /// bit. A transfer exceeding its timeout including stretch allowance is aborted with
+    /// [`Error::Timeout`]; if the bus stays held afterwards, release the pads and run
+    /// [`recover_bus`] on them.
+    #[inline]
+    pub fn enable_clock_stretching(&mut self, polls: u32) {
+        unsafe { self.i2c.config.modify(|config| config.enable_scl_sync()) };
+        self.stretch_timeout = Some(polls);
+    }
+
+    /// Forbid clock stretching, as configured by [`I2c::new`].
+    ///
+    /// Controller drives the clock at fixed timing regardless of the clock line level,
+    /// for deterministic transfer time; only use it with slaves that never stretch clock.
+    #[inline]
+    pub fn disable_clock_stretching(&mut self) {
+        unsafe { self.i2c.config.modify(|config| config.disable_scl_sync()) };
+        self.stretch_timeout = None;
+    }
+
+    /// Check if slaves are allowed to stretch clock.
+    #[inline]
+    pub fn is_clock_stretching_enabled(&self) -> bool {
+        self.stretch_timeout.is_some()
+    }
 
     /// Enable sub-address.
     #[inline]
@@ -583,27 +695,185 @@ impl<I2C: Deref<Target = RegisterBlock>, SCL, SDA> I2c<I2C, (SCL, SDA)> {
                 .modify(|config| config.disable_sub_address());
         }
     }
-}
 
-/// I2C error.
-#[derive(Debug)]
-#[non_exhaustive]
-pub enum Error {
-    Other,
-}
+    /// Scan the bus and return addresses of all present slave devices in ascending order.
+    ///
+    /// Each 7-bit address is probed with [`I2c::probe`], except reserved addresses
+    /// `0x00..=0x07` and `0x78..=0x7f`.
+    #[inline]
+    pub fn scan(&mut self) -> heapless::Vec<u8, 128> {
+        let mut found = heapless::Vec::new();
+        for address in (0..128).filter(|&address| !is_reserved_address(address)) {
+            if self.probe(address) {
+                // At most 128 addresses, never exceeds capacity.
+                found.push(address).ok();
+            }
+        }
+        found
+    }
 
-impl embedded_hal::i2c::Error for Error {
-    #[inline(always)]
-    fn kind(&self) -> embedded_hal::i2c::ErrorKind {
-        use embedded_hal::i2c::ErrorKind;
-        match self {
-            Error::Other => ErrorKind::Other,
+    /// Check if a slave device acknowledges `address`.
+    ///
+    /// Controller cannot send a zero-length write, so this reads one byte from the slave
+    /// and discards it. Sub-address, if enabled, is not sent during the probe.
+    pub fn probe(&mut self, address: u8) -> bool {
+        let config = self.i2c.config.read();
+        unsafe {
+            self.i2c.interrupt_clear.write(
+                InterruptClear(0)
+                    .clear_interrupt(Interrupt::TransferEnd)
+                    .clear_interrupt(Interrupt::NackReceived),
+            );
+            self.i2c.config.write(
+                config
+                    .disable_sub_address()
+                    .set_read_direction()
+                    .set_slave_address(address as u16)
+                    .set_packet_length(0)
+                    .enable_master(),
+            )
+        };
+
+        let max_retry = self.transfer_timeout(1);
+        let mut retry = 0;
+        let present = loop {
+            let state = self.i2c.interrupt_state.read();
+            if state.has_interrupt(Interrupt::NackReceived) {
+                break false;
+            }
+            if state.has_interrupt(Interrupt::TransferEnd) {
+                break true;
+            }
+            retry += 1;
+            if retry >= max_retry {
+                break false;
+            }
+        };
+
+        unsafe {
+            self.i2c.config.write(config.disable_master());
+            self.i2c
+                .fifo_config_0
+                .modify(|config| config.clear_receive_fifo());
         }
+        present
     }
-}
 
-impl<I2C: Deref<Target = RegisterBlock>, PADS> embedded_hal::i2c::ErrorType for I2c<I2C, PADS> {
-    type Error = Error;
+    /// Write `bytes` to slave device in one transfer.
+    fn write_bytes(&mut self, address: u8, bytes: &[u8]) -> Result<(), Error> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        let len = bytes.len();
+        unsafe {
+            self.i2c.interrupt_clear.write(
+                InterruptClear(0)
+                    .clear_interrupt(Interrupt::TransferEnd)
+                    .clear_interrupt(Interrupt::NackReceived),
+            );
+            self.i2c.config.modify(|config| {
+                config
+                    .set_write_direction()
+                    .set_slave_address(address as u16)
+                    .set_packet_length((len - 1) as u8)
+                    .enable_master()
+            })
+        };
+
+        let max_retry = self.transfer_timeout(len);
+        let mut retry = 0;
+        for chunk in bytes.chunks(4) {
+            while self.i2c.fifo_config_1.read().transmit_available_bytes() == 0 {
+                retry += 1;
+                if retry >= max_retry {
+                    unsafe { self.i2c.config.modify(|config| config.disable_master()) };
+                    return Err(Error::Timeout);
+                }
+            }
+            let mut word = 0u32;
+            for (j, byte) in chunk.iter().enumerate() {
+                word |= (*byte as u32) << (j * 8);
+            }
+            unsafe { self.i2c.fifo_write.write(word) };
+        }
+        loop {
+            let state = self.i2c.interrupt_state.read();
+            if state.has_interrupt(Interrupt::NackReceived) {
+                unsafe { self.i2c.config.modify(|config| config.disable_master()) };
+                return Err(Error::Nack);
+            }
+            if state.has_interrupt(Interrupt::TransferEnd) {
+                break;
+            }
+            retry += 1;
+            if retry >= max_retry {
+                unsafe { self.i2c.config.modify(|config| config.disable_master()) };
+                return Err(Error::Timeout);
+            }
+        }
+
+        unsafe { self.i2c.config.modify(|config| config.disable_master()) };
+        Ok(())
+    }
+
+    /// Read `bytes` from slave device in one transfer.
+    fn read_bytes(&mut self, address: u8, bytes: &mut [u8]) -> Result<(), Error> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        let len = bytes.len();
+        unsafe {
+            self.i2c.interrupt_clear.write(
+                InterruptClear(0)
+                    .clear_interrupt(Interrupt::TransferEnd)
+                    .clear_interrupt(Interrupt::NackReceived),
+            );
+            self.i2c.config.modify(|config| {
+                config
+                    .set_read_direction()
+                    .set_slave_address(address as u16)
+                    .set_packet_length((len - 1) as u8)
+                    .enable_master()
+            })
+        };
+
+        let mut i = 0;
+        let max_retry = self.transfer_timeout(len);
+        let mut retry = 0;
+        while i < len {
+            while self.i2c.fifo_config_1.read().receive_available_bytes() == 0 {
+                if self
+                    .i2c
+                    .interrupt_state
+                    .read()
+                    .has_interrupt(Interrupt::NackReceived)
+                {
+                    unsafe { self.i2c.config.modify(|config| config.disable_master()) };
+                    return Err(Error::Nack);
+                }
+                retry += 1;
+                if retry >= max_retry {
+                    unsafe { self.i2c.config.modify(|config| config.disable_master()) };
+                    return Err(Error::Timeout);
+                }
+            }
+            let word = self.i2c.fifo_read.read();
+            let bytes_to_read = core::cmp::min(len - i, 4);
+            for j in 0..bytes_to_read {
+                bytes[i] = (word >> (j * 8)) as u8;
+                i += 1;
+            }
+        }
+
+        unsafe { self.i2c.config.modify(|config| config.disable_master()) };
+        Ok(())
+    }
+
+    /// Status polls allowed for a transfer of `len` data bytes and the address byte.
+    #[inline]
+    fn transfer_timeout(&self, len: usize) -> u32 {
+        transfer_polls(self.timeout, self.stretch_timeout.unwrap_or(0), len)
+    }
 }
 
 impl<I2C: Deref<Target = RegisterBlock>, PADS> embedded_hal::i2c::I2c for I2c<I2C, PADS> {
@@ -615,48 +885,231 @@ impl<I2C: Deref<Target = RegisterBlock>, PADS> embedded_hal::i2c::I2c for I2c<I2
     ) -> Result<(), Self::Error> {
         for op in operations {
             match op {
-                embedded_hal::i2c::Operation::Write(_bytes) => {
-                    todo!()
-                }
-                embedded_hal::i2c::Operation::Read(bytes) => {
-                    let len = bytes.len() as u8;
-                    unsafe {
-                        self.i2c.config.modify(|config| {
-                            config
-                                .set_read_direction()
-                                .set_slave_address(address as u16)
-                                .set_packet_length(len - 1)
-                                .enable_master()
-                        })
-                    };
-
-                    let mut i = 0;
-                    let max_retry = len * 100;
-                    let mut retry = 0;
-                    while i < len {
-                        while self.i2c.fifo_config_1.read().receive_available_bytes() == 0 {
-                            retry += 1;
-                            if retry >= max_retry {
-                                unsafe { self.i2c.config.modify(|config| config.disable_master()) };
-                                return Err(Error::Other);
-                            }
-                        }
-                        let word = self.i2c.fifo_read.read();
-                        let bytes_to_read = core::cmp::min(len - i, 4);
-                        for j in 0..bytes_to_read {
-                            bytes[i as usize] = (word >> (j * 8)) as u8;
-                            i += 1;
-                        }
-                    }
+                embedded_hal::i2c::Operation::Write(bytes) => self.write_bytes(address, bytes)?,
+                embedded_hal::i2c::Operation::Read(bytes) => self.read_bytes(address, bytes)?,
+            }
+        }
+        Ok(())
+    }
+}
 
-                    unsafe { self.i2c.config.modify(|config| config.disable_master()) };
-                }
+/// Maximum data length of SMBus block transfers.
+pub const SMBUS_BLOCK_MAX: usize = 32;
+
+/// System Management Bus controller over an I2C peripheral.
+///
+/// Command codes are sent as I2C sub-address on reads, so the wrapped `I2c`
+/// sub-address setting is overwritten by every SMBus transfer.
+pub struct SmbusController<I2C, PADS> {
+    i2c: I2c<I2C, PADS>,
+    pec: bool,
+}
+
+impl<I2C: Deref<Target = RegisterBlock>, PADS> SmbusController<I2C, PADS> {
+    /// Create an SMBus controller with packet error checking disabled.
+    #[inline]
+    pub fn new(i2c: I2c<I2C, PADS>) -> Self {
+        Self { i2c, pec: false }
+    }
+    /// Release the SMBus controller and return the I2C instance.
+    #[inline]
+    pub fn free(self) -> I2c<I2C, PADS> {
+        self.i2c
+    }
+    /// Enable packet error checking.
+    ///
+    /// A CRC-8 byte is appended to each write and checked on each read.
+    #[inline]
+    pub fn enable_pec(&mut self) {
+        self.pec = true;
+    }
+    /// Disable packet error checking.
+    #[inline]
+    pub fn disable_pec(&mut self) {
+        self.pec = false;
+    }
+    /// Check if packet error checking is enabled.
+    #[inline]
+    pub fn is_pec_enabled(&self) -> bool {
+        self.pec
+    }
+
+    /// Write one data byte to command code `command`.
+    #[inline]
+    pub fn write_byte(&mut self, address: u8, command: u8, data: u8) -> Result<(), Error> {
+        self.write(address, command, &[data])
+    }
+    /// Write a little endian data word to command code `command`.
+    #[inline]
+    pub fn write_word(&mut self, address: u8, command: u8, data: u16) -> Result<(), Error> {
+        self.write(address, command, &data.to_le_bytes())
+    }
+    /// Write a block of at most 32 bytes to command code `command`.
+    #[inline]
+    pub fn block_write(&mut self, address: u8, command: u8, data: &[u8]) -> Result<(), Error> {
+        if data.len() > SMBUS_BLOCK_MAX {
+            return Err(Error::BlockLength(data.len() as u8));
+        }
+        let mut buf = [0u8; SMBUS_BLOCK_MAX + 1];
+        buf[0] = data.len() as u8;
+        buf[1..=data.len()].copy_from_slice(data);
+        self.write(address, command, &buf[..=data.len()])
+    }
+    /// Read one data byte from command code `command`.
+    #[inline]
+    pub fn read_byte(&mut self, address: u8, command: u8) -> Result<u8, Error> {
+        let mut buf = [0u8; 1];
+        self.read(address, command, &mut buf)?;
+        Ok(buf[0])
+    }
+    /// Read a little endian data word from command code `command`.
+    #[inline]
+    pub fn read_word(&mut self, address: u8, command: u8) -> Result<u16, Error> {
+        let mut buf = [0u8; 2];
+        self.read(address, command, &mut buf)?;
+        Ok(u16::from_le_bytes(buf))
+    }
+    /// Read a block from command code `command` into `buf`, returning block length.
+    ///
+    /// The first byte sent by device is block length. Transfer length is fixed before
+    /// transfer starts, so `buf.len()` data bytes are always clocked out; pass a buffer
+    /// of the expected block length for strict SMBus compliance.
+    pub fn block_read(&mut self, address: u8, command: u8, buf: &mut [u8]) -> Result<usize, Error> {
+        let len = buf.len().min(SMBUS_BLOCK_MAX);
+        let pec_len = self.pec as usize;
+        let mut raw = [0u8; SMBUS_BLOCK_MAX + 2];
+        self.i2c.enable_sub_address(command);
+        let ans = self.i2c.read_bytes(address, &mut raw[..1 + len + pec_len]);
+        self.i2c.disable_sub_address();
+        ans?;
+        let count = raw[0] as usize;
+        if count > len {
+            return Err(Error::BlockLength(raw[0]));
+        }
+        if self.pec {
+            let expected = read_pec(address, command, &raw[..=count]);
+            if raw[1 + count] != expected {
+                return Err(Error::Pec {
+                    expected,
+                    received: raw[1 + count],
+                });
             }
         }
+        buf[..count].copy_from_slice(&raw[1..=count]);
+        Ok(count)
+    }
+
+    fn write(&mut self, address: u8, command: u8, data: &[u8]) -> Result<(), Error> {
+        let mut buf = [0u8; SMBUS_BLOCK_MAX + 3];
+        buf[0] = command;
+        buf[1..=data.len()].copy_from_slice(data);
+        let mut len = 1 + data.len();
+        if self.pec {
+            buf[len] = crc8(crc8(0, &[address << 1]), &buf[..len]);
+            len += 1;
+        }
+        self.i2c.disable_sub_address();
+        self.i2c.write_bytes(address, &buf[..len])
+    }
+
+    fn read(&mut self, address: u8, command: u8, data: &mut [u8]) -> Result<(), Error> {
+        let mut buf = [0u8; 3];
+        let len = data.len() + self.pec as usize;
+        self.i2c.enable_sub_address(command);
+        let ans = self.i2c.read_bytes(address, &mut buf[..len]);
+        self.i2c.disable_sub_address();
+        ans?;
+        if self.pec {
+            let expected = read_pec(address, command, &buf[..data.len()]);
+            if buf[data.len()] != expected {
+                return Err(Error::Pec {
+                    expected,
+                    received: buf[data.len()],
+                });
+            }
+        }
+        data.copy_from_slice(&buf[..data.len()]);
         Ok(())
     }
 }
 
+/// Status polls allowed for a transfer of `len` data bytes and the address byte.
+#[inline]
+const fn transfer_polls(byte_polls: u32, stretch_polls: u32, len: usize) -> u32 {
+    byte_polls
+        .saturating_add(stretch_polls)
+        .saturating_mul(len as u32 + 1)
+}
+
+/// Release a bus held by a slave stuck in the middle of a transfer.
+///
+/// Clocks `scl` up to nine times until the slave releases `sda`, so that it finishes
+/// the byte it was sending; the next transfer then starts with a start condition that
+/// resets the slave. Pads should be released from [`I2c`] and converted to GPIO
+/// beforehand, with `scl` as an output and `sda` as an input, both pulled up.
+/// `half_period_ns` is half the clock period, e.g. `5_000` for 100 kHz.
+///
+/// Returns [`Error::ArbitrationOrStretch`] if `sda` is still held low afterwards.
+pub fn recover_bus<SCL, SDA, DELAY>(
+    scl: &mut SCL,
+    sda: &mut SDA,
+    delay: &mut DELAY,
+    half_period_ns: u32,
+) -> Result<(), Error>
+where
+    SCL: embedded_hal::digital::OutputPin,
+    SDA: embedded_hal::digital::InputPin,
+    DELAY: embedded_hal::delay::DelayNs,
+{
+    scl.set_high().map_err(|_| Error::Other)?;
+    for _ in 0..9 {
+        if sda.is_high().map_err(|_| Error::Other)? {
+            return Ok(());
+        }
+        scl.set_low().map_err(|_| Error::Other)?;
+        delay.delay_ns(half_period_ns);
+        scl.set_high().map_err(|_| Error::Other)?;
+        delay.delay_ns(half_period_ns);
+    }
+    if sda.is_high().map_err(|_| Error::Other)? {
+        Ok(())
+    } else {
+        Err(Error::ArbitrationOrStretch)
+    }
+}
+
+/// Check if 7-bit `address` is reserved by the bus specification and not used by devices.
+#[inline]
+const fn is_reserved_address(address: u8) -> bool {
+    address < 0x08 || address > 0x77
+}
+
+/// Packet error code of a read transfer with command code and received `data`.
+#[inline]
+fn read_pec(address: u8, command: u8, data: &[u8]) -> u8 {
+    crc8(crc8(0, &[address << 1, command, (address << 1) | 1]), data)
+}
+
+/// SMBus packet error code, CRC-8 with polynomial `x^8 + x^2 + x + 1`.
+#[inline]
+const fn crc8(mut crc: u8, data: &[u8]) -> u8 {
+    let mut i = 0;
+    while i < data.len() {
+        crc ^= data[i];
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+            bit += 1;
+        }
+        i += 1;
+    }
+    crc
+}
+
 pub trait SclPin<const I: usize> {}
 
 pub trait SdaPin<const I: usize> {}
@@ -703,7 +1156,8 @@ mod i2c_impls {
 #[cfg(test)]
 mod tests {
     use super::{
-        BusBusy, Config, FifoConfig0, FifoConfig1, Interrupt, InterruptClear, InterruptEnable,
+        byte_timeout, crc8, is_reserved_address, read_pec, recover_bus, transfer_polls, BusBusy,
+        Config, Error, FifoConfig0, FifoConfig1, Interrupt, InterruptClear, InterruptEnable,
         InterruptMask, InterruptState, PeriodData, PeriodStart, PeriodStop, RegisterBlock,
         SubAddressByteCount,
     };
@@ -959,4 +1413,99 @@ mod tests {
         fifo_config = FifoConfig1(0x0);
         assert_eq!(fifo_config.receive_threshold(), 0x00);
     }
+
+    #[test]
+    fn function_crc8() {
+        assert_eq!(crc8(0, b"123456789"), 0xf4);
+        assert_eq!(crc8(crc8(0, b"1234"), b"56789"), 0xf4);
+        assert_eq!(crc8(0, &[]), 0x00);
+        // SMBus read word from battery gauge 0x0b, command 0x09, data 0x3e8f.
+        let pec = read_pec(0x0b, 0x09, &[0x8f, 0x3e]);
+        assert_eq!(pec, crc8(0, &[0x16, 0x09, 0x17, 0x8f, 0x3e]));
+    }
+
+    #[test]
+    fn function_byte_timeout() {
+        assert_eq!(byte_timeout(0, 0), 72);
+        assert_eq!(byte_timeout(0xff, 0xff), 2 * 9 * 4 * 256 * 256);
+    }
+
+    #[test]
+    fn function_transfer_polls() {
+        assert_eq!(transfer_polls(100, 0, 0), 100);
+        assert_eq!(transfer_polls(100, 0, 3), 400);
+        assert_eq!(transfer_polls(100, 50, 3), 600);
+        assert_eq!(transfer_polls(u32::MAX, 1, 1), u32::MAX);
+    }
+
+    #[test]
+    fn function_recover_bus() {
+        use core::cell::Cell;
+        use core::convert::Infallible;
+        use embedded_hal::digital::{ErrorType, InputPin, OutputPin};
+
+        // Slave holds data line low until it has seen `held` clock pulses.
+        struct Bus {
+            pulses: Cell<usize>,
+            held: usize,
+        }
+        struct Scl<'a>(&'a Bus);
+        struct Sda<'a>(&'a Bus);
+        struct NoDelay;
+        impl ErrorType for Scl<'_> {
+            type Error = Infallible;
+        }
+        impl OutputPin for Scl<'_> {
+            fn set_low(&mut self) -> Result<(), Infallible> {
+                Ok(())
+            }
+            fn set_high(&mut self) -> Result<(), Infallible> {
+                self.0.pulses.set(self.0.pulses.get() + 1);
+                Ok(())
+            }
+        }
+        impl ErrorType for Sda<'_> {
+            type Error = Infallible;
+        }
+        impl InputPin for Sda<'_> {
+            fn is_high(&mut self) -> Result<bool, Infallible> {
+                // First rising edge only idles the clock line.
+                Ok(self.0.pulses.get() > self.0.held)
+            }
+            fn is_low(&mut self) -> Result<bool, Infallible> {
+                Ok(!self.is_high()?)
+            }
+        }
+        impl embedded_hal::delay::DelayNs for NoDelay {
+            fn delay_ns(&mut self, _: u32) {}
+        }
+
+        for (held, pulses) in [(0, 0), (3, 3), (9, 9)] {
+            let bus = Bus {
+                pulses: Cell::new(0),
+                held,
+            };
+            let ans = recover_bus(&mut Scl(&bus), &mut Sda(&bus), &mut NoDelay, 5_000);
+            assert!(ans.is_ok());
+            assert_eq!(bus.pulses.get() - 1, pulses);
+        }
+        let bus = Bus {
+            pulses: Cell::new(0),
+            held: 10,
+        };
+        let ans = recover_bus(&mut Scl(&bus), &mut Sda(&bus), &mut NoDelay, 5_000);
+        assert!(matches!(ans, Err(Error::ArbitrationOrStretch)));
+        assert_eq!(bus.pulses.get() - 1, 9);
+    }
+
+    #[test]
+    fn function_is_reserved_address() {
+        assert!(is_reserved_address(0x00));
+        assert!(is_reserved_address(0x07));
+        assert!(!is_reserved_address(0x08));
+        assert!(!is_reserved_address(0x77));
+        assert!(is_reserved_address(0x78));
+        assert!(is_reserved_address(0x7f));
+        assert_eq!((0..128).filter(|&a| !is_reserved_address(a)).count(), 112);
+    }
 }
@@ -1,5 +1,14 @@
 //! Inter-IC sound bus peripheral.
+//!
+//! Besides two channel formats, frames of up to four time division multiplexed (TDM)
+//! channels are supported in DSP format, with slot width independent of sample width.
+//!
+//! The controller only handles PCM frames. Pulse density modulated (PDM) microphones are
+//! decoded by the separate PDM interface with its decimation filter, which takes pads
+//! of `Function::Pdm` in GLB and delivers samples over DMA request `PdmRx`; it is not
+//! described by this crate yet.
 
+use core::ops::Deref;
 use volatile_register::{RO, RW, WO};
 
 /// Inter-IC sound bus peripheral registers.
@@ -30,6 +39,235 @@ pub struct RegisterBlock {
 #[repr(transparent)]
 pub struct Config(u32);
 
+impl Config {
+    const MASTER_ENABLE: u32 = 1 << 0;
+    const SLAVE_ENABLE: u32 = 1 << 1;
+    const TRANSMIT_ENABLE: u32 = 1 << 2;
+    const RECEIVE_ENABLE: u32 = 1 << 3;
+    const MONO_MODE: u32 = 1 << 4;
+    const MUTE: u32 = 1 << 5;
+    const SHORT_FRAME_SYNC: u32 = 1 << 6;
+    const CHANNEL_COUNT: u32 = 0x3 << 7;
+    const FRAME_SIZE: u32 = 0x3 << 12;
+    const DATA_SIZE: u32 = 0x3 << 14;
+    const MODE: u32 = 0x3 << 16;
+    const OFFSET: u32 = 0x1f << 20;
+    const OFFSET_ENABLE: u32 = 1 << 25;
+
+    /// Enable master mode.
+    #[inline]
+    pub const fn enable_master(self) -> Self {
+        Self(self.0 | Self::MASTER_ENABLE)
+    }
+    /// Disable master mode.
+    #[inline]
+    pub const fn disable_master(self) -> Self {
+        Self(self.0 & !Self::MASTER_ENABLE)
+    }
+    /// Check if master mode is enabled.
+    #[inline]
+    pub const fn is_master_enabled(self) -> bool {
+        self.0 & Self::MASTER_ENABLE != 0
+    }
+    /// Enable slave mode.
+    #[inline]
+    pub const fn enable_slave(self) -> Self {
+        Self(self.0 | Self::SLAVE_ENABLE)
+    }
+    /// Disable slave mode.
+    #[inline]
+    pub const fn disable_slave(self) -> Self {
+        Self(self.0 & !Self::SLAVE_ENABLE)
+    }
+    /// Check if slave mode is enabled.
+    #[inline]
+    pub const fn is_slave_enabled(self) -> bool {
+        self.0 & Self::SLAVE_ENABLE != 0
+    }
+    /// Enable data transmission.
+    #[inline]
+    pub const fn enable_transmit(self) -> Self {
+        Self(self.0 | Self::TRANSMIT_ENABLE)
+    }
+    /// Disable data transmission.
+    #[inline]
+    pub const fn disable_transmit(self) -> Self {
+        Self(self.0 & !Self::TRANSMIT_ENABLE)
+    }
+    /// Check if data transmission is enabled.
+    #[inline]
+    pub const fn is_transmit_enabled(self) -> bool {
+        self.0 & Self::TRANSMIT_ENABLE != 0
+    }
+    /// Enable data reception.
+    #[inline]
+    pub const fn enable_receive(self) -> Self {
+        Self(self.0 | Self::RECEIVE_ENABLE)
+    }
+    /// Disable data reception.
+    #[inline]
+    pub const fn disable_receive(self) -> Self {
+        Self(self.0 & !Self::RECEIVE_ENABLE)
+    }
+    /// Check if data reception is enabled.
+    #[inline]
+    pub const fn is_receive_enabled(self) -> bool {
+        self.0 & Self::RECEIVE_ENABLE != 0
+    }
+    /// Enable mono mode, sending the same sample on both channels.
+    #[inline]
+    pub const fn enable_mono(self) -> Self {
+        Self(self.0 | Self::MONO_MODE)
+    }
+    /// Disable mono mode.
+    #[inline]
+    pub const fn disable_mono(self) -> Self {
+        Self(self.0 & !Self::MONO_MODE)
+    }
+    /// Check if mono mode is enabled.
+    #[inline]
+    pub const fn is_mono_enabled(self) -> bool {
+        self.0 & Self::MONO_MODE != 0
+    }
+    /// Mute output, sending zeros.
+    #[inline]
+    pub const fn enable_mute(self) -> Self {
+        Self(self.0 | Self::MUTE)
+    }
+    /// Unmute output.
+    #[inline]
+    pub const fn disable_mute(self) -> Self {
+        Self(self.0 & !Self::MUTE)
+    }
+    /// Check if output is muted.
+    #[inline]
+    pub const fn is_mute_enabled(self) -> bool {
+        self.0 & Self::MUTE != 0
+    }
+    /// Shorten frame sync pulse to one bit clock cycle in DSP mode.
+    #[inline]
+    pub const fn enable_short_frame_sync(self) -> Self {
+        Self(self.0 | Self::SHORT_FRAME_SYNC)
+    }
+    /// Make frame sync pulse last one slot in DSP mode.
+    #[inline]
+    pub const fn disable_short_frame_sync(self) -> Self {
+        Self(self.0 & !Self::SHORT_FRAME_SYNC)
+    }
+    /// Check if frame sync pulse lasts one bit clock cycle.
+    #[inline]
+    pub const fn is_short_frame_sync_enabled(self) -> bool {
+        self.0 & Self::SHORT_FRAME_SYNC != 0
+    }
+    /// Set number of channels in each frame in DSP mode, from 1 to 4.
+    #[inline]
+    pub const fn set_channel_count(self, val: u8) -> Self {
+        Self((self.0 & !Self::CHANNEL_COUNT) | ((((val - 1) as u32) << 7) & Self::CHANNEL_COUNT))
+    }
+    /// Get number of channels in each frame in DSP mode.
+    #[inline]
+    pub const fn channel_count(self) -> u8 {
+        ((self.0 & Self::CHANNEL_COUNT) >> 7) as u8 + 1
+    }
+    /// Set slot size of each channel.
+    #[inline]
+    pub const fn set_frame_size(self, val: WordSize) -> Self {
+        Self((self.0 & !Self::FRAME_SIZE) | ((val as u32) << 12))
+    }
+    /// Get slot size of each channel.
+    #[inline]
+    pub const fn frame_size(self) -> WordSize {
+        WordSize::from_bits((self.0 & Self::FRAME_SIZE) >> 12)
+    }
+    /// Set sample size within each slot.
+    #[inline]
+    pub const fn set_data_size(self, val: WordSize) -> Self {
+        Self((self.0 & !Self::DATA_SIZE) | ((val as u32) << 14))
+    }
+    /// Get sample size within each slot.
+    #[inline]
+    pub const fn data_size(self) -> WordSize {
+        WordSize::from_bits((self.0 & Self::DATA_SIZE) >> 14)
+    }
+    /// Set data alignment mode.
+    #[inline]
+    pub const fn set_mode(self, val: Mode) -> Self {
+        Self((self.0 & !Self::MODE) | ((val as u32) << 16))
+    }
+    /// Get data alignment mode.
+    #[inline]
+    pub const fn mode(self) -> Mode {
+        match (self.0 & Self::MODE) >> 16 {
+            0 => Mode::LeftJustified,
+            1 => Mode::RightJustified,
+            _ => Mode::Dsp,
+        }
+    }
+    /// Set data offset from frame sync edge, in bit clock cycles minus one.
+    #[inline]
+    pub const fn set_offset(self, val: u8) -> Self {
+        Self((self.0 & !Self::OFFSET) | (((val as u32) << 20) & Self::OFFSET))
+    }
+    /// Get data offset from frame sync edge.
+    #[inline]
+    pub const fn offset(self) -> u8 {
+        ((self.0 & Self::OFFSET) >> 20) as u8
+    }
+    /// Enable data offset from frame sync edge.
+    #[inline]
+    pub const fn enable_offset(self) -> Self {
+        Self(self.0 | Self::OFFSET_ENABLE)
+    }
+    /// Disable data offset from frame sync edge.
+    #[inline]
+    pub const fn disable_offset(self) -> Self {
+        Self(self.0 & !Self::OFFSET_ENABLE)
+    }
+    /// Check if data offset from frame sync edge is enabled.
+    #[inline]
+    pub const fn is_offset_enabled(self) -> bool {
+        self.0 & Self::OFFSET_ENABLE != 0
+    }
+}
+
+/// Size of a slot or a sample in bits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum WordSize {
+    /// 8 bits.
+    Eight = 0,
+    /// 16 bits.
+    Sixteen = 1,
+    /// 24 bits.
+    TwentyFour = 2,
+    /// 32 bits.
+    ThirtyTwo = 3,
+}
+
+impl WordSize {
+    #[inline]
+    const fn from_bits(val: u32) -> Self {
+        match val {
+            0 => WordSize::Eight,
+            1 => WordSize::Sixteen,
+            2 => WordSize::TwentyFour,
+            _ => WordSize::ThirtyTwo,
+        }
+    }
+}
+
+/// Data alignment mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Mode {
+    /// Samples start at frame sync edge, plus offset if enabled.
+    LeftJustified = 0,
+    /// Samples end at frame sync edge.
+    RightJustified = 1,
+    /// Frame sync pulse starts a frame of all channels in turn.
+    Dsp = 2,
+}
+
 /// Interrupt configuration and state register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
@@ -40,24 +278,269 @@ pub struct InterruptConfig(u32);
 #[repr(transparent)]
 pub struct BclkConfig(u32);
 
+impl BclkConfig {
+    const LOW_PERIOD: u32 = 0xfff;
+    const HIGH_PERIOD: u32 = 0xfff << 16;
+
+    /// Set low period of bit clock, in source clock cycles minus one.
+    #[inline]
+    pub const fn set_low_period(self, val: u16) -> Self {
+        Self((self.0 & !Self::LOW_PERIOD) | (val as u32 & Self::LOW_PERIOD))
+    }
+    /// Get low period of bit clock.
+    #[inline]
+    pub const fn low_period(self) -> u16 {
+        (self.0 & Self::LOW_PERIOD) as u16
+    }
+    /// Set high period of bit clock, in source clock cycles minus one.
+    #[inline]
+    pub const fn set_high_period(self, val: u16) -> Self {
+        Self((self.0 & !Self::HIGH_PERIOD) | (((val as u32) << 16) & Self::HIGH_PERIOD))
+    }
+    /// Get high period of bit clock.
+    #[inline]
+    pub const fn high_period(self) -> u16 {
+        ((self.0 & Self::HIGH_PERIOD) >> 16) as u16
+    }
+}
+
 /// First-in first-out queue configuration register 0.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct FifoConfig0(u32);
 
+impl FifoConfig0 {
+    const TRANSMIT_FIFO_CLEAR: u32 = 1 << 2;
+    const RECEIVE_FIFO_CLEAR: u32 = 1 << 3;
+
+    /// Clear transmit FIFO.
+    #[inline]
+    pub const fn clear_transmit_fifo(self) -> Self {
+        Self(self.0 | Self::TRANSMIT_FIFO_CLEAR)
+    }
+    /// Clear receive FIFO.
+    #[inline]
+    pub const fn clear_receive_fifo(self) -> Self {
+        Self(self.0 | Self::RECEIVE_FIFO_CLEAR)
+    }
+}
+
 /// First-in first-out queue configuration register 1.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct FifoConfig1(u32);
 
+impl FifoConfig1 {
+    const TRANSMIT_COUNT: u32 = 0x3f;
+    const RECEIVE_COUNT: u32 = 0x3f << 8;
+
+    /// Get number of empty slots in transmit FIFO.
+    #[inline]
+    pub const fn transmit_available(self) -> u8 {
+        (self.0 & Self::TRANSMIT_COUNT) as u8
+    }
+    /// Get number of samples in receive FIFO.
+    #[inline]
+    pub const fn receive_available(self) -> u8 {
+        ((self.0 & Self::RECEIVE_COUNT) >> 8) as u8
+    }
+}
+
 /// Input/output signal configuration register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct IoConfig(u32);
 
+/// Frame format on the bus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// Standard I2S: two channels, data one bit clock after frame sync edge.
+    Philips,
+    /// Two channels, data at frame sync edge.
+    LeftJustified,
+    /// Two channels, data ending at frame sync edge.
+    RightJustified,
+    /// Frame sync pulse of one bit clock cycle, followed by all channels in turn.
+    DspShortFrame,
+    /// Frame sync pulse of one slot, followed by all channels in turn.
+    DspLongFrame,
+}
+
+/// Inter-IC sound bus configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct I2sConfig {
+    /// Frame format.
+    pub format: Format,
+    /// Slot size of each channel on the bus.
+    pub slot_size: WordSize,
+    /// Sample size within each slot, no larger than slot size.
+    pub data_size: WordSize,
+    /// Number of channels in each frame.
+    pub slots: u8,
+}
+
+impl I2sConfig {
+    /// Set frame format.
+    #[inline]
+    pub const fn set_format(self, format: Format) -> Self {
+        Self { format, ..self }
+    }
+    /// Set slot size of each channel, independently of sample size.
+    #[inline]
+    pub const fn set_slot_size(self, slot_size: WordSize) -> Self {
+        Self { slot_size, ..self }
+    }
+    /// Set sample size within each slot.
+    #[inline]
+    pub const fn set_data_size(self, data_size: WordSize) -> Self {
+        Self { data_size, ..self }
+    }
+    /// Set number of time division multiplexed channels in each frame.
+    ///
+    /// The controller packs 2, 3 or 4 channels into a frame; TDM frames use DSP format,
+    /// so a two channel format is switched to [`Format::DspShortFrame`] if `n` isn't 2.
+    /// Eight channel codecs should be run in four channel mode.
+    #[inline]
+    pub const fn set_tdm_slots(self, n: u8) -> Self {
+        let format = match self.format {
+            Format::DspShortFrame | Format::DspLongFrame => self.format,
+            _ if n != 2 => Format::DspShortFrame,
+            _ => self.format,
+        };
+        Self {
+            slots: n,
+            format,
+            ..self
+        }
+    }
+}
+
+impl Default for I2sConfig {
+    /// Standard I2S with two 16-bit channels.
+    #[inline]
+    fn default() -> Self {
+        Self {
+            format: Format::Philips,
+            slot_size: WordSize::Sixteen,
+            data_size: WordSize::Sixteen,
+            slots: 2,
+        }
+    }
+}
+
+/// Inter-IC sound bus configuration error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigError {
+    /// Number of slots is not 2 to 4, or not 2 in a two channel format.
+    SlotCount,
+    /// Sample size is larger than slot size.
+    DataLargerThanSlot,
+}
+
+/// Managed Inter-IC sound bus peripheral in master transmit mode.
+///
+/// Pads for bit clock, frame sync and data output should be routed to I2S function
+/// by the caller.
+pub struct I2s<I2S> {
+    i2s: I2S,
+    slots: u8,
+}
+
+impl<I2S: Deref<Target = RegisterBlock>> I2s<I2S> {
+    /// Create and enable a bus master transmitting frames in `config`.
+    #[inline]
+    pub fn new(i2s: I2S, config: I2sConfig) -> Result<Self, ConfigError> {
+        let val = i2s_config(config)?;
+        unsafe {
+            i2s.config.write(Config(0));
+            i2s.fifo_config_0
+                .modify(|v| v.clear_transmit_fifo().clear_receive_fifo());
+            i2s.config.write(val.enable_master().enable_transmit());
+        }
+        Ok(I2s {
+            i2s,
+            slots: config.slots,
+        })
+    }
+    /// Set length of each bit clock half period in I2S source clock cycles, from 1 to 4096.
+    ///
+    /// Frame rate is source clock divided by twice this value, slot count and slot size.
+    #[inline]
+    pub fn set_half_period(&mut self, cycles: u16) {
+        assert!(
+            (1..=4096).contains(&cycles),
+            "half period should be in 1..=4096 cycles"
+        );
+        let val = cycles - 1;
+        unsafe {
+            self.i2s
+                .bclk_config
+                .write(BclkConfig(0).set_low_period(val).set_high_period(val))
+        };
+    }
+    /// Write interleaved samples of whole frames, blocking until all are queued.
+    ///
+    /// Each frame holds one sample per slot, first slot first. Samples are right aligned,
+    /// e.g. a 24-bit sample takes the lower 24 bits.
+    ///
+    /// # Panics
+    ///
+    /// If `frames` doesn't hold a whole number of frames.
+    #[inline]
+    pub fn write_tdm(&mut self, frames: &[i32]) {
+        assert!(
+            frames.len().is_multiple_of(self.slots as usize),
+            "samples should fill whole frames"
+        );
+        for &sample in frames {
+            while self.i2s.fifo_config_1.read().transmit_available() == 0 {
+                core::hint::spin_loop();
+            }
+            unsafe { self.i2s.fifo_write.write(sample as u32) };
+        }
+    }
+    /// Disable the peripheral and release it.
+    #[inline]
+    pub fn free(self) -> I2S {
+        unsafe { self.i2s.config.write(Config(0)) };
+        self.i2s
+    }
+}
+
+/// Compute configuration register value for `config`, without enabling the bus.
+#[inline]
+const fn i2s_config(config: I2sConfig) -> Result<Config, ConfigError> {
+    let dsp = matches!(config.format, Format::DspShortFrame | Format::DspLongFrame);
+    if config.slots < 2 || config.slots > 4 || (!dsp && config.slots != 2) {
+        return Err(ConfigError::SlotCount);
+    }
+    if config.data_size as u8 > config.slot_size as u8 {
+        return Err(ConfigError::DataLargerThanSlot);
+    }
+    let val = Config(0)
+        .set_frame_size(config.slot_size)
+        .set_data_size(config.data_size);
+    Ok(match config.format {
+        Format::Philips => val
+            .set_mode(Mode::LeftJustified)
+            .set_offset(0)
+            .enable_offset(),
+        Format::LeftJustified => val.set_mode(Mode::LeftJustified),
+        Format::RightJustified => val.set_mode(Mode::RightJustified),
+        Format::DspShortFrame => val
+            .set_mode(Mode::Dsp)
+            .enable_short_frame_sync()
+            .set_channel_count(config.slots),
+        Format::DspLongFrame => val.set_mode(Mode::Dsp).set_channel_count(config.slots),
+    })
+}
+
 #[cfg(test)]
 mod tests {
-    use super::RegisterBlock;
+    use super::{
+        i2s_config, BclkConfig, Config, ConfigError, Format, I2sConfig, Mode, RegisterBlock,
+        WordSize,
+    };
     use memoffset::offset_of;
 
     #[test]
@@ -71,4 +554,89 @@ mod tests {
         assert_eq!(offset_of!(RegisterBlock, fifo_read), 0x8c);
         assert_eq!(offset_of!(RegisterBlock, io_config), 0xfc);
     }
+
+    #[test]
+    fn struct_config_functions() {
+        let mut val = Config(0x0);
+
+        val = val.enable_master().enable_transmit();
+        assert_eq!(val.0, 0x0000_0005);
+        assert!(val.is_master_enabled());
+        assert!(val.is_transmit_enabled());
+        assert!(!val.is_receive_enabled());
+        val = val.set_channel_count(4);
+        assert_eq!(val.0, 0x0000_0185);
+        assert_eq!(val.channel_count(), 4);
+        val = val.set_frame_size(WordSize::ThirtyTwo);
+        assert_eq!(val.0, 0x0000_3185);
+        assert_eq!(val.frame_size(), WordSize::ThirtyTwo);
+        val = val.set_data_size(WordSize::TwentyFour);
+        assert_eq!(val.0, 0x0000_b185);
+        assert_eq!(val.data_size(), WordSize::TwentyFour);
+        val = val.set_mode(Mode::Dsp).enable_short_frame_sync();
+        assert_eq!(val.0, 0x0002_b1c5);
+        assert_eq!(val.mode(), Mode::Dsp);
+        assert!(val.is_short_frame_sync_enabled());
+        val = val.set_offset(0x1f).enable_offset();
+        assert_eq!(val.0, 0x03f2_b1c5);
+        assert_eq!(val.offset(), 0x1f);
+        assert!(val.is_offset_enabled());
+        val = val.disable_master().disable_transmit().enable_mute();
+        assert!(val.is_mute_enabled());
+        assert_eq!(val.0, 0x03f2_b1e0);
+    }
+
+    #[test]
+    fn struct_bclk_config_functions() {
+        let mut val = BclkConfig(0x0);
+
+        val = val.set_low_period(0xfff);
+        assert_eq!(val.0, 0x0000_0fff);
+        assert_eq!(val.low_period(), 0xfff);
+        val = val.set_high_period(0x123);
+        assert_eq!(val.0, 0x0123_0fff);
+        assert_eq!(val.high_period(), 0x123);
+    }
+
+    #[test]
+    fn function_i2s_config() {
+        let val = i2s_config(I2sConfig::default()).unwrap();
+        assert_eq!(val.mode(), Mode::LeftJustified);
+        assert!(val.is_offset_enabled());
+        assert_eq!(val.frame_size(), WordSize::Sixteen);
+
+        // Four 32-bit slots carrying 24-bit samples, as for multi-channel codecs.
+        let config = I2sConfig::default()
+            .set_slot_size(WordSize::ThirtyTwo)
+            .set_data_size(WordSize::TwentyFour)
+            .set_tdm_slots(4);
+        assert_eq!(config.format, Format::DspShortFrame);
+        let val = i2s_config(config).unwrap();
+        assert_eq!(val.mode(), Mode::Dsp);
+        assert!(val.is_short_frame_sync_enabled());
+        assert_eq!(val.channel_count(), 4);
+        assert_eq!(val.frame_size(), WordSize::ThirtyTwo);
+        assert_eq!(val.data_size(), WordSize::TwentyFour);
+
+        let config = I2sConfig::default()
+            .set_format(Format::DspLongFrame)
+            .set_tdm_slots(2);
+        let val = i2s_config(config).unwrap();
+        assert!(!val.is_short_frame_sync_enabled());
+        assert_eq!(val.channel_count(), 2);
+
+        assert_eq!(
+            i2s_config(I2sConfig::default().set_tdm_slots(8)),
+            Err(ConfigError::SlotCount)
+        );
+        let config = I2sConfig {
+            slots: 4,
+            ..I2sConfig::default()
+        };
+        assert_eq!(i2s_config(config), Err(ConfigError::SlotCount));
+        assert_eq!(
+            i2s_config(I2sConfig::default().set_data_size(WordSize::ThirtyTwo)),
+            Err(ConfigError::DataLargerThanSlot)
+        );
+    }
 }
@@ -0,0 +1,357 @@
+//! Inter-Processor Communication mailbox.
+//!
+//! Each BL808 core has an IPC peripheral with 32 interrupt channels any core can raise.
+//! The peripheral carries no data by itself, so [`Mailbox`] pairs every channel with a
+//! word in [`MailboxSlots`], a block of memory both cores can reach at the same address.
+//! A sender writes the word and raises the channel; the receiving core's interrupt
+//! handler moves the word into a per-channel queue and clears the channel, which lets
+//! the next message through. Each direction uses its own slots, and each channel should
+//! have only one sending core.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! static MAILBOX_STATE: MailboxState<4, 8> = MailboxState::new();
+//!
+//! #[interrupt]
+//! fn ipc_d0() {
+//!     MAILBOX_STATE.on_interrupt();
+//! }
+//!
+//! // Slots at addresses agreed with the firmware of M0 core.
+//! let inbox = unsafe { &*(0x3eff_7000 as *const MailboxSlots) };
+//! let outbox = unsafe { &*(0x3eff_7080 as *const MailboxSlots) };
+//! let mut mailbox = Mailbox::new(p.ipc_d0, p.ipc_m0, inbox, outbox, &MAILBOX_STATE);
+//! nb::block!(mailbox.send(0, 0x1234)).ok();
+//! let reply = mailbox.receive(1).await;
+//! ```
+
+use core::{
+    cell::RefCell,
+    future::poll_fn,
+    ops::Deref,
+    sync::atomic::{fence, AtomicU32, AtomicUsize, Ordering},
+    task::Poll,
+};
+use critical_section::Mutex;
+use heapless::spsc::Queue;
+use volatile_register::{RO, RW, WO};
+
+/// Number of channels of an IPC peripheral.
+pub const CHANNEL_COUNT: usize = 32;
+
+/// Inter-processor communication registers of one core.
+#[repr(C)]
+pub struct RegisterBlock {
+    /// Raise channels of this core by writing ones.
+    pub interrupt_set: WO<u32>,
+    /// Raised channels, masked or not.
+    pub raw_interrupt_state: RO<u32>,
+    /// Clear raised channels by writing ones.
+    pub interrupt_clear: WO<u32>,
+    /// Unmask channels by writing ones.
+    pub interrupt_unmask: WO<u32>,
+    /// Mask channels by writing ones.
+    pub interrupt_mask: WO<u32>,
+    /// Interrupt line select of channels 0 to 15.
+    pub line_select_low: RW<u32>,
+    /// Interrupt line select of channels 16 to 31.
+    pub line_select_high: RW<u32>,
+    /// Raised and unmasked channels.
+    pub interrupt_state: RO<u32>,
+}
+
+/// Message words of all channels in one direction.
+///
+/// It must be placed in memory shared by both cores, at an address known to both
+/// firmware images, e.g. a fixed location in D0 core's data RAM.
+#[repr(C)]
+pub struct MailboxSlots([AtomicU32; CHANNEL_COUNT]);
+
+impl MailboxSlots {
+    /// Creates a set of empty message words.
+    #[inline]
+    pub const fn new() -> MailboxSlots {
+        MailboxSlots([const { AtomicU32::new(0) }; CHANNEL_COUNT])
+    }
+}
+
+impl Default for MailboxSlots {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Inter-processor communication error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IpcError {
+    /// Channel number is not below the channel count of the mailbox.
+    InvalidChannel,
+}
+
+/// Mailbox sending words to another core and receiving words from it.
+pub struct Mailbox<LOCAL, REMOTE, const C: usize, const N: usize> {
+    local: LOCAL,
+    remote: REMOTE,
+    outbox: &'static MailboxSlots,
+    state: &'static MailboxState<C, N>,
+}
+
+impl<
+        LOCAL: Deref<Target = RegisterBlock>,
+        REMOTE: Deref<Target = RegisterBlock>,
+        const C: usize,
+        const N: usize,
+    > Mailbox<LOCAL, REMOTE, C, N>
+{
+    /// Creates a mailbox receiving on `local` IPC peripheral from `inbox`, and sending to
+    /// `remote` IPC peripheral through `outbox`.
+    ///
+    /// Channels `0..C` of the local peripheral are unmasked; the IPC interrupt of this
+    /// core should call [`MailboxState::on_interrupt`].
+    #[inline]
+    pub fn new(
+        local: LOCAL,
+        remote: REMOTE,
+        inbox: &'static MailboxSlots,
+        outbox: &'static MailboxSlots,
+        state: &'static MailboxState<C, N>,
+    ) -> Self {
+        state
+            .ref_to_inbox
+            .store(inbox as *const _ as usize, Ordering::Release);
+        state
+            .ref_to_ipc
+            .store(&*local as *const _ as usize, Ordering::Release);
+        unsafe { local.interrupt_unmask.write(channel_mask(C)) };
+        Mailbox {
+            local,
+            remote,
+            outbox,
+            state,
+        }
+    }
+    /// Send `value` on `channel`.
+    ///
+    /// Returns `WouldBlock` while the remote core hasn't taken the previous message of
+    /// this channel.
+    #[inline]
+    pub fn send(&mut self, channel: u8, value: u32) -> nb::Result<(), IpcError> {
+        let channel = check_channel(channel, C)?;
+        if self.remote.raw_interrupt_state.read() & (1 << channel) != 0 {
+            return Err(nb::Error::WouldBlock);
+        }
+        self.outbox.0[channel].store(value, Ordering::Relaxed);
+        // Message word must be visible before the remote core is interrupted.
+        fence(Ordering::SeqCst);
+        unsafe { self.remote.interrupt_set.write(1 << channel) };
+        Ok(())
+    }
+    /// Take the oldest received message of `channel`, if any.
+    ///
+    /// # Panics
+    ///
+    /// If `channel` is not below the channel count of this mailbox.
+    #[inline]
+    pub fn try_receive(&mut self, channel: u8) -> Option<u32> {
+        let Ok(channel) = check_channel(channel, C) else {
+            panic!("invalid IPC channel");
+        };
+        let ans =
+            critical_section::with(|cs| self.state.queues.borrow_ref_mut(cs)[channel].dequeue());
+        if ans.is_some() {
+            // The interrupt handler masks a channel whose queue is full; there's room now.
+            unsafe { self.local.interrupt_unmask.write(1 << channel) };
+        }
+        ans
+    }
+    /// Wait for a message on `channel`.
+    ///
+    /// # Panics
+    ///
+    /// If `channel` is not below the channel count of this mailbox.
+    #[inline]
+    pub async fn receive(&mut self, channel: u8) -> u32 {
+        poll_fn(|cx| {
+            // Register before checking, so an interrupt in between still wakes us.
+            if let Some(waker) = self.state.wakers.get(channel as usize) {
+                waker.register(cx.waker());
+            }
+            match self.try_receive(channel) {
+                Some(value) => Poll::Ready(value),
+                None => Poll::Pending,
+            }
+        })
+        .await
+    }
+    /// Release the mailbox and return its IPC peripherals.
+    ///
+    /// Channels of the local peripheral are masked; queued messages are kept in state.
+    #[inline]
+    pub fn free(self) -> (LOCAL, REMOTE) {
+        unsafe { self.local.interrupt_mask.write(channel_mask(C)) };
+        self.state.ref_to_ipc.store(0, Ordering::Release);
+        (self.local, self.remote)
+    }
+}
+
+/// Message queues and wakers of `C` channels, each queue holding up to `N - 1` words.
+pub struct MailboxState<const C: usize, const N: usize> {
+    queues: Mutex<RefCell<[Queue<u32, N>; C]>>,
+    wakers: [atomic_waker::AtomicWaker; C],
+    ref_to_ipc: AtomicUsize,
+    ref_to_inbox: AtomicUsize,
+}
+
+impl<const C: usize, const N: usize> MailboxState<C, N> {
+    /// Creates empty queues and wakers for a mailbox.
+    #[inline]
+    pub const fn new() -> Self {
+        MailboxState {
+            queues: Mutex::new(RefCell::new([const { Queue::new() }; C])),
+            wakers: [const { atomic_waker::AtomicWaker::new() }; C],
+            ref_to_ipc: AtomicUsize::new(0),
+            ref_to_inbox: AtomicUsize::new(0),
+        }
+    }
+    /// Use this state to handle IPC interrupt.
+    ///
+    /// Moves the message of every raised channel into its queue and clears the channel,
+    /// waking tasks waiting on it. A channel whose queue is full is masked instead and
+    /// stays raised, so its sender waits until the queue is drained.
+    #[inline]
+    pub fn on_interrupt(&self) {
+        let ipc = self.ref_to_ipc.load(Ordering::Acquire) as *const RegisterBlock;
+        if ipc.is_null() {
+            return;
+        }
+        let ipc = unsafe { &*ipc };
+        let inbox = unsafe { &*(self.ref_to_inbox.load(Ordering::Acquire) as *const MailboxSlots) };
+        let pending = ipc.interrupt_state.read() & channel_mask(C);
+        // Sender has written the message word before raising its channel.
+        fence(Ordering::SeqCst);
+        let (received, full) = critical_section::with(|cs| {
+            let mut queues = self.queues.borrow_ref_mut(cs);
+            receive_pending(
+                pending,
+                |channel| inbox.0[channel].load(Ordering::Relaxed),
+                &mut queues[..],
+            )
+        });
+        unsafe {
+            if full != 0 {
+                ipc.interrupt_mask.write(full);
+            }
+            if received != 0 {
+                ipc.interrupt_clear.write(received);
+            }
+        }
+        for (channel, waker) in self.wakers.iter().enumerate() {
+            if (received | full) & (1 << channel) != 0 {
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl<const C: usize, const N: usize> Default for MailboxState<C, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Bits of channels `0..count`.
+#[inline]
+const fn channel_mask(count: usize) -> u32 {
+    if count >= CHANNEL_COUNT {
+        u32::MAX
+    } else {
+        (1 << count) - 1
+    }
+}
+
+#[inline]
+const fn check_channel(channel: u8, count: usize) -> Result<usize, IpcError> {
+    if (channel as usize) < count && (channel as usize) < CHANNEL_COUNT {
+        Ok(channel as usize)
+    } else {
+        Err(IpcError::InvalidChannel)
+    }
+}
+
+/// Queue messages of `pending` channels read by `read`.
+///
+/// Returns bits of channels received, and of channels left pending for their queue is full.
+#[inline]
+fn receive_pending<const N: usize>(
+    pending: u32,
+    mut read: impl FnMut(usize) -> u32,
+    queues: &mut [Queue<u32, N>],
+) -> (u32, u32) {
+    let (mut received, mut full) = (0, 0);
+    for (channel, queue) in queues.iter_mut().enumerate() {
+        if pending & (1 << channel) == 0 {
+            continue;
+        }
+        if queue.enqueue(read(channel)).is_ok() {
+            received |= 1 << channel;
+        } else {
+            full |= 1 << channel;
+        }
+    }
+    (received, full)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{channel_mask, check_channel, receive_pending, IpcError, RegisterBlock};
+    use heapless::spsc::Queue;
+    use memoffset::offset_of;
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, interrupt_set), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, raw_interrupt_state), 0x04);
+        assert_eq!(offset_of!(RegisterBlock, interrupt_clear), 0x08);
+        assert_eq!(offset_of!(RegisterBlock, interrupt_unmask), 0x0c);
+        assert_eq!(offset_of!(RegisterBlock, interrupt_mask), 0x10);
+        assert_eq!(offset_of!(RegisterBlock, line_select_low), 0x14);
+        assert_eq!(offset_of!(RegisterBlock, line_select_high), 0x18);
+        assert_eq!(offset_of!(RegisterBlock, interrupt_state), 0x1c);
+    }
+
+    #[test]
+    fn function_channel_mask() {
+        assert_eq!(channel_mask(0), 0);
+        assert_eq!(channel_mask(4), 0xf);
+        assert_eq!(channel_mask(32), u32::MAX);
+        assert_eq!(check_channel(3, 4), Ok(3));
+        assert_eq!(check_channel(4, 4), Err(IpcError::InvalidChannel));
+        assert_eq!(check_channel(32, 40), Err(IpcError::InvalidChannel));
+    }
+
+    #[test]
+    fn function_receive_pending() {
+        let mut queues: [Queue<u32, 3>; 3] = [const { Queue::new() }; 3];
+        let slots = [10, 11, 12];
+        assert_eq!(
+            receive_pending(0b101, |c| slots[c], &mut queues),
+            (0b101, 0)
+        );
+        assert_eq!(
+            receive_pending(0b001, |c| slots[c], &mut queues),
+            (0b001, 0)
+        );
+        // Queue of channel 0 holds two words; the third stays in its slot.
+        assert_eq!(
+            receive_pending(0b011, |c| slots[c], &mut queues),
+            (0b010, 0b001)
+        );
+        assert_eq!(queues[0].dequeue(), Some(10));
+        assert_eq!(queues[1].dequeue(), Some(11));
+        assert_eq!(queues[2].dequeue(), Some(12));
+        assert_eq!(queues[2].dequeue(), None);
+    }
+}
@@ -1,5 +1,19 @@
 //! Infrared remote peripheral.
+//!
+//! Remote control protocols are not decoded in this module. Instead, receiver in pulse
+//! width mode reports edges as [`IrEvent`]s, which fit event-driven decoders like those of
+//! the `infrared` crate:
+//!
+//! ```ignore
+//! let mut decoder = infrared::Receiver::builder().nec().event_driven().resolution(1_000_000).build();
+//! while let Ok(event) = nb::block!(ir_rx.read_event()) {
+//!     if let Ok(Some(command)) = decoder.event(event.dt, event.edge) {
+//!         // handle command
+//!     }
+//! }
+//! ```
 
+use core::ops::Deref;
 use volatile_register::{RO, RW};
 
 /// Infrared remote peripheral registers.
@@ -35,29 +49,368 @@ pub struct RegisterBlock {
 #[repr(transparent)]
 pub struct ReceiveConfig(u32);
 
+impl ReceiveConfig {
+    const ENABLE: u32 = 1 << 0;
+    const INPUT_INVERSE: u32 = 1 << 1;
+    const MODE: u32 = 0x3 << 2;
+    const DEGLITCH_ENABLE: u32 = 1 << 4;
+    const DEGLITCH_COUNT: u32 = 0xf << 8;
+
+    /// Enable receiver.
+    #[inline]
+    pub const fn enable_receive(self) -> Self {
+        Self(self.0 | Self::ENABLE)
+    }
+    /// Disable receiver.
+    #[inline]
+    pub const fn disable_receive(self) -> Self {
+        Self(self.0 & !Self::ENABLE)
+    }
+    /// Check if receiver is enabled.
+    #[inline]
+    pub const fn is_receive_enabled(self) -> bool {
+        self.0 & Self::ENABLE != 0
+    }
+    /// Enable input signal inverse.
+    #[inline]
+    pub const fn enable_input_inverse(self) -> Self {
+        Self(self.0 | Self::INPUT_INVERSE)
+    }
+    /// Disable input signal inverse.
+    #[inline]
+    pub const fn disable_input_inverse(self) -> Self {
+        Self(self.0 & !Self::INPUT_INVERSE)
+    }
+    /// Check if input signal inverse is enabled.
+    #[inline]
+    pub const fn is_input_inverse_enabled(self) -> bool {
+        self.0 & Self::INPUT_INVERSE != 0
+    }
+    /// Set receive mode.
+    #[inline]
+    pub const fn set_mode(self, mode: ReceiveMode) -> Self {
+        Self((self.0 & !Self::MODE) | ((mode as u32) << 2))
+    }
+    /// Get receive mode.
+    #[inline]
+    pub const fn mode(self) -> ReceiveMode {
+        match (self.0 & Self::MODE) >> 2 {
+            0 => ReceiveMode::Nec,
+            1 => ReceiveMode::Rc5,
+            2 => ReceiveMode::PulseWidth,
+            _ => unreachable!(),
+        }
+    }
+    /// Enable input deglitch.
+    #[inline]
+    pub const fn enable_deglitch(self) -> Self {
+        Self(self.0 | Self::DEGLITCH_ENABLE)
+    }
+    /// Disable input deglitch.
+    #[inline]
+    pub const fn disable_deglitch(self) -> Self {
+        Self(self.0 & !Self::DEGLITCH_ENABLE)
+    }
+    /// Check if input deglitch is enabled.
+    #[inline]
+    pub const fn is_deglitch_enabled(self) -> bool {
+        self.0 & Self::DEGLITCH_ENABLE != 0
+    }
+    /// Set deglitch cycle count.
+    #[inline]
+    pub const fn set_deglitch_count(self, val: u8) -> Self {
+        Self((self.0 & !Self::DEGLITCH_COUNT) | (((val as u32) << 8) & Self::DEGLITCH_COUNT))
+    }
+    /// Get deglitch cycle count.
+    #[inline]
+    pub const fn deglitch_count(self) -> u8 {
+        ((self.0 & Self::DEGLITCH_COUNT) >> 8) as u8
+    }
+}
+
+/// Infrared receive mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum ReceiveMode {
+    /// Hardware NEC protocol decoder.
+    Nec = 0,
+    /// Hardware RC-5 protocol decoder.
+    Rc5 = 1,
+    /// Software decoding from pulse widths in FIFO queue.
+    PulseWidth = 2,
+}
+
 /// Receive interrupt state and configuration register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct ReceiveInterrupt(u32);
 
+impl ReceiveInterrupt {
+    const END: u32 = 1 << 0;
+    const END_MASK: u32 = 1 << 8;
+    const END_CLEAR: u32 = 1 << 16;
+    const END_ENABLE: u32 = 1 << 24;
+
+    /// Check if end of frame interrupt is pending.
+    #[inline]
+    pub const fn is_end(self) -> bool {
+        self.0 & Self::END != 0
+    }
+    /// Mask end of frame interrupt.
+    #[inline]
+    pub const fn mask_end(self) -> Self {
+        Self(self.0 | Self::END_MASK)
+    }
+    /// Unmask end of frame interrupt.
+    #[inline]
+    pub const fn unmask_end(self) -> Self {
+        Self(self.0 & !Self::END_MASK)
+    }
+    /// Check if end of frame interrupt is masked.
+    #[inline]
+    pub const fn is_end_masked(self) -> bool {
+        self.0 & Self::END_MASK != 0
+    }
+    /// Clear end of frame interrupt.
+    #[inline]
+    pub const fn clear_end(self) -> Self {
+        Self(self.0 | Self::END_CLEAR)
+    }
+    /// Enable end of frame interrupt.
+    #[inline]
+    pub const fn enable_end(self) -> Self {
+        Self(self.0 | Self::END_ENABLE)
+    }
+    /// Disable end of frame interrupt.
+    #[inline]
+    pub const fn disable_end(self) -> Self {
+        Self(self.0 & !Self::END_ENABLE)
+    }
+    /// Check if end of frame interrupt is enabled.
+    #[inline]
+    pub const fn is_end_enabled(self) -> bool {
+        self.0 & Self::END_ENABLE != 0
+    }
+}
+
 /// Receive pulse width threshold configuration.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct ReceiveThreshold(u32);
 
+impl ReceiveThreshold {
+    const DATA: u32 = 0xffff;
+    const END: u32 = 0xffff << 16;
+
+    /// Set pulse width threshold between bit 0 and bit 1 for hardware decoders.
+    #[inline]
+    pub const fn set_data_threshold(self, val: u16) -> Self {
+        Self((self.0 & !Self::DATA) | val as u32)
+    }
+    /// Get pulse width threshold between bit 0 and bit 1 for hardware decoders.
+    #[inline]
+    pub const fn data_threshold(self) -> u16 {
+        (self.0 & Self::DATA) as u16
+    }
+    /// Set idle width threshold that ends a frame.
+    #[inline]
+    pub const fn set_end_threshold(self, val: u16) -> Self {
+        Self((self.0 & !Self::END) | ((val as u32) << 16))
+    }
+    /// Get idle width threshold that ends a frame.
+    #[inline]
+    pub const fn end_threshold(self) -> u16 {
+        ((self.0 & Self::END) >> 16) as u16
+    }
+}
+
 /// First-in first-out queue configuration register 0.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct FifoConfig0(u32);
 
+impl FifoConfig0 {
+    const RECEIVE_FIFO_CLEAR: u32 = 1 << 2;
+    const RECEIVE_FIFO_OVERFLOW: u32 = 1 << 4;
+    const RECEIVE_FIFO_UNDERFLOW: u32 = 1 << 5;
+
+    /// Clear receive first-in first-out queue.
+    #[inline]
+    pub const fn clear_receive_fifo(self) -> Self {
+        Self(self.0 | Self::RECEIVE_FIFO_CLEAR)
+    }
+    /// Check if receive first-in first-out queue has overflowed.
+    #[inline]
+    pub const fn is_receive_fifo_overflow(self) -> bool {
+        self.0 & Self::RECEIVE_FIFO_OVERFLOW != 0
+    }
+    /// Check if receive first-in first-out queue has underflowed.
+    #[inline]
+    pub const fn is_receive_fifo_underflow(self) -> bool {
+        self.0 & Self::RECEIVE_FIFO_UNDERFLOW != 0
+    }
+}
+
 /// First-in first-out queue configuration register 1.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct FifoConfig1(u32);
 
+impl FifoConfig1 {
+    const RECEIVE_COUNT: u32 = 0x7f;
+
+    /// Get number of pulse widths in receive FIFO queue.
+    #[inline]
+    pub const fn receive_available(self) -> u8 {
+        (self.0 & Self::RECEIVE_COUNT) as u8
+    }
+}
+
+/// Infrared receiver configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IrRxConfig {
+    /// Frequency of infrared peripheral clock, as configured in global peripheral.
+    pub clock_hz: u32,
+    /// Invert input signal, for receiver modules with active-low output.
+    pub invert: bool,
+    /// Filter out glitches shorter than this number of clock cycles, or `None` to disable.
+    pub deglitch: Option<u8>,
+    /// Idle width in clock cycles that ends a frame.
+    pub end_threshold: u16,
+}
+
+impl Default for IrRxConfig {
+    /// Active-low receiver module on 1 MHz clock, with frames ended by 10 ms of idle.
+    #[inline]
+    fn default() -> Self {
+        Self {
+            clock_hz: 1_000_000,
+            invert: true,
+            deglitch: None,
+            end_threshold: 10_000,
+        }
+    }
+}
+
+/// Edge on infrared receive signal.
+///
+/// Fields match the `event(dt, edge)` arguments of `infrared` crate event-driven receivers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IrEvent {
+    /// Microseconds since previous edge.
+    pub dt: u32,
+    /// Signal level after this edge, `true` if carrier is present.
+    pub edge: bool,
+}
+
+/// Infrared receive error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// Pulse widths were lost because the queue was not read in time.
+    FifoOverflow,
+}
+
+/// Managed infrared receiver in pulse width mode.
+///
+/// Each pulse of a frame is queued in hardware as its width in clock cycles, starting with
+/// a carrier-present pulse. The receiver converts widths into [`IrEvent`]s; the leading
+/// edge of each frame is reported with the end threshold as idle time before it.
+pub struct IrRx<IR, PADS> {
+    ir: IR,
+    pads: PADS,
+    clock_hz: u32,
+    idle_us: u32,
+    index: usize,
+}
+
+impl<IR: Deref<Target = RegisterBlock>, PADS> IrRx<IR, PADS> {
+    /// Create and enable infrared receiver in pulse width mode.
+    #[inline]
+    pub fn new(ir: IR, pads: PADS, config: IrRxConfig) -> Self {
+        let mut receive_config = ReceiveConfig(0).set_mode(ReceiveMode::PulseWidth);
+        if config.invert {
+            receive_config = receive_config.enable_input_inverse();
+        }
+        if let Some(count) = config.deglitch {
+            receive_config = receive_config.enable_deglitch().set_deglitch_count(count);
+        }
+        unsafe {
+            ir.receive_config.write(receive_config);
+            ir.receive_threshold
+                .modify(|v| v.set_end_threshold(config.end_threshold));
+            ir.receive_interrupt
+                .modify(|v| v.mask_end().enable_end().clear_end());
+            ir.fifo_config_0.modify(|v| v.clear_receive_fifo());
+            ir.receive_config.write(receive_config.enable_receive());
+        }
+        IrRx {
+            ir,
+            pads,
+            clock_hz: config.clock_hz,
+            idle_us: ticks_to_us(config.end_threshold as u32, config.clock_hz),
+            index: 0,
+        }
+    }
+
+    /// Disable receiver and return its peripheral and pads.
+    #[inline]
+    pub fn free(self) -> (IR, PADS) {
+        unsafe { self.ir.receive_config.modify(|v| v.disable_receive()) };
+        (self.ir, self.pads)
+    }
+
+    /// Read next edge of received signal.
+    ///
+    /// Should be polled at least once per frame, so that the queue does not overflow
+    /// and pulses of adjacent frames are separated correctly.
+    pub fn read_event(&mut self) -> nb::Result<IrEvent, Error> {
+        if self.ir.fifo_config_0.read().is_receive_fifo_overflow() {
+            unsafe { self.ir.fifo_config_0.modify(|v| v.clear_receive_fifo()) };
+            self.index = 0;
+            return Err(nb::Error::Other(Error::FifoOverflow));
+        }
+        if self.ir.fifo_config_1.read().receive_available() == 0 {
+            if self.ir.receive_interrupt.read().is_end() {
+                unsafe { self.ir.receive_interrupt.modify(|v| v.clear_end()) };
+                self.index = 0;
+            }
+            return Err(nb::Error::WouldBlock);
+        }
+        if self.index == 0 {
+            self.index = 1;
+            return Ok(IrEvent {
+                dt: self.idle_us,
+                edge: true,
+            });
+        }
+        let width = self.ir.fifo_read.read() & 0xffff;
+        let event = pulse_end_event(self.index - 1, width, self.clock_hz);
+        self.index += 1;
+        Ok(event)
+    }
+}
+
+/// Edge at the end of pulse `index` in a frame lasting `width` clock cycles.
+#[inline]
+const fn pulse_end_event(index: usize, width: u32, clock_hz: u32) -> IrEvent {
+    IrEvent {
+        dt: ticks_to_us(width, clock_hz),
+        // Frames start with carrier present, levels alternate afterwards.
+        edge: index % 2 == 1,
+    }
+}
+
+#[inline]
+const fn ticks_to_us(ticks: u32, clock_hz: u32) -> u32 {
+    (ticks as u64 * 1_000_000 / clock_hz as u64) as u32
+}
+
 #[cfg(test)]
 mod tests {
-    use super::RegisterBlock;
+    use super::{
+        pulse_end_event, FifoConfig0, FifoConfig1, IrEvent, ReceiveConfig, ReceiveInterrupt,
+        ReceiveMode, ReceiveThreshold, RegisterBlock,
+    };
     use memoffset::offset_of;
 
     #[test]
@@ -72,4 +425,79 @@ mod tests {
         assert_eq!(offset_of!(RegisterBlock, fifo_config_1), 0x84);
         assert_eq!(offset_of!(RegisterBlock, fifo_read), 0x8c);
     }
+
+    #[test]
+    fn struct_receive_config_functions() {
+        let val = ReceiveConfig(0x0).enable_receive();
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_receive_enabled());
+        assert!(!val.disable_receive().is_receive_enabled());
+        let val = ReceiveConfig(0x0).enable_input_inverse();
+        assert_eq!(val.0, 0x0000_0002);
+        assert!(val.is_input_inverse_enabled());
+        assert!(!val.disable_input_inverse().is_input_inverse_enabled());
+        let val = ReceiveConfig(0x0).set_mode(ReceiveMode::PulseWidth);
+        assert_eq!(val.0, 0x0000_0008);
+        assert_eq!(val.mode(), ReceiveMode::PulseWidth);
+        let val = val.set_mode(ReceiveMode::Rc5);
+        assert_eq!(val.0, 0x0000_0004);
+        assert_eq!(val.mode(), ReceiveMode::Rc5);
+        let val = ReceiveConfig(0x0).enable_deglitch().set_deglitch_count(0xf);
+        assert_eq!(val.0, 0x0000_0f10);
+        assert!(val.is_deglitch_enabled());
+        assert_eq!(val.deglitch_count(), 0xf);
+        assert!(!val.disable_deglitch().is_deglitch_enabled());
+    }
+
+    #[test]
+    fn struct_receive_interrupt_functions() {
+        assert!(ReceiveInterrupt(0x0000_0001).is_end());
+        let val = ReceiveInterrupt(0x0).mask_end();
+        assert_eq!(val.0, 0x0000_0100);
+        assert!(val.is_end_masked());
+        assert!(!val.unmask_end().is_end_masked());
+        assert_eq!(ReceiveInterrupt(0x0).clear_end().0, 0x0001_0000);
+        let val = ReceiveInterrupt(0x0).enable_end();
+        assert_eq!(val.0, 0x0100_0000);
+        assert!(val.is_end_enabled());
+        assert!(!val.disable_end().is_end_enabled());
+    }
+
+    #[test]
+    fn struct_receive_threshold_functions() {
+        let val = ReceiveThreshold(0x0).set_data_threshold(0x1234);
+        assert_eq!(val.0, 0x0000_1234);
+        assert_eq!(val.data_threshold(), 0x1234);
+        let val = val.set_end_threshold(0xabcd);
+        assert_eq!(val.0, 0xabcd_1234);
+        assert_eq!(val.end_threshold(), 0xabcd);
+    }
+
+    #[test]
+    fn struct_fifo_config_functions() {
+        assert_eq!(FifoConfig0(0x0).clear_receive_fifo().0, 0x0000_0004);
+        assert!(FifoConfig0(0x0000_0010).is_receive_fifo_overflow());
+        assert!(FifoConfig0(0x0000_0020).is_receive_fifo_underflow());
+        assert_eq!(FifoConfig1(0xffff_ff40).receive_available(), 0x40);
+    }
+
+    #[test]
+    fn function_pulse_end_event() {
+        // NEC leader: 9 ms carrier, 4.5 ms space on 2 MHz clock.
+        assert_eq!(
+            pulse_end_event(0, 18_000, 2_000_000),
+            IrEvent {
+                dt: 9_000,
+                edge: false
+            }
+        );
+        assert_eq!(
+            pulse_end_event(1, 9_000, 2_000_000),
+            IrEvent {
+                dt: 4_500,
+                edge: true
+            }
+        );
+        assert_eq!(pulse_end_event(2, 1_120, 2_000_000).dt, 560);
+    }
 }
@@ -13,6 +13,7 @@ pub mod clocks;
 pub mod audio;
 pub mod dbi;
 pub mod dma;
+pub mod efuse;
 pub mod emac;
 pub mod glb;
 pub mod gpio;
@@ -20,11 +21,13 @@ pub mod gpip;
 pub mod hbn;
 pub mod i2c;
 pub mod i2s;
+pub mod ipc;
 pub mod ir;
 pub mod lz4d;
 pub mod psram;
 pub mod pwm;
 pub mod sdio;
+pub mod sec;
 pub mod spi;
 pub mod timer;
 pub mod uart;
@@ -1,4 +1,7 @@
 //! Pseudo Static Random Access Memory.
+//!
+//! Controller settings and memory map in this module are those of BL808 and its 64 MiB
+//! UHS PSRAM; other chips have their PSRAM elsewhere and are not supported yet.
 
 use core::ptr;
 
@@ -18,9 +21,42 @@ pub struct RegisterBlock {
     pub phy_config: [RW<u32>; 21],
 }
 
-/// Initializes the PSRAM.
+/// Start address of BL808 PSRAM in system address space.
+pub const PSRAM_START: usize = 0x5000_0000;
+/// Size of BL808 on-chip PSRAM in bytes.
+pub const PSRAM_SIZE: usize = 64 * 1024 * 1024;
+
+/// Memory region of initialized PSRAM.
+///
+/// This structure is only returned by [`init_psram`], thus owning it proves that the
+/// region is ready for use.
+#[derive(Debug, PartialEq, Eq)]
+pub struct PsramRegion {
+    start: usize,
+    size: usize,
+}
+
+impl PsramRegion {
+    /// Start address of this region.
+    #[inline]
+    pub const fn start(&self) -> usize {
+        self.start
+    }
+    /// Size of this region in bytes.
+    #[inline]
+    pub const fn size(&self) -> usize {
+        self.size
+    }
+    /// End address of this region, exclusive.
+    #[inline]
+    pub const fn end(&self) -> usize {
+        self.start + self.size
+    }
+}
+
+/// Initializes the PSRAM, returning its memory region.
 #[inline]
-pub fn init_psram(psram: &RegisterBlock, glb: &glb::v2::RegisterBlock) {
+pub fn init_psram(psram: &RegisterBlock, glb: &glb::v2::RegisterBlock) -> PsramRegion {
     unsafe {
         glb.ldo12uhs_config
             .modify(|w| w.power_up().set_output_voltage(6));
@@ -53,6 +89,10 @@ pub fn init_psram(psram: &RegisterBlock, glb: &glb::v2::RegisterBlock) {
 
         ptr::write_volatile(0x200007E8 as *mut u32, 0x32000); // TODO: fix magic and hardcode
     }
+    PsramRegion {
+        start: PSRAM_START,
+        size: PSRAM_SIZE,
+    }
 }
 
 #[cfg(test)]
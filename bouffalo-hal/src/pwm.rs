@@ -7,6 +7,7 @@ use crate::glb::{
 use crate::gpio::{self, Alternate};
 use core::marker::PhantomData;
 use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicBool, Ordering};
 use embedded_time::rate::Hertz;
 use volatile_register::{RO, RW, WO};
 
@@ -662,6 +663,9 @@ impl<PWM: Deref<Target = RegisterBlock>, S0: Signal0, S1: Signal1> Pwm<PWM, (S0,
             glb.clock_config_1.modify(|config| config.enable_pwm());
         }
         drop((signal_0, signal_1));
+        for center_aligned in &CENTER_ALIGNED {
+            center_aligned.store(false, Ordering::Relaxed);
+        }
         Pwm {
             group0: Channels {
                 channel0: Channel { pwm: unsafe { core::ptr::read(&pwm as *const _) }, _signals: PhantomData },
@@ -707,11 +711,7 @@ impl<PWM: Deref<Target = RegisterBlock>, S, const I: usize> Channels<PWM, S, I>
     /// Clock settings would affect all the channels in the PWM group.
     #[inline]
     pub fn set_clock(&mut self, frequency: Hertz, source: ClockSource, clocks: &Clocks) {
-        let source_freq = match source {
-            ClockSource::Xclk => clocks.xclk(),
-            ClockSource::Bclk => todo!(),
-            ClockSource::F32kClk => todo!(),
-        };
+        let source_freq = source_frequency(source, clocks);
         let clock_divisor = source_freq.0 / frequency.0;
         if !(1..=65535).contains(&clock_divisor) {
             panic!("impossible frequency");
@@ -724,13 +724,106 @@ impl<PWM: Deref<Target = RegisterBlock>, S, const I: usize> Channels<PWM, S, I>
         };
     }
     /// Configure maximum duty cycle for this PWM group.
+    ///
+    /// With [`Alignment::Center`] the counter period is twice `duty`, so `duty` must not
+    /// exceed 32767.
     #[inline]
     pub fn set_max_duty_cycle(&mut self, duty: u16) {
+        let Some(period) = counter_period(duty, self.alignment()) else {
+            panic!("impossible maximum duty cycle");
+        };
         unsafe {
             self.pwm.group[I]
                 .period_config
-                .modify(|val| val.set_period(duty))
+                .modify(|val| val.set_period(period))
+        }
+    }
+    /// Change pulse alignment of all the channels in current PWM group.
+    ///
+    /// The hardware counter only counts up, so up-down counting is emulated for
+    /// [`Alignment::Center`]: counter period is doubled, and each pulse is placed
+    /// symmetrically around the middle of period. Maximum duty cycle is kept, thus output
+    /// frequency is halved, and duty cycle of each channel is converted to keep its ratio.
+    ///
+    /// Like [`set_frequency`](Self::set_frequency), new settings are written right after
+    /// the next period end event on a running group. Returns [`FrequencyError::FrequencyTooLow`]
+    /// if doubled period would not fit in 16 bits.
+    #[inline]
+    pub fn set_alignment(&mut self, alignment: Alignment) -> Result<(), FrequencyError> {
+        let old_alignment = self.alignment();
+        if alignment == old_alignment {
+            return Ok(());
+        }
+        let group = &self.pwm.group[I];
+        let old_period = group.period_config.read().period();
+        let max_duty = period_max_duty(old_period, old_alignment);
+        let Some(period) = counter_period(max_duty, alignment) else {
+            return Err(FrequencyError::FrequencyTooLow);
+        };
+        let mut thresholds = [Threshold(0); 4];
+        for (j, threshold) in thresholds.iter_mut().enumerate() {
+            let duty = threshold_duty(group.threshold[j].read(), old_alignment);
+            *threshold = aligned_threshold(duty, max_duty, alignment);
+        }
+        wait_period_end(group);
+        unsafe {
+            group.period_config.modify(|val| val.set_period(period));
+            for (j, threshold) in thresholds.into_iter().enumerate() {
+                group.threshold[j].write(threshold);
+            }
+        }
+        CENTER_ALIGNED[I].store(alignment == Alignment::Center, Ordering::Relaxed);
+        Ok(())
+    }
+    /// Get pulse alignment of current PWM group.
+    #[inline]
+    pub fn alignment(&self) -> Alignment {
+        group_alignment(I)
+    }
+    /// Change output frequency of current PWM group while it is running.
+    ///
+    /// Clock divisor and period are recomputed from current clock source, and duty cycle
+    /// of each channel is scaled to keep its ratio. New settings are written right after
+    /// the next period end event, so the output would not glitch; on a stopped group they
+    /// are applied immediately.
+    ///
+    /// With source clock `f_src`, achievable frequency ranges from `f_src / (65535 * 65535)`
+    /// to `f_src / 2`. The smallest divisor is always chosen to keep period as long as
+    /// possible; frequency resolution is then about `1 / period` of target frequency,
+    /// e.g. with 40 MHz crystal, a 2 kHz output has period 20000 and 0.005% resolution.
+    #[inline]
+    pub fn set_frequency(
+        &mut self,
+        frequency: Hertz,
+        clocks: &Clocks,
+    ) -> Result<(), FrequencyError> {
+        let group = &self.pwm.group[I];
+        let source_freq = source_frequency(group.group_config.read().clock_source(), clocks);
+        let (divisor, period) = divisor_and_period(source_freq.0, frequency.0)?;
+        // Center aligned pulses need an even period to stay symmetric.
+        let period = match self.alignment() {
+            Alignment::EdgeLeft => period,
+            Alignment::Center => period & !1,
+        };
+        let old_period = group.period_config.read().period() as u32;
+        let mut thresholds = [Threshold(0); 4];
+        for (j, threshold) in thresholds.iter_mut().enumerate() {
+            let old = group.threshold[j].read();
+            *threshold = old
+                .set_low(scale_duty(old.low(), old_period, period))
+                .set_high(scale_duty(old.high(), old_period, period));
+        }
+        wait_period_end(group);
+        unsafe {
+            group
+                .group_config
+                .modify(|val| val.set_clock_divide(divisor));
+            group.period_config.modify(|val| val.set_period(period));
+            for (j, threshold) in thresholds.into_iter().enumerate() {
+                group.threshold[j].write(threshold);
+            }
         }
+        Ok(())
     }
     /// Start current PWM group.
     #[inline]
@@ -758,6 +851,147 @@ impl<PWM: Deref<Target = RegisterBlock>, S, const I: usize> Channels<PWM, S, I>
     }
 }
 
+/// Pulse alignment within PWM period.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Alignment {
+    /// Pulse starts at beginning of each period.
+    #[default]
+    EdgeLeft,
+    /// Pulse is centered in each period, as with an up-down counter.
+    Center,
+}
+
+/// Center alignment flags of both PWM groups, shared by a group and its channels.
+static CENTER_ALIGNED: [AtomicBool; 2] = [AtomicBool::new(false), AtomicBool::new(false)];
+
+#[inline]
+fn group_alignment(group: usize) -> Alignment {
+    if CENTER_ALIGNED[group].load(Ordering::Relaxed) {
+        Alignment::Center
+    } else {
+        Alignment::EdgeLeft
+    }
+}
+
+/// Wait for the end of current period if the group is running.
+#[inline]
+fn wait_period_end(group: &Group) {
+    if group.group_config.read().is_stopped() {
+        return;
+    }
+    unsafe {
+        group
+            .interrupt_clear
+            .write(InterruptClear(0).clear_interrupt(Interrupt::PeriodEnd))
+    };
+    while !group
+        .interrupt_state
+        .read()
+        .has_interrupt(Interrupt::PeriodEnd)
+    {
+        core::hint::spin_loop();
+    }
+}
+
+/// Counter period for `max_duty` under `alignment`, or `None` if it doesn't fit in 16 bits.
+#[inline]
+const fn counter_period(max_duty: u16, alignment: Alignment) -> Option<u16> {
+    match alignment {
+        Alignment::EdgeLeft => Some(max_duty),
+        Alignment::Center => max_duty.checked_mul(2),
+    }
+}
+
+/// Maximum duty cycle for counter `period` under `alignment`.
+#[inline]
+const fn period_max_duty(period: u16, alignment: Alignment) -> u16 {
+    match alignment {
+        Alignment::EdgeLeft => period,
+        Alignment::Center => period / 2,
+    }
+}
+
+/// Compute threshold for `duty` out of `max_duty` under `alignment`.
+///
+/// Center aligned pulses span from `max_duty - duty` to `max_duty + duty` in a period
+/// of `2 * max_duty`.
+#[inline]
+const fn aligned_threshold(duty: u16, max_duty: u16, alignment: Alignment) -> Threshold {
+    let duty = if duty > max_duty { max_duty } else { duty };
+    match alignment {
+        Alignment::EdgeLeft => Threshold(0).set_low(0).set_high(duty),
+        Alignment::Center => Threshold(0)
+            .set_low(max_duty - duty)
+            .set_high(max_duty + duty),
+    }
+}
+
+/// Get duty cycle from `threshold` under `alignment`.
+#[inline]
+const fn threshold_duty(threshold: Threshold, alignment: Alignment) -> u16 {
+    let width = threshold.high().saturating_sub(threshold.low());
+    match alignment {
+        Alignment::EdgeLeft => width,
+        Alignment::Center => width / 2,
+    }
+}
+
+/// Errors on changing PWM frequency.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrequencyError {
+    /// Impossibly high frequency for current clock source.
+    FrequencyTooHigh,
+    /// Impossibly low frequency for current clock source.
+    FrequencyTooLow,
+}
+
+/// Nominal frequency of low frequency clock source.
+const F32K_HZ: u32 = 32_768;
+
+#[inline]
+fn source_frequency(source: ClockSource, clocks: &Clocks) -> Hertz {
+    match source {
+        ClockSource::Xclk => clocks.xclk(),
+        ClockSource::Bclk => clocks.bus_clk(),
+        ClockSource::F32kClk => Hertz(F32K_HZ),
+    }
+}
+
+/// Compute clock divisor and period for output `frequency` from `source` clock.
+///
+/// The smallest divisor keeping period within 16 bits is chosen for best resolution.
+#[inline]
+const fn divisor_and_period(source: u32, frequency: u32) -> Result<(u16, u16), FrequencyError> {
+    if frequency == 0 {
+        return Err(FrequencyError::FrequencyTooLow);
+    }
+    let cycles = source as u64 / frequency as u64;
+    if cycles < 2 {
+        return Err(FrequencyError::FrequencyTooHigh);
+    }
+    let divisor = cycles.div_ceil(65535);
+    if divisor > 65535 {
+        return Err(FrequencyError::FrequencyTooLow);
+    }
+    let period = (source as u64 + divisor * frequency as u64 / 2) / (divisor * frequency as u64);
+    let period = if period > 65535 { 65535 } else { period };
+    Ok((divisor as u16, period as u16))
+}
+
+/// Scale `duty` from `old_period` to `new_period`, keeping its ratio.
+#[inline]
+const fn scale_duty(duty: u16, old_period: u32, new_period: u16) -> u16 {
+    if old_period == 0 {
+        return 0;
+    }
+    let ans = (duty as u32 * new_period as u32 + old_period / 2) / old_period;
+    if ans > new_period as u32 {
+        new_period
+    } else {
+        ans as u16
+    }
+}
+
 /// Pulse Width Modulation channel.
 pub struct Channel<PWM, S, const I: usize, const J: usize> {
     pwm: PWM,
@@ -862,11 +1096,14 @@ impl<PWM: Deref<Target = RegisterBlock>, S, const I: usize, const J: usize>
 {
     #[inline]
     fn max_duty_cycle(&self) -> u16 {
-        self.pwm.group[I].period_config.read().period()
+        let period = self.pwm.group[I].period_config.read().period();
+        period_max_duty(period, group_alignment(I))
     }
     #[inline]
     fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
-        unsafe { self.pwm.group[I].threshold[J].modify(|val| val.set_low(0).set_high(duty)) };
+        // Both thresholds are written at once, so the output never sees a partial update.
+        let threshold = aligned_threshold(duty, self.max_duty_cycle(), group_alignment(I));
+        unsafe { self.pwm.group[I].threshold[J].write(threshold) };
         Ok(())
     }
 }
@@ -1186,9 +1423,11 @@ mod gpio_impls {
 #[cfg(test)]
 mod tests {
     use super::{
-        AdcTriggerSource, ChannelConfig, ClockSource, DeadTime, ElectricLevel, Group, GroupConfig,
-        Interrupt, InterruptClear, InterruptConfig, InterruptEnable, InterruptMask, InterruptState,
-        PeriodConfig, Polarity, RegisterBlock, StopMode, Threshold,
+        aligned_threshold, counter_period, divisor_and_period, period_max_duty, scale_duty,
+        threshold_duty, AdcTriggerSource, Alignment, ChannelConfig, ClockSource, DeadTime,
+        ElectricLevel, FrequencyError, Group, GroupConfig, Interrupt, InterruptClear,
+        InterruptConfig, InterruptEnable, InterruptMask, InterruptState, PeriodConfig, Polarity,
+        RegisterBlock, StopMode, Threshold,
     };
     use memoffset::offset_of;
 
@@ -1525,4 +1764,66 @@ mod tests {
             assert_eq!(val.0, 0x00000000 << idx);
         }
     }
+
+    #[test]
+    fn function_divisor_and_period() {
+        assert_eq!(divisor_and_period(40_000_000, 2_000), Ok((1, 20000)));
+        assert_eq!(divisor_and_period(40_000_000, 400), Ok((2, 50000)));
+        assert_eq!(divisor_and_period(40_000_000, 20_000_000), Ok((1, 2)));
+        assert_eq!(
+            divisor_and_period(40_000_000, 30_000_000),
+            Err(FrequencyError::FrequencyTooHigh)
+        );
+        assert_eq!(
+            divisor_and_period(40_000_000, 0),
+            Err(FrequencyError::FrequencyTooLow)
+        );
+        assert_eq!(divisor_and_period(40_000_000, 1), Ok((611, 65466)));
+        assert_eq!(
+            divisor_and_period(u32::MAX, 1),
+            Err(FrequencyError::FrequencyTooLow)
+        );
+    }
+
+    #[test]
+    fn function_scale_duty() {
+        assert_eq!(scale_duty(50, 100, 20000), 10000);
+        assert_eq!(scale_duty(10000, 20000, 100), 50);
+        assert_eq!(scale_duty(100, 100, 300), 300);
+        assert_eq!(scale_duty(200, 100, 300), 300);
+        assert_eq!(scale_duty(7, 0, 300), 0);
+    }
+
+    #[test]
+    fn function_aligned_threshold() {
+        let val = aligned_threshold(30, 100, Alignment::EdgeLeft);
+        assert_eq!((val.low(), val.high()), (0, 30));
+        assert_eq!(threshold_duty(val, Alignment::EdgeLeft), 30);
+
+        let val = aligned_threshold(30, 100, Alignment::Center);
+        assert_eq!((val.low(), val.high()), (70, 130));
+        assert_eq!(threshold_duty(val, Alignment::Center), 30);
+
+        let val = aligned_threshold(150, 100, Alignment::Center);
+        assert_eq!((val.low(), val.high()), (0, 200));
+        let val = aligned_threshold(0, 100, Alignment::Center);
+        assert_eq!(threshold_duty(val, Alignment::Center), 0);
+    }
+
+    #[test]
+    fn function_counter_period() {
+        // Same maximum duty cycle takes twice the period, thus half the frequency, centered.
+        assert_eq!(counter_period(1000, Alignment::EdgeLeft), Some(1000));
+        assert_eq!(counter_period(1000, Alignment::Center), Some(2000));
+        assert_eq!(counter_period(32767, Alignment::Center), Some(65534));
+        assert_eq!(counter_period(32768, Alignment::Center), None);
+        assert_eq!(counter_period(65535, Alignment::EdgeLeft), Some(65535));
+        for alignment in [Alignment::EdgeLeft, Alignment::Center] {
+            let period = counter_period(1000, alignment).unwrap();
+            assert_eq!(period_max_duty(period, alignment), 1000);
+            let val = aligned_threshold(250, period_max_duty(period, alignment), alignment);
+            assert_eq!(threshold_duty(val, alignment), 250);
+            assert!(val.high() <= period);
+        }
+    }
 }
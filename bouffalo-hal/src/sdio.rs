@@ -3581,12 +3581,25 @@ fn sleep_ms(n: u32) {
     }
 }
 
+/// Type of device attached to host controller.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CardType {
+    /// No device initialized yet.
+    Unknown,
+    /// Secure Digital High Capacity card.
+    SdhcCard,
+    /// Embedded MultiMediaCard.
+    Emmc,
+}
+
 /// Managed Secure Digital Host Controller peripheral.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Sdh<SDH, PADS, const I: usize> {
     sdh: SDH,
     pads: PADS,
     block_count: u32,
+    card_type: CardType,
+    block_addressing: bool,
 }
 
 impl<SDH: Deref<Target = RegisterBlock>, PADS, const I: usize> Sdh<SDH, PADS, I> {
@@ -3638,6 +3651,9 @@ impl<SDH: Deref<Target = RegisterBlock>, PADS, const I: usize> Sdh<SDH, PADS, I>
             // SDH enable interrupt.
             sdh.normal_interrupt_status_enable
                 .modify(|val| val.enable_buffer_read_ready());
+            // Command timeout tells SD cards from eMMC devices on CMD8.
+            sdh.error_interrupt_status_enable
+                .modify(|val| val.enable_cmd_timeout_err());
             // SDH_Set_Timeout.
             sdh.timeout_control.modify(|val| val.set_timeout_val(0x0e));
             // SDH_Powon.
@@ -3647,13 +3663,21 @@ impl<SDH: Deref<Target = RegisterBlock>, PADS, const I: usize> Sdh<SDH, PADS, I>
             sdh,
             pads,
             block_count: 0,
+            card_type: CardType::Unknown,
+            block_addressing: true,
         }
     }
 
     /// Initialize the SDH peripheral (enable debug to print card info).
+    ///
+    /// SD cards and eMMC devices are told apart by response to CMD8; eMMC devices
+    /// don't respond to it and are initialized with CMD1 instead.
     // TODO a more proper abstraction
     #[inline]
-    pub fn init<W: Write>(&mut self, w: &mut W, debug: bool) {
+    pub fn init<W: Write>(&mut self, w: &mut W, debug: bool)
+    where
+        PADS: Pads<I>,
+    {
         // Sdcard idle.
         loop {
             self.send_command(SDHResp::None, CmdType::Normal, 0, 0, false);
@@ -3662,6 +3686,13 @@ impl<SDH: Deref<Target = RegisterBlock>, PADS, const I: usize> Sdh<SDH, PADS, I>
             // Send CMD8.
             self.send_command(SDHResp::R7, CmdType::Normal, 8, 0x1AA, false);
             sleep_ms(100);
+            if self.take_cmd_timeout() {
+                if debug {
+                    writeln!(*w, "no response to CMD8, trying eMMC").ok();
+                }
+                self.init_emmc(w, debug);
+                return;
+            }
             let data = self.get_resp();
             if data != 0x1AA {
                 writeln!(
@@ -3726,8 +3757,9 @@ impl<SDH: Deref<Target = RegisterBlock>, PADS, const I: usize> Sdh<SDH, PADS, I>
             writeln!(*w, "csd: {:#034X}, c_size: {}", csd_raw, c_size).ok();
         }
 
-        let block_size = 512;
         self.block_count = (c_size + 1) * 1024;
+        self.card_type = CardType::SdhcCard;
+        self.block_addressing = true;
 
         // Send CMD7 to select card.
         self.send_command(SDHResp::R1B, CmdType::Normal, 7, rca << 16, false);
@@ -3739,19 +3771,154 @@ impl<SDH: Deref<Target = RegisterBlock>, PADS, const I: usize> Sdh<SDH, PADS, I>
         self.send_command(SDHResp::R1, CmdType::Normal, 6, 0x0, false);
         sleep_ms(100);
 
-        let kb_size = (self.block_count as f64) * (block_size as f64) / 1024.0;
-        let mb_size = kb_size / 1024.0;
-        let gb_size = mb_size / 1024.0;
-
         if debug {
-            if kb_size < 1024.0 {
-                writeln!(*w, "sdcard init done, size: {:.2} KB", kb_size).ok();
-            } else if mb_size < 1024.0 {
-                writeln!(*w, "sdcard init done, size: {:.2} MB", mb_size).ok();
-            } else {
-                writeln!(*w, "sdcard init done, size: {:.2} GB", gb_size).ok();
+            write_capacity(w, "sdcard", self.block_count);
+        }
+    }
+
+    /// Initialize an eMMC device after CMD0, negotiating operating conditions with CMD1.
+    ///
+    /// Capacity is read from EXT_CSD, and the widest bus supported by `PADS` is selected.
+    fn init_emmc<W: Write>(&mut self, w: &mut W, debug: bool)
+    where
+        PADS: Pads<I>,
+    {
+        const OCR_NBUSY: u32 = 0x80000000;
+        const OCR_SECTOR_MODE: u32 = 0x40000000;
+        const OCR_VOLTAGE_3V3: u32 = 0x00FF8000;
+        // Relative address is assigned by host for eMMC.
+        const RCA: u32 = 1;
+
+        self.send_command(SDHResp::None, CmdType::Normal, 0, 0, false);
+        sleep_ms(100);
+        let ocr = loop {
+            // Send CMD1.
+            self.send_command(
+                SDHResp::R3,
+                CmdType::Normal,
+                1,
+                OCR_SECTOR_MODE | OCR_VOLTAGE_3V3,
+                false,
+            );
+            sleep_ms(100);
+            let ocr = self.get_resp() as u32;
+            if self.take_cmd_timeout() {
+                writeln!(*w, "no response to CMD1, is a card inserted?").ok();
+            } else if ocr & OCR_NBUSY == OCR_NBUSY {
+                break ocr;
             }
+            sleep_ms(100);
+        };
+        self.block_addressing = ocr & OCR_SECTOR_MODE != 0;
+        if debug {
+            writeln!(*w, "ocr: {:#010X}", ocr).ok();
+        }
+
+        // Send CMD2 to get CID.
+        self.send_command(SDHResp::R2, CmdType::Normal, 2, 0, false);
+        sleep_ms(100);
+        let cid = self.get_resp();
+        if debug {
+            writeln!(*w, "cid: {:#034X}", cid).ok();
+        }
+
+        // Send CMD3 to assign RCA.
+        self.send_command(SDHResp::R1, CmdType::Normal, 3, RCA << 16, false);
+        sleep_ms(100);
+
+        // Send CMD7 to select device.
+        self.send_command(SDHResp::R1B, CmdType::Normal, 7, RCA << 16, false);
+        sleep_ms(100);
+
+        // Send CMD8 to read EXT_CSD.
+        let mut ext_csd = Block::new();
+        self.read_data(&mut ext_csd, 8, 0);
+        let (sec_count, revision) = parse_ext_csd(&ext_csd.contents);
+        self.block_count = if self.block_addressing {
+            sec_count
+        } else {
+            // Byte addressed devices up to 2 GiB report capacity in CSD only.
+            self.read_csd_capacity(RCA)
+        };
+        self.card_type = CardType::Emmc;
+        if debug {
+            writeln!(*w, "ext_csd revision: {}", revision).ok();
+        }
+
+        // CMD6 SWITCH to write BUS_WIDTH byte of EXT_CSD.
+        let (bus_width, transfer_width, extended) = match PADS::DATA_LINES {
+            8 => (2, TransferWidth::OneBitMode, BusWidthMode::EightBitWidth),
+            4 => (
+                1,
+                TransferWidth::FourBitMode,
+                BusWidthMode::SelectByDataTransferWidth,
+            ),
+            _ => (
+                0,
+                TransferWidth::OneBitMode,
+                BusWidthMode::SelectByDataTransferWidth,
+            ),
+        };
+        self.send_command(
+            SDHResp::R1B,
+            CmdType::Normal,
+            6,
+            switch_argument(EXT_CSD_BUS_WIDTH, bus_width),
+            false,
+        );
+        sleep_ms(100);
+        unsafe {
+            self.sdh.host_control_1.modify(|val| {
+                val.set_bus_width(extended)
+                    .set_transfer_width(transfer_width)
+            })
+        };
+        if debug {
+            writeln!(*w, "emmc bus width: {} bit", PADS::DATA_LINES).ok();
+            write_capacity(w, "emmc", self.block_count);
+        }
+    }
+
+    /// Get type of initialized device.
+    #[inline]
+    pub fn card_type(&self) -> CardType {
+        self.card_type
+    }
+
+    /// Read capacity of a byte addressed device from CSD version 1.0 fields, in blocks.
+    fn read_csd_capacity(&self, rca: u32) -> u32 {
+        // CMD9 is only accepted in stand-by state, deselect device around it.
+        self.send_command(SDHResp::R1B, CmdType::Normal, 7, 0, false);
+        sleep_ms(100);
+        self.send_command(SDHResp::R2, CmdType::Normal, 9, rca << 16, false);
+        sleep_ms(100);
+        let csd = self.get_resp();
+        self.send_command(SDHResp::R1B, CmdType::Normal, 7, rca << 16, false);
+        sleep_ms(100);
+        parse_csd_v1_blocks(csd)
+    }
+
+    /// Check and clear command timeout error of last command.
+    #[inline]
+    fn take_cmd_timeout(&self) -> bool {
+        if !self
+            .sdh
+            .error_interrupt_status
+            .read()
+            .if_cmd_timeout_err_occurs()
+        {
+            return false;
+        }
+        unsafe {
+            self.sdh
+                .error_interrupt_status
+                .write(ErrorInterruptStatus(0).clear_cmd_timeout_err());
+            self.sdh.software_reset.modify(|val| val.reset_cmd());
+        }
+        while !self.sdh.software_reset.read().is_reset_cmd_finished() {
+            core::hint::spin_loop()
         }
+        true
     }
 
     /// Send command to sdcard.
@@ -3807,6 +3974,17 @@ impl<SDH: Deref<Target = RegisterBlock>, PADS, const I: usize> Sdh<SDH, PADS, I>
     /// Read block from sdcard.
     #[inline]
     fn read_block(&self, block: &mut Block, block_idx: u32) {
+        let address = if self.block_addressing {
+            block_idx
+        } else {
+            block_idx * Block::LEN_U32
+        };
+        self.read_data(block, 17, address);
+    }
+
+    /// Read one 512-byte data block returned by command `cmd_idx`.
+    #[inline]
+    fn read_data(&self, block: &mut Block, cmd_idx: u32, argument: u32) {
         unsafe {
             // SDH_SD_TRANSFER_MODE.
             self.sdh.transfer_mode.modify(|val| {
@@ -3827,7 +4005,7 @@ impl<SDH: Deref<Target = RegisterBlock>, PADS, const I: usize> Sdh<SDH, PADS, I>
                 .normal_interrupt_status
                 .write(NormalInterruptStatus(0x00000020));
         }
-        self.send_command(SDHResp::R1, CmdType::Normal, 17, block_idx, true);
+        self.send_command(SDHResp::R1, CmdType::Normal, cmd_idx, argument, true);
         while !self
             .sdh
             .normal_interrupt_status
@@ -3889,8 +4067,53 @@ fn parse_csd_v2(csd: u128) -> (u32, u32) {
     (csd_structure, c_size)
 }
 
+/// Byte index of BUS_WIDTH field in EXT_CSD.
+const EXT_CSD_BUS_WIDTH: u8 = 183;
+
+/// Argument of eMMC CMD6 SWITCH writing `value` into EXT_CSD byte `index`.
+#[inline]
+const fn switch_argument(index: u8, value: u8) -> u32 {
+    // Access mode 0b11: write byte.
+    (0b11 << 24) | ((index as u32) << 16) | ((value as u32) << 8)
+}
+
+/// Parse sector count and revision from eMMC EXT_CSD.
+#[inline]
+fn parse_ext_csd(ext_csd: &[u8; 512]) -> (u32, u8) {
+    let sec_count = u32::from_le_bytes([ext_csd[212], ext_csd[213], ext_csd[214], ext_csd[215]]);
+    (sec_count, ext_csd[192])
+}
+
+/// Parse capacity in 512-byte blocks from CSD version 1.0.
+///
+/// Response register holds CSD without its CRC byte, i.e. CSD bit `n` is at bit `n - 8`.
+#[inline]
+fn parse_csd_v1_blocks(csd: u128) -> u32 {
+    let read_bl_len = ((csd >> (80 - 8)) & 0xF) as u32;
+    let c_size = ((csd >> (62 - 8)) & 0xFFF) as u32;
+    let c_size_mult = ((csd >> (47 - 8)) & 0x7) as u32;
+    ((c_size + 1) << (c_size_mult + 2 + read_bl_len)) / 512
+}
+
+/// Write device capacity in human readable units.
+fn write_capacity<W: Write>(w: &mut W, name: &str, block_count: u32) {
+    let kb_size = (block_count as f64) * 512.0 / 1024.0;
+    let mb_size = kb_size / 1024.0;
+    let gb_size = mb_size / 1024.0;
+    if kb_size < 1024.0 {
+        writeln!(*w, "{} init done, size: {:.2} KB", name, kb_size).ok();
+    } else if mb_size < 1024.0 {
+        writeln!(*w, "{} init done, size: {:.2} MB", name, mb_size).ok();
+    } else {
+        writeln!(*w, "{} init done, size: {:.2} GB", name, gb_size).ok();
+    }
+}
+
 /// Valid SDH pads.
-pub trait Pads<const I: usize> {}
+pub trait Pads<const I: usize> {
+    /// Number of connected data lines.
+    const DATA_LINES: u8;
+}
 
 impl<
         'a,
@@ -3922,6 +4145,7 @@ where
     Alternate<'e, N5, gpio::Sdh>: HasDat2Signal,
     Alternate<'f, N6, gpio::Sdh>: HasDat3Signal,
 {
+    const DATA_LINES: u8 = 4;
 }
 
 /// Check if target gpio `Pin` is internally connected to SDH clock signal.
@@ -3957,6 +4181,7 @@ impl<'a> HasDat3Signal for Alternate<'a, 5, gpio::Sdh> {}
 #[cfg(test)]
 mod tests {
     use super::RegisterBlock;
+    use super::{parse_csd_v1_blocks, parse_ext_csd, switch_argument, EXT_CSD_BUS_WIDTH};
     use super::{
         ADMAErrorStatus, ADMASystemAddress, Argument, AutoCMDErrorStatus, AutoCMDMode, BlockCount,
         BlockGap, BlockMode, BlockSize, BufferDataPort, BusVoltage, BusWidthMode, Capabilities,
@@ -5516,4 +5741,26 @@ mod tests {
     fn struct_tuning_configuration_functions() {
         // TODO
     }
+
+    #[test]
+    fn function_parse_ext_csd() {
+        let mut ext_csd = [0u8; 512];
+        ext_csd[192] = 8;
+        // 16 GiB device: 0x0200_0000 sectors.
+        ext_csd[212..216].copy_from_slice(&[0x00, 0x00, 0x00, 0x02]);
+        assert_eq!(parse_ext_csd(&ext_csd), (0x0200_0000, 8));
+    }
+
+    #[test]
+    fn function_switch_argument() {
+        assert_eq!(switch_argument(EXT_CSD_BUS_WIDTH, 2), 0x03B7_0200);
+        assert_eq!(switch_argument(EXT_CSD_BUS_WIDTH, 1), 0x03B7_0100);
+    }
+
+    #[test]
+    fn function_parse_csd_v1_blocks() {
+        // READ_BL_LEN = 10, C_SIZE = 0xFFF, C_SIZE_MULT = 7: 2 GiB.
+        let csd: u128 = (10 << (80 - 8)) | (0xFFF << (62 - 8)) | (7 << (47 - 8));
+        assert_eq!(parse_csd_v1_blocks(csd), 0x40_0000);
+    }
 }
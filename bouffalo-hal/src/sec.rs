@@ -0,0 +1,970 @@
+//! Security engine and cryptographic helpers.
+//!
+//! Helpers in this module handle secret data: comparisons don't leak where two buffers
+//! differ through timing, and buffers holding key material can be cleared without the
+//! compiler removing the writes.
+//!
+//! The AES engine can take its key from an efuse key slot over an internal key bus, so
+//! decryption keys never pass through the CPU or RAM; see [`Aes::with_efuse_key`].
+//! Software keys are written to engine key registers for ECB, CBC and CTR modes through
+//! [`Aes::new`], and authenticated encryption in GCM mode is provided by [`AesGcm`].
+//! Both clear key registers when dropped.
+//!
+//! Legacy hashes [`Sha1`] and `Md5` are provided to interoperate with existing
+//! protocols and formats only; neither is collision resistant. Hashers implement traits
+//! of the `digest` crate with feature `digest`, which software `Md5` also needs.
+//!
+//! Secure boot images signed with ECDSA P-256 are checked by `verify_signature` over
+//! the image hash, e.g. computed by [`Sha256`] or [`sha256_oneshot`]; it needs feature
+//! `ecdsa`. Long messages in memory can be hashed by [`Sha256Dma`] without the CPU
+//! waiting on the engine.
+//!
+//! Random numbers for keys and nonces come from [`Trng`], a true random number generator
+//! with health tests, which also implements `RngCore` and `CryptoRng` of `rand_core`.
+
+#[cfg(feature = "ecdsa")]
+mod ecdsa;
+#[cfg(feature = "digest")]
+mod md5;
+mod sha;
+mod trng;
+#[cfg(feature = "ecdsa")]
+pub use ecdsa::*;
+#[cfg(feature = "digest")]
+pub use md5::*;
+pub use sha::*;
+pub use trng::*;
+
+use core::{
+    mem::ManuallyDrop,
+    ops::Deref,
+    sync::atomic::{compiler_fence, Ordering},
+};
+use volatile_register::{RO, RW};
+
+/// Security engine registers.
+#[repr(C)]
+pub struct RegisterBlock {
+    /// Secure Hash Algorithm engine.
+    pub sha: ShaRegisters,
+    /// Advanced Encryption Standard engine.
+    pub aes: AesRegisters,
+    /// True random number generator.
+    pub trng: TrngRegisters,
+}
+
+/// Advanced Encryption Standard engine registers.
+#[repr(C)]
+pub struct AesRegisters {
+    /// Engine control and message length.
+    pub control: RW<AesControl>,
+    /// Message source address.
+    pub source_address: RW<u32>,
+    /// Message destination address.
+    pub destination_address: RW<u32>,
+    /// Engine status.
+    pub status: RO<u32>,
+    /// Initial vector or initial counter.
+    pub iv: [RW<u32>; 4],
+    /// Software key words.
+    pub key: [RW<u32>; 8],
+    /// Efuse key slot of lower and upper key half, used when hardware key is enabled.
+    pub key_select: [RW<u32>; 2],
+    /// Byte order of data, key and initial vector.
+    pub endian: RW<u32>,
+    _reserved0: [u8; 0xb4],
+}
+
+/// AES engine control register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct AesControl(u32);
+
+impl AesControl {
+    const BUSY: u32 = 1 << 0;
+    const TRIGGER: u32 = 1 << 1;
+    const ENABLE: u32 = 1 << 2;
+    const KEY_SIZE: u32 = 0x3 << 3;
+    const DECRYPT: u32 = 1 << 5;
+    const HARDWARE_KEY: u32 = 1 << 7;
+    const INTERRUPT_CLEAR: u32 = 1 << 9;
+    const INTERRUPT_MASK: u32 = 1 << 11;
+    const BLOCK_MODE: u32 = 0x3 << 12;
+    const CONTINUE_IV: u32 = 1 << 14;
+    const MESSAGE_LENGTH: u32 = 0xffff << 16;
+
+    /// Check if engine is processing a message.
+    #[inline]
+    pub const fn is_busy(self) -> bool {
+        self.0 & Self::BUSY != 0
+    }
+    /// Start processing message.
+    #[inline]
+    pub const fn trigger(self) -> Self {
+        Self(self.0 | Self::TRIGGER)
+    }
+    /// Enable AES engine.
+    #[inline]
+    pub const fn enable(self) -> Self {
+        Self(self.0 | Self::ENABLE)
+    }
+    /// Disable AES engine.
+    #[inline]
+    pub const fn disable(self) -> Self {
+        Self(self.0 & !Self::ENABLE)
+    }
+    /// Check if AES engine is enabled.
+    #[inline]
+    pub const fn is_enabled(self) -> bool {
+        self.0 & Self::ENABLE != 0
+    }
+    /// Set key size.
+    #[inline]
+    pub const fn set_key_size(self, val: KeySize) -> Self {
+        Self((self.0 & !Self::KEY_SIZE) | ((val as u32) << 3))
+    }
+    /// Get key size.
+    #[inline]
+    pub const fn key_size(self) -> KeySize {
+        match (self.0 & Self::KEY_SIZE) >> 3 {
+            0 => KeySize::Aes128,
+            1 => KeySize::Aes256,
+            _ => KeySize::Aes192,
+        }
+    }
+    /// Enable decryption, or encryption if disabled.
+    #[inline]
+    pub const fn enable_decrypt(self) -> Self {
+        Self(self.0 | Self::DECRYPT)
+    }
+    /// Disable decryption, i.e. encrypt messages.
+    #[inline]
+    pub const fn disable_decrypt(self) -> Self {
+        Self(self.0 & !Self::DECRYPT)
+    }
+    /// Check if decryption is enabled.
+    #[inline]
+    pub const fn is_decrypt_enabled(self) -> bool {
+        self.0 & Self::DECRYPT != 0
+    }
+    /// Enable key from efuse key slot instead of software key registers.
+    #[inline]
+    pub const fn enable_hardware_key(self) -> Self {
+        Self(self.0 | Self::HARDWARE_KEY)
+    }
+    /// Disable key from efuse key slot.
+    #[inline]
+    pub const fn disable_hardware_key(self) -> Self {
+        Self(self.0 & !Self::HARDWARE_KEY)
+    }
+    /// Check if key from efuse key slot is enabled.
+    #[inline]
+    pub const fn is_hardware_key_enabled(self) -> bool {
+        self.0 & Self::HARDWARE_KEY != 0
+    }
+    /// Clear message done interrupt.
+    #[inline]
+    pub const fn clear_interrupt(self) -> Self {
+        Self(self.0 | Self::INTERRUPT_CLEAR)
+    }
+    /// Mask message done interrupt.
+    #[inline]
+    pub const fn mask_interrupt(self) -> Self {
+        Self(self.0 | Self::INTERRUPT_MASK)
+    }
+    /// Unmask message done interrupt.
+    #[inline]
+    pub const fn unmask_interrupt(self) -> Self {
+        Self(self.0 & !Self::INTERRUPT_MASK)
+    }
+    /// Check if message done interrupt is masked.
+    #[inline]
+    pub const fn is_interrupt_masked(self) -> bool {
+        self.0 & Self::INTERRUPT_MASK != 0
+    }
+    /// Set block cipher mode.
+    #[inline]
+    pub const fn set_block_mode(self, val: BlockMode) -> Self {
+        Self((self.0 & !Self::BLOCK_MODE) | ((val as u32) << 12))
+    }
+    /// Get block cipher mode.
+    #[inline]
+    pub const fn block_mode(self) -> BlockMode {
+        match (self.0 & Self::BLOCK_MODE) >> 12 {
+            0 => BlockMode::Ecb,
+            1 => BlockMode::Ctr,
+            2 => BlockMode::Cbc,
+            _ => BlockMode::Xts,
+        }
+    }
+    /// Continue from initial vector state of previous message.
+    #[inline]
+    pub const fn enable_continue_iv(self) -> Self {
+        Self(self.0 | Self::CONTINUE_IV)
+    }
+    /// Load initial vector from registers for next message.
+    #[inline]
+    pub const fn disable_continue_iv(self) -> Self {
+        Self(self.0 & !Self::CONTINUE_IV)
+    }
+    /// Check if initial vector state continues from previous message.
+    #[inline]
+    pub const fn is_continue_iv_enabled(self) -> bool {
+        self.0 & Self::CONTINUE_IV != 0
+    }
+    /// Set message length in 16-byte blocks.
+    #[inline]
+    pub const fn set_message_length(self, val: u16) -> Self {
+        Self((self.0 & !Self::MESSAGE_LENGTH) | ((val as u32) << 16))
+    }
+    /// Get message length in 16-byte blocks.
+    #[inline]
+    pub const fn message_length(self) -> u16 {
+        ((self.0 & Self::MESSAGE_LENGTH) >> 16) as u16
+    }
+}
+
+/// AES key size.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum KeySize {
+    /// 128-bit key.
+    Aes128 = 0,
+    /// 256-bit key.
+    Aes256 = 1,
+    /// 192-bit key.
+    Aes192 = 2,
+}
+
+/// AES block cipher mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum BlockMode {
+    /// Electronic codebook.
+    Ecb = 0,
+    /// Counter mode.
+    Ctr = 1,
+    /// Cipher block chaining.
+    Cbc = 2,
+    /// XEX-based tweaked codebook with ciphertext stealing.
+    Xts = 3,
+}
+
+/// Efuse AES key slot.
+///
+/// Slot numbers are the same as `key_sel` in boot image header, so firmware can decrypt
+/// data with the key the boot ROM used for the image. All four slots can feed the AES
+/// engine, provided they are programmed; an unprogrammed slot reads as an all-zero key.
+///
+/// Efuse key slots have read and write lock bits. A read-locked slot can no longer be
+/// read from efuse registers by any core, but is still delivered to the AES engine over
+/// its internal key bus; this is the intended state for production keys. A write-locked
+/// slot can no longer be programmed. Both locks are permanent, and this module neither
+/// programs nor locks efuse.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum EfuseKeySlot {
+    /// Key slot 0.
+    Slot0 = 0,
+    /// Key slot 1.
+    Slot1 = 1,
+    /// Key slot 2.
+    Slot2 = 2,
+    /// Key slot 3.
+    Slot3 = 3,
+}
+
+/// AES operation error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AesError {
+    /// Message length is zero or not a multiple of 16 bytes.
+    Unaligned,
+    /// Message is longer than 65535 blocks.
+    TooLong,
+    /// Input and output buffers differ in length.
+    LengthMismatch,
+    /// Key is not 16, 24 or 32 bytes long.
+    InvalidKeyLength,
+    /// Authentication tag doesn't match message.
+    AuthenticationFailed,
+}
+
+/// Maximum number of bytes the engine processes in one run.
+const MAX_RUN_LENGTH: usize = 16 * u16::MAX as usize;
+
+/// Message block buffer, word aligned for the engine to read and write.
+#[derive(Clone, Copy)]
+#[repr(C, align(4))]
+struct AesBlock([u8; 16]);
+
+/// Managed Advanced Encryption Standard engine.
+///
+/// Software keys stay in engine key registers until the engine is dropped or freed,
+/// when key registers are cleared.
+pub struct Aes<SEC: Deref<Target = RegisterBlock>> {
+    sec: SEC,
+    key_size: KeySize,
+    hardware_key: bool,
+}
+
+impl<SEC: Deref<Target = RegisterBlock>> Aes<SEC> {
+    /// Create an AES engine which takes software keys on each operation.
+    #[inline]
+    pub fn new(sec: SEC) -> Self {
+        unsafe {
+            // Data, key and initial vector are in big endian byte order.
+            sec.aes.endian.write(0x1f);
+            sec.aes
+                .control
+                .write(AesControl(0).mask_interrupt().enable());
+        }
+        Aes {
+            sec,
+            key_size: KeySize::Aes128,
+            hardware_key: false,
+        }
+    }
+    /// Create an AES engine which takes its key from efuse `slot`.
+    ///
+    /// Key is routed from efuse into the engine directly, without passing through CPU
+    /// registers or memory. Both key select registers are set to `slot`, as the boot
+    /// ROM does when decrypting images.
+    #[inline]
+    pub fn with_efuse_key(sec: SEC, slot: EfuseKeySlot, key_size: KeySize) -> Self {
+        unsafe {
+            // Data, key and initial vector are in big endian byte order.
+            sec.aes.endian.write(0x1f);
+            sec.aes.key_select[0].write(slot as u32);
+            sec.aes.key_select[1].write(slot as u32);
+            sec.aes.control.write(
+                AesControl(0)
+                    .enable_hardware_key()
+                    .set_key_size(key_size)
+                    .mask_interrupt()
+                    .enable(),
+            );
+        }
+        Aes {
+            sec,
+            key_size,
+            hardware_key: true,
+        }
+    }
+    /// Encrypt `input` into `output` with block cipher `mode` and initial vector `iv`.
+    ///
+    /// `iv` is ignored in ECB mode, and is the initial counter block in CTR mode.
+    #[inline]
+    pub fn encrypt(
+        &mut self,
+        mode: BlockMode,
+        iv: &[u8; 16],
+        input: &[u8],
+        output: &mut [u8],
+    ) -> Result<(), AesError> {
+        self.process(false, mode, iv, input, output)
+    }
+    /// Decrypt `input` into `output` with block cipher `mode` and initial vector `iv`.
+    ///
+    /// `iv` is ignored in ECB mode, and is the initial counter block in CTR mode.
+    #[inline]
+    pub fn decrypt(
+        &mut self,
+        mode: BlockMode,
+        iv: &[u8; 16],
+        input: &[u8],
+        output: &mut [u8],
+    ) -> Result<(), AesError> {
+        self.process(true, mode, iv, input, output)
+    }
+    /// Encrypt `plaintext` into `ciphertext` in ECB mode with software `key`.
+    ///
+    /// `key` is 16, 24 or 32 bytes long, and message length is a multiple of 16 bytes.
+    #[inline]
+    pub fn encrypt_ecb(
+        &mut self,
+        key: &[u8],
+        plaintext: &[u8],
+        ciphertext: &mut [u8],
+    ) -> Result<(), AesError> {
+        self.load_key(key)?;
+        self.crypt(false, BlockMode::Ecb, &[0; 16], plaintext, ciphertext)
+    }
+    /// Decrypt `ciphertext` into `plaintext` in ECB mode with software `key`.
+    #[inline]
+    pub fn decrypt_ecb(
+        &mut self,
+        key: &[u8],
+        ciphertext: &[u8],
+        plaintext: &mut [u8],
+    ) -> Result<(), AesError> {
+        self.load_key(key)?;
+        self.crypt(true, BlockMode::Ecb, &[0; 16], ciphertext, plaintext)
+    }
+    /// Encrypt `plaintext` into `ciphertext` in CBC mode with software `key` and initial vector `iv`.
+    ///
+    /// `key` is 16, 24 or 32 bytes long, and message length is a multiple of 16 bytes.
+    #[inline]
+    pub fn encrypt_cbc(
+        &mut self,
+        key: &[u8],
+        iv: &[u8; 16],
+        plaintext: &[u8],
+        ciphertext: &mut [u8],
+    ) -> Result<(), AesError> {
+        self.load_key(key)?;
+        self.crypt(false, BlockMode::Cbc, iv, plaintext, ciphertext)
+    }
+    /// Decrypt `ciphertext` into `plaintext` in CBC mode with software `key` and initial vector `iv`.
+    #[inline]
+    pub fn decrypt_cbc(
+        &mut self,
+        key: &[u8],
+        iv: &[u8; 16],
+        ciphertext: &[u8],
+        plaintext: &mut [u8],
+    ) -> Result<(), AesError> {
+        self.load_key(key)?;
+        self.crypt(true, BlockMode::Cbc, iv, ciphertext, plaintext)
+    }
+    /// Encrypt `plaintext` into `ciphertext` in CTR mode with software `key` and initial `counter` block.
+    ///
+    /// `key` is 16, 24 or 32 bytes long; message can be of any length. Counter block is
+    /// incremented as a 128-bit big endian integer.
+    #[inline]
+    pub fn encrypt_ctr(
+        &mut self,
+        key: &[u8],
+        counter: &[u8; 16],
+        plaintext: &[u8],
+        ciphertext: &mut [u8],
+    ) -> Result<(), AesError> {
+        self.load_key(key)?;
+        self.crypt(false, BlockMode::Ctr, counter, plaintext, ciphertext)
+    }
+    /// Decrypt `ciphertext` into `plaintext` in CTR mode with software `key` and initial `counter` block.
+    #[inline]
+    pub fn decrypt_ctr(
+        &mut self,
+        key: &[u8],
+        counter: &[u8; 16],
+        ciphertext: &[u8],
+        plaintext: &mut [u8],
+    ) -> Result<(), AesError> {
+        self.load_key(key)?;
+        self.crypt(true, BlockMode::Ctr, counter, ciphertext, plaintext)
+    }
+    /// Disable the engine, clear key registers and release the security engine peripheral.
+    #[inline]
+    pub fn free(self) -> SEC {
+        let this = ManuallyDrop::new(self);
+        this.clear();
+        // Engine is cleared above and never dropped, so moving peripheral out is sound.
+        unsafe { core::ptr::read(&this.sec) }
+    }
+
+    /// Clear key registers and disable the engine.
+    fn clear(&self) {
+        unsafe {
+            for reg in &self.sec.aes.key {
+                reg.write(0);
+            }
+            self.sec.aes.control.modify(|v| v.disable());
+        }
+        compiler_fence(Ordering::SeqCst);
+    }
+    /// Write software `key` into key registers.
+    fn load_key(&mut self, key: &[u8]) -> Result<(), AesError> {
+        self.key_size = key_size(key.len())?;
+        self.hardware_key = false;
+        for (reg, word) in self.sec.aes.key.iter().zip(key.chunks_exact(4)) {
+            unsafe { reg.write(u32::from_be_bytes(word.try_into().unwrap())) };
+        }
+        Ok(())
+    }
+    /// Encrypt one block in place with the key in key registers.
+    fn encrypt_block(&mut self, block: &mut [u8; 16]) {
+        let (src, mut dst) = (AesBlock(*block), AesBlock([0; 16]));
+        let (src_ptr, dst_ptr) = (src.0.as_ptr(), dst.0.as_mut_ptr());
+        self.run(false, BlockMode::Ecb, &[0; 16], false, src_ptr, dst_ptr, 1);
+        *block = dst.0;
+        zeroize(&mut dst.0);
+    }
+    /// Process a message of any number of blocks, and a partial tail block in CTR mode.
+    fn crypt(
+        &mut self,
+        decrypt: bool,
+        mode: BlockMode,
+        iv: &[u8; 16],
+        input: &[u8],
+        output: &mut [u8],
+    ) -> Result<(), AesError> {
+        if input.len() != output.len() {
+            return Err(AesError::LengthMismatch);
+        }
+        let full = input.len() & !0xf;
+        if full != input.len() && mode != BlockMode::Ctr {
+            return Err(AesError::Unaligned);
+        }
+        // Long messages are split into engine runs, each continuing from the state of previous run.
+        let runs = input[..full]
+            .chunks(MAX_RUN_LENGTH)
+            .zip(output[..full].chunks_mut(MAX_RUN_LENGTH));
+        for (i, (src, dst)) in runs.enumerate() {
+            let blocks = (src.len() / 16) as u16;
+            self.run(
+                decrypt,
+                mode,
+                iv,
+                i != 0,
+                src.as_ptr(),
+                dst.as_mut_ptr(),
+                blocks,
+            );
+        }
+        if full != input.len() {
+            let counter = u128::from_be_bytes(*iv).wrapping_add((full / 16) as u128);
+            let (mut src, mut dst) = (AesBlock([0; 16]), AesBlock([0; 16]));
+            let tail = input.len() - full;
+            src.0[..tail].copy_from_slice(&input[full..]);
+            let (src_ptr, dst_ptr) = (src.0.as_ptr(), dst.0.as_mut_ptr());
+            self.run(
+                decrypt,
+                mode,
+                &counter.to_be_bytes(),
+                false,
+                src_ptr,
+                dst_ptr,
+                1,
+            );
+            output[full..].copy_from_slice(&dst.0[..tail]);
+            zeroize(&mut src.0);
+            zeroize(&mut dst.0);
+        }
+        Ok(())
+    }
+    fn process(
+        &mut self,
+        decrypt: bool,
+        mode: BlockMode,
+        iv: &[u8; 16],
+        input: &[u8],
+        output: &mut [u8],
+    ) -> Result<(), AesError> {
+        let blocks = message_blocks(input.len(), output.len())?;
+        self.run(
+            decrypt,
+            mode,
+            iv,
+            false,
+            input.as_ptr(),
+            output.as_mut_ptr(),
+            blocks,
+        );
+        Ok(())
+    }
+    /// Run the engine on `blocks` 16-byte blocks from `input` to `output`.
+    #[allow(clippy::too_many_arguments)]
+    fn run(
+        &mut self,
+        decrypt: bool,
+        mode: BlockMode,
+        iv: &[u8; 16],
+        continue_iv: bool,
+        input: *const u8,
+        output: *mut u8,
+        blocks: u16,
+    ) {
+        let aes = &self.sec.aes;
+        unsafe {
+            for (reg, word) in aes.iv.iter().zip(iv.chunks_exact(4)) {
+                reg.write(u32::from_be_bytes(word.try_into().unwrap()));
+            }
+            aes.source_address.write(input as usize as u32);
+            aes.destination_address.write(output as usize as u32);
+            let control = AesControl(aes.control.read().0 & !AesControl::BUSY)
+                .set_key_size(self.key_size)
+                .set_block_mode(mode)
+                .set_message_length(blocks);
+            let control = if self.hardware_key {
+                control.enable_hardware_key()
+            } else {
+                control.disable_hardware_key()
+            };
+            let control = if continue_iv {
+                control.enable_continue_iv()
+            } else {
+                control.disable_continue_iv()
+            };
+            let control = if decrypt {
+                control.enable_decrypt()
+            } else {
+                control.disable_decrypt()
+            };
+            aes.control.write(control);
+            compiler_fence(Ordering::SeqCst);
+            aes.control.write(control.trigger());
+        }
+        while aes.control.read().is_busy() {
+            core::hint::spin_loop();
+        }
+        compiler_fence(Ordering::SeqCst);
+        unsafe { aes.control.modify(|v| v.clear_interrupt()) };
+    }
+}
+
+impl<SEC: Deref<Target = RegisterBlock>> Drop for Aes<SEC> {
+    #[inline]
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// AES engine in Galois/Counter Mode, for authenticated encryption.
+///
+/// Key is written to engine key registers once on creation. Counter mode encryption runs
+/// on the engine, while authentication tags are computed in software from the hash subkey,
+/// which is cleared together with key registers when dropped.
+pub struct AesGcm<SEC: Deref<Target = RegisterBlock>> {
+    aes: Aes<SEC>,
+    hash_key: [u8; 16],
+}
+
+impl<SEC: Deref<Target = RegisterBlock>> AesGcm<SEC> {
+    /// Create a GCM engine with software `key` of 16, 24 or 32 bytes.
+    #[inline]
+    pub fn new(sec: SEC, key: &[u8]) -> Result<Self, AesError> {
+        let mut aes = Aes::new(sec);
+        aes.load_key(key)?;
+        let mut hash_key = [0; 16];
+        aes.encrypt_block(&mut hash_key);
+        Ok(AesGcm { aes, hash_key })
+    }
+    /// Encrypt `plaintext` into `ciphertext` with 96-bit `nonce`, and authenticate it
+    /// together with additional data `aad`.
+    ///
+    /// Returns the 16-byte authentication tag. A nonce must never be reused with the same key.
+    pub fn encrypt_and_authenticate(
+        &mut self,
+        nonce: &[u8; 12],
+        aad: &[u8],
+        plaintext: &[u8],
+        ciphertext: &mut [u8],
+    ) -> Result<[u8; 16], AesError> {
+        let counter = gcm_counter(nonce);
+        self.aes
+            .crypt(false, BlockMode::Ctr, &counter, plaintext, ciphertext)?;
+        Ok(self.tag(nonce, aad, ciphertext))
+    }
+    /// Verify `tag` of `ciphertext` and additional data `aad` with 96-bit `nonce`, and
+    /// decrypt `ciphertext` into `plaintext`.
+    ///
+    /// On authentication failure `plaintext` is cleared, so unauthenticated data is never released.
+    pub fn decrypt_and_verify(
+        &mut self,
+        nonce: &[u8; 12],
+        aad: &[u8],
+        ciphertext: &[u8],
+        tag: &[u8; 16],
+        plaintext: &mut [u8],
+    ) -> Result<(), AesError> {
+        let counter = gcm_counter(nonce);
+        self.aes
+            .crypt(true, BlockMode::Ctr, &counter, ciphertext, plaintext)?;
+        if !ct_eq(&self.tag(nonce, aad, ciphertext), tag) {
+            zeroize(plaintext);
+            return Err(AesError::AuthenticationFailed);
+        }
+        Ok(())
+    }
+    /// Clear key material, disable the engine and release the security engine peripheral.
+    #[inline]
+    pub fn free(self) -> SEC {
+        let mut this = ManuallyDrop::new(self);
+        zeroize(&mut this.hash_key);
+        // Hash subkey is cleared above and never dropped, so moving engine out is sound.
+        unsafe { core::ptr::read(&this.aes) }.free()
+    }
+
+    /// Authentication tag of `aad` and `ciphertext`.
+    fn tag(&mut self, nonce: &[u8; 12], aad: &[u8], ciphertext: &[u8]) -> [u8; 16] {
+        let mut mask = [0; 16];
+        mask[..12].copy_from_slice(nonce);
+        mask[15] = 1;
+        self.aes.encrypt_block(&mut mask);
+        let hash = ghash(&self.hash_key, aad, ciphertext);
+        let mut tag = [0; 16];
+        for (t, (h, m)) in tag.iter_mut().zip(hash.iter().zip(&mask)) {
+            *t = h ^ m;
+        }
+        zeroize(&mut mask);
+        tag
+    }
+}
+
+impl<SEC: Deref<Target = RegisterBlock>> Drop for AesGcm<SEC> {
+    #[inline]
+    fn drop(&mut self) {
+        zeroize(&mut self.hash_key);
+    }
+}
+
+/// Key size of a software key `len` bytes long.
+#[inline]
+const fn key_size(len: usize) -> Result<KeySize, AesError> {
+    match len {
+        16 => Ok(KeySize::Aes128),
+        24 => Ok(KeySize::Aes192),
+        32 => Ok(KeySize::Aes256),
+        _ => Err(AesError::InvalidKeyLength),
+    }
+}
+
+/// First counter block for message encryption in GCM mode with a 96-bit `nonce`.
+#[inline]
+fn gcm_counter(nonce: &[u8; 12]) -> [u8; 16] {
+    let mut counter = [0; 16];
+    counter[..12].copy_from_slice(nonce);
+    counter[15] = 2;
+    counter
+}
+
+/// Multiply `x` by `y` in the binary field GF(2^128) of GCM, in constant time.
+#[inline]
+const fn gf128_mul(x: u128, y: u128) -> u128 {
+    let (mut z, mut v) = (0u128, y);
+    let mut i = 0;
+    while i < 128 {
+        z ^= v & 0u128.wrapping_sub((x >> (127 - i)) & 1);
+        v = (v >> 1) ^ (0xe1 << 120 & 0u128.wrapping_sub(v & 1));
+        i += 1;
+    }
+    z
+}
+
+/// GHASH of additional data `aad` and `ciphertext` under hash subkey `h`.
+#[inline]
+fn ghash(h: &[u8; 16], aad: &[u8], ciphertext: &[u8]) -> [u8; 16] {
+    let h = u128::from_be_bytes(*h);
+    let mut y = 0;
+    for data in [aad, ciphertext] {
+        for chunk in data.chunks(16) {
+            let mut block = [0; 16];
+            block[..chunk.len()].copy_from_slice(chunk);
+            y = gf128_mul(y ^ u128::from_be_bytes(block), h);
+        }
+    }
+    let lengths = ((aad.len() as u128 * 8) << 64) | (ciphertext.len() as u128 * 8);
+    gf128_mul(y ^ lengths, h).to_be_bytes()
+}
+
+/// Number of 16-byte blocks in a message of `input` and `output` lengths.
+#[inline]
+const fn message_blocks(input: usize, output: usize) -> Result<u16, AesError> {
+    if input != output {
+        return Err(AesError::LengthMismatch);
+    }
+    if input == 0 || !input.is_multiple_of(16) {
+        return Err(AesError::Unaligned);
+    }
+    if input / 16 > u16::MAX as usize {
+        return Err(AesError::TooLong);
+    }
+    Ok((input / 16) as u16)
+}
+
+/// Compare two byte slices in constant time.
+///
+/// Every byte is examined regardless of where the first difference is, so time spent only
+/// depends on slice lengths. Slices of different lengths compare unequal immediately, as
+/// lengths are not considered secret. Use this function to check message authentication
+/// codes and tags.
+#[inline]
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut diff = 0u8;
+    for (x, y) in a.iter().zip(b) {
+        diff |= x ^ y;
+    }
+    core::hint::black_box(diff) == 0
+}
+
+/// Overwrite a byte slice with zeros in a way that won't be optimized away.
+///
+/// Use this function to clear key material and other secrets before buffers are released.
+#[inline]
+pub fn zeroize(buf: &mut [u8]) {
+    for byte in buf.iter_mut() {
+        unsafe { core::ptr::write_volatile(byte, 0) };
+    }
+    compiler_fence(Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ct_eq, gcm_counter, gf128_mul, ghash, key_size, message_blocks, zeroize, AesControl,
+        AesError, AesRegisters, BlockMode, KeySize, RegisterBlock, ShaRegisters, TrngRegisters,
+    };
+    use memoffset::offset_of;
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, sha), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, aes), 0x100);
+        assert_eq!(offset_of!(RegisterBlock, trng), 0x200);
+        assert_eq!(offset_of!(ShaRegisters, control), 0x00);
+        assert_eq!(offset_of!(ShaRegisters, message_address), 0x04);
+        assert_eq!(offset_of!(ShaRegisters, status), 0x08);
+        assert_eq!(offset_of!(ShaRegisters, endian), 0x0c);
+        assert_eq!(offset_of!(ShaRegisters, hash_low), 0x10);
+        assert_eq!(offset_of!(ShaRegisters, hash_high), 0x30);
+        assert_eq!(offset_of!(ShaRegisters, link), 0x50);
+        assert_eq!(core::mem::size_of::<ShaRegisters>(), 0x100);
+        assert_eq!(offset_of!(AesRegisters, control), 0x00);
+        assert_eq!(offset_of!(AesRegisters, source_address), 0x04);
+        assert_eq!(offset_of!(AesRegisters, destination_address), 0x08);
+        assert_eq!(offset_of!(AesRegisters, status), 0x0c);
+        assert_eq!(offset_of!(AesRegisters, iv), 0x10);
+        assert_eq!(offset_of!(AesRegisters, key), 0x20);
+        assert_eq!(offset_of!(AesRegisters, key_select), 0x40);
+        assert_eq!(offset_of!(AesRegisters, endian), 0x48);
+        assert_eq!(core::mem::size_of::<AesRegisters>(), 0x100);
+        assert_eq!(offset_of!(TrngRegisters, control), 0x00);
+        assert_eq!(offset_of!(TrngRegisters, status), 0x04);
+        assert_eq!(offset_of!(TrngRegisters, output), 0x08);
+        assert_eq!(offset_of!(TrngRegisters, test), 0x28);
+        assert_eq!(offset_of!(TrngRegisters, test_output), 0x2c);
+        assert_eq!(offset_of!(TrngRegisters, reseed_interval), 0x3c);
+        assert_eq!(offset_of!(TrngRegisters, health_test), 0x44);
+        assert_eq!(core::mem::size_of::<TrngRegisters>(), 0x100);
+    }
+
+    #[test]
+    fn struct_aes_control_functions() {
+        let mut val = AesControl(0x0);
+        assert!(!val.is_busy());
+        assert!(AesControl(0x1).is_busy());
+        assert_eq!(val.trigger().0, 0x0000_0002);
+
+        val = val.enable();
+        assert_eq!(val.0, 0x0000_0004);
+        assert!(val.is_enabled());
+        val = val.disable();
+        assert_eq!(val.0, 0x0000_0000);
+
+        val = val.set_key_size(KeySize::Aes192);
+        assert_eq!(val.0, 0x0000_0010);
+        assert_eq!(val.key_size(), KeySize::Aes192);
+        val = val.set_key_size(KeySize::Aes256);
+        assert_eq!(val.0, 0x0000_0008);
+        assert_eq!(val.key_size(), KeySize::Aes256);
+        val = val.set_key_size(KeySize::Aes128);
+        assert_eq!(val.0, 0x0000_0000);
+
+        val = val.enable_decrypt();
+        assert_eq!(val.0, 0x0000_0020);
+        assert!(val.is_decrypt_enabled());
+        val = val.disable_decrypt();
+        assert_eq!(val.0, 0x0000_0000);
+
+        val = val.enable_hardware_key();
+        assert_eq!(val.0, 0x0000_0080);
+        assert!(val.is_hardware_key_enabled());
+        val = val.disable_hardware_key();
+        assert_eq!(val.0, 0x0000_0000);
+
+        assert_eq!(val.clear_interrupt().0, 0x0000_0200);
+        val = val.mask_interrupt();
+        assert_eq!(val.0, 0x0000_0800);
+        assert!(val.is_interrupt_masked());
+        val = val.unmask_interrupt();
+        assert_eq!(val.0, 0x0000_0000);
+
+        val = val.set_block_mode(BlockMode::Xts);
+        assert_eq!(val.0, 0x0000_3000);
+        assert_eq!(val.block_mode(), BlockMode::Xts);
+        val = val.set_block_mode(BlockMode::Ctr);
+        assert_eq!(val.0, 0x0000_1000);
+        assert_eq!(val.block_mode(), BlockMode::Ctr);
+        val = val.set_block_mode(BlockMode::Ecb);
+
+        val = val.enable_continue_iv();
+        assert_eq!(val.0, 0x0000_4000);
+        assert!(val.is_continue_iv_enabled());
+        val = val.disable_continue_iv();
+        assert_eq!(val.0, 0x0000_0000);
+
+        val = val.set_message_length(0xffff);
+        assert_eq!(val.0, 0xffff_0000);
+        assert_eq!(val.message_length(), 0xffff);
+    }
+
+    #[test]
+    fn function_message_blocks() {
+        assert_eq!(message_blocks(32, 32), Ok(2));
+        assert_eq!(message_blocks(16, 32), Err(AesError::LengthMismatch));
+        assert_eq!(message_blocks(0, 0), Err(AesError::Unaligned));
+        assert_eq!(message_blocks(20, 20), Err(AesError::Unaligned));
+        assert_eq!(
+            message_blocks(16 * 0x10000, 16 * 0x10000),
+            Err(AesError::TooLong)
+        );
+    }
+
+    #[test]
+    fn function_key_size() {
+        assert_eq!(key_size(16), Ok(KeySize::Aes128));
+        assert_eq!(key_size(24), Ok(KeySize::Aes192));
+        assert_eq!(key_size(32), Ok(KeySize::Aes256));
+        assert_eq!(key_size(0), Err(AesError::InvalidKeyLength));
+        assert_eq!(key_size(20), Err(AesError::InvalidKeyLength));
+    }
+
+    fn hex<const N: usize>(s: &str) -> [u8; N] {
+        let mut ans = [0; N];
+        for (i, b) in ans.iter_mut().enumerate() {
+            *b = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16).unwrap();
+        }
+        ans
+    }
+
+    #[test]
+    fn function_ghash() {
+        let one = 1u128 << 127;
+        let x = 0x0388dace60b6a392f328c2b971b2fe78;
+        assert_eq!(gf128_mul(x, one), x);
+        assert_eq!(gf128_mul(one, x), x);
+        assert_eq!(gf128_mul(x, 0), 0);
+        assert_eq!(gcm_counter(&[0xca; 12])[12..], [0, 0, 0, 2]);
+
+        // Test cases 2 and 4 of the GCM specification.
+        let h = hex::<16>("66e94bd4ef8a2c3b884cfa59ca342b2e");
+        let c = hex::<16>("0388dace60b6a392f328c2b971b2fe78");
+        assert_eq!(ghash(&h, &[], &c), hex("f38cbb1ad69223dcc3457ae5b6b0f885"));
+        let h = hex::<16>("b83b533708bf535d0aa6e52980d53b78");
+        let a = hex::<20>("feedfacedeadbeeffeedfacedeadbeefabaddad2");
+        let c = hex::<60>(concat!(
+            "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e",
+            "21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091"
+        ));
+        assert_eq!(ghash(&h, &a, &c), hex("698e57f70e6ecc7fd9463b7260a9ae5f"));
+    }
+
+    #[test]
+    fn function_ct_eq() {
+        assert!(ct_eq(b"", b""));
+        assert!(ct_eq(b"bouffalo", b"bouffalo"));
+        assert!(!ct_eq(b"bouffalo", b"bouffalp"));
+        assert!(!ct_eq(b"bouffalo", b"Bouffalo"));
+        assert!(!ct_eq(b"bouffalo", b"bouffal"));
+    }
+
+    #[test]
+    fn function_zeroize() {
+        let mut key = [0x5au8; 32];
+        zeroize(&mut key);
+        assert_eq!(key, [0u8; 32]);
+    }
+}
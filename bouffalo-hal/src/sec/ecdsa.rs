@@ -0,0 +1,101 @@
+//! ECDSA P-256 signature verification.
+//!
+//! Curve arithmetic comes from the `p256` crate; the hardware public key accelerator
+//! is not driven here. Enable feature `ecdsa` to use this module.
+
+use p256::ecdsa::signature::hazmat::PrehashVerifier;
+use p256::ecdsa::{Signature, VerifyingKey};
+use p256::EncodedPoint;
+
+/// ECDSA signature verification error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignatureError {
+    /// Public key is not a point on curve P-256.
+    InvalidPublicKey,
+    /// Signature component `r` or `s` is zero or not less than curve order.
+    Malformed,
+    /// Signature does not match public key and message hash.
+    Mismatch,
+}
+
+/// Verify an ECDSA P-256 signature over a SHA-256 message hash.
+///
+/// `pubkey` is the uncompressed public key as big-endian `x` followed by `y`, without
+/// the `0x04` prefix; `signature` is big-endian `r` followed by `s`. Returns `Ok(())` only
+/// if the signature was made by the private key of `pubkey` over `message_hash`.
+pub fn verify_signature(
+    pubkey: &[u8; 64],
+    message_hash: &[u8; 32],
+    signature: &[u8; 64],
+) -> Result<(), SignatureError> {
+    let point = EncodedPoint::from_untagged_bytes(pubkey.into());
+    let key =
+        VerifyingKey::from_encoded_point(&point).map_err(|_| SignatureError::InvalidPublicKey)?;
+    let signature = Signature::from_slice(signature).map_err(|_| SignatureError::Malformed)?;
+    key.verify_prehash(message_hash, &signature)
+        .map_err(|_| SignatureError::Mismatch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{verify_signature, SignatureError};
+
+    const PUBKEY: [u8; 64] = [
+        0x1e, 0xfc, 0x46, 0x60, 0x71, 0xa9, 0x55, 0x75, 0xcc, 0x92, 0xdd, 0x81, 0x82, 0x49, 0x8d,
+        0x16, 0xa4, 0x41, 0xe6, 0x28, 0x9e, 0x1e, 0xa7, 0xc5, 0x9d, 0x67, 0x56, 0x35, 0xfd, 0x35,
+        0x90, 0x5b, 0x7b, 0xcb, 0xc8, 0x21, 0x3c, 0x09, 0x4a, 0xd8, 0x4d, 0x76, 0x00, 0x49, 0x16,
+        0x0c, 0x84, 0xe2, 0x58, 0xb9, 0xc1, 0x39, 0xe1, 0xd0, 0xa9, 0xb9, 0xb4, 0xb9, 0x68, 0xd1,
+        0x96, 0x3b, 0x38, 0xb6,
+    ];
+    /// SHA-256 of `b"bouffalo firmware image"`.
+    const HASH: [u8; 32] = [
+        0x74, 0x0b, 0xaa, 0x24, 0x2e, 0xfc, 0x61, 0xbe, 0x8d, 0xa6, 0x78, 0x89, 0x93, 0xbc, 0x8e,
+        0x86, 0xc7, 0xcc, 0x31, 0x69, 0x35, 0xc9, 0xc8, 0x7d, 0x8d, 0xe0, 0x6b, 0x74, 0xb4, 0x64,
+        0x47, 0x26,
+    ];
+    const SIGNATURE: [u8; 64] = [
+        0xf4, 0xcd, 0x46, 0x54, 0x4e, 0xfa, 0x56, 0xd5, 0x69, 0x3e, 0xcf, 0xba, 0xf2, 0xf5, 0x95,
+        0x37, 0x41, 0x98, 0xf5, 0x76, 0xb3, 0xea, 0xe0, 0x33, 0x10, 0x56, 0x99, 0xc0, 0x5b, 0x84,
+        0x4c, 0x0c, 0x4d, 0x26, 0xb8, 0x20, 0x68, 0x87, 0xe6, 0x08, 0xb0, 0x6c, 0xf5, 0xaa, 0x43,
+        0x70, 0x1e, 0xc1, 0x30, 0x35, 0x2c, 0xe9, 0xc9, 0x2e, 0x84, 0xc4, 0xcd, 0xec, 0x19, 0xd5,
+        0x3c, 0x9d, 0xc4, 0xcd,
+    ];
+
+    #[test]
+    fn function_verify_signature() {
+        assert_eq!(verify_signature(&PUBKEY, &HASH, &SIGNATURE), Ok(()));
+
+        let mut hash = HASH;
+        hash[31] ^= 1;
+        assert_eq!(
+            verify_signature(&PUBKEY, &hash, &SIGNATURE),
+            Err(SignatureError::Mismatch)
+        );
+        let mut signature = SIGNATURE;
+        signature[40] ^= 0x80;
+        assert_eq!(
+            verify_signature(&PUBKEY, &HASH, &signature),
+            Err(SignatureError::Mismatch)
+        );
+
+        let mut signature = SIGNATURE;
+        signature[..32].fill(0);
+        assert_eq!(
+            verify_signature(&PUBKEY, &HASH, &signature),
+            Err(SignatureError::Malformed)
+        );
+        let mut signature = SIGNATURE;
+        signature[32..].fill(0xff);
+        assert_eq!(
+            verify_signature(&PUBKEY, &HASH, &signature),
+            Err(SignatureError::Malformed)
+        );
+
+        let mut pubkey = PUBKEY;
+        pubkey[63] ^= 1;
+        assert_eq!(
+            verify_signature(&pubkey, &HASH, &SIGNATURE),
+            Err(SignatureError::InvalidPublicKey)
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use super::sha::final_blocks;
+use digest::{
+    typenum::U16, FixedOutput, FixedOutputReset, HashMarker, Output, OutputSizeUser, Reset, Update,
+};
+
+/// Software MD5 hasher.
+///
+/// **MD5 is broken and is not a cryptographic hash.** It is provided only to compute
+/// checksums required by legacy protocols and formats, and must not be used to sign,
+/// verify or otherwise protect data.
+///
+/// Implements `Digest` of the `digest` crate through its component traits.
+#[derive(Clone, Debug)]
+pub struct Md5 {
+    state: [u32; 4],
+    block: [u8; 64],
+    buffered: usize,
+    length: u64,
+}
+
+const INITIAL_STATE: [u32; 4] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476];
+
+const SHIFTS: [u32; 16] = [7, 12, 17, 22, 5, 9, 14, 20, 4, 11, 16, 23, 6, 10, 15, 21];
+
+/// Round constants, `floor(abs(sin(i + 1)) * 2^32)`.
+const K: [u32; 64] = [
+    0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501,
+    0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821,
+    0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+    0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a,
+    0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c, 0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70,
+    0x289b7ec6, 0xeaa127fa, 0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+    0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+    0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
+];
+
+impl Md5 {
+    /// Create an MD5 hasher.
+    #[inline]
+    pub const fn new() -> Self {
+        Md5 {
+            state: INITIAL_STATE,
+            block: [0; 64],
+            buffered: 0,
+            length: 0,
+        }
+    }
+    fn finish(&mut self, out: &mut Output<Self>) {
+        let (tail, len) = final_blocks(
+            &self.block[..self.buffered],
+            (self.length * 8).to_le_bytes(),
+        );
+        for block in tail[..len].chunks_exact(64) {
+            compress(&mut self.state, block.try_into().unwrap());
+        }
+        for (bytes, word) in out.chunks_exact_mut(4).zip(self.state) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+    }
+}
+
+impl Default for Md5 {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Update for Md5 {
+    fn update(&mut self, mut data: &[u8]) {
+        self.length += data.len() as u64;
+        while !data.is_empty() {
+            let n = data.len().min(64 - self.buffered);
+            self.block[self.buffered..self.buffered + n].copy_from_slice(&data[..n]);
+            self.buffered += n;
+            data = &data[n..];
+            if self.buffered == 64 {
+                compress(&mut self.state, &self.block);
+                self.buffered = 0;
+            }
+        }
+    }
+}
+
+impl OutputSizeUser for Md5 {
+    type OutputSize = U16;
+}
+
+impl FixedOutput for Md5 {
+    #[inline]
+    fn finalize_into(mut self, out: &mut Output<Self>) {
+        self.finish(out);
+    }
+}
+
+impl Reset for Md5 {
+    #[inline]
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl FixedOutputReset for Md5 {
+    #[inline]
+    fn finalize_into_reset(&mut self, out: &mut Output<Self>) {
+        self.finish(out);
+        Reset::reset(self);
+    }
+}
+
+impl HashMarker for Md5 {}
+
+/// Process one 64-byte block into MD5 `state`.
+fn compress(state: &mut [u32; 4], block: &[u8; 64]) {
+    let mut m = [0u32; 16];
+    for (word, bytes) in m.iter_mut().zip(block.chunks_exact(4)) {
+        *word = u32::from_le_bytes(bytes.try_into().unwrap());
+    }
+    let [mut a, mut b, mut c, mut d] = *state;
+    for i in 0..64 {
+        let (f, g) = match i / 16 {
+            0 => ((b & c) | (!b & d), i),
+            1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+            2 => (b ^ c ^ d, (3 * i + 5) % 16),
+            _ => (c ^ (b | !d), (7 * i) % 16),
+        };
+        let f = f.wrapping_add(a).wrapping_add(K[i]).wrapping_add(m[g]);
+        a = d;
+        d = c;
+        c = b;
+        b = b.wrapping_add(f.rotate_left(SHIFTS[(i / 16) * 4 + i % 4]));
+    }
+    state[0] = state[0].wrapping_add(a);
+    state[1] = state[1].wrapping_add(b);
+    state[2] = state[2].wrapping_add(c);
+    state[3] = state[3].wrapping_add(d);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Md5;
+    use digest::Digest;
+
+    fn hex(data: &[u8]) -> [u8; 16] {
+        Md5::digest(data).into()
+    }
+
+    #[test]
+    fn function_md5_vectors() {
+        // Test suite of RFC 1321.
+        assert_eq!(
+            hex(b""),
+            0xd41d8cd98f00b204e9800998ecf8427eu128.to_be_bytes()
+        );
+        assert_eq!(
+            hex(b"abc"),
+            0x900150983cd24fb0d6963f7d28e17f72u128.to_be_bytes()
+        );
+        assert_eq!(
+            hex(b"message digest"),
+            0xf96b697d7cb7938d525a2f31aaf161d0u128.to_be_bytes()
+        );
+        assert_eq!(
+            hex(
+                b"12345678901234567890123456789012345678901234567890123456789012345678901234567890"
+            ),
+            0x57edf4a22be3c955ac49da2e2107b67au128.to_be_bytes()
+        );
+    }
+
+    #[test]
+    fn function_md5_incremental() {
+        let data = [0xa5u8; 200];
+        let mut hasher = Md5::new();
+        for chunk in data.chunks(7) {
+            hasher.update(chunk);
+        }
+        assert_eq!(hasher.finalize_reset(), Md5::digest(data));
+        hasher.update(b"abc");
+        assert_eq!(
+            <[u8; 16]>::from(hasher.finalize()),
+            0x900150983cd24fb0d6963f7d28e17f72u128.to_be_bytes()
+        );
+    }
+}
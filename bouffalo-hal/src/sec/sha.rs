@@ -0,0 +1,572 @@
+use super::RegisterBlock;
+use core::{
+    ops::Deref,
+    sync::atomic::{compiler_fence, Ordering},
+};
+#[cfg(feature = "digest")]
+use digest::{
+    typenum::{U20, U32},
+    FixedOutput, FixedOutputReset, HashMarker, Output, OutputSizeUser, Reset, Update,
+};
+use volatile_register::{RO, RW};
+
+/// Secure Hash Algorithm engine registers.
+#[repr(C)]
+pub struct ShaRegisters {
+    /// Engine control and message length.
+    pub control: RW<ShaControl>,
+    /// Message source address.
+    pub message_address: RW<u32>,
+    /// Engine status.
+    pub status: RO<u32>,
+    /// Byte order of message and hash.
+    pub endian: RW<u32>,
+    /// Lower half of hash state words.
+    pub hash_low: [RO<u32>; 8],
+    /// Upper half of hash state words, used by SHA-512 family.
+    pub hash_high: [RO<u32>; 8],
+    /// Linked list configuration address.
+    pub link: RW<u32>,
+    _reserved0: [u8; 0xac],
+}
+
+/// SHA engine control register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct ShaControl(u32);
+
+impl ShaControl {
+    const BUSY: u32 = 1 << 0;
+    const TRIGGER: u32 = 1 << 1;
+    const MODE: u32 = 0x7 << 2;
+    const ENABLE: u32 = 1 << 5;
+    const CONTINUE_HASH: u32 = 1 << 6;
+    const INTERRUPT_CLEAR: u32 = 1 << 9;
+    const INTERRUPT_MASK: u32 = 1 << 11;
+    const MESSAGE_LENGTH: u32 = 0xffff << 16;
+
+    /// Check if engine is processing a message.
+    #[inline]
+    pub const fn is_busy(self) -> bool {
+        self.0 & Self::BUSY != 0
+    }
+    /// Start processing message.
+    #[inline]
+    pub const fn trigger(self) -> Self {
+        Self(self.0 | Self::TRIGGER)
+    }
+    /// Set hash algorithm.
+    #[inline]
+    pub const fn set_mode(self, val: ShaMode) -> Self {
+        Self((self.0 & !Self::MODE) | ((val as u32) << 2))
+    }
+    /// Get hash algorithm.
+    #[inline]
+    pub const fn mode(self) -> ShaMode {
+        match (self.0 & Self::MODE) >> 2 {
+            0 => ShaMode::Sha256,
+            1 => ShaMode::Sha224,
+            2 | 3 => ShaMode::Sha1,
+            4 => ShaMode::Sha512,
+            5 => ShaMode::Sha384,
+            6 => ShaMode::Sha512Trunc224,
+            _ => ShaMode::Sha512Trunc256,
+        }
+    }
+    /// Enable SHA engine.
+    #[inline]
+    pub const fn enable(self) -> Self {
+        Self(self.0 | Self::ENABLE)
+    }
+    /// Disable SHA engine.
+    #[inline]
+    pub const fn disable(self) -> Self {
+        Self(self.0 & !Self::ENABLE)
+    }
+    /// Check if SHA engine is enabled.
+    #[inline]
+    pub const fn is_enabled(self) -> bool {
+        self.0 & Self::ENABLE != 0
+    }
+    /// Continue from hash state of previous message.
+    #[inline]
+    pub const fn enable_continue_hash(self) -> Self {
+        Self(self.0 | Self::CONTINUE_HASH)
+    }
+    /// Start from initial hash state for next message.
+    #[inline]
+    pub const fn disable_continue_hash(self) -> Self {
+        Self(self.0 & !Self::CONTINUE_HASH)
+    }
+    /// Check if hash state continues from previous message.
+    #[inline]
+    pub const fn is_continue_hash_enabled(self) -> bool {
+        self.0 & Self::CONTINUE_HASH != 0
+    }
+    /// Clear message done interrupt.
+    #[inline]
+    pub const fn clear_interrupt(self) -> Self {
+        Self(self.0 | Self::INTERRUPT_CLEAR)
+    }
+    /// Mask message done interrupt.
+    #[inline]
+    pub const fn mask_interrupt(self) -> Self {
+        Self(self.0 | Self::INTERRUPT_MASK)
+    }
+    /// Unmask message done interrupt.
+    #[inline]
+    pub const fn unmask_interrupt(self) -> Self {
+        Self(self.0 & !Self::INTERRUPT_MASK)
+    }
+    /// Check if message done interrupt is masked.
+    #[inline]
+    pub const fn is_interrupt_masked(self) -> bool {
+        self.0 & Self::INTERRUPT_MASK != 0
+    }
+    /// Set message length in 64-byte blocks.
+    #[inline]
+    pub const fn set_message_length(self, val: u16) -> Self {
+        Self((self.0 & !Self::MESSAGE_LENGTH) | ((val as u32) << 16))
+    }
+    /// Get message length in 64-byte blocks.
+    #[inline]
+    pub const fn message_length(self) -> u16 {
+        ((self.0 & Self::MESSAGE_LENGTH) >> 16) as u16
+    }
+}
+
+/// SHA engine hash algorithm.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum ShaMode {
+    /// SHA-256.
+    Sha256 = 0,
+    /// SHA-224.
+    Sha224 = 1,
+    /// SHA-1, a legacy algorithm.
+    Sha1 = 2,
+    /// SHA-512.
+    Sha512 = 4,
+    /// SHA-384.
+    Sha384 = 5,
+    /// SHA-512/224.
+    Sha512Trunc224 = 6,
+    /// SHA-512/256.
+    Sha512Trunc256 = 7,
+}
+
+/// Message block buffer, word aligned for the engine to read.
+#[derive(Clone, Copy)]
+#[repr(C, align(4))]
+struct Block([u8; 64]);
+
+/// Hash state of a 64-byte block algorithm on the engine, buffering partial blocks.
+struct Engine<SEC> {
+    sec: SEC,
+    mode: ShaMode,
+    block: Block,
+    buffered: usize,
+    length: u64,
+    started: bool,
+}
+
+impl<SEC: Deref<Target = RegisterBlock>> Engine<SEC> {
+    #[inline]
+    const fn new(sec: SEC, mode: ShaMode) -> Self {
+        Engine {
+            sec,
+            mode,
+            block: Block([0; 64]),
+            buffered: 0,
+            length: 0,
+            started: false,
+        }
+    }
+    #[inline]
+    fn free(self) -> SEC {
+        unsafe { self.sec.sha.control.modify(|v| v.disable()) };
+        self.sec
+    }
+    /// Start hashing `blocks` 64-byte blocks at word aligned address `data`.
+    fn start(&mut self, data: *const u8, blocks: u16) {
+        let sha = &self.sec.sha;
+        let control = ShaControl(0)
+            .set_mode(self.mode)
+            .mask_interrupt()
+            .set_message_length(blocks)
+            .enable();
+        let control = if self.started {
+            control.enable_continue_hash()
+        } else {
+            control.disable_continue_hash()
+        };
+        unsafe {
+            sha.message_address.write(data as usize as u32);
+            sha.control.write(control);
+            compiler_fence(Ordering::SeqCst);
+            sha.control.write(control.trigger());
+        }
+        self.started = true;
+    }
+    /// Check if engine has finished hashing blocks, and clear its interrupt if so.
+    fn poll_done(&mut self) -> bool {
+        let sha = &self.sec.sha;
+        if sha.control.read().is_busy() {
+            return false;
+        }
+        compiler_fence(Ordering::SeqCst);
+        unsafe { sha.control.modify(|v| v.clear_interrupt()) };
+        true
+    }
+    /// Hash `blocks` 64-byte blocks at word aligned address `data`.
+    fn compress(&mut self, data: *const u8, blocks: u16) {
+        self.start(data, blocks);
+        while !self.poll_done() {
+            core::hint::spin_loop();
+        }
+    }
+    fn update(&mut self, mut data: &[u8]) {
+        self.length += data.len() as u64;
+        if self.buffered != 0 {
+            let n = data.len().min(64 - self.buffered);
+            self.block.0[self.buffered..self.buffered + n].copy_from_slice(&data[..n]);
+            self.buffered += n;
+            data = &data[n..];
+            if self.buffered < 64 {
+                return;
+            }
+            self.compress(self.block.0.as_ptr(), 1);
+            self.buffered = 0;
+        }
+        // Aligned whole blocks are read by the engine in place.
+        if (data.as_ptr() as usize).is_multiple_of(4) {
+            while data.len() >= 64 {
+                let blocks = (data.len() / 64).min(u16::MAX as usize);
+                self.compress(data.as_ptr(), blocks as u16);
+                data = &data[blocks * 64..];
+            }
+        }
+        for chunk in data.chunks(64) {
+            self.block.0[..chunk.len()].copy_from_slice(chunk);
+            if chunk.len() < 64 {
+                self.buffered = chunk.len();
+                break;
+            }
+            self.compress(self.block.0.as_ptr(), 1);
+        }
+    }
+    fn finish(&mut self, out: &mut [u8]) {
+        let (tail, len) = final_blocks(
+            &self.block.0[..self.buffered],
+            (self.length * 8).to_be_bytes(),
+        );
+        for block in tail[..len].chunks_exact(64) {
+            self.block.0.copy_from_slice(block);
+            self.compress(self.block.0.as_ptr(), 1);
+        }
+        self.read_hash(out);
+    }
+    fn read_hash(&self, out: &mut [u8]) {
+        for (bytes, reg) in out.chunks_exact_mut(4).zip(&self.sec.sha.hash_low) {
+            bytes.copy_from_slice(&reg.read().to_le_bytes());
+        }
+    }
+    #[inline]
+    fn reset(&mut self) {
+        self.buffered = 0;
+        self.length = 0;
+        self.started = false;
+    }
+}
+
+/// Hardware SHA-1 hasher.
+///
+/// **SHA-1 is broken for collision resistance.** It is provided only to interoperate
+/// with legacy protocols and formats that require it, and must not be used to sign,
+/// verify or otherwise protect data.
+///
+/// With feature `digest`, implements `Update`, `FixedOutput` and `Reset` of the `digest`
+/// crate. It does not implement `Digest`, which requires `Default` that a hardware engine
+/// can't provide.
+pub struct Sha1<SEC> {
+    engine: Engine<SEC>,
+}
+
+impl<SEC: Deref<Target = RegisterBlock>> Sha1<SEC> {
+    /// Create a SHA-1 hasher on the security engine.
+    #[inline]
+    pub fn new(sec: SEC) -> Self {
+        Sha1 {
+            engine: Engine::new(sec, ShaMode::Sha1),
+        }
+    }
+    /// Disable the engine and release the security engine peripheral.
+    #[inline]
+    pub fn free(self) -> SEC {
+        self.engine.free()
+    }
+}
+
+/// Hardware SHA-256 hasher.
+///
+/// Partial blocks are buffered and padded in software, while whole blocks at word aligned
+/// addresses are read by the engine in place. Implements the same `digest` traits as
+/// [`Sha1`] with feature `digest`.
+pub struct Sha256<SEC> {
+    engine: Engine<SEC>,
+}
+
+impl<SEC: Deref<Target = RegisterBlock>> Sha256<SEC> {
+    /// Create a SHA-256 hasher on the security engine.
+    #[inline]
+    pub fn new(sec: SEC) -> Self {
+        Sha256 {
+            engine: Engine::new(sec, ShaMode::Sha256),
+        }
+    }
+    /// Disable the engine and release the security engine peripheral.
+    #[inline]
+    pub fn free(self) -> SEC {
+        self.engine.free()
+    }
+}
+
+#[cfg(feature = "digest")]
+macro_rules! impl_digest {
+    ($($hasher: ident, $size: ty;)+) => {
+$(
+impl<SEC: Deref<Target = RegisterBlock>> Update for $hasher<SEC> {
+    #[inline]
+    fn update(&mut self, data: &[u8]) {
+        self.engine.update(data);
+    }
+}
+
+impl<SEC> OutputSizeUser for $hasher<SEC> {
+    type OutputSize = $size;
+}
+
+impl<SEC: Deref<Target = RegisterBlock>> FixedOutput for $hasher<SEC> {
+    #[inline]
+    fn finalize_into(mut self, out: &mut Output<Self>) {
+        self.engine.finish(out);
+    }
+}
+
+impl<SEC: Deref<Target = RegisterBlock>> Reset for $hasher<SEC> {
+    #[inline]
+    fn reset(&mut self) {
+        self.engine.reset();
+    }
+}
+
+impl<SEC: Deref<Target = RegisterBlock>> FixedOutputReset for $hasher<SEC> {
+    #[inline]
+    fn finalize_into_reset(&mut self, out: &mut Output<Self>) {
+        self.engine.finish(out);
+        self.engine.reset();
+    }
+}
+
+impl<SEC> HashMarker for $hasher<SEC> {}
+)+
+    };
+}
+
+#[cfg(feature = "digest")]
+impl_digest! {
+    Sha1, U20;
+    Sha256, U32;
+}
+
+/// Compute SHA-256 hash of `data` into `hash` on security engine `sec`.
+#[inline]
+pub fn sha256_oneshot(sec: &RegisterBlock, data: &[u8], hash: &mut [u8; 32]) {
+    let mut engine = Engine::new(sec, ShaMode::Sha256);
+    engine.update(data);
+    engine.finish(hash);
+}
+
+/// SHA-256 hasher reading whole messages from memory in place.
+///
+/// Unlike [`Sha256`], the CPU doesn't wait for the engine: a [`ShaTransfer`] is polled
+/// until the hash is ready, leaving the CPU free while the engine reads long buffers.
+/// Messages must start at a word aligned address; only the last partial block and
+/// padding are copied.
+pub struct Sha256Dma<SEC> {
+    engine: Engine<SEC>,
+    tail: [Block; 2],
+}
+
+/// SHA engine error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShaError {
+    /// Message doesn't start at a word aligned address.
+    Unaligned,
+}
+
+impl<SEC: Deref<Target = RegisterBlock>> Sha256Dma<SEC> {
+    /// Create an in-place SHA-256 hasher on the security engine.
+    #[inline]
+    pub fn new(sec: SEC) -> Self {
+        Sha256Dma {
+            engine: Engine::new(sec, ShaMode::Sha256),
+            tail: [Block([0; 64]); 2],
+        }
+    }
+    /// Start hashing `data`, returning a transfer to poll for the hash.
+    #[inline]
+    pub fn hash<'a>(&'a mut self, data: &'a [u8]) -> Result<ShaTransfer<'a, SEC>, ShaError> {
+        if !(data.as_ptr() as usize).is_multiple_of(4) {
+            return Err(ShaError::Unaligned);
+        }
+        self.engine.reset();
+        let whole = data.len() & !0x3f;
+        let (tail, len) = final_blocks(&data[whole..], (data.len() as u64 * 8).to_be_bytes());
+        for (block, bytes) in self.tail.iter_mut().zip(tail[..len].chunks_exact(64)) {
+            block.0.copy_from_slice(bytes);
+        }
+        Ok(ShaTransfer {
+            remaining: &data[..whole],
+            tail_blocks: (len / 64) as u16,
+            running: false,
+            hasher: self,
+        })
+    }
+    /// Disable the engine and release the security engine peripheral.
+    #[inline]
+    pub fn free(self) -> SEC {
+        self.engine.free()
+    }
+}
+
+/// Ongoing SHA-256 hashing of a message in memory.
+pub struct ShaTransfer<'a, SEC: Deref<Target = RegisterBlock>> {
+    hasher: &'a mut Sha256Dma<SEC>,
+    remaining: &'a [u8],
+    tail_blocks: u16,
+    running: bool,
+}
+
+impl<SEC: Deref<Target = RegisterBlock>> ShaTransfer<'_, SEC> {
+    /// Check if hashing is finished, starting the next engine run if needed.
+    ///
+    /// Returns the hash once all blocks and padding are hashed.
+    pub fn poll(&mut self) -> nb::Result<[u8; 32], core::convert::Infallible> {
+        let engine = &mut self.hasher.engine;
+        if self.running && !engine.poll_done() {
+            return Err(nb::Error::WouldBlock);
+        }
+        if !self.remaining.is_empty() {
+            let blocks = (self.remaining.len() / 64).min(u16::MAX as usize);
+            engine.start(self.remaining.as_ptr(), blocks as u16);
+            self.remaining = &self.remaining[blocks * 64..];
+            self.running = true;
+            return Err(nb::Error::WouldBlock);
+        }
+        if self.tail_blocks != 0 {
+            engine.start(self.hasher.tail.as_ptr() as *const u8, self.tail_blocks);
+            self.tail_blocks = 0;
+            self.running = true;
+            return Err(nb::Error::WouldBlock);
+        }
+        self.running = false;
+        let mut hash = [0; 32];
+        engine.read_hash(&mut hash);
+        Ok(hash)
+    }
+    /// Wait until hashing is finished and return the hash.
+    #[inline]
+    pub fn wait(mut self) -> [u8; 32] {
+        nb::block!(self.poll()).unwrap()
+    }
+}
+
+impl<SEC: Deref<Target = RegisterBlock>> Drop for ShaTransfer<'_, SEC> {
+    /// Wait for the engine to stop reading the message.
+    #[inline]
+    fn drop(&mut self) {
+        while self.running && !self.hasher.engine.poll_done() {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+/// Pad the last partial block of a 64-byte block hash.
+///
+/// Returns one or two blocks holding `partial` bytes, the `0x80` end marker, zeros and
+/// message bit length in the byte order of the algorithm, and the number of bytes used.
+#[inline]
+pub(crate) fn final_blocks(partial: &[u8], bit_length: [u8; 8]) -> ([u8; 128], usize) {
+    let mut tail = [0u8; 128];
+    tail[..partial.len()].copy_from_slice(partial);
+    tail[partial.len()] = 0x80;
+    let len = if partial.len() < 56 { 64 } else { 128 };
+    tail[len - 8..len].copy_from_slice(&bit_length);
+    (tail, len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{final_blocks, ShaControl, ShaMode};
+
+    #[test]
+    fn struct_sha_control_functions() {
+        let mut val = ShaControl(0x0);
+        assert!(!val.is_busy());
+        assert!(ShaControl(0x1).is_busy());
+        assert_eq!(val.trigger().0, 0x0000_0002);
+
+        val = val.set_mode(ShaMode::Sha1);
+        assert_eq!(val.0, 0x0000_0008);
+        assert_eq!(val.mode(), ShaMode::Sha1);
+        assert_eq!(ShaControl(0x0000_000c).mode(), ShaMode::Sha1);
+        val = val.set_mode(ShaMode::Sha512Trunc256);
+        assert_eq!(val.0, 0x0000_001c);
+        assert_eq!(val.mode(), ShaMode::Sha512Trunc256);
+        val = val.set_mode(ShaMode::Sha256);
+        assert_eq!(val.0, 0x0000_0000);
+
+        val = val.enable();
+        assert_eq!(val.0, 0x0000_0020);
+        assert!(val.is_enabled());
+        val = val.disable();
+        assert_eq!(val.0, 0x0000_0000);
+
+        val = val.enable_continue_hash();
+        assert_eq!(val.0, 0x0000_0040);
+        assert!(val.is_continue_hash_enabled());
+        val = val.disable_continue_hash();
+        assert_eq!(val.0, 0x0000_0000);
+
+        assert_eq!(val.clear_interrupt().0, 0x0000_0200);
+        val = val.mask_interrupt();
+        assert_eq!(val.0, 0x0000_0800);
+        assert!(val.is_interrupt_masked());
+        val = val.unmask_interrupt();
+        assert_eq!(val.0, 0x0000_0000);
+
+        val = val.set_message_length(0xffff);
+        assert_eq!(val.0, 0xffff_0000);
+        assert_eq!(val.message_length(), 0xffff);
+    }
+
+    #[test]
+    fn function_final_blocks() {
+        let (tail, len) = final_blocks(b"abc", 24u64.to_be_bytes());
+        assert_eq!(len, 64);
+        assert_eq!(&tail[..4], b"abc\x80");
+        assert!(tail[4..63].iter().all(|&b| b == 0));
+        assert_eq!(tail[63], 24);
+
+        let (tail, len) = final_blocks(&[0x11; 56], 448u64.to_le_bytes());
+        assert_eq!(len, 128);
+        assert_eq!(tail[56], 0x80);
+        assert!(tail[57..120].iter().all(|&b| b == 0));
+        assert_eq!(&tail[120..122], &[0xc0, 0x01]);
+
+        let (tail, len) = final_blocks(&[], [0; 8]);
+        assert_eq!(len, 64);
+        assert_eq!(tail[0], 0x80);
+    }
+}
@@ -0,0 +1,427 @@
+use super::{zeroize, RegisterBlock};
+use core::{
+    num::NonZeroU32,
+    ops::Deref,
+    sync::atomic::{compiler_fence, Ordering},
+};
+use volatile_register::{RO, RW};
+
+/// True random number generator registers.
+#[repr(C)]
+pub struct TrngRegisters {
+    /// Generator control and health test result.
+    pub control: RW<TrngControl>,
+    /// Generator status.
+    pub status: RO<u32>,
+    /// Conditioned random output words.
+    pub output: [RO<u32>; 8],
+    /// Test mode control.
+    pub test: RW<u32>,
+    /// Test mode output words.
+    pub test_output: [RO<u32>; 4],
+    /// Number of output blocks before reseeding, lower and upper half.
+    pub reseed_interval: [RW<u32>; 2],
+    /// Entropy source and health test configuration.
+    pub health_test: RW<HealthTest>,
+    _reserved0: [u8; 0xb8],
+}
+
+/// TRNG control register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct TrngControl(u32);
+
+impl TrngControl {
+    const BUSY: u32 = 1 << 0;
+    const TRIGGER: u32 = 1 << 1;
+    const ENABLE: u32 = 1 << 2;
+    const OUTPUT_CLEAR: u32 = 1 << 3;
+    const HEALTH_TEST_ERROR: u32 = 1 << 4;
+    const INTERRUPT_CLEAR: u32 = 1 << 9;
+    const INTERRUPT_MASK: u32 = 1 << 11;
+
+    /// Check if generator is producing an output block.
+    #[inline]
+    pub const fn is_busy(self) -> bool {
+        self.0 & Self::BUSY != 0
+    }
+    /// Start producing an output block.
+    #[inline]
+    pub const fn trigger(self) -> Self {
+        Self(self.0 | Self::TRIGGER)
+    }
+    /// Enable random number generator.
+    #[inline]
+    pub const fn enable(self) -> Self {
+        Self(self.0 | Self::ENABLE)
+    }
+    /// Disable random number generator.
+    #[inline]
+    pub const fn disable(self) -> Self {
+        Self(self.0 & !Self::ENABLE)
+    }
+    /// Check if random number generator is enabled.
+    #[inline]
+    pub const fn is_enabled(self) -> bool {
+        self.0 & Self::ENABLE != 0
+    }
+    /// Clear output words.
+    #[inline]
+    pub const fn clear_output(self) -> Self {
+        Self(self.0 | Self::OUTPUT_CLEAR)
+    }
+    /// Check if entropy source failed its health tests.
+    #[inline]
+    pub const fn is_health_test_failed(self) -> bool {
+        self.0 & Self::HEALTH_TEST_ERROR != 0
+    }
+    /// Clear output done interrupt.
+    #[inline]
+    pub const fn clear_interrupt(self) -> Self {
+        Self(self.0 | Self::INTERRUPT_CLEAR)
+    }
+    /// Mask output done interrupt.
+    #[inline]
+    pub const fn mask_interrupt(self) -> Self {
+        Self(self.0 | Self::INTERRUPT_MASK)
+    }
+    /// Unmask output done interrupt.
+    #[inline]
+    pub const fn unmask_interrupt(self) -> Self {
+        Self(self.0 & !Self::INTERRUPT_MASK)
+    }
+    /// Check if output done interrupt is masked.
+    #[inline]
+    pub const fn is_interrupt_masked(self) -> bool {
+        self.0 & Self::INTERRUPT_MASK != 0
+    }
+}
+
+/// Entropy source and health test configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct HealthTest(u32);
+
+impl HealthTest {
+    const REPETITION_CUTOFF: u32 = 0xff << 8;
+    const ADAPTIVE_CUTOFF: u32 = 0x3ff << 16;
+    const ON_DEMAND: u32 = 1 << 26;
+    const OSCILLATOR: u32 = 1 << 31;
+
+    /// Set cutoff of repetition count test.
+    #[inline]
+    pub const fn set_repetition_cutoff(self, val: u8) -> Self {
+        Self((self.0 & !Self::REPETITION_CUTOFF) | ((val as u32) << 8))
+    }
+    /// Get cutoff of repetition count test.
+    #[inline]
+    pub const fn repetition_cutoff(self) -> u8 {
+        ((self.0 & Self::REPETITION_CUTOFF) >> 8) as u8
+    }
+    /// Set cutoff of adaptive proportion test.
+    #[inline]
+    pub const fn set_adaptive_cutoff(self, val: u16) -> Self {
+        Self((self.0 & !Self::ADAPTIVE_CUTOFF) | (((val as u32) << 16) & Self::ADAPTIVE_CUTOFF))
+    }
+    /// Get cutoff of adaptive proportion test.
+    #[inline]
+    pub const fn adaptive_cutoff(self) -> u16 {
+        ((self.0 & Self::ADAPTIVE_CUTOFF) >> 16) as u16
+    }
+    /// Run health tests on next output block, in addition to continuous tests.
+    #[inline]
+    pub const fn enable_on_demand_test(self) -> Self {
+        Self(self.0 | Self::ON_DEMAND)
+    }
+    /// Run continuous health tests only.
+    #[inline]
+    pub const fn disable_on_demand_test(self) -> Self {
+        Self(self.0 & !Self::ON_DEMAND)
+    }
+    /// Check if on-demand health test is enabled.
+    #[inline]
+    pub const fn is_on_demand_test_enabled(self) -> bool {
+        self.0 & Self::ON_DEMAND != 0
+    }
+    /// Enable ring oscillator entropy source.
+    #[inline]
+    pub const fn enable_oscillator(self) -> Self {
+        Self(self.0 | Self::OSCILLATOR)
+    }
+    /// Disable ring oscillator entropy source.
+    #[inline]
+    pub const fn disable_oscillator(self) -> Self {
+        Self(self.0 & !Self::OSCILLATOR)
+    }
+    /// Check if ring oscillator entropy source is enabled.
+    #[inline]
+    pub const fn is_oscillator_enabled(self) -> bool {
+        self.0 & Self::OSCILLATOR != 0
+    }
+}
+
+/// Random number generation error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrngError {
+    /// Entropy source failed its continuous health tests; no output was produced.
+    HealthTestFailed,
+}
+
+/// Health test error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrngHealthError {
+    /// Hardware on-demand health test of the entropy source failed.
+    EntropySource,
+    /// Output repeated the same byte too many times in a row.
+    RepetitionCount,
+    /// One byte value occurred too often in a test window.
+    AdaptiveProportion,
+}
+
+/// Repetition count test cutoff for byte samples.
+///
+/// NIST SP800-90B section 4.4.1 cutoff `1 + ceil(20 / H)` with false positive probability
+/// of 2^-20, assuming at least one bit of min-entropy `H` per byte.
+const REPETITION_CUTOFF: usize = 21;
+/// Adaptive proportion test window for byte samples, NIST SP800-90B section 4.4.2.
+const ADAPTIVE_WINDOW: usize = 512;
+/// Adaptive proportion test cutoff for the window above and one bit of min-entropy per byte.
+const ADAPTIVE_CUTOFF: usize = 311;
+
+/// Managed true random number generator.
+///
+/// Output blocks come from a conditioned ring oscillator entropy source which is checked
+/// by continuous health tests in hardware. Reading fails with [`TrngError`] instead of
+/// returning output once those tests fail.
+pub struct Trng<SEC> {
+    sec: SEC,
+}
+
+impl<SEC: Deref<Target = RegisterBlock>> Trng<SEC> {
+    /// Create a random number generator on the security engine.
+    #[inline]
+    pub fn new(sec: SEC) -> Self {
+        unsafe {
+            sec.trng.health_test.modify(|v| v.enable_oscillator());
+            sec.trng
+                .control
+                .write(TrngControl(0).mask_interrupt().clear_interrupt().enable());
+        }
+        Trng { sec }
+    }
+    /// Fill `dest` with random bytes.
+    ///
+    /// Polls the generator for as many 32-byte output blocks as needed. Returns an error
+    /// once the entropy source fails its health tests, leaving `dest` partly filled.
+    pub fn fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), TrngError> {
+        let mut bytes = [0u8; 32];
+        let mut ans = Ok(());
+        for chunk in dest.chunks_mut(32) {
+            if let Err(e) = self.generate(&mut bytes) {
+                ans = Err(e);
+                break;
+            }
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+        zeroize(&mut bytes);
+        ans
+    }
+    /// Run health tests on the entropy source and on generator output.
+    ///
+    /// The entropy source is checked by hardware on-demand tests, then a window of output
+    /// is checked by the NIST SP800-90B repetition count and adaptive proportion tests.
+    /// Output used for testing is discarded.
+    pub fn test_health(&mut self) -> Result<(), TrngHealthError> {
+        let mut window = [0u8; ADAPTIVE_WINDOW];
+        unsafe {
+            self.sec
+                .trng
+                .health_test
+                .modify(|v| v.enable_on_demand_test())
+        };
+        let source = self.generate(&mut window[..32]);
+        unsafe {
+            self.sec
+                .trng
+                .health_test
+                .modify(|v| v.disable_on_demand_test())
+        };
+        if source.is_err() {
+            return Err(TrngHealthError::EntropySource);
+        }
+        let ans = match self.fill_bytes(&mut window) {
+            Ok(()) => check_samples(&window),
+            Err(_) => Err(TrngHealthError::EntropySource),
+        };
+        zeroize(&mut window);
+        ans
+    }
+    /// Disable the generator and release the security engine peripheral.
+    #[inline]
+    pub fn free(self) -> SEC {
+        unsafe { self.sec.trng.control.modify(|v| v.disable()) };
+        self.sec
+    }
+
+    /// Produce one output block into `buf` of at most 32 bytes.
+    fn generate(&mut self, buf: &mut [u8]) -> Result<(), TrngError> {
+        let trng = &self.sec.trng;
+        while trng.control.read().is_busy() {
+            core::hint::spin_loop();
+        }
+        unsafe { trng.control.modify(|v| v.clear_interrupt().trigger()) };
+        compiler_fence(Ordering::SeqCst);
+        while trng.control.read().is_busy() {
+            core::hint::spin_loop();
+        }
+        compiler_fence(Ordering::SeqCst);
+        let ans = if trng.control.read().is_health_test_failed() {
+            Err(TrngError::HealthTestFailed)
+        } else {
+            for (bytes, reg) in buf.chunks_mut(4).zip(&trng.output) {
+                bytes.copy_from_slice(&reg.read().to_le_bytes()[..bytes.len()]);
+            }
+            Ok(())
+        };
+        // Output words are consumed, so they can't be read again.
+        unsafe { trng.control.modify(|v| v.clear_interrupt().clear_output()) };
+        ans
+    }
+}
+
+/// Check byte samples with NIST SP800-90B repetition count and adaptive proportion tests.
+#[inline]
+fn check_samples(samples: &[u8]) -> Result<(), TrngHealthError> {
+    let mut run = 0;
+    for (i, &byte) in samples.iter().enumerate() {
+        run = if i > 0 && samples[i - 1] == byte {
+            run + 1
+        } else {
+            1
+        };
+        if run >= REPETITION_CUTOFF {
+            return Err(TrngHealthError::RepetitionCount);
+        }
+    }
+    for window in samples.chunks_exact(ADAPTIVE_WINDOW) {
+        let count = window.iter().filter(|&&b| b == window[0]).count();
+        if count >= ADAPTIVE_CUTOFF {
+            return Err(TrngHealthError::AdaptiveProportion);
+        }
+    }
+    Ok(())
+}
+
+impl<SEC: Deref<Target = RegisterBlock>> rand_core::RngCore for Trng<SEC> {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+    /// Fill `dest` with random bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the entropy source fails its health tests.
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        Trng::fill_bytes(self, dest).expect("entropy source failed health tests")
+    }
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        Trng::fill_bytes(self, dest).map_err(|_| {
+            rand_core::Error::from(NonZeroU32::new(rand_core::Error::CUSTOM_START).unwrap())
+        })
+    }
+}
+
+impl<SEC: Deref<Target = RegisterBlock>> rand_core::CryptoRng for Trng<SEC> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_samples, HealthTest, TrngControl, TrngHealthError, ADAPTIVE_WINDOW};
+
+    #[test]
+    fn struct_trng_control_functions() {
+        let mut val = TrngControl(0x0);
+        assert!(!val.is_busy());
+        assert!(TrngControl(0x1).is_busy());
+        assert_eq!(val.trigger().0, 0x0000_0002);
+
+        val = val.enable();
+        assert_eq!(val.0, 0x0000_0004);
+        assert!(val.is_enabled());
+        val = val.disable();
+        assert_eq!(val.0, 0x0000_0000);
+
+        assert_eq!(val.clear_output().0, 0x0000_0008);
+        assert!(TrngControl(0x10).is_health_test_failed());
+        assert!(!val.is_health_test_failed());
+
+        assert_eq!(val.clear_interrupt().0, 0x0000_0200);
+        val = val.mask_interrupt();
+        assert_eq!(val.0, 0x0000_0800);
+        assert!(val.is_interrupt_masked());
+        val = val.unmask_interrupt();
+        assert_eq!(val.0, 0x0000_0000);
+    }
+
+    #[test]
+    fn struct_health_test_functions() {
+        let mut val = HealthTest(0x0);
+
+        val = val.set_repetition_cutoff(0xff);
+        assert_eq!(val.0, 0x0000_ff00);
+        assert_eq!(val.repetition_cutoff(), 0xff);
+        val = val.set_repetition_cutoff(0);
+
+        val = val.set_adaptive_cutoff(0xffff);
+        assert_eq!(val.0, 0x03ff_0000);
+        assert_eq!(val.adaptive_cutoff(), 0x3ff);
+        val = val.set_adaptive_cutoff(0);
+
+        val = val.enable_on_demand_test();
+        assert_eq!(val.0, 0x0400_0000);
+        assert!(val.is_on_demand_test_enabled());
+        val = val.disable_on_demand_test();
+        assert_eq!(val.0, 0x0000_0000);
+
+        val = val.enable_oscillator();
+        assert_eq!(val.0, 0x8000_0000);
+        assert!(val.is_oscillator_enabled());
+        val = val.disable_oscillator();
+        assert_eq!(val.0, 0x0000_0000);
+    }
+
+    #[test]
+    fn function_check_samples() {
+        let mut samples = [0u8; ADAPTIVE_WINDOW];
+        for (i, b) in samples.iter_mut().enumerate() {
+            *b = (i * 7 + i / 256) as u8;
+        }
+        assert_eq!(check_samples(&samples), Ok(()));
+
+        samples[100..120].fill(0x5a);
+        assert_eq!(check_samples(&samples), Ok(()));
+        samples[120] = 0x5a;
+        assert_eq!(
+            check_samples(&samples),
+            Err(TrngHealthError::RepetitionCount)
+        );
+
+        for (i, b) in samples.iter_mut().enumerate() {
+            *b = if i % 2 == 0 { 0xa5 } else { i as u8 };
+        }
+        assert_eq!(check_samples(&samples), Ok(()));
+        for b in samples.iter_mut().step_by(3) {
+            *b = 0xa5;
+        }
+        assert_eq!(
+            check_samples(&samples),
+            Err(TrngHealthError::AdaptiveProportion)
+        );
+    }
+}
@@ -1,4 +1,9 @@
 //! Serial Peripheral Interface peripheral.
+//!
+//! The controller shifts one data bit per clock cycle, on MOSI out and MISO in, or
+//! on the bidirectional line of a 3-wire bus. It has no dual or quad data line modes, so
+//! SPI NOR flash and PSRAM can only be driven in single line mode here; quad access to
+//! them is provided by the dedicated flash and PSRAM controllers of the chip.
 
 use crate::glb::{self, v2::SpiMode};
 use crate::gpio::{self, Alternate};
@@ -7,6 +12,13 @@ use core::ops::Deref;
 use embedded_hal::spi::Mode;
 use volatile_register::{RO, RW, WO};
 
+mod bitbang;
+pub use bitbang::*;
+mod shared;
+pub use shared::*;
+mod stream;
+pub use stream::*;
+
 /// Serial Peripheral Interface registers.
 #[repr(C)]
 pub struct RegisterBlock {
@@ -620,11 +632,34 @@ impl FifoConfig1 {
 }
 
 /// Managed Serial Peripheral Interface peripheral.
-pub struct Spi<SPI, PADS, const I: usize> {
+///
+/// `MASTER` selects whether this chip drives the bus, created by [`Spi::new`], or is
+/// driven by an external master, created by [`Spi::new_slave`].
+pub struct Spi<SPI, PADS, const I: usize, const MASTER: bool = true> {
     spi: SPI,
     pads: PADS,
 }
 
+impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize, const MASTER: bool>
+    Spi<SPI, PADS, I, MASTER>
+{
+    /// Release the SPI instance and return the pads.
+    #[inline]
+    pub fn free(self) -> (SPI, PADS) {
+        (self.spi, self.pads)
+    }
+
+    /// Set clock polarity and phase for following transfers.
+    #[inline]
+    pub fn set_mode(&mut self, mode: Mode) {
+        unsafe {
+            self.spi
+                .config
+                .modify(|config| config_with_mode(config, mode))
+        };
+    }
+}
+
 impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> Spi<SPI, PADS, I> {
     /// Create a new Serial Peripheral Interface instance.
     #[inline]
@@ -641,21 +676,7 @@ impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> Spi<SPI, PADS, I>
             .disable_bit_inverse()
             .set_frame_size(FrameSize::Eight)
             .disable_master();
-
-        config = match mode.phase {
-            embedded_hal::spi::Phase::CaptureOnFirstTransition => {
-                config.set_clock_phase(Phase::CaptureOnFirstTransition)
-            }
-
-            embedded_hal::spi::Phase::CaptureOnSecondTransition => {
-                config.set_clock_phase(Phase::CaptureOnSecondTransition)
-            }
-        };
-
-        config = match mode.polarity {
-            embedded_hal::spi::Polarity::IdleHigh => config.set_clock_polarity(Polarity::IdleHigh),
-            embedded_hal::spi::Polarity::IdleLow => config.set_clock_polarity(Polarity::IdleLow),
-        };
+        config = config_with_mode(config, mode);
 
         unsafe {
             glb.param_config
@@ -682,10 +703,349 @@ impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> Spi<SPI, PADS, I>
         Spi { spi, pads }
     }
 
-    /// Release the SPI instance and return the pads.
+    /// Set length of each clock half period in SPI source clock cycles.
+    ///
+    /// Serial clock frequency equals source clock divided by `2 * cycles`.
+    ///
+    /// # Panics
+    ///
+    /// If `cycles` is not in range `1..=256`.
+    #[inline]
+    pub fn set_half_period(&mut self, cycles: u16) {
+        assert!(
+            (1..=256).contains(&cycles),
+            "half period should be in 1..=256 cycles"
+        );
+        let val = (cycles - 1) as u8;
+        unsafe {
+            self.spi
+                .period_signal
+                .modify(|v| v.set_data_phase_0(val).set_data_phase_1(val))
+        };
+    }
+
+    /// Set chip select setup time in SPI source clock cycles.
+    ///
+    /// Hardware chip select is asserted this long before the first clock edge of a
+    /// transfer. One serial clock period lasts twice the half period set by
+    /// [`set_half_period`](Self::set_half_period), in the same source clock cycles.
+    ///
+    /// # Panics
+    ///
+    /// If `cycles` is not in range `1..=256`.
+    #[inline]
+    pub fn set_cs_setup(&mut self, cycles: u16) {
+        assert!(
+            (1..=256).contains(&cycles),
+            "chip select setup should be in 1..=256 cycles"
+        );
+        let val = (cycles - 1) as u8;
+        unsafe {
+            self.spi
+                .period_signal
+                .modify(|v| v.set_start_condition(val))
+        };
+    }
+
+    /// Set chip select hold time in SPI source clock cycles.
+    ///
+    /// Hardware chip select stays asserted this long after the last clock edge of a
+    /// transfer before it's released.
+    ///
+    /// # Panics
+    ///
+    /// If `cycles` is not in range `1..=256`.
     #[inline]
-    pub fn free(self) -> (SPI, PADS) {
-        (self.spi, self.pads)
+    pub fn set_cs_hold(&mut self, cycles: u16) {
+        assert!(
+            (1..=256).contains(&cycles),
+            "chip select hold should be in 1..=256 cycles"
+        );
+        let val = (cycles - 1) as u8;
+        unsafe { self.spi.period_signal.modify(|v| v.set_stop_condition(val)) };
+    }
+
+    /// Set idle time between frames in SPI source clock cycles.
+    ///
+    /// Serial clock pauses this long between consecutive frames of a transfer; with
+    /// frame size of eight bits, a frame is one byte.
+    ///
+    /// # Panics
+    ///
+    /// If `cycles` is not in range `1..=256`.
+    #[inline]
+    pub fn set_frame_interval(&mut self, cycles: u16) {
+        assert!(
+            (1..=256).contains(&cycles),
+            "frame interval should be in 1..=256 cycles"
+        );
+        let val = (cycles - 1) as u8;
+        unsafe {
+            self.spi
+                .period_interval
+                .modify(|v| v.set_frame_interval(val))
+        };
+    }
+
+    /// Write `words` and replace each with the byte clocked in on MISO at the same time.
+    ///
+    /// Receive FIFO is drained in lockstep with transmit, so the status that a device
+    /// streams during a command write is kept. Unlike a write followed by a read, both
+    /// directions share the same clock frames. Bytes left in receive FIFO by earlier
+    /// writes are discarded first.
+    pub fn write_read_duplex(&mut self, words: &mut [u8]) -> Result<(), Error> {
+        const MAX_RETRY: usize = 1000;
+        unsafe {
+            self.spi
+                .fifo_config_0
+                .modify(|config| config.clear_receive_fifo());
+            self.spi.config.modify(|config| config.enable_master());
+        }
+
+        let (mut tx, mut rx) = (0, 0);
+        let mut fifo_config = self.spi.fifo_config_1.read();
+        let mut retry = 0;
+        while tx < words.len() || rx < words.len() {
+            while fifo_config.receive_available_bytes() == 0
+                && fifo_config.transmit_available_bytes() == 0
+            {
+                fifo_config = self.spi.fifo_config_1.read();
+            }
+            if fifo_config.transmit_available_bytes() != 0 && tx < words.len() {
+                unsafe { self.spi.fifo_write.write(words[tx]) }
+                tx += 1;
+            }
+            if fifo_config.receive_available_bytes() != 0 && rx < tx {
+                words[rx] = self.spi.fifo_read.read();
+                rx += 1;
+            }
+            fifo_config = self.spi.fifo_config_1.read();
+            retry += 1;
+            if retry > MAX_RETRY * words.len() {
+                unsafe { self.spi.config.modify(|config| config.disable_master()) };
+                return Err(Error::Other);
+            }
+        }
+
+        unsafe { self.spi.config.modify(|config| config.disable_master()) };
+        Ok(())
+    }
+
+    /// Wait until all queued words are shifted out and bus becomes idle.
+    #[inline]
+    fn wait_idle(&self) {
+        while self.spi.fifo_config_1.read().transmit_available_bytes() != 32
+            || self.spi.bus_busy.read().is_bus_busy()
+        {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> Spi<SPI, PADS, I, false> {
+    /// Create a new Serial Peripheral Interface instance in slave mode.
+    ///
+    /// Clock, chip select and MOSI are driven by the external master; this chip drives
+    /// MISO while selected. The peripheral listens from creation on, so bytes the master
+    /// sends between transfers queue up in receive FIFO. Slave timeout defaults to the
+    /// longest value, see [`set_timeout`](Self::set_timeout).
+    #[inline]
+    pub fn new_slave<GLB>(spi: SPI, pads: PADS, mode: Mode, glb: &GLB) -> Self
+    where
+        PADS: SlavePads<I>,
+        GLB: Deref<Target = glb::v2::RegisterBlock>,
+    {
+        unsafe {
+            glb.param_config
+                .modify(|c| c.set_spi_mode::<I>(SpiMode::Slave));
+
+            spi.config.write(slave_config(mode).disable_slave());
+            spi.fifo_config_0.write(
+                FifoConfig0(0)
+                    .disable_dma_receive()
+                    .disable_dma_transmit()
+                    .clear_transmit_fifo()
+                    .clear_receive_fifo(),
+            );
+            spi.fifo_config_1.write(
+                FifoConfig1(0)
+                    .set_receive_threshold(0)
+                    .set_transmit_threshold(0),
+            );
+            spi.slave_timeout
+                .write(SlaveTimeout(0).set_threshold(SLAVE_TIMEOUT_MAX));
+            spi.interrupt_config
+                .modify(|v| v.clear_interrupt(Interrupt::SlaveTimeout));
+            spi.config.write(slave_config(mode));
+        }
+        Spi { spi, pads }
+    }
+
+    /// Set slave timeout in SPI source clock cycles.
+    ///
+    /// Once a transfer has received its first byte, the bus staying idle this long fails
+    /// it with [`Error::Timeout`], so a master that stops clocking or a stuck chip select
+    /// doesn't block forever. Waiting for the first byte is not limited.
+    ///
+    /// # Panics
+    ///
+    /// If `cycles` is not in range `1..=4095`.
+    #[inline]
+    pub fn set_timeout(&mut self, cycles: u16) {
+        assert!(
+            (1..=SLAVE_TIMEOUT_MAX).contains(&cycles),
+            "slave timeout should be in 1..=4095 cycles"
+        );
+        unsafe { self.spi.slave_timeout.modify(|v| v.set_threshold(cycles)) };
+    }
+
+    /// Clear slave timeout flag raised by earlier bus idle.
+    #[inline]
+    fn clear_timeout(&self) {
+        unsafe {
+            self.spi
+                .interrupt_config
+                .modify(|v| v.clear_interrupt(Interrupt::SlaveTimeout))
+        };
+    }
+
+    /// Fail with `Error::Timeout` if bus has been idle longer than slave timeout.
+    #[inline]
+    fn check_timeout(&self) -> Result<(), Error> {
+        if self
+            .spi
+            .interrupt_config
+            .read()
+            .has_interrupt(Interrupt::SlaveTimeout)
+        {
+            self.clear_timeout();
+            return Err(Error::Timeout);
+        }
+        Ok(())
+    }
+}
+
+/// Longest slave timeout in SPI source clock cycles.
+const SLAVE_TIMEOUT_MAX: u16 = 0xFFF;
+
+/// Peripheral configuration of slave mode with clock `mode`.
+#[inline]
+const fn slave_config(mode: Mode) -> Config {
+    let config = Config(0)
+        .disable_deglitch()
+        .disable_slave_three_pin()
+        .disable_byte_inverse()
+        .disable_bit_inverse()
+        .set_frame_size(FrameSize::Eight)
+        .disable_master()
+        .enable_slave();
+    config_with_mode(config, mode)
+}
+
+/// Apply embedded-hal clock `mode` onto peripheral configuration.
+#[inline]
+const fn config_with_mode(config: Config, mode: Mode) -> Config {
+    let config = match mode.phase {
+        embedded_hal::spi::Phase::CaptureOnFirstTransition => {
+            config.set_clock_phase(Phase::CaptureOnFirstTransition)
+        }
+        embedded_hal::spi::Phase::CaptureOnSecondTransition => {
+            config.set_clock_phase(Phase::CaptureOnSecondTransition)
+        }
+    };
+    match mode.polarity {
+        embedded_hal::spi::Polarity::IdleHigh => config.set_clock_polarity(Polarity::IdleHigh),
+        embedded_hal::spi::Polarity::IdleLow => config.set_clock_polarity(Polarity::IdleLow),
+    }
+}
+
+impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> Spi<SPI, PADS, I>
+where
+    PADS: ThreeWirePads<I>,
+{
+    /// Write `write` and then read into `read` over bidirectional data line of a 3-wire bus.
+    ///
+    /// Chip select stays asserted across both phases. On turnaround, MOSI and MISO signals
+    /// are swapped in GLB so that the MOSI pad turns into input of read phase; dummy zero
+    /// bytes are clocked out on the unconnected MISO signal meanwhile.
+    pub fn write_then_read<GLB>(
+        &mut self,
+        write: &[u8],
+        read: &mut [u8],
+        glb: &GLB,
+    ) -> Result<(), Error>
+    where
+        GLB: Deref<Target = glb::v2::RegisterBlock>,
+    {
+        const MAX_RETRY: usize = 1000;
+        let max_retry = MAX_RETRY * max(write.len(), read.len());
+        unsafe {
+            self.spi
+                .fifo_config_0
+                .modify(|config| config.clear_transmit_fifo().clear_receive_fifo());
+            self.spi.config.modify(|config| config.enable_master());
+        }
+
+        let mut retry = 0;
+        for &word in write {
+            while self.spi.fifo_config_1.read().transmit_available_bytes() == 0 {
+                retry += 1;
+                if retry > max_retry {
+                    return Err(self.abort_three_wire(glb));
+                }
+            }
+            unsafe { self.spi.fifo_write.write(word) };
+        }
+        // Turnaround: wait until all written bytes are shifted out before releasing data line.
+        while self.spi.fifo_config_1.read().transmit_available_bytes() != 32
+            || self.spi.bus_busy.read().is_bus_busy()
+        {
+            retry += 1;
+            if retry > max_retry {
+                return Err(self.abort_three_wire(glb));
+            }
+        }
+        unsafe {
+            self.spi
+                .fifo_config_0
+                .modify(|config| config.clear_receive_fifo());
+            glb.param_config.modify(|c| c.enable_spi_swap::<I>());
+        }
+
+        let (mut tx, mut rx) = (0, 0);
+        while rx < read.len() {
+            let fifo_config = self.spi.fifo_config_1.read();
+            if fifo_config.transmit_available_bytes() != 0 && tx < read.len() {
+                unsafe { self.spi.fifo_write.write(0) };
+                tx += 1;
+            }
+            if fifo_config.receive_available_bytes() != 0 {
+                read[rx] = self.spi.fifo_read.read();
+                rx += 1;
+            }
+            retry += 1;
+            if retry > max_retry {
+                return Err(self.abort_three_wire(glb));
+            }
+        }
+
+        unsafe {
+            self.spi.config.modify(|config| config.disable_master());
+            glb.param_config.modify(|c| c.disable_spi_swap::<I>());
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn abort_three_wire<GLB>(&mut self, glb: &GLB) -> Error
+    where
+        GLB: Deref<Target = glb::v2::RegisterBlock>,
+    {
+        unsafe {
+            self.spi.config.modify(|config| config.disable_master());
+            glb.param_config.modify(|c| c.disable_spi_swap::<I>());
+        }
+        Error::Other
     }
 }
 
@@ -693,6 +1053,10 @@ impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> Spi<SPI, PADS, I>
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum Error {
+    /// Chip select pin of a shared bus device failed to switch.
+    ChipSelect,
+    /// Slave mode transfer stalled longer than the slave timeout.
+    Timeout,
     Other,
 }
 
@@ -701,7 +1065,8 @@ impl embedded_hal::spi::Error for Error {
     fn kind(&self) -> embedded_hal::spi::ErrorKind {
         use embedded_hal::spi::ErrorKind;
         match self {
-            Error::Other => ErrorKind::Other,
+            Error::ChipSelect => ErrorKind::ChipSelectFault,
+            Error::Timeout | Error::Other => ErrorKind::Other,
         }
     }
 }
@@ -777,34 +1142,7 @@ impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> embedded_hal::spi
     }
     #[inline]
     fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
-        const MAX_RETRY: usize = 1000;
-        unsafe { self.spi.config.modify(|config| config.enable_master()) };
-
-        let (mut tx, mut rx) = (0, 0);
-        let mut fifo_config = self.spi.fifo_config_1.read();
-        let mut retry = 0;
-        while tx < words.len() || rx < words.len() {
-            while fifo_config.receive_available_bytes() == 0
-                && fifo_config.transmit_available_bytes() == 0
-            {
-                fifo_config = self.spi.fifo_config_1.read();
-            }
-            if fifo_config.transmit_available_bytes() != 0 && tx < words.len() {
-                unsafe { self.spi.fifo_write.write(words[tx]) }
-                tx += 1;
-            }
-            if fifo_config.receive_available_bytes() != 0 && rx < tx {
-                words[rx] = self.spi.fifo_read.read();
-                rx += 1;
-            }
-            retry += 1;
-            if retry > MAX_RETRY * words.len() {
-                return Err(Error::Other);
-            }
-        }
-
-        unsafe { self.spi.config.modify(|config| config.disable_master()) };
-        Ok(())
+        self.write_read_duplex(words)
     }
     #[inline]
     fn flush(&mut self) -> Result<(), Self::Error> {
@@ -851,6 +1189,87 @@ impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> embedded_hal::spi
     }
 }
 
+impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> embedded_hal::spi::ErrorType
+    for Spi<SPI, PADS, I, false>
+{
+    type Error = Error;
+}
+
+impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> embedded_hal::spi::SpiBus
+    for Spi<SPI, PADS, I, false>
+{
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.transfer(buf, &[])
+    }
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.transfer(&mut [], buf)
+    }
+    /// Queue `write` for the master to clock out and receive into `read` meanwhile.
+    ///
+    /// Zeros are sent once `write` is exhausted and received bytes beyond `read` are
+    /// discarded; returns after the master has clocked the longer of both.
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
+        let len = max(read.len(), write.len());
+        let (mut tx, mut rx) = (0, 0);
+        while rx < len {
+            let fifo_config = self.spi.fifo_config_1.read();
+            if fifo_config.transmit_available_bytes() != 0 && tx < len {
+                unsafe {
+                    self.spi
+                        .fifo_write
+                        .write(write.get(tx).copied().unwrap_or(0))
+                };
+                tx += 1;
+            }
+            if fifo_config.receive_available_bytes() != 0 && rx < tx {
+                let word = self.spi.fifo_read.read();
+                if let Some(slot) = read.get_mut(rx) {
+                    *slot = word;
+                }
+                if rx == 0 {
+                    self.clear_timeout();
+                }
+                rx += 1;
+            } else if rx != 0 {
+                self.check_timeout()?;
+            }
+        }
+        Ok(())
+    }
+    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        let (mut tx, mut rx) = (0, 0);
+        while rx < words.len() {
+            let fifo_config = self.spi.fifo_config_1.read();
+            if fifo_config.transmit_available_bytes() != 0 && tx < words.len() {
+                unsafe { self.spi.fifo_write.write(words[tx]) };
+                tx += 1;
+            }
+            // Word `rx` is always queued before it is overwritten.
+            if fifo_config.receive_available_bytes() != 0 && rx < tx {
+                words[rx] = self.spi.fifo_read.read();
+                if rx == 0 {
+                    self.clear_timeout();
+                }
+                rx += 1;
+            } else if rx != 0 {
+                self.check_timeout()?;
+            }
+        }
+        Ok(())
+    }
+    /// Wait until the master has clocked out all queued words.
+    #[inline]
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.clear_timeout();
+        while self.spi.fifo_config_1.read().transmit_available_bytes() != 32 {
+            self.check_timeout()?;
+        }
+        Ok(())
+    }
+}
+
 // This part of implementation using `embedded_hal_027` is designed for backward compatibility of
 // ecosystem crates, as some of them depends on embedded-hal v0.2.7 traits.
 // We encourage ecosystem developers to use embedded-hal v1.0.0 traits; after that, this part of code
@@ -893,6 +1312,22 @@ where
 {
 }
 
+/// Valid SPI pads for 3-wire bus, where MOSI pad carries data in both directions.
+pub trait ThreeWirePads<const I: usize>: Pads<I> {}
+
+impl<'a, 'b, 'c, const N1: usize, const N2: usize, const N3: usize> ThreeWirePads<1>
+    for (
+        Alternate<'a, N1, gpio::Spi<1>>,
+        Alternate<'b, N2, gpio::Spi<1>>,
+        Alternate<'c, N3, gpio::Spi<1>>,
+    )
+where
+    Alternate<'a, N1, gpio::Spi<1>>: HasClkSignal,
+    Alternate<'b, N2, gpio::Spi<1>>: HasMosiSignal,
+    Alternate<'c, N3, gpio::Spi<1>>: HasCsSignal,
+{
+}
+
 impl<'a, 'b, 'c, 'd, const N1: usize, const N2: usize, const N3: usize, const N4: usize> Pads<1>
     for (
         Alternate<'a, N1, gpio::Spi<1>>,
@@ -908,6 +1343,25 @@ where
 {
 }
 
+/// Valid SPI pads for slave mode, including chip select and both data signals.
+pub trait SlavePads<const I: usize>: Pads<I> {}
+
+impl<'a, 'b, 'c, 'd, const N1: usize, const N2: usize, const N3: usize, const N4: usize>
+    SlavePads<1>
+    for (
+        Alternate<'a, N1, gpio::Spi<1>>,
+        Alternate<'b, N2, gpio::Spi<1>>,
+        Alternate<'c, N3, gpio::Spi<1>>,
+        Alternate<'d, N4, gpio::Spi<1>>,
+    )
+where
+    Alternate<'a, N1, gpio::Spi<1>>: HasClkSignal,
+    Alternate<'b, N2, gpio::Spi<1>>: HasMosiSignal,
+    Alternate<'c, N3, gpio::Spi<1>>: HasMisoSignal,
+    Alternate<'d, N4, gpio::Spi<1>>: HasCsSignal,
+{
+}
+
 /// Check if target gpio `Pin` is internally connected to SPI clock signal.
 pub trait HasClkSignal {}
 
@@ -973,8 +1427,9 @@ impl<'a> HasCsSignal for Alternate<'a, 44, gpio::Spi<1>> {}
 #[cfg(test)]
 mod tests {
     use super::{
-        BusBusy, Config, FifoConfig0, FifoConfig1, FrameSize, Interrupt, InterruptConfig,
-        PeriodInterval, PeriodSignal, Phase, Polarity, ReceiveIgnore, RegisterBlock, SlaveTimeout,
+        config_with_mode, slave_config, BusBusy, Config, FifoConfig0, FifoConfig1, FrameSize,
+        Interrupt, InterruptConfig, PeriodInterval, PeriodSignal, Phase, Polarity, ReceiveIgnore,
+        RegisterBlock, SlaveTimeout,
     };
     use memoffset::offset_of;
 
@@ -1261,4 +1716,29 @@ mod tests {
         assert_eq!(config.0, 0x1f000000);
         assert_eq!(config.receive_threshold(), 0x1f);
     }
+
+    #[test]
+    fn function_config_with_mode() {
+        let config = config_with_mode(Config(0x0), embedded_hal::spi::MODE_0);
+        assert_eq!(config.clock_polarity(), Polarity::IdleLow);
+        assert_eq!(config.clock_phase(), Phase::CaptureOnFirstTransition);
+        let config = config_with_mode(config, embedded_hal::spi::MODE_3);
+        assert_eq!(config.clock_polarity(), Polarity::IdleHigh);
+        assert_eq!(config.clock_phase(), Phase::CaptureOnSecondTransition);
+        let config = config_with_mode(config.enable_master(), embedded_hal::spi::MODE_1);
+        assert!(config.is_master_enabled());
+        assert_eq!(config.clock_polarity(), Polarity::IdleLow);
+        assert_eq!(config.clock_phase(), Phase::CaptureOnSecondTransition);
+    }
+
+    #[test]
+    fn function_slave_config() {
+        let config = slave_config(embedded_hal::spi::MODE_3);
+        assert!(config.is_slave_enabled());
+        assert!(!config.is_master_enabled());
+        assert!(!config.is_slave_three_pin_enabled());
+        assert_eq!(config.frame_size(), FrameSize::Eight);
+        assert_eq!(config.clock_polarity(), Polarity::IdleHigh);
+        assert_eq!(config.clock_phase(), Phase::CaptureOnSecondTransition);
+    }
 }
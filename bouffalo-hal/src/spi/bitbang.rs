@@ -0,0 +1,285 @@
+use super::Error;
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{InputPin, OutputPin};
+use embedded_hal::spi::{Mode, Phase, Polarity};
+use embedded_time::rate::Hertz;
+
+/// Software Serial Peripheral Interface bus over three GPIO pads.
+///
+/// Serves as a fallback for pads not routable to a hardware SPI instance. Words are
+/// shifted most significant bit first in the given clock polarity and phase; the clock
+/// rate is an upper bound, as pad operations add to every half period. Chip select is
+/// not driven by the bus, wrap it in an `embedded-hal-bus` device for that.
+pub struct BitBangSpi<CLK, MOSI, MISO, DELAY> {
+    clk: CLK,
+    mosi: MOSI,
+    miso: MISO,
+    delay: DELAY,
+    mode: Mode,
+    half_period_ns: u32,
+}
+
+impl<CLK: OutputPin, MOSI: OutputPin, MISO: InputPin, DELAY: DelayNs>
+    BitBangSpi<CLK, MOSI, MISO, DELAY>
+{
+    /// Create a bit-banged bus from clock, data output and data input pads.
+    ///
+    /// Clock pad is driven to its idle level of `mode` on creation.
+    #[inline]
+    pub fn new(
+        clk: CLK,
+        mosi: MOSI,
+        miso: MISO,
+        delay: DELAY,
+        mode: Mode,
+        frequency: Hertz,
+    ) -> Result<Self, Error> {
+        let mut spi = Self {
+            clk,
+            mosi,
+            miso,
+            delay,
+            mode,
+            half_period_ns: half_period_ns(frequency),
+        };
+        spi.set_clock(false)?;
+        Ok(spi)
+    }
+    /// Change clock polarity and phase, driving clock pad to its new idle level.
+    #[inline]
+    pub fn set_mode(&mut self, mode: Mode) -> Result<(), Error> {
+        self.mode = mode;
+        self.set_clock(false)
+    }
+    /// Change clock frequency.
+    #[inline]
+    pub fn set_frequency(&mut self, frequency: Hertz) {
+        self.half_period_ns = half_period_ns(frequency);
+    }
+    /// Release the bus and return its pads and delay.
+    #[inline]
+    pub fn free(self) -> (CLK, MOSI, MISO, DELAY) {
+        (self.clk, self.mosi, self.miso, self.delay)
+    }
+
+    /// Drive clock pad to its active level if `active`, or idle level otherwise.
+    #[inline]
+    fn set_clock(&mut self, active: bool) -> Result<(), Error> {
+        let high = active ^ (self.mode.polarity == Polarity::IdleHigh);
+        if high {
+            self.clk.set_high()
+        } else {
+            self.clk.set_low()
+        }
+        .map_err(|_| Error::Other)
+    }
+    #[inline]
+    fn set_mosi(&mut self, high: bool) -> Result<(), Error> {
+        if high {
+            self.mosi.set_high()
+        } else {
+            self.mosi.set_low()
+        }
+        .map_err(|_| Error::Other)
+    }
+    #[inline]
+    fn sample_miso(&mut self) -> Result<bool, Error> {
+        self.miso.is_high().map_err(|_| Error::Other)
+    }
+    /// Shift out one word while shifting in another.
+    #[inline]
+    fn transfer_word(&mut self, word: u8) -> Result<u8, Error> {
+        let mut ans = 0;
+        for bit in (0..8).rev() {
+            let out = word & (1 << bit) != 0;
+            let sampled = match self.mode.phase {
+                // Data is set up before leading edge and captured on it.
+                Phase::CaptureOnFirstTransition => {
+                    self.set_mosi(out)?;
+                    self.delay.delay_ns(self.half_period_ns);
+                    self.set_clock(true)?;
+                    let sampled = self.sample_miso()?;
+                    self.delay.delay_ns(self.half_period_ns);
+                    self.set_clock(false)?;
+                    sampled
+                }
+                // Data is shifted out on leading edge and captured on trailing edge.
+                Phase::CaptureOnSecondTransition => {
+                    self.set_clock(true)?;
+                    self.set_mosi(out)?;
+                    self.delay.delay_ns(self.half_period_ns);
+                    self.set_clock(false)?;
+                    let sampled = self.sample_miso()?;
+                    self.delay.delay_ns(self.half_period_ns);
+                    sampled
+                }
+            };
+            ans |= (sampled as u8) << bit;
+        }
+        Ok(ans)
+    }
+}
+
+impl<CLK, MOSI, MISO, DELAY> embedded_hal::spi::ErrorType for BitBangSpi<CLK, MOSI, MISO, DELAY> {
+    type Error = Error;
+}
+
+impl<CLK: OutputPin, MOSI: OutputPin, MISO: InputPin, DELAY: DelayNs> embedded_hal::spi::SpiBus
+    for BitBangSpi<CLK, MOSI, MISO, DELAY>
+{
+    #[inline]
+    fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        for word in words {
+            *word = self.transfer_word(0)?;
+        }
+        Ok(())
+    }
+    #[inline]
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        for &word in words {
+            self.transfer_word(word)?;
+        }
+        Ok(())
+    }
+    #[inline]
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
+        for i in 0..read.len().max(write.len()) {
+            let word = self.transfer_word(write.get(i).copied().unwrap_or(0))?;
+            if let Some(slot) = read.get_mut(i) {
+                *slot = word;
+            }
+        }
+        Ok(())
+    }
+    #[inline]
+    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        for word in words {
+            *word = self.transfer_word(*word)?;
+        }
+        Ok(())
+    }
+    #[inline]
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        // Every word is complete on return.
+        Ok(())
+    }
+}
+
+/// Half clock period in nanoseconds, rounded up so the bus never runs too fast.
+#[inline]
+const fn half_period_ns(frequency: Hertz) -> u32 {
+    let frequency = if frequency.0 == 0 { 1 } else { frequency.0 };
+    500_000_000u32.div_ceil(frequency)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{half_period_ns, BitBangSpi};
+    use core::cell::Cell;
+    use core::convert::Infallible;
+    use embedded_hal::digital::{ErrorType, InputPin, OutputPin};
+    use embedded_hal::spi::{SpiBus, MODE_0, MODE_1, MODE_2, MODE_3};
+    use embedded_time::rate::Hertz;
+
+    /// Simulated wires with data output looped back to data input, counting clock edges.
+    struct Wires {
+        clk: Cell<bool>,
+        mosi: Cell<bool>,
+        edges: Cell<usize>,
+    }
+
+    struct Clk<'a>(&'a Wires);
+    struct Mosi<'a>(&'a Wires);
+    struct Miso<'a>(&'a Wires);
+    struct NoDelay;
+
+    impl ErrorType for Clk<'_> {
+        type Error = Infallible;
+    }
+    impl OutputPin for Clk<'_> {
+        fn set_low(&mut self) -> Result<(), Infallible> {
+            if self.0.clk.replace(false) {
+                self.0.edges.set(self.0.edges.get() + 1);
+            }
+            Ok(())
+        }
+        fn set_high(&mut self) -> Result<(), Infallible> {
+            if !self.0.clk.replace(true) {
+                self.0.edges.set(self.0.edges.get() + 1);
+            }
+            Ok(())
+        }
+    }
+    impl ErrorType for Mosi<'_> {
+        type Error = Infallible;
+    }
+    impl OutputPin for Mosi<'_> {
+        fn set_low(&mut self) -> Result<(), Infallible> {
+            self.0.mosi.set(false);
+            Ok(())
+        }
+        fn set_high(&mut self) -> Result<(), Infallible> {
+            self.0.mosi.set(true);
+            Ok(())
+        }
+    }
+    impl ErrorType for Miso<'_> {
+        type Error = Infallible;
+    }
+    impl InputPin for Miso<'_> {
+        // Loop back data output, valid whenever the bus samples.
+        fn is_high(&mut self) -> Result<bool, Infallible> {
+            Ok(self.0.mosi.get())
+        }
+        fn is_low(&mut self) -> Result<bool, Infallible> {
+            Ok(!self.0.mosi.get())
+        }
+    }
+    impl embedded_hal::delay::DelayNs for NoDelay {
+        fn delay_ns(&mut self, _: u32) {}
+    }
+
+    #[test]
+    fn struct_bit_bang_spi_loopback() {
+        for (mode, idle) in [
+            (MODE_0, false),
+            (MODE_1, false),
+            (MODE_2, true),
+            (MODE_3, true),
+        ] {
+            let wires = Wires {
+                clk: Cell::new(!idle),
+                mosi: Cell::new(false),
+                edges: Cell::new(0),
+            };
+            let mut spi = BitBangSpi::new(
+                Clk(&wires),
+                Mosi(&wires),
+                Miso(&wires),
+                NoDelay,
+                mode,
+                Hertz(1_000_000),
+            )
+            .unwrap();
+            assert_eq!(wires.clk.get(), idle);
+            wires.edges.set(0);
+
+            let mut buf = [0xA5, 0x3C, 0x00, 0xFF];
+            spi.transfer_in_place(&mut buf).unwrap();
+            assert_eq!(buf, [0xA5, 0x3C, 0x00, 0xFF]);
+            let mut read = [0; 3];
+            spi.transfer(&mut read, &[0x81, 0x7E]).unwrap();
+            assert_eq!(read, [0x81, 0x7E, 0x00]);
+            // Two clock edges per bit, clock idle after every word.
+            assert_eq!(wires.edges.get(), 7 * 8 * 2);
+            assert_eq!(wires.clk.get(), idle);
+        }
+    }
+
+    #[test]
+    fn function_half_period_ns() {
+        assert_eq!(half_period_ns(Hertz(1_000_000)), 500);
+        assert_eq!(half_period_ns(Hertz(3_000_000)), 167);
+        assert_eq!(half_period_ns(Hertz(0)), 500_000_000);
+    }
+}
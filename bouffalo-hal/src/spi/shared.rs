@@ -0,0 +1,156 @@
+use super::{Error, RegisterBlock, Spi};
+use core::cell::RefCell;
+use core::ops::Deref;
+use embedded_hal::digital::OutputPin;
+use embedded_hal::spi::{Mode, Operation};
+
+/// Per-device bus settings, applied at start of every transaction on a shared bus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeviceConfig {
+    /// Clock polarity and phase.
+    pub mode: Mode,
+    /// Clock half period in SPI source clock cycles, see [`Spi::set_half_period`].
+    pub half_period: u16,
+}
+
+impl Default for DeviceConfig {
+    /// Mode 0 with the clock speed used by [`Spi::new`].
+    #[inline]
+    fn default() -> Self {
+        Self {
+            mode: embedded_hal::spi::MODE_0,
+            half_period: 5,
+        }
+    }
+}
+
+/// Shared bus device for single-threaded use, with its own chip select pin.
+///
+/// The bus is borrowed from a `RefCell` for each transaction. Borrowing the bus while
+/// another device is using it, e.g. from an interrupt handler, panics; use
+/// [`CriticalSectionDevice`] if the bus is shared with interrupt handlers or other harts.
+///
+/// Pads of the shared `Spi` should not contain hardware chip select signal.
+pub struct RefCellDevice<'a, SPI, PADS, const I: usize, CS> {
+    bus: &'a RefCell<Spi<SPI, PADS, I>>,
+    cs: CS,
+    config: DeviceConfig,
+}
+
+impl<'a, SPI, PADS, const I: usize, CS: OutputPin> RefCellDevice<'a, SPI, PADS, I, CS> {
+    /// Creates a device on shared `bus` with chip select pin and bus settings.
+    ///
+    /// Chip select pin is driven high (inactive) on creation.
+    #[inline]
+    pub fn new(
+        bus: &'a RefCell<Spi<SPI, PADS, I>>,
+        mut cs: CS,
+        config: DeviceConfig,
+    ) -> Result<Self, Error> {
+        cs.set_high().map_err(|_| Error::ChipSelect)?;
+        Ok(Self { bus, cs, config })
+    }
+
+    /// Release the device and return its chip select pin.
+    #[inline]
+    pub fn free(self) -> CS {
+        self.cs
+    }
+
+    /// Change bus settings for following transactions of this device.
+    #[inline]
+    pub fn set_config(&mut self, config: DeviceConfig) {
+        self.config = config;
+    }
+}
+
+impl<SPI, PADS, const I: usize, CS> embedded_hal::spi::ErrorType
+    for RefCellDevice<'_, SPI, PADS, I, CS>
+{
+    type Error = Error;
+}
+
+impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize, CS: OutputPin>
+    embedded_hal::spi::SpiDevice for RefCellDevice<'_, SPI, PADS, I, CS>
+{
+    #[inline]
+    fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Self::Error> {
+        let bus = &mut *self.bus.borrow_mut();
+        exclusive_transaction(bus, &mut self.cs, self.config, operations)
+    }
+}
+
+/// Shared bus device guarded by a critical section, with its own chip select pin.
+///
+/// Interrupts are disabled during each transaction, thus the bus can be shared with
+/// interrupt handlers and other harts.
+///
+/// Pads of the shared `Spi` should not contain hardware chip select signal.
+pub struct CriticalSectionDevice<'a, SPI, PADS, const I: usize, CS> {
+    bus: &'a critical_section::Mutex<RefCell<Spi<SPI, PADS, I>>>,
+    cs: CS,
+    config: DeviceConfig,
+}
+
+impl<'a, SPI, PADS, const I: usize, CS: OutputPin> CriticalSectionDevice<'a, SPI, PADS, I, CS> {
+    /// Creates a device on shared `bus` with chip select pin and bus settings.
+    ///
+    /// Chip select pin is driven high (inactive) on creation.
+    #[inline]
+    pub fn new(
+        bus: &'a critical_section::Mutex<RefCell<Spi<SPI, PADS, I>>>,
+        mut cs: CS,
+        config: DeviceConfig,
+    ) -> Result<Self, Error> {
+        cs.set_high().map_err(|_| Error::ChipSelect)?;
+        Ok(Self { bus, cs, config })
+    }
+
+    /// Release the device and return its chip select pin.
+    #[inline]
+    pub fn free(self) -> CS {
+        self.cs
+    }
+
+    /// Change bus settings for following transactions of this device.
+    #[inline]
+    pub fn set_config(&mut self, config: DeviceConfig) {
+        self.config = config;
+    }
+}
+
+impl<SPI, PADS, const I: usize, CS> embedded_hal::spi::ErrorType
+    for CriticalSectionDevice<'_, SPI, PADS, I, CS>
+{
+    type Error = Error;
+}
+
+impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize, CS: OutputPin>
+    embedded_hal::spi::SpiDevice for CriticalSectionDevice<'_, SPI, PADS, I, CS>
+{
+    #[inline]
+    fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Self::Error> {
+        critical_section::with(|cs| {
+            let bus = &mut *self.bus.borrow_ref_mut(cs);
+            exclusive_transaction(bus, &mut self.cs, self.config, operations)
+        })
+    }
+}
+
+/// Reconfigure the bus for a device, then run operations with its chip select asserted.
+#[inline]
+fn exclusive_transaction<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize>(
+    bus: &mut Spi<SPI, PADS, I>,
+    cs: &mut impl OutputPin,
+    config: DeviceConfig,
+    operations: &mut [Operation<'_, u8>],
+) -> Result<(), Error> {
+    bus.set_mode(config.mode);
+    bus.set_half_period(config.half_period);
+    cs.set_low().map_err(|_| Error::ChipSelect)?;
+    let ans = embedded_hal::spi::SpiDevice::transaction(bus, operations);
+    bus.wait_idle();
+    // Always release chip select, so that a failed device does not block the whole bus.
+    let cs_ans = cs.set_high().map_err(|_| Error::ChipSelect);
+    ans.and(cs_ans)
+}
@@ -0,0 +1,282 @@
+use super::{RegisterBlock, Spi};
+use crate::dma::{
+    self, DmaChannel, DmaChannelConfig, DmaMode, LliItem, LliTransfer, Periph4Dma01, TransferError,
+};
+use core::{
+    future::Future,
+    ops::Deref,
+    pin::Pin,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    task::{Context, Poll},
+};
+
+/// Waker registry and completion counter as the state for double-buffered SPI streaming.
+#[derive(Debug)]
+pub struct StreamState {
+    waker: atomic_waker::AtomicWaker,
+    ref_to_dma: AtomicUsize,
+    channel: AtomicUsize,
+    completed: AtomicUsize,
+    error: AtomicBool,
+}
+
+impl StreamState {
+    /// Creates the waker registry for a streaming DMA channel.
+    #[inline]
+    pub const fn new() -> StreamState {
+        StreamState {
+            waker: atomic_waker::AtomicWaker::new(),
+            ref_to_dma: AtomicUsize::new(0),
+            channel: AtomicUsize::new(0),
+            completed: AtomicUsize::new(0),
+            error: AtomicBool::new(false),
+        }
+    }
+    /// Use this waker registry to handle DMA interrupt.
+    ///
+    /// Each finished buffer half is counted, so the streaming task can detect halves
+    /// it failed to refill in time.
+    #[inline]
+    pub fn on_interrupt(&self) {
+        let dma =
+            unsafe { &*(self.ref_to_dma.load(Ordering::Acquire) as *const dma::RegisterBlock) };
+        let mask = 1 << self.channel.load(Ordering::Relaxed);
+        if dma.interrupts.error_state.read() & mask != 0 {
+            unsafe { dma.interrupts.error_clear.write(mask) };
+            self.error.store(true, Ordering::Release);
+            self.waker.wake();
+        }
+        if dma.interrupts.transfer_complete_state.read() & mask != 0 {
+            unsafe { dma.interrupts.transfer_complete_clear.write(mask) };
+            self.completed.fetch_add(1, Ordering::AcqRel);
+            self.waker.wake();
+        }
+    }
+}
+
+impl Default for StreamState {
+    /// Creates an empty waker registry.
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Double-buffered streaming error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamError {
+    /// Linked list could not be built for the buffers.
+    Transfer(TransferError),
+    /// Buffers are empty or differ in length.
+    BufferLength,
+    /// A buffer half was not refilled before DMA reached it again.
+    Underrun,
+    /// DMA channel stopped with a bus error.
+    Dma,
+}
+
+impl From<TransferError> for StreamError {
+    #[inline]
+    fn from(value: TransferError) -> Self {
+        StreamError::Transfer(value)
+    }
+}
+
+/// Continuous SPI transmit from two alternating buffers over a circular DMA linked list.
+///
+/// While DMA sends one buffer, the other is refilled, and the linked list continues into
+/// the next buffer without software involvement, so there is no gap on the bus between
+/// frames. Useful for streaming framebuffer lines to a display.
+pub struct SpiStream<'a, SPI, PADS, const I: usize, DMA> {
+    spi: Spi<SPI, PADS, I>,
+    channel: DmaChannel<DMA>,
+    buffers: [&'a mut [u8]; 2],
+    pool: &'a mut [LliItem],
+    state: &'static StreamState,
+}
+
+impl<
+        'a,
+        SPI: Deref<Target = RegisterBlock>,
+        PADS,
+        const I: usize,
+        DMA: Deref<Target = dma::RegisterBlock>,
+    > SpiStream<'a, SPI, PADS, I, DMA>
+{
+    const DMA_REQUEST: Periph4Dma01 = match I {
+        0 => Periph4Dma01::Spi0Tx,
+        _ => panic!("SPI streaming is only supported on SPI0 with DMA0 or DMA1"),
+    };
+
+    /// Creates a stream from SPI, a DMA channel, two equally sized buffers and a pool of
+    /// linked list items.
+    ///
+    /// The channel is configured for byte-wide transfers into SPI transmit FIFO. Pool
+    /// should hold linked list items for both buffers, i.e. two items for each 4095 bytes.
+    #[inline]
+    pub fn new(
+        spi: Spi<SPI, PADS, I>,
+        mut channel: DmaChannel<DMA>,
+        buffers: [&'a mut [u8]; 2],
+        pool: &'a mut [LliItem],
+        state: &'static StreamState,
+    ) -> Result<Self, StreamError> {
+        if buffers[0].is_empty() || buffers[0].len() != buffers[1].len() {
+            return Err(StreamError::BufferLength);
+        }
+        channel
+            .configure(DmaChannelConfig {
+                direction: DmaMode::Mem2Periph,
+                dst_req: Some(Self::DMA_REQUEST),
+                dst_addr_inc: false,
+                ..Default::default()
+            })
+            .expect("spi stream channel configuration is valid");
+        state.channel.store(channel.id(), Ordering::Relaxed);
+        state.ref_to_dma.store(
+            channel.register_block() as *const _ as usize,
+            Ordering::Release,
+        );
+        Ok(SpiStream {
+            spi,
+            channel,
+            buffers,
+            pool,
+            state,
+        })
+    }
+
+    /// Release the stream and return SPI, DMA channel and buffers.
+    #[inline]
+    pub fn free(self) -> (Spi<SPI, PADS, I>, DmaChannel<DMA>, [&'a mut [u8]; 2]) {
+        (self.spi, self.channel, self.buffers)
+    }
+
+    /// Stream frames until `fill` returns `false`.
+    ///
+    /// `fill` writes the next frame into the given buffer and returns whether more frames
+    /// follow; the frame it wrote when returning `false` is the last one sent. After both
+    /// buffers are filled, each call happens right after DMA finishes a buffer, and should
+    /// complete within the time SPI takes to send one buffer, or the stream stops with
+    /// [`StreamError::Underrun`].
+    pub async fn stream(
+        &mut self,
+        mut fill: impl FnMut(&mut [u8]) -> bool,
+    ) -> Result<(), StreamError> {
+        self.state.completed.store(0, Ordering::Relaxed);
+        self.state.error.store(false, Ordering::Relaxed);
+        let mut last = if !fill(self.buffers[0]) {
+            Some(0)
+        } else if !fill(self.buffers[1]) {
+            Some(1)
+        } else {
+            None
+        };
+        let fifo = &self.spi.spi.fifo_write as *const _ as usize as u32;
+        let transfers = self.buffers.each_ref().map(|buffer| LliTransfer {
+            src_addr: buffer.as_ptr() as usize as u32,
+            dst_addr: fifo,
+            nbytes: buffer.len() as u32,
+        });
+        // Items of one buffer end at `(half + 1) * per_half - 1`.
+        let per_half = if last == Some(0) {
+            self.channel.lli_reload(self.pool, &transfers[..1])?
+        } else {
+            self.channel.lli_reload_ring(self.pool, &transfers)? / 2
+        };
+        if last == Some(1) {
+            self.cut(1, per_half);
+        }
+        unsafe {
+            self.spi.spi.fifo_config_0.modify(|config| {
+                config
+                    .clear_transmit_fifo()
+                    .clear_receive_fifo()
+                    .enable_dma_transmit()
+            });
+            self.spi.spi.config.modify(|config| config.enable_master());
+        }
+        self.channel.start();
+        let mut next = 0;
+        let ans = loop {
+            if let Err(e) = (WaitForCompletion { state: self.state }).await {
+                break Err(e);
+            }
+            let finished = next;
+            next ^= 1;
+            if last == Some(finished) {
+                break Ok(());
+            }
+            if last.is_some() {
+                continue;
+            }
+            if self.state.completed.load(Ordering::Acquire) != 0 {
+                break Err(StreamError::Underrun);
+            }
+            if !fill(self.buffers[finished]) {
+                last = Some(finished);
+                self.cut(finished, per_half);
+            }
+            if self.state.completed.load(Ordering::Acquire) != 0 {
+                break Err(StreamError::Underrun);
+            }
+        };
+        if ans.is_err() {
+            self.channel.stop();
+        }
+        while self.channel.is_busy() {
+            core::hint::spin_loop();
+        }
+        self.spi.wait_idle();
+        unsafe {
+            self.spi
+                .spi
+                .fifo_config_0
+                .modify(|config| config.clear_receive_fifo().disable_dma_transmit());
+            self.spi.spi.config.modify(|config| config.disable_master());
+        }
+        ans
+    }
+
+    /// End linked list after buffer `half`, so DMA stops there.
+    #[inline]
+    fn cut(&mut self, half: usize, per_half: usize) {
+        let item = &mut self.pool[(half + 1) * per_half - 1];
+        unsafe { core::ptr::write_volatile(&mut item.linked_list_item, 0) };
+    }
+}
+
+struct WaitForCompletion<'r> {
+    state: &'r StreamState,
+}
+
+impl WaitForCompletion<'_> {
+    #[inline]
+    fn take(&self) -> Option<Result<(), StreamError>> {
+        if self.state.error.load(Ordering::Acquire) {
+            return Some(Err(StreamError::Dma));
+        }
+        self.state
+            .completed
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |v| v.checked_sub(1))
+            .ok()
+            .map(|_| Ok(()))
+    }
+}
+
+impl Future for WaitForCompletion<'_> {
+    type Output = Result<(), StreamError>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Some(ans) = self.take() {
+            return Poll::Ready(ans);
+        }
+        self.state.waker.register(cx.waker());
+        // Buffer may have finished before the waker was registered.
+        match self.take() {
+            Some(ans) => Poll::Ready(ans),
+            None => Poll::Pending,
+        }
+    }
+}
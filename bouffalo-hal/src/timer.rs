@@ -1,7 +1,705 @@
 //! Timer and watchdog peripheral.
 
+use core::ops::Deref;
+use embedded_time::rate::Hertz;
+use volatile_register::{RO, RW, WO};
+
+mod asynch;
+mod capture;
+mod frequency;
+mod queue;
+mod watchdog;
+pub use asynch::*;
+pub use capture::*;
+pub use frequency::*;
+pub use queue::*;
+pub use watchdog::*;
+
 /// Timer and watchdog peripheral registers.
 #[repr(C)]
 pub struct RegisterBlock {
-    // todo fields
+    /// Clock source configuration.
+    pub timer_config: RW<TimerConfig>,
+    _reserved0: [u8; 0xc],
+    /// Match values of comparators 0 to 2 for each timer.
+    pub match_value: [[RW<u32>; 3]; 2],
+    _reserved1: [u8; 0x4],
+    /// Current counter value of each timer.
+    pub counter_value: [RO<u32>; 2],
+    _reserved2: [u8; 0x4],
+    /// Match status of each timer.
+    pub match_status: [RO<u32>; 2],
+    _reserved3: [u8; 0x4],
+    /// Match interrupt enable of each timer.
+    pub match_interrupt_enable: [RW<u32>; 2],
+    _reserved4: [u8; 0x4],
+    /// Counter preload value of each timer.
+    pub preload_value: [RW<u32>; 2],
+    _reserved5: [u8; 0x4],
+    /// Counter preload trigger source of each timer.
+    pub preload_control: [RW<u32>; 2],
+    /// Watchdog mode configuration.
+    pub watchdog_mode: RW<u32>,
+    /// Watchdog match value.
+    pub watchdog_match: RW<u32>,
+    /// Watchdog counter value.
+    pub watchdog_value: RO<u32>,
+    /// Watchdog reset status.
+    pub watchdog_status: RW<u32>,
+    _reserved6: [u8; 0x4],
+    /// Match interrupt clear of each timer.
+    pub match_interrupt_clear: [WO<u32>; 2],
+    /// Watchdog interrupt clear.
+    pub watchdog_interrupt_clear: WO<u32>,
+    /// Counter enable of each timer.
+    pub counter_enable: RW<CounterEnable>,
+    /// Counter mode of each timer.
+    pub counter_mode: RW<CounterMode>,
+    _reserved7: [u8; 0x4],
+    /// Match interrupt mode of each timer.
+    pub interrupt_mode: [RW<u32>; 2],
+    /// Watchdog counter reset.
+    pub watchdog_counter_reset: WO<u32>,
+    /// Watchdog access keys.
+    pub watchdog_access_key: [WO<u32>; 2],
+    _reserved8: [u8; 0x18],
+    /// Clock divide factor of each timer and the watchdog.
+    pub clock_divide: RW<ClockDivide>,
+    /// GPIO pulse capture configuration, on BL616 and BL808 only.
+    pub gpio_capture: RW<GpioCapture>,
+    /// Counter 0 values latched at start and end of a captured GPIO pulse.
+    pub gpio_latch: [RO<u32>; 2],
+}
+
+/// Clock source configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct TimerConfig(u32);
+
+impl TimerConfig {
+    const CLOCK_SOURCE: [u32; 2] = [0xf, 0xf << 4];
+    const WATCHDOG_CLOCK_SOURCE: u32 = 0xf << 8;
+
+    /// Set clock source of timer.
+    #[inline]
+    pub const fn set_clock_source(self, timer: usize, val: ClockSource) -> Self {
+        Self((self.0 & !Self::CLOCK_SOURCE[timer]) | ((val as u32) << (timer * 4)))
+    }
+    /// Get clock source of timer.
+    #[inline]
+    pub const fn clock_source(self, timer: usize) -> ClockSource {
+        ClockSource::from_bits((self.0 & Self::CLOCK_SOURCE[timer]) >> (timer * 4))
+    }
+    /// Set clock source of watchdog.
+    #[inline]
+    pub const fn set_watchdog_clock_source(self, val: ClockSource) -> Self {
+        Self((self.0 & !Self::WATCHDOG_CLOCK_SOURCE) | ((val as u32) << 8))
+    }
+    /// Get clock source of watchdog.
+    #[inline]
+    pub const fn watchdog_clock_source(self) -> ClockSource {
+        ClockSource::from_bits((self.0 & Self::WATCHDOG_CLOCK_SOURCE) >> 8)
+    }
+}
+
+/// Timer and watchdog clock source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum ClockSource {
+    /// Bus clock.
+    Bclk = 0,
+    /// 32 kHz clock.
+    F32k = 1,
+    /// 1 kHz clock.
+    F1k = 2,
+    /// Crystal oscillator clock.
+    Xtal = 3,
+    /// External clock from GPIO pad.
+    Gpio = 4,
+    /// No clock.
+    None = 5,
+}
+
+impl ClockSource {
+    #[inline]
+    const fn from_bits(val: u32) -> Self {
+        match val {
+            0 => ClockSource::Bclk,
+            1 => ClockSource::F32k,
+            2 => ClockSource::F1k,
+            3 => ClockSource::Xtal,
+            4 => ClockSource::Gpio,
+            5 => ClockSource::None,
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Counter enable register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct CounterEnable(u32);
+
+impl CounterEnable {
+    const ENABLE: [u32; 2] = [1 << 1, 1 << 2];
+
+    /// Enable counter of timer.
+    #[inline]
+    pub const fn enable(self, timer: usize) -> Self {
+        Self(self.0 | Self::ENABLE[timer])
+    }
+    /// Disable counter of timer.
+    #[inline]
+    pub const fn disable(self, timer: usize) -> Self {
+        Self(self.0 & !Self::ENABLE[timer])
+    }
+    /// Check if counter of timer is enabled.
+    #[inline]
+    pub const fn is_enabled(self, timer: usize) -> bool {
+        self.0 & Self::ENABLE[timer] != 0
+    }
+}
+
+/// Counter mode register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct CounterMode(u32);
+
+impl CounterMode {
+    const FREE_RUN: [u32; 2] = [1 << 1, 1 << 2];
+
+    /// Set counter mode of timer.
+    #[inline]
+    pub const fn set_mode(self, timer: usize, val: Mode) -> Self {
+        match val {
+            Mode::Preload => Self(self.0 & !Self::FREE_RUN[timer]),
+            Mode::FreeRun => Self(self.0 | Self::FREE_RUN[timer]),
+        }
+    }
+    /// Get counter mode of timer.
+    #[inline]
+    pub const fn mode(self, timer: usize) -> Mode {
+        match self.0 & Self::FREE_RUN[timer] {
+            0 => Mode::Preload,
+            _ => Mode::FreeRun,
+        }
+    }
+}
+
+/// Timer counter mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Counter restarts from preload value on preload trigger.
+    Preload,
+    /// Counter runs freely and wraps around.
+    FreeRun,
+}
+
+/// Clock divide factor register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct ClockDivide(u32);
+
+impl ClockDivide {
+    const DIVIDE: [u32; 2] = [0xff << 8, 0xff << 16];
+    const WATCHDOG_DIVIDE: u32 = 0xff << 24;
+
+    /// Set clock divide factor of timer.
+    ///
+    /// Counter clock is source clock divided by `val + 1`.
+    #[inline]
+    pub const fn set_divide(self, timer: usize, val: u8) -> Self {
+        Self((self.0 & !Self::DIVIDE[timer]) | ((val as u32) << (8 + timer * 8)))
+    }
+    /// Get clock divide factor of timer.
+    #[inline]
+    pub const fn divide(self, timer: usize) -> u8 {
+        ((self.0 & Self::DIVIDE[timer]) >> (8 + timer * 8)) as u8
+    }
+    /// Set clock divide factor of watchdog.
+    #[inline]
+    pub const fn set_watchdog_divide(self, val: u8) -> Self {
+        Self((self.0 & !Self::WATCHDOG_DIVIDE) | ((val as u32) << 24))
+    }
+    /// Get clock divide factor of watchdog.
+    #[inline]
+    pub const fn watchdog_divide(self) -> u8 {
+        ((self.0 & Self::WATCHDOG_DIVIDE) >> 24) as u8
+    }
+}
+
+/// GPIO pulse capture configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct GpioCapture(u32);
+
+impl GpioCapture {
+    const ENABLE: u32 = 1 << 1;
+    const INVERT: [u32; 2] = [1 << 5, 1 << 6];
+    const LATCH_READY: u32 = 1 << 31;
+
+    /// Enable capture of GPIO pulses by counter 0.
+    #[inline]
+    pub const fn enable_capture(self) -> Self {
+        Self(self.0 | Self::ENABLE)
+    }
+    /// Disable capture of GPIO pulses by counter 0.
+    #[inline]
+    pub const fn disable_capture(self) -> Self {
+        Self(self.0 & !Self::ENABLE)
+    }
+    /// Check if capture of GPIO pulses is enabled.
+    #[inline]
+    pub const fn is_capture_enabled(self) -> bool {
+        self.0 & Self::ENABLE != 0
+    }
+    /// Invert GPIO input of timer, so that pulses start on falling edge.
+    #[inline]
+    pub const fn enable_invert(self, timer: usize) -> Self {
+        Self(self.0 | Self::INVERT[timer])
+    }
+    /// Use GPIO input of timer as is, so that pulses start on rising edge.
+    #[inline]
+    pub const fn disable_invert(self, timer: usize) -> Self {
+        Self(self.0 & !Self::INVERT[timer])
+    }
+    /// Check if GPIO input of timer is inverted.
+    #[inline]
+    pub const fn is_invert_enabled(self, timer: usize) -> bool {
+        self.0 & Self::INVERT[timer] != 0
+    }
+    /// Check if both ends of a pulse are latched.
+    #[inline]
+    pub const fn is_latch_ready(self) -> bool {
+        self.0 & Self::LATCH_READY != 0
+    }
+}
+
+/// Managed timer peripheral with both of its counters.
+pub struct Timers<TIMER> {
+    /// Counter 0.
+    pub timer0: Timer<TIMER, 0>,
+    /// Counter 1.
+    pub timer1: Timer<TIMER, 1>,
+}
+
+impl<TIMER: Deref<Target = RegisterBlock>> Timers<TIMER> {
+    /// Create a timer instance and split it into counters.
+    #[inline]
+    pub fn new(timer: TIMER) -> Self {
+        Timers {
+            timer0: Timer {
+                timer: unsafe { core::ptr::read(&timer as *const _) },
+            },
+            timer1: Timer { timer },
+        }
+    }
+}
+
+/// Counter `I` of a timer peripheral.
+pub struct Timer<TIMER, const I: usize> {
+    timer: TIMER,
+}
+
+impl<TIMER: Deref<Target = RegisterBlock>, const I: usize> Timer<TIMER, I> {
+    /// Get current counter value.
+    #[inline]
+    pub fn counter(&self) -> u32 {
+        self.timer.counter_value[I].read()
+    }
+    /// Configure this counter to expire every `interval` ticks of `source` divided by `divide + 1`.
+    ///
+    /// Comparator 0 matches at the end of each period and reloads the counter from zero,
+    /// so periods do not drift with interrupt latency. Match interrupt of comparator 0
+    /// is enabled; the interrupt of this timer should be routed to handler by the caller.
+    ///
+    /// # Panics
+    ///
+    /// If `interval` is zero.
+    #[inline]
+    pub fn into_periodic(
+        self,
+        source: ClockSource,
+        divide: u8,
+        interval: u32,
+    ) -> Periodic<TIMER, I> {
+        assert!(interval != 0, "periodic interval should not be zero");
+        let timer = self.timer;
+        unsafe {
+            timer.counter_enable.modify(|v| v.disable(I));
+            timer.timer_config.modify(|v| v.set_clock_source(I, source));
+            timer.clock_divide.modify(|v| v.set_divide(I, divide));
+            timer.counter_mode.modify(|v| v.set_mode(I, Mode::Preload));
+            timer.preload_value[I].write(0);
+            timer.preload_control[I].write(PRELOAD_ON_MATCH_0);
+            // Counter restarts from preload value on the tick after match.
+            timer.match_value[I][0].write(interval - 1);
+            timer.match_interrupt_clear[I].write(MATCH_0);
+            timer.match_interrupt_enable[I].write(MATCH_0);
+            timer.counter_enable.modify(|v| v.enable(I));
+        }
+        Periodic { timer }
+    }
+    /// Configure this counter to free run on `source` divided by `divide + 1` for time measurement.
+    ///
+    /// `source_hz` is the frequency of `source`, used to convert ticks into milliseconds
+    /// and microseconds. The 32-bit counter wraps, so an interval measured by the stopwatch
+    /// should not exceed `u32::MAX` ticks, e.g. about 107 seconds on a 40 MHz crystal.
+    #[inline]
+    pub fn into_stopwatch(
+        self,
+        source: ClockSource,
+        divide: u8,
+        source_hz: Hertz,
+    ) -> Stopwatch<TIMER, I> {
+        let timer = self.timer;
+        unsafe {
+            timer.counter_enable.modify(|v| v.disable(I));
+            timer.timer_config.modify(|v| v.set_clock_source(I, source));
+            timer.clock_divide.modify(|v| v.set_divide(I, divide));
+            timer.counter_mode.modify(|v| v.set_mode(I, Mode::FreeRun));
+            timer.counter_enable.modify(|v| v.enable(I));
+        }
+        Stopwatch {
+            timer,
+            tick_hz: source_hz.0 / (divide as u32 + 1),
+        }
+    }
+}
+
+/// Preload trigger source value selecting comparator 0.
+const PRELOAD_ON_MATCH_0: u32 = 1;
+/// Comparator 0 bit in match status, interrupt enable and clear registers.
+const MATCH_0: u32 = 1 << 0;
+
+/// Counter `I` of a timer peripheral expiring periodically.
+pub struct Periodic<TIMER, const I: usize> {
+    timer: TIMER,
+}
+
+impl<TIMER: Deref<Target = RegisterBlock>, const I: usize> Periodic<TIMER, I> {
+    /// Check if a period has expired since last check and clear the match state.
+    ///
+    /// Returns `WouldBlock` if current period has not yet expired.
+    #[inline]
+    pub fn wait(&mut self) -> nb::Result<(), core::convert::Infallible> {
+        if self.timer.match_status[I].read() & MATCH_0 == 0 {
+            return Err(nb::Error::WouldBlock);
+        }
+        unsafe { self.timer.match_interrupt_clear[I].write(MATCH_0) };
+        Ok(())
+    }
+    /// Stop the counter, disable its match interrupt and return the timer counter.
+    #[inline]
+    pub fn free(self) -> Timer<TIMER, I> {
+        unsafe {
+            self.timer.counter_enable.modify(|v| v.disable(I));
+            self.timer.match_interrupt_enable[I].write(0);
+            self.timer.match_interrupt_clear[I].write(MATCH_0);
+        }
+        Timer { timer: self.timer }
+    }
+}
+
+/// Counter `I` of a timer peripheral free running for time measurement.
+pub struct Stopwatch<TIMER, const I: usize> {
+    timer: TIMER,
+    tick_hz: u32,
+}
+
+impl<TIMER: Deref<Target = RegisterBlock>, const I: usize> Stopwatch<TIMER, I> {
+    /// Get current counter value in ticks, as the start of a measured interval.
+    #[inline]
+    pub fn now(&self) -> u32 {
+        self.timer.counter_value[I].read()
+    }
+    /// Get ticks elapsed since `start`, handling counter wrap around.
+    #[inline]
+    pub fn elapsed_ticks(&self, start: u32) -> u32 {
+        self.now().wrapping_sub(start)
+    }
+    /// Get milliseconds elapsed since `start`.
+    #[inline]
+    pub fn elapsed_ms(&self, start: u32) -> u32 {
+        ticks_to_units(self.elapsed_ticks(start), self.tick_hz, 1_000)
+    }
+    /// Get microseconds elapsed since `start`.
+    #[inline]
+    pub fn elapsed_us(&self, start: u32) -> u32 {
+        ticks_to_units(self.elapsed_ticks(start), self.tick_hz, 1_000_000)
+    }
+    /// Get counter frequency after clock divide.
+    #[inline]
+    pub fn tick_rate(&self) -> Hertz {
+        Hertz(self.tick_hz)
+    }
+    /// Stop the counter and return the timer counter.
+    #[inline]
+    pub fn free(self) -> Timer<TIMER, I> {
+        unsafe { self.timer.counter_enable.modify(|v| v.disable(I)) };
+        Timer { timer: self.timer }
+    }
+}
+
+/// Convert `ticks` of a `tick_hz` counter into time units of `units_per_second`, saturating.
+#[inline]
+const fn ticks_to_units(ticks: u32, tick_hz: u32, units_per_second: u32) -> u32 {
+    let units = ticks as u64 * units_per_second as u64 / tick_hz as u64;
+    if units > u32::MAX as u64 {
+        u32::MAX
+    } else {
+        units as u32
+    }
+}
+
+impl<TIMER: Deref<Target = RegisterBlock>> Timer<TIMER, 0> {
+    /// Link counter 0 and counter 1 of the same timer into a cascaded counter on `source`.
+    ///
+    /// Hardware offers no overflow clock between counters, so the cascade runs both
+    /// counters on `source`: `low` undivided and `high` divided by 256. They are started
+    /// in one register write so `high` counts every 256 `low` ticks, and the combined
+    /// counter is rebuilt from `high` and the lowest 8 bits of `low`.
+    ///
+    /// Pairing constraints: only counter 0 and counter 1 of one timer peripheral can be
+    /// cascaded, and both clock divide factors are taken by the cascade. The combined
+    /// counter has a range of 40 bits, which is about 7.6 hours on a 40 MHz crystal.
+    #[inline]
+    pub fn cascade(
+        low: Timer<TIMER, 0>,
+        high: Timer<TIMER, 1>,
+        source: ClockSource,
+    ) -> Cascade<TIMER> {
+        let timer = low.timer;
+        core::mem::forget(high);
+        unsafe {
+            timer.counter_enable.modify(|v| v.disable(0).disable(1));
+            timer
+                .timer_config
+                .modify(|v| v.set_clock_source(0, source).set_clock_source(1, source));
+            timer
+                .clock_divide
+                .modify(|v| v.set_divide(0, 0).set_divide(1, 0xff));
+            timer
+                .counter_mode
+                .modify(|v| v.set_mode(0, Mode::FreeRun).set_mode(1, Mode::FreeRun));
+            timer.counter_enable.modify(|v| v.enable(0).enable(1));
+        }
+        Cascade {
+            timer,
+            start: 0,
+            interval: None,
+        }
+    }
+}
+
+/// Number of bits in cascaded counter value.
+pub const CASCADE_BITS: u32 = 40;
+
+const CASCADE_MASK: u64 = (1 << CASCADE_BITS) - 1;
+
+/// Two timer counters cascaded into one long counter.
+pub struct Cascade<TIMER> {
+    timer: TIMER,
+    start: u64,
+    interval: Option<(u64, bool)>,
+}
+
+impl<TIMER: Deref<Target = RegisterBlock>> Cascade<TIMER> {
+    /// Get combined counter value in source clock ticks, wrapping at 40 bits.
+    #[inline]
+    pub fn counter(&self) -> u64 {
+        loop {
+            let high = self.timer.counter_value[1].read();
+            let low = self.timer.counter_value[0].read();
+            if self.timer.counter_value[1].read() == high {
+                return combine(high, low);
+            }
+        }
+    }
+    /// Start a one-shot interval of `ticks` source clock ticks.
+    ///
+    /// `ticks` is limited to the 40-bit counter range.
+    #[inline]
+    pub fn start_one_shot(&mut self, ticks: u64) {
+        self.start = self.counter();
+        self.interval = Some((ticks.min(CASCADE_MASK), false));
+    }
+    /// Start a periodic interval of `ticks` source clock ticks.
+    ///
+    /// Each period starts exactly at the end of the previous one, so it does not drift
+    /// with polling delay. `ticks` is limited to the 40-bit counter range.
+    #[inline]
+    pub fn start_periodic(&mut self, ticks: u64) {
+        self.start = self.counter();
+        self.interval = Some((ticks.min(CASCADE_MASK), true));
+    }
+    /// Check if current interval has expired.
+    ///
+    /// Returns `WouldBlock` if no interval is running or it has not yet expired.
+    /// A one-shot interval stops after expiring, while a periodic one restarts.
+    #[inline]
+    pub fn wait(&mut self) -> nb::Result<(), core::convert::Infallible> {
+        let Some((ticks, periodic)) = self.interval else {
+            return Err(nb::Error::WouldBlock);
+        };
+        if self.counter().wrapping_sub(self.start) & CASCADE_MASK < ticks {
+            return Err(nb::Error::WouldBlock);
+        }
+        if periodic {
+            self.start = (self.start + ticks) & CASCADE_MASK;
+        } else {
+            self.interval = None;
+        }
+        Ok(())
+    }
+    /// Stop both counters and return timer counters.
+    #[inline]
+    pub fn free(self) -> (Timer<TIMER, 0>, Timer<TIMER, 1>) {
+        unsafe {
+            self.timer
+                .counter_enable
+                .modify(|v| v.disable(0).disable(1))
+        };
+        let Timers { timer0, timer1 } = Timers::new(self.timer);
+        (timer0, timer1)
+    }
+}
+
+/// Combine cascaded counter values read at the same high counter value.
+#[inline]
+const fn combine(high: u32, low: u32) -> u64 {
+    ((high as u64) << 8) | (low & 0xff) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        combine, ticks_to_units, ClockDivide, ClockSource, CounterEnable, CounterMode, GpioCapture,
+        Mode, RegisterBlock, TimerConfig,
+    };
+    use memoffset::offset_of;
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, timer_config), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, match_value), 0x10);
+        assert_eq!(offset_of!(RegisterBlock, counter_value), 0x2c);
+        assert_eq!(offset_of!(RegisterBlock, match_status), 0x38);
+        assert_eq!(offset_of!(RegisterBlock, match_interrupt_enable), 0x44);
+        assert_eq!(offset_of!(RegisterBlock, preload_value), 0x50);
+        assert_eq!(offset_of!(RegisterBlock, preload_control), 0x5c);
+        assert_eq!(offset_of!(RegisterBlock, watchdog_mode), 0x64);
+        assert_eq!(offset_of!(RegisterBlock, watchdog_match), 0x68);
+        assert_eq!(offset_of!(RegisterBlock, watchdog_value), 0x6c);
+        assert_eq!(offset_of!(RegisterBlock, watchdog_status), 0x70);
+        assert_eq!(offset_of!(RegisterBlock, match_interrupt_clear), 0x78);
+        assert_eq!(offset_of!(RegisterBlock, watchdog_interrupt_clear), 0x80);
+        assert_eq!(offset_of!(RegisterBlock, counter_enable), 0x84);
+        assert_eq!(offset_of!(RegisterBlock, counter_mode), 0x88);
+        assert_eq!(offset_of!(RegisterBlock, interrupt_mode), 0x90);
+        assert_eq!(offset_of!(RegisterBlock, watchdog_counter_reset), 0x98);
+        assert_eq!(offset_of!(RegisterBlock, watchdog_access_key), 0x9c);
+        assert_eq!(offset_of!(RegisterBlock, clock_divide), 0xbc);
+        assert_eq!(offset_of!(RegisterBlock, gpio_capture), 0xc0);
+        assert_eq!(offset_of!(RegisterBlock, gpio_latch), 0xc4);
+    }
+
+    #[test]
+    fn struct_timer_config_functions() {
+        let mut val = TimerConfig(0x0);
+
+        val = val.set_clock_source(0, ClockSource::None);
+        assert_eq!(val.0, 0x0000_0005);
+        assert_eq!(val.clock_source(0), ClockSource::None);
+        val = val.set_clock_source(1, ClockSource::Xtal);
+        assert_eq!(val.0, 0x0000_0035);
+        assert_eq!(val.clock_source(1), ClockSource::Xtal);
+        val = val.set_watchdog_clock_source(ClockSource::F1k);
+        assert_eq!(val.0, 0x0000_0235);
+        assert_eq!(val.watchdog_clock_source(), ClockSource::F1k);
+        val = val.set_clock_source(0, ClockSource::Bclk);
+        assert_eq!(val.0, 0x0000_0230);
+        assert_eq!(val.clock_source(0), ClockSource::Bclk);
+    }
+
+    #[test]
+    fn struct_counter_enable_functions() {
+        let mut val = CounterEnable(0x0);
+
+        val = val.enable(0);
+        assert_eq!(val.0, 0x0000_0002);
+        assert!(val.is_enabled(0));
+        assert!(!val.is_enabled(1));
+        val = val.enable(1);
+        assert_eq!(val.0, 0x0000_0006);
+        val = val.disable(0);
+        assert_eq!(val.0, 0x0000_0004);
+        assert!(!val.is_enabled(0));
+        assert!(val.is_enabled(1));
+    }
+
+    #[test]
+    fn struct_counter_mode_functions() {
+        let mut val = CounterMode(0x0);
+
+        val = val.set_mode(1, Mode::FreeRun);
+        assert_eq!(val.0, 0x0000_0004);
+        assert_eq!(val.mode(1), Mode::FreeRun);
+        assert_eq!(val.mode(0), Mode::Preload);
+        val = val.set_mode(1, Mode::Preload);
+        assert_eq!(val.0, 0x0000_0000);
+    }
+
+    #[test]
+    fn struct_clock_divide_functions() {
+        let mut val = ClockDivide(0x0);
+
+        val = val.set_divide(0, 0xff);
+        assert_eq!(val.0, 0x0000_ff00);
+        assert_eq!(val.divide(0), 0xff);
+        val = val.set_divide(1, 0x12);
+        assert_eq!(val.0, 0x0012_ff00);
+        assert_eq!(val.divide(1), 0x12);
+        val = val.set_watchdog_divide(0x34);
+        assert_eq!(val.0, 0x3412_ff00);
+        assert_eq!(val.watchdog_divide(), 0x34);
+    }
+
+    #[test]
+    fn struct_gpio_capture_functions() {
+        let mut val = GpioCapture(0x0);
+
+        val = val.enable_capture();
+        assert_eq!(val.0, 0x0000_0002);
+        assert!(val.is_capture_enabled());
+        val = val.enable_invert(0);
+        assert_eq!(val.0, 0x0000_0022);
+        assert!(val.is_invert_enabled(0));
+        assert!(!val.is_invert_enabled(1));
+        val = val.enable_invert(1);
+        assert_eq!(val.0, 0x0000_0062);
+        val = val.disable_invert(0).disable_capture();
+        assert_eq!(val.0, 0x0000_0040);
+        assert!(!val.is_capture_enabled());
+        assert!(!val.is_latch_ready());
+        assert!(GpioCapture(0x8000_0000).is_latch_ready());
+    }
+
+    #[test]
+    fn function_combine() {
+        assert_eq!(combine(0, 0), 0);
+        assert_eq!(combine(0, 0xff), 0xff);
+        assert_eq!(combine(1, 0x100), 0x100);
+        assert_eq!(combine(0x1234_5678, 0x9abc_de9a), 0x12_3456_789a);
+        assert_eq!(combine(u32::MAX, u32::MAX), 0xff_ffff_ffff);
+    }
+
+    #[test]
+    fn function_ticks_to_units() {
+        assert_eq!(ticks_to_units(40_000, 40_000_000, 1_000), 1);
+        assert_eq!(ticks_to_units(39_999, 40_000_000, 1_000), 0);
+        assert_eq!(ticks_to_units(40_000, 40_000_000, 1_000_000), 1_000);
+        assert_eq!(ticks_to_units(u32::MAX, 40_000_000, 1_000), 107_374);
+        assert_eq!(ticks_to_units(u32::MAX, 1, 1_000_000), u32::MAX);
+    }
 }
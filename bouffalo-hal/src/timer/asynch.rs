@@ -0,0 +1,122 @@
+use super::{Periodic, RegisterBlock, MATCH_0};
+use core::{
+    future::Future,
+    ops::Deref,
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::{Context, Poll},
+};
+
+/// Waker registry and tick counter as the state for an async/await periodic timer.
+#[derive(Debug)]
+pub struct TickState {
+    tick: atomic_waker::AtomicWaker,
+    ref_to_timer: AtomicUsize,
+    counter: AtomicUsize,
+    pending: AtomicUsize,
+}
+
+impl TickState {
+    /// Creates the waker registry for a periodic timer counter.
+    #[inline]
+    pub const fn new() -> TickState {
+        TickState {
+            tick: atomic_waker::AtomicWaker::new(),
+            ref_to_timer: AtomicUsize::new(0),
+            counter: AtomicUsize::new(0),
+            pending: AtomicUsize::new(0),
+        }
+    }
+    /// Use this waker registry to handle interrupt.
+    ///
+    /// Each expired period is counted, so ticks are not lost if the waiting task is
+    /// polled late.
+    #[inline]
+    pub fn on_interrupt(&self) {
+        let timer =
+            unsafe { &*(self.ref_to_timer.load(Ordering::Acquire) as *const RegisterBlock) };
+        let counter = self.counter.load(Ordering::Relaxed);
+        if timer.match_status[counter].read() & MATCH_0 != 0 {
+            unsafe { timer.match_interrupt_clear[counter].write(MATCH_0) };
+            self.pending.fetch_add(1, Ordering::AcqRel);
+            self.tick.wake();
+        }
+    }
+}
+
+impl Default for TickState {
+    /// Creates an empty waker registry.
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Managed async/await periodic timer counter.
+pub struct AsyncPeriodic<TIMER, const I: usize> {
+    periodic: Periodic<TIMER, I>,
+    state: &'static TickState,
+}
+
+impl<TIMER: Deref<Target = RegisterBlock>, const I: usize> AsyncPeriodic<TIMER, I> {
+    /// Creates the async/await periodic timer from a periodic counter and a waker registry.
+    #[inline]
+    pub fn new(periodic: Periodic<TIMER, I>, state: &'static TickState) -> Self {
+        state.pending.store(0, Ordering::Relaxed);
+        state.counter.store(I, Ordering::Relaxed);
+        state
+            .ref_to_timer
+            .store(&*periodic.timer as *const _ as usize, Ordering::Release);
+        AsyncPeriodic { periodic, state }
+    }
+
+    /// Release async/await timer and return the periodic counter.
+    #[inline]
+    pub fn free(self) -> Periodic<TIMER, I> {
+        self.periodic
+    }
+
+    /// Wait until next period expires.
+    ///
+    /// Returns immediately if periods have expired since last call, one at a time.
+    #[inline]
+    pub async fn wait_tick(&mut self) {
+        WaitForTick {
+            pending: &self.state.pending,
+            registry: &self.state.tick,
+        }
+        .await
+    }
+}
+
+struct WaitForTick<'r> {
+    pending: &'r AtomicUsize,
+    registry: &'r atomic_waker::AtomicWaker,
+}
+
+impl WaitForTick<'_> {
+    #[inline]
+    fn take(&self) -> bool {
+        self.pending
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |v| v.checked_sub(1))
+            .is_ok()
+    }
+}
+
+impl Future for WaitForTick<'_> {
+    type Output = ();
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.take() {
+            return Poll::Ready(());
+        }
+        self.registry.register(cx.waker());
+        // Tick may have arrived before the waker was registered.
+        if self.take() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use super::{ClockSource, Mode, RegisterBlock, Timer};
+use crate::gpio::Input;
+use core::{future::poll_fn, ops::Deref, task::Poll};
+use embedded_time::rate::Hertz;
+
+/// Edge of input signal starting a captured pulse.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CaptureEdge {
+    /// Pulse starts on rising edge and ends on the following falling edge.
+    Rising,
+    /// Pulse starts on falling edge and ends on the following rising edge.
+    Falling,
+}
+
+/// Pulse measured by a capture channel, in counter ticks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Capture {
+    /// Pulse width from its starting edge to its ending edge.
+    pub width: u32,
+    /// Distance between starting edges of this pulse and the previous captured one.
+    ///
+    /// `None` on the first capture after creating or restarting the channel.
+    pub period: Option<u32>,
+}
+
+/// Counter 0 of a timer capturing pulse width and period of a GPIO input signal.
+///
+/// Counter 0 free runs, and the hardware latches its value at the starting edge and the
+/// ending edge of a pulse on the GPIO clock source of timer, which should be routed to
+/// `pin` in GLB. Each read re-arms the latch, so reading captures in a loop measures a
+/// PWM input continuously, duty cycle being `width / period`.
+///
+/// Both widths and periods are computed with wrapping arithmetic, so a counter overflow
+/// between two latches is handled; intervals longer than `u32::MAX` ticks cannot be told
+/// apart, choose clock source and divide accordingly. A period is only meaningful if the
+/// previous capture was read before the next starting edge, otherwise pulses in between
+/// are missed and the period spans several input periods.
+pub struct CaptureChannel<'a, TIMER, const N: usize, M> {
+    timer: TIMER,
+    pin: Input<'a, N, M>,
+    tick_hz: u32,
+    last_start: Option<u32>,
+}
+
+impl<TIMER: Deref<Target = RegisterBlock>> Timer<TIMER, 0> {
+    /// Configure counter 0 to capture pulses on `pin` starting at `edge`.
+    ///
+    /// Counter runs on `source` divided by `divide + 1`; `source_hz` is the frequency of
+    /// `source`, used to report tick rate. Only available on BL616 and BL808, whose timer
+    /// latches counter 0 on GPIO edges.
+    #[inline]
+    pub fn into_capture<'a, const N: usize, M>(
+        self,
+        pin: Input<'a, N, M>,
+        edge: CaptureEdge,
+        source: ClockSource,
+        divide: u8,
+        source_hz: Hertz,
+    ) -> CaptureChannel<'a, TIMER, N, M> {
+        let timer = self.timer;
+        unsafe {
+            timer.counter_enable.modify(|v| v.disable(0));
+            timer.timer_config.modify(|v| v.set_clock_source(0, source));
+            timer.clock_divide.modify(|v| v.set_divide(0, divide));
+            timer.counter_mode.modify(|v| v.set_mode(0, Mode::FreeRun));
+            timer.gpio_capture.modify(|v| {
+                let v = v.disable_capture();
+                match edge {
+                    CaptureEdge::Rising => v.disable_invert(0),
+                    CaptureEdge::Falling => v.enable_invert(0),
+                }
+            });
+            timer.counter_enable.modify(|v| v.enable(0));
+            timer.gpio_capture.modify(|v| v.enable_capture());
+        }
+        CaptureChannel {
+            timer,
+            pin,
+            tick_hz: source_hz.0 / (divide as u32 + 1),
+            last_start: None,
+        }
+    }
+}
+
+impl<'a, TIMER: Deref<Target = RegisterBlock>, const N: usize, M> CaptureChannel<'a, TIMER, N, M> {
+    /// Read the latest captured pulse and re-arm the latch for the next one.
+    #[inline]
+    pub fn read_capture(&mut self) -> nb::Result<Capture, core::convert::Infallible> {
+        if !self.timer.gpio_capture.read().is_latch_ready() {
+            return Err(nb::Error::WouldBlock);
+        }
+        let start = self.timer.gpio_latch[0].read();
+        let end = self.timer.gpio_latch[1].read();
+        self.rearm();
+        let ans = capture_from(start, end, self.last_start);
+        self.last_start = Some(start);
+        Ok(ans)
+    }
+    /// Wait for the next captured pulse and re-arm the latch for the one after.
+    ///
+    /// The latch raises no interrupt, so the returned future wakes itself to be polled
+    /// again until a pulse is captured; it suits executors that idle between polls.
+    #[inline]
+    pub async fn wait_capture(&mut self) -> Capture {
+        poll_fn(|cx| match self.read_capture() {
+            Ok(capture) => Poll::Ready(capture),
+            Err(nb::Error::WouldBlock) => {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        })
+        .await
+    }
+    /// Discard the pending latch and forget the previous pulse.
+    ///
+    /// The next capture then reports no period, e.g. after the input signal has changed.
+    #[inline]
+    pub fn restart(&mut self) {
+        self.rearm();
+        self.last_start = None;
+    }
+    /// Get counter tick rate.
+    #[inline]
+    pub fn tick_rate(&self) -> Hertz {
+        Hertz(self.tick_hz)
+    }
+    /// Disable capture, stop the counter and return the input pin and timer counter.
+    #[inline]
+    pub fn free(self) -> (Input<'a, N, M>, Timer<TIMER, 0>) {
+        unsafe {
+            self.timer.gpio_capture.modify(|v| v.disable_capture());
+            self.timer.counter_enable.modify(|v| v.disable(0));
+        }
+        (self.pin, Timer { timer: self.timer })
+    }
+
+    #[inline]
+    fn rearm(&mut self) {
+        unsafe {
+            self.timer.gpio_capture.modify(|v| v.disable_capture());
+            self.timer.gpio_capture.modify(|v| v.enable_capture());
+        }
+    }
+}
+
+/// Compute pulse width and period from latched counter values.
+#[inline]
+const fn capture_from(start: u32, end: u32, last_start: Option<u32>) -> Capture {
+    Capture {
+        width: end.wrapping_sub(start),
+        period: match last_start {
+            Some(last) => Some(start.wrapping_sub(last)),
+            None => None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{capture_from, Capture};
+
+    #[test]
+    fn function_capture_from() {
+        assert_eq!(
+            capture_from(1_000, 1_250, None),
+            Capture {
+                width: 250,
+                period: None
+            }
+        );
+        assert_eq!(
+            capture_from(2_000, 2_250, Some(1_000)),
+            Capture {
+                width: 250,
+                period: Some(1_000)
+            }
+        );
+        // Counter overflows within the pulse, and between starting edges.
+        assert_eq!(
+            capture_from(0xffff_ff00, 0x0000_0100, Some(0xffff_fe00)),
+            Capture {
+                width: 0x200,
+                period: Some(0x100)
+            }
+        );
+        assert_eq!(
+            capture_from(0x0000_0080, 0x0000_0100, Some(0xffff_ff80)),
+            Capture {
+                width: 0x80,
+                period: Some(0x100)
+            }
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use super::{ClockSource, Mode, RegisterBlock, Timer, Timers, MATCH_0, PRELOAD_ON_MATCH_0};
+use crate::gpio::Input;
+use core::ops::Deref;
+use embedded_time::rate::Hertz;
+
+/// Frequency counter measuring an input signal by counting its edges over a gate time.
+///
+/// Counter 0 of the timer runs on a known clock source as the gate, restarting exactly at
+/// the end of each gate period. Counter 1 is clocked by the GPIO pad and counts rising
+/// edges of the input signal; its value is sampled at gate boundaries. The GPIO clock
+/// source of timer should be routed to `pin` in GLB before measuring.
+///
+/// Resolution is one edge per gate time, e.g. 10 Hz with a 100 ms gate, so a longer gate
+/// suits low frequencies. Input frequency should not exceed half of the timer module
+/// clock, as pad signal is synchronized before reaching the counter.
+pub struct FrequencyCounter<'a, TIMER, const N: usize, M> {
+    timer: TIMER,
+    pin: Input<'a, N, M>,
+    tick_hz: u32,
+    gate_ticks: u32,
+}
+
+impl<'a, TIMER: Deref<Target = RegisterBlock>, const N: usize, M>
+    FrequencyCounter<'a, TIMER, N, M>
+{
+    /// Create a frequency counter on `pin` with a gate time of `gate_ms` milliseconds.
+    ///
+    /// `source` clocks the gate and `source_hz` is its frequency. Gate clock is divided
+    /// as little as possible while the gate still fits in 32-bit counter.
+    ///
+    /// # Panics
+    ///
+    /// If `gate_ms` is zero, or too long for `source` even with the largest clock divide.
+    #[inline]
+    pub fn new(
+        pin: Input<'a, N, M>,
+        gate: Timer<TIMER, 0>,
+        counter: Timer<TIMER, 1>,
+        source: ClockSource,
+        source_hz: Hertz,
+        gate_ms: u32,
+    ) -> Self {
+        let Some((divide, gate_ticks)) = gate_divide_and_ticks(source_hz.0, gate_ms) else {
+            panic!("impossible gate time");
+        };
+        let timer = gate.timer;
+        core::mem::forget(counter);
+        unsafe {
+            timer.counter_enable.modify(|v| v.disable(0).disable(1));
+            timer.timer_config.modify(|v| {
+                v.set_clock_source(0, source)
+                    .set_clock_source(1, ClockSource::Gpio)
+            });
+            timer
+                .clock_divide
+                .modify(|v| v.set_divide(0, divide).set_divide(1, 0));
+            timer
+                .counter_mode
+                .modify(|v| v.set_mode(0, Mode::Preload).set_mode(1, Mode::FreeRun));
+            timer.preload_value[0].write(0);
+            timer.preload_control[0].write(PRELOAD_ON_MATCH_0);
+            // Counter restarts from preload value on the tick after match.
+            timer.match_value[0][0].write(gate_ticks - 1);
+            timer.match_interrupt_clear[0].write(MATCH_0);
+            timer.counter_enable.modify(|v| v.enable(0).enable(1));
+        }
+        FrequencyCounter {
+            timer,
+            pin,
+            tick_hz: source_hz.0 / (divide as u32 + 1),
+            gate_ticks,
+        }
+    }
+    /// Measure input frequency, blocking for one to two gate times.
+    ///
+    /// It waits for the next gate boundary, then counts input edges until the one after.
+    #[inline]
+    pub fn measure(&mut self) -> Hertz {
+        self.wait_gate();
+        let start = self.timer.counter_value[1].read();
+        self.wait_gate();
+        let edges = self.timer.counter_value[1].read().wrapping_sub(start);
+        Hertz(edges_to_hertz(edges, self.tick_hz, self.gate_ticks))
+    }
+    /// Get gate time in gate clock ticks.
+    #[inline]
+    pub fn gate_ticks(&self) -> u32 {
+        self.gate_ticks
+    }
+    /// Stop both counters and return the input pin and timer counters.
+    #[inline]
+    pub fn free(self) -> (Input<'a, N, M>, Timer<TIMER, 0>, Timer<TIMER, 1>) {
+        unsafe {
+            self.timer
+                .counter_enable
+                .modify(|v| v.disable(0).disable(1));
+            self.timer.match_interrupt_clear[0].write(MATCH_0);
+        }
+        let Timers { timer0, timer1 } = Timers::new(self.timer);
+        (self.pin, timer0, timer1)
+    }
+
+    #[inline]
+    fn wait_gate(&self) {
+        unsafe { self.timer.match_interrupt_clear[0].write(MATCH_0) };
+        while self.timer.match_status[0].read() & MATCH_0 == 0 {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+/// Compute the smallest clock divide and gate ticks for `gate_ms` on a `source_hz` clock.
+#[inline]
+const fn gate_divide_and_ticks(source_hz: u32, gate_ms: u32) -> Option<(u8, u32)> {
+    let cycles = source_hz as u64 * gate_ms as u64 / 1_000;
+    if cycles == 0 {
+        return None;
+    }
+    let divide = cycles.div_ceil(u32::MAX as u64);
+    if divide > 256 {
+        return None;
+    }
+    Some(((divide - 1) as u8, (cycles / divide) as u32))
+}
+
+/// Convert `edges` counted over `gate_ticks` of a `tick_hz` clock into hertz, rounded.
+#[inline]
+const fn edges_to_hertz(edges: u32, tick_hz: u32, gate_ticks: u32) -> u32 {
+    let gate_ticks = gate_ticks as u64;
+    let hz = (edges as u64 * tick_hz as u64 + gate_ticks / 2) / gate_ticks;
+    if hz > u32::MAX as u64 {
+        u32::MAX
+    } else {
+        hz as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{edges_to_hertz, gate_divide_and_ticks};
+
+    #[test]
+    fn function_gate_divide_and_ticks() {
+        assert_eq!(gate_divide_and_ticks(40_000_000, 100), Some((0, 4_000_000)));
+        assert_eq!(
+            gate_divide_and_ticks(40_000_000, 1_000),
+            Some((0, 40_000_000))
+        );
+        assert_eq!(
+            gate_divide_and_ticks(40_000_000, 200_000),
+            Some((1, 4_000_000_000))
+        );
+        assert_eq!(gate_divide_and_ticks(32_768, 1), Some((0, 32)));
+        assert_eq!(gate_divide_and_ticks(1_000, 0), None);
+        assert_eq!(gate_divide_and_ticks(999, 1), None);
+        assert_eq!(gate_divide_and_ticks(u32::MAX, u32::MAX), None);
+    }
+
+    #[test]
+    fn function_edges_to_hertz() {
+        assert_eq!(edges_to_hertz(1_000, 40_000_000, 4_000_000), 10_000);
+        assert_eq!(edges_to_hertz(1_000_000, 20_000_000, 4_000_000_000), 5_000);
+        assert_eq!(edges_to_hertz(0, 40_000_000, 4_000_000), 0);
+        assert_eq!(edges_to_hertz(u32::MAX, u32::MAX, 1), u32::MAX);
+    }
+}
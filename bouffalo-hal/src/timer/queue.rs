@@ -0,0 +1,334 @@
+use super::{RegisterBlock, Stopwatch, MATCH_0};
+use core::{
+    cell::RefCell,
+    future::Future,
+    ops::Deref,
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::{Context, Poll, Waker},
+};
+use critical_section::Mutex;
+
+/// Error returned when every alarm slot of a timer queue is occupied.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QueueFull;
+
+/// Deadline and waker registry as the state for a timer queue of `N` alarms.
+///
+/// Deadlines are kept sorted; comparator 0 of the counter is always programmed to the
+/// nearest one, and its waker is woken once the counter passes it.
+#[derive(Debug)]
+pub struct TimerQueue<const N: usize> {
+    alarms: Mutex<RefCell<Alarms<N>>>,
+    ref_to_timer: AtomicUsize,
+    counter: AtomicUsize,
+}
+
+impl<const N: usize> TimerQueue<N> {
+    /// Creates an empty timer queue.
+    #[inline]
+    pub const fn new() -> TimerQueue<N> {
+        TimerQueue {
+            alarms: Mutex::new(RefCell::new(Alarms::new())),
+            ref_to_timer: AtomicUsize::new(0),
+            counter: AtomicUsize::new(0),
+        }
+    }
+    /// Use this timer queue to handle interrupt.
+    ///
+    /// Wakes every alarm whose deadline has passed, then programs the comparator
+    /// to the nearest remaining deadline.
+    #[inline]
+    pub fn on_interrupt(&self) {
+        let timer = self.ref_to_timer.load(Ordering::Acquire) as *const RegisterBlock;
+        if timer.is_null() {
+            return;
+        }
+        let timer = unsafe { &*timer };
+        let counter = self.counter.load(Ordering::Relaxed);
+        if timer.match_status[counter].read() & MATCH_0 == 0 {
+            return;
+        }
+        unsafe { timer.match_interrupt_clear[counter].write(MATCH_0) };
+        self.schedule(timer, counter);
+    }
+
+    /// Wake expired alarms and program comparator 0 to the nearest deadline.
+    ///
+    /// Comparator matches only on equality, so a deadline passed while it was being
+    /// programmed is handled here instead of waiting for the counter to wrap around.
+    #[inline]
+    fn schedule(&self, timer: &RegisterBlock, counter: usize) {
+        loop {
+            let now = timer.counter_value[counter].read();
+            // Wake outside critical section, a waker may run arbitrary code.
+            let expired =
+                critical_section::with(|cs| self.alarms.borrow_ref_mut(cs).pop_expired(now));
+            if let Some(waker) = expired {
+                waker.wake();
+                continue;
+            }
+            let next = critical_section::with(|cs| {
+                let next = self.alarms.borrow_ref(cs).next();
+                unsafe {
+                    match next {
+                        Some(deadline) => {
+                            timer.match_value[counter][0].write(deadline);
+                            timer.match_interrupt_enable[counter].write(MATCH_0);
+                        }
+                        None => timer.match_interrupt_enable[counter].write(0),
+                    }
+                }
+                next
+            });
+            match next {
+                Some(deadline) if is_expired(deadline, timer.counter_value[counter].read()) => {}
+                _ => return,
+            }
+        }
+    }
+}
+
+impl<const N: usize> Default for TimerQueue<N> {
+    /// Creates an empty timer queue.
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Managed timer counter waking tasks at multiple deadlines.
+///
+/// Counter free runs as a [`Stopwatch`]; deadlines are absolute counter values in ticks,
+/// e.g. [`now`](Self::now) plus a delay. A deadline should be less than `i32::MAX` ticks
+/// ahead of current counter value, otherwise it's regarded as already passed.
+pub struct AlarmTimer<TIMER, const I: usize, const N: usize> {
+    stopwatch: Stopwatch<TIMER, I>,
+    state: &'static TimerQueue<N>,
+}
+
+impl<TIMER: Deref<Target = RegisterBlock>, const I: usize, const N: usize> AlarmTimer<TIMER, I, N> {
+    /// Creates the alarm timer from a free running counter and a timer queue.
+    ///
+    /// `state` should be a `static` shared with the interrupt handler of this timer;
+    /// alarms left from a previous use are discarded without waking.
+    #[inline]
+    pub fn new(stopwatch: Stopwatch<TIMER, I>, state: &'static TimerQueue<N>) -> Self {
+        let timer = &*stopwatch.timer;
+        unsafe {
+            // Counter should not restart on comparator match.
+            timer.preload_control[I].write(0);
+            timer.match_interrupt_enable[I].write(0);
+            timer.match_interrupt_clear[I].write(MATCH_0);
+        }
+        critical_section::with(|cs| state.alarms.borrow_ref_mut(cs).clear());
+        state.counter.store(I, Ordering::Relaxed);
+        state
+            .ref_to_timer
+            .store(timer as *const _ as usize, Ordering::Release);
+        AlarmTimer { stopwatch, state }
+    }
+
+    /// Get current counter value in ticks.
+    #[inline]
+    pub fn now(&self) -> u32 {
+        self.stopwatch.now()
+    }
+
+    /// Wake `waker` once the counter reaches `deadline`.
+    ///
+    /// Registering the same waker for the same deadline again only replaces the waker.
+    /// If `deadline` has already passed, `waker` is woken at once.
+    #[inline]
+    pub fn register(&self, deadline: u32, waker: &Waker) -> Result<(), QueueFull> {
+        register(self.state, &self.stopwatch.timer, I, deadline, waker)
+    }
+
+    /// Wait until the counter reaches `deadline`.
+    #[inline]
+    pub fn at(&self, deadline: u32) -> Alarm<'_, TIMER, I, N> {
+        Alarm {
+            timer: self,
+            deadline,
+        }
+    }
+
+    /// Wait for `ticks` counter ticks from now.
+    #[inline]
+    pub fn after(&self, ticks: u32) -> Alarm<'_, TIMER, I, N> {
+        self.at(self.now().wrapping_add(ticks))
+    }
+
+    /// Release alarm timer and return the free running counter.
+    ///
+    /// Pending alarms are discarded without waking.
+    #[inline]
+    pub fn free(self) -> Stopwatch<TIMER, I> {
+        self.state.ref_to_timer.store(0, Ordering::Release);
+        unsafe {
+            self.stopwatch.timer.match_interrupt_enable[I].write(0);
+            self.stopwatch.timer.match_interrupt_clear[I].write(MATCH_0);
+        }
+        critical_section::with(|cs| self.state.alarms.borrow_ref_mut(cs).clear());
+        self.stopwatch
+    }
+}
+
+#[inline]
+fn register<const N: usize>(
+    state: &TimerQueue<N>,
+    timer: &RegisterBlock,
+    counter: usize,
+    deadline: u32,
+    waker: &Waker,
+) -> Result<(), QueueFull> {
+    let now = timer.counter_value[counter].read();
+    if is_expired(deadline, now) {
+        waker.wake_by_ref();
+        return Ok(());
+    }
+    critical_section::with(|cs| state.alarms.borrow_ref_mut(cs).insert(now, deadline, waker))?;
+    state.schedule(timer, counter);
+    Ok(())
+}
+
+/// Future completing once counter of an alarm timer reaches a deadline.
+///
+/// If the timer queue is full, the alarm keeps waking itself and polls the counter
+/// until an alarm slot is available.
+pub struct Alarm<'a, TIMER, const I: usize, const N: usize> {
+    timer: &'a AlarmTimer<TIMER, I, N>,
+    deadline: u32,
+}
+
+impl<TIMER: Deref<Target = RegisterBlock>, const I: usize, const N: usize> Future
+    for Alarm<'_, TIMER, I, N>
+{
+    type Output = ();
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if is_expired(self.deadline, self.timer.now()) {
+            return Poll::Ready(());
+        }
+        if self.timer.register(self.deadline, cx.waker()).is_err() {
+            cx.waker().wake_by_ref();
+        }
+        Poll::Pending
+    }
+}
+
+/// Check if `deadline` has passed at counter value `now`, handling counter wrap around.
+#[inline]
+const fn is_expired(deadline: u32, now: u32) -> bool {
+    (now.wrapping_sub(deadline) as i32) >= 0
+}
+
+/// Deadlines and wakers sorted by nearest deadline first.
+#[derive(Debug)]
+struct Alarms<const N: usize> {
+    entries: heapless::Vec<(u32, Waker), N>,
+}
+
+impl<const N: usize> Alarms<N> {
+    #[inline]
+    const fn new() -> Self {
+        Alarms {
+            entries: heapless::Vec::new(),
+        }
+    }
+    /// Insert `waker` at `deadline`, or replace the waker of an equal registration.
+    #[inline]
+    fn insert(&mut self, now: u32, deadline: u32, waker: &Waker) -> Result<(), QueueFull> {
+        if let Some((_, registered)) = self
+            .entries
+            .iter_mut()
+            .find(|(d, w)| *d == deadline && w.will_wake(waker))
+        {
+            registered.clone_from(waker);
+            return Ok(());
+        }
+        // Order by distance from now, as absolute values wrap around.
+        let distance = deadline.wrapping_sub(now);
+        let index = self
+            .entries
+            .iter()
+            .position(|(d, _)| d.wrapping_sub(now) > distance)
+            .unwrap_or(self.entries.len());
+        self.entries
+            .insert(index, (deadline, waker.clone()))
+            .map_err(|_| QueueFull)
+    }
+    /// Remove and return the waker of nearest deadline if it has passed at `now`.
+    #[inline]
+    fn pop_expired(&mut self, now: u32) -> Option<Waker> {
+        match self.entries.first() {
+            Some(&(deadline, _)) if is_expired(deadline, now) => Some(self.entries.remove(0).1),
+            _ => None,
+        }
+    }
+    /// Get nearest deadline.
+    #[inline]
+    fn next(&self) -> Option<u32> {
+        self.entries.first().map(|&(deadline, _)| deadline)
+    }
+    #[inline]
+    fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_expired, Alarms, QueueFull};
+    use core::task::{RawWaker, RawWakerVTable, Waker};
+
+    static VTABLE: RawWakerVTable =
+        RawWakerVTable::new(|data| RawWaker::new(data, &VTABLE), |_| {}, |_| {}, |_| {});
+
+    fn test_waker() -> Waker {
+        unsafe { Waker::from_raw(RawWaker::new(core::ptr::null(), &VTABLE)) }
+    }
+
+    #[test]
+    fn function_is_expired() {
+        assert!(is_expired(100, 100));
+        assert!(is_expired(100, 101));
+        assert!(!is_expired(100, 99));
+        assert!(!is_expired(5, u32::MAX - 5));
+        assert!(is_expired(u32::MAX - 5, 5));
+    }
+
+    #[test]
+    fn struct_alarms_sorted() {
+        let waker = &test_waker();
+        let mut alarms = Alarms::<4>::new();
+        assert_eq!(alarms.next(), None);
+        let now = u32::MAX - 10;
+        assert_eq!(alarms.insert(now, 20, waker), Ok(()));
+        assert_eq!(alarms.insert(now, u32::MAX - 2, waker), Ok(()));
+        assert_eq!(alarms.insert(now, 5, waker), Ok(()));
+        // Same waker at same deadline takes no extra slot.
+        assert_eq!(alarms.insert(now, 5, waker), Ok(()));
+        assert_eq!(alarms.entries.len(), 3);
+        assert_eq!(alarms.next(), Some(u32::MAX - 2));
+
+        assert!(alarms.pop_expired(u32::MAX - 3).is_none());
+        assert!(alarms.pop_expired(6).is_some());
+        assert!(alarms.pop_expired(6).is_some());
+        assert!(alarms.pop_expired(6).is_none());
+        assert_eq!(alarms.next(), Some(20));
+        alarms.clear();
+        assert_eq!(alarms.next(), None);
+    }
+
+    #[test]
+    fn struct_alarms_full() {
+        let waker = &test_waker();
+        let mut alarms = Alarms::<2>::new();
+        assert_eq!(alarms.insert(0, 10, waker), Ok(()));
+        assert_eq!(alarms.insert(0, 20, waker), Ok(()));
+        assert_eq!(alarms.insert(0, 30, waker), Err(QueueFull));
+        assert_eq!(alarms.insert(0, 10, waker), Ok(()));
+    }
+}
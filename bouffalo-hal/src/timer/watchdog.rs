@@ -0,0 +1,222 @@
+use super::{ClockSource, RegisterBlock};
+use crate::clocks::Clocks;
+use core::{ops::Deref, time::Duration};
+
+/// Watchdog enable bit in mode register.
+const WATCHDOG_ENABLE: u32 = 1 << 0;
+/// Mode register bit to reset the chip on timeout, rather than raising interrupt.
+const WATCHDOG_RESET: u32 = 1 << 1;
+/// Status bit set if the last reset was caused by watchdog.
+const RESET_STATUS: u32 = 1 << 0;
+/// Bit to restart watchdog counter from zero.
+const COUNTER_RESET: u32 = 1 << 0;
+/// Keys unlocking the next write to a watchdog register.
+const ACCESS_KEYS: [u32; 2] = [0xBABA, 0xEB10];
+/// Frequency of 32 kHz clock source.
+const F32K_HZ: u32 = 32_768;
+
+/// Hardware watchdog resetting the chip if not fed in time.
+///
+/// The 16-bit watchdog counter runs on crystal oscillator, or on the 32 kHz clock for
+/// long timeouts, divided by up to 256; the most precise setting is chosen for each
+/// timeout, which ranges up to about 8.5 minutes. Watchdog registers are unlocked with
+/// access keys before every write, so a stray write doesn't stop the watchdog.
+///
+/// The watchdog shares its peripheral with timer counters. To use both, pass the
+/// register block reference, e.g. `&*p.timer0`, to the watchdog and to [`Timers::new`](super::Timers::new).
+pub struct Watchdog<TIMER> {
+    timer: TIMER,
+    xtal_hz: u32,
+}
+
+impl<TIMER: Deref<Target = RegisterBlock>> Watchdog<TIMER> {
+    /// Create a watchdog with `timeout`, leaving it disabled until [`enable`](Self::enable).
+    ///
+    /// # Panics
+    ///
+    /// If `timeout` is shorter than one crystal cycle, or longer than the watchdog counts.
+    #[inline]
+    pub fn new(timer: TIMER, timeout: Duration, clocks: &Clocks) -> Self {
+        let mut ans = Watchdog {
+            timer,
+            xtal_hz: clocks.xclk().0,
+        };
+        ans.disable();
+        ans.set_timeout(timeout);
+        ans
+    }
+    /// Change timeout, restarting the watchdog counter.
+    ///
+    /// # Panics
+    ///
+    /// If `timeout` is shorter than one crystal cycle, or longer than the watchdog counts.
+    #[inline]
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        let timeout_us = timeout.as_micros().min(u64::MAX as u128) as u64;
+        let Some((source, divide, ticks)) = watchdog_timing(timeout_us, self.xtal_hz) else {
+            panic!("impossible watchdog timeout");
+        };
+        unsafe {
+            self.timer
+                .timer_config
+                .modify(|v| v.set_watchdog_clock_source(source));
+            self.timer
+                .clock_divide
+                .modify(|v| v.set_watchdog_divide(divide));
+            self.unlock();
+            self.timer.watchdog_match.write(ticks as u32);
+        }
+        self.feed();
+    }
+    /// Restart the watchdog counter, postponing the next timeout.
+    #[inline]
+    pub fn feed(&mut self) {
+        unsafe {
+            self.unlock();
+            self.timer.watchdog_counter_reset.write(COUNTER_RESET);
+        }
+    }
+    /// Start the watchdog, resetting the chip if not fed within timeout.
+    #[inline]
+    pub fn enable(&mut self) {
+        self.feed();
+        unsafe {
+            self.unlock();
+            self.timer
+                .watchdog_mode
+                .write(WATCHDOG_ENABLE | WATCHDOG_RESET);
+        }
+    }
+    /// Stop the watchdog.
+    #[inline]
+    pub fn disable(&mut self) {
+        unsafe {
+            self.unlock();
+            self.timer.watchdog_mode.write(0);
+        }
+    }
+    /// Check if the watchdog is running.
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.timer.watchdog_mode.read() & WATCHDOG_ENABLE != 0
+    }
+    /// Check if the last chip reset was caused by this watchdog.
+    ///
+    /// The flag survives the watchdog reset itself, so firmware can tell it on next boot;
+    /// clear it with [`clear_reset_status`](Self::clear_reset_status) once handled.
+    #[inline]
+    pub fn is_reset_by_watchdog(&self) -> bool {
+        self.timer.watchdog_status.read() & RESET_STATUS != 0
+    }
+    /// Clear watchdog reset flag.
+    #[inline]
+    pub fn clear_reset_status(&mut self) {
+        unsafe {
+            self.unlock();
+            self.timer.watchdog_status.write(0);
+        }
+    }
+    /// Stop the watchdog and release the timer peripheral.
+    #[inline]
+    pub fn free(mut self) -> TIMER {
+        self.disable();
+        self.timer
+    }
+
+    /// Unlock the next write to a watchdog register.
+    #[inline]
+    unsafe fn unlock(&self) {
+        self.timer.watchdog_access_key[0].write(ACCESS_KEYS[0]);
+        self.timer.watchdog_access_key[1].write(ACCESS_KEYS[1]);
+    }
+}
+
+#[cfg(feature = "embedded-hal-027-watchdog")]
+impl<TIMER: Deref<Target = RegisterBlock>> embedded_hal_027::watchdog::Watchdog
+    for Watchdog<TIMER>
+{
+    #[inline]
+    fn feed(&mut self) {
+        Watchdog::feed(self)
+    }
+}
+
+#[cfg(feature = "embedded-hal-027-watchdog")]
+impl<TIMER: Deref<Target = RegisterBlock>> embedded_hal_027::watchdog::WatchdogEnable
+    for Watchdog<TIMER>
+{
+    type Time = Duration;
+
+    #[inline]
+    fn start<T: Into<Duration>>(&mut self, period: T) {
+        self.set_timeout(period.into());
+        self.enable();
+    }
+}
+
+#[cfg(feature = "embedded-hal-027-watchdog")]
+impl<TIMER: Deref<Target = RegisterBlock>> embedded_hal_027::watchdog::WatchdogDisable
+    for Watchdog<TIMER>
+{
+    #[inline]
+    fn disable(&mut self) {
+        Watchdog::disable(self)
+    }
+}
+
+/// Compute clock source, clock divide and match ticks for `timeout_us` microseconds.
+///
+/// Crystal oscillator is preferred for precision, and the smallest divide is chosen.
+#[inline]
+const fn watchdog_timing(timeout_us: u64, xtal_hz: u32) -> Option<(ClockSource, u8, u16)> {
+    let sources = [(ClockSource::Xtal, xtal_hz), (ClockSource::F32k, F32K_HZ)];
+    let mut i = 0;
+    while i < sources.len() {
+        let (source, hz) = sources[i];
+        // Product overflow means far too many cycles for this source.
+        let cycles = match (hz as u64).checked_mul(timeout_us) {
+            Some(product) => product / 1_000_000,
+            None => 0,
+        };
+        if cycles != 0 {
+            let divide = cycles.div_ceil(u16::MAX as u64);
+            if divide <= 256 {
+                return Some((source, (divide - 1) as u8, (cycles / divide) as u16));
+            }
+        }
+        i += 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{watchdog_timing, ClockSource};
+
+    #[test]
+    fn function_watchdog_timing() {
+        assert_eq!(
+            watchdog_timing(1_000, 40_000_000),
+            Some((ClockSource::Xtal, 0, 40_000))
+        );
+        assert_eq!(
+            watchdog_timing(100_000, 40_000_000),
+            Some((ClockSource::Xtal, 61, 64_516))
+        );
+        assert_eq!(
+            watchdog_timing(1_000_000, 40_000_000),
+            Some((ClockSource::F32k, 0, 32_768))
+        );
+        assert_eq!(
+            watchdog_timing(10_000_000, 40_000_000),
+            Some((ClockSource::F32k, 5, 54_613))
+        );
+        assert_eq!(
+            watchdog_timing(500_000_000, 40_000_000),
+            Some((ClockSource::F32k, 250, 65_274))
+        );
+        assert_eq!(watchdog_timing(512_000_000, 40_000_000), None);
+        assert_eq!(watchdog_timing(0, 40_000_000), None);
+        assert_eq!(watchdog_timing(u64::MAX, 40_000_000), None);
+    }
+}
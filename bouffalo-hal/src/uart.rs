@@ -16,6 +16,12 @@ mod blocking;
 pub use blocking::*;
 mod asynch;
 pub use asynch::*;
+mod multidrop;
+pub use multidrop::*;
+mod rs485;
+pub use rs485::*;
+mod buffered;
+pub use buffered::*;
 
 /// Extend constructor to owned UART register blocks.
 pub trait UartExt<PADS>: Sized {
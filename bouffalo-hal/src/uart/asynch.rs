@@ -1,5 +1,6 @@
 use super::{
     uart_config, Config, ConfigError, Error, Interrupt, InterruptClear, Pads, RegisterBlock,
+    FIFO_DEPTH,
 };
 use crate::clocks::Clocks;
 use core::{
@@ -11,11 +12,17 @@ use core::{
 };
 
 /// Managed async/await serial peripheral.
+///
+/// Besides `Read`, the serial implements `embedded_io_async::BufRead` over a receive
+/// buffer as deep as the receive FIFO. Each refill takes every byte received so far, so
+/// line-based protocols can scan for delimiters in the returned slice instead of reading
+/// byte by byte.
 #[derive(Debug)]
 pub struct AsyncSerial<UART, PADS> {
     uart: UART,
     pads: PADS,
     state: &'static SerialState,
+    receive: ReceiveBuffer,
 }
 
 impl<UART: Deref<Target = RegisterBlock>, PADS> AsyncSerial<UART, PADS> {
@@ -49,14 +56,198 @@ impl<UART: Deref<Target = RegisterBlock>, PADS> AsyncSerial<UART, PADS> {
             .ref_to_serial
             .store(&*uart as *const _ as usize, Ordering::Release);
 
-        Ok(AsyncSerial { uart, pads, state })
+        Ok(AsyncSerial {
+            uart,
+            pads,
+            state,
+            receive: ReceiveBuffer::new(),
+        })
     }
 
     /// Release serial instance and return its peripheral and pads.
+    ///
+    /// Bytes received into the buffer but not yet consumed are discarded.
     #[inline]
     pub fn free(self) -> (UART, PADS) {
         (self.uart, self.pads)
     }
+
+    /// Set number of received bytes to raise receive interrupt, clamped to `1..=32`.
+    ///
+    /// See [`AsyncReceiveHalf::set_rx_fifo_threshold`].
+    #[inline]
+    pub fn set_rx_fifo_threshold(&mut self, n: u8) {
+        set_rx_fifo_threshold(&self.uart, n)
+    }
+
+    /// Set number of empty transmit FIFO slots to raise transmit interrupt, clamped to `1..=32`.
+    ///
+    /// See [`AsyncTransmitHalf::set_tx_fifo_threshold`].
+    #[inline]
+    pub fn set_tx_fifo_threshold(&mut self, n: u8) {
+        set_tx_fifo_threshold(&self.uart, n)
+    }
+
+    /// Split serial instance into transmit and receive halves.
+    ///
+    /// Both halves share the waker registry `state`, in which transmit and receive wakers are
+    /// separate; the interrupt handler calling [`SerialState::on_interrupt`] serves both.
+    /// Halves update interrupt enable register in critical sections, so they can be moved to
+    /// different tasks or harts as long as the `critical-section` implementation in use covers
+    /// all harts involved. Pads stay with the transmit half, and bytes buffered but not yet
+    /// consumed move to the receive half.
+    #[inline]
+    pub fn split(self) -> (AsyncTransmitHalf<UART, PADS>, AsyncReceiveHalf<UART>) {
+        (
+            AsyncTransmitHalf {
+                uart: unsafe { core::ptr::read_volatile(&self.uart) },
+                _pads: self.pads,
+                state: self.state,
+            },
+            AsyncReceiveHalf {
+                uart: self.uart,
+                state: self.state,
+                receive: self.receive,
+            },
+        )
+    }
+}
+
+/// Transmit half from splitted async/await serial structure.
+pub struct AsyncTransmitHalf<UART, PADS> {
+    uart: UART,
+    _pads: PADS,
+    state: &'static SerialState,
+}
+
+/// Receive half from splitted async/await serial structure.
+///
+/// Implements `embedded_io_async::BufRead` like [`AsyncSerial`].
+pub struct AsyncReceiveHalf<UART> {
+    uart: UART,
+    state: &'static SerialState,
+    receive: ReceiveBuffer,
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS> AsyncTransmitHalf<UART, PADS> {
+    /// Set number of empty transmit FIFO slots to raise transmit interrupt, clamped to `1..=32`.
+    ///
+    /// A pending write wakes once at least `n` bytes fit into the FIFO. Higher values
+    /// move more bytes per interrupt for bulk transfers, at the cost of the FIFO running
+    /// closer to empty before refilled. Default is one.
+    #[inline]
+    pub fn set_tx_fifo_threshold(&mut self, n: u8) {
+        set_tx_fifo_threshold(&self.uart, n)
+    }
+}
+
+impl<UART: Deref<Target = RegisterBlock>> AsyncReceiveHalf<UART> {
+    /// Set number of received bytes to raise receive interrupt, clamped to `1..=32`.
+    ///
+    /// A pending read wakes once `n` bytes are received, or when the line idles after
+    /// fewer bytes as signalled by receive timeout. Lower values reduce latency for
+    /// interactive use; higher values reduce interrupt load for bulk transfers. Default is one.
+    #[inline]
+    pub fn set_rx_fifo_threshold(&mut self, n: u8) {
+        set_rx_fifo_threshold(&self.uart, n)
+    }
+}
+
+/// Register value of a FIFO threshold raising interrupt at `n` bytes, clamped to FIFO depth.
+///
+/// Hardware raises FIFO ready interrupt when byte count exceeds the register value.
+#[inline]
+const fn fifo_threshold(n: u8) -> u8 {
+    let n = if n == 0 {
+        1
+    } else if n > FIFO_DEPTH {
+        FIFO_DEPTH
+    } else {
+        n
+    };
+    n - 1
+}
+
+/// Bytes taken from receive FIFO but not yet consumed by the reader.
+#[derive(Debug)]
+struct ReceiveBuffer {
+    buf: [u8; FIFO_DEPTH as usize],
+    pos: usize,
+    len: usize,
+}
+
+impl ReceiveBuffer {
+    #[inline]
+    const fn new() -> Self {
+        Self {
+            buf: [0; FIFO_DEPTH as usize],
+            pos: 0,
+            len: 0,
+        }
+    }
+    /// Bytes not yet consumed.
+    #[inline]
+    fn remaining(&self) -> &[u8] {
+        &self.buf[self.pos..self.len]
+    }
+    /// Mark `amt` bytes as consumed, clamped to remaining bytes.
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.pos = core::cmp::min(self.pos + amt, self.len);
+    }
+    /// Copy remaining bytes into `buf` and consume them, returning the number copied.
+    #[inline]
+    fn read_into(&mut self, buf: &mut [u8]) -> usize {
+        let len = core::cmp::min(self.len - self.pos, buf.len());
+        buf[..len].copy_from_slice(&self.buf[self.pos..self.pos + len]);
+        self.pos += len;
+        len
+    }
+    /// Read from serial into the buffer if it is empty, and return remaining bytes.
+    #[inline]
+    async fn fill(
+        &mut self,
+        uart: &RegisterBlock,
+        registry: &atomic_waker::AtomicWaker,
+    ) -> Result<&[u8], Error> {
+        if self.pos == self.len {
+            self.len = uart_read_async(uart, &mut self.buf, registry).await?;
+            self.pos = 0;
+        }
+        Ok(self.remaining())
+    }
+    /// Read into `buf` from buffered bytes first, otherwise directly from serial.
+    #[inline]
+    async fn read(
+        &mut self,
+        uart: &RegisterBlock,
+        buf: &mut [u8],
+        registry: &atomic_waker::AtomicWaker,
+    ) -> Result<usize, Error> {
+        if self.pos != self.len {
+            Ok(self.read_into(buf))
+        } else {
+            uart_read_async(uart, buf, registry).await
+        }
+    }
+}
+
+#[inline]
+fn set_rx_fifo_threshold(uart: &RegisterBlock, n: u8) {
+    // FIFO configuration register is shared by transmit and receive halves.
+    critical_section::with(|_| unsafe {
+        uart.fifo_config_1
+            .modify(|v| v.set_receive_threshold(fifo_threshold(n)))
+    });
+}
+
+#[inline]
+fn set_tx_fifo_threshold(uart: &RegisterBlock, n: u8) {
+    // FIFO configuration register is shared by transmit and receive halves.
+    critical_section::with(|_| unsafe {
+        uart.fifo_config_1
+            .modify(|v| v.set_transmit_threshold(fifo_threshold(n)))
+    });
 }
 
 /// Set of wakers as the state for an async/await serial peripheral.
@@ -78,11 +269,23 @@ impl SerialState {
         }
     }
     /// Use this waker set to handle interrupt.
+    ///
+    /// FIFO ready interrupts follow thresholds set by `set_rx_fifo_threshold` and
+    /// `set_tx_fifo_threshold`. Receive timeout also wakes a pending read, so bytes fewer
+    /// than receive threshold are delivered once the line idles; it is disabled here and
+    /// cleared by the read that consumes it.
     #[inline]
     pub fn on_interrupt(&self) {
         let uart =
             unsafe { &*(self.ref_to_serial.load(Ordering::Acquire) as *const RegisterBlock) };
         let state = uart.interrupt_state.read();
+        if state.has_interrupt(Interrupt::ReceiveTimeout) {
+            unsafe {
+                uart.interrupt_enable
+                    .modify(|val| val.disable_interrupt(Interrupt::ReceiveTimeout))
+            };
+            self.receive_ready.wake();
+        }
         for (interrupt, waker) in [
             (Interrupt::ReceiveFifoReady, &self.receive_ready),
             (Interrupt::TransmitFifoReady, &self.transmit_ready),
@@ -100,7 +303,7 @@ impl SerialState {
 
 struct WaitForInterrupt<'r> {
     uart: &'r RegisterBlock,
-    interrupt: Interrupt,
+    interrupts: &'r [Interrupt],
     registry: &'r atomic_waker::AtomicWaker,
 }
 
@@ -108,12 +311,12 @@ impl<'r> WaitForInterrupt<'r> {
     #[inline]
     pub const fn new(
         uart: &'r RegisterBlock,
-        interrupt: Interrupt,
+        interrupts: &'r [Interrupt],
         registry: &'r atomic_waker::AtomicWaker,
     ) -> Self {
         Self {
             uart,
-            interrupt,
+            interrupts,
             registry,
         }
     }
@@ -124,12 +327,8 @@ impl Future for WaitForInterrupt<'_> {
 
     #[inline]
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        if self
-            .uart
-            .interrupt_state
-            .read()
-            .has_interrupt(self.interrupt)
-        {
+        let state = self.uart.interrupt_state.read();
+        if self.interrupts.iter().any(|&i| state.has_interrupt(i)) {
             Poll::Ready(())
         } else {
             self.registry.register(cx.waker());
@@ -148,11 +347,12 @@ async fn uart_write_async(
         0 => return Ok(0),
         _ => buf,
     };
-    unsafe {
+    // Interrupt enable register is shared by transmit and receive halves.
+    critical_section::with(|_| unsafe {
         uart.interrupt_enable
             .modify(|val| val.enable_interrupt(Interrupt::TransmitFifoReady))
-    };
-    WaitForInterrupt::new(uart, Interrupt::TransmitFifoReady, registry).await;
+    });
+    WaitForInterrupt::new(uart, &[Interrupt::TransmitFifoReady], registry).await;
     let len = core::cmp::min(
         uart.fifo_config_1.read().transmit_available_bytes() as usize,
         buf.len(),
@@ -173,15 +373,30 @@ async fn uart_read_async(
         0 => return Ok(0),
         _ => buf,
     };
-    unsafe {
-        uart.interrupt_enable
-            .modify(|val| val.enable_interrupt(Interrupt::ReceiveFifoReady))
+    let len = loop {
+        // Interrupt enable register is shared by transmit and receive halves.
+        critical_section::with(|_| unsafe {
+            uart.interrupt_enable.modify(|val| {
+                val.enable_interrupt(Interrupt::ReceiveFifoReady)
+                    .enable_interrupt(Interrupt::ReceiveTimeout)
+            })
+        });
+        WaitForInterrupt::new(
+            uart,
+            &[Interrupt::ReceiveFifoReady, Interrupt::ReceiveTimeout],
+            registry,
+        )
+        .await;
+        unsafe {
+            uart.interrupt_clear
+                .write(InterruptClear::default().clear_interrupt(Interrupt::ReceiveTimeout))
+        };
+        // Timeout may be left from bytes already taken by an earlier read.
+        let available = uart.fifo_config_1.read().receive_available_bytes() as usize;
+        if available != 0 {
+            break core::cmp::min(available, buf.len());
+        }
     };
-    WaitForInterrupt::new(uart, Interrupt::ReceiveFifoReady, registry).await;
-    let len = core::cmp::min(
-        uart.fifo_config_1.read().receive_available_bytes() as usize,
-        buf.len(),
-    );
     buf.iter_mut()
         .take(len)
         .for_each(|slot| *slot = uart.fifo_read.read());
@@ -206,6 +421,110 @@ impl<UART: Deref<Target = RegisterBlock>, PADS> embedded_io_async::Read
 {
     #[inline]
     async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
-        uart_read_async(&self.uart, buf, &self.state.receive_ready).await
+        self.receive
+            .read(&self.uart, buf, &self.state.receive_ready)
+            .await
+    }
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS> embedded_io_async::BufRead
+    for AsyncSerial<UART, PADS>
+{
+    #[inline]
+    async fn fill_buf(&mut self) -> Result<&[u8], Self::Error> {
+        self.receive
+            .fill(&self.uart, &self.state.receive_ready)
+            .await
+    }
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.receive.consume(amt)
+    }
+}
+
+impl<UART, PADS> embedded_io_async::ErrorType for AsyncTransmitHalf<UART, PADS> {
+    type Error = Error;
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS> embedded_io_async::Write
+    for AsyncTransmitHalf<UART, PADS>
+{
+    #[inline]
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        uart_write_async(&self.uart, buf, &self.state.transmit_ready).await
+    }
+}
+
+impl<UART> embedded_io_async::ErrorType for AsyncReceiveHalf<UART> {
+    type Error = Error;
+}
+
+impl<UART: Deref<Target = RegisterBlock>> embedded_io_async::Read for AsyncReceiveHalf<UART> {
+    #[inline]
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        self.receive
+            .read(&self.uart, buf, &self.state.receive_ready)
+            .await
+    }
+}
+
+impl<UART: Deref<Target = RegisterBlock>> embedded_io_async::BufRead for AsyncReceiveHalf<UART> {
+    #[inline]
+    async fn fill_buf(&mut self) -> Result<&[u8], Self::Error> {
+        self.receive
+            .fill(&self.uart, &self.state.receive_ready)
+            .await
+    }
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.receive.consume(amt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fifo_threshold, AsyncReceiveHalf, AsyncTransmitHalf, ReceiveBuffer};
+    use crate::uart::{BlockingReceiveHalf, BlockingTransmitHalf};
+
+    #[test]
+    fn function_fifo_threshold() {
+        assert_eq!(fifo_threshold(0), 0);
+        assert_eq!(fifo_threshold(1), 0);
+        assert_eq!(fifo_threshold(16), 15);
+        assert_eq!(fifo_threshold(32), 31);
+        assert_eq!(fifo_threshold(255), 31);
+    }
+
+    #[test]
+    fn struct_receive_buffer() {
+        let mut receive = ReceiveBuffer::new();
+        assert_eq!(receive.remaining(), b"");
+        receive.buf[..6].copy_from_slice(b"OK\r\nAT");
+        receive.len = 6;
+        let line = receive
+            .remaining()
+            .iter()
+            .position(|&b| b == b'\n')
+            .unwrap()
+            + 1;
+        assert_eq!(&receive.remaining()[..line], b"OK\r\n");
+        receive.consume(line);
+        assert_eq!(receive.remaining(), b"AT");
+        let mut buf = [0; 1];
+        assert_eq!(receive.read_into(&mut buf), 1);
+        assert_eq!(&buf, b"A");
+        receive.consume(10);
+        assert_eq!(receive.remaining(), b"");
+        assert_eq!(receive.read_into(&mut buf), 0);
+    }
+
+    #[test]
+    fn struct_split_halves_send() {
+        fn is_send<T: Send>() {}
+        // Halves are `Send` by auto traits once both peripheral and pads are.
+        is_send::<AsyncTransmitHalf<u8, u8>>();
+        is_send::<AsyncReceiveHalf<u8>>();
+        is_send::<BlockingTransmitHalf<u8, u8>>();
+        is_send::<BlockingReceiveHalf<u8, u8>>();
     }
 }
@@ -1,4 +1,4 @@
-use super::{uart_config, Config, ConfigError, Error, Pads, RegisterBlock};
+use super::{uart_config, Config, ConfigError, Error, Pads, RegisterBlock, FIFO_DEPTH};
 use crate::clocks::Clocks;
 use core::ops::Deref;
 
@@ -56,12 +56,18 @@ impl<UART: Deref<Target = RegisterBlock>, PADS> BlockingSerial<UART, PADS> {
 }
 
 /// Transmit half from splitted serial structure.
+///
+/// Transmit half only accesses transmit FIFO, thus it can be moved to another task or hart
+/// independently from the receive half.
 pub struct BlockingTransmitHalf<UART, PADS> {
     pub(crate) uart: UART,
     pub(crate) _pads: PADS,
 }
 
 /// Receive half from splitted serial structure.
+///
+/// Receive half only accesses receive FIFO, thus it can be moved to another task or hart
+/// independently from the transmit half.
 pub struct BlockingReceiveHalf<UART, PADS> {
     pub(crate) uart: UART,
     pub(crate) _pads: PADS,
@@ -95,7 +101,7 @@ fn uart_write_nb(uart: &RegisterBlock, word: u8) -> nb::Result<(), Error> {
 fn uart_flush(uart: &RegisterBlock) -> Result<(), Error> {
     // There are maximum 32 bytes in transmit FIFO queue, wait until all bytes are available,
     // meaning that all data in queue has been sent into UART bus.
-    while uart.fifo_config_1.read().transmit_available_bytes() != 32 {
+    while uart.fifo_config_1.read().transmit_available_bytes() != FIFO_DEPTH {
         core::hint::spin_loop();
     }
     Ok(())
@@ -103,7 +109,7 @@ fn uart_flush(uart: &RegisterBlock) -> Result<(), Error> {
 
 #[inline]
 fn uart_flush_nb(uart: &RegisterBlock) -> nb::Result<(), Error> {
-    if uart.fifo_config_1.read().transmit_available_bytes() != 32 {
+    if uart.fifo_config_1.read().transmit_available_bytes() != FIFO_DEPTH {
         return Err(nb::Error::WouldBlock);
     }
     Ok(())
@@ -0,0 +1,322 @@
+use super::{
+    uart_config, Config, ConfigError, Error, Interrupt, InterruptClear, Pads, RegisterBlock,
+    FIFO_DEPTH,
+};
+use crate::clocks::Clocks;
+use core::{
+    ops::Deref,
+    sync::atomic::{AtomicPtr, AtomicUsize, Ordering},
+};
+
+/// Managed serial peripheral transmitting from a ring buffer.
+///
+/// Writes are queued into the user provided buffer and return at once; the interrupt
+/// handler calling [`BufferedSerialState::on_interrupt`] drains the buffer into transmit
+/// FIFO whenever it has room. A write reports [`Error::WouldBlock`] only if the buffer is
+/// full, so logging never waits on the serial bus as long as the buffer is large enough.
+pub struct BufferedSerial<UART, PADS> {
+    uart: UART,
+    pads: PADS,
+    state: &'static BufferedSerialState,
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS> BufferedSerial<UART, PADS> {
+    /// Creates a buffered serial instance transmitting from `buffer`.
+    ///
+    /// `state` should be a `static` shared with the interrupt handler of this serial
+    /// peripheral.
+    #[inline]
+    pub fn new<const I: usize>(
+        uart: UART,
+        config: Config,
+        pads: PADS,
+        clocks: &Clocks,
+        buffer: &'static mut [u8],
+        state: &'static BufferedSerialState,
+    ) -> Result<Self, ConfigError>
+    where
+        PADS: Pads<I>,
+    {
+        let (bit_period, data_config, transmit_config, receive_config) =
+            uart_config::<I, PADS>(config, clocks)?;
+
+        unsafe {
+            uart.bit_period.write(bit_period);
+            uart.data_config.write(data_config);
+            uart.transmit_config.write(transmit_config.enable_freerun());
+            uart.receive_config.write(receive_config);
+            uart.interrupt_enable
+                .modify(|v| v.disable_interrupt(Interrupt::TransmitFifoReady));
+            state.attach(buffer.as_mut_ptr(), buffer.len());
+        }
+        state
+            .ref_to_serial
+            .store(&*uart as *const _ as usize, Ordering::Release);
+
+        Ok(Self { uart, pads, state })
+    }
+
+    /// Number of bytes that can be queued without blocking.
+    #[inline]
+    pub fn free_space(&self) -> usize {
+        self.state.free_space()
+    }
+
+    /// Release serial instance and return its peripheral and pads.
+    ///
+    /// Bytes still in the buffer are discarded.
+    #[inline]
+    pub fn free(self) -> (UART, PADS) {
+        critical_section::with(|_| unsafe {
+            self.uart
+                .interrupt_enable
+                .modify(|v| v.disable_interrupt(Interrupt::TransmitFifoReady))
+        });
+        self.state.ref_to_serial.store(0, Ordering::Release);
+        unsafe { self.state.attach(core::ptr::null_mut(), 0) };
+        (self.uart, self.pads)
+    }
+
+    #[inline]
+    fn enqueue(&mut self, buf: &[u8]) -> usize {
+        let len = self.state.push(buf);
+        if len != 0 {
+            // Interrupt enable register is also written by the interrupt handler.
+            critical_section::with(|_| unsafe {
+                self.uart
+                    .interrupt_enable
+                    .modify(|v| v.enable_interrupt(Interrupt::TransmitFifoReady))
+            });
+        }
+        len
+    }
+}
+
+/// Ring buffer state shared by a buffered serial peripheral and its interrupt handler.
+///
+/// The ring has a single producer, the serial instance, and a single consumer, the
+/// interrupt handler; `head` and `tail` count bytes ever pushed and popped.
+#[derive(Debug)]
+pub struct BufferedSerialState {
+    buffer: AtomicPtr<u8>,
+    capacity: AtomicUsize,
+    head: AtomicUsize,
+    tail: AtomicUsize,
+    ref_to_serial: AtomicUsize,
+}
+
+impl BufferedSerialState {
+    /// Creates an empty ring buffer state.
+    #[inline]
+    pub const fn new() -> BufferedSerialState {
+        BufferedSerialState {
+            buffer: AtomicPtr::new(core::ptr::null_mut()),
+            capacity: AtomicUsize::new(0),
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+            ref_to_serial: AtomicUsize::new(0),
+        }
+    }
+    /// Use this ring buffer state to handle interrupt.
+    ///
+    /// Moves queued bytes into transmit FIFO, and disables the FIFO ready interrupt
+    /// once the buffer is empty until more bytes are written.
+    #[inline]
+    pub fn on_interrupt(&self) {
+        let uart = self.ref_to_serial.load(Ordering::Acquire) as *const RegisterBlock;
+        if uart.is_null() {
+            return;
+        }
+        let uart = unsafe { &*uart };
+        if !uart
+            .interrupt_state
+            .read()
+            .has_interrupt(Interrupt::TransmitFifoReady)
+        {
+            return;
+        }
+        let mut available = uart.fifo_config_1.read().transmit_available_bytes();
+        while available != 0 {
+            let Some(byte) = self.pop() else {
+                unsafe {
+                    uart.interrupt_enable
+                        .modify(|v| v.disable_interrupt(Interrupt::TransmitFifoReady))
+                };
+                break;
+            };
+            unsafe { uart.fifo_write.write(byte) };
+            available -= 1;
+        }
+        unsafe {
+            uart.interrupt_clear
+                .write(InterruptClear::default().clear_interrupt(Interrupt::TransmitFifoReady))
+        };
+    }
+
+    /// Replace backing buffer and empty the ring.
+    ///
+    /// # Safety
+    ///
+    /// `buffer` must be valid for `capacity` bytes as long as it's attached, and the
+    /// interrupt handler must not run concurrently.
+    #[inline]
+    unsafe fn attach(&self, buffer: *mut u8, capacity: usize) {
+        self.buffer.store(buffer, Ordering::Relaxed);
+        self.capacity.store(capacity, Ordering::Relaxed);
+        self.head.store(0, Ordering::Relaxed);
+        self.tail.store(0, Ordering::Release);
+    }
+    #[inline]
+    fn free_space(&self) -> usize {
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Acquire);
+        self.capacity.load(Ordering::Relaxed) - head.wrapping_sub(tail)
+    }
+    /// Check if any queued byte is not yet moved into transmit FIFO.
+    #[inline]
+    fn has_pending(&self) -> bool {
+        self.head.load(Ordering::Relaxed) != self.tail.load(Ordering::Acquire)
+    }
+    /// Queue as many bytes of `data` as fit, returning the number queued.
+    #[inline]
+    fn push(&self, data: &[u8]) -> usize {
+        let buffer = self.buffer.load(Ordering::Relaxed);
+        let capacity = self.capacity.load(Ordering::Relaxed);
+        let head = self.head.load(Ordering::Relaxed);
+        let len = data.len().min(self.free_space());
+        for (i, &byte) in data[..len].iter().enumerate() {
+            let index = head.wrapping_add(i) % capacity;
+            unsafe { buffer.add(index).write_volatile(byte) };
+        }
+        self.head.store(head.wrapping_add(len), Ordering::Release);
+        len
+    }
+    /// Take the oldest queued byte.
+    #[inline]
+    fn pop(&self) -> Option<u8> {
+        let tail = self.tail.load(Ordering::Relaxed);
+        if self.head.load(Ordering::Acquire) == tail {
+            return None;
+        }
+        let index = tail % self.capacity.load(Ordering::Relaxed);
+        let byte = unsafe {
+            self.buffer
+                .load(Ordering::Relaxed)
+                .add(index)
+                .read_volatile()
+        };
+        self.tail.store(tail.wrapping_add(1), Ordering::Release);
+        Some(byte)
+    }
+}
+
+impl Default for BufferedSerialState {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<UART, PADS> embedded_io::ErrorType for BufferedSerial<UART, PADS> {
+    type Error = Error;
+}
+
+impl<UART, PADS> embedded_hal_nb::serial::ErrorType for BufferedSerial<UART, PADS> {
+    type Error = Error;
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS> embedded_io::Write for BufferedSerial<UART, PADS> {
+    /// Queue bytes for transmission, returning [`Error::WouldBlock`] if the buffer is full.
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        match self.enqueue(buf) {
+            0 => Err(Error::WouldBlock),
+            len => Ok(len),
+        }
+    }
+    /// Wait until all queued bytes are sent onto serial bus.
+    ///
+    /// Blocks forever if called with the serial interrupt unable to be handled.
+    #[inline]
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        while self.state.has_pending() {
+            core::hint::spin_loop();
+        }
+        while self.uart.fifo_config_1.read().transmit_available_bytes() != FIFO_DEPTH {
+            core::hint::spin_loop();
+        }
+        Ok(())
+    }
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS> embedded_io::WriteReady
+    for BufferedSerial<UART, PADS>
+{
+    #[inline]
+    fn write_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.state.free_space() != 0)
+    }
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS> embedded_hal_nb::serial::Write
+    for BufferedSerial<UART, PADS>
+{
+    #[inline]
+    fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+        match self.enqueue(&[word]) {
+            0 => Err(nb::Error::WouldBlock),
+            _ => Ok(()),
+        }
+    }
+    #[inline]
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        if self.state.has_pending()
+            || self.uart.fifo_config_1.read().transmit_available_bytes() != FIFO_DEPTH
+        {
+            return Err(nb::Error::WouldBlock);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BufferedSerialState;
+
+    #[test]
+    fn struct_buffered_serial_state_ring() {
+        let mut buffer = [0u8; 4];
+        let state = BufferedSerialState::new();
+        unsafe { state.attach(buffer.as_mut_ptr(), buffer.len()) };
+        assert_eq!(state.free_space(), 4);
+        assert_eq!(state.pop(), None);
+
+        assert_eq!(state.push(b"abc"), 3);
+        assert_eq!(state.free_space(), 1);
+        assert_eq!(state.push(b"de"), 1);
+        assert_eq!(state.push(b"f"), 0);
+        assert!(state.has_pending());
+
+        assert_eq!(state.pop(), Some(b'a'));
+        assert_eq!(state.pop(), Some(b'b'));
+        assert_eq!(state.push(b"gh"), 2);
+        assert_eq!(state.pop(), Some(b'c'));
+        assert_eq!(state.pop(), Some(b'd'));
+        assert_eq!(state.pop(), Some(b'g'));
+        assert_eq!(state.pop(), Some(b'h'));
+        assert_eq!(state.pop(), None);
+        assert!(!state.has_pending());
+        assert_eq!(state.free_space(), 4);
+    }
+
+    #[test]
+    fn struct_buffered_serial_state_empty_buffer() {
+        let state = BufferedSerialState::new();
+        assert_eq!(state.free_space(), 0);
+        assert_eq!(state.push(b"a"), 0);
+        assert_eq!(state.pop(), None);
+    }
+}
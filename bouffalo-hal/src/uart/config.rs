@@ -3,6 +3,14 @@ use crate::clocks::Clocks;
 use embedded_time::rate::{Baud, Extensions};
 
 /// Serial configuration.
+///
+/// Signals are always non-inverted: lines idle high and a break holds them low. The
+/// peripheral only inverts its signals in IR mode, where bits are sent as short pulses
+/// instead of levels, thus links with inverted idle level need an external inverter.
+///
+/// There is no internal loopback path from transmit to receive half on BL808 UART, so
+/// a self-test without external wiring is not possible; connect transmit and receive
+/// pads together on the board instead.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Config {
     /// Baudrate on the transmit half.
@@ -21,6 +29,10 @@ pub struct Config {
     pub transmit_word_length: WordLength,
     /// Data word length on the receive half.
     pub receive_word_length: WordLength,
+    /// Hardware flow control.
+    pub flow_control: FlowControl,
+    /// RS-485 half-duplex settings, or `None` for full-duplex links.
+    pub rs485: Option<Rs485Config>,
 }
 
 impl Config {
@@ -53,22 +65,57 @@ impl Config {
             ..self
         }
     }
+    /// Set hardware flow control.
+    ///
+    /// Pads given to the serial must include the signals the flow control mode uses on
+    /// each half present, request-to-send with receive and clear-to-send with transmit,
+    /// otherwise creating the serial fails with [`ConfigError::FlowControlPads`].
+    /// Clear-to-send input is ignored without flow control.
+    #[inline]
+    pub const fn set_flow_control(self, flow_control: FlowControl) -> Self {
+        Self {
+            flow_control,
+            ..self
+        }
+    }
+    /// Set RS-485 half-duplex mode.
+    ///
+    /// Only [`Rs485Serial`](super::Rs485Serial) drives the transceiver direction; other
+    /// serials fail with [`ConfigError::Rs485Unsupported`]. Direction on request-to-send
+    /// needs the request-to-send pad and excludes flow control, otherwise creating the
+    /// serial fails with [`ConfigError::Rs485Pads`].
+    #[inline]
+    pub const fn set_rs485(self, rs485: Rs485Config) -> Self {
+        Self {
+            rs485: Some(rs485),
+            ..self
+        }
+    }
     #[inline]
     fn into_registers(self) -> (DataConfig, TransmitConfig, ReceiveConfig) {
         let data_config = DataConfig::default().set_bit_order(self.bit_order);
+        let transmit_parity = match self.transmit_word_length {
+            WordLength::Nine => Parity::Even,
+            _ => self.transmit_parity,
+        };
+        let receive_parity = match self.receive_word_length {
+            WordLength::Nine => Parity::Even,
+            _ => self.receive_parity,
+        };
         let transmit_config = TransmitConfig::default()
-            .set_parity(self.transmit_parity)
+            .set_parity(transmit_parity)
             .set_stop_bits(self.stop_bits)
             .set_word_length(self.transmit_word_length);
         let receive_config = ReceiveConfig::default()
-            .set_parity(self.receive_parity)
+            .set_parity(receive_parity)
             .set_word_length(self.receive_word_length);
         (data_config, transmit_config, receive_config)
     }
 }
 
 impl Default for Config {
-    /// Serial configuration defaults to 8-bit word, no parity check, 1 stop bit, LSB first.
+    /// Serial configuration defaults to 8-bit word, no parity check, 1 stop bit, LSB first,
+    /// without flow control, in full-duplex mode.
     #[inline]
     fn default() -> Self {
         Config {
@@ -80,6 +127,8 @@ impl Default for Config {
             stop_bits: StopBits::One,
             transmit_word_length: WordLength::Eight,
             receive_word_length: WordLength::Eight,
+            flow_control: FlowControl::None,
+            rs485: None,
         }
     }
 }
@@ -89,6 +138,9 @@ pub(crate) fn uart_config<const I: usize, PADS: Pads<I>>(
     config: Config,
     clocks: &Clocks,
 ) -> Result<(BitPeriod, DataConfig, TransmitConfig, ReceiveConfig), ConfigError> {
+    if config.rs485.is_some() {
+        return Err(ConfigError::Rs485Unsupported);
+    }
     let uart_clock = match clocks.uart_clock::<I>() {
         Some(freq) => freq,
         None => return Err(ConfigError::ClockSource),
@@ -112,7 +164,7 @@ pub(crate) fn uart_config<const I: usize, PADS: Pads<I>>(
     if PADS::TXD {
         transmit_config = transmit_config.enable_txd();
     }
-    if PADS::CTS {
+    if flow_control_cts::<I, PADS>(config.flow_control)? {
         transmit_config = transmit_config.enable_cts();
     }
     if PADS::RXD {
@@ -121,6 +173,49 @@ pub(crate) fn uart_config<const I: usize, PADS: Pads<I>>(
     Ok((bit_period, data_config, transmit_config, receive_config))
 }
 
+/// Check if pads carry signals of `flow_control`, and if clear-to-send should gate transmit.
+///
+/// Signals are only required for the halves present: request-to-send with receive pad
+/// and clear-to-send with transmit pad.
+#[inline]
+const fn flow_control_cts<const I: usize, PADS: Pads<I>>(
+    flow_control: FlowControl,
+) -> Result<bool, ConfigError> {
+    match flow_control {
+        FlowControl::None => Ok(false),
+        FlowControl::Rts if PADS::RTS || !PADS::RXD => Ok(false),
+        FlowControl::RtsCts if (PADS::RTS || !PADS::RXD) && (PADS::CTS || !PADS::TXD) => {
+            Ok(PADS::CTS)
+        }
+        _ => Err(ConfigError::FlowControlPads),
+    }
+}
+
+/// Check if pads carry request-to-send signal for RS-485 direction control.
+///
+/// Direction control takes over request-to-send pin, thus it can't be shared with
+/// hardware flow control.
+#[inline]
+pub(crate) const fn rs485_pads<const I: usize, PADS: Pads<I>>(
+    config: Config,
+) -> Result<(), ConfigError> {
+    match (config.rs485, config.flow_control) {
+        (
+            Some(Rs485Config {
+                rts_direction: true,
+            }),
+            FlowControl::None,
+        ) if PADS::RTS => Ok(()),
+        (
+            Some(Rs485Config {
+                rts_direction: true,
+            }),
+            _,
+        ) => Err(ConfigError::Rs485Pads),
+        _ => Ok(()),
+    }
+}
+
 /// Errors on serial configuration.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ConfigError {
@@ -134,6 +229,14 @@ pub enum ConfigError {
     ReceiveBaudrateTooLow,
     /// Clock source unavailable.
     ClockSource,
+    /// Pads lack request-to-send or clear-to-send signal required by flow control.
+    FlowControlPads,
+    /// RS-485 direction on request-to-send lacks its pad, or conflicts with flow control.
+    Rs485Pads,
+    /// RS-485 mode has neither request-to-send direction nor a direction pin.
+    Rs485Direction,
+    /// RS-485 mode is set for a serial that doesn't drive transceiver direction.
+    Rs485Unsupported,
 }
 
 /// Order of the bits transmitted and received on the wire.
@@ -145,6 +248,37 @@ pub enum BitOrder {
     MsbFirst,
 }
 
+/// Hardware flow control.
+///
+/// Request-to-send output is driven by the receiver, which deasserts it while it cannot
+/// take more data; clear-to-send input pauses the transmitter between words while
+/// deasserted by the peer. Both signals are active low.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FlowControl {
+    /// No flow control; clear-to-send input is ignored.
+    None,
+    /// Request-to-send output and clear-to-send input.
+    RtsCts,
+    /// Request-to-send output only, for peers that don't drive clear-to-send.
+    Rts,
+}
+
+/// RS-485 half-duplex configuration.
+///
+/// The transceiver driver is enabled by software before the first word is written into
+/// transmit FIFO, and disabled by software once polling sees the last stop bit leave the
+/// shifter, so that the bus is free for other devices. The peripheral has no hardware
+/// driver enable timing, thus the release lags the stop bit by the polling latency.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct Rs485Config {
+    /// Drive transceiver driver enable on request-to-send pin, high while transmitting.
+    ///
+    /// The pin is switched through the software override register. A separate direction
+    /// pin can be given to [`Rs485Serial`](super::Rs485Serial) instead, or in addition for
+    /// transceivers with separate driver and receiver enable inputs.
+    pub rts_direction: bool,
+}
+
 /// Parity check.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Parity {
@@ -180,4 +314,89 @@ pub enum WordLength {
     Seven,
     /// Eight bits per word.
     Eight,
+    /// Nine bits per word, emulated by eight data bits and parity bit.
+    ///
+    /// Parity setting of the half is ignored; see [`MultidropSerial`](super::MultidropSerial)
+    /// for transmitting and receiving the ninth bit.
+    Nine,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        flow_control_cts, rs485_pads, uart_config, Clocks, Config, ConfigError, FlowControl, Pads,
+        Rs485Config,
+    };
+    use embedded_time::rate::Hertz;
+
+    struct TestPads<const RTS: bool, const CTS: bool, const TXD: bool, const RXD: bool>;
+
+    impl<const RTS: bool, const CTS: bool, const TXD: bool, const RXD: bool> Pads<0>
+        for TestPads<RTS, CTS, TXD, RXD>
+    {
+        const RTS: bool = RTS;
+        const CTS: bool = CTS;
+        const TXD: bool = TXD;
+        const RXD: bool = RXD;
+        type Split<T> = ();
+        fn split<T>(self, _: T) {}
+    }
+
+    #[test]
+    fn function_flow_control_cts() {
+        type Full = TestPads<true, true, true, true>;
+        type TxRx = TestPads<false, false, true, true>;
+        type TxRxRts = TestPads<true, false, true, true>;
+        type TxCts = TestPads<false, true, true, false>;
+        assert_eq!(flow_control_cts::<0, TxRx>(FlowControl::None), Ok(false));
+        assert_eq!(flow_control_cts::<0, Full>(FlowControl::None), Ok(false));
+        assert_eq!(flow_control_cts::<0, Full>(FlowControl::RtsCts), Ok(true));
+        assert_eq!(flow_control_cts::<0, TxCts>(FlowControl::RtsCts), Ok(true));
+        assert_eq!(flow_control_cts::<0, TxRxRts>(FlowControl::Rts), Ok(false));
+        assert_eq!(
+            flow_control_cts::<0, TxRx>(FlowControl::Rts),
+            Err(ConfigError::FlowControlPads)
+        );
+        assert_eq!(
+            flow_control_cts::<0, TxRxRts>(FlowControl::RtsCts),
+            Err(ConfigError::FlowControlPads)
+        );
+    }
+
+    #[test]
+    fn function_rs485_pads() {
+        type TxRx = TestPads<false, false, true, true>;
+        type TxRxRts = TestPads<true, false, true, true>;
+        let rts = Config::default().set_rs485(Rs485Config {
+            rts_direction: true,
+        });
+        let manual = Config::default().set_rs485(Rs485Config::default());
+        assert_eq!(rs485_pads::<0, TxRx>(Config::default()), Ok(()));
+        assert_eq!(rs485_pads::<0, TxRx>(manual), Ok(()));
+        assert_eq!(rs485_pads::<0, TxRxRts>(rts), Ok(()));
+        assert_eq!(rs485_pads::<0, TxRx>(rts), Err(ConfigError::Rs485Pads));
+        assert_eq!(
+            rs485_pads::<0, TxRxRts>(rts.set_flow_control(FlowControl::Rts)),
+            Err(ConfigError::Rs485Pads)
+        );
+    }
+
+    #[test]
+    fn function_uart_config_rs485() {
+        type TxRx = TestPads<false, false, true, true>;
+        let clocks = Clocks {
+            xtal: Hertz(40_000_000),
+            cpu: Hertz(320_000_000),
+            bus: Hertz(80_000_000),
+            uart: Hertz(80_000_000),
+            spi: Hertz(160_000_000),
+            i2c: Hertz(80_000_000),
+        };
+        assert!(uart_config::<0, TxRx>(Config::default(), &clocks).is_ok());
+        assert_eq!(
+            uart_config::<0, TxRx>(Config::default().set_rs485(Rs485Config::default()), &clocks)
+                .err(),
+            Some(ConfigError::Rs485Unsupported)
+        );
+    }
 }
@@ -10,6 +10,8 @@ pub enum Error {
     Overrun,
     /// Parity check error.
     Parity,
+    /// Transmit buffer is full, the operation would block.
+    WouldBlock,
 }
 
 impl embedded_io::Error for Error {
@@ -27,6 +29,7 @@ impl embedded_hal_nb::serial::Error for Error {
             Error::Noise => embedded_hal_nb::serial::ErrorKind::Noise,
             Error::Overrun => embedded_hal_nb::serial::ErrorKind::Overrun,
             Error::Parity => embedded_hal_nb::serial::ErrorKind::Parity,
+            Error::WouldBlock => embedded_hal_nb::serial::ErrorKind::Other,
         }
     }
 }
@@ -0,0 +1,237 @@
+use super::{
+    uart_config, Config, ConfigError, Error, Interrupt, InterruptClear, Pads, Parity,
+    RegisterBlock, WordLength, FIFO_DEPTH,
+};
+use crate::clocks::Clocks;
+use core::ops::Deref;
+
+/// Managed nine-bit multidrop serial peripheral.
+///
+/// The ninth bit of each word marks an address byte. It is carried by parity bit,
+/// where transmit parity is switched between even and odd for each word, and receive
+/// side recovers the ninth bit from parity check result.
+///
+/// On receive, data bytes are only returned after an address byte matching own address
+/// or broadcast address arrives; frames addressed to other devices are dropped. Parity
+/// check result is sampled when each byte is read, thus the receiver should be polled
+/// at least once per character time to keep address bytes recognizable.
+pub struct MultidropSerial<UART, PADS> {
+    uart: UART,
+    pads: PADS,
+    address: u8,
+    broadcast: Option<u8>,
+    addressed: bool,
+    transmit_parity: Parity,
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS> MultidropSerial<UART, PADS> {
+    /// Creates a polling multidrop serial instance with own device `address`.
+    ///
+    /// Word length in `config` is overridden to nine bits.
+    #[inline]
+    pub fn new<const I: usize>(
+        uart: UART,
+        config: Config,
+        pads: PADS,
+        clocks: &Clocks,
+        address: u8,
+    ) -> Result<Self, ConfigError>
+    where
+        PADS: Pads<I>,
+    {
+        let config = config.set_word_length(WordLength::Nine);
+        let (bit_period, data_config, transmit_config, receive_config) =
+            uart_config::<I, PADS>(config, clocks)?;
+
+        unsafe {
+            uart.bit_period.write(bit_period);
+            uart.data_config.write(data_config);
+            uart.transmit_config.write(transmit_config.enable_freerun());
+            uart.receive_config.write(receive_config);
+            uart.interrupt_clear
+                .write(InterruptClear::default().clear_interrupt(Interrupt::ReceiveParityError));
+        }
+
+        Ok(Self {
+            uart,
+            pads,
+            address,
+            broadcast: None,
+            addressed: false,
+            transmit_parity: Parity::Even,
+        })
+    }
+
+    /// Release serial instance and return its peripheral and pads.
+    #[inline]
+    pub fn free(self) -> (UART, PADS) {
+        (self.uart, self.pads)
+    }
+
+    /// Set own device address.
+    #[inline]
+    pub fn set_address(&mut self, address: u8) {
+        self.address = address;
+        self.addressed = false;
+    }
+
+    /// Get own device address.
+    #[inline]
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    /// Set broadcast address also accepted by receiver, or `None` to disable broadcast.
+    #[inline]
+    pub fn set_broadcast_address(&mut self, broadcast: Option<u8>) {
+        self.broadcast = broadcast;
+    }
+
+    /// Check if current frame on bus is addressed to this device.
+    #[inline]
+    pub fn is_addressed(&self) -> bool {
+        self.addressed
+    }
+
+    /// Transmit a frame to device `address`, with address bit set on the first byte only.
+    pub fn write_frame(&mut self, address: u8, data: &[u8]) -> Result<(), Error> {
+        self.write_word(address, true);
+        for &byte in data {
+            self.write_word(byte, false);
+        }
+        self.flush();
+        Ok(())
+    }
+
+    /// Receive one data byte addressed to this device.
+    ///
+    /// Address bytes are consumed to update addressing state and never returned.
+    pub fn read_nb(&mut self) -> nb::Result<u8, Error> {
+        loop {
+            if self.uart.fifo_config_1.read().receive_available_bytes() == 0 {
+                return Err(nb::Error::WouldBlock);
+            }
+            let parity_error = self
+                .uart
+                .interrupt_state
+                .read()
+                .has_interrupt(Interrupt::ReceiveParityError);
+            let byte = self.uart.fifo_read.read();
+            if parity_error {
+                unsafe {
+                    self.uart.interrupt_clear.write(
+                        InterruptClear::default().clear_interrupt(Interrupt::ReceiveParityError),
+                    )
+                };
+            }
+            if ninth_bit(byte, parity_error) {
+                self.addressed = byte == self.address || Some(byte) == self.broadcast;
+            } else if self.addressed {
+                return Ok(byte);
+            }
+        }
+    }
+
+    #[inline]
+    fn write_word(&mut self, byte: u8, ninth: bool) {
+        let parity = parity_for(byte, ninth);
+        if parity != self.transmit_parity {
+            // Parity bit setting applies to the shifter, drain words queued with previous setting.
+            self.flush();
+            unsafe { self.uart.transmit_config.modify(|v| v.set_parity(parity)) };
+            self.transmit_parity = parity;
+        }
+        while self.uart.fifo_config_1.read().transmit_available_bytes() == 0 {
+            core::hint::spin_loop();
+        }
+        unsafe { self.uart.fifo_write.write(byte) };
+    }
+
+    #[inline]
+    fn flush(&self) {
+        while self.uart.fifo_config_1.read().transmit_available_bytes() != FIFO_DEPTH
+            || self.uart.bus_state.read().transmit_busy()
+        {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+/// Parity setting to transmit `byte` with `ninth` as parity bit.
+#[inline]
+const fn parity_for(byte: u8, ninth: bool) -> Parity {
+    // Even parity bit equals number of ones in data modulo 2.
+    if (byte.count_ones() & 1 == 1) == ninth {
+        Parity::Even
+    } else {
+        Parity::Odd
+    }
+}
+
+/// Recover ninth bit from `byte` received with even parity check.
+#[inline]
+const fn ninth_bit(byte: u8, parity_error: bool) -> bool {
+    (byte.count_ones() & 1 == 1) != parity_error
+}
+
+impl<UART, PADS> embedded_io::ErrorType for MultidropSerial<UART, PADS> {
+    type Error = Error;
+}
+
+impl<UART, PADS> embedded_hal_nb::serial::ErrorType for MultidropSerial<UART, PADS> {
+    type Error = Error;
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS> embedded_io::Read for MultidropSerial<UART, PADS> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        buf[0] = nb::block!(self.read_nb())?;
+        let mut len = 1;
+        while len < buf.len() {
+            match self.read_nb() {
+                Ok(byte) => buf[len] = byte,
+                Err(nb::Error::WouldBlock) => break,
+                Err(nb::Error::Other(e)) => return Err(e),
+            }
+            len += 1;
+        }
+        Ok(len)
+    }
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS> embedded_hal_nb::serial::Read
+    for MultidropSerial<UART, PADS>
+{
+    #[inline]
+    fn read(&mut self) -> nb::Result<u8, Self::Error> {
+        self.read_nb()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ninth_bit, parity_for, Parity};
+
+    #[test]
+    fn function_parity_for() {
+        assert_eq!(parity_for(0x00, false), Parity::Even);
+        assert_eq!(parity_for(0x00, true), Parity::Odd);
+        assert_eq!(parity_for(0x01, true), Parity::Even);
+        assert_eq!(parity_for(0x01, false), Parity::Odd);
+        assert_eq!(parity_for(0x03, false), Parity::Even);
+    }
+
+    #[test]
+    fn function_ninth_bit() {
+        for byte in [0x00u8, 0x01, 0x5a, 0x7f, 0xff] {
+            for ninth in [false, true] {
+                // Receive side checks even parity; odd parity on wire is reported as parity error.
+                let parity_error = parity_for(byte, ninth) == Parity::Odd;
+                assert_eq!(ninth_bit(byte, parity_error), ninth);
+            }
+        }
+    }
+}
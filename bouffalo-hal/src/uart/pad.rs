@@ -209,10 +209,10 @@ where
     Alternate<'e, N3, Uart>: HasUartSignal<I3>,
     Alternate<'g, N4, Uart>: HasUartSignal<I4>,
 {
-    const RTS: bool = false;
+    const RTS: bool = true;
     const CTS: bool = true;
     const TXD: bool = true;
-    const RXD: bool = false;
+    const RXD: bool = true;
     type Split<T> = (
         BlockingTransmitHalf<
             T,
@@ -235,6 +235,51 @@ where
     }
 }
 
+impl<
+        'a,
+        'b,
+        'c,
+        'd,
+        'e,
+        'f,
+        const I1: usize,
+        const I2: usize,
+        const I3: usize,
+        const U: usize,
+        const N1: usize,
+        const N2: usize,
+        const N3: usize,
+    > Pads<U>
+    for (
+        (Alternate<'a, N1, Uart>, UartMux<'b, I1, MuxTxd<U>>),
+        (Alternate<'c, N2, Uart>, UartMux<'d, I2, MuxRxd<U>>),
+        (Alternate<'e, N3, Uart>, UartMux<'f, I3, MuxRts<U>>),
+    )
+where
+    Alternate<'a, N1, Uart>: HasUartSignal<I1>,
+    Alternate<'c, N2, Uart>: HasUartSignal<I2>,
+    Alternate<'e, N3, Uart>: HasUartSignal<I3>,
+{
+    const RTS: bool = true;
+    const CTS: bool = false;
+    const TXD: bool = true;
+    const RXD: bool = true;
+    type Split<T> = (
+        BlockingTransmitHalf<T, (Alternate<'a, N1, Uart>, UartMux<'b, I1, MuxTxd<U>>)>,
+        BlockingReceiveHalf<
+            T,
+            (
+                (Alternate<'c, N2, Uart>, UartMux<'d, I2, MuxRxd<U>>),
+                (Alternate<'e, N3, Uart>, UartMux<'f, I3, MuxRts<U>>),
+            ),
+        >,
+    );
+    #[inline]
+    fn split<T>(self, uart: T) -> Self::Split<T> {
+        from_pads(uart, self.0, (self.1, self.2))
+    }
+}
+
 // TODO: support split for MmUart pads.
 
 const MMUART_UART_ID: usize = 3;
@@ -12,7 +12,9 @@ pub struct RegisterBlock {
     pub bit_period: RW<BitPeriod>,
     /// Data format configuration.
     pub data_config: RW<DataConfig>,
-    _reserved1: [u8; 0x10],
+    _reserved1: [u8; 0xc],
+    /// Software control of request-to-send pin.
+    pub software_mode: RW<SoftwareMode>,
     /// Interrupt state register.
     pub interrupt_state: RO<InterruptState>,
     /// Interrupt mask register.
@@ -183,6 +185,8 @@ impl TransmitConfig {
         self.0 & Self::IR_INVERSE != 0
     }
     /// Set word length.
+    ///
+    /// Nine-bit words use eight data bits here; the ninth bit is carried by parity bit.
     #[inline]
     pub const fn set_word_length(self, val: WordLength) -> Self {
         let field = transmit_config::WordLength::from(self.0);
@@ -190,7 +194,7 @@ impl TransmitConfig {
             WordLength::Five => 4,
             WordLength::Six => 5,
             WordLength::Seven => 6,
-            WordLength::Eight => 7,
+            WordLength::Eight | WordLength::Nine => 7,
         };
         Self(field.set(val))
     }
@@ -396,6 +400,8 @@ impl ReceiveConfig {
         self.0 & Self::IR_INVERSE != 0
     }
     /// Set word length.
+    ///
+    /// Nine-bit words use eight data bits here; the ninth bit is carried by parity bit.
     #[inline]
     pub const fn set_word_length(self, val: WordLength) -> Self {
         let field = receive_config::WordLength::from(self.0);
@@ -403,7 +409,7 @@ impl ReceiveConfig {
             WordLength::Five => 4,
             WordLength::Six => 5,
             WordLength::Seven => 6,
-            WordLength::Eight => 7,
+            WordLength::Eight | WordLength::Nine => 7,
         };
         Self(field.set(val))
     }
@@ -625,6 +631,36 @@ impl InterruptEnable {
     }
 }
 
+/// Software pin control register.
+///
+/// Under software control, request-to-send pin is driven by the value in this register
+/// instead of the UART state machine, e.g. for RS-485 transceiver direction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct SoftwareMode(u32);
+
+impl SoftwareMode {
+    const RTS_SOFTWARE: u32 = 1 << 2;
+    const RTS_VALUE: u32 = 1 << 3;
+
+    /// Drive request-to-send pin by software with level `high`.
+    #[inline]
+    pub const fn enable_rts_software(self, high: bool) -> Self {
+        let val = if high { Self::RTS_VALUE } else { 0 };
+        Self((self.0 & !Self::RTS_VALUE) | Self::RTS_SOFTWARE | val)
+    }
+    /// Return request-to-send pin to UART control.
+    #[inline]
+    pub const fn disable_rts_software(self) -> Self {
+        Self(self.0 & !Self::RTS_SOFTWARE)
+    }
+    /// Check if request-to-send pin is driven by software.
+    #[inline]
+    pub const fn is_rts_software_enabled(self) -> bool {
+        self.0 & Self::RTS_SOFTWARE != 0
+    }
+}
+
 /// Bus state register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 #[repr(transparent)]
@@ -723,6 +759,9 @@ impl FifoConfig0 {
     }
 }
 
+/// Depth of transmit and receive FIFO queues in bytes.
+pub(crate) const FIFO_DEPTH: u8 = 32;
+
 /// First-in first-out queue configuration 1.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 #[repr(transparent)]
@@ -770,7 +809,7 @@ impl FifoConfig1 {
 mod tests {
     use crate::uart::{StopBits, WordLength};
 
-    use super::{BitPeriod, Parity, ReceiveConfig, RegisterBlock, TransmitConfig};
+    use super::{BitPeriod, Parity, ReceiveConfig, RegisterBlock, SoftwareMode, TransmitConfig};
     use memoffset::offset_of;
 
     #[test]
@@ -779,6 +818,7 @@ mod tests {
         assert_eq!(offset_of!(RegisterBlock, receive_config), 0x4);
         assert_eq!(offset_of!(RegisterBlock, bit_period), 0x08);
         assert_eq!(offset_of!(RegisterBlock, data_config), 0x0c);
+        assert_eq!(offset_of!(RegisterBlock, software_mode), 0x1c);
         assert_eq!(offset_of!(RegisterBlock, interrupt_state), 0x20);
         assert_eq!(offset_of!(RegisterBlock, interrupt_mask), 0x24);
         assert_eq!(offset_of!(RegisterBlock, interrupt_clear), 0x28);
@@ -860,6 +900,9 @@ mod tests {
         val = val.set_word_length(WordLength::Eight);
         assert_eq!(val.0, 0x00000700);
         assert_eq!(val.word_length(), WordLength::Eight);
+        val = val.set_word_length(WordLength::Nine);
+        assert_eq!(val.0, 0x00000700);
+        assert_eq!(val.word_length(), WordLength::Eight);
 
         val = TransmitConfig(0x0);
 
@@ -988,6 +1031,9 @@ mod tests {
         val = val.set_word_length(WordLength::Eight);
         assert_eq!(val.0, 0x00000700);
         assert_eq!(val.word_length(), WordLength::Eight);
+        val = val.set_word_length(WordLength::Nine);
+        assert_eq!(val.0, 0x00000700);
+        assert_eq!(val.word_length(), WordLength::Eight);
 
         val = ReceiveConfig(0x0);
 
@@ -1014,4 +1060,12 @@ mod tests {
     }
 
     // TODO: use getter functions to check default value for ReceiveConfig
+
+    #[test]
+    fn struct_software_mode_functions() {
+        let val = SoftwareMode(0x0).enable_rts_software(true);
+        assert_eq!(val.0, 0x0000_000c);
+        assert!(val.is_rts_software_enabled());
+        assert!(!val.disable_rts_software().is_rts_software_enabled());
+    }
 }
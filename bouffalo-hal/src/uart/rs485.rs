@@ -0,0 +1,262 @@
+use super::{rs485_pads, uart_config, Config, ConfigError, Error, Pads, RegisterBlock, FIFO_DEPTH};
+use crate::clocks::Clocks;
+use core::convert::Infallible;
+use core::ops::Deref;
+use embedded_hal::digital::{ErrorType, OutputPin};
+
+/// Managed RS-485 half-duplex serial peripheral.
+///
+/// Transceiver driver is enabled on the first word written, and stays enabled until
+/// the serial is flushed or read from; flushing polls for the last stop bit before the
+/// driver is disabled. Both switches are made by software, see
+/// [`Rs485Config`](super::Rs485Config) for the resulting latency. Driver enable is driven
+/// on request-to-send pin if configured, and on the direction pin if one is given.
+pub struct Rs485Serial<UART, PADS, DE = NoDirectionPin> {
+    uart: UART,
+    pads: PADS,
+    direction_pin: DE,
+    rts_direction: bool,
+    transmitting: bool,
+}
+
+/// Placeholder direction pin for RS-485 serial with request-to-send direction only.
+pub struct NoDirectionPin;
+
+impl ErrorType for NoDirectionPin {
+    type Error = Infallible;
+}
+
+impl OutputPin for NoDirectionPin {
+    #[inline]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+    #[inline]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS> Rs485Serial<UART, PADS> {
+    /// Creates a polling RS-485 serial instance with direction driven on request-to-send pin.
+    ///
+    /// Fails with [`ConfigError::Rs485Direction`] if `config` doesn't enable RS-485
+    /// request-to-send direction.
+    #[inline]
+    pub fn new<const I: usize>(
+        uart: UART,
+        config: Config,
+        pads: PADS,
+        clocks: &Clocks,
+    ) -> Result<Self, ConfigError>
+    where
+        PADS: Pads<I>,
+    {
+        match config.rs485 {
+            Some(rs485) if rs485.rts_direction => {}
+            _ => return Err(ConfigError::Rs485Direction),
+        }
+        Self::with_direction_pin(uart, config, pads, clocks, NoDirectionPin)
+    }
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS, DE: OutputPin> Rs485Serial<UART, PADS, DE> {
+    /// Creates a polling RS-485 serial instance driving transceiver direction on `direction_pin`.
+    ///
+    /// Request-to-send pin is driven as well if `config` enables request-to-send direction.
+    #[inline]
+    pub fn with_direction_pin<const I: usize>(
+        uart: UART,
+        config: Config,
+        pads: PADS,
+        clocks: &Clocks,
+        mut direction_pin: DE,
+    ) -> Result<Self, ConfigError>
+    where
+        PADS: Pads<I>,
+    {
+        rs485_pads::<I, PADS>(config)?;
+        let rts_direction = config.rs485.is_some_and(|rs485| rs485.rts_direction);
+        let (bit_period, data_config, transmit_config, receive_config) = uart_config::<I, PADS>(
+            Config {
+                rs485: None,
+                ..config
+            },
+            clocks,
+        )?;
+
+        unsafe {
+            uart.bit_period.write(bit_period);
+            uart.data_config.write(data_config);
+            uart.transmit_config.write(transmit_config.enable_freerun());
+            uart.receive_config.write(receive_config);
+            if rts_direction {
+                uart.software_mode.modify(|v| v.enable_rts_software(false));
+            }
+        }
+        direction_pin.set_low().ok();
+
+        Ok(Self {
+            uart,
+            pads,
+            direction_pin,
+            rts_direction,
+            transmitting: false,
+        })
+    }
+
+    /// Release serial instance and return its peripheral, pads and direction pin.
+    ///
+    /// Request-to-send pin is returned to serial control.
+    #[inline]
+    pub fn free(mut self) -> (UART, PADS, DE) {
+        self.release();
+        if self.rts_direction {
+            unsafe { self.uart.software_mode.modify(|v| v.disable_rts_software()) };
+        }
+        (self.uart, self.pads, self.direction_pin)
+    }
+
+    /// Check if transceiver driver is enabled.
+    #[inline]
+    pub fn is_transmitting(&self) -> bool {
+        self.transmitting
+    }
+
+    /// Enable transceiver driver before the first word is written.
+    #[inline]
+    fn acquire(&mut self) {
+        if self.transmitting {
+            return;
+        }
+        if self.rts_direction {
+            unsafe {
+                self.uart
+                    .software_mode
+                    .modify(|v| v.enable_rts_software(true))
+            };
+        }
+        self.direction_pin.set_high().ok();
+        self.transmitting = true;
+    }
+
+    /// Wait for the last stop bit and disable transceiver driver.
+    #[inline]
+    fn release(&mut self) {
+        if !self.transmitting {
+            return;
+        }
+        while self.uart.fifo_config_1.read().transmit_available_bytes() != FIFO_DEPTH
+            || self.uart.bus_state.read().transmit_busy()
+        {
+            core::hint::spin_loop();
+        }
+        if self.rts_direction {
+            unsafe {
+                self.uart
+                    .software_mode
+                    .modify(|v| v.enable_rts_software(false))
+            };
+        }
+        self.direction_pin.set_low().ok();
+        self.transmitting = false;
+    }
+
+    #[inline]
+    fn write_nb(&mut self, word: u8) -> nb::Result<(), Error> {
+        if self.uart.fifo_config_1.read().transmit_available_bytes() == 0 {
+            return Err(nb::Error::WouldBlock);
+        }
+        self.acquire();
+        unsafe { self.uart.fifo_write.write(word) };
+        Ok(())
+    }
+
+    #[inline]
+    fn read_nb(&mut self) -> nb::Result<u8, Error> {
+        self.release();
+        if self.uart.fifo_config_1.read().receive_available_bytes() == 0 {
+            return Err(nb::Error::WouldBlock);
+        }
+        Ok(self.uart.fifo_read.read())
+    }
+}
+
+impl<UART, PADS, DE> embedded_io::ErrorType for Rs485Serial<UART, PADS, DE> {
+    type Error = Error;
+}
+
+impl<UART, PADS, DE> embedded_hal_nb::serial::ErrorType for Rs485Serial<UART, PADS, DE> {
+    type Error = Error;
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS, DE: OutputPin> embedded_io::Write
+    for Rs485Serial<UART, PADS, DE>
+{
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        nb::block!(self.write_nb(buf[0]))?;
+        let mut len = 1;
+        while len < buf.len() {
+            match self.write_nb(buf[len]) {
+                Ok(()) => len += 1,
+                Err(nb::Error::WouldBlock) => break,
+                Err(nb::Error::Other(e)) => return Err(e),
+            }
+        }
+        Ok(len)
+    }
+    #[inline]
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.release();
+        Ok(())
+    }
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS, DE: OutputPin> embedded_hal_nb::serial::Write
+    for Rs485Serial<UART, PADS, DE>
+{
+    #[inline]
+    fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+        self.write_nb(word)
+    }
+    #[inline]
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        self.release();
+        Ok(())
+    }
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS, DE: OutputPin> embedded_io::Read
+    for Rs485Serial<UART, PADS, DE>
+{
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        buf[0] = nb::block!(self.read_nb())?;
+        let mut len = 1;
+        while len < buf.len() {
+            match self.read_nb() {
+                Ok(byte) => buf[len] = byte,
+                Err(nb::Error::WouldBlock) => break,
+                Err(nb::Error::Other(e)) => return Err(e),
+            }
+            len += 1;
+        }
+        Ok(len)
+    }
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS, DE: OutputPin> embedded_hal_nb::serial::Read
+    for Rs485Serial<UART, PADS, DE>
+{
+    #[inline]
+    fn read(&mut self) -> nb::Result<u8, Self::Error> {
+        self.read_nb()
+    }
+}
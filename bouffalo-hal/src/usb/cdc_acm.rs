@@ -0,0 +1,273 @@
+//! Communications Device Class Abstract Control Model, the USB virtual serial port.
+//!
+//! This module handles class-specific control requests of the communication interface,
+//! so a device controller driver can forward them here and leave data transfer to bulk
+//! endpoints. Applications mirroring host settings, like boot loaders switching the baud
+//! rate of a real serial port, read them from [`AcmControl`].
+use super::{RequestError, SetupPacket};
+
+/// Request type of class-specific host-to-device requests to an interface.
+const CLASS_INTERFACE_OUT: u8 = 0x21;
+/// Request type of class-specific device-to-host requests to an interface.
+const CLASS_INTERFACE_IN: u8 = 0xa1;
+/// Set line coding request.
+const SET_LINE_CODING: u8 = 0x20;
+/// Get line coding request.
+const GET_LINE_CODING: u8 = 0x21;
+/// Set control line state request.
+const SET_CONTROL_LINE_STATE: u8 = 0x22;
+/// Data terminal ready bit in control line state.
+const DTR: u16 = 1 << 0;
+/// Request to send bit in control line state.
+const RTS: u16 = 1 << 1;
+
+/// Stop bits setting of line coding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StopBits {
+    /// 1 stop bit.
+    One = 0,
+    /// 1.5 stop bits.
+    OnePointFive = 1,
+    /// 2 stop bits.
+    Two = 2,
+}
+
+/// Parity setting of line coding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Parity {
+    /// No parity bit.
+    None = 0,
+    /// Odd parity.
+    Odd = 1,
+    /// Even parity.
+    Even = 2,
+    /// Parity bit always one.
+    Mark = 3,
+    /// Parity bit always zero.
+    Space = 4,
+}
+
+/// Serial line settings requested by host.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LineCoding {
+    /// Baud rate in bits per second.
+    pub baudrate: u32,
+    /// Number of stop bits.
+    pub stop_bits: StopBits,
+    /// Parity type.
+    pub parity: Parity,
+    /// Number of data bits, one of 5, 6, 7, 8 or 16.
+    pub data_bits: u8,
+}
+
+impl LineCoding {
+    /// Parse line coding from data stage of set line coding request.
+    ///
+    /// Returns `None` if stop bits, parity or data bits field is invalid.
+    #[inline]
+    pub const fn from_bytes(val: [u8; 7]) -> Option<Self> {
+        let stop_bits = match val[4] {
+            0 => StopBits::One,
+            1 => StopBits::OnePointFive,
+            2 => StopBits::Two,
+            _ => return None,
+        };
+        let parity = match val[5] {
+            0 => Parity::None,
+            1 => Parity::Odd,
+            2 => Parity::Even,
+            3 => Parity::Mark,
+            4 => Parity::Space,
+            _ => return None,
+        };
+        let data_bits = match val[6] {
+            5 | 6 | 7 | 8 | 16 => val[6],
+            _ => return None,
+        };
+        Some(Self {
+            baudrate: u32::from_le_bytes([val[0], val[1], val[2], val[3]]),
+            stop_bits,
+            parity,
+            data_bits,
+        })
+    }
+    /// Encode line coding for data stage of get line coding request.
+    #[inline]
+    pub const fn to_bytes(self) -> [u8; 7] {
+        let baudrate = self.baudrate.to_le_bytes();
+        [
+            baudrate[0],
+            baudrate[1],
+            baudrate[2],
+            baudrate[3],
+            self.stop_bits as u8,
+            self.parity as u8,
+            self.data_bits,
+        ]
+    }
+}
+
+impl Default for LineCoding {
+    /// 115200 bauds, 8 data bits, no parity and 1 stop bit.
+    #[inline]
+    fn default() -> Self {
+        Self {
+            baudrate: 115_200,
+            stop_bits: StopBits::One,
+            parity: Parity::None,
+            data_bits: 8,
+        }
+    }
+}
+
+/// Communication interface state of a CDC-ACM function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AcmControl {
+    interface: u8,
+    line_coding: LineCoding,
+    control_line_state: u16,
+}
+
+impl AcmControl {
+    /// Create state of communication interface number `interface`, with default line coding.
+    #[inline]
+    pub const fn new(interface: u8) -> Self {
+        Self {
+            interface,
+            line_coding: LineCoding {
+                baudrate: 115_200,
+                stop_bits: StopBits::One,
+                parity: Parity::None,
+                data_bits: 8,
+            },
+            control_line_state: 0,
+        }
+    }
+    /// Handle host-to-device request `setup` with its data stage `data`.
+    ///
+    /// Supports set line coding and set control line state requests.
+    #[inline]
+    pub fn control_out(&mut self, setup: &SetupPacket, data: &[u8]) -> Result<(), RequestError> {
+        if setup.request_type != CLASS_INTERFACE_OUT || setup.index != self.interface as u16 {
+            return Err(RequestError::Unsupported);
+        }
+        match setup.request {
+            SET_LINE_CODING => {
+                let bytes = data.try_into().map_err(|_| RequestError::InvalidData)?;
+                self.line_coding =
+                    LineCoding::from_bytes(bytes).ok_or(RequestError::InvalidData)?;
+                Ok(())
+            }
+            SET_CONTROL_LINE_STATE => {
+                self.control_line_state = setup.value;
+                Ok(())
+            }
+            _ => Err(RequestError::Unsupported),
+        }
+    }
+    /// Handle device-to-host request `setup`, writing its data stage to `buf`.
+    ///
+    /// Supports get line coding request. Returns number of bytes written, at most
+    /// the length requested by host.
+    #[inline]
+    pub fn control_in(&self, setup: &SetupPacket, buf: &mut [u8]) -> Result<usize, RequestError> {
+        if setup.request_type != CLASS_INTERFACE_IN || setup.index != self.interface as u16 {
+            return Err(RequestError::Unsupported);
+        }
+        match setup.request {
+            GET_LINE_CODING => {
+                let bytes = self.line_coding.to_bytes();
+                let len = bytes.len().min(setup.length as usize).min(buf.len());
+                buf[..len].copy_from_slice(&bytes[..len]);
+                Ok(len)
+            }
+            _ => Err(RequestError::Unsupported),
+        }
+    }
+    /// Get line coding last set by host.
+    #[inline]
+    pub const fn line_coding(&self) -> LineCoding {
+        self.line_coding
+    }
+    /// Get baud rate last set by host.
+    #[inline]
+    pub const fn baudrate(&self) -> u32 {
+        self.line_coding.baudrate
+    }
+    /// Check if host asserts data terminal ready, which terminals do while port is open.
+    #[inline]
+    pub const fn is_dtr_asserted(&self) -> bool {
+        self.control_line_state & DTR != 0
+    }
+    /// Check if host asserts request to send.
+    #[inline]
+    pub const fn is_rts_asserted(&self) -> bool {
+        self.control_line_state & RTS != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AcmControl, LineCoding, Parity, RequestError, SetupPacket, StopBits};
+
+    #[test]
+    fn struct_line_coding_functions() {
+        let bytes = [0x00, 0x10, 0x0e, 0x00, 0x02, 0x02, 0x07];
+        let val = LineCoding::from_bytes(bytes).unwrap();
+        assert_eq!(val.baudrate, 921_600);
+        assert_eq!(val.stop_bits, StopBits::Two);
+        assert_eq!(val.parity, Parity::Even);
+        assert_eq!(val.data_bits, 7);
+        assert_eq!(val.to_bytes(), bytes);
+        assert_eq!(
+            LineCoding::default().to_bytes(),
+            [0x00, 0xc2, 0x01, 0x00, 0x00, 0x00, 0x08]
+        );
+        assert_eq!(LineCoding::from_bytes([0, 0, 0, 0, 3, 0, 8]), None);
+        assert_eq!(LineCoding::from_bytes([0, 0, 0, 0, 0, 5, 8]), None);
+        assert_eq!(LineCoding::from_bytes([0, 0, 0, 0, 0, 0, 9]), None);
+    }
+
+    #[test]
+    fn struct_acm_control_functions() {
+        let mut acm = AcmControl::new(0);
+        assert_eq!(acm.line_coding(), LineCoding::default());
+        assert_eq!(acm.baudrate(), 115_200);
+
+        let setup = SetupPacket::from_bytes([0x21, 0x20, 0, 0, 0, 0, 7, 0]);
+        assert_eq!(setup.length, 7);
+        acm.control_out(&setup, &[0x80, 0x25, 0x00, 0x00, 0x00, 0x00, 0x08])
+            .unwrap();
+        assert_eq!(acm.baudrate(), 9_600);
+        assert_eq!(
+            acm.control_out(&setup, &[0x80, 0x25, 0x00]),
+            Err(RequestError::InvalidData)
+        );
+        assert_eq!(acm.baudrate(), 9_600);
+
+        let setup = SetupPacket::from_bytes([0xa1, 0x21, 0, 0, 0, 0, 7, 0]);
+        let mut buf = [0; 8];
+        assert_eq!(acm.control_in(&setup, &mut buf), Ok(7));
+        assert_eq!(buf[..7], [0x80, 0x25, 0x00, 0x00, 0x00, 0x00, 0x08]);
+        let setup = SetupPacket::from_bytes([0xa1, 0x21, 0, 0, 0, 0, 4, 0]);
+        assert_eq!(acm.control_in(&setup, &mut buf), Ok(4));
+
+        let setup = SetupPacket::from_bytes([0x21, 0x22, 0x03, 0, 0, 0, 0, 0]);
+        acm.control_out(&setup, &[]).unwrap();
+        assert!(acm.is_dtr_asserted());
+        assert!(acm.is_rts_asserted());
+        let setup = SetupPacket::from_bytes([0x21, 0x22, 0x01, 0, 0, 0, 0, 0]);
+        acm.control_out(&setup, &[]).unwrap();
+        assert!(acm.is_dtr_asserted());
+        assert!(!acm.is_rts_asserted());
+
+        // Requests to another interface, or of other types, are left to the caller.
+        let setup = SetupPacket::from_bytes([0x21, 0x22, 0x03, 0, 1, 0, 0, 0]);
+        assert_eq!(acm.control_out(&setup, &[]), Err(RequestError::Unsupported));
+        let setup = SetupPacket::from_bytes([0x80, 0x06, 0, 1, 0, 0, 18, 0]);
+        assert_eq!(
+            acm.control_in(&setup, &mut buf),
+            Err(RequestError::Unsupported)
+        );
+    }
+}
@@ -0,0 +1,476 @@
+//! Human Interface Device class, for keyboards, mice, gamepads and the like.
+//!
+//! Report descriptors are built at compile time with [`HidReportBuilder`]. [`HidControl`]
+//! handles standard and class-specific control requests of a HID interface, so a device
+//! controller driver can forward them here and send input reports on interrupt endpoint.
+//!
+//! # Example
+//!
+//! A keyboard reporting up to 6 keys pressed at a time, with the boot protocol layout:
+//!
+//! ```
+//! use bouffalo_hal::usb::hid::{Collection, HidReportBuilder, ReportDescriptor, CONSTANT, VARIABLE};
+//!
+//! static KEYBOARD: HidReportBuilder<64> = HidReportBuilder::new()
+//!     .usage_page(0x01) // Generic desktop
+//!     .usage(0x06) // Keyboard
+//!     .collection(Collection::Application)
+//!     // Modifier keys, one bit each.
+//!     .usage_page(0x07) // Keyboard/keypad
+//!     .usage_minimum(0xe0)
+//!     .usage_maximum(0xe7)
+//!     .logical_minimum(0)
+//!     .logical_maximum(1)
+//!     .report_size(1)
+//!     .report_count(8)
+//!     .input(VARIABLE)
+//!     // Reserved byte.
+//!     .report_count(1)
+//!     .report_size(8)
+//!     .input(CONSTANT)
+//!     // Keyboard lights.
+//!     .report_count(5)
+//!     .report_size(1)
+//!     .usage_page(0x08) // LEDs
+//!     .usage_minimum(1)
+//!     .usage_maximum(5)
+//!     .output(VARIABLE)
+//!     .report_count(1)
+//!     .report_size(3)
+//!     .output(CONSTANT)
+//!     // Codes of pressed keys.
+//!     .report_count(6)
+//!     .report_size(8)
+//!     .logical_minimum(0)
+//!     .logical_maximum(0x65)
+//!     .usage_page(0x07)
+//!     .usage_minimum(0)
+//!     .usage_maximum(0x65)
+//!     .input(0)
+//!     .end_collection();
+//!
+//! let descriptor = ReportDescriptor::new(KEYBOARD.as_bytes());
+//! assert_eq!(descriptor.as_bytes().len(), 63);
+//!
+//! // Left shift held with 'a' pressed; 8-byte report sent on interrupt endpoint.
+//! let report: [u8; 8] = [0x02, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00];
+//! # let _ = report;
+//! ```
+use super::{RequestError, SetupPacket};
+
+/// Request type of standard device-to-host requests to an interface.
+const STANDARD_INTERFACE_IN: u8 = 0x81;
+/// Request type of class-specific host-to-device requests to an interface.
+const CLASS_INTERFACE_OUT: u8 = 0x21;
+/// Request type of class-specific device-to-host requests to an interface.
+const CLASS_INTERFACE_IN: u8 = 0xa1;
+/// Standard get descriptor request.
+const GET_DESCRIPTOR: u8 = 0x06;
+/// Descriptor type of report descriptor.
+const REPORT_DESCRIPTOR_TYPE: u8 = 0x22;
+/// Get idle rate request.
+const GET_IDLE: u8 = 0x02;
+/// Get protocol request.
+const GET_PROTOCOL: u8 = 0x03;
+/// Set report request.
+const SET_REPORT: u8 = 0x09;
+/// Set idle rate request.
+const SET_IDLE: u8 = 0x0a;
+/// Set protocol request.
+const SET_PROTOCOL: u8 = 0x0b;
+/// Report type of output reports.
+const OUTPUT_REPORT_TYPE: u8 = 0x02;
+/// Longest output report kept, one full speed control packet.
+const MAX_OUTPUT_REPORT: usize = 64;
+
+/// Main item flag of constant data, e.g. padding.
+pub const CONSTANT: u8 = 1 << 0;
+/// Main item flag of one field per usage, rather than an array of usage indices.
+pub const VARIABLE: u8 = 1 << 1;
+/// Main item flag of values relative to previous report.
+pub const RELATIVE: u8 = 1 << 2;
+
+/// HID report descriptor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ReportDescriptor(&'static [u8]);
+
+impl ReportDescriptor {
+    /// Wrap encoded report descriptor `val`.
+    #[inline]
+    pub const fn new(val: &'static [u8]) -> Self {
+        Self(val)
+    }
+    /// Get encoded report descriptor.
+    #[inline]
+    pub const fn as_bytes(&self) -> &'static [u8] {
+        self.0
+    }
+}
+
+/// Collection kind.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Collection {
+    /// Group of items with a common physical source.
+    Physical = 0,
+    /// Top level group an application sees as one device.
+    Application = 1,
+    /// Group of items forming one data structure.
+    Logical = 2,
+}
+
+/// Report descriptor encoder holding up to `N` bytes.
+///
+/// All functions are `const`, so descriptors are built at compile time into a `static`.
+/// Items are encoded in the shortest form holding their value.
+#[derive(Clone, Copy, Debug)]
+pub struct HidReportBuilder<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> HidReportBuilder<N> {
+    /// Create an empty report descriptor.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            buf: [0; N],
+            len: 0,
+        }
+    }
+    /// Add usage page global item.
+    #[inline]
+    pub const fn usage_page(self, val: u16) -> Self {
+        self.unsigned_item(0x04, val as u32)
+    }
+    /// Add usage local item.
+    #[inline]
+    pub const fn usage(self, val: u16) -> Self {
+        self.unsigned_item(0x08, val as u32)
+    }
+    /// Add usage minimum local item.
+    #[inline]
+    pub const fn usage_minimum(self, val: u16) -> Self {
+        self.unsigned_item(0x18, val as u32)
+    }
+    /// Add usage maximum local item.
+    #[inline]
+    pub const fn usage_maximum(self, val: u16) -> Self {
+        self.unsigned_item(0x28, val as u32)
+    }
+    /// Add logical minimum global item.
+    #[inline]
+    pub const fn logical_minimum(self, val: i32) -> Self {
+        self.signed_item(0x14, val)
+    }
+    /// Add logical maximum global item.
+    #[inline]
+    pub const fn logical_maximum(self, val: i32) -> Self {
+        self.signed_item(0x24, val)
+    }
+    /// Add report size global item, the number of bits in each field.
+    #[inline]
+    pub const fn report_size(self, val: u8) -> Self {
+        self.unsigned_item(0x74, val as u32)
+    }
+    /// Add report count global item, the number of fields in following main items.
+    #[inline]
+    pub const fn report_count(self, val: u8) -> Self {
+        self.unsigned_item(0x94, val as u32)
+    }
+    /// Add report identifier global item, prefixing reports with `val`.
+    #[inline]
+    pub const fn report_id(self, val: u8) -> Self {
+        self.unsigned_item(0x84, val as u32)
+    }
+    /// Add input main item with `flags`, e.g. [`VARIABLE`].
+    #[inline]
+    pub const fn input(self, flags: u8) -> Self {
+        self.unsigned_item(0x80, flags as u32)
+    }
+    /// Add output main item with `flags`, e.g. [`VARIABLE`].
+    #[inline]
+    pub const fn output(self, flags: u8) -> Self {
+        self.unsigned_item(0x90, flags as u32)
+    }
+    /// Add feature main item with `flags`, e.g. [`VARIABLE`].
+    #[inline]
+    pub const fn feature(self, flags: u8) -> Self {
+        self.unsigned_item(0xb0, flags as u32)
+    }
+    /// Begin a collection of `kind`.
+    #[inline]
+    pub const fn collection(self, kind: Collection) -> Self {
+        self.unsigned_item(0xa0, kind as u32)
+    }
+    /// End the innermost collection.
+    #[inline]
+    pub const fn end_collection(self) -> Self {
+        self.item(0xc0, 0, 0)
+    }
+    /// Get encoded report descriptor.
+    #[inline]
+    pub const fn as_bytes(&self) -> &[u8] {
+        self.buf.split_at(self.len).0
+    }
+
+    #[inline]
+    const fn unsigned_item(self, prefix: u8, val: u32) -> Self {
+        let size = if val <= 0xff {
+            1
+        } else if val <= 0xffff {
+            2
+        } else {
+            4
+        };
+        self.item(prefix, val, size)
+    }
+    #[inline]
+    const fn signed_item(self, prefix: u8, val: i32) -> Self {
+        let size = if val >= i8::MIN as i32 && val <= i8::MAX as i32 {
+            1
+        } else if val >= i16::MIN as i32 && val <= i16::MAX as i32 {
+            2
+        } else {
+            4
+        };
+        self.item(prefix, val as u32, size)
+    }
+    /// Append item of `prefix` with `size` bytes of `data`, little endian.
+    ///
+    /// # Panics
+    ///
+    /// If the item doesn't fit in `N` bytes, which fails compilation of a `static`.
+    #[inline]
+    const fn item(mut self, prefix: u8, data: u32, size: usize) -> Self {
+        assert!(
+            self.len + 1 + size <= N,
+            "report descriptor exceeds builder size"
+        );
+        let size_code = match size {
+            0 => 0,
+            1 => 1,
+            2 => 2,
+            _ => 3,
+        };
+        self.buf[self.len] = prefix | size_code;
+        let bytes = data.to_le_bytes();
+        let mut i = 0;
+        while i < size {
+            self.buf[self.len + 1 + i] = bytes[i];
+            i += 1;
+        }
+        self.len += 1 + size;
+        self
+    }
+}
+
+impl<const N: usize> Default for HidReportBuilder<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// HID interface state.
+#[derive(Clone, Debug)]
+pub struct HidControl {
+    interface: u8,
+    report_descriptor: ReportDescriptor,
+    idle_rate: u8,
+    boot_protocol: bool,
+    output_report: [u8; MAX_OUTPUT_REPORT],
+    output_len: Option<usize>,
+}
+
+impl HidControl {
+    /// Create state of HID interface number `interface` described by `report_descriptor`.
+    #[inline]
+    pub const fn new(interface: u8, report_descriptor: ReportDescriptor) -> Self {
+        Self {
+            interface,
+            report_descriptor,
+            idle_rate: 0,
+            boot_protocol: false,
+            output_report: [0; MAX_OUTPUT_REPORT],
+            output_len: None,
+        }
+    }
+    /// Handle host-to-device request `setup` with its data stage `data`.
+    ///
+    /// Supports set report for output reports, set idle and set protocol requests.
+    #[inline]
+    pub fn control_out(&mut self, setup: &SetupPacket, data: &[u8]) -> Result<(), RequestError> {
+        if setup.request_type != CLASS_INTERFACE_OUT || setup.index != self.interface as u16 {
+            return Err(RequestError::Unsupported);
+        }
+        match setup.request {
+            SET_REPORT if (setup.value >> 8) as u8 == OUTPUT_REPORT_TYPE => {
+                if data.len() > MAX_OUTPUT_REPORT {
+                    return Err(RequestError::InvalidData);
+                }
+                self.output_report[..data.len()].copy_from_slice(data);
+                self.output_len = Some(data.len());
+                Ok(())
+            }
+            SET_IDLE => {
+                self.idle_rate = (setup.value >> 8) as u8;
+                Ok(())
+            }
+            SET_PROTOCOL => {
+                self.boot_protocol = setup.value == 0;
+                Ok(())
+            }
+            _ => Err(RequestError::Unsupported),
+        }
+    }
+    /// Handle device-to-host request `setup`, writing its data stage to `buf`.
+    ///
+    /// Supports get report descriptor, get idle and get protocol requests. Returns number
+    /// of bytes written, at most the length requested by host.
+    #[inline]
+    pub fn control_in(&self, setup: &SetupPacket, buf: &mut [u8]) -> Result<usize, RequestError> {
+        if setup.index != self.interface as u16 {
+            return Err(RequestError::Unsupported);
+        }
+        let idle_rate = [self.idle_rate];
+        let protocol = [!self.boot_protocol as u8];
+        let bytes: &[u8] = match (setup.request_type, setup.request) {
+            (STANDARD_INTERFACE_IN, GET_DESCRIPTOR)
+                if (setup.value >> 8) as u8 == REPORT_DESCRIPTOR_TYPE =>
+            {
+                self.report_descriptor.as_bytes()
+            }
+            (CLASS_INTERFACE_IN, GET_IDLE) => &idle_rate,
+            (CLASS_INTERFACE_IN, GET_PROTOCOL) => &protocol,
+            _ => return Err(RequestError::Unsupported),
+        };
+        let len = bytes.len().min(setup.length as usize).min(buf.len());
+        buf[..len].copy_from_slice(&bytes[..len]);
+        Ok(len)
+    }
+    /// Take the output report last sent by host, e.g. keyboard lights, into `buf`.
+    ///
+    /// Returns number of bytes copied, or `None` if no report arrived since last take.
+    #[inline]
+    pub fn take_output_report(&mut self, buf: &mut [u8]) -> Option<usize> {
+        let len = self.output_len.take()?.min(buf.len());
+        buf[..len].copy_from_slice(&self.output_report[..len]);
+        Some(len)
+    }
+    /// Get idle rate set by host, in units of 4 milliseconds; zero means report only on change.
+    #[inline]
+    pub const fn idle_rate(&self) -> u8 {
+        self.idle_rate
+    }
+    /// Check if host selected boot protocol, as BIOS does with keyboards and mice.
+    #[inline]
+    pub const fn is_boot_protocol(&self) -> bool {
+        self.boot_protocol
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Collection, HidControl, HidReportBuilder, ReportDescriptor, RequestError, SetupPacket,
+        CONSTANT, RELATIVE, VARIABLE,
+    };
+
+    static MOUSE: HidReportBuilder<64> = HidReportBuilder::new()
+        .usage_page(0x01)
+        .usage(0x02)
+        .collection(Collection::Application)
+        .usage(0x01)
+        .collection(Collection::Physical)
+        .usage_page(0x09)
+        .usage_minimum(1)
+        .usage_maximum(3)
+        .logical_minimum(0)
+        .logical_maximum(1)
+        .report_count(3)
+        .report_size(1)
+        .input(VARIABLE)
+        .report_count(1)
+        .report_size(5)
+        .input(CONSTANT)
+        .usage_page(0x01)
+        .usage(0x30)
+        .usage(0x31)
+        .logical_minimum(-127)
+        .logical_maximum(127)
+        .report_size(8)
+        .report_count(2)
+        .input(VARIABLE | RELATIVE)
+        .end_collection()
+        .end_collection();
+
+    #[test]
+    fn struct_hid_report_builder_functions() {
+        // Boot protocol mouse from HID specification appendix.
+        assert_eq!(
+            MOUSE.as_bytes(),
+            [
+                0x05, 0x01, 0x09, 0x02, 0xa1, 0x01, 0x09, 0x01, 0xa1, 0x00, 0x05, 0x09, 0x19, 0x01,
+                0x29, 0x03, 0x15, 0x00, 0x25, 0x01, 0x95, 0x03, 0x75, 0x01, 0x81, 0x02, 0x95, 0x01,
+                0x75, 0x05, 0x81, 0x01, 0x05, 0x01, 0x09, 0x30, 0x09, 0x31, 0x15, 0x81, 0x25, 0x7f,
+                0x75, 0x08, 0x95, 0x02, 0x81, 0x06, 0xc0, 0xc0
+            ]
+        );
+        let val = HidReportBuilder::<16>::new()
+            .usage_page(0xff00)
+            .logical_minimum(-200)
+            .logical_maximum(0x12345);
+        assert_eq!(
+            val.as_bytes(),
+            [0x06, 0x00, 0xff, 0x16, 0x38, 0xff, 0x27, 0x45, 0x23, 0x01, 0x00]
+        );
+    }
+
+    #[test]
+    fn struct_hid_control_functions() {
+        let mut hid = HidControl::new(1, ReportDescriptor::new(MOUSE.as_bytes()));
+        let mut buf = [0; 64];
+
+        let setup = SetupPacket::from_bytes([0x81, 0x06, 0x00, 0x22, 1, 0, 50, 0]);
+        assert_eq!(hid.control_in(&setup, &mut buf), Ok(50));
+        assert_eq!(buf[..50], *MOUSE.as_bytes());
+        let setup = SetupPacket::from_bytes([0x81, 0x06, 0x00, 0x22, 1, 0, 0xff, 0]);
+        assert_eq!(hid.control_in(&setup, &mut buf), Ok(50));
+
+        let setup = SetupPacket::from_bytes([0x21, 0x0a, 0x00, 0x7d, 1, 0, 0, 0]);
+        hid.control_out(&setup, &[]).unwrap();
+        assert_eq!(hid.idle_rate(), 0x7d);
+        let setup = SetupPacket::from_bytes([0xa1, 0x02, 0, 0, 1, 0, 1, 0]);
+        assert_eq!(hid.control_in(&setup, &mut buf), Ok(1));
+        assert_eq!(buf[0], 0x7d);
+
+        assert!(!hid.is_boot_protocol());
+        let setup = SetupPacket::from_bytes([0x21, 0x0b, 0, 0, 1, 0, 0, 0]);
+        hid.control_out(&setup, &[]).unwrap();
+        assert!(hid.is_boot_protocol());
+        let setup = SetupPacket::from_bytes([0xa1, 0x03, 0, 0, 1, 0, 1, 0]);
+        assert_eq!(hid.control_in(&setup, &mut buf), Ok(1));
+        assert_eq!(buf[0], 0);
+
+        assert_eq!(hid.take_output_report(&mut buf), None);
+        let setup = SetupPacket::from_bytes([0x21, 0x09, 0x00, 0x02, 1, 0, 1, 0]);
+        hid.control_out(&setup, &[0x02]).unwrap();
+        assert_eq!(hid.take_output_report(&mut buf), Some(1));
+        assert_eq!(buf[0], 0x02);
+        assert_eq!(hid.take_output_report(&mut buf), None);
+
+        // Input reports, other interfaces and oversized output reports are rejected.
+        let setup = SetupPacket::from_bytes([0x21, 0x09, 0x00, 0x01, 1, 0, 1, 0]);
+        assert_eq!(
+            hid.control_out(&setup, &[0]),
+            Err(RequestError::Unsupported)
+        );
+        let setup = SetupPacket::from_bytes([0x21, 0x0a, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(hid.control_out(&setup, &[]), Err(RequestError::Unsupported));
+        let setup = SetupPacket::from_bytes([0x21, 0x09, 0x00, 0x02, 1, 0, 65, 0]);
+        assert_eq!(
+            hid.control_out(&setup, &[0; 65]),
+            Err(RequestError::InvalidData)
+        );
+    }
+}
@@ -1,3 +1,45 @@
 //! Universal Serial Bus peripheral.
 
+pub mod cdc_acm;
+pub mod hid;
 pub mod v1;
+
+/// Setup packet of a control transfer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SetupPacket {
+    /// Direction, type and recipient of request.
+    pub request_type: u8,
+    /// Request code.
+    pub request: u8,
+    /// Request specific value.
+    pub value: u16,
+    /// Request specific index, interface number for requests to an interface.
+    pub index: u16,
+    /// Number of bytes in data stage.
+    pub length: u16,
+}
+
+impl SetupPacket {
+    /// Parse setup packet from the 8 bytes received on control endpoint.
+    #[inline]
+    pub const fn from_bytes(val: [u8; 8]) -> Self {
+        Self {
+            request_type: val[0],
+            request: val[1],
+            value: u16::from_le_bytes([val[2], val[3]]),
+            index: u16::from_le_bytes([val[4], val[5]]),
+            length: u16::from_le_bytes([val[6], val[7]]),
+        }
+    }
+}
+
+/// Error handling a control request.
+///
+/// Device controller drivers respond to both errors by stalling the control endpoint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RequestError {
+    /// Request is not supported by this interface.
+    Unsupported,
+    /// Data stage of request is malformed.
+    InvalidData,
+}
@@ -45,6 +45,7 @@ MEMORY {
     PSEUDO_HEADER : ORIGIN = 0xA0000000 - 0x1000, LENGTH = 4K
     FLASH : ORIGIN = 0xA0000000, LENGTH = 4M - 4K
     OCRAM : ORIGIN = 0x62FC0000, LENGTH = 320K
+    PSRAM : ORIGIN = 0xA8000000, LENGTH = 4M
 }
 SECTIONS {
     .head : ALIGN(4) {
@@ -55,7 +56,7 @@ SECTIONS {
         KEEP(*(.head.base.flag));
         LONG(ADDR(.text) - ORIGIN(PSEUDO_HEADER));
         KEEP(*(.head.base.aes-region));
-        LONG(SIZEOF(.text) + SIZEOF(.rodata) + SIZEOF(.data));
+        LONG(SIZEOF(.text) + SIZEOF(.rodata) + SIZEOF(.data) + SIZEOF(.psram));
         KEEP(*(.head.base.hash));
         KEEP(*(.head.cpu));
         LONG(0);
@@ -87,10 +88,18 @@ SECTIONS {
         sdata = .;
         *(.data .data.*)
         *(.sdata .sdata.*)
+        *(.ocram .ocram.*)
         . = ALIGN(4);
         edata = .;
     } > OCRAM AT>FLASH
     sidata = LOADADDR(.data);
+    .psram : ALIGN(4) {
+        spsram = .;
+        *(.psram .psram.*)
+        . = ALIGN(4);
+        epsram = .;
+    } > PSRAM AT>FLASH
+    sipsram = LOADADDR(.psram);
     .bss (NOLOAD) : ALIGN(4) {
         *(.bss.uninit)
         sbss = .;
@@ -101,7 +110,10 @@ SECTIONS {
     /DISCARD/ : {
         *(.eh_frame)
     }
-}";
+}
+/* hooks */
+PROVIDE(__pre_init = default_pre_init);
+";
 
 #[cfg(feature = "bl808-mcu")]
 const LINKER_SCRIPT_BL808_MCU: &[u8] = b"
@@ -111,6 +123,7 @@ MEMORY {
     PSEUDO_HEADER : ORIGIN = 0x58000000 - 0x1000, LENGTH = 4K
     FLASH : ORIGIN = 0x58000000, LENGTH = 32M - 4K
     WRAM : ORIGIN = 0x62030000, LENGTH = 160K
+    PSRAM : ORIGIN = 0x50000000, LENGTH = 32M
 }
 SECTIONS {
     .head : ALIGN(4) {
@@ -121,7 +134,7 @@ SECTIONS {
         KEEP(*(.head.base.flag));
         LONG(ADDR(.text) - ORIGIN(PSEUDO_HEADER));
         KEEP(*(.head.base.aes-region));
-        LONG(SIZEOF(.text) + SIZEOF(.rodata) + SIZEOF(.data));
+        LONG(SIZEOF(.text) + SIZEOF(.rodata) + SIZEOF(.data) + SIZEOF(.psram));
         KEEP(*(.head.base.hash));
         KEEP(*(.head.cpu));
         LONG(0);
@@ -157,10 +170,20 @@ SECTIONS {
         sdata = .;
         *(.data .data.*)
         *(.sdata .sdata.*)
+        *(.wram .wram.*)
         . = ALIGN(4);
         edata = .;
     } > WRAM AT>FLASH
     sidata = LOADADDR(.data);
+    .psram : ALIGN(4) {
+        spsram = .;
+        *(.psram .psram.*)
+        . = ALIGN(4);
+        epsram = .;
+    } > PSRAM AT>FLASH
+    sipsram = LOADADDR(.psram);
+    sheap = epsram;
+    eheap = ORIGIN(PSRAM) + LENGTH(PSRAM);
     .bss (NOLOAD) : ALIGN(4) {
         *(.bss.uninit)
         sbss = .;
@@ -171,7 +194,10 @@ SECTIONS {
     /DISCARD/ : {
         *(.eh_frame)
     }
-}";
+}
+/* hooks */
+PROVIDE(__pre_init = default_pre_init);
+";
 
 #[cfg(feature = "bl808-dsp")]
 const LINKER_SCRIPT_BL808_DSP: &[u8] = b"
@@ -182,6 +208,7 @@ MEMORY {
     FLASH : ORIGIN = 0x58000000, LENGTH = 32M - 4K
     DRAM : ORIGIN = 0x3EFF7000, LENGTH = 4K
     VRAM : ORIGIN = 0x3F000000, LENGTH = 32K
+    PSRAM : ORIGIN = 0x52000000, LENGTH = 32M
 }
 SECTIONS {
     .head : ALIGN(8) {
@@ -192,7 +219,7 @@ SECTIONS {
         KEEP(*(.head.base.flag));
         LONG(ADDR(.text) - ORIGIN(PSEUDO_HEADER));
         KEEP(*(.head.base.aes-region));
-        LONG(SIZEOF(.text) + SIZEOF(.rodata) + SIZEOF(.data));
+        LONG(SIZEOF(.text) + SIZEOF(.rodata) + SIZEOF(.data) + SIZEOF(.psram));
         KEEP(*(.head.base.hash));
         KEEP(*(.head.cpu));
         LONG(0);
@@ -230,10 +257,20 @@ SECTIONS {
         sdata = .;
         *(.data .data.*)
         *(.sdata .sdata.*)
+        *(.vram .vram.*)
         . = ALIGN(8);
         edata = .;
     } > VRAM AT>FLASH
     sidata = LOADADDR(.data);
+    .psram : ALIGN(8) {
+        spsram = .;
+        *(.psram .psram.*)
+        . = ALIGN(8);
+        epsram = .;
+    } > PSRAM AT>FLASH
+    sipsram = LOADADDR(.psram);
+    sheap = epsram;
+    eheap = ORIGIN(PSRAM) + LENGTH(PSRAM);
     .bss (NOLOAD) : ALIGN(8) {
         *(.bss.uninit)
         sbss = .;
@@ -315,6 +352,8 @@ PROVIDE(wdt = default_handler);
 PROVIDE(audio = default_handler);
 PROVIDE(wl_all = default_handler);
 PROVIDE(pds = default_handler);
+/* hooks */
+PROVIDE(__pre_init = default_pre_init);
 ";
 
 #[cfg(feature = "bl808-lp")]
@@ -356,6 +395,8 @@ SECTIONS {
     .text : ALIGN(4) {
         stext = .;
         KEEP(*(.text.entry))
+        . = ALIGN(4);
+        *(.trap.trap-entry)
         *(.text .text.*)
         . = ALIGN(4);
         etext = .;
@@ -371,6 +412,7 @@ SECTIONS {
         sdata = .;
         *(.data .data.*)
         *(.sdata .sdata.*)
+        *(.ram .ram.*)
         . = ALIGN(4);
         edata = .;
     } > RAM AT>FLASH
@@ -385,7 +427,14 @@ SECTIONS {
     /DISCARD/ : {
         *(.eh_frame)
     }
-}";
+}
+/* exceptions */
+PROVIDE(exceptions = default_lp_exceptions);
+/* interrupts */
+PROVIDE(lp_interrupt = default_handler);
+/* hooks */
+PROVIDE(__pre_init = default_pre_init);
+";
 
 #[cfg(feature = "bl702")]
 const LINKER_SCRIPT_BL702: &[u8] = b"
@@ -431,6 +480,7 @@ SECTIONS {
         sdata = .;
         *(.data .data.*)
         *(.sdata .sdata.*)
+        *(.ocram .ocram.*)
         . = ALIGN(4);
         edata = .;
     } > OCRAM AT>XIP
@@ -445,4 +495,7 @@ SECTIONS {
     /DISCARD/ : {
         *(.eh_frame)
     }
-}";
+}
+/* hooks */
+PROVIDE(__pre_init = default_pre_init);
+";
@@ -4,7 +4,7 @@ use proc_macro::TokenStream;
 use proc_macro2::Span;
 use quote::quote;
 use syn::spanned::Spanned;
-use syn::{parse, parse_macro_input, ItemFn, ReturnType, Type, Visibility};
+use syn::{parse, parse_macro_input, Ident, Item, ItemFn, ReturnType, Type, Visibility};
 
 /// ROM runtime function entry.
 #[proc_macro_attribute]
@@ -199,3 +199,143 @@ pub fn exception(args: TokenStream, input: TokenStream) -> TokenStream {
     )
     .into()
 }
+
+/// Pre-initialization hook, run at start of reset before RAM initialization.
+///
+/// The function is called right after the stack pointer is set up, before `.bss` is
+/// zeroed, `.data` is copied from flash and trap handler is installed. It can configure
+/// pin multiplexing or external memory such as PSRAM that later initialization or the
+/// entry function depends on.
+///
+/// Only the runtime stack and peripheral registers are usable that early. Static variables
+/// must not be read or written, as they are not initialized and would be overwritten
+/// afterwards; heap, `Peripherals`, `Clocks` and panic handling output are not available,
+/// and traps are not handled. Access peripherals through raw register block pointers.
+#[proc_macro_attribute]
+pub fn pre_init(args: TokenStream, input: TokenStream) -> TokenStream {
+    if !args.is_empty() {
+        return parse::Error::new(
+            Span::call_site(),
+            "#[pre_init] attribute accepts no arguments",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let f = parse_macro_input!(input as ItemFn);
+
+    if !f.sig.inputs.is_empty() {
+        return parse::Error::new(
+            f.sig.inputs.span(),
+            "`#[pre_init]` function should not include any parameter",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let valid_signature = f.sig.constness.is_none()
+        && f.sig.asyncness.is_none()
+        && f.sig.unsafety.is_some()
+        && f.vis == Visibility::Inherited
+        && f.sig.abi.is_none()
+        && f.sig.generics.params.is_empty()
+        && f.sig.generics.where_clause.is_none()
+        && f.sig.variadic.is_none()
+        && match f.sig.output {
+            ReturnType::Default => true,
+            ReturnType::Type(_, ref ty) => match **ty {
+                Type::Tuple(ref tuple) => tuple.elems.is_empty(),
+                _ => false,
+            },
+        };
+
+    if !valid_signature {
+        return parse::Error::new(
+            f.sig.span(),
+            "`#[pre_init]` function must have signature `unsafe fn()`",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let attrs = f.attrs;
+    let stmts = f.block.stmts;
+    let ident = f.sig.ident;
+
+    quote!(
+        #(#attrs)*
+        #[export_name = "__pre_init"]
+        pub unsafe extern "C" fn #ident() {
+            #(#stmts)*
+        }
+    )
+    .into()
+}
+
+/// Place a function or static variable in a named memory region.
+///
+/// The attribute takes the lowercase name of a linker script memory region and expands
+/// to a `#[link_section]` the runtime linker script places in that region. Items in
+/// on-chip RAM regions are copied from flash with `.data` on startup, so functions there
+/// run without flash cache misses. Items in `psram` are copied from flash after the
+/// `#[pre_init]` hook returns, which must therefore initialize the PSRAM controller
+/// whenever anything is placed there.
+///
+/// Available regions per chip feature:
+///
+/// | Feature     | Regions            |
+/// |-------------|--------------------|
+/// | `bl616`     | `ocram`, `psram`   |
+/// | `bl808-mcu` | `wram`, `psram`    |
+/// | `bl808-dsp` | `vram`, `psram`    |
+/// | `bl808-lp`  | `ram`              |
+/// | `bl702`     | `ocram`            |
+///
+/// On BL808, the MCU and DSP cores are linked separately and share one PSRAM; the MCU
+/// image uses its lower 32 MiB and the DSP image its upper 32 MiB. Items in `psram` are
+/// placed at the start of the half, and the heap of `alloc` feature only takes the rest
+/// of the half after them.
+///
+/// # Examples
+///
+/// ```ignore
+/// #[link_region(psram)]
+/// static SINE_TABLE: [i16; 4096] = make_sine_table();
+///
+/// #[link_region(ocram)]
+/// #[inline(never)]
+/// fn filter(samples: &mut [i16]) {
+///     // ...
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn link_region(args: TokenStream, input: TokenStream) -> TokenStream {
+    let region = parse_macro_input!(args as Ident);
+
+    if let Some(syntax_err) = soc::check_link_region(&region) {
+        return syntax_err.to_compile_error().into();
+    }
+
+    let item = parse_macro_input!(input as Item);
+
+    let ident = match item {
+        Item::Fn(ref f) => &f.sig.ident,
+        Item::Static(ref s) => &s.ident,
+        _ => {
+            return parse::Error::new(
+                item.span(),
+                "`#[link_region]` can only be applied to functions and static variables",
+            )
+            .to_compile_error()
+            .into();
+        }
+    };
+
+    let section = format!(".{}.{}", region, ident);
+
+    quote!(
+        #[link_section = #section]
+        #item
+    )
+    .into()
+}
@@ -19,3 +19,43 @@ pub fn check_interrupt_name(ident: &Ident) -> Option<Error> {
     let _ = ident;
     None
 }
+
+/// Memory regions of the current chip accepted by `#[link_region]`.
+#[cfg(feature = "bl616")]
+pub const LINK_REGIONS: &[&str] = &["ocram", "psram"];
+#[cfg(feature = "bl808-mcu")]
+pub const LINK_REGIONS: &[&str] = &["wram", "psram"];
+#[cfg(feature = "bl808-dsp")]
+pub const LINK_REGIONS: &[&str] = &["vram", "psram"];
+#[cfg(feature = "bl808-lp")]
+pub const LINK_REGIONS: &[&str] = &["ram"];
+#[cfg(feature = "bl702")]
+pub const LINK_REGIONS: &[&str] = &["ocram"];
+
+pub fn check_link_region(ident: &Ident) -> Option<Error> {
+    #[cfg(any(
+        feature = "bl616",
+        feature = "bl808-mcu",
+        feature = "bl808-dsp",
+        feature = "bl808-lp",
+        feature = "bl702"
+    ))]
+    if !LINK_REGIONS.contains(&format!("{}", ident).as_str()) {
+        return Some(Error::new(
+            ident.span(),
+            format!(
+                "invalid `#[link_region]` memory region. Must be one of: {}.",
+                LINK_REGIONS.join(", ")
+            ),
+        ));
+    }
+    #[cfg(not(any(
+        feature = "bl616",
+        feature = "bl808-mcu",
+        feature = "bl808-dsp",
+        feature = "bl808-lp",
+        feature = "bl702"
+    )))]
+    let _ = ident;
+    None
+}
@@ -0,0 +1,97 @@
+//! Global heap allocator over PSRAM.
+//!
+//! With the `alloc` feature enabled on BL808 MCU or DSP core, this module registers a
+//! linked list allocator as the `#[global_allocator]`, so that `alloc` collections can be
+//! used once [`init_heap`] is called. The heap takes the memory region returned by
+//! `bouffalo_hal::psram::init_psram`; PSRAM must be initialized before the heap, and any
+//! allocation made before [`init_heap`] fails.
+//!
+//! The heap never covers the whole PSRAM. On BL808 the MCU and DSP images each own one
+//! 32 MiB half of PSRAM, and statics placed by `#[link_region(psram)]` sit at the start
+//! of the half; the heap only takes the rest of the current core's half, from linker
+//! symbol `sheap` to `eheap`.
+//!
+//! Allocator state is guarded by disabling machine interrupts, thus it can be used from
+//! both thread and interrupt contexts on one core.
+
+use crate::interrupt;
+use bouffalo_hal::psram::PsramRegion;
+use core::alloc::{GlobalAlloc, Layout};
+use core::cell::UnsafeCell;
+use core::ptr::{self, NonNull};
+use linked_list_allocator::Heap;
+
+struct RtHeap {
+    heap: UnsafeCell<Heap>,
+}
+
+// Accesses to inner heap are serialized by `interrupt::free`.
+unsafe impl Sync for RtHeap {}
+
+#[global_allocator]
+static HEAP: RtHeap = RtHeap {
+    heap: UnsafeCell::new(Heap::empty()),
+};
+
+unsafe impl GlobalAlloc for RtHeap {
+    #[inline]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        interrupt::free(|| {
+            let heap = unsafe { &mut *self.heap.get() };
+            heap.allocate_first_fit(layout)
+                .map_or(ptr::null_mut(), |p| p.as_ptr())
+        })
+    }
+    #[inline]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        interrupt::free(|| {
+            let heap = unsafe { &mut *self.heap.get() };
+            unsafe { heap.deallocate(NonNull::new_unchecked(ptr), layout) }
+        })
+    }
+}
+
+/// Initialize global heap over PSRAM region.
+///
+/// Only the part of `region` after `#[link_region(psram)]` statics and inside the PSRAM
+/// half of current core is handed over to the allocator. This function must only be
+/// called once; it panics if the heap is already initialized, or if no PSRAM is left.
+#[inline]
+pub fn init_heap(region: PsramRegion) {
+    extern "C" {
+        static sheap: u8;
+        static eheap: u8;
+    }
+    let (start, end) = heap_bounds(
+        (region.start(), region.end()),
+        (ptr::addr_of!(sheap) as usize, ptr::addr_of!(eheap) as usize),
+    );
+    assert!(start < end, "no PSRAM left for heap");
+    interrupt::free(|| {
+        let heap = unsafe { &mut *HEAP.heap.get() };
+        assert!(heap.size() == 0, "heap is already initialized");
+        // SAFETY: `PsramRegion` is only created by `init_psram`, and consuming it here
+        // guarantees the region is valid and handed over to the heap exactly once. Linker
+        // places neither statics nor the other core's image between `sheap` and `eheap`.
+        unsafe { heap.init(start as *mut u8, end - start) };
+    })
+}
+
+/// Intersection of initialized PSRAM `region` and heap range `linker` left by linker.
+#[inline]
+fn heap_bounds(region: (usize, usize), linker: (usize, usize)) -> (usize, usize) {
+    (region.0.max(linker.0), region.1.min(linker.1))
+}
+
+/// Number of bytes currently allocated on the heap.
+#[inline]
+pub fn used() -> usize {
+    interrupt::free(|| unsafe { &*HEAP.heap.get() }.used())
+}
+
+/// Number of bytes currently free on the heap.
+#[inline]
+pub fn free() -> usize {
+    interrupt::free(|| unsafe { &*HEAP.heap.get() }.free())
+}
+
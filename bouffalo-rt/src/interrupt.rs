@@ -0,0 +1,140 @@
+//! Machine interrupt enable and critical sections.
+//!
+//! Critical sections here disable machine interrupts of the current core by clearing
+//! `mstatus.MIE`, and restore its previous value on exit, so they nest correctly. They
+//! don't exclude other cores; data shared between cores needs a lock in addition.
+//!
+//! With the `critical-section-single-hart` feature enabled, the runtime also provides
+//! the implementation of the `critical-section` crate, which drivers of `bouffalo-hal`
+//! and ecosystem crates like `heapless` use to guard shared state. Only one crate in a
+//! program may provide this implementation.
+
+/// Machine interrupt enable bit of `mstatus`.
+const MSTATUS_MIE: usize = 1 << 3;
+
+/// Disable machine interrupts on the current core.
+#[inline]
+pub fn disable() {
+    clear_mie();
+}
+
+/// Enable machine interrupts on the current core.
+///
+/// # Safety
+///
+/// Must not be called inside a critical section, as interrupt handlers may then run
+/// while the section is still accessing shared state.
+#[inline]
+pub unsafe fn enable() {
+    set_mie();
+}
+
+/// Check if machine interrupts are enabled on the current core.
+#[inline]
+pub fn is_enabled() -> bool {
+    read_mstatus() & MSTATUS_MIE != 0
+}
+
+/// Execute closure `f` with machine interrupts disabled on the current core.
+///
+/// Previous interrupt enable state is restored when `f` returns.
+#[inline]
+pub fn free<T>(f: impl FnOnce() -> T) -> T {
+    let _guard = InterruptGuard::new();
+    f()
+}
+
+/// Interrupts disabled for the lifetime of this guard.
+///
+/// Dropping the guard enables interrupts again only if they were enabled when it was
+/// created.
+#[must_use = "interrupts are enabled again as soon as the guard is dropped"]
+pub struct InterruptGuard {
+    was_enabled: bool,
+}
+
+impl InterruptGuard {
+    /// Disable machine interrupts and remember previous state.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            was_enabled: clear_mie() & MSTATUS_MIE != 0,
+        }
+    }
+}
+
+impl Default for InterruptGuard {
+    /// Disable machine interrupts and remember previous state.
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for InterruptGuard {
+    #[inline]
+    fn drop(&mut self) {
+        if self.was_enabled {
+            set_mie();
+        }
+    }
+}
+
+#[cfg(feature = "critical-section-single-hart")]
+struct SingleHartCriticalSection;
+
+#[cfg(feature = "critical-section-single-hart")]
+critical_section::set_impl!(SingleHartCriticalSection);
+
+#[cfg(feature = "critical-section-single-hart")]
+unsafe impl critical_section::Impl for SingleHartCriticalSection {
+    #[inline]
+    unsafe fn acquire() -> critical_section::RawRestoreState {
+        clear_mie() & MSTATUS_MIE != 0
+    }
+    #[inline]
+    unsafe fn release(was_enabled: critical_section::RawRestoreState) {
+        if was_enabled {
+            set_mie();
+        }
+    }
+}
+
+/// Clear `mstatus.MIE` and return previous `mstatus` value.
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+#[inline(always)]
+fn clear_mie() -> usize {
+    let mstatus: usize;
+    unsafe { core::arch::asm!("csrrci {}, mstatus, 8", out(reg) mstatus) };
+    mstatus
+}
+
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+#[inline(always)]
+fn set_mie() {
+    unsafe { core::arch::asm!("csrsi mstatus, 8") };
+}
+
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+#[inline(always)]
+fn read_mstatus() -> usize {
+    let mstatus: usize;
+    unsafe { core::arch::asm!("csrr {}, mstatus", out(reg) mstatus) };
+    mstatus
+}
+
+#[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+#[inline(always)]
+fn clear_mie() -> usize {
+    0
+}
+
+#[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+#[inline(always)]
+fn set_mie() {}
+
+#[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+#[inline(always)]
+fn read_mstatus() -> usize {
+    0
+}
@@ -5,15 +5,59 @@
 #[macro_use]
 mod macros;
 
-pub use bouffalo_rt_macros::{entry, exception, interrupt};
+pub use bouffalo_rt_macros::{entry, exception, interrupt, link_region, pre_init};
 
 pub mod arch;
+#[cfg(all(feature = "alloc", any(feature = "bl808-mcu", feature = "bl808-dsp")))]
+pub mod heap;
+#[cfg(all(
+    feature = "alloc",
+    any(feature = "bl616", feature = "bl702", feature = "bl808-lp")
+))]
+compile_error!("`alloc` feature needs PSRAM support, only available on BL808 MCU and DSP cores");
+pub mod interrupt;
+#[cfg(feature = "panic-uart")]
+pub mod panic_uart;
+#[cfg(feature = "semihosting")]
+pub mod semihosting;
 pub mod soc;
 
 pub mod prelude {
     pub use bouffalo_hal::prelude::*;
 }
 
+/// Print to debugger console over semihosting; no-op without the `semihosting` feature.
+#[cfg(feature = "semihosting")]
+#[macro_export]
+macro_rules! hprint {
+    ($($arg:tt)*) => {
+        $crate::semihosting::_print(format_args!($($arg)*))
+    };
+}
+
+/// Print to debugger console over semihosting; no-op without the `semihosting` feature.
+#[cfg(not(feature = "semihosting"))]
+#[macro_export]
+macro_rules! hprint {
+    ($($arg:tt)*) => {
+        if false {
+            let _ = format_args!($($arg)*);
+        }
+    };
+}
+
+/// Print to debugger console over semihosting with a newline; no-op without the
+/// `semihosting` feature.
+#[macro_export]
+macro_rules! hprintln {
+    () => {
+        $crate::hprint!("\n")
+    };
+    ($($arg:tt)*) => {
+        $crate::hprint!("{}\n", format_args!($($arg)*))
+    };
+}
+
 cfg_if::cfg_if! {
     if #[cfg(any(feature = "bl808-mcu", feature = "bl808-dsp", feature = "bl808-lp"))] {
         pub use soc::bl808::{Peripherals, Clocks};
@@ -42,6 +86,10 @@ cfg_if::cfg_if! {
 #[no_mangle]
 pub extern "C" fn default_handler() {}
 
+#[doc(hidden)]
+#[no_mangle]
+pub extern "C" fn default_pre_init() {}
+
 /// Flash configuration in ROM header.
 #[repr(C)]
 pub struct HalFlashConfig {
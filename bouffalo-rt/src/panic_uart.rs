@@ -0,0 +1,145 @@
+//! Panic handler that reports over a serial port.
+//!
+//! With the `panic-uart` feature enabled, this module provides a `#[panic_handler]` that
+//! prints the panic message, machine trap registers, `ra`, `sp`, `gp` and `tp`, the
+//! callee-saved frame pointer `s0` and a short frame pointer backtrace to a Universal
+//! Asynchronous Receiver/Transmitter, then halts.
+//!
+//! By default the handler writes to UART0 with its current settings. Use [`set_uart`]
+//! to select another serial port, and [`set_baudrate`] if the handler should reprogram
+//! the transmit bit period before printing.
+//!
+//! Backtrace requires the program to be compiled with `-C force-frame-pointers=yes`;
+//! otherwise frames after the first one would be meaningless and walking stops early.
+
+use bouffalo_hal::uart::RegisterBlock;
+use core::fmt::{self, Write};
+use core::panic::PanicInfo;
+use core::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use embedded_time::rate::{Baud, Hertz};
+
+#[cfg(feature = "bl702")]
+const DEFAULT_UART_BASE: usize = 0x4000A000;
+#[cfg(not(feature = "bl702"))]
+const DEFAULT_UART_BASE: usize = 0x2000A000;
+
+/// Maximum number of backtrace frames to print.
+const MAX_BACKTRACE_DEPTH: usize = 16;
+
+static UART_BASE: AtomicUsize = AtomicUsize::new(DEFAULT_UART_BASE);
+static BIT_PERIOD: AtomicU32 = AtomicU32::new(0);
+
+/// Select serial port used by panic handler from its register block base address.
+#[inline]
+pub fn set_uart(base: usize) {
+    UART_BASE.store(base, Ordering::Relaxed);
+}
+
+/// Reprogram transmit baudrate of panic serial port before printing.
+///
+/// `uart_clock` is the source clock of selected serial port, e.g. from `Clocks::uart_clock`.
+/// Baudrates impossible for given clock are ignored, and current settings are kept.
+#[inline]
+pub fn set_baudrate(baudrate: Baud, uart_clock: Hertz) {
+    let interval = uart_clock.0 / baudrate.0;
+    if (1..=65535).contains(&interval) {
+        BIT_PERIOD.store(interval, Ordering::Relaxed);
+    }
+}
+
+struct PanicWriter<'a>(&'a RegisterBlock);
+
+impl Write for PanicWriter<'_> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for &byte in s.as_bytes() {
+            while self.0.fifo_config_1.read().transmit_available_bytes() == 0 {
+                core::hint::spin_loop();
+            }
+            unsafe { self.0.fifo_write.write(byte) };
+        }
+        Ok(())
+    }
+}
+
+#[panic_handler]
+fn panic(info: &PanicInfo) -> ! {
+    crate::interrupt::disable();
+    let uart = unsafe { &*(UART_BASE.load(Ordering::Relaxed) as *const RegisterBlock) };
+    let interval = BIT_PERIOD.load(Ordering::Relaxed);
+    if interval != 0 {
+        unsafe {
+            uart.bit_period
+                .modify(|v| v.set_transmit_time_interval(interval as u16));
+            uart.transmit_config.modify(|v| v.enable_txd());
+        }
+    }
+    let mut w = PanicWriter(uart);
+    writeln!(w, "\r\n*** panic: {}\r", info.message()).ok();
+    if let Some(location) = info.location() {
+        writeln!(
+            w,
+            "    at {}:{}:{}\r",
+            location.file(),
+            location.line(),
+            location.column()
+        )
+        .ok();
+    }
+    dump_registers(&mut w);
+    loop {
+        core::hint::spin_loop();
+    }
+}
+
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+#[inline(always)]
+fn dump_registers(w: &mut PanicWriter) {
+    use core::arch::asm;
+    let (mepc, mcause, mtval, mstatus): (usize, usize, usize, usize);
+    let (ra, sp, gp, tp, fp): (usize, usize, usize, usize, usize);
+    unsafe {
+        asm!("csrr {}, mepc", out(reg) mepc);
+        asm!("csrr {}, mcause", out(reg) mcause);
+        asm!("csrr {}, mtval", out(reg) mtval);
+        asm!("csrr {}, mstatus", out(reg) mstatus);
+        asm!("mv {}, ra", out(reg) ra);
+        asm!("mv {}, sp", out(reg) sp);
+        asm!("mv {}, gp", out(reg) gp);
+        asm!("mv {}, tp", out(reg) tp);
+        asm!("mv {}, s0", out(reg) fp);
+    }
+    writeln!(
+        w,
+        "    mepc {mepc:#x} mcause {mcause:#x} mtval {mtval:#x} mstatus {mstatus:#x}\r"
+    )
+    .ok();
+    writeln!(
+        w,
+        "    ra {ra:#x} sp {sp:#x} gp {gp:#x} tp {tp:#x} s0 {fp:#x}\r"
+    )
+    .ok();
+    writeln!(w, "    backtrace:\r").ok();
+    let word = core::mem::size_of::<usize>();
+    let mut fp = fp;
+    for depth in 0..MAX_BACKTRACE_DEPTH {
+        if fp == 0 || fp % word != 0 || fp < sp {
+            break;
+        }
+        // RISC-V frame record: return address at fp - 1 word, previous frame pointer at fp - 2 words.
+        let ra = unsafe { *((fp - word) as *const usize) };
+        let prev_fp = unsafe { *((fp - 2 * word) as *const usize) };
+        if ra == 0 {
+            break;
+        }
+        writeln!(w, "    #{depth:02} {ra:#x}\r").ok();
+        if prev_fp <= fp {
+            break;
+        }
+        fp = prev_fp;
+    }
+}
+
+#[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+#[inline(always)]
+fn dump_registers(_w: &mut PanicWriter) {}
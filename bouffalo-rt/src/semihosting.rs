@@ -0,0 +1,93 @@
+//! Debug output over RISC-V semihosting.
+//!
+//! With the `semihosting` feature enabled, [`hprint!`](crate::hprint) and
+//! [`hprintln!`](crate::hprintln) send formatted messages to the debugger console through
+//! a JTAG probe, without occupying any serial port. With the feature disabled, both macros
+//! compile to nothing and their arguments are not evaluated.
+//!
+//! Semihosting requires a debug probe attached and semihosting enabled in the debugger,
+//! e.g. `arm semihosting enable` in OpenOCD. Each call halts the core until the debugger
+//! services it, so output is slow and changes program timing. Without a debugger the
+//! semihosting breakpoint raises a breakpoint exception instead; do not leave output
+//! enabled in firmware that runs standalone.
+
+use core::fmt::{self, Write};
+
+/// Semihosting operation writing a null-terminated string to debugger console.
+const SYS_WRITE0: usize = 0x04;
+
+/// Length of stack buffer holding one null-terminated chunk of output.
+const CHUNK_LENGTH: usize = 64;
+
+/// Writer to debugger console over semihosting.
+pub struct HostStdout;
+
+impl Write for HostStdout {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        // last byte is never written in the loop, keeping full chunks null-terminated
+        let mut buf = [0u8; CHUNK_LENGTH];
+        let mut len = 0;
+        for &byte in s.as_bytes() {
+            // null terminates the string on host, skip it to keep the rest of output
+            if byte == 0 {
+                continue;
+            }
+            buf[len] = byte;
+            len += 1;
+            if len == CHUNK_LENGTH - 1 {
+                write0(&buf[..=len]);
+                len = 0;
+            }
+        }
+        if len != 0 {
+            buf[len] = 0;
+            write0(&buf[..=len]);
+        }
+        Ok(())
+    }
+}
+
+#[doc(hidden)]
+#[inline]
+pub fn _print(args: fmt::Arguments) {
+    HostStdout.write_fmt(args).ok();
+}
+
+/// Write null-terminated `string` to debugger console.
+#[inline]
+fn write0(string: &[u8]) {
+    debug_assert_eq!(string.last(), Some(&0));
+    unsafe { syscall(SYS_WRITE0, string.as_ptr() as usize) };
+}
+
+/// Issue a semihosting call with operation number `op` and parameter `arg`.
+///
+/// Debugger recognizes the call by the `slli`, `ebreak`, `srai` sequence, which must
+/// be uncompressed and must not cross a page boundary.
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+#[inline(always)]
+unsafe fn syscall(op: usize, arg: usize) -> usize {
+    let mut ret = op;
+    unsafe {
+        core::arch::asm!(
+            ".option push",
+            ".option norvc",
+            ".balign 16",
+            "slli x0, x0, 0x1f",
+            "ebreak",
+            "srai x0, x0, 7",
+            ".option pop",
+            inout("a0") ret,
+            in("a1") arg,
+            options(nostack),
+        )
+    };
+    ret
+}
+
+#[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+#[inline(always)]
+unsafe fn syscall(_op: usize, _arg: usize) -> usize {
+    0
+}
@@ -16,6 +16,7 @@ unsafe extern "C" fn start() -> ! {
         "   la      sp, {stack}
             li      t0, {hart_stack_size}
             add     sp, sp, t0",
+        "   call    __pre_init",
         "   la      t1, sbss
             la      t2, ebss
         1:  bgeu    t1, t2, 1f
@@ -33,6 +34,16 @@ unsafe extern "C" fn start() -> ! {
             addi    t4, t4, 4
             j       1b
         1:",
+        "   la      t3, sipsram
+            la      t4, spsram
+            la      t5, epsram
+        1:  bgeu    t4, t5, 1f
+            lw      t6, 0(t3)
+            sw      t6, 0(t4)
+            addi    t3, t3, 4
+            addi    t4, t4, 4
+            j       1b
+        1:",
         "   call  {main}",
         stack = sym STACK,
         hart_stack_size = const LEN_STACK,
@@ -230,8 +241,12 @@ pub struct Peripherals<'a> {
     pub i2c0: I2C0,
     /// Pulse Width Modulation peripheral.
     pub pwm: PWM,
+    /// Timer and watchdog peripheral 0.
+    pub timer0: TIMER0,
     /// Inter-Integrated Circuit bus peripheral 1.
     pub i2c1: I2C1,
+    /// Direct Memory Access peripheral 0.
+    pub dma0: DMA0,
     /// Hibernation control peripheral.
     pub hbn: HBN,
     /// Ethernet Media Access Control peripheral.
@@ -251,8 +266,12 @@ soc! {
     pub struct I2C0 => 0x2000A300, bouffalo_hal::i2c::RegisterBlock;
     /// Pulse Width Modulation peripheral.
     pub struct PWM => 0x2000A400, bouffalo_hal::pwm::RegisterBlock;
+    /// Timer and watchdog peripheral 0.
+    pub struct TIMER0 => 0x2000A500, bouffalo_hal::timer::RegisterBlock;
     /// Inter-Integrated Circuit bus 1 with fixed base address.
     pub struct I2C1 => 0x2000A900, bouffalo_hal::i2c::RegisterBlock;
+    /// Direct Memory Access peripheral 0.
+    pub struct DMA0 => 0x2000C000, bouffalo_hal::dma::RegisterBlock;
    /// Hibernation control peripheral.
     pub struct HBN => 0x2000F000, bouffalo_hal::hbn::RegisterBlock;
     /// Ethernet Media Access Control peripheral.
@@ -281,13 +300,19 @@ pub fn __rom_init_params(xtal_hz: u32) -> (Peripherals<'static>, Clocks) {
         spi: SPI { _private: () },
         i2c0: I2C0 { _private: () },
         pwm: PWM { _private: () },
+        timer0: TIMER0 { _private: () },
         i2c1: I2C1 { _private: () },
+        dma0: DMA0 { _private: () },
         hbn: HBN { _private: () },
         emac: EMAC { _private: () },
     };
-    let clocks = Clocks {
-        xtal: Hertz(xtal_hz),
-    };
+    let clocks = Clocks::from_registers(
+        &GLBv2 { _private: () },
+        &HBN { _private: () },
+        None,
+        Hertz(xtal_hz),
+    )
+    .expect("processor clock source set up at boot");
     (peripherals, clocks)
 }
 
@@ -25,6 +25,7 @@ unsafe extern "C" fn start() -> ! {
         "   la      sp, {stack}
             li      t0, {hart_stack_size}
             add     sp, sp, t0",
+        "   call    __pre_init",
         "   la      t1, sbss
             la      t2, ebss
         1:  bgeu    t1, t2, 1f
@@ -231,9 +232,12 @@ pub fn __rom_init_params(xtal_hz: u32) -> (Peripherals, Clocks) {
         hbn: HBN { _private: () },
         usb: USBv1 { _private: () },
     };
-    let clocks = Clocks {
-        xtal: Hertz(xtal_hz),
-    };
+    let clocks = Clocks::from_registers_v1(
+        &GLBv1 { _private: () },
+        &HBN { _private: () },
+        Hertz(xtal_hz),
+    )
+    .expect("processor clock source set up at boot");
     (peripherals, clocks)
 }
 
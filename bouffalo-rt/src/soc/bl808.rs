@@ -16,6 +16,7 @@ unsafe extern "C" fn start() -> ! {
         "   la      sp, {stack}
             li      t0, {hart_stack_size}
             add     sp, sp, t0",
+        "   call    __pre_init",
         "   la      t1, sbss
             la      t2, ebss
         1:  bgeu    t1, t2, 1f
@@ -33,6 +34,16 @@ unsafe extern "C" fn start() -> ! {
             addi    t4, t4, 4
             j       1b
         1:",
+        "   la      t3, sipsram
+            la      t4, spsram
+            la      t5, epsram
+        1:  bgeu    t4, t5, 1f
+            lw      t6, 0(t3)
+            sw      t6, 0(t4)
+            addi    t3, t3, 4
+            addi    t4, t4, 4
+            j       1b
+        1:",
         "   la      t0, {trap_entry}
             ori     t0, t0, {trap_mode}
             csrw    mtvec, t0",
@@ -68,6 +79,7 @@ unsafe extern "C" fn start() -> ! {
         "   la      sp, {stack}
             li      t0, {hart_stack_size}
             add     sp, sp, t0",
+        "   call    __pre_init",
         "   la      t1, sbss
             la      t2, ebss
         1:  bgeu    t1, t2, 1f
@@ -85,6 +97,16 @@ unsafe extern "C" fn start() -> ! {
             addi    t4, t4, 8
             j       1b
         1:",
+        "   la      t3, sipsram
+            la      t4, spsram
+            la      t5, epsram
+        1:  bgeu    t4, t5, 1f
+            ld      t6, 0(t3)
+            sd      t6, 0(t4)
+            addi    t3, t3, 8
+            addi    t4, t4, 8
+            j       1b
+        1:",
         "   la      t0, {trap_entry}
             ori     t0, t0, {trap_mode}
             csrw    mtvec, t0",
@@ -116,6 +138,7 @@ unsafe extern "C" fn start() -> ! {
         "   la      sp, {stack}
             li      t0, {hart_stack_size}
             add     sp, sp, t0",
+        "   call    __pre_init",
         "   la      t1, sbss
             la      t2, ebss
         1:  bgeu    t1, t2, 1f
@@ -133,11 +156,15 @@ unsafe extern "C" fn start() -> ! {
             addi    t4, t4, 4
             j       1b
         1:",
-        // TODO trap support
+        "   la      t0, {trap_entry}
+            ori     t0, t0, {trap_mode}
+            csrw    mtvec, t0",
         // TODO pmp support
         "   call  {main}",
         stack = sym STACK,
         hart_stack_size = const LEN_STACK_LP,
+        trap_entry = sym trap_vectored,
+        trap_mode = const 1, // RISC-V standard vectored trap
         main = sym main,
     )
 }
@@ -209,6 +236,171 @@ extern "C" {
     fn exceptions(tf: &mut crate::arch::rvi::TrapFrame);
 }
 
+// Alignment of this function is ensured by `build.rs` script.
+#[cfg(all(feature = "bl808-lp", target_arch = "riscv32"))]
+#[link_section = ".trap.trap-entry"]
+#[naked]
+unsafe extern "C" fn trap_vectored() -> ! {
+    core::arch::naked_asm!(
+        ".p2align 2",
+        "j {exceptions}",
+        "j {reserved}",
+        "j {reserved}",
+        "j {machine_software}",
+        "j {reserved}",
+        "j {reserved}",
+        "j {reserved}",
+        "j {machine_timer}",
+        "j {reserved}",
+        "j {reserved}",
+        "j {reserved}",
+        "j {machine_external}",
+        "j {reserved}",
+        "j {reserved}",
+        "j {reserved}",
+        "j {reserved}",
+        // Peripheral interrupts are vectored from cause 16 onwards.
+        ".rept {interrupt_sources}",
+        "j {machine_external}",
+        ".endr",
+        exceptions = sym exceptions_trampoline,
+        machine_software = sym reserved,
+        machine_timer = sym reserved,
+        machine_external = sym machine_external_trampoline,
+        reserved = sym reserved,
+        interrupt_sources = const LP_INTERRUPT_SOURCES,
+    )
+}
+
+/// Number of peripheral interrupt sources on LP core.
+#[cfg(all(feature = "bl808-lp", target_arch = "riscv32"))]
+const LP_INTERRUPT_SOURCES: usize = 64;
+
+#[cfg(all(feature = "bl808-lp", target_arch = "riscv32"))]
+#[naked]
+unsafe extern "C" fn reserved() -> ! {
+    core::arch::naked_asm!("1: j   1b")
+}
+
+#[cfg(all(feature = "bl808-lp", target_arch = "riscv32"))]
+extern "C" {
+    fn exceptions(tf: &mut crate::arch::rve::TrapFrame);
+}
+
+#[cfg(all(feature = "bl808-lp", target_arch = "riscv32"))]
+#[naked]
+unsafe extern "C" fn exceptions_trampoline() -> ! {
+    core::arch::naked_asm!(
+        "addi   sp, sp, -13*4",
+        "sw     ra, 0*4(sp)",
+        "sw     t0, 1*4(sp)",
+        "sw     t1, 2*4(sp)",
+        "sw     t2, 3*4(sp)",
+        "sw     a0, 4*4(sp)",
+        "sw     a1, 5*4(sp)",
+        "sw     a2, 6*4(sp)",
+        "sw     a3, 7*4(sp)",
+        "sw     a4, 8*4(sp)",
+        "sw     a5, 9*4(sp)",
+        "csrr   t0, mcause",
+        "sw     t0, 10*4(sp)",
+        "csrr   t1, mepc",
+        "sw     t1, 11*4(sp)",
+        "csrr   t2, mstatus",
+        "sw     t2, 12*4(sp)",
+        "mv     a0, sp",
+        "call   {rust_exceptions}",
+        "lw     t0, 10*4(sp)",
+        "csrw   mcause, t0",
+        "lw     t1, 11*4(sp)",
+        "csrw   mepc, t1",
+        "lw     t2, 12*4(sp)",
+        "csrw   mstatus, t2",
+        "lw     ra, 0*4(sp)",
+        "lw     t0, 1*4(sp)",
+        "lw     t1, 2*4(sp)",
+        "lw     t2, 3*4(sp)",
+        "lw     a0, 4*4(sp)",
+        "lw     a1, 5*4(sp)",
+        "lw     a2, 6*4(sp)",
+        "lw     a3, 7*4(sp)",
+        "lw     a4, 8*4(sp)",
+        "lw     a5, 9*4(sp)",
+        "addi   sp, sp, 13*4",
+        "mret",
+        rust_exceptions = sym exceptions,
+    )
+}
+
+#[cfg(all(feature = "bl808-lp", target_arch = "riscv32"))]
+#[naked]
+unsafe extern "C" fn machine_external_trampoline() -> ! {
+    core::arch::naked_asm!(
+        "addi   sp, sp, -13*4",
+        "sw     ra, 0*4(sp)",
+        "sw     t0, 1*4(sp)",
+        "sw     t1, 2*4(sp)",
+        "sw     t2, 3*4(sp)",
+        "sw     a0, 4*4(sp)",
+        "sw     a1, 5*4(sp)",
+        "sw     a2, 6*4(sp)",
+        "sw     a3, 7*4(sp)",
+        "sw     a4, 8*4(sp)",
+        "sw     a5, 9*4(sp)",
+        "csrr   t0, mcause",
+        "sw     t0, 10*4(sp)",
+        "csrr   t1, mepc",
+        "sw     t1, 11*4(sp)",
+        "csrr   t2, mstatus",
+        "sw     t2, 12*4(sp)",
+        "mv     a0, sp",
+        "call   {rust_all_traps}",
+        "lw     t0, 10*4(sp)",
+        "csrw   mcause, t0",
+        "lw     t1, 11*4(sp)",
+        "csrw   mepc, t1",
+        "lw     t2, 12*4(sp)",
+        "csrw   mstatus, t2",
+        "lw     ra, 0*4(sp)",
+        "lw     t0, 1*4(sp)",
+        "lw     t1, 2*4(sp)",
+        "lw     t2, 3*4(sp)",
+        "lw     a0, 4*4(sp)",
+        "lw     a1, 5*4(sp)",
+        "lw     a2, 6*4(sp)",
+        "lw     a3, 7*4(sp)",
+        "lw     a4, 8*4(sp)",
+        "lw     a5, 9*4(sp)",
+        "addi   sp, sp, 13*4",
+        "mret",
+        rust_all_traps = sym rust_bl808_lp_machine_external,
+    )
+}
+
+#[cfg(all(feature = "bl808-lp", target_arch = "riscv32"))]
+fn rust_bl808_lp_machine_external(tf: &mut crate::arch::rve::TrapFrame) {
+    let idx = tf.mcause & 0xfff;
+    if (16..16 + LP_INTERRUPT_SOURCES).contains(&idx) {
+        unsafe { lp_interrupt(idx - 16) };
+    }
+}
+
+#[cfg(all(feature = "bl808-lp", target_arch = "riscv32"))]
+extern "C" {
+    /// Peripheral interrupt handler on LP core, called with interrupt source number.
+    fn lp_interrupt(source: usize);
+}
+
+#[cfg(all(feature = "bl808-lp", target_arch = "riscv32"))]
+#[doc(hidden)]
+#[no_mangle]
+extern "C" fn default_lp_exceptions(_tf: &mut crate::arch::rve::TrapFrame) -> ! {
+    // Returning would retry the faulting instruction forever; stop here instead.
+    loop {
+        core::hint::spin_loop()
+    }
+}
+
 // TODO exceptions_trampoline for bl808-mcu
 #[cfg(all(feature = "bl808-mcu", target_arch = "riscv32"))]
 #[naked]
@@ -519,6 +711,22 @@ impl plic::InterruptSource for RawPlicSource {
 pub enum DspInterrupt {
     /// UART3 interrupt.
     UART3 = 16 + 4,
+    /// DMA2 channel 0 interrupt.
+    DMA2_INT0 = 16 + 24,
+    /// DMA2 channel 1 interrupt.
+    DMA2_INT1 = 16 + 25,
+    /// DMA2 channel 2 interrupt.
+    DMA2_INT2 = 16 + 26,
+    /// DMA2 channel 3 interrupt.
+    DMA2_INT3 = 16 + 27,
+    /// DMA2 channel 4 interrupt.
+    DMA2_INT4 = 16 + 28,
+    /// DMA2 channel 5 interrupt.
+    DMA2_INT5 = 16 + 29,
+    /// DMA2 channel 6 interrupt.
+    DMA2_INT6 = 16 + 30,
+    /// DMA2 channel 7 interrupt.
+    DMA2_INT7 = 16 + 31,
     // TODO other interrupts.
     // /// I2C2 interrupt.
     // I2C2 = 16 + 5,
@@ -811,12 +1019,18 @@ pub struct Peripherals<'a> {
     pub i2c0: I2C0,
     /// Pulse Width Modulation peripheral.
     pub pwm: PWM,
+    /// Timer and watchdog peripheral 0.
+    pub timer0: TIMER0,
+    /// Display Bus Interface peripheral.
+    pub dbi: DBI,
     /// Inter-Integrated Circuit bus peripheral 1.
     pub i2c1: I2C1,
     /// Universal Asynchronous Receiver/Transmitter peripheral 2.
     pub uart2: UART2,
     /// Hardware LZ4 Decompressor.
     pub lz4d: LZ4D,
+    /// Direct Memory Access peripheral 0.
+    pub dma0: DMA0,
     /// Hibernation control peripheral.
     pub hbn: HBN,
     /// Ethernet Media Access Control peripheral.
@@ -837,11 +1051,27 @@ pub struct Peripherals<'a> {
     pub psram: PSRAM,
     /// Secure Digital High Capacity peripheral.
     pub sdh: SDH,
+    /// Security engine.
+    pub sec: SEC,
+    /// Inter-processor communication peripheral of M0 core.
+    pub ipc_m0: IPC0,
+    /// Inter-processor communication peripheral of LP core.
+    pub ipc_lp: IPC2,
+    /// Inter-processor communication peripheral of D0 core.
+    pub ipc_d0: IPC1,
+    /// Processor phase-locked loop.
+    pub cpu_pll: CPUPLL,
+    /// Efuse data shadow registers.
+    pub efuse: EFUSE,
 }
 
 soc! {
     /// Global configuration peripheral.
     pub struct GLBv2 => 0x20000000, bouffalo_hal::glb::v2::RegisterBlock;
+    /// Security engine.
+    pub struct SEC => 0x20004000, bouffalo_hal::sec::RegisterBlock;
+    /// Processor phase-locked loop.
+    pub struct CPUPLL => 0x20008750, bouffalo_hal::glb::pll::RegisterBlock;
     /// Universal Asynchronous Receiver/Transmitter 0 with fixed base address.
     pub struct UART0 => 0x2000A000, bouffalo_hal::uart::RegisterBlock;
     /// Universal Asynchronous Receiver/Transmitter 1 with fixed base address.
@@ -852,14 +1082,26 @@ soc! {
     pub struct I2C0 => 0x2000A300, bouffalo_hal::i2c::RegisterBlock;
     /// Pulse Width Modulation peripheral.
     pub struct PWM => 0x2000A400, bouffalo_hal::pwm::RegisterBlock;
+    /// Timer and watchdog peripheral 0.
+    pub struct TIMER0 => 0x2000A500, bouffalo_hal::timer::RegisterBlock;
+    /// Display Bus Interface peripheral.
+    pub struct DBI => 0x2000A800, bouffalo_hal::dbi::RegisterBlock;
+    /// Inter-processor communication peripheral of M0 core.
+    pub struct IPC0 => 0x2000A840, bouffalo_hal::ipc::RegisterBlock;
+    /// Inter-processor communication peripheral of LP core.
+    pub struct IPC2 => 0x2000A880, bouffalo_hal::ipc::RegisterBlock;
     /// Inter-Integrated Circuit bus 1 with fixed base address.
     pub struct I2C1 => 0x2000A900, bouffalo_hal::i2c::RegisterBlock;
     /// Universal Asynchronous Receiver/Transmitter 2 with fixed base address.
     pub struct UART2 => 0x2000AA00, bouffalo_hal::uart::RegisterBlock;
     /// Hardware LZ4 Decompressor.
     pub struct LZ4D => 0x2000AD00, bouffalo_hal::lz4d::RegisterBlock;
+    /// Direct Memory Access peripheral 0.
+    pub struct DMA0 => 0x2000C000, bouffalo_hal::dma::RegisterBlock;
     /// Hibernation control peripheral.
     pub struct HBN => 0x2000F000, bouffalo_hal::hbn::RegisterBlock;
+    /// Efuse data shadow registers.
+    pub struct EFUSE => 0x20056000, bouffalo_hal::efuse::RegisterBlock;
     /// Secure Digital High Capacity peripheral.
     pub struct SDH => 0x20060000, bouffalo_hal::sdio::RegisterBlock;
     /// Ethernet Media Access Control peripheral.
@@ -870,6 +1112,8 @@ soc! {
     pub struct I2C2 => 0x30003000, bouffalo_hal::i2c::RegisterBlock;
     /// Inter-Integrated Circuit bus 3 with fixed base address.
     pub struct I2C3 => 0x30004000, bouffalo_hal::i2c::RegisterBlock;
+    /// Inter-processor communication peripheral of D0 core.
+    pub struct IPC1 => 0x30005000, bouffalo_hal::ipc::RegisterBlock;
     /// Multi-media subsystem global peripheral.
     pub struct MMGLB => 0x30007000, bouffalo_hal::glb::mm::RegisterBlock;
     /// Serial Peripheral Interface peripheral 1.
@@ -902,9 +1146,12 @@ pub fn __rom_init_params(xtal_hz: u32) -> (Peripherals<'static>, Clocks) {
         spi0: SPI0 { _private: () },
         i2c0: I2C0 { _private: () },
         pwm: PWM { _private: () },
+        timer0: TIMER0 { _private: () },
+        dbi: DBI { _private: () },
         i2c1: I2C1 { _private: () },
         uart2: UART2 { _private: () },
         lz4d: LZ4D { _private: () },
+        dma0: DMA0 { _private: () },
         hbn: HBN { _private: () },
         emac: EMAC { _private: () },
         uart3: UART3 { _private: () },
@@ -915,10 +1162,31 @@ pub fn __rom_init_params(xtal_hz: u32) -> (Peripherals<'static>, Clocks) {
         mmglb: MMGLB { _private: () },
         psram: PSRAM { _private: () },
         sdh: SDH { _private: () },
+        sec: SEC { _private: () },
+        ipc_m0: IPC0 { _private: () },
+        ipc_lp: IPC2 { _private: () },
+        ipc_d0: IPC1 { _private: () },
+        cpu_pll: CPUPLL { _private: () },
+        efuse: EFUSE { _private: () },
     };
-    let clocks = Clocks {
-        xtal: Hertz(xtal_hz),
-    };
+    let clocks = match () {
+        #[cfg(feature = "bl808-dsp")]
+        () => Clocks::from_registers_d0(
+            &GLBv2 { _private: () },
+            &MMGLB { _private: () },
+            &HBN { _private: () },
+            &CPUPLL { _private: () },
+            Hertz(xtal_hz),
+        ),
+        #[cfg(not(feature = "bl808-dsp"))]
+        () => Clocks::from_registers(
+            &GLBv2 { _private: () },
+            &HBN { _private: () },
+            Some(&CPUPLL { _private: () }),
+            Hertz(xtal_hz),
+        ),
+    }
+    .expect("processor clock source set up at boot");
     (peripherals, clocks)
 }
 
@@ -3,7 +3,12 @@
 
 use core::{arch::asm, ptr};
 
-use bouffalo_hal::{prelude::*, psram::init_psram, uart::Config};
+use bouffalo_hal::{
+    prelude::*,
+    psram::init_psram,
+    timer::{ClockSource, Timers},
+    uart::Config,
+};
 use bouffalo_rt::{entry, Clocks, Peripherals};
 use embedded_time::rate::*;
 use panic_halt as _;
@@ -27,7 +32,13 @@ fn main(p: Peripherals, c: Clocks) -> ! {
 
     writeln!(serial, "Welcome to psram-demo🦀!").ok();
 
+    // Crystal divided by 40 gives microsecond ticks, wrapping after about 71 minutes.
+    let Timers { timer0, .. } = Timers::new(p.timer0);
+    let stopwatch = timer0.into_stopwatch(ClockSource::Xtal, 39, c.xclk());
+
+    let start = stopwatch.now();
     init_psram(&p.psram, &p.glb);
+    let psram_init_ms = stopwatch.elapsed_ms(start);
 
     const MEMORY_SIZE: usize = 64 * 1024 * 1024;
     const START_ADDRESS: u32 = 0x50000000;
@@ -35,6 +46,7 @@ fn main(p: Peripherals, c: Clocks) -> ! {
     writeln!(serial, "start memory test...").ok();
 
     writeln!(serial, "  write start...").ok();
+    let start = stopwatch.now();
     for i in 0..MEMORY_SIZE / 4 {
         if (i + 1) % PROGRESS_INTERVAL == 0 {
             writeln!(
@@ -47,9 +59,11 @@ fn main(p: Peripherals, c: Clocks) -> ! {
         let addr = START_ADDRESS + (i as u32 * 4);
         write_memory(addr, i as u32);
     }
+    let write_ms = stopwatch.elapsed_ms(start);
     writeln!(serial, "  write finish").ok();
 
     writeln!(serial, "  read start...").ok();
+    let start = stopwatch.now();
     let mut error_cnt = 0;
     for i in 0..MEMORY_SIZE / 4 {
         if (i + 1) % PROGRESS_INTERVAL == 0 {
@@ -74,7 +88,14 @@ fn main(p: Peripherals, c: Clocks) -> ! {
             }
         }
     }
+    let read_ms = stopwatch.elapsed_ms(start);
     writeln!(serial, "  read finish").ok();
+    writeln!(
+        serial,
+        "PSRAM init: {}ms, memory write: {}ms, memory read: {}ms",
+        psram_init_ms, write_ms, read_ms
+    )
+    .ok();
 
     if error_cnt == 0 {
         writeln!(serial, "memory test success.").ok();
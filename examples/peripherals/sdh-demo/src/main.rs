@@ -1,7 +1,7 @@
 #![no_std]
 #![no_main]
 
-use bouffalo_hal::{prelude::*, sdio::Sdh, uart::Config};
+use bouffalo_hal::{configure_pins, prelude::*, sdio::Sdh, uart::Config};
 use bouffalo_rt::{entry, Clocks, Peripherals};
 use embedded_sdmmc::VolumeManager;
 use embedded_time::rate::*;
@@ -38,12 +38,14 @@ fn main(p: Peripherals, c: Clocks) -> ! {
     writeln!(serial, "Welcome to sdh-demo!").ok();
 
     // Sdh gpio init.
-    let sdh_clk = p.gpio.io0.into_sdh();
-    let sdh_cmd = p.gpio.io1.into_sdh();
-    let sdh_d0 = p.gpio.io2.into_sdh();
-    let sdh_d1 = p.gpio.io3.into_sdh();
-    let sdh_d2 = p.gpio.io4.into_sdh();
-    let sdh_d3 = p.gpio.io5.into_sdh();
+    configure_pins!(p.gpio, {
+        let sdh_clk = io0.into_sdh();
+        let sdh_cmd = io1.into_sdh();
+        let sdh_d0 = io2.into_sdh();
+        let sdh_d1 = io3.into_sdh();
+        let sdh_d2 = io4.into_sdh();
+        let sdh_d3 = io5.into_sdh();
+    });
     let pads = (sdh_clk, sdh_cmd, sdh_d0, sdh_d1, sdh_d2, sdh_d3);
 
     // Sdh init.
@@ -1,14 +1,19 @@
 #![no_std]
 #![no_main]
 
-use bouffalo_hal::{prelude::*, uart::Config};
+use bouffalo_hal::{
+    glb::v2::{software_reset, ResetScope},
+    i2c::I2c,
+    prelude::*,
+    uart::Config,
+};
 use bouffalo_rt::{entry, Clocks, Peripherals};
 use embedded_cli::{cli::CliBuilder, Command};
 use embedded_time::rate::*;
 use panic_halt as _;
 
 #[derive(Command)]
-enum Base {
+enum Base<'a> {
     /// Print out 'Hello world!'.
     Hello,
     /// LED control command.
@@ -16,6 +21,13 @@ enum Base {
         #[command(subcommand)]
         command: Option<LedCommand>,
     },
+    /// I2C bus probing command.
+    I2c {
+        #[command(subcommand)]
+        command: I2cCommand<'a>,
+    },
+    /// Reset the chip by software.
+    Reset,
 }
 
 #[derive(Command)]
@@ -28,6 +40,33 @@ enum LedCommand {
     Switch,
 }
 
+#[derive(Command)]
+enum I2cCommand<'a> {
+    /// Print addresses of all devices present on the bus.
+    Scan,
+    /// Read bytes from a device register.
+    Read {
+        /// Device address, e.g. 0x50.
+        address: &'a str,
+        /// Register address.
+        register: &'a str,
+        /// Number of bytes to read, at most 32.
+        length: &'a str,
+    },
+    /// Write bytes to a device register.
+    Write {
+        /// Device address, e.g. 0x50.
+        address: &'a str,
+        /// Register address.
+        register: &'a str,
+        /// Bytes in hexadecimal, e.g. 01a0ff.
+        bytes: &'a str,
+    },
+}
+
+/// Maximum data length of I2C read and write commands.
+const I2C_DATA_MAX: usize = 32;
+
 #[entry]
 fn main(p: Peripherals, c: Clocks) -> ! {
     let tx = p.gpio.io14.into_uart();
@@ -42,7 +81,11 @@ fn main(p: Peripherals, c: Clocks) -> ! {
     let (mut tx, mut rx) = serial.split();
 
     let mut led = p.gpio.io8.into_floating_output();
+    let scl = p.gpio.io6.into_i2c::<0>();
+    let sda = p.gpio.io7.into_i2c::<0>();
+    let mut i2c = I2c::new(p.i2c0, (scl, sda), &p.glb);
     let mut led_state = PinState::Low;
+    let mut reset_requested = false;
 
     writeln!(tx, "Welcome to embedded-cli example by bouffalo-hal🦀!").ok();
     writeln!(tx, "For command helps, type 'help'.").ok();
@@ -76,9 +119,100 @@ fn main(p: Peripherals, c: Clocks) -> ! {
                             PinState::Low => cli.writer().write_str("LED state: Low").unwrap(),
                         },
                     },
+                    Base::I2c { command } => match command {
+                        I2cCommand::Scan => {
+                            let found = i2c.scan();
+                            for address in &found {
+                                write!(cli.writer(), "{:02x} ", address).ok();
+                            }
+                            write!(cli.writer(), "\r\n{} device(s) found", found.len()).ok();
+                        }
+                        I2cCommand::Read {
+                            address,
+                            register,
+                            length,
+                        } => {
+                            let (Some(address), Some(register), Some(length)) =
+                                (parse_u8(address), parse_u8(register), parse_u8(length))
+                            else {
+                                cli.writer().write_str("Invalid argument").ok();
+                                return Ok(());
+                            };
+                            let length = length as usize;
+                            if length == 0 || length > I2C_DATA_MAX {
+                                cli.writer().write_str("Invalid length").ok();
+                                return Ok(());
+                            }
+                            let mut buf = [0u8; I2C_DATA_MAX];
+                            i2c.enable_sub_address(register);
+                            let result = i2c.read(address, &mut buf[..length]);
+                            i2c.disable_sub_address();
+                            match result {
+                                Ok(()) => {
+                                    for byte in &buf[..length] {
+                                        write!(cli.writer(), "{:02x} ", byte).ok();
+                                    }
+                                }
+                                Err(e) => {
+                                    write!(cli.writer(), "Read failed: {:?}", e).ok();
+                                }
+                            }
+                        }
+                        I2cCommand::Write {
+                            address,
+                            register,
+                            bytes,
+                        } => {
+                            let mut buf = [0u8; I2C_DATA_MAX + 1];
+                            let (Some(address), Some(register), Some(length)) = (
+                                parse_u8(address),
+                                parse_u8(register),
+                                parse_hex_bytes(bytes, &mut buf[1..]),
+                            ) else {
+                                cli.writer().write_str("Invalid argument").ok();
+                                return Ok(());
+                            };
+                            buf[0] = register;
+                            match i2c.write(address, &buf[..=length]) {
+                                Ok(()) => write!(cli.writer(), "{} byte(s) written", length).ok(),
+                                Err(e) => write!(cli.writer(), "Write failed: {:?}", e).ok(),
+                            };
+                        }
+                    },
+                    Base::Reset => {
+                        cli.writer().write_str("Resetting...").ok();
+                        reset_requested = true;
+                    }
                 }
                 Ok(())
             }),
         );
+        if reset_requested {
+            // Wait for transmit FIFO to drain, at most 32 bytes at 2 Mbaud.
+            for _ in 0..1_000_000 {
+                core::hint::spin_loop();
+            }
+            software_reset(&p.glb, ResetScope::System);
+        }
+    }
+}
+
+/// Parse a decimal or `0x` prefixed hexadecimal byte.
+fn parse_u8(s: &str) -> Option<u8> {
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u8::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+}
+
+/// Parse hexadecimal digit pairs into `buf`, returning the number of bytes.
+fn parse_hex_bytes(s: &str, buf: &mut [u8]) -> Option<usize> {
+    let digits = s.as_bytes();
+    if digits.is_empty() || digits.len() % 2 != 0 || digits.len() / 2 > buf.len() {
+        return None;
+    }
+    for (byte, pair) in buf.iter_mut().zip(digits.chunks(2)) {
+        *byte = u8::from_str_radix(core::str::from_utf8(pair).ok()?, 16).ok()?;
     }
+    Some(digits.len() / 2)
 }